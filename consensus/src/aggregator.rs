@@ -78,6 +78,13 @@ impl<V: StepVote> Aggregator<V> {
             .map_or(false, |(_, cluster)| cluster.contains_key(signer))
     }
 
+    /// Collects a vote, aggregating its signature with the ones of the votes
+    /// collected for the same step and result.
+    ///
+    /// Returns the [StepVotes] collected so far, made of the aggregated
+    /// signature and the bitset of the voters, and whether they reach the
+    /// quorum. The [StepVotes] are verified with a single aggregated key by
+    /// [`verify_votes`](crate::quorum::verifiers::verify_votes).
     pub fn collect_vote(
         &mut self,
         committee: &Committee,
@@ -234,6 +241,7 @@ impl AggrSignature {
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
+    use std::sync::Arc;
 
     use dusk_bytes::DeserializableSlice;
     use dusk_core::signatures::bls::{
@@ -246,6 +254,7 @@ mod tests {
     use super::*;
     use crate::aggregator::Aggregator;
     use crate::commons::RoundUpdate;
+    use crate::signer::LocalSigner;
     use crate::user::committee::Committee;
     use crate::user::provisioners::{Provisioners, DUSK};
     use crate::user::sortition::Config;
@@ -259,8 +268,8 @@ mod tests {
         }
     }
 
-    #[test]
-    fn test_collect_votes() {
+    #[tokio::test]
+    async fn test_collect_votes() {
         let sks = [
             "7f6f2ccdb23f2abb7b69278e947c01c6160a31cf02c19d06d0f6e5ab1d768b15",
             "611830d3641a68f94a690dcc25d1f4b0dac948325ac18f6dd32564371735f32c",
@@ -303,9 +312,10 @@ mod tests {
 
             p.add_member_with_value(pubkey_bls.clone(), 1000 * DUSK);
 
+            let signer = LocalSigner::new(secret_key, *pubkey_bls.inner());
             let ru = RoundUpdate::new(
                 pubkey_bls,
-                secret_key,
+                Arc::new(signer),
                 &tip_header,
                 HashMap::new(),
                 vec![],
@@ -315,7 +325,9 @@ mod tests {
                 init_vote.clone(),
                 &ru,
                 iteration,
-            );
+            )
+            .await
+            .expect("vote to be signed");
 
             // Message headers to be used in test for voting for hash:
             // block_hash
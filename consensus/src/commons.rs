@@ -8,26 +8,31 @@
 // Provisioners, the BidList, the Seed and the Hash.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 
-use dusk_core::signatures::bls::SecretKey as BlsSecretKey;
 use node_data::bls::PublicKey;
 use node_data::ledger::*;
-use node_data::message::{payload, ConsensusHeader};
+use node_data::message::{payload, ConsensusHeader, SignedStepMessage};
 use node_data::StepName;
+use tracing::error;
 
 use crate::operations::Voter;
+use crate::sign_guard::SigningGuard;
+use crate::signer::{sign_step_message, Signer, SignerError};
 
 pub type TimeoutSet = HashMap<StepName, Duration>;
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Debug)]
 pub struct RoundUpdate {
     // Current round number of the ongoing consensus
     pub round: u64,
 
-    // This provisioner consensus keys
+    // This provisioner consensus key, and the signer of its messages
     pub pubkey_bls: PublicKey,
-    pub secret_key: BlsSecretKey,
+    signer: Arc<dyn Signer>,
+    // Journal of the messages signed, refusing conflicting ones
+    signing_guard: Option<Arc<SigningGuard>>,
 
     seed: Seed,
     hash: [u8; 32],
@@ -42,7 +47,7 @@ pub struct RoundUpdate {
 impl RoundUpdate {
     pub fn new(
         pubkey_bls: PublicKey,
-        secret_key: BlsSecretKey,
+        signer: Arc<dyn Signer>,
         tip_header: &Header,
         base_timeouts: TimeoutSet,
         att_voters: Vec<Voter>,
@@ -51,7 +56,8 @@ impl RoundUpdate {
         RoundUpdate {
             round,
             pubkey_bls,
-            secret_key,
+            signer,
+            signing_guard: None,
             att: tip_header.att,
             hash: tip_header.hash,
             seed: tip_header.seed,
@@ -62,6 +68,49 @@ impl RoundUpdate {
         }
     }
 
+    /// Records the messages in `guard` before signing them, refusing the ones
+    /// conflicting with a message already signed.
+    pub fn with_signing_guard(
+        mut self,
+        guard: Option<Arc<SigningGuard>>,
+    ) -> Self {
+        self.signing_guard = guard;
+        self
+    }
+
+    /// Returns the BLS multisig signature of `msg` by this provisioner.
+    pub async fn sign_multisig(
+        &self,
+        msg: &[u8],
+    ) -> Result<[u8; 48], SignerError> {
+        self.signer.sign_multisig(msg).await
+    }
+
+    /// Signs `msg` as this provisioner.
+    pub async fn try_sign<M: SignedStepMessage>(
+        &self,
+        msg: &mut M,
+    ) -> Result<(), SignerError> {
+        if let Some(guard) = &self.signing_guard {
+            guard.check(msg).await?;
+        }
+        sign_step_message(self.signer.as_ref(), &self.pubkey_bls, msg).await
+    }
+
+    /// Signs the vote `msg` as this provisioner.
+    ///
+    /// Returns whether the vote is signed. A vote the signer fails or
+    /// refuses to sign is not to be cast, rather than holding the step.
+    pub async fn sign<M: SignedStepMessage>(&self, msg: &mut M) -> bool {
+        match self.try_sign(msg).await {
+            Ok(()) => true,
+            Err(err) => {
+                error!(event = "cannot sign vote", step = ?M::STEP_NAME, %err);
+                false
+            }
+        }
+    }
+
     pub fn seed(&self) -> Seed {
         self.seed
     }
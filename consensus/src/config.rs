@@ -47,6 +47,9 @@ pub const MIN_EMERGENCY_BLOCK_TIME: Duration =
 
 mod default {
     pub const MINIMUM_BLOCK_TIME: u64 = 10;
+    pub const MAX_CLOCK_SKEW: u64 = 30;
+    pub const FUTURE_MSG_TTL: u64 = 120;
+    pub const MAX_BLOCK_TIME_DRIFT: u64 = 3;
 }
 
 pub static MINIMUM_BLOCK_TIME: LazyLock<u64> = LazyLock::new(|| {
@@ -56,6 +59,36 @@ pub static MINIMUM_BLOCK_TIME: LazyLock<u64> = LazyLock::new(|| {
         .unwrap_or(default::MINIMUM_BLOCK_TIME)
 });
 
+/// Number of blocks whose median timestamp a block timestamp must exceed
+pub const MEDIAN_TIME_BLOCKS: usize = 11;
+
+/// Maximum number of seconds a block timestamp can be ahead of the local time
+pub static MAX_BLOCK_TIME_DRIFT: LazyLock<u64> = LazyLock::new(|| {
+    env::var("RUSK_MAX_BLOCK_TIME_DRIFT")
+        .unwrap_or_default()
+        .parse()
+        .unwrap_or(default::MAX_BLOCK_TIME_DRIFT)
+});
+
+/// Maximum number of seconds a message timestamp can be ahead of the local
+/// time, to tolerate peers whose clock is slightly off.
+pub static MAX_CLOCK_SKEW: LazyLock<u64> = LazyLock::new(|| {
+    env::var("RUSK_MAX_CLOCK_SKEW")
+        .unwrap_or_default()
+        .parse()
+        .unwrap_or(default::MAX_CLOCK_SKEW)
+});
+
+/// Time after which the queued messages of future rounds and steps are
+/// discarded.
+pub static FUTURE_MSG_TTL: LazyLock<Duration> = LazyLock::new(|| {
+    let secs = env::var("RUSK_FUTURE_MSG_TTL")
+        .unwrap_or_default()
+        .parse()
+        .unwrap_or(default::FUTURE_MSG_TTL);
+    Duration::from_secs(secs)
+});
+
 /// Maximum allowable round difference for message signature verification and
 /// for determining if a consensus message is close enough to the network tip
 /// for enqueuing.
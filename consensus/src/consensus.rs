@@ -249,6 +249,17 @@ impl<T: Operations + 'static, D: Database + 'static> Consensus<T, D> {
                         ))
                         .await;
 
+                    // Report the faults detected while collecting the votes,
+                    // and gossip their evidence for any generator to include
+                    // it in a block
+                    let faults = sv_registry.lock().await.take_faults();
+                    if !faults.is_empty() {
+                        for fault in &faults {
+                            outbound.try_send(Message::from(fault.clone()));
+                        }
+                        executor.report_faults(faults).await;
+                    }
+
                     // Handle Quorum messages produced by Consensus or received
                     // from the network. A Quorum for the current iteration
                     // means the iteration is over.
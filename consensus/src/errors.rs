@@ -13,6 +13,8 @@ use node_data::message::payload::{RatificationResult, Vote};
 use node_data::StepName;
 use thiserror::Error;
 
+use crate::signer::SignerError;
+
 #[derive(Debug, Clone, Copy, Error)]
 pub enum StepSigError {
     #[error("Failed to reach a quorum")]
@@ -41,6 +43,7 @@ pub enum ConsensusError {
     InvalidQuorumType,
     InvalidVote(Vote),
     InvalidMsgIteration(u8),
+    InvalidMsgTimestamp(u64),
     FutureEvent,
     PastEvent,
     NotCommitteeMember,
@@ -81,6 +84,8 @@ pub enum OperationError {
     InvalidIterationInfo(io::Error),
     #[error("Invalid Faults {0}")]
     InvalidFaults(InvalidFault),
+    #[error("Cannot sign {0}")]
+    SigningFailed(SignerError),
 }
 
 #[derive(Debug, Error)]
@@ -93,6 +98,8 @@ pub enum HeaderError {
     MismatchHeight(u64, u64),
     #[error("block time is less than minimum block time")]
     BlockTimeLess,
+    #[error("block timestamp is not higher than median time past {0}")]
+    BlockTimeBeforeMedian(u64),
     #[error("block timestamp {0} is higher than local time")]
     BlockTimeHigher(u64),
     #[error("invalid previous block hash")]
@@ -108,6 +115,8 @@ pub enum HeaderError {
     InvalidAttestation(AttestationError),
     #[error("Invalid Failed Iterations: {0}")]
     InvalidFailedIterations(FailedIterationError),
+    #[error("invalid gas limit, expected: {0}, got: {1}")]
+    InvalidGasLimit(u64, u64),
 
     #[error("Generic error in header verification: {0}")]
     Generic(&'static str),
@@ -157,6 +166,8 @@ impl HeaderError {
             HeaderError::Storage(..) => false,
 
             HeaderError::BlockTimeLess => true,
+            HeaderError::BlockTimeBeforeMedian(_) => true,
+            HeaderError::InvalidGasLimit(..) => true,
             HeaderError::UnsupportedVersion => true,
             HeaderError::EmptyHash => true,
             HeaderError::InvalidSeed(_) => true,
@@ -23,6 +23,8 @@ mod proposal;
 pub mod queue;
 pub mod quorum;
 mod ratification;
+pub mod sign_guard;
+pub mod signer;
 mod step_votes_reg;
 mod validation;
 
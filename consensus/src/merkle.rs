@@ -196,4 +196,27 @@ mod tests {
             assert_eq!(actual, expected_hash)
         }
     }
+
+    #[test]
+    fn light_client_paths() {
+        use node_data::ledger::merkle_path;
+
+        for len in 1..=17u8 {
+            let leaves: Vec<[u8; 32]> = (0..len).map(|i| [i + 1; 32]).collect();
+            let root = merkle_root(&leaves[..]);
+
+            for index in 0..leaves.len() {
+                let path = merkle_path(&leaves, index).unwrap();
+                let mut node = leaves[index];
+                for (level, sibling) in path.iter().enumerate() {
+                    let (left, right) = match (index >> level) % 2 {
+                        0 => (node, sibling.unwrap_or(node)),
+                        _ => (sibling.unwrap(), node),
+                    };
+                    node = Hash::aggregate([&Hash(left), &Hash(right)]).0;
+                }
+                assert_eq!(node, root, "path of {index} of {len} leaves");
+            }
+        }
+    }
 }
@@ -7,10 +7,11 @@
 use async_trait::async_trait;
 use node_data::bls::PublicKeyBytes;
 use node_data::message::{Message, Payload, Status};
-use node_data::StepName;
+use node_data::{get_current_timestamp, StepName};
 use tracing::{debug, warn};
 
 use crate::commons::RoundUpdate;
+use crate::config::MAX_CLOCK_SKEW;
 use crate::errors::ConsensusError;
 use crate::iteration_ctx::RoundCommittees;
 use crate::ratification::handler::RatificationHandler;
@@ -53,6 +54,8 @@ pub trait MsgHandler {
             ray_id = msg.ray_id(),
         );
 
+        Self::verify_timestamp(msg)?;
+
         // We don't verify the tip here, otherwise future round messages will be
         // discarded and not put into the queue
         let msg_tip = msg.header.prev_block_hash;
@@ -89,6 +92,22 @@ pub trait MsgHandler {
         }
     }
 
+    /// Rejects messages whose timestamp is ahead of the local time by more
+    /// than [`MAX_CLOCK_SKEW`], so that peers with badly skewed clocks cannot
+    /// fill the queues.
+    fn verify_timestamp(msg: &Message) -> Result<(), ConsensusError> {
+        let timestamp = match &msg.payload {
+            Payload::Ratification(r) => r.timestamp,
+            Payload::Candidate(c) => c.candidate.header().timestamp,
+            _ => return Ok(()),
+        };
+
+        if timestamp > get_current_timestamp() + *MAX_CLOCK_SKEW {
+            return Err(ConsensusError::InvalidMsgTimestamp(timestamp));
+        }
+        Ok(())
+    }
+
     /// Verify step message for the current round with different iteration
     fn verify_message(
         msg: &Message,
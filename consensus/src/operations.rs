@@ -27,6 +27,7 @@ pub struct CallParams {
     pub voters_pubkey: Vec<Voter>,
     pub max_txs_bytes: usize,
     pub prev_state_root: StateRoot,
+    pub block_gas_limit: u64,
 }
 
 #[derive(Default)]
@@ -62,6 +63,11 @@ impl fmt::Display for VerificationOutput {
     }
 }
 
+/// Operations the consensus performs on the chain state.
+///
+/// The verification methods can be called concurrently, for the candidates
+/// of different iterations, and should not exclude each other. Only the
+/// transitions altering the state need to be serialized by the implementor.
 #[async_trait::async_trait]
 pub trait Operations: Send + Sync {
     async fn verify_candidate_header(
@@ -96,4 +102,17 @@ pub trait Operations: Send + Sync {
     ) -> Result<(), OperationError>;
 
     async fn get_block_gas_limit(&self) -> u64;
+
+    /// Reports faults committed by provisioners during consensus, to be
+    /// included in a later block.
+    async fn report_faults(&self, faults: Vec<Fault>);
+
+    /// Returns the faults to be included in the candidate block of `round`.
+    async fn pending_faults(&self, round: u64) -> Vec<Fault>;
+
+    /// Returns whether candidate blocks are generated when this provisioner
+    /// is selected as generator.
+    fn block_production_enabled(&self) -> bool {
+        true
+    }
 }
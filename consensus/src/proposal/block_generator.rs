@@ -8,15 +8,15 @@ use std::cmp::max;
 use std::sync::Arc;
 use std::time::Instant;
 
-use dusk_bytes::Serializable;
 use node_data::ledger::{to_str, Block, Fault, IterationsInfo, Seed, Slash};
 use node_data::message::payload::Candidate;
-use node_data::message::{Message, SignedStepMessage, BLOCK_HEADER_VERSION};
+use node_data::message::{Message, BLOCK_HEADER_VERSION};
 use node_data::{get_current_timestamp, ledger};
 use tracing::{debug, info};
 
 use crate::commons::RoundUpdate;
 use crate::config::{MAX_BLOCK_SIZE, MAX_NUMBER_OF_FAULTS, MINIMUM_BLOCK_TIME};
+use crate::errors::OperationError;
 use crate::merkle::merkle_root;
 use crate::operations::{CallParams, Operations};
 
@@ -29,19 +29,26 @@ impl<T: Operations> Generator<T> {
         Self { executor }
     }
 
+    pub fn is_enabled(&self) -> bool {
+        self.executor.block_production_enabled()
+    }
+
     pub async fn generate_candidate_message(
         &self,
         ru: &RoundUpdate,
         iteration: u8,
         failed_iterations: IterationsInfo,
     ) -> Result<Message, crate::errors::OperationError> {
+        let faults = self.executor.pending_faults(ru.round).await;
         let candidate = self
-            .generate_block(ru, iteration, failed_iterations, &[])
+            .generate_block(ru, iteration, failed_iterations, &faults)
             .await?;
 
         let mut candidate_msg = Candidate { candidate };
 
-        candidate_msg.sign(&ru.secret_key, ru.pubkey_bls.inner());
+        ru.try_sign(&mut candidate_msg)
+            .await
+            .map_err(OperationError::SigningFailed)?;
 
         debug!(event = "Candidate signed", header = ?candidate_msg.candidate.header());
 
@@ -58,10 +65,10 @@ impl<T: Operations> Generator<T> {
         let start = Instant::now();
 
         // Sign seed
-        let seed_sig: [u8; 48] = ru
-            .secret_key
-            .sign_multisig(ru.pubkey_bls.inner(), &ru.seed().inner()[..])
-            .to_bytes();
+        let seed_sig = ru
+            .sign_multisig(&ru.seed().inner()[..])
+            .await
+            .map_err(OperationError::SigningFailed)?;
         let seed = Seed::from(seed_sig);
 
         // Limit number of faults in the block
@@ -118,6 +125,7 @@ impl<T: Operations> Generator<T> {
             voters_pubkey: voters.to_owned(),
             max_txs_bytes,
             prev_state_root: ru.state_root(),
+            block_gas_limit,
         };
 
         let result =
@@ -12,7 +12,7 @@ use node_data::get_current_timestamp;
 use node_data::ledger::IterationsInfo;
 use node_data::message::Message;
 use tokio::sync::Mutex;
-use tracing::{debug, error};
+use tracing::{debug, error, info};
 
 use crate::commons::Database;
 use crate::config;
@@ -59,7 +59,13 @@ impl<T: Operations + 'static, D: Database> ProposalStep<T, D> {
 
         let tip_timestamp = ctx.round_update.timestamp();
 
-        if ctx.am_member(committee) {
+        if ctx.am_member(committee) && !self.bg.is_enabled() {
+            info!(
+                event = "block production disabled",
+                round = ctx.round_update.round,
+                iteration = ctx.iteration,
+            );
+        } else if ctx.am_member(committee) {
             let iteration =
                 cmp::min(config::RELAX_ITERATION_THRESHOLD, ctx.iteration);
 
@@ -6,18 +6,33 @@
 
 use std::collections::{BTreeMap, VecDeque};
 use std::fmt::Debug;
+use std::time::{Duration, Instant};
 
 use node_data::message::Message;
 use thiserror::Error;
 use tracing::warn;
 
-type StepMap<T> = BTreeMap<u8, VecDeque<T>>;
+use crate::config::FUTURE_MSG_TTL;
+
+/// A queued message along with the time it was queued at
+type Queued<T> = (Instant, T);
+type StepMap<T> = BTreeMap<u8, VecDeque<Queued<T>>>;
 type RoundMap<T> = BTreeMap<u64, StepMap<T>>;
 
 const MAX_MESSAGES_PER_QUEUE: usize = 1000;
 
-#[derive(Debug, Default)]
-pub struct MsgRegistry<T: QueueMessage>(RoundMap<T>);
+#[derive(Debug)]
+pub struct MsgRegistry<T: QueueMessage> {
+    msgs: RoundMap<T>,
+    /// Time after which a queued message is discarded
+    ttl: Duration,
+}
+
+impl<T: QueueMessage> Default for MsgRegistry<T> {
+    fn default() -> Self {
+        Self::with_ttl(*FUTURE_MSG_TTL)
+    }
+}
 
 pub trait QueueMessage: Debug + Clone {
     fn step(&self) -> u8;
@@ -49,12 +64,24 @@ pub enum MsgRegistryError<T> {
 
 /// A message registry that stores messages based on their round and step.
 impl<T: QueueMessage> MsgRegistry<T> {
+    /// Creates a registry discarding the messages queued for longer than
+    /// `ttl`.
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            msgs: RoundMap::default(),
+            ttl,
+        }
+    }
+
     /// Inserts a message into the registry based on its round and step.
+    ///
+    /// The expired messages of the same round and step are discarded first.
     pub fn put_msg(&mut self, msg: T) -> Result<T, MsgRegistryError<T>> {
         let round = msg.round();
         let step = msg.step();
+        let ttl = self.ttl;
         let vec = self
-            .0
+            .msgs
             .entry(round)
             .or_default()
             .entry(step)
@@ -62,7 +89,13 @@ impl<T: QueueMessage> MsgRegistry<T> {
         if msg.signer().is_none() {
             return Err(MsgRegistryError::NoSigner(msg));
         }
-        if vec.iter().any(|m| m.signer() == msg.signer()) {
+
+        // Messages are queued in order, so the expired ones are at the front
+        while vec.front().is_some_and(|(at, _)| at.elapsed() > ttl) {
+            vec.pop_front();
+        }
+
+        if vec.iter().any(|(_, m)| m.signer() == msg.signer()) {
             return Err(MsgRegistryError::SignerAlreadyEnqueue(msg));
         }
 
@@ -72,29 +105,48 @@ impl<T: QueueMessage> MsgRegistry<T> {
         }
 
         let ret = msg.clone();
-        vec.push_back(msg);
+        vec.push_back((Instant::now(), msg));
         Ok(ret)
     }
 
     /// Drains and returns all messages that belong to the specified round and
-    /// step.
+    /// step, leaving out the expired ones.
     pub fn drain_msg_by_round_step(
         &mut self,
         round: u64,
         step: u8,
     ) -> Option<VecDeque<T>> {
-        self.0
+        let ttl = self.ttl;
+        self.msgs
             .get_mut(&round)
             .and_then(|r| r.remove_entry(&step).map(|(_, v)| v))
+            .map(|v| {
+                v.into_iter()
+                    .filter(|(at, _)| at.elapsed() <= ttl)
+                    .map(|(_, msg)| msg)
+                    .collect()
+            })
     }
 
     /// Removes all messages that belong to the specified round.
     pub fn remove_msgs_by_round(&mut self, round: u64) {
-        if let Some(r) = self.0.get_mut(&round) {
+        if let Some(r) = self.msgs.get_mut(&round) {
             r.clear();
         };
 
-        self.0.remove(&round);
+        self.msgs.remove(&round);
+    }
+
+    /// Removes all messages queued for longer than the TTL of the registry.
+    pub fn remove_expired_msgs(&mut self) {
+        let ttl = self.ttl;
+        for steps in self.msgs.values_mut() {
+            for vec in steps.values_mut() {
+                vec.retain(|(at, _)| at.elapsed() <= ttl);
+            }
+            steps.retain(|_, vec| !vec.is_empty());
+        }
+        self.msgs.retain(|_, steps| !steps.is_empty());
     }
 
     /// Removes all messages that do not belong to the range (closed interval)
@@ -102,8 +154,8 @@ impl<T: QueueMessage> MsgRegistry<T> {
     pub fn remove_msgs_out_of_range(&mut self, start_round: u64, offset: u64) {
         let end_round = start_round + offset;
 
-        self.0 = self
-            .0
+        self.msgs = self
+            .msgs
             .split_off(&start_round)
             .into_iter()
             .filter(|(k, _)| *k <= end_round)
@@ -112,7 +164,7 @@ impl<T: QueueMessage> MsgRegistry<T> {
 
     /// Returns the total number of messages in the registry.
     pub fn msg_count(&self) -> usize {
-        self.0
+        self.msgs
             .values()
             .map(|round| round.values().map(|items| items.len()).sum::<usize>())
             .sum()
@@ -121,6 +173,8 @@ impl<T: QueueMessage> MsgRegistry<T> {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use node_data::bls::PUBLIC_BLS_SIZE;
 
     use super::QueueMessage;
@@ -209,4 +263,25 @@ mod tests {
         assert!(reg.drain_msg_by_round_step(round + 2, 1).is_some());
         Ok(())
     }
+
+    #[test]
+    fn test_expired_msgs() -> Result<(), super::MsgRegistryError<Item>> {
+        let round = 100;
+
+        let mut reg = MsgRegistry::<Item>::with_ttl(Duration::ZERO);
+        reg.put_msg(Item::new(round, 1, 1))?;
+        reg.put_msg(Item::new(round + 1, 1, 1))?;
+        std::thread::sleep(Duration::from_millis(1));
+
+        // Expired messages are discarded when a new one is queued
+        reg.put_msg(Item::new(round, 1, 2))?;
+        assert_eq!(reg.msg_count(), 2);
+
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(reg.drain_msg_by_round_step(round, 1), Some([].into()));
+
+        reg.remove_expired_msgs();
+        assert_eq!(reg.msg_count(), 0);
+        Ok(())
+    }
 }
@@ -17,7 +17,6 @@ use node_data::{Serializable, StepName};
 use tokio::sync::RwLock;
 use tracing::error;
 
-use crate::config::exclude_next_generator;
 use crate::errors::StepSigError;
 use crate::operations::Voter;
 use crate::user::cluster::Cluster;
@@ -31,31 +30,8 @@ pub async fn verify_step_votes(
     committees_set: &RwLock<CommitteeSet<'_>>,
     seed: Seed,
     step: StepName,
-) -> Result<(QuorumResult, Vec<Voter>), StepSigError> {
-    let round = header.round;
-    let iteration = header.iteration;
-
-    let mut exclusion_list = vec![];
-    let generator = committees_set
-        .read()
-        .await
-        .provisioners()
-        .get_generator(iteration, seed, round);
-
-    exclusion_list.push(generator);
-
-    if exclude_next_generator(iteration) {
-        let next_generator = committees_set
-            .read()
-            .await
-            .provisioners()
-            .get_generator(iteration + 1, seed, round);
-
-        exclusion_list.push(next_generator);
-    }
-
-    let cfg =
-        sortition::Config::new(seed, round, iteration, step, exclusion_list);
+) -> Result<(QuorumResult, Vec<Voter>, Committee), StepSigError> {
+    let cfg = step_config(header, committees_set, seed, step).await;
 
     if committees_set.read().await.get(&cfg).is_none() {
         let _ = committees_set.write().await.get_or_create(&cfg);
@@ -81,7 +57,7 @@ pub async fn verify_step_votes(
             }
         )?;
 
-    Ok((quorum_result, voters))
+    Ok((quorum_result, voters, committee.clone()))
 }
 
 pub struct QuorumResult {
@@ -200,38 +176,31 @@ pub async fn get_step_voters(
     q_committee.to_voters()
 }
 
+/// Returns the sortition config of the committee of `step`, with the
+/// generators excluded as in [`Committee::for_step`].
+async fn step_config(
+    header: &ConsensusHeader,
+    committees_set: &RwLock<CommitteeSet<'_>>,
+    seed: Seed,
+    step: StepName,
+) -> sortition::Config {
+    let set = committees_set.read().await;
+    Committee::step_config(
+        set.provisioners(),
+        seed,
+        header.round,
+        header.iteration,
+        step,
+    )
+}
+
 async fn get_step_committee(
     header: &ConsensusHeader,
     committees_set: &RwLock<CommitteeSet<'_>>,
     seed: Seed,
     step: StepName,
 ) -> Committee {
-    let round = header.round;
-    let iteration = header.iteration;
-
-    // exclude current-iteration generator
-    let mut exclusion_list = vec![];
-    let generator = committees_set
-        .read()
-        .await
-        .provisioners()
-        .get_generator(iteration, seed, round);
-
-    exclusion_list.push(generator);
-
-    // exclude next-iteration generator
-    if exclude_next_generator(iteration) {
-        let next_generator = committees_set
-            .read()
-            .await
-            .provisioners()
-            .get_generator(iteration + 1, seed, round);
-
-        exclusion_list.push(next_generator);
-    }
-
-    let cfg =
-        sortition::Config::new(seed, round, iteration, step, exclusion_list);
+    let cfg = step_config(header, committees_set, seed, step).await;
 
     if committees_set.read().await.get(&cfg).is_none() {
         let _ = committees_set.write().await.get_or_create(&cfg);
@@ -6,7 +6,7 @@
 
 use async_trait::async_trait;
 use node_data::bls::PublicKeyBytes;
-use node_data::ledger::Attestation;
+use node_data::ledger::{Attestation, Fault};
 use node_data::message::payload::{Ratification, ValidationResult, Vote};
 use node_data::message::{
     payload, ConsensusHeader, Message, Payload, SignedStepMessage, StepMessage,
@@ -14,7 +14,7 @@ use node_data::message::{
 use node_data::{ledger, StepName};
 use tracing::{debug, error, info, warn};
 
-use crate::aggregator::{Aggregator, StepVote};
+use crate::aggregator::{Aggregator, AggregatorError, StepVote};
 use crate::commons::RoundUpdate;
 use crate::config::is_emergency_iter;
 use crate::errors::ConsensusError;
@@ -181,10 +181,14 @@ impl MsgHandler for RatificationHandler {
         }
 
         // Collect vote
-        let (ratification_sv, quorum_reached) = self
-            .aggregator
-            .collect_vote(committee, &p)
-            .map_err(|error| {
+        let collect_vote = self.aggregator.collect_vote(committee, &p);
+        if let Err(AggregatorError::ConflictingVote(prev)) = &collect_vote {
+            let fault = Fault::double_ratification(prev, &p);
+            self.sv_registry.lock().await.add_fault(fault);
+        }
+
+        let (ratification_sv, quorum_reached) =
+            collect_vote.map_err(|error| {
                 warn!(
                     event = "Cannot collect vote",
                     ?error,
@@ -254,6 +258,10 @@ impl MsgHandler for RatificationHandler {
                 }
             }
             Err(error) => {
+                if let AggregatorError::ConflictingVote(prev) = &error {
+                    let fault = Fault::double_ratification(prev, &p);
+                    self.sv_registry.lock().await.add_fault(fault);
+                }
                 warn!(
                     event = "Cannot collect vote",
                     ?error,
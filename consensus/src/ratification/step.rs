@@ -24,15 +24,17 @@ pub struct RatificationStep {
 }
 
 impl RatificationStep {
+    /// Casts the ratification vote, returning it unless it could not be
+    /// signed.
     pub async fn try_vote(
         ru: &RoundUpdate,
         iteration: u8,
         result: &ValidationResult,
         outbound: AsyncQueue<Message>,
-    ) -> Message {
+    ) -> Option<Message> {
         // Sign and construct ratification message
         let ratification =
-            self::build_ratification_payload(ru, iteration, result);
+            self::build_ratification_payload(ru, iteration, result).await?;
 
         let vote = ratification.vote;
         let msg = Message::from(ratification);
@@ -53,15 +55,17 @@ impl RatificationStep {
             outbound.try_send(msg.clone());
         }
 
-        msg
+        Some(msg)
     }
 }
 
-pub fn build_ratification_payload(
+/// Builds the ratification vote, signed unless the signer fails or refuses
+/// to.
+pub async fn build_ratification_payload(
     ru: &RoundUpdate,
     iteration: u8,
     result: &ValidationResult,
-) -> payload::Ratification {
+) -> Option<payload::Ratification> {
     let header = message::ConsensusHeader {
         prev_block_hash: ru.hash(),
         round: ru.round,
@@ -76,8 +80,8 @@ pub fn build_ratification_payload(
         validation_result: result.clone(),
         timestamp: get_current_timestamp(),
     };
-    ratification.sign(&ru.secret_key, ru.pubkey_bls.inner());
-    ratification
+    let signed = ru.sign(&mut ratification).await;
+    signed.then_some(ratification)
 }
 
 impl RatificationStep {
@@ -141,19 +145,21 @@ impl RatificationStep {
             .await;
 
             // Collect my own vote
-            match handler
-                .collect(
-                    vote_msg,
-                    &ctx.round_update,
-                    committee,
-                    generator,
-                    &ctx.iter_ctx.committees,
-                )
-                .await
-            {
-                Ok(StepOutcome::Ready(m)) => return m,
-                Ok(_) => {}
-                Err(e) => warn!("Error collecting own vote: {e:?}"),
+            if let Some(vote_msg) = vote_msg {
+                match handler
+                    .collect(
+                        vote_msg,
+                        &ctx.round_update,
+                        committee,
+                        generator,
+                        &ctx.iter_ctx.committees,
+                    )
+                    .await
+                {
+                    Ok(StepOutcome::Ready(m)) => return m,
+                    Ok(_) => {}
+                    Err(e) => warn!("Error collecting own vote: {e:?}"),
+                }
             }
         }
 
@@ -0,0 +1,241 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Protection of the provisioner against signing conflicting messages.
+//!
+//! Every consensus message is recorded, by round, iteration and step, in an
+//! append-only journal synced to disk before the message is signed. A message
+//! conflicting with one already recorded is refused, so that a node restarted
+//! after a crash, or restored from a backup of its database, cannot
+//! equivocate.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use node_data::message::SignedStepMessage;
+use node_data::StepName;
+use sha3::{Digest, Sha3_256};
+use tokio::task;
+use tracing::info;
+
+use crate::signer::SignerError;
+
+/// Number of rounds, before the last one signed, kept in the journal when it
+/// is compacted
+const KEPT_ROUNDS: u64 = 1_000;
+
+/// Size of a record: round, iteration, step and hash of the message
+const RECORD_SIZE: usize = 8 + 1 + 1 + 32;
+
+/// Round, iteration and step a message is signed for
+type Slot = (u64, u8, u8);
+
+#[derive(Debug)]
+pub struct SigningGuard {
+    journal: Mutex<Journal>,
+}
+
+#[derive(Debug)]
+struct Journal {
+    file: File,
+    /// Length of the file, up to the last complete record
+    len: u64,
+    signed: BTreeMap<Slot, [u8; 32]>,
+}
+
+impl SigningGuard {
+    /// Opens the journal at `path`, creating it if missing.
+    ///
+    /// The records of the rounds older than the last [`KEPT_ROUNDS`] signed
+    /// are dropped.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut signed = BTreeMap::new();
+        match fs::read(path) {
+            // A record partially written by a crash is discarded, its message
+            // having never been signed
+            Ok(bytes) => signed
+                .extend(bytes.chunks_exact(RECORD_SIZE).map(decode_record)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        if let Some(&(last, _, _)) = signed.keys().next_back() {
+            let first = last.saturating_sub(KEPT_ROUNDS);
+            signed = signed.split_off(&(first, 0, 0));
+        }
+
+        // The compacted journal replaces the previous one only once complete
+        let tmp = tmp_path(path);
+        let mut file = File::create(&tmp)?;
+        for (slot, hash) in &signed {
+            file.write_all(&encode_record(slot, hash))?;
+        }
+        file.sync_all()?;
+        fs::rename(&tmp, path)?;
+
+        let file = OpenOptions::new().append(true).open(path)?;
+        let len = file.metadata()?.len();
+        info!(
+            event = "signing journal loaded",
+            ?path,
+            records = signed.len()
+        );
+
+        Ok(Self {
+            journal: Mutex::new(Journal { file, len, signed }),
+        })
+    }
+
+    /// Records `msg` before it is signed.
+    ///
+    /// Signing again the message recorded for its round, iteration and step
+    /// is allowed, while a different one is refused. The journal is synced
+    /// to disk on a blocking thread, not to stall the consensus tasks.
+    pub async fn check<M: SignedStepMessage>(
+        self: &Arc<Self>,
+        msg: &M,
+    ) -> Result<(), SignerError> {
+        let header = msg.header();
+        let (round, iteration) = (header.round, header.iteration);
+        let step = M::STEP_NAME;
+        let hash = Sha3_256::digest(msg.signable()).into();
+
+        let guard = self.clone();
+        task::spawn_blocking(move || guard.record(round, iteration, step, hash))
+            .await
+            .map_err(|e| {
+                SignerError::Refused(format!(
+                    "cannot record in the signing journal: {e}"
+                ))
+            })?
+    }
+
+    fn record(
+        &self,
+        round: u64,
+        iteration: u8,
+        step: StepName,
+        hash: [u8; 32],
+    ) -> Result<(), SignerError> {
+        let mut journal = self
+            .journal
+            .lock()
+            .map_err(|_| SignerError::Refused("journal poisoned".into()))?;
+
+        let slot = (round, iteration, step as u8);
+        match journal.signed.get(&slot) {
+            Some(signed) if *signed == hash => return Ok(()),
+            Some(_) => {
+                return Err(SignerError::Refused(format!(
+                    "conflicting {step:?} already signed for round {round} \
+                     iteration {iteration}"
+                )))
+            }
+            None => {}
+        }
+
+        let record = encode_record(&slot, &hash);
+        let written = journal
+            .file
+            .write_all(&record)
+            .and_then(|_| journal.file.sync_data());
+        if let Err(e) = written {
+            // A partial record would misalign the following ones
+            let len = journal.len;
+            let _ = journal.file.set_len(len);
+            return Err(SignerError::Refused(format!(
+                "cannot record in the signing journal: {e}"
+            )));
+        }
+
+        journal.len += RECORD_SIZE as u64;
+        journal.signed.insert(slot, hash);
+        Ok(())
+    }
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp = OsString::from(path.as_os_str());
+    tmp.push(".tmp");
+    tmp.into()
+}
+
+fn encode_record(
+    &(round, iteration, step): &Slot,
+    hash: &[u8; 32],
+) -> [u8; RECORD_SIZE] {
+    let mut record = [0u8; RECORD_SIZE];
+    record[..8].copy_from_slice(&round.to_le_bytes());
+    record[8] = iteration;
+    record[9] = step;
+    record[10..].copy_from_slice(hash);
+    record
+}
+
+fn decode_record(record: &[u8]) -> (Slot, [u8; 32]) {
+    let mut round = [0u8; 8];
+    round.copy_from_slice(&record[..8]);
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&record[10..RECORD_SIZE]);
+    ((u64::from_le_bytes(round), record[8], record[9]), hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conflicting_message_refused_after_restart() {
+        let dir = std::env::temp_dir()
+            .join(format!("signing-guard-{}", std::process::id()));
+        let path = dir.join("consensus.journal");
+
+        let guard = SigningGuard::open(&path).expect("journal to open");
+        guard
+            .record(10, 0, StepName::Validation, [1; 32])
+            .expect("first vote to be recorded");
+        guard
+            .record(10, 0, StepName::Ratification, [2; 32])
+            .expect("other step to be recorded");
+        drop(guard);
+
+        // A crash while appending leaves a partial record
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[0xff; 5]).unwrap();
+        drop(file);
+
+        let guard = SigningGuard::open(&path).expect("journal to reopen");
+        guard
+            .record(10, 0, StepName::Validation, [1; 32])
+            .expect("same vote to be signed again");
+        assert!(guard.record(10, 0, StepName::Validation, [3; 32]).is_err());
+        guard
+            .record(10, 1, StepName::Validation, [3; 32])
+            .expect("next iteration to be recorded");
+
+        // Old rounds are compacted away
+        guard
+            .record(10 + KEPT_ROUNDS + 1, 0, StepName::Proposal, [4; 32])
+            .expect("later round to be recorded");
+        drop(guard);
+        let guard = SigningGuard::open(&path).expect("journal to reopen");
+        guard
+            .record(10, 0, StepName::Validation, [3; 32])
+            .expect("compacted round to be recorded");
+        assert_eq!(fs::metadata(&path).unwrap().len(), 2 * RECORD_SIZE as u64);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,71 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Signing on behalf of the provisioner running the consensus, so that its
+//! secret key doesn't have to be held by the node process.
+
+use std::fmt;
+
+use async_trait::async_trait;
+use dusk_bytes::Serializable;
+use dusk_core::signatures::bls::{
+    PublicKey as BlsPublicKey, SecretKey as BlsSecretKey,
+};
+use node_data::message::{SignInfo, SignedStepMessage};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SignerError {
+    #[error("signer unreachable: {0}")]
+    Unreachable(String),
+    #[error("signature refused: {0}")]
+    Refused(String),
+}
+
+#[async_trait]
+pub trait Signer: fmt::Debug + Send + Sync {
+    /// Returns the BLS multisig signature of `msg` by the provisioner.
+    async fn sign_multisig(&self, msg: &[u8]) -> Result<[u8; 48], SignerError>;
+}
+
+/// Signer holding the secret key in memory.
+#[derive(Clone)]
+pub struct LocalSigner {
+    sk: BlsSecretKey,
+    pk: BlsPublicKey,
+}
+
+impl LocalSigner {
+    pub fn new(sk: BlsSecretKey, pk: BlsPublicKey) -> Self {
+        Self { sk, pk }
+    }
+}
+
+impl fmt::Debug for LocalSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSigner").finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl Signer for LocalSigner {
+    async fn sign_multisig(&self, msg: &[u8]) -> Result<[u8; 48], SignerError> {
+        Ok(self.sk.sign_multisig(&self.pk, msg).to_bytes())
+    }
+}
+
+/// Signs `msg` with `signer`, as the provisioner of public key `pk`.
+pub(crate) async fn sign_step_message<M: SignedStepMessage>(
+    signer: &dyn Signer,
+    pk: &node_data::bls::PublicKey,
+    msg: &mut M,
+) -> Result<(), SignerError> {
+    let signature = signer.sign_multisig(&msg.signable()).await?;
+    *msg.sign_info_mut() = SignInfo {
+        signer: pk.clone(),
+        signature: signature.into(),
+    };
+    Ok(())
+}
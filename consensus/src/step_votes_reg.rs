@@ -9,7 +9,7 @@ use std::fmt;
 use std::sync::Arc;
 
 use node_data::bls::PublicKeyBytes;
-use node_data::ledger::{Attestation, IterationInfo, StepVotes};
+use node_data::ledger::{Attestation, Fault, IterationInfo, StepVotes};
 use node_data::message::payload::{RatificationResult, Vote};
 use node_data::message::{payload, Message};
 use node_data::StepName;
@@ -156,6 +156,9 @@ pub struct AttInfoRegistry {
 
     /// Iterations attestations for current round keyed by iteration
     att_list: HashMap<u8, IterationAtts>,
+
+    /// Faults detected while collecting the votes, not yet reported
+    faults: Vec<Fault>,
 }
 
 impl AttInfoRegistry {
@@ -163,9 +166,22 @@ impl AttInfoRegistry {
         Self {
             ru,
             att_list: HashMap::new(),
+            faults: vec![],
+        }
+    }
+
+    /// Records a fault committed by a committee member
+    pub(crate) fn add_fault(&mut self, fault: Fault) {
+        if !self.faults.iter().any(|f| f.same(&fault)) {
+            self.faults.push(fault);
         }
     }
 
+    /// Returns the faults recorded since the last call
+    pub(crate) fn take_faults(&mut self) -> Vec<Fault> {
+        std::mem::take(&mut self.faults)
+    }
+
     /// Set step votes per iteration
     /// Returns a quorum if both validation and ratification for an iteration
     /// exist
@@ -8,9 +8,11 @@ use std::collections::{BTreeMap, HashMap};
 use std::{fmt, mem};
 
 use node_data::bls::{PublicKey, PublicKeyBytes};
+use node_data::ledger::{CommitteeMember, Header, Seed, TrustedCommittees};
+use node_data::StepName;
 
 use super::cluster::Cluster;
-use crate::config::{majority, supermajority};
+use crate::config::{exclude_next_generator, majority, supermajority};
 use crate::user::provisioners::Provisioners;
 use crate::user::sortition;
 
@@ -57,6 +59,51 @@ impl Committee {
         committee
     }
 
+    /// Generates the committee of `step` at `iteration` of `round`, as
+    /// consensus does.
+    ///
+    /// The generators of the iteration and of the next one are excluded from
+    /// the Validation and Ratification committees.
+    ///
+    /// Returns the committee along with the sortition config it is drawn
+    /// with, for the sortition to be recomputed.
+    pub fn for_step(
+        provisioners: &Provisioners,
+        seed: Seed,
+        round: u64,
+        iteration: u8,
+        step: StepName,
+    ) -> (Self, sortition::Config) {
+        let cfg = Self::step_config(provisioners, seed, round, iteration, step);
+        (Self::new(provisioners, &cfg), cfg)
+    }
+
+    /// Returns the sortition config of the committee of `step` at
+    /// `iteration` of `round`, as drawn by [`Committee::for_step`].
+    pub fn step_config(
+        provisioners: &Provisioners,
+        seed: Seed,
+        round: u64,
+        iteration: u8,
+        step: StepName,
+    ) -> sortition::Config {
+        let exclusion = match step {
+            StepName::Proposal => vec![],
+            _ => {
+                let mut exclusion =
+                    vec![provisioners.get_generator(iteration, seed, round)];
+                if exclude_next_generator(iteration) {
+                    let next = iteration + 1;
+                    exclusion
+                        .push(provisioners.get_generator(next, seed, round));
+                }
+                exclusion
+            }
+        };
+
+        sortition::Config::new(seed, round, iteration, step, exclusion)
+    }
+
     pub fn excluded(&self) -> &Vec<PublicKeyBytes> {
         &self.excluded
     }
@@ -142,6 +189,33 @@ impl fmt::Display for &Committee {
     }
 }
 
+/// Draws the committees light clients expect from the provisioners they trust
+/// at a checkpoint.
+impl TrustedCommittees for Provisioners {
+    fn committee(
+        &self,
+        prev: &Header,
+        header: &Header,
+        step: StepName,
+    ) -> Vec<CommitteeMember> {
+        let (committee, _) = Committee::for_step(
+            self,
+            prev.seed,
+            header.height,
+            header.iteration,
+            step,
+        );
+        committee
+            .members()
+            .iter()
+            .map(|(pk, credits)| CommitteeMember {
+                key: *pk.bytes(),
+                credits: *credits,
+            })
+            .collect()
+    }
+}
+
 /// Implements a cache of generated committees so that they can be reused.
 #[derive(Clone)]
 pub struct CommitteeSet<'p> {
@@ -23,6 +23,8 @@ pub const DUSK: u64 = dusk(1.0);
 #[derive(Clone, Debug)]
 pub struct Provisioners {
     members: BTreeMap<PublicKey, Stake>,
+    /// Minimum stake for a provisioner to be eligible
+    minimum_stake: u64,
 }
 
 impl Provisioners {
@@ -107,9 +109,21 @@ impl Provisioners {
     pub fn empty() -> Self {
         Self {
             members: BTreeMap::default(),
+            minimum_stake: DEFAULT_MINIMUM_STAKE,
         }
     }
 
+    /// Returns the minimum stake for a provisioner to be eligible
+    pub fn minimum_stake(&self) -> u64 {
+        self.minimum_stake
+    }
+
+    /// Sets the minimum stake for a provisioner to be eligible, as configured
+    /// in the stake contract
+    pub fn set_minimum_stake(&mut self, minimum_stake: u64) {
+        self.minimum_stake = minimum_stake;
+    }
+
     /// Adds a provisioner with stake.
     ///
     /// If the provisioner already exists, no action is performed.
@@ -182,7 +196,7 @@ impl Provisioners {
         round: u64,
     ) -> impl Iterator<Item = (&PublicKey, &Stake)> {
         self.members.iter().filter(move |(_, m)| {
-            m.is_eligible(round) && m.value() >= DEFAULT_MINIMUM_STAKE
+            m.is_eligible(round) && m.value() >= self.minimum_stake
         })
     }
 
@@ -48,6 +48,10 @@ impl Config {
         }
     }
 
+    pub fn seed(&self) -> Seed {
+        self.seed
+    }
+
     pub fn committee_credits(&self) -> usize {
         self.committee_credits
     }
@@ -8,7 +8,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use node_data::bls::PublicKeyBytes;
-use node_data::ledger::{to_str, Block, StepVotes};
+use node_data::ledger::{to_str, Block, Fault, StepVotes};
 use node_data::message::payload::{
     GetResource, Inv, QuorumType, Validation, Vote,
 };
@@ -19,7 +19,7 @@ use node_data::StepName;
 use tokio::sync::Mutex;
 use tracing::{debug, info, warn};
 
-use crate::aggregator::{Aggregator, StepVote};
+use crate::aggregator::{Aggregator, AggregatorError, StepVote};
 use crate::commons::{Database, RoundUpdate};
 use crate::config::is_emergency_iter;
 use crate::errors::ConsensusError;
@@ -171,19 +171,24 @@ impl<D: Database> MsgHandler for ValidationHandler<D> {
             return Err(ConsensusError::InvalidMsgIteration(iteration));
         }
 
-        let (sv, quorum_reached) =
-            self.aggr.collect_vote(committee, &p).map_err(|error| {
-                warn!(
-                    event = "Cannot collect vote",
-                    ?error,
-                    from = p.sign_info().signer.to_bs58(),
-                    vote = ?p.vote,
-                    msg_step = p.get_step(),
-                    msg_iter = p.header().iteration,
-                    msg_height = p.header().round,
-                );
-                ConsensusError::InvalidVote(p.vote)
-            })?;
+        let collect_vote = self.aggr.collect_vote(committee, &p);
+        if let Err(AggregatorError::ConflictingVote(prev)) = &collect_vote {
+            let fault = Fault::double_validation(prev, &p);
+            self.sv_registry.lock().await.add_fault(fault);
+        }
+
+        let (sv, quorum_reached) = collect_vote.map_err(|error| {
+            warn!(
+                event = "Cannot collect vote",
+                ?error,
+                from = p.sign_info().signer.to_bs58(),
+                vote = ?p.vote,
+                msg_step = p.get_step(),
+                msg_iter = p.header().iteration,
+                msg_height = p.header().round,
+            );
+            ConsensusError::InvalidVote(p.vote)
+        })?;
         // Record result in global round registry
         _ = self.sv_registry.lock().await.set_step_votes(
             iteration,
@@ -288,6 +293,10 @@ impl<D: Database> MsgHandler for ValidationHandler<D> {
                 }
             }
             Err(error) => {
+                if let AggregatorError::ConflictingVote(prev) = &error {
+                    let fault = Fault::double_validation(prev, &p);
+                    self.sv_registry.lock().await.add_fault(fault);
+                }
                 warn!(
                     event = "Cannot collect vote",
                     ?error,
@@ -144,7 +144,11 @@ impl<T: Operations + 'static, D: Database> ValidationStep<T, D> {
         inbound: AsyncQueue<Message>,
     ) {
         // Sign and construct validation message
-        let validation = self::build_validation_payload(vote, ru, iteration);
+        let Some(validation) =
+            self::build_validation_payload(vote, ru, iteration).await
+        else {
+            return;
+        };
         let vote = validation.vote;
         let msg = Message::from(validation);
 
@@ -196,11 +200,13 @@ impl<T: Operations + 'static, D: Database> ValidationStep<T, D> {
     }
 }
 
-pub fn build_validation_payload(
+/// Builds the validation vote, signed unless the signer fails or refuses
+/// to.
+pub async fn build_validation_payload(
     vote: Vote,
     ru: &RoundUpdate,
     iteration: u8,
-) -> Validation {
+) -> Option<Validation> {
     let header = ConsensusHeader {
         prev_block_hash: ru.hash(),
         round: ru.round,
@@ -213,8 +219,8 @@ pub fn build_validation_payload(
         vote,
         sign_info,
     };
-    validation.sign(&ru.secret_key, ru.pubkey_bls.inner());
-    validation
+    let signed = ru.sign(&mut validation).await;
+    signed.then_some(validation)
 }
 
 impl<T: Operations + 'static, D: Database> ValidationStep<T, D> {
@@ -0,0 +1,295 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Invariants of the deterministic sortition and of the committees built
+//! upon it.
+
+use std::sync::OnceLock;
+
+use dusk_consensus::user::committee::Committee;
+use dusk_consensus::user::provisioners::{Provisioners, DUSK};
+use dusk_consensus::user::sortition::{
+    create_sortition_hash, generate_sortition_score, Config,
+};
+use dusk_consensus::user::stake::Stake;
+use node_data::bls::{PublicKey, PublicKeyBytes};
+use node_data::ledger::Seed;
+use node_data::StepName;
+use num_bigint::BigInt;
+use proptest::prelude::*;
+
+const MAX_PROVISIONERS: usize = 16;
+
+/// Minimum stake to be eligible, in DUSK
+const MINIMUM_STAKE: u64 = 1_000;
+
+/// Keys of the provisioners, generated once as it is expensive.
+fn keys() -> &'static [PublicKey] {
+    static KEYS: OnceLock<Vec<PublicKey>> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        (0..MAX_PROVISIONERS as u64)
+            .map(PublicKey::from_sk_seed_u64)
+            .collect()
+    })
+}
+
+fn provisioners(stakes: &[Stake]) -> Provisioners {
+    let mut provisioners = Provisioners::empty();
+    for (pk, stake) in keys().iter().zip(stakes) {
+        provisioners.add_member_with_stake(pk.clone(), stake.clone());
+    }
+    provisioners
+}
+
+fn seed() -> impl Strategy<Value = Seed> {
+    prop::collection::vec(any::<u8>(), 48).prop_map(|bytes| {
+        let bytes: [u8; 48] = bytes.try_into().expect("48 bytes");
+        Seed::from(bytes)
+    })
+}
+
+fn step() -> impl Strategy<Value = StepName> {
+    prop_oneof![
+        Just(StepName::Proposal),
+        Just(StepName::Validation),
+        Just(StepName::Ratification),
+    ]
+}
+
+/// Stakes, in DUSK, of provisioners all eligible from round 0
+fn eligible_stakes() -> impl Strategy<Value = Vec<Stake>> {
+    prop::collection::vec(MINIMUM_STAKE..1_000_000, 1..=MAX_PROVISIONERS)
+        .prop_map(|values| {
+            values
+                .into_iter()
+                .map(|value| Stake::from_value(value * DUSK))
+                .collect()
+        })
+}
+
+proptest! {
+    #[test]
+    fn credits_are_conserved(
+        stakes in eligible_stakes(),
+        seed in seed(),
+        round in 0..1_000_000u64,
+        iteration in 0..50u8,
+        step in step(),
+    ) {
+        let provisioners = provisioners(&stakes);
+        let cfg = Config::new(seed, round, iteration, step, vec![]);
+        let committee = Committee::new(&provisioners, &cfg);
+
+        let credits: usize = committee.get_occurrences().iter().sum();
+        prop_assert_eq!(credits, cfg.committee_credits());
+        prop_assert!(committee.size() <= stakes.len());
+    }
+
+    #[test]
+    fn exclusion_is_respected(
+        stakes in eligible_stakes(),
+        excluded in any::<u16>(),
+        seed in seed(),
+        round in 0..1_000_000u64,
+        iteration in 0..50u8,
+    ) {
+        // The first provisioner is never excluded, so that there is
+        // always someone left to extract
+        let exclusion: Vec<PublicKeyBytes> = keys()[..stakes.len()]
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i > 0 && excluded & (1 << i) != 0)
+            .map(|(_, pk)| *pk.bytes())
+            .collect();
+
+        let provisioners = provisioners(&stakes);
+        let cfg = Config::new(
+            seed,
+            round,
+            iteration,
+            StepName::Validation,
+            exclusion.clone(),
+        );
+        let committee = Committee::new(&provisioners, &cfg);
+
+        prop_assert_eq!(committee.excluded(), &exclusion);
+        for member in committee.iter() {
+            prop_assert!(!exclusion.contains(member.bytes()));
+        }
+        let credits: usize = committee.get_occurrences().iter().sum();
+        prop_assert_eq!(credits, cfg.committee_credits());
+    }
+
+    #[test]
+    fn only_eligibles_are_extracted(
+        stakes in prop::collection::vec(
+            (0..2 * MINIMUM_STAKE, 0..2_000u64),
+            1..MAX_PROVISIONERS,
+        ),
+        seed in seed(),
+        iteration in 0..50u8,
+    ) {
+        let round = 1_000;
+
+        // Make sure at least one provisioner is eligible
+        let first = Stake::new(MINIMUM_STAKE * DUSK, 0);
+        let stakes: Vec<_> = std::iter::once(first)
+            .chain(stakes.into_iter().map(|(value, eligible_since)| {
+                Stake::new(value * DUSK, eligible_since)
+            }))
+            .collect();
+
+        let provisioners = provisioners(&stakes);
+        let cfg = Config::new(
+            seed,
+            round,
+            iteration,
+            StepName::Ratification,
+            vec![],
+        );
+        let committee = Committee::new(&provisioners, &cfg);
+
+        for (pk, stake) in keys().iter().zip(&stakes) {
+            let eligible = stake.is_eligible(round)
+                && stake.value() >= MINIMUM_STAKE * DUSK;
+            if !eligible {
+                prop_assert!(!committee.is_member(pk));
+            }
+        }
+    }
+
+    #[test]
+    fn sortition_is_deterministic(
+        stakes in eligible_stakes(),
+        seed in seed(),
+        round in 0..1_000_000u64,
+        iteration in 0..50u8,
+        step in step(),
+    ) {
+        let cfg = Config::new(seed, round, iteration, step, vec![]);
+        let committee = Committee::new(&provisioners(&stakes), &cfg);
+
+        // The insertion order of the provisioners does not matter
+        let mut reversed = Provisioners::empty();
+        for (pk, stake) in keys().iter().zip(&stakes).rev() {
+            reversed.add_member_with_stake(pk.clone(), stake.clone());
+        }
+
+        prop_assert_eq!(
+            committee.members(),
+            Committee::new(&reversed, &cfg).members()
+        );
+        prop_assert_eq!(
+            committee.members(),
+            Committee::new(&provisioners(&stakes), &cfg).members()
+        );
+    }
+
+    #[test]
+    fn score_is_below_total_weight(
+        seed in seed(),
+        iteration in 0..50u8,
+        counter in any::<u32>(),
+        total_weight in 1..u64::MAX,
+    ) {
+        let cfg =
+            Config::new(seed, 0, iteration, StepName::Validation, vec![]);
+        let hash = create_sortition_hash(&cfg, counter);
+        let total_weight = BigInt::from(total_weight);
+
+        let score = generate_sortition_score(hash, &total_weight);
+
+        // The hash is read as a big-endian number on every platform
+        let num = hash
+            .iter()
+            .fold(BigInt::from(0), |num, byte| num * 256u32 + *byte);
+        prop_assert_eq!(&score, &(num % &total_weight));
+        prop_assert!(score < total_weight);
+    }
+
+    #[test]
+    fn step_committee_excludes_generators(
+        stakes in prop::collection::vec(
+            MINIMUM_STAKE..1_000_000,
+            3..=MAX_PROVISIONERS,
+        ),
+        seed in seed(),
+        round in 0..1_000_000u64,
+        iteration in 0..50u8,
+        step in step(),
+    ) {
+        let stakes: Vec<_> = stakes
+            .into_iter()
+            .map(|value| Stake::from_value(value * DUSK))
+            .collect();
+        let provisioners = provisioners(&stakes);
+        let (committee, cfg) =
+            Committee::for_step(&provisioners, seed, round, iteration, step);
+
+        let generator = provisioners.get_generator(iteration, seed, round);
+        let excluded = match step {
+            StepName::Proposal => vec![],
+            _ if iteration == 49 => vec![generator],
+            _ => vec![
+                generator,
+                provisioners.get_generator(iteration + 1, seed, round),
+            ],
+        };
+        prop_assert_eq!(cfg.exclusion(), &excluded);
+        prop_assert_eq!(cfg.step(), step.to_step(iteration));
+
+        // The committee can be recomputed from the sortition config alone
+        prop_assert_eq!(
+            committee.members(),
+            Committee::new(&provisioners, &cfg).members()
+        );
+    }
+}
+
+/// Over many rounds, the credits of each provisioner are proportional to its
+/// stake.
+#[test]
+fn committee_distribution_follows_stake() {
+    const ROUNDS: u64 = 2_000;
+
+    // Stakes large enough not to be affected by the 1 DUSK subtracted at
+    // each extraction
+    let weights = [1, 2, 3, 4];
+    let stakes: Vec<_> = weights
+        .iter()
+        .map(|w| Stake::from_value(w * 1_000_000 * DUSK))
+        .collect();
+    let provisioners = provisioners(&stakes);
+
+    let mut credits = [0usize; 4];
+    for round in 0..ROUNDS {
+        let mut seed = [0u8; 48];
+        seed[..8].copy_from_slice(&round.to_le_bytes());
+        let cfg = Config::new(
+            Seed::from(seed),
+            round,
+            0,
+            StepName::Validation,
+            vec![],
+        );
+
+        let committee = Committee::new(&provisioners, &cfg);
+        for (i, pk) in keys().iter().take(weights.len()).enumerate() {
+            credits[i] += committee.votes_for(pk).unwrap_or_default();
+        }
+    }
+
+    let total_credits: usize = credits.iter().sum();
+    let total_weight: u64 = weights.iter().sum();
+    for (credits, weight) in credits.iter().zip(weights) {
+        let share = *credits as f64 / total_credits as f64;
+        let expected = weight as f64 / total_weight as f64;
+        assert!(
+            (share - expected).abs() < 0.01,
+            "share {share:.4} too far from the expected {expected:.4}"
+        );
+    }
+}
@@ -46,6 +46,22 @@ unsafe fn withdraw(arg_len: u32) -> u32 {
     })
 }
 
+#[no_mangle]
+unsafe fn set_beneficiary(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |arg| {
+        assert_transfer_caller();
+        STATE.set_beneficiary(arg)
+    })
+}
+
+#[no_mangle]
+unsafe fn withdraw_to_beneficiary(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |arg| {
+        assert_transfer_caller();
+        STATE.withdraw_to_beneficiary(arg)
+    })
+}
+
 #[no_mangle]
 unsafe fn stake_from_contract(arg_len: u32) -> u32 {
     abi::wrap_call(arg_len, |receive| {
@@ -83,6 +99,21 @@ unsafe fn get_stake_keys(arg_len: u32) -> u32 {
     abi::wrap_call(arg_len, |pk| STATE.get_stake_keys(&pk).cloned())
 }
 
+#[no_mangle]
+unsafe fn get_beneficiary(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |pk| STATE.get_beneficiary(&pk).cloned())
+}
+
+#[no_mangle]
+unsafe fn beneficiary_nonce(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |pk| STATE.beneficiary_nonce(&pk))
+}
+
+#[no_mangle]
+unsafe fn withdraw_nonce(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |pk| STATE.withdraw_nonce(&pk))
+}
+
 #[no_mangle]
 unsafe fn burnt_amount(arg_len: u32) -> u32 {
     abi::wrap_call(arg_len, |_: ()| STATE.burnt_amount())
@@ -128,6 +159,14 @@ unsafe fn set_config(arg_len: u32) -> u32 {
     })
 }
 
+#[no_mangle]
+unsafe fn set_slashing_config(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |slashing| {
+        assert_external_caller();
+        STATE.configure_slashing(slashing)
+    })
+}
+
 #[no_mangle]
 unsafe fn insert_stake(arg_len: u32) -> u32 {
     abi::wrap_call(arg_len, |(pk, stake_data)| {
@@ -12,9 +12,10 @@ use dusk_bytes::Serializable;
 use dusk_core::abi::{self, ContractId};
 use dusk_core::signatures::bls::PublicKey as BlsPublicKey;
 use dusk_core::stake::{
-    next_epoch, Reward, SlashEvent, Stake, StakeAmount, StakeConfig, StakeData,
-    StakeEvent, StakeFundOwner, StakeKeys, Withdraw, WithdrawToContract, EPOCH,
-    STAKE_CONTRACT,
+    Beneficiary, BeneficiaryEvent, Reward, SetBeneficiary, SlashEvent,
+    SlashingConfig, Stake, StakeAmount, StakeConfig, StakeData, StakeEvent,
+    StakeFundOwner, StakeKeys, Withdraw, WithdrawToBeneficiary,
+    WithdrawToContract, STAKE_CONTRACT,
 };
 use dusk_core::transfer::{
     ContractToContract, ReceiveFromContract, TRANSFER_CONTRACT,
@@ -35,9 +36,17 @@ pub struct StakeState {
     previous_block_state:
         BTreeMap<[u8; BlsPublicKey::SIZE], (Option<StakeData>, BlsPublicKey)>,
     stakes: BTreeMap<[u8; BlsPublicKey::SIZE], (StakeData, StakeKeys)>,
+    // NOTE: entries are never removed from this map, since they hold the
+    //       nonce of the last registration. Removing them would allow old
+    //       registrations to be replayed.
+    beneficiaries:
+        BTreeMap<[u8; BlsPublicKey::SIZE], (Option<Beneficiary>, u64)>,
+    // NOTE: for the same reason, the nonce of the last withdrawal to the
+    //       beneficiary is kept after the stake is removed.
+    withdraw_nonces: BTreeMap<[u8; BlsPublicKey::SIZE], u64>,
 }
 
-const STAKE_CONTRACT_VERSION: u64 = 8;
+const STAKE_CONTRACT_VERSION: u64 = 9;
 
 impl StakeState {
     pub const fn new() -> Self {
@@ -46,6 +55,8 @@ impl StakeState {
             config: StakeConfig::new(),
             previous_block_state: BTreeMap::new(),
             stakes: BTreeMap::new(),
+            beneficiaries: BTreeMap::new(),
+            withdraw_nonces: BTreeMap::new(),
         }
     }
 
@@ -54,9 +65,14 @@ impl StakeState {
     }
 
     pub fn configure(&mut self, config: StakeConfig) {
+        assert!(config.epoch > 0, "The epoch must not be empty");
         self.config = config;
     }
 
+    pub fn configure_slashing(&mut self, slashing: SlashingConfig) {
+        self.config.slashing = slashing;
+    }
+
     pub fn on_new_block(&mut self) {
         self.previous_block_state.clear()
     }
@@ -87,6 +103,8 @@ impl StakeState {
 
     pub fn stake(&mut self, stake: Stake) {
         let minimum_stake = self.config.minimum_stake;
+        // A new stake is eligible once matured
+        let eligibility = self.config.eligibility(abi::block_height());
         let value = stake.value();
         let signature = *stake.signature();
 
@@ -133,7 +151,8 @@ impl StakeState {
                 StakeEvent::new(*keys, value).locked(locked)
             }
             amount => {
-                let _ = amount.insert(StakeAmount::new(value, block_height));
+                let _ = amount
+                    .insert(StakeAmount::with_eligibility(value, eligibility));
                 StakeEvent::new(*keys, value)
             }
         };
@@ -150,6 +169,7 @@ impl StakeState {
             rkyv::from_bytes(&recv.data).expect("Invalid stake received");
         let value = stake.value();
         let minimum_stake = self.config.minimum_stake;
+        let eligibility = self.config.eligibility(abi::block_height());
 
         if stake.chain_id() != self.chain_id() {
             panic!("The stake must target the correct chain");
@@ -192,7 +212,8 @@ impl StakeState {
                 StakeEvent::new(*keys, value).locked(locked)
             }
             amount => {
-                let _ = amount.insert(StakeAmount::new(value, block_height));
+                let _ = amount
+                    .insert(StakeAmount::with_eligibility(value, eligibility));
                 StakeEvent::new(*keys, value)
             }
         };
@@ -431,6 +452,123 @@ impl StakeState {
         }
     }
 
+    /// Registers, or clears, the beneficiary of the rewards of a stake.
+    pub fn set_beneficiary(&mut self, set: SetBeneficiary) {
+        if set.chain_id() != self.chain_id() {
+            panic!("The beneficiary must target the correct chain");
+        }
+
+        let keys = self
+            .get_stake_keys(set.account())
+            .expect("A stake should exist in the map to set its beneficiary");
+        let owner = Self::unwrap_account_owner(&keys.owner);
+
+        if !abi::verify_bls(set.signature_message(), owner, *set.signature()) {
+            panic!("Invalid owner signature!");
+        }
+
+        if set.nonce() <= self.beneficiary_nonce(set.account()) {
+            panic!("Already used nonce");
+        }
+
+        let beneficiary = set.beneficiary().cloned();
+        self.beneficiaries.insert(
+            set.account().to_bytes(),
+            (beneficiary.clone(), set.nonce()),
+        );
+
+        abi::emit(
+            "beneficiary",
+            BeneficiaryEvent {
+                account: *set.account(),
+                beneficiary,
+            },
+        );
+    }
+
+    /// Withdraws rewards to the beneficiary registered for the stake.
+    ///
+    /// Since the value can only go to the beneficiary, only the signature of
+    /// the stake account is required.
+    pub fn withdraw_to_beneficiary(&mut self, withdraw: WithdrawToBeneficiary) {
+        if withdraw.chain_id() != self.chain_id() {
+            panic!("The withdrawal must target the correct chain");
+        }
+
+        let account = withdraw.account();
+        let value = withdraw.value();
+
+        let beneficiary = self
+            .get_beneficiary(account)
+            .cloned()
+            .expect("A beneficiary should be registered to withdraw to it");
+
+        let nonce = withdraw.nonce();
+        if nonce <= self.withdraw_nonce(account) {
+            panic!("Already used nonce");
+        }
+
+        let (loaded_stake, keys) = self
+            .get_stake_mut(account)
+            .expect("A stake should exist in the map to get rewards!");
+
+        // ensure no 0 reward is executed,
+        if value == 0 {
+            panic!("Withdrawing 0 reward is not allowed");
+        }
+
+        // ensure that the withdrawal amount is not greater than the current
+        // reward
+        if value > loaded_stake.reward {
+            panic!("Value to withdraw is higher than available reward");
+        }
+
+        let msg = withdraw.signature_message();
+        if !abi::verify_bls(msg, keys.account, *withdraw.signature()) {
+            panic!("Invalid account signature!");
+        }
+
+        let receiver = withdraw.receiver(&beneficiary);
+        let _: () = abi::call(
+            TRANSFER_CONTRACT,
+            "mint_to_beneficiary",
+            &(receiver, value),
+        )
+        .expect("Withdrawing reward to the beneficiary should succeed");
+
+        // update the state accordingly
+        loaded_stake.reward -= value;
+        abi::emit("withdraw", StakeEvent::new(*keys, value));
+
+        if loaded_stake.reward == 0 && loaded_stake.amount.is_none() {
+            self.stakes.remove(&account.to_bytes());
+        }
+
+        self.withdraw_nonces.insert(account.to_bytes(), nonce);
+    }
+
+    /// Gets the beneficiary registered for a stake, if any.
+    pub fn get_beneficiary(&self, key: &BlsPublicKey) -> Option<&Beneficiary> {
+        self.beneficiaries
+            .get(&key.to_bytes())
+            .and_then(|(b, _)| b.as_ref())
+    }
+
+    /// Gets the nonce of the last beneficiary registration for a stake.
+    pub fn beneficiary_nonce(&self, key: &BlsPublicKey) -> u64 {
+        self.beneficiaries
+            .get(&key.to_bytes())
+            .map_or(0, |(_, nonce)| *nonce)
+    }
+
+    /// Gets the nonce of the last withdrawal to the beneficiary of a stake.
+    pub fn withdraw_nonce(&self, key: &BlsPublicKey) -> u64 {
+        self.withdraw_nonces
+            .get(&key.to_bytes())
+            .copied()
+            .unwrap_or_default()
+    }
+
     /// Gets a reference to a stake.
     pub fn get_stake(&self, key: &BlsPublicKey) -> Option<&StakeData> {
         self.stakes.get(&key.to_bytes()).map(|(s, _)| s)
@@ -516,6 +654,8 @@ impl StakeState {
     /// next epoch as well
     pub fn slash(&mut self, account: &BlsPublicKey, to_slash: Option<u64>) {
         let stake_warnings = self.config.warnings;
+        let config = self.config.clone();
+        let slashing = &config.slashing;
         let (stake, _) = self
             .get_stake_mut(account)
             .expect("The stake to slash should exist");
@@ -536,17 +676,18 @@ impl StakeState {
         // saturated
         if effective_faults > 0 {
             // The stake is suspended for the rest of the current epoch plus
-            // effective_faults epochs
-            let to_shift = effective_faults * EPOCH;
+            // the suspension configured for effective_faults
+            let to_shift = config.suspension(effective_faults);
 
             stake_amount.eligibility =
-                next_epoch(abi::block_height()) + to_shift;
+                config.next_epoch(abi::block_height()) + to_shift;
         }
 
         // Slash the provided amount or calculate the percentage according to
         // effective faults
-        let to_slash = to_slash
-            .unwrap_or(stake_amount.value / 100 * effective_faults * 10);
+        let to_slash = to_slash.unwrap_or_else(|| {
+            slashing.soft_slash(stake_amount.value, effective_faults)
+        });
         let to_slash = min(to_slash, stake_amount.value);
 
         if to_slash > 0 {
@@ -580,6 +721,8 @@ impl StakeState {
         to_slash: Option<u64>,
         severity: Option<u8>,
     ) {
+        let config = self.config.clone();
+        let slashing = &config.slashing;
         let (stake, _) = self
             .get_stake_mut(account)
             .expect("The stake to slash should exist");
@@ -598,15 +741,17 @@ impl StakeState {
         let hard_faults = stake.hard_faults as u64;
 
         // The stake is shifted (aka suspended) for the rest of the current
-        // epoch plus hard_faults epochs
-        let to_shift = hard_faults * EPOCH;
-        let next_eligibility = next_epoch(abi::block_height()) + to_shift;
+        // epoch plus the suspension configured for hard_faults
+        let to_shift = config.suspension(hard_faults);
+        let next_eligibility =
+            config.next_epoch(abi::block_height()) + to_shift;
         stake_amount.eligibility = next_eligibility;
 
         // Slash the provided amount or calculate the percentage according to
         // hard faults
-        let to_slash =
-            to_slash.unwrap_or(stake_amount.value / 100 * hard_faults * 10);
+        let to_slash = to_slash.unwrap_or_else(|| {
+            slashing.hard_slash(stake_amount.value, hard_faults)
+        });
         let to_slash = min(to_slash, stake_amount.value);
 
         if to_slash > 0 {
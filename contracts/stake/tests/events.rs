@@ -14,7 +14,8 @@ use dusk_core::{
     dusk,
     signatures::bls::{PublicKey as BlsPublicKey, SecretKey as BlsSecretKey},
     stake::{
-        Reward, RewardReason, StakeAmount, StakeData, StakeKeys, STAKE_CONTRACT,
+        Reward, RewardReason, SlashingConfig, StakeAmount, StakeConfig,
+        StakeData, StakeKeys, STAKE_CONTRACT,
     },
     transfer::{
         phoenix::{
@@ -119,6 +120,105 @@ fn reward_slash() -> Result<(), VMError> {
     Ok(())
 }
 
+#[test]
+fn configured_slash() -> Result<(), VMError> {
+    let rng = &mut StdRng::seed_from_u64(0xfeeb);
+
+    let vm = &mut VM::ephemeral().expect("Creating ephemeral VM should work");
+
+    let sk = PhoenixSecretKey::random(rng);
+    let pk = PhoenixPublicKey::from(&sk);
+
+    let stake_sk = BlsSecretKey::random(rng);
+    let stake_pk = BlsPublicKey::from(&stake_sk);
+    let stake_pks = StakeKeys::single_key(stake_pk);
+
+    let mut session = instantiate(rng, vm, &pk, GENESIS_VALUE);
+
+    let stake_amount = dusk(100.0);
+
+    let stake_data = StakeData {
+        reward: 0,
+        amount: Some(StakeAmount {
+            value: stake_amount,
+            eligibility: 0,
+            locked: 0,
+        }),
+        faults: 0,
+        hard_faults: 0,
+    };
+
+    session.call::<_, ()>(
+        TRANSFER_CONTRACT,
+        "add_contract_balance",
+        &(STAKE_CONTRACT, stake_amount),
+        u64::MAX,
+    )?;
+
+    session.call::<_, ()>(
+        STAKE_CONTRACT,
+        "insert_stake",
+        &(stake_pks, stake_data),
+        u64::MAX,
+    )?;
+
+    let slashing = SlashingConfig {
+        soft_slash_percent: 25,
+        hard_slash_percent: 50,
+        max_slash_percent: 40,
+        suspension_epochs: 2,
+    };
+    session.call::<_, ()>(
+        STAKE_CONTRACT,
+        "set_slashing_config",
+        &slashing,
+        u64::MAX,
+    )?;
+
+    let config = session
+        .call::<_, StakeConfig>(STAKE_CONTRACT, "get_config", &(), u64::MAX)?
+        .data;
+    assert_eq!(config.slashing, slashing);
+
+    // The first fault is a warning
+    let receipt = session.call::<_, ()>(
+        STAKE_CONTRACT,
+        "slash",
+        &(stake_pk, None::<u64>),
+        u64::MAX,
+    )?;
+    assert!(receipt.events.is_empty(), "No penalty at first warn");
+
+    // 25% of current amount, suspended for 2 epochs
+    let receipt = session.call::<_, ()>(
+        STAKE_CONTRACT,
+        "slash",
+        &(stake_pk, None::<u64>),
+        u64::MAX,
+    )?;
+    let slash_amount = stake_amount / 100 * 25;
+    assert_slash_event(&receipt.events, "slash", &stake_pk, slash_amount, 6480);
+    let stake_amount = stake_amount - slash_amount;
+
+    // 50% of current amount, capped at 40%
+    let receipt = session.call::<_, ()>(
+        STAKE_CONTRACT,
+        "hard_slash",
+        &(stake_pk, None::<u64>, None::<u8>),
+        u64::MAX,
+    )?;
+    let slash_amount = stake_amount / 100 * 40;
+    assert_slash_event(
+        &receipt.events,
+        "hard_slash",
+        &stake_pk,
+        slash_amount,
+        6480,
+    );
+
+    Ok(())
+}
+
 #[test]
 fn stake_hard_slash() -> Result<(), VMError> {
     let rng = &mut StdRng::seed_from_u64(0xfeeb);
@@ -8,13 +8,17 @@ use dusk_core::dusk;
 use dusk_core::signatures::bls::{
     PublicKey as BlsPublicKey, SecretKey as BlsSecretKey,
 };
-use dusk_core::stake::{Reward, RewardReason, EPOCH, STAKE_CONTRACT};
+use dusk_core::stake::{
+    Beneficiary, Reward, RewardReason, StakeConfig, StakeData, EPOCH,
+    STAKE_CONTRACT,
+};
 use dusk_core::transfer::TRANSFER_CONTRACT;
 use dusk_vm::{execute, ContractData, Error as VMError, Session, VM};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use wallet_core::transaction::{
-    moonlight_stake, moonlight_stake_reward, moonlight_unstake,
+    moonlight_set_stake_beneficiary, moonlight_stake, moonlight_stake_reward,
+    moonlight_stake_reward_to_beneficiary, moonlight_unstake,
 };
 
 pub mod common;
@@ -430,6 +434,220 @@ fn withdraw_reward() -> Result<(), VMError> {
     Ok(())
 }
 
+#[test]
+fn withdraw_reward_to_beneficiary() -> Result<(), VMError> {
+    // ------
+    // instantiate the test
+
+    let rng = &mut StdRng::seed_from_u64(0xfeeb);
+
+    let moonlight_sk = BlsSecretKey::random(rng);
+    let moonlight_pk = BlsPublicKey::from(&moonlight_sk);
+
+    let stake_sk = BlsSecretKey::random(rng);
+    let stake_pk = BlsPublicKey::from(&stake_sk);
+
+    // the owner of the stake also owns the account receiving the rewards
+    let owner_sk = BlsSecretKey::random(rng);
+    let owner_pk = BlsPublicKey::from(&owner_sk);
+
+    let mut vm = &mut VM::ephemeral()?;
+    let mut session = instantiate(&mut vm, &moonlight_pk);
+
+    // initial stake
+    let mut nonce = GENESIS_NONCE + 1;
+    let tx = moonlight_stake(
+        &moonlight_sk,
+        &stake_sk,
+        &owner_sk,
+        STAKE_VALUE,
+        GAS_LIMIT,
+        GAS_PRICE,
+        nonce,
+        CHAIN_ID,
+    )
+    .expect("tx creation should pass");
+    execute(&mut session, &tx, 0, 0, 0)?;
+
+    const REWARD_AMOUNT: u64 = dusk(3.0);
+    add_reward(&mut session, &stake_pk, REWARD_AMOUNT)?;
+
+    // ------
+    // Register the owner account as the beneficiary
+
+    nonce += 1;
+    let tx = moonlight_set_stake_beneficiary(
+        &moonlight_sk,
+        &stake_pk,
+        &owner_sk,
+        Some(Beneficiary::Moonlight(owner_pk)),
+        1,
+        GAS_LIMIT,
+        GAS_PRICE,
+        nonce,
+        CHAIN_ID,
+    )
+    .expect("tx creation should pass");
+    let receipt = execute(&mut session, &tx, 0, 0, 0)?;
+    assert!(
+        receipt.data.is_ok(),
+        "Setting the beneficiary should succeed"
+    );
+
+    // ------
+    // Withdraw half the reward with the stake key only
+
+    const WITHDRAWAL: u64 = REWARD_AMOUNT / 2;
+
+    nonce += 1;
+    let tx = moonlight_stake_reward_to_beneficiary(
+        rng,
+        &moonlight_sk,
+        &stake_sk,
+        WITHDRAWAL,
+        1,
+        GAS_LIMIT,
+        GAS_PRICE,
+        nonce,
+        CHAIN_ID,
+    )
+    .expect("tx creation should pass");
+    let receipt = execute(&mut session, &tx, 0, 0, 0)?;
+
+    assert_stake_event(&receipt.events, "withdraw", &stake_pk, WITHDRAWAL, 0);
+    assert_stake(&mut session, &stake_pk, STAKE_VALUE, 0, WITHDRAWAL);
+    assert_moonlight(&mut session, &owner_pk, WITHDRAWAL, 0);
+
+    // ------
+    // Reusing the withdrawal nonce fails
+
+    nonce += 1;
+    let tx = moonlight_stake_reward_to_beneficiary(
+        rng,
+        &moonlight_sk,
+        &stake_sk,
+        WITHDRAWAL,
+        1,
+        GAS_LIMIT,
+        GAS_PRICE,
+        nonce,
+        CHAIN_ID,
+    )
+    .expect("tx creation should pass");
+    let receipt = execute(&mut session, &tx, 0, 0, 0)?;
+    assert!(
+        receipt.data.is_err(),
+        "Reusing the withdrawal nonce should fail"
+    );
+    assert_stake(&mut session, &stake_pk, STAKE_VALUE, 0, WITHDRAWAL);
+
+    // ------
+    // Withdraw the rest of the reward with the next nonce
+
+    nonce += 1;
+    let tx = moonlight_stake_reward_to_beneficiary(
+        rng,
+        &moonlight_sk,
+        &stake_sk,
+        WITHDRAWAL,
+        2,
+        GAS_LIMIT,
+        GAS_PRICE,
+        nonce,
+        CHAIN_ID,
+    )
+    .expect("tx creation should pass");
+    let receipt = execute(&mut session, &tx, 0, 0, 0)?;
+
+    assert_stake_event(&receipt.events, "withdraw", &stake_pk, WITHDRAWAL, 0);
+    assert_stake(&mut session, &stake_pk, STAKE_VALUE, 0, 0);
+    assert_moonlight(&mut session, &owner_pk, REWARD_AMOUNT, 0);
+
+    Ok(())
+}
+
+#[test]
+fn stake_configured_maturity() -> Result<(), VMError> {
+    let rng = &mut StdRng::seed_from_u64(0xfeeb);
+
+    let moonlight_sk = BlsSecretKey::random(rng);
+    let moonlight_pk = BlsPublicKey::from(&moonlight_sk);
+
+    let stake_sk = BlsSecretKey::random(rng);
+    let stake_pk = BlsPublicKey::from(&stake_sk);
+
+    let mut vm = &mut VM::ephemeral()?;
+    let mut session = instantiate(&mut vm, &moonlight_pk);
+
+    let config = StakeConfig {
+        epoch: 100,
+        maturity: 300,
+        ..StakeConfig::new()
+    };
+    session.call::<_, ()>(STAKE_CONTRACT, "set_config", &config, GAS_LIMIT)?;
+
+    // A stake created at height 1 matures after the end of its epoch
+    let stake_1 = STAKE_VALUE / 2;
+    let mut nonce = GENESIS_NONCE + 1;
+    let tx = moonlight_stake(
+        &moonlight_sk,
+        &stake_sk,
+        &stake_sk,
+        stake_1,
+        GAS_LIMIT,
+        GAS_PRICE,
+        nonce,
+        CHAIN_ID,
+    )
+    .expect("tx creation should pass");
+    execute(&mut session, &tx, 0, 0, 0)?;
+
+    let stake = session
+        .call::<_, Option<StakeData>>(
+            STAKE_CONTRACT,
+            "get_stake",
+            &stake_pk,
+            GAS_LIMIT,
+        )?
+        .data
+        .expect("stake should exist");
+    let amount = stake.amount.expect("stake amount should exist");
+    assert_eq!(amount.eligibility, 400);
+    assert_eq!(amount.eligibility, config.eligibility(1));
+
+    // A top-up is locked once the stake is eligible, long before the default
+    // maturity
+    let base = session.commit()?;
+    let mut session = vm.session(base, CHAIN_ID, 400)?;
+
+    let stake_2 = STAKE_VALUE - stake_1;
+    nonce += 1;
+    let tx = moonlight_stake(
+        &moonlight_sk,
+        &stake_sk,
+        &stake_sk,
+        stake_2,
+        GAS_LIMIT,
+        GAS_PRICE,
+        nonce,
+        CHAIN_ID,
+    )
+    .expect("tx creation should pass");
+    let receipt = execute(&mut session, &tx, 0, 0, 0)?;
+
+    let locked = stake_2 / 10;
+    assert_stake_event(
+        &receipt.events,
+        "stake",
+        &stake_pk,
+        stake_2 - locked,
+        locked,
+    );
+    assert_stake(&mut session, &stake_pk, STAKE_VALUE, locked, 0);
+
+    Ok(())
+}
+
 fn add_reward(
     session: &mut Session,
     stake_pk: &BlsPublicKey,
@@ -0,0 +1,76 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+#![cfg_attr(target_family = "wasm", no_std)]
+#![cfg(target_family = "wasm")]
+#![feature(arbitrary_self_types)]
+#![deny(unused_crate_dependencies)]
+#![deny(unused_extern_crates)]
+
+extern crate alloc;
+
+use dusk_core::abi;
+
+mod state;
+use state::TokenState;
+
+static mut STATE: TokenState = TokenState::new();
+
+#[no_mangle]
+unsafe fn init(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |arg| STATE.init(arg))
+}
+
+// Transactions
+
+#[no_mangle]
+unsafe fn transfer(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |arg| STATE.transfer(arg))
+}
+
+#[no_mangle]
+unsafe fn approve(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |arg| STATE.approve(arg))
+}
+
+#[no_mangle]
+unsafe fn transfer_from(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |arg| STATE.transfer_from(arg))
+}
+
+#[no_mangle]
+unsafe fn mint(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |arg| STATE.mint(arg))
+}
+
+#[no_mangle]
+unsafe fn burn(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |arg| STATE.burn(arg))
+}
+
+// Queries
+
+#[no_mangle]
+unsafe fn info(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |_: ()| STATE.info())
+}
+
+#[no_mangle]
+unsafe fn minter(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |_: ()| STATE.minter())
+}
+
+#[no_mangle]
+unsafe fn balance_of(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |account| STATE.balance_of(&account))
+}
+
+#[no_mangle]
+unsafe fn allowance(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |(owner, spender)| {
+        STATE.allowance(&owner, &spender)
+    })
+}
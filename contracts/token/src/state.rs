@@ -0,0 +1,238 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+
+use dusk_core::abi;
+use dusk_core::signatures::bls::PublicKey as AccountPublicKey;
+use dusk_core::token::{
+    Account, Approve, ApproveEvent, Burn, Mint, SupplyEvent, TokenInfo,
+    TokenInit, Transfer, TransferEvent, TransferFrom, APPROVE_TOPIC,
+    BURN_TOPIC, MINT_TOPIC, TRANSFER_TOPIC,
+};
+use dusk_core::transfer::TRANSFER_CONTRACT;
+
+type AccountKey = [u8; Account::SIZE];
+
+/// Reference fungible-token contract.
+#[derive(Debug, Clone)]
+pub struct TokenState {
+    name: String,
+    symbol: String,
+    decimals: u8,
+    total_supply: u64,
+    minter: Option<Account>,
+    initialized: bool,
+    balances: BTreeMap<AccountKey, u64>,
+    allowances: BTreeMap<(AccountKey, AccountKey), u64>,
+}
+
+impl TokenState {
+    pub const fn new() -> Self {
+        Self {
+            name: String::new(),
+            symbol: String::new(),
+            decimals: 0,
+            total_supply: 0,
+            minter: None,
+            initialized: false,
+            balances: BTreeMap::new(),
+            allowances: BTreeMap::new(),
+        }
+    }
+
+    pub fn init(&mut self, init: TokenInit) {
+        if self.initialized {
+            panic!("The token is already initialized");
+        }
+        self.initialized = true;
+
+        self.name = init.name;
+        self.symbol = init.symbol;
+        self.decimals = init.decimals;
+        self.minter = init.minter;
+
+        for (account, value) in init.balances {
+            self.increase_balance(&account, value);
+            self.total_supply = self
+                .total_supply
+                .checked_add(value)
+                .expect("The total supply should not overflow");
+        }
+    }
+
+    pub fn transfer(&mut self, transfer: Transfer) {
+        let sender = sender();
+
+        self.decrease_balance(&sender, transfer.value);
+        self.increase_balance(&transfer.receiver, transfer.value);
+
+        abi::emit(
+            TRANSFER_TOPIC,
+            TransferEvent {
+                sender,
+                receiver: transfer.receiver,
+                value: transfer.value,
+            },
+        );
+    }
+
+    pub fn approve(&mut self, approve: Approve) {
+        let owner = sender();
+
+        let key = (owner.to_bytes(), approve.spender.to_bytes());
+        if approve.value == 0 {
+            self.allowances.remove(&key);
+        } else {
+            self.allowances.insert(key, approve.value);
+        }
+
+        abi::emit(
+            APPROVE_TOPIC,
+            ApproveEvent {
+                owner,
+                spender: approve.spender,
+                value: approve.value,
+            },
+        );
+    }
+
+    pub fn transfer_from(&mut self, transfer: TransferFrom) {
+        let spender = sender();
+
+        let key = (transfer.owner.to_bytes(), spender.to_bytes());
+        let allowance = self.allowances.get(&key).copied().unwrap_or(0);
+        if allowance < transfer.value {
+            panic!("The spender's allowance is insufficient");
+        }
+        if allowance == transfer.value {
+            self.allowances.remove(&key);
+        } else {
+            self.allowances.insert(key, allowance - transfer.value);
+        }
+
+        self.decrease_balance(&transfer.owner, transfer.value);
+        self.increase_balance(&transfer.receiver, transfer.value);
+
+        abi::emit(
+            TRANSFER_TOPIC,
+            TransferEvent {
+                sender: transfer.owner,
+                receiver: transfer.receiver,
+                value: transfer.value,
+            },
+        );
+    }
+
+    pub fn mint(&mut self, mint: Mint) {
+        if self.minter != Some(sender()) {
+            panic!("Only the minter can mint new tokens");
+        }
+
+        self.total_supply = self
+            .total_supply
+            .checked_add(mint.value)
+            .expect("The total supply should not overflow");
+        self.increase_balance(&mint.receiver, mint.value);
+
+        abi::emit(
+            MINT_TOPIC,
+            SupplyEvent {
+                account: mint.receiver,
+                value: mint.value,
+            },
+        );
+    }
+
+    pub fn burn(&mut self, burn: Burn) {
+        let owner = sender();
+
+        self.decrease_balance(&owner, burn.value);
+        self.total_supply -= burn.value;
+
+        abi::emit(
+            BURN_TOPIC,
+            SupplyEvent {
+                account: owner,
+                value: burn.value,
+            },
+        );
+    }
+
+    pub fn info(&self) -> TokenInfo {
+        TokenInfo {
+            name: self.name.clone(),
+            symbol: self.symbol.clone(),
+            decimals: self.decimals,
+            total_supply: self.total_supply,
+        }
+    }
+
+    pub fn minter(&self) -> Option<Account> {
+        self.minter
+    }
+
+    pub fn balance_of(&self, account: &Account) -> u64 {
+        self.balances.get(&account.to_bytes()).copied().unwrap_or(0)
+    }
+
+    pub fn allowance(&self, owner: &Account, spender: &Account) -> u64 {
+        self.allowances
+            .get(&(owner.to_bytes(), spender.to_bytes()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn increase_balance(&mut self, account: &Account, value: u64) {
+        if value == 0 {
+            return;
+        }
+        let balance = self.balances.entry(account.to_bytes()).or_insert(0);
+        *balance = balance
+            .checked_add(value)
+            .expect("The balance should not overflow");
+    }
+
+    fn decrease_balance(&mut self, account: &Account, value: u64) {
+        let key = account.to_bytes();
+        let balance = self.balances.get(&key).copied().unwrap_or(0);
+        if balance < value {
+            panic!("The account doesn't have enough tokens");
+        }
+        if balance == value {
+            self.balances.remove(&key);
+        } else {
+            self.balances.insert(key, balance - value);
+        }
+    }
+}
+
+/// Returns the account performing the current operation.
+///
+/// A direct call from a Moonlight transaction is performed by the sender of
+/// the transaction, while an inter-contract call is performed by the calling
+/// contract.
+///
+/// # Panics
+/// When the call is made from outside the VM, or from a Phoenix transaction.
+fn sender() -> Account {
+    let caller = abi::caller()
+        .expect("Token operations must be called by a transaction");
+
+    if caller != TRANSFER_CONTRACT {
+        return Account::Contract(caller);
+    }
+
+    let tx_sender: Option<AccountPublicKey> =
+        abi::call(TRANSFER_CONTRACT, "tx_sender", &())
+            .expect("Querying the transaction sender should succeed");
+    let tx_sender = tx_sender.expect(
+        "Token operations are only supported for Moonlight transactions",
+    );
+
+    Account::External(tx_sender)
+}
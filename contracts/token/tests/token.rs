@@ -0,0 +1,362 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_core::abi::{ContractError, ContractId, ARGBUF_LEN};
+use dusk_core::signatures::bls::{
+    PublicKey as AccountPublicKey, SecretKey as AccountSecretKey,
+};
+use dusk_core::token::{
+    Account, Approve, Burn, Mint, SupplyEvent, TokenInfo, TokenInit, Transfer,
+    TransferEvent, TransferFrom, BURN_TOPIC, TRANSFER_TOPIC,
+};
+use dusk_core::transfer::data::ContractCall;
+use dusk_core::transfer::moonlight::Transaction as MoonlightTransaction;
+use dusk_core::transfer::{Transaction, TRANSFER_CONTRACT};
+use dusk_core::{dusk, LUX};
+use dusk_vm::{
+    execute, CallReceipt, ContractData, Error as VMError, Session, VM,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rkyv::ser::serializers::AllocSerializer;
+use rkyv::Serialize;
+
+const MOONLIGHT_GENESIS_VALUE: u64 = dusk(1_000.0);
+const TOKEN_GENESIS_VALUE: u64 = 1_000_000;
+
+const GAS_LIMIT: u64 = 0x10000000;
+
+const TOKEN_ID: ContractId = {
+    let mut bytes = [0u8; 32];
+    bytes[0] = 0xFC;
+    ContractId::from_bytes(bytes)
+};
+
+const OWNER: [u8; 32] = [0; 32];
+const CHAIN_ID: u8 = 0xFA;
+
+/// Instantiate the virtual machine with the transfer and token contracts
+/// deployed, where each of the given moonlight accounts owns
+/// `MOONLIGHT_GENESIS_VALUE`, and the first one is the minter of the token and
+/// owns `TOKEN_GENESIS_VALUE` tokens.
+fn instantiate(vm: &VM, moonlight_pks: &[AccountPublicKey]) -> Session {
+    let transfer_bytecode = include_bytes!(
+        "../../../target/dusk/wasm64-unknown-unknown/release/transfer_contract.wasm"
+    );
+    let token_bytecode = include_bytes!(
+        "../../../target/dusk/wasm32-unknown-unknown/release/token_contract.wasm"
+    );
+
+    let mut session = vm.genesis_session(CHAIN_ID);
+
+    session
+        .deploy(
+            transfer_bytecode,
+            ContractData::builder()
+                .owner(OWNER)
+                .contract_id(TRANSFER_CONTRACT),
+            GAS_LIMIT,
+        )
+        .expect("Deploying the transfer contract should succeed");
+
+    let minter = Account::External(moonlight_pks[0]);
+    let init = TokenInit {
+        name: String::from("Reference Token"),
+        symbol: String::from("REF"),
+        decimals: 6,
+        minter: Some(minter),
+        balances: vec![(minter, TOKEN_GENESIS_VALUE)],
+    };
+    session
+        .deploy(
+            token_bytecode,
+            ContractData::builder()
+                .owner(OWNER)
+                .contract_id(TOKEN_ID)
+                .init_arg(&init),
+            GAS_LIMIT,
+        )
+        .expect("Deploying the token contract should succeed");
+
+    for pk in moonlight_pks {
+        session
+            .call::<_, ()>(
+                TRANSFER_CONTRACT,
+                "add_account_balance",
+                &(*pk, MOONLIGHT_GENESIS_VALUE),
+                GAS_LIMIT,
+            )
+            .expect("Inserting genesis account should succeed");
+    }
+
+    let base = session.commit().expect("Committing should succeed");
+    vm.session(base, CHAIN_ID, 1)
+        .expect("Instantiating new session should succeed")
+}
+
+/// Executes a moonlight transaction calling the given function of the token
+/// contract.
+fn call_token<A>(
+    session: &mut Session,
+    sender_sk: &AccountSecretKey,
+    nonce: u64,
+    fn_name: &str,
+    fn_args: &A,
+) -> CallReceipt<Result<Vec<u8>, ContractError>>
+where
+    A: Serialize<AllocSerializer<ARGBUF_LEN>>,
+{
+    let call = ContractCall::new(TOKEN_ID, fn_name, fn_args)
+        .expect("Creating the contract call should succeed");
+
+    let transaction: Transaction = MoonlightTransaction::new(
+        sender_sk,
+        None,
+        0,
+        0,
+        GAS_LIMIT,
+        LUX,
+        nonce,
+        CHAIN_ID,
+        Some(call),
+    )
+    .expect("Creating moonlight transaction should succeed")
+    .into();
+
+    execute(session, &transaction, 0, 0, 0)
+        .expect("Executing the transaction should succeed")
+}
+
+fn balance_of(session: &mut Session, account: Account) -> Result<u64, VMError> {
+    session
+        .call(TOKEN_ID, "balance_of", &account, GAS_LIMIT)
+        .map(|r| r.data)
+}
+
+fn allowance(
+    session: &mut Session,
+    owner: Account,
+    spender: Account,
+) -> Result<u64, VMError> {
+    session
+        .call(TOKEN_ID, "allowance", &(owner, spender), GAS_LIMIT)
+        .map(|r| r.data)
+}
+
+fn info(session: &mut Session) -> Result<TokenInfo, VMError> {
+    session
+        .call(TOKEN_ID, "info", &(), GAS_LIMIT)
+        .map(|r| r.data)
+}
+
+#[test]
+fn transfer() {
+    const TRANSFER_VALUE: u64 = 1_000;
+
+    let rng = &mut StdRng::seed_from_u64(0xfeeb);
+
+    let alice_sk = AccountSecretKey::random(rng);
+    let alice_pk = AccountPublicKey::from(&alice_sk);
+    let bob_pk = AccountPublicKey::from(&AccountSecretKey::random(rng));
+
+    let vm = VM::ephemeral().expect("Creating ephemeral VM should work");
+    let session = &mut instantiate(&vm, &[alice_pk]);
+
+    let alice = Account::External(alice_pk);
+    let bob = Account::External(bob_pk);
+
+    let receipt = call_token(
+        session,
+        &alice_sk,
+        1,
+        "transfer",
+        &Transfer {
+            receiver: bob,
+            value: TRANSFER_VALUE,
+        },
+    );
+    receipt.data.expect("The transfer should succeed");
+
+    let event = receipt
+        .events
+        .iter()
+        .find(|e| e.source == TOKEN_ID && e.topic == TRANSFER_TOPIC)
+        .expect("A transfer event should be emitted");
+    let event: TransferEvent = rkyv::from_bytes(&event.data)
+        .expect("The transfer event should deserialize");
+    assert_eq!(event.sender, alice);
+    assert_eq!(event.receiver, bob);
+    assert_eq!(event.value, TRANSFER_VALUE);
+
+    assert_eq!(
+        balance_of(session, alice).expect("Querying should succeed"),
+        TOKEN_GENESIS_VALUE - TRANSFER_VALUE
+    );
+    assert_eq!(
+        balance_of(session, bob).expect("Querying should succeed"),
+        TRANSFER_VALUE
+    );
+
+    // transferring more tokens than owned fails
+    let receipt = call_token(
+        session,
+        &alice_sk,
+        2,
+        "transfer",
+        &Transfer {
+            receiver: bob,
+            value: TOKEN_GENESIS_VALUE,
+        },
+    );
+    assert!(matches!(receipt.data, Err(ContractError::Panic(_))));
+    assert_eq!(
+        balance_of(session, alice).expect("Querying should succeed"),
+        TOKEN_GENESIS_VALUE - TRANSFER_VALUE
+    );
+}
+
+#[test]
+fn approve_and_transfer_from() {
+    const ALLOWANCE: u64 = 2_000;
+    const TRANSFER_VALUE: u64 = 1_500;
+
+    let rng = &mut StdRng::seed_from_u64(0xfeeb);
+
+    let alice_sk = AccountSecretKey::random(rng);
+    let alice_pk = AccountPublicKey::from(&alice_sk);
+    let bob_sk = AccountSecretKey::random(rng);
+    let bob_pk = AccountPublicKey::from(&bob_sk);
+    let charlie_pk = AccountPublicKey::from(&AccountSecretKey::random(rng));
+
+    let vm = VM::ephemeral().expect("Creating ephemeral VM should work");
+    let session = &mut instantiate(&vm, &[alice_pk, bob_pk]);
+
+    let alice = Account::External(alice_pk);
+    let bob = Account::External(bob_pk);
+    let charlie = Account::External(charlie_pk);
+
+    let receipt = call_token(
+        session,
+        &alice_sk,
+        1,
+        "approve",
+        &Approve {
+            spender: bob,
+            value: ALLOWANCE,
+        },
+    );
+    receipt.data.expect("The approval should succeed");
+    assert_eq!(
+        allowance(session, alice, bob).expect("Querying should succeed"),
+        ALLOWANCE
+    );
+
+    let receipt = call_token(
+        session,
+        &bob_sk,
+        1,
+        "transfer_from",
+        &TransferFrom {
+            owner: alice,
+            receiver: charlie,
+            value: TRANSFER_VALUE,
+        },
+    );
+    receipt.data.expect("The transfer should succeed");
+
+    assert_eq!(
+        allowance(session, alice, bob).expect("Querying should succeed"),
+        ALLOWANCE - TRANSFER_VALUE
+    );
+    assert_eq!(
+        balance_of(session, charlie).expect("Querying should succeed"),
+        TRANSFER_VALUE
+    );
+
+    // spending more than the remaining allowance fails
+    let receipt = call_token(
+        session,
+        &bob_sk,
+        2,
+        "transfer_from",
+        &TransferFrom {
+            owner: alice,
+            receiver: charlie,
+            value: TRANSFER_VALUE,
+        },
+    );
+    assert!(matches!(receipt.data, Err(ContractError::Panic(_))));
+    assert_eq!(
+        balance_of(session, charlie).expect("Querying should succeed"),
+        TRANSFER_VALUE
+    );
+}
+
+#[test]
+fn mint_and_burn() {
+    const MINT_VALUE: u64 = 5_000;
+    const BURN_VALUE: u64 = 3_000;
+
+    let rng = &mut StdRng::seed_from_u64(0xfeeb);
+
+    let alice_sk = AccountSecretKey::random(rng);
+    let alice_pk = AccountPublicKey::from(&alice_sk);
+    let bob_sk = AccountSecretKey::random(rng);
+    let bob_pk = AccountPublicKey::from(&bob_sk);
+
+    let vm = VM::ephemeral().expect("Creating ephemeral VM should work");
+    let session = &mut instantiate(&vm, &[alice_pk, bob_pk]);
+
+    let bob = Account::External(bob_pk);
+
+    // only the minter can mint new tokens
+    let receipt = call_token(
+        session,
+        &bob_sk,
+        1,
+        "mint",
+        &Mint {
+            receiver: bob,
+            value: MINT_VALUE,
+        },
+    );
+    assert!(matches!(receipt.data, Err(ContractError::Panic(_))));
+
+    let receipt = call_token(
+        session,
+        &alice_sk,
+        1,
+        "mint",
+        &Mint {
+            receiver: bob,
+            value: MINT_VALUE,
+        },
+    );
+    receipt.data.expect("Minting should succeed");
+
+    let receipt =
+        call_token(session, &bob_sk, 2, "burn", &Burn { value: BURN_VALUE });
+    receipt.data.expect("Burning should succeed");
+
+    let event = receipt
+        .events
+        .iter()
+        .find(|e| e.source == TOKEN_ID && e.topic == BURN_TOPIC)
+        .expect("A burn event should be emitted");
+    let event: SupplyEvent = rkyv::from_bytes(&event.data)
+        .expect("The burn event should deserialize");
+    assert_eq!(event.account, bob);
+    assert_eq!(event.value, BURN_VALUE);
+
+    assert_eq!(
+        balance_of(session, bob).expect("Querying should succeed"),
+        MINT_VALUE - BURN_VALUE
+    );
+    assert_eq!(
+        info(session).expect("Querying should succeed").total_supply,
+        TOKEN_GENESIS_VALUE + MINT_VALUE - BURN_VALUE
+    );
+}
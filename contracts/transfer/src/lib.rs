@@ -36,6 +36,13 @@ unsafe fn mint_to_contract(arg_len: u32) -> u32 {
     abi::wrap_call(arg_len, |arg| STATE.mint_to_contract(arg))
 }
 
+#[no_mangle]
+unsafe fn mint_to_beneficiary(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |(receiver, value)| {
+        STATE.mint_to_beneficiary(receiver, value)
+    })
+}
+
 #[no_mangle]
 unsafe fn deposit(arg_len: u32) -> u32 {
     abi::wrap_call(arg_len, |arg| STATE.deposit(arg))
@@ -61,6 +68,16 @@ unsafe fn contract_to_account(arg_len: u32) -> u32 {
     abi::wrap_call(arg_len, |arg| STATE.contract_to_account(arg))
 }
 
+#[no_mangle]
+unsafe fn transfer_ownership(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |arg| STATE.transfer_ownership(arg))
+}
+
+#[no_mangle]
+unsafe fn approve_upgrade(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |arg| STATE.approve_upgrade(arg))
+}
+
 // Queries
 
 #[no_mangle]
@@ -98,6 +115,26 @@ unsafe fn chain_id(arg_len: u32) -> u32 {
     abi::wrap_call(arg_len, |_: ()| STATE.chain_id())
 }
 
+#[no_mangle]
+unsafe fn tx_sender(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |_: ()| STATE.tx_sender())
+}
+
+#[no_mangle]
+unsafe fn owner(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |contract| STATE.owner(contract))
+}
+
+#[no_mangle]
+unsafe fn upgrade_approval(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |contract| STATE.upgrade_approval(contract))
+}
+
+#[no_mangle]
+unsafe fn successor(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |contract| STATE.successor(contract))
+}
+
 // "Feeder" queries
 
 #[no_mangle]
@@ -156,6 +193,14 @@ unsafe fn refund(arg_len: u32) -> u32 {
     })
 }
 
+#[no_mangle]
+unsafe fn complete_upgrade(arg_len: u32) -> u32 {
+    abi::wrap_call(arg_len, |upgrade| {
+        assert_external_caller();
+        STATE.complete_upgrade(upgrade)
+    })
+}
+
 #[no_mangle]
 unsafe fn push_note(arg_len: u32) -> u32 {
     abi::wrap_call(arg_len, |(block_height, note)| {
@@ -29,12 +29,14 @@ use dusk_core::transfer::withdraw::{
     Withdraw, WithdrawReceiver, WithdrawReplayToken, WithdrawSignature,
 };
 use dusk_core::transfer::{
-    ContractToAccount, ContractToAccountEvent, ContractToContract,
-    ContractToContractEvent, ConvertEvent, DepositEvent,
-    MoonlightTransactionEvent, PhoenixTransactionEvent, ReceiveFromContract,
-    Transaction, WithdrawEvent, CONTRACT_TO_ACCOUNT_TOPIC,
+    ApproveUpgrade, CompleteUpgrade, ContractToAccount, ContractToAccountEvent,
+    ContractToContract, ContractToContractEvent, ConvertEvent, DepositEvent,
+    MoonlightTransactionEvent, OwnershipEvent, PhoenixTransactionEvent,
+    ReceiveFromContract, Transaction, TransferOwnership, UpgradeApprovalEvent,
+    UpgradeEvent, WithdrawEvent, CONTRACT_TO_ACCOUNT_TOPIC,
     CONTRACT_TO_CONTRACT_TOPIC, CONVERT_TOPIC, DEPOSIT_TOPIC, MINT_TOPIC,
-    MOONLIGHT_TOPIC, PANIC_NONCE_NOT_READY, PHOENIX_TOPIC, TRANSFER_CONTRACT,
+    MOONLIGHT_TOPIC, OWNERSHIP_TOPIC, PANIC_NONCE_NOT_READY, PANIC_TX_EXPIRED,
+    PHOENIX_TOPIC, TRANSFER_CONTRACT, UPGRADE_APPROVAL_TOPIC, UPGRADE_TOPIC,
     WITHDRAW_TOPIC,
 };
 use dusk_core::BlsScalar;
@@ -73,6 +75,12 @@ pub struct TransferState {
     //       up to replay attacks.
     accounts: BTreeMap<[u8; 193], AccountData>,
     contract_balances: BTreeMap<ContractId, u64>,
+    // Owners of the contracts whose ownership has been transferred. Other
+    // contracts are owned by the owner they were deployed with.
+    owners: BTreeMap<ContractId, AccountPublicKey>,
+    // Bytecode hash each contract has been approved to be upgraded to.
+    upgrade_approvals: BTreeMap<ContractId, [u8; 32]>,
+    successors: BTreeMap<ContractId, ContractId>,
 }
 
 impl TransferState {
@@ -83,6 +91,9 @@ impl TransferState {
             roots: ConstGenericRingBuffer::new(),
             accounts: BTreeMap::new(),
             contract_balances: BTreeMap::new(),
+            owners: BTreeMap::new(),
+            upgrade_approvals: BTreeMap::new(),
+            successors: BTreeMap::new(),
         }
     }
 
@@ -181,6 +192,51 @@ impl TransferState {
         abi::emit(MINT_TOPIC, WithdrawEvent::from(mint));
     }
 
+    /// Mint more Dusk to the beneficiary of a stake.
+    ///
+    /// This can only be called by the stake contract, during the execution of
+    /// the `withdraw_to_beneficiary` function. Unlike [`Self::mint`], the
+    /// receiver doesn't sign the withdrawal, since the stake contract ensures
+    /// it is the beneficiary registered by the owner of the stake.
+    ///
+    /// # Safety
+    /// We assume on trust that the value sent by the stake contract is
+    /// according to consensus rules.
+    pub fn mint_to_beneficiary(
+        &mut self,
+        receiver: WithdrawReceiver,
+        value: u64,
+    ) {
+        const PANIC_MSG: &str = "Can only be called by the stake contract";
+        if abi::caller().expect(PANIC_MSG) != STAKE_CONTRACT {
+            panic!("{PANIC_MSG}")
+        }
+
+        match receiver {
+            WithdrawReceiver::Phoenix(address) => {
+                let sender = contract_fn_sender("mint", STAKE_CONTRACT);
+                let note = Note::transparent_stealth(address, value, sender);
+                self.push_note_current_height(note);
+            }
+            WithdrawReceiver::Moonlight(account) => {
+                let account = self
+                    .accounts
+                    .entry(account.to_raw_bytes())
+                    .or_insert(EMPTY_ACCOUNT);
+                account.balance += value;
+            }
+        }
+
+        abi::emit(
+            MINT_TOPIC,
+            WithdrawEvent {
+                sender: STAKE_CONTRACT,
+                receiver,
+                value,
+            },
+        );
+    }
+
     /// Mint more Dusk to be owned by a contract.
     ///
     /// This can only be called by the stake contract, and will increase the
@@ -477,6 +533,143 @@ impl TransferState {
         );
     }
 
+    /// Hands the ownership of a contract over to another account.
+    ///
+    /// # Panics
+    /// Unless called directly by a Moonlight transaction sent by the current
+    /// owner of the contract.
+    pub fn transfer_ownership(&mut self, transfer: TransferOwnership) {
+        let owner = self.assert_owner_call(transfer.contract);
+
+        // a pending upgrade was approved by the previous owner, and should
+        // not outlive its ownership
+        self.upgrade_approvals.remove(&transfer.contract);
+        self.owners.insert(transfer.contract, transfer.new_owner);
+
+        abi::emit(
+            OWNERSHIP_TOPIC,
+            OwnershipEvent {
+                contract: transfer.contract,
+                previous_owner: owner,
+                new_owner: transfer.new_owner,
+            },
+        );
+    }
+
+    /// Approves the bytecode a contract may be upgraded to, replacing any
+    /// previous approval.
+    ///
+    /// # Panics
+    /// Unless called directly by a Moonlight transaction sent by the current
+    /// owner of the contract.
+    pub fn approve_upgrade(&mut self, approve: ApproveUpgrade) {
+        let owner = self.assert_owner_call(approve.contract);
+
+        if self.successors.contains_key(&approve.contract) {
+            panic!("The contract has already been upgraded");
+        }
+
+        self.upgrade_approvals
+            .insert(approve.contract, approve.bytecode_hash);
+
+        abi::emit(
+            UPGRADE_APPROVAL_TOPIC,
+            UpgradeApprovalEvent {
+                contract: approve.contract,
+                owner,
+                bytecode_hash: approve.bytecode_hash,
+            },
+        );
+    }
+
+    /// Records a newly deployed contract as the successor of the contract
+    /// that approved its bytecode, if any, and returns the upgraded contract.
+    ///
+    /// The upgrade is only completed if the new contract is deployed by the
+    /// current owner of the upgraded contract, in a Moonlight transaction it
+    /// signed. The raw owner of the deployment is chosen freely, so it is not
+    /// enough to authenticate the owner.
+    ///
+    /// This function guarantees that it will not panic.
+    pub fn complete_upgrade(
+        &mut self,
+        upgrade: CompleteUpgrade,
+    ) -> Option<ContractId> {
+        // the sender's signature over the deployment was verified when
+        // spending the transaction
+        let sender = match transitory::ongoing_transaction()? {
+            Transaction::Moonlight(tx) => *tx.sender(),
+            Transaction::Phoenix(_) => return None,
+        };
+
+        let upgraded = self
+            .upgrade_approvals
+            .iter()
+            .find(|(contract, hash)| {
+                **hash == upgrade.bytecode_hash
+                    && self.owner(**contract).is_some_and(|owner| {
+                        owner == sender
+                            && owner.to_bytes()[..] == upgrade.owner[..]
+                    })
+            })
+            .map(|(contract, _)| *contract)?;
+
+        self.upgrade_approvals.remove(&upgraded);
+        self.successors.insert(upgraded, upgrade.contract);
+
+        abi::emit(
+            UPGRADE_TOPIC,
+            UpgradeEvent {
+                contract: upgraded,
+                successor: upgrade.contract,
+            },
+        );
+
+        Some(upgraded)
+    }
+
+    /// Returns the current owner of a contract.
+    pub fn owner(&self, contract: ContractId) -> Option<AccountPublicKey> {
+        self.owners
+            .get(&contract)
+            .copied()
+            .or_else(|| abi::owner(contract))
+    }
+
+    /// Returns the bytecode hash a contract has been approved to be upgraded
+    /// to, if any.
+    pub fn upgrade_approval(&self, contract: ContractId) -> Option<[u8; 32]> {
+        self.upgrade_approvals.get(&contract).copied()
+    }
+
+    /// Returns the contract succeeding the given one after an upgrade, if
+    /// any.
+    pub fn successor(&self, contract: ContractId) -> Option<ContractId> {
+        self.successors.get(&contract).copied()
+    }
+
+    /// Asserts the ongoing transaction is a Moonlight transaction, sent by
+    /// the owner of `contract`, directly calling the transfer contract.
+    /// Returns the owner.
+    fn assert_owner_call(&self, contract: ContractId) -> AccountPublicKey {
+        // since each transaction only has, at maximum, a single contract call,
+        // this check implies that the sender of the transaction is the one
+        // requesting the call, and not another contract acting on its behalf.
+        let caller = abi::caller().expect(
+            "Ownership must be managed in the context of a transaction",
+        );
+        if caller != TRANSFER_CONTRACT {
+            panic!("Ownership can only be managed by the first contract call");
+        }
+
+        let owner = self.owner(contract).expect("The contract must exist");
+        if transitory::moonlight_transaction().sender() != &owner {
+            panic!("Only the owner can manage the contract");
+        }
+
+        owner
+    }
+
     /// The top level transaction execution function.
     ///
     /// This will emplace the deposit in the state, if it exists - making it
@@ -577,6 +770,14 @@ impl TransferState {
             panic!("The tx must target the correct chain");
         }
 
+        // a transaction past its expiry height can never be included
+        if moonlight_tx
+            .expiry_height()
+            .is_some_and(|expiry_height| abi::block_height() > expiry_height)
+        {
+            panic!("{PANIC_TX_EXPIRED}");
+        }
+
         // check the signature is valid and made by `sender`
         if !abi::verify_bls(
             moonlight_tx.signature_message(),
@@ -586,6 +787,22 @@ impl TransferState {
             panic!("Invalid signature!");
         }
 
+        // a sponsored transaction has its gas paid by the sponsor, which must
+        // have signed over the transaction and be refunded any unspent gas.
+        let sponsor = moonlight_tx.sponsor();
+        if let Some(sponsor) = sponsor {
+            if !abi::verify_bls(
+                moonlight_tx.sponsor_signature_message(),
+                sponsor.account,
+                sponsor.signature,
+            ) {
+                panic!("Invalid sponsor signature!");
+            }
+            if moonlight_tx.refund_address() != &sponsor.account {
+                panic!("The refund address must be the sponsor's account");
+            }
+        }
+
         // check `sender` has the funds necessary to suppress the total value
         // available in this transaction, and that the `nonce` is higher than
         // the currently held number. If these conditions are violated we panic
@@ -603,10 +820,19 @@ impl TransferState {
         let sender_bytes = moonlight_tx.sender().to_raw_bytes();
 
         // the total value carried by a transaction is the sum of the value, the
-        // deposit, and gas_limit * gas_price.
-        let total_value = moonlight_tx.value()
-            + moonlight_tx.deposit()
-            + moonlight_tx.gas_limit() * moonlight_tx.gas_price();
+        // deposit, and gas_limit * gas_price. The latter is charged to the
+        // sponsor instead, if there is one.
+        let gas_value = moonlight_tx.gas_limit() * moonlight_tx.gas_price();
+        let mut total_value = moonlight_tx.value() + moonlight_tx.deposit();
+        if sponsor.is_none() {
+            total_value += gas_value;
+        }
+
+        // a sponsored sender may hold no funds at all, in which case it still
+        // needs an account to keep track of its nonce.
+        if sponsor.is_some() && total_value == 0 {
+            self.accounts.entry(sender_bytes).or_insert(EMPTY_ACCOUNT);
+        }
 
         match self.accounts.get_mut(&sender_bytes) {
             Some(account) => {
@@ -632,6 +858,15 @@ impl TransferState {
             None => panic!("Account has no funds"),
         }
 
+        if let Some(sponsor) = sponsor {
+            match self.accounts.get_mut(&sponsor.account.to_raw_bytes()) {
+                Some(account) if account.balance >= gas_value => {
+                    account.balance -= gas_value;
+                }
+                _ => panic!("Sponsor doesn't have enough funds"),
+            }
+        }
+
         // add the value to the receiver account
         if moonlight_tx.value() > 0 {
             // if the receiver has no entry, we simply instantiate a new one
@@ -697,8 +932,21 @@ impl TransferState {
             }
             Transaction::Moonlight(tx) => {
                 let remaining_gas = tx.gas_limit() - gas_spent;
-                let refund = remaining_gas * tx.gas_price()
-                    + deposit.unwrap_or_default();
+                let mut refund = remaining_gas * tx.gas_price();
+
+                // a sponsor only pays for the gas, so the deposit is returned
+                // to the sender rather than to the sponsor's refund-address.
+                let deposit = deposit.unwrap_or_default();
+                if tx.sponsor().is_some() {
+                    if deposit > 0 {
+                        self.accounts
+                            .entry(tx.sender().to_raw_bytes())
+                            .or_insert(EMPTY_ACCOUNT)
+                            .balance += deposit;
+                    }
+                } else {
+                    refund += deposit;
+                }
 
                 let refund_account = self
                     .accounts
@@ -915,6 +1163,20 @@ impl TransferState {
     pub fn chain_id(&self) -> u8 {
         abi::chain_id()
     }
+
+    /// Returns the sender of the ongoing Moonlight transaction, as long as it
+    /// is queried by the contract the transaction calls.
+    ///
+    /// Restricting the query to the called contract prevents other contracts
+    /// from impersonating the sender by routing calls through this contract.
+    pub fn tx_sender(&self) -> Option<AccountPublicKey> {
+        let tx = transitory::ongoing_transaction()?;
+        let call = tx.call()?;
+        if abi::caller() != Some(call.contract) {
+            return None;
+        }
+        tx.moonlight_sender().copied()
+    }
 }
 
 fn verify_tx_proof(tx: &PhoenixTransaction) -> bool {
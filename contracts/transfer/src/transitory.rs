@@ -150,6 +150,11 @@ pub fn transaction() -> &'static Transaction {
     }
 }
 
+/// Get a reference of the current ongoing transaction, if there is one.
+pub fn ongoing_transaction() -> Option<&'static Transaction> {
+    unsafe { CURRENT_TX.as_ref().map(|ongoing| &ongoing.tx) }
+}
+
 /// Get a reference of the current ongoing transaction, assuming it's Moonlight.
 pub fn moonlight_transaction() -> &'static MoonlightTransaction {
     match transaction() {
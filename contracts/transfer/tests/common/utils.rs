@@ -42,6 +42,26 @@ pub fn account(
         .map(|r| r.data)
 }
 
+// contract ownership helper functions
+
+pub fn owner(
+    session: &mut Session,
+    contract: ContractId,
+) -> Result<Option<AccountPublicKey>, VMError> {
+    session
+        .call(TRANSFER_CONTRACT, "owner", &contract, GAS_LIMIT)
+        .map(|r| r.data)
+}
+
+pub fn successor(
+    session: &mut Session,
+    contract: ContractId,
+) -> Result<Option<ContractId>, VMError> {
+    session
+        .call(TRANSFER_CONTRACT, "successor", &contract, GAS_LIMIT)
+        .map(|r| r.data)
+}
+
 // phoenix helper functions
 
 pub fn new_owned_notes_value(
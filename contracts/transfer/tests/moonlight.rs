@@ -4,11 +4,14 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use dusk_bytes::Serializable;
 use dusk_core::abi::{ContractError, ContractId};
 use dusk_core::signatures::bls::{
     PublicKey as AccountPublicKey, SecretKey as AccountSecretKey,
 };
-use dusk_core::transfer::data::{ContractCall, TransactionData};
+use dusk_core::transfer::data::{
+    ContractBytecode, ContractCall, ContractDeploy, TransactionData,
+};
 use dusk_core::transfer::moonlight::Transaction as MoonlightTransaction;
 use dusk_core::transfer::phoenix::{
     Note, PublicKey as PhoenixPublicKey, SecretKey as PhoenixSecretKey,
@@ -18,10 +21,11 @@ use dusk_core::transfer::withdraw::{
     Withdraw, WithdrawReceiver, WithdrawReplayToken,
 };
 use dusk_core::transfer::{
-    ContractToAccount, ContractToContract, Transaction, TRANSFER_CONTRACT,
+    ApproveUpgrade, ContractToAccount, ContractToContract, Transaction,
+    TransferOwnership, TRANSFER_CONTRACT,
 };
 use dusk_core::{dusk, JubJubScalar, LUX};
-use dusk_vm::{execute, ContractData, Session, VM};
+use dusk_vm::{execute, gen_contract_id, ContractData, Session, VM};
 use ff::Field;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
@@ -29,7 +33,8 @@ use rand::SeedableRng;
 pub mod common;
 use crate::common::utils::{
     account, chain_id, contract_balance, existing_nullifiers,
-    filter_notes_owned_by, leaves_from_height, owned_notes_value, update_root,
+    filter_notes_owned_by, leaves_from_height, owned_notes_value, owner,
+    successor, update_root,
 };
 
 const MOONLIGHT_GENESIS_VALUE: u64 = dusk(1_000.0);
@@ -277,6 +282,78 @@ fn transfer_with_refund() {
     );
 }
 
+/// Refunds the unspent gas of a sponsored transaction to the sponsor, and the
+/// deposit that wasn't picked up to the sender.
+#[test]
+fn sponsored_refund() {
+    const DEPOSIT_VALUE: u64 = dusk(1.0);
+    const SPONSOR_VALUE: u64 = dusk(10.0);
+
+    let rng = &mut StdRng::seed_from_u64(0xfeeb);
+
+    let moonlight_sender_sk = AccountSecretKey::random(rng);
+    let moonlight_sender_pk = AccountPublicKey::from(&moonlight_sender_sk);
+
+    let moonlight_sponsor_sk = AccountSecretKey::random(rng);
+    let moonlight_sponsor_pk = AccountPublicKey::from(&moonlight_sponsor_sk);
+
+    let session = &mut instantiate(&moonlight_sender_pk);
+
+    session
+        .call::<_, ()>(
+            TRANSFER_CONTRACT,
+            "add_account_balance",
+            &(moonlight_sponsor_pk, SPONSOR_VALUE),
+            GAS_LIMIT,
+        )
+        .expect("Funding the sponsor account should succeed");
+
+    // alice's `ping` doesn't pick up the deposit
+    let contract_call = ContractCall {
+        contract: ALICE_ID,
+        fn_name: String::from("ping"),
+        fn_args: vec![],
+    };
+
+    let transaction: Transaction = MoonlightTransaction::new_with_refund(
+        &moonlight_sender_sk,
+        &moonlight_sponsor_pk,
+        None,
+        0,
+        DEPOSIT_VALUE,
+        GAS_LIMIT,
+        LUX,
+        MOONLIGHT_GENESIS_NONCE + 1,
+        CHAIN_ID,
+        Some(contract_call),
+    )
+    .expect("Creating moonlight transaction should succeed")
+    .sponsored(&moonlight_sponsor_sk)
+    .into();
+
+    let gas_spent = execute(session, &transaction, 0, 0, 0)
+        .expect("Transaction should succeed")
+        .gas_spent;
+
+    println!("SPONSORED REFUND: {} gas", gas_spent);
+
+    let sender_account = account(session, &moonlight_sender_pk)
+        .expect("Getting the sender account should succeed");
+    let sponsor_account = account(session, &moonlight_sponsor_pk)
+        .expect("Getting the sponsor account should succeed");
+
+    assert_eq!(
+        sender_account.balance, MOONLIGHT_GENESIS_VALUE,
+        "The sender should be refunded the deposit"
+    );
+    assert_eq!(sender_account.nonce, MOONLIGHT_GENESIS_NONCE + 1);
+    assert_eq!(
+        sponsor_account.balance,
+        SPONSOR_VALUE - gas_spent * LUX,
+        "The sponsor should only be charged the gas spent"
+    );
+}
+
 /// Checks if a transaction fails when the gas-price is 0.
 #[test]
 fn transfer_gas_fails() {
@@ -980,3 +1057,294 @@ fn contract_to_account_direct_call() {
         "Alice's balance should be unchanged"
     );
 }
+
+/// Deploys the given bytecode from the given account, owning the deployed
+/// contract, and returns the ID of the contract.
+fn deploy_owned(
+    session: &mut Session,
+    owner_sk: &AccountSecretKey,
+    nonce: u64,
+    bytecode: &[u8],
+    init_args: Option<Vec<u8>>,
+) -> ContractId {
+    let owner = AccountPublicKey::from(owner_sk).to_bytes().to_vec();
+    let deploy = ContractDeploy {
+        bytecode: ContractBytecode {
+            hash: blake3::hash(bytecode).into(),
+            bytes: bytecode.to_vec(),
+        },
+        owner: owner.clone(),
+        init_args,
+        nonce: 0,
+    };
+
+    let transaction = Transaction::moonlight(
+        owner_sk,
+        None,
+        0,
+        0,
+        GAS_LIMIT,
+        LUX,
+        nonce,
+        CHAIN_ID,
+        Some(deploy),
+    )
+    .expect("Creating moonlight transaction should succeed");
+
+    let receipt = execute(session, &transaction, 0, 0, 0)
+        .expect("Transaction should succeed");
+    assert!(receipt.data.is_ok(), "The deployment should succeed");
+
+    gen_contract_id(bytecode, 0, owner)
+}
+
+/// Sends a moonlight transaction calling the transfer contract to manage the
+/// ownership of a contract.
+fn manage_contract(
+    session: &mut Session,
+    owner_sk: &AccountSecretKey,
+    nonce: u64,
+    fn_name: &str,
+    fn_args: Vec<u8>,
+) -> Result<Vec<u8>, ContractError> {
+    let contract_call = ContractCall {
+        contract: TRANSFER_CONTRACT,
+        fn_name: String::from(fn_name),
+        fn_args,
+    };
+
+    let transaction = Transaction::moonlight(
+        owner_sk,
+        None,
+        0,
+        0,
+        GAS_LIMIT,
+        LUX,
+        nonce,
+        CHAIN_ID,
+        Some(contract_call),
+    )
+    .expect("Creating moonlight transaction should succeed");
+
+    execute(session, &transaction, 0, 0, 0)
+        .expect("Transaction should succeed")
+        .data
+}
+
+/// Upgrades a contract by approving the bytecode of its successor and then
+/// deploying it.
+#[test]
+fn contract_upgrade() {
+    let alice_bytecode = include_bytes!(
+        "../../../target/dusk/wasm32-unknown-unknown/release/alice.wasm"
+    );
+    let bob_bytecode = include_bytes!(
+        "../../../target/dusk/wasm32-unknown-unknown/release/bob.wasm"
+    );
+
+    let rng = &mut StdRng::seed_from_u64(0xfeeb);
+
+    let owner_sk = AccountSecretKey::random(rng);
+    let owner_pk = AccountPublicKey::from(&owner_sk);
+
+    let session = &mut instantiate(&owner_pk);
+
+    let contract = deploy_owned(
+        session,
+        &owner_sk,
+        MOONLIGHT_GENESIS_NONCE + 1,
+        alice_bytecode,
+        None,
+    );
+    assert_eq!(
+        owner(session, contract).expect("Querying the owner should succeed"),
+        Some(owner_pk),
+        "The deployer should own the contract"
+    );
+
+    let approve = ApproveUpgrade {
+        contract,
+        bytecode_hash: blake3::hash(bob_bytecode).into(),
+    };
+    manage_contract(
+        session,
+        &owner_sk,
+        MOONLIGHT_GENESIS_NONCE + 2,
+        "approve_upgrade",
+        rkyv::to_bytes::<_, 256>(&approve)
+            .expect("Serializing should succeed")
+            .to_vec(),
+    )
+    .expect("Approving the upgrade should succeed");
+
+    let init_args = rkyv::to_bytes::<_, 256>(&1u8)
+        .expect("Serializing should succeed")
+        .to_vec();
+    let upgraded = deploy_owned(
+        session,
+        &owner_sk,
+        MOONLIGHT_GENESIS_NONCE + 3,
+        bob_bytecode,
+        Some(init_args),
+    );
+
+    assert_eq!(
+        successor(session, contract)
+            .expect("Querying the successor should succeed"),
+        Some(upgraded),
+        "The deployed contract should succeed the upgraded one"
+    );
+}
+
+/// Deploying the approved bytecode on behalf of the owner of a contract, from
+/// another account, doesn't upgrade the contract.
+#[test]
+fn contract_upgrade_impostor() {
+    let alice_bytecode = include_bytes!(
+        "../../../target/dusk/wasm32-unknown-unknown/release/alice.wasm"
+    );
+    let bob_bytecode = include_bytes!(
+        "../../../target/dusk/wasm32-unknown-unknown/release/bob.wasm"
+    );
+
+    let rng = &mut StdRng::seed_from_u64(0xfeeb);
+
+    let owner_sk = AccountSecretKey::random(rng);
+    let owner_pk = AccountPublicKey::from(&owner_sk);
+    let impostor_sk = AccountSecretKey::random(rng);
+    let impostor_pk = AccountPublicKey::from(&impostor_sk);
+
+    let session = &mut instantiate(&owner_pk);
+    session
+        .call::<_, ()>(
+            TRANSFER_CONTRACT,
+            "add_account_balance",
+            &(impostor_pk, MOONLIGHT_GENESIS_VALUE),
+            GAS_LIMIT,
+        )
+        .expect("Funding the impostor account should succeed");
+
+    let contract = deploy_owned(
+        session,
+        &owner_sk,
+        MOONLIGHT_GENESIS_NONCE + 1,
+        alice_bytecode,
+        None,
+    );
+
+    let approve = ApproveUpgrade {
+        contract,
+        bytecode_hash: blake3::hash(bob_bytecode).into(),
+    };
+    manage_contract(
+        session,
+        &owner_sk,
+        MOONLIGHT_GENESIS_NONCE + 2,
+        "approve_upgrade",
+        rkyv::to_bytes::<_, 256>(&approve)
+            .expect("Serializing should succeed")
+            .to_vec(),
+    )
+    .expect("Approving the upgrade should succeed");
+
+    // the impostor claims the owner's key as the owner of the deployment
+    let deploy = ContractDeploy {
+        bytecode: ContractBytecode {
+            hash: blake3::hash(bob_bytecode).into(),
+            bytes: bob_bytecode.to_vec(),
+        },
+        owner: owner_pk.to_bytes().to_vec(),
+        init_args: Some(
+            rkyv::to_bytes::<_, 256>(&1u8)
+                .expect("Serializing should succeed")
+                .to_vec(),
+        ),
+        nonce: 0,
+    };
+    let transaction = Transaction::moonlight(
+        &impostor_sk,
+        None,
+        0,
+        0,
+        GAS_LIMIT,
+        LUX,
+        MOONLIGHT_GENESIS_NONCE + 1,
+        CHAIN_ID,
+        Some(deploy),
+    )
+    .expect("Creating moonlight transaction should succeed");
+    let receipt = execute(session, &transaction, 0, 0, 0)
+        .expect("Transaction should succeed");
+    assert!(receipt.data.is_ok(), "The deployment should succeed");
+
+    assert_eq!(
+        successor(session, contract)
+            .expect("Querying the successor should succeed"),
+        None,
+        "The impostor's deployment should not upgrade the contract"
+    );
+}
+
+/// Transfers the ownership of a contract, after which the previous owner can
+/// no longer manage it.
+#[test]
+fn contract_ownership_transfer() {
+    let alice_bytecode = include_bytes!(
+        "../../../target/dusk/wasm32-unknown-unknown/release/alice.wasm"
+    );
+
+    let rng = &mut StdRng::seed_from_u64(0xfeeb);
+
+    let owner_sk = AccountSecretKey::random(rng);
+    let owner_pk = AccountPublicKey::from(&owner_sk);
+    let new_owner_pk = AccountPublicKey::from(&AccountSecretKey::random(rng));
+
+    let session = &mut instantiate(&owner_pk);
+
+    let contract = deploy_owned(
+        session,
+        &owner_sk,
+        MOONLIGHT_GENESIS_NONCE + 1,
+        alice_bytecode,
+        None,
+    );
+
+    let transfer = TransferOwnership {
+        contract,
+        new_owner: new_owner_pk,
+    };
+    manage_contract(
+        session,
+        &owner_sk,
+        MOONLIGHT_GENESIS_NONCE + 2,
+        "transfer_ownership",
+        rkyv::to_bytes::<_, 256>(&transfer)
+            .expect("Serializing should succeed")
+            .to_vec(),
+    )
+    .expect("Transferring the ownership should succeed");
+
+    assert_eq!(
+        owner(session, contract).expect("Querying the owner should succeed"),
+        Some(new_owner_pk),
+        "The contract should be owned by the new owner"
+    );
+
+    let approve = ApproveUpgrade {
+        contract,
+        bytecode_hash: [0; 32],
+    };
+    let result = manage_contract(
+        session,
+        &owner_sk,
+        MOONLIGHT_GENESIS_NONCE + 3,
+        "approve_upgrade",
+        rkyv::to_bytes::<_, 256>(&approve)
+            .expect("Serializing should succeed")
+            .to_vec(),
+    );
+    assert!(
+        matches!(result, Err(ContractError::Panic(_))),
+        "The previous owner should not be able to approve an upgrade"
+    );
+}
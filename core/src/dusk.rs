@@ -6,17 +6,29 @@
 
 //! Dusk denomination.
 
+use alloc::format;
+use core::fmt;
+
 const DUSK_UNIT: f64 = 1_000_000_000.0;
 
+/// Number of decimals of a Dusk amount.
+const DECIMALS: usize = 9;
+
 /// The minimum increment of Dusk.
 pub const LUX: Dusk = dusk(1.0 / DUSK_UNIT);
 
+/// The number of [`LUX`] in one Dusk.
+pub const LUX_PER_DUSK: Dusk = 1_000_000_000;
+
 /// The Dusk denomination. Use the [`dusk`] function to convert from floating
 /// point format, and the [`from_dusk`] function to convert back to Dusk.
 ///
 /// Values of Dusk should *never* be assigned directly. Instead they should use
 /// a call to the [`dusk`] function. If increments of the smallest denomination
 /// are desired, the [`LUX`] constant can be used.
+///
+/// Amounts given by users or displayed to them should go through
+/// [`parse_dusk`] and [`DuskDisplay`], which are exact.
 pub type Dusk = u64;
 
 /// Converts from floating point format to Dusk.
@@ -34,8 +46,148 @@ pub const fn from_dusk(dusk: Dusk) -> f64 {
     dusk as f64 / DUSK_UNIT
 }
 
+/// Error parsing a Dusk amount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseDuskError {
+    /// The amount has no digit.
+    Empty,
+    /// The amount has a character other than digits and a decimal point.
+    InvalidDigit,
+    /// The amount has more decimals than the [`LUX`] can represent.
+    TooManyDecimals,
+    /// The amount is larger than the largest Dusk value.
+    Overflow,
+}
+
+impl fmt::Display for ParseDuskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty amount"),
+            Self::InvalidDigit => write!(f, "invalid digit in amount"),
+            Self::TooManyDecimals => {
+                write!(f, "amount with more than {DECIMALS} decimals")
+            }
+            Self::Overflow => write!(f, "amount too large"),
+        }
+    }
+}
+
+fn parse_digits(digits: &str) -> Result<u64, ParseDuskError> {
+    digits.bytes().try_fold(0u64, |acc, b| {
+        if !b.is_ascii_digit() {
+            return Err(ParseDuskError::InvalidDigit);
+        }
+        acc.checked_mul(10)
+            .and_then(|acc| acc.checked_add(u64::from(b - b'0')))
+            .ok_or(ParseDuskError::Overflow)
+    })
+}
+
+/// Parses a decimal amount of Dusk, such as `"1000.25"`, into [`LUX`].
+///
+/// The parsing is exact, and doesn't depend on the locale: the decimal
+/// separator is always a point, and no digit grouping is allowed.
+///
+/// # Errors
+/// Returns a [`ParseDuskError`] if the amount is malformed, has more than 9
+/// significant decimals, or is larger than the largest Dusk value.
+pub fn parse_dusk(amount: &str) -> Result<Dusk, ParseDuskError> {
+    let amount = amount.trim();
+    let (units, decimals) = amount.split_once('.').unwrap_or((amount, ""));
+    if units.is_empty() && decimals.is_empty() {
+        return Err(ParseDuskError::Empty);
+    }
+
+    let significant = decimals.trim_end_matches('0');
+    if significant.len() > DECIMALS {
+        if !significant.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseDuskError::InvalidDigit);
+        }
+        return Err(ParseDuskError::TooManyDecimals);
+    }
+    // At most 9 digits, scaled up to 9 digits, cannot overflow
+    let decimals = (significant.len()..DECIMALS)
+        .fold(parse_digits(significant)?, |decimals, _| decimals * 10);
+
+    parse_digits(units)?
+        .checked_mul(LUX_PER_DUSK)
+        .and_then(|lux| lux.checked_add(decimals))
+        .ok_or(ParseDuskError::Overflow)
+}
+
+/// Displays an amount of [`LUX`] in Dusk, with a point as decimal separator
+/// whatever the locale.
+///
+/// Without precision, the trailing zeros of the decimals are left out. A
+/// precision truncates the decimals, up to 9 of them. Width, alignment and
+/// the `+` flag are honored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DuskDisplay {
+    lux: Dusk,
+    negative: bool,
+}
+
+impl DuskDisplay {
+    /// Displays an amount of Lux.
+    #[must_use]
+    pub const fn new(lux: Dusk) -> Self {
+        Self {
+            lux,
+            negative: false,
+        }
+    }
+
+    /// Displays a signed amount of Lux, such as the balance change of an
+    /// account.
+    #[must_use]
+    pub const fn signed(lux: i64) -> Self {
+        Self {
+            lux: lux.unsigned_abs(),
+            negative: lux < 0,
+        }
+    }
+}
+
+impl fmt::Display for DuskDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let units = self.lux / LUX_PER_DUSK;
+        let decimals = self.lux % LUX_PER_DUSK;
+
+        let mut amount = format!("{units}.{decimals:09}");
+        match f.precision() {
+            Some(precision) => {
+                amount.truncate(
+                    amount.len() - DECIMALS + precision.min(DECIMALS),
+                );
+            }
+            None => amount.truncate(amount.trim_end_matches('0').len()),
+        }
+        if amount.ends_with('.') {
+            amount.pop();
+        }
+
+        f.pad_integral(!self.negative, "", &amount)
+    }
+}
+
+/// Returns `basis_points` hundredths of a percent of `amount`, rounded down.
+#[must_use]
+pub fn basis_points_of(amount: Dusk, basis_points: u16) -> Dusk {
+    let part = u128::from(amount) * u128::from(basis_points) / 10_000;
+    Dusk::try_from(part).unwrap_or(Dusk::MAX)
+}
+
+/// Returns the fee paid for `gas_spent` at `gas_price`, or `None` if it
+/// overflows.
+#[must_use]
+pub const fn checked_fee(gas_spent: u64, gas_price: Dusk) -> Option<Dusk> {
+    gas_spent.checked_mul(gas_price)
+}
+
 #[cfg(test)]
 mod tests {
+    use alloc::string::ToString;
+
     use super::*;
 
     #[test]
@@ -50,4 +202,62 @@ mod tests {
     fn lux_is_one() {
         assert_eq!(LUX, 1);
     }
+
+    #[test]
+    fn parse_amounts() {
+        assert_eq!(parse_dusk("1"), Ok(LUX_PER_DUSK));
+        assert_eq!(parse_dusk(" 69.420 "), Ok(69_420_000_000));
+        assert_eq!(parse_dusk(".5"), Ok(500_000_000));
+        assert_eq!(parse_dusk("2."), Ok(2 * LUX_PER_DUSK));
+        assert_eq!(parse_dusk("0.000000001"), Ok(LUX));
+        assert_eq!(parse_dusk("1.0000000000"), Ok(LUX_PER_DUSK));
+        assert_eq!(parse_dusk("18446744073.709551615"), Ok(u64::MAX));
+
+        assert_eq!(parse_dusk(""), Err(ParseDuskError::Empty));
+        assert_eq!(parse_dusk("."), Err(ParseDuskError::Empty));
+        assert_eq!(parse_dusk("-1"), Err(ParseDuskError::InvalidDigit));
+        assert_eq!(parse_dusk("1,5"), Err(ParseDuskError::InvalidDigit));
+        assert_eq!(parse_dusk("1e9"), Err(ParseDuskError::InvalidDigit));
+        assert_eq!(
+            parse_dusk("0.0000000001"),
+            Err(ParseDuskError::TooManyDecimals)
+        );
+        assert_eq!(
+            parse_dusk("18446744073.709551616"),
+            Err(ParseDuskError::Overflow)
+        );
+    }
+
+    #[test]
+    fn display_amounts() {
+        assert_eq!(DuskDisplay::new(69_420_000_000).to_string(), "69.42");
+        assert_eq!(DuskDisplay::new(2 * LUX_PER_DUSK).to_string(), "2");
+        assert_eq!(DuskDisplay::new(LUX).to_string(), "0.000000001");
+        assert_eq!(
+            DuskDisplay::new(u64::MAX).to_string(),
+            "18446744073.709551615"
+        );
+        assert_eq!(format!("{:.2}", DuskDisplay::new(1_999_999_999)), "1.99");
+        assert_eq!(format!("{:.0}", DuskDisplay::new(1_999_999_999)), "1");
+        assert_eq!(
+            format!("{:>8.3}", DuskDisplay::new(LUX_PER_DUSK)),
+            "   1.000"
+        );
+        assert_eq!(format!("{:+}", DuskDisplay::signed(500_000_000)), "+0.5");
+        assert_eq!(format!("{}", DuskDisplay::signed(-500_000_000)), "-0.5");
+
+        for amount in [0, LUX, 1_234_567_890_123, u64::MAX] {
+            let displayed = DuskDisplay::new(amount).to_string();
+            assert_eq!(parse_dusk(&displayed), Ok(amount));
+        }
+    }
+
+    #[test]
+    fn fee_math() {
+        assert_eq!(basis_points_of(LUX_PER_DUSK, 250), 25_000_000);
+        assert_eq!(basis_points_of(3, 5000), 1);
+        assert_eq!(basis_points_of(u64::MAX, 10_000), u64::MAX);
+        assert_eq!(checked_fee(21_000, 1), Some(21_000));
+        assert_eq!(checked_fee(u64::MAX, 2), None);
+    }
 }
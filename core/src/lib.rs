@@ -4,7 +4,8 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
-//! Types used for interacting with Dusk's transfer and stake contracts.
+//! Types used for interacting with Dusk's transfer and stake contracts, as
+//! well as the reference token contract.
 
 #![no_std]
 #![deny(missing_docs)]
@@ -20,13 +21,17 @@ extern crate alloc;
 pub mod abi;
 
 pub mod stake;
+pub mod token;
 pub mod transfer;
 
 mod error;
 pub use error::Error;
 
 mod dusk;
-pub use dusk::{dusk, from_dusk, Dusk, LUX};
+pub use dusk::{
+    basis_points_of, checked_fee, dusk, from_dusk, parse_dusk, Dusk,
+    DuskDisplay, ParseDuskError, LUX, LUX_PER_DUSK,
+};
 
 // elliptic curve types
 pub use dusk_bls12_381::BlsScalar;
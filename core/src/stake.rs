@@ -11,7 +11,9 @@ use alloc::vec::Vec;
 
 use bytecheck::CheckBytes;
 use dusk_bytes::Serializable;
+use ff::Field;
 use piecrust_uplink::CONTRACT_ID_BYTES;
+use rand::{CryptoRng, RngCore};
 use rkyv::{Archive, Deserialize, Serialize};
 
 use crate::abi::ContractId;
@@ -19,15 +21,22 @@ use crate::signatures::bls::{
     PublicKey as BlsPublicKey, SecretKey as BlsSecretKey,
     Signature as BlsSignature,
 };
-use crate::transfer::withdraw::Withdraw as TransferWithdraw;
-use crate::{dusk, Dusk};
+use crate::transfer::phoenix::PublicKey as PhoenixPublicKey;
+use crate::transfer::withdraw::{
+    Withdraw as TransferWithdraw, WithdrawReceiver,
+};
+use crate::{dusk, Dusk, JubJubScalar};
 
 /// ID of the genesis stake contract
 pub const STAKE_CONTRACT: ContractId = crate::reserved(0x2);
 
-/// Epoch used for stake operations
+/// Default epoch used for stake operations
 pub const EPOCH: u64 = 2160;
 
+/// Default number of blocks a stake takes to mature, once the epoch it is
+/// created in ends
+pub const DEFAULT_MATURITY: u64 = EPOCH;
+
 /// Default number of warnings before being penalized
 pub const DEFAULT_STAKE_WARNINGS: u8 = 1;
 
@@ -41,6 +50,12 @@ pub const MINIMUM_STAKE: Dusk = DEFAULT_MINIMUM_STAKE;
 /// The default minimum amount of Dusk one can stake.
 pub const DEFAULT_MINIMUM_STAKE: Dusk = dusk(1_000.0);
 
+/// Default percentage of the stake slashed for each fault
+pub const DEFAULT_SLASH_PERCENT: u8 = 10;
+
+/// Default number of epochs a stake is suspended for each fault
+pub const DEFAULT_SUSPENSION_EPOCHS: u64 = 1;
+
 /// Configuration for the stake contract
 #[derive(Debug, Clone, Archive, Serialize, Deserialize)]
 #[archive_attr(derive(CheckBytes))]
@@ -49,6 +64,13 @@ pub struct StakeConfig {
     pub warnings: u8,
     /// Minimum amount of Dusk that can be staked
     pub minimum_stake: Dusk,
+    /// Penalties applied to faulty provisioners
+    pub slashing: SlashingConfig,
+    /// Number of blocks in an epoch of the stake operations
+    pub epoch: u64,
+    /// Number of blocks a stake takes to mature, once the epoch it is created
+    /// in ends
+    pub maturity: u64,
 }
 
 impl StakeConfig {
@@ -58,8 +80,99 @@ impl StakeConfig {
         Self {
             warnings: DEFAULT_STAKE_WARNINGS,
             minimum_stake: DEFAULT_MINIMUM_STAKE,
+            slashing: SlashingConfig::new(),
+            epoch: EPOCH,
+            maturity: DEFAULT_MATURITY,
         }
     }
+
+    /// Calculate the block height at which the next epoch takes effect.
+    #[must_use]
+    pub const fn next_epoch(&self, block_height: u64) -> u64 {
+        let to_next_epoch = self.epoch - (block_height % self.epoch);
+        block_height + to_next_epoch
+    }
+
+    /// Compute the eligibility of a stake created at `block_height`.
+    #[must_use]
+    pub const fn eligibility(&self, block_height: u64) -> u64 {
+        self.next_epoch(block_height) + self.maturity
+    }
+
+    /// Number of blocks a stake is suspended for the given number of faults.
+    #[must_use]
+    pub fn suspension(&self, faults: u64) -> u64 {
+        faults
+            .saturating_mul(self.slashing.suspension_epochs)
+            .saturating_mul(self.epoch)
+    }
+}
+
+/// Slashing schedule of the stake contract.
+///
+/// Penalties grow linearly with the number of faults of a provisioner: each
+/// fault (beyond the warnings, for soft faults) slashes a further percentage
+/// of the stake, up to `max_slash_percent`, and suspends the stake for a
+/// further number of epochs.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct SlashingConfig {
+    /// Percentage of the stake locked for each soft fault
+    pub soft_slash_percent: u8,
+    /// Percentage of the stake burnt for each hard fault
+    pub hard_slash_percent: u8,
+    /// Maximum percentage of the stake slashed at once
+    pub max_slash_percent: u8,
+    /// Number of epochs the stake is suspended for each fault
+    pub suspension_epochs: u64,
+}
+
+impl SlashingConfig {
+    /// Create a new default slashing configuration.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            soft_slash_percent: DEFAULT_SLASH_PERCENT,
+            hard_slash_percent: DEFAULT_SLASH_PERCENT,
+            max_slash_percent: 100,
+            suspension_epochs: DEFAULT_SUSPENSION_EPOCHS,
+        }
+    }
+
+    /// Amount to slash from `value` for the given number of soft faults.
+    #[must_use]
+    pub fn soft_slash(&self, value: u64, faults: u64) -> u64 {
+        self.slash(value, faults, self.soft_slash_percent)
+    }
+
+    /// Amount to slash from `value` for the given number of hard faults.
+    #[must_use]
+    pub fn hard_slash(&self, value: u64, faults: u64) -> u64 {
+        self.slash(value, faults, self.hard_slash_percent)
+    }
+
+    /// Number of blocks a stake is suspended for the given number of faults,
+    /// with epochs of the default length.
+    ///
+    /// Use [`StakeConfig::suspension`] for the configured epoch length.
+    #[must_use]
+    pub fn suspension(&self, faults: u64) -> u64 {
+        faults
+            .saturating_mul(self.suspension_epochs)
+            .saturating_mul(EPOCH)
+    }
+
+    fn slash(&self, value: u64, faults: u64, percent: u8) -> u64 {
+        let max = u64::from(self.max_slash_percent.min(100));
+        let percent = faults.saturating_mul(u64::from(percent)).min(max);
+        value / 100 * percent
+    }
+}
+
+impl Default for SlashingConfig {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Default for StakeConfig {
@@ -68,7 +181,35 @@ impl Default for StakeConfig {
     }
 }
 
-/// Calculate the block height at which the next epoch takes effect.
+/// Configuration of the stake contract up to its version
+/// [`LegacyStakeConfig::VERSION`], replaced by [`StakeConfig`] when the
+/// contract is migrated.
+#[derive(Debug, Clone, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct LegacyStakeConfig {
+    /// Number of warnings before being penalized
+    pub warnings: u8,
+    /// Minimum amount of Dusk that can be staked
+    pub minimum_stake: Dusk,
+}
+
+impl LegacyStakeConfig {
+    /// Last version of the stake contract storing this configuration
+    pub const VERSION: u64 = 8;
+}
+
+impl From<LegacyStakeConfig> for StakeConfig {
+    fn from(config: LegacyStakeConfig) -> Self {
+        Self {
+            warnings: config.warnings,
+            minimum_stake: config.minimum_stake,
+            ..Self::new()
+        }
+    }
+}
+
+/// Calculate the block height at which the next epoch takes effect, with
+/// epochs of the default length.
 #[must_use]
 pub const fn next_epoch(block_height: u64) -> u64 {
     let to_next_epoch = EPOCH - (block_height % EPOCH);
@@ -357,6 +498,213 @@ impl Withdraw {
     }
 }
 
+/// The receiver of the rewards of a stake, registered by the stake owner.
+///
+/// Once registered, rewards can be withdrawn to the beneficiary with the sole
+/// signature of the stake account, allowing the consensus key to be kept on a
+/// server while the rewards accrue to a cold wallet.
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub enum Beneficiary {
+    /// Rewards are withdrawn into Phoenix notes owned by this key.
+    Phoenix(PhoenixPublicKey),
+    /// Rewards are withdrawn into this Moonlight account.
+    Moonlight(BlsPublicKey),
+}
+
+/// Register, or clear, the beneficiary of the rewards of a stake.
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct SetBeneficiary {
+    chain_id: u8,
+    account: BlsPublicKey,
+    beneficiary: Option<Beneficiary>,
+    nonce: u64,
+    signature: BlsSignature,
+}
+
+impl SetBeneficiary {
+    /// Create a new beneficiary registration, signed by the stake owner.
+    ///
+    /// The `nonce` must be higher than the one of the previous registration
+    /// for the same stake.
+    #[must_use]
+    pub fn new(
+        owner_sk: &BlsSecretKey,
+        account: BlsPublicKey,
+        beneficiary: Option<Beneficiary>,
+        nonce: u64,
+        chain_id: u8,
+    ) -> Self {
+        let mut set_beneficiary = SetBeneficiary {
+            chain_id,
+            account,
+            beneficiary,
+            nonce,
+            signature: BlsSignature::default(),
+        };
+
+        let msg = set_beneficiary.signature_message();
+        set_beneficiary.signature = owner_sk.sign(&msg);
+
+        set_beneficiary
+    }
+
+    /// The stake to register the beneficiary of.
+    #[must_use]
+    pub fn account(&self) -> &BlsPublicKey {
+        &self.account
+    }
+
+    /// The beneficiary to register, or `None` to clear it.
+    #[must_use]
+    pub fn beneficiary(&self) -> Option<&Beneficiary> {
+        self.beneficiary.as_ref()
+    }
+
+    /// The nonce of the registration.
+    #[must_use]
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    /// Returns the chain ID of the registration.
+    #[must_use]
+    pub fn chain_id(&self) -> u8 {
+        self.chain_id
+    }
+
+    /// Signature of the stake owner.
+    #[must_use]
+    pub fn signature(&self) -> &BlsSignature {
+        &self.signature
+    }
+
+    /// Signature message used for [`SetBeneficiary`].
+    #[must_use]
+    pub fn signature_message(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.push(self.chain_id);
+        bytes.extend(self.account.to_bytes());
+
+        match &self.beneficiary {
+            None => bytes.push(0),
+            Some(Beneficiary::Phoenix(pk)) => {
+                bytes.push(1);
+                bytes.extend(pk.to_bytes());
+            }
+            Some(Beneficiary::Moonlight(pk)) => {
+                bytes.push(2);
+                bytes.extend(pk.to_bytes());
+            }
+        }
+
+        bytes.extend(self.nonce.to_bytes());
+
+        bytes
+    }
+}
+
+/// Withdraw rewards from the stake contract to the registered beneficiary.
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct WithdrawToBeneficiary {
+    chain_id: u8,
+    account: BlsPublicKey,
+    value: u64,
+    nonce: u64,
+    r: JubJubScalar,
+    signature: BlsSignature,
+}
+
+impl WithdrawToBeneficiary {
+    /// Create a new withdrawal to the beneficiary, signed by the stake
+    /// account.
+    ///
+    /// The `nonce` must be higher than the one of the previous withdrawal to
+    /// the beneficiary of the same stake.
+    #[must_use]
+    pub fn new<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        account_sk: &BlsSecretKey,
+        value: u64,
+        nonce: u64,
+        chain_id: u8,
+    ) -> Self {
+        let mut withdraw = WithdrawToBeneficiary {
+            chain_id,
+            account: BlsPublicKey::from(account_sk),
+            value,
+            nonce,
+            r: JubJubScalar::random(&mut *rng),
+            signature: BlsSignature::default(),
+        };
+
+        let msg = withdraw.signature_message();
+        withdraw.signature = account_sk.sign(&msg);
+
+        withdraw
+    }
+
+    /// The stake to withdraw the rewards of.
+    #[must_use]
+    pub fn account(&self) -> &BlsPublicKey {
+        &self.account
+    }
+
+    /// The value to withdraw.
+    #[must_use]
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// The nonce of the withdrawal.
+    #[must_use]
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    /// Returns the chain ID of the withdrawal.
+    #[must_use]
+    pub fn chain_id(&self) -> u8 {
+        self.chain_id
+    }
+
+    /// Signature of the stake account.
+    #[must_use]
+    pub fn signature(&self) -> &BlsSignature {
+        &self.signature
+    }
+
+    /// The receiver of the value withdrawn, given the registered
+    /// beneficiary. A Phoenix beneficiary receives the value at a fresh
+    /// stealth address.
+    #[must_use]
+    pub fn receiver(&self, beneficiary: &Beneficiary) -> WithdrawReceiver {
+        match beneficiary {
+            Beneficiary::Phoenix(pk) => {
+                WithdrawReceiver::Phoenix(pk.gen_stealth_address(&self.r))
+            }
+            Beneficiary::Moonlight(pk) => WithdrawReceiver::Moonlight(*pk),
+        }
+    }
+
+    /// Signature message used for [`WithdrawToBeneficiary`].
+    #[must_use]
+    pub fn signature_message(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.push(self.chain_id);
+        bytes.extend(self.account.to_bytes());
+        bytes.extend(self.value.to_bytes());
+        bytes.extend(self.nonce.to_bytes());
+        bytes.extend(self.r.to_bytes());
+
+        bytes
+    }
+}
+
 /// Event emitted after a stake contract operation is performed.
 #[derive(Debug, Clone, Archive, Deserialize, Serialize)]
 #[archive_attr(derive(CheckBytes))]
@@ -412,6 +760,16 @@ pub struct SlashEvent {
     pub next_eligibility: u64,
 }
 
+/// Event emitted after the beneficiary of a stake is registered or cleared.
+#[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct BeneficiaryEvent {
+    /// Account of the stake.
+    pub account: BlsPublicKey,
+    /// The registered beneficiary, or `None` if cleared.
+    pub beneficiary: Option<Beneficiary>,
+}
+
 /// The representation of a public key's stake.
 ///
 /// A user can stake for a particular `amount` larger in value than the
@@ -638,10 +996,11 @@ impl StakeAmount {
         }
     }
 
-    /// Compute the eligibility of a stake from the starting block height.
+    /// Compute the eligibility of a stake from the starting block height,
+    /// with the default epoch and maturity.
     #[must_use]
     pub const fn eligibility_from_height(block_height: u64) -> u64 {
-        let maturity_blocks = EPOCH;
+        let maturity_blocks = DEFAULT_MATURITY;
         next_epoch(block_height) + maturity_blocks
     }
 
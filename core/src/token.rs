@@ -0,0 +1,193 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Types used by the reference fungible-token contract.
+//!
+//! Operations on the token are authorized by their sender. A contract is the
+//! sender of the calls it makes to the token contract, while an external
+//! account is the sender of a Moonlight transaction directly calling the
+//! token contract.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use bytecheck::CheckBytes;
+use dusk_bytes::Serializable;
+use rkyv::{Archive, Deserialize, Serialize};
+
+use crate::abi::{ContractId, CONTRACT_ID_BYTES};
+use crate::signatures::bls::PublicKey as BlsPublicKey;
+
+/// Topic for the token transfer event.
+pub const TRANSFER_TOPIC: &str = "token_transfer";
+/// Topic for the token approval event.
+pub const APPROVE_TOPIC: &str = "token_approve";
+/// Topic for the token mint event.
+pub const MINT_TOPIC: &str = "token_mint";
+/// Topic for the token burn event.
+pub const BURN_TOPIC: &str = "token_burn";
+
+/// A holder of tokens.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Archive, Serialize, Deserialize,
+)]
+#[archive_attr(derive(CheckBytes))]
+pub enum Account {
+    /// An external account, identified by its public key.
+    External(BlsPublicKey),
+    /// A contract.
+    Contract(ContractId),
+}
+
+impl Account {
+    /// Size of the byte representation of an account.
+    pub const SIZE: usize = 1 + BlsPublicKey::SIZE;
+
+    /// Returns a unique byte representation of the account, usable as a key.
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0u8; Self::SIZE];
+        match self {
+            Account::External(pk) => {
+                bytes[0] = 0;
+                bytes[1..].copy_from_slice(&pk.to_bytes());
+            }
+            Account::Contract(contract) => {
+                bytes[0] = 1;
+                bytes[1..=CONTRACT_ID_BYTES]
+                    .copy_from_slice(&contract.to_bytes());
+            }
+        }
+        bytes
+    }
+}
+
+impl From<BlsPublicKey> for Account {
+    fn from(pk: BlsPublicKey) -> Self {
+        Self::External(pk)
+    }
+}
+
+impl From<ContractId> for Account {
+    fn from(contract: ContractId) -> Self {
+        Self::Contract(contract)
+    }
+}
+
+/// Arguments of the token contract's `init` function.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct TokenInit {
+    /// Name of the token.
+    pub name: String,
+    /// Symbol of the token.
+    pub symbol: String,
+    /// Number of decimals used to display the token.
+    pub decimals: u8,
+    /// The account allowed to mint new tokens, if any.
+    pub minter: Option<Account>,
+    /// Initial balances of the token.
+    pub balances: Vec<(Account, u64)>,
+}
+
+/// Information about a token.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct TokenInfo {
+    /// Name of the token.
+    pub name: String,
+    /// Symbol of the token.
+    pub symbol: String,
+    /// Number of decimals used to display the token.
+    pub decimals: u8,
+    /// Total amount of tokens in circulation.
+    pub total_supply: u64,
+}
+
+/// Transfer tokens from the sender to another account.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct Transfer {
+    /// Account receiving the tokens.
+    pub receiver: Account,
+    /// Amount of tokens transferred.
+    pub value: u64,
+}
+
+/// Allow a spender to transfer up to `value` tokens of the sender.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct Approve {
+    /// Account allowed to spend the tokens.
+    pub spender: Account,
+    /// Amount of tokens the spender is allowed to transfer, replacing any
+    /// previous allowance.
+    pub value: u64,
+}
+
+/// Transfer tokens from an account that approved the sender as spender.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct TransferFrom {
+    /// Account owning the tokens.
+    pub owner: Account,
+    /// Account receiving the tokens.
+    pub receiver: Account,
+    /// Amount of tokens transferred.
+    pub value: u64,
+}
+
+/// Mint new tokens. Only the minter of the token can do so.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct Mint {
+    /// Account receiving the tokens.
+    pub receiver: Account,
+    /// Amount of tokens minted.
+    pub value: u64,
+}
+
+/// Burn tokens of the sender.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct Burn {
+    /// Amount of tokens burnt.
+    pub value: u64,
+}
+
+/// Event emitted on a transfer of tokens.
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct TransferEvent {
+    /// The account the tokens were transferred from.
+    pub sender: Account,
+    /// The account the tokens were transferred to.
+    pub receiver: Account,
+    /// The amount of tokens transferred.
+    pub value: u64,
+}
+
+/// Event emitted when an account approves a spender.
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct ApproveEvent {
+    /// The account owning the tokens.
+    pub owner: Account,
+    /// The account allowed to spend the tokens.
+    pub spender: Account,
+    /// The amount of tokens the spender is allowed to transfer.
+    pub value: u64,
+}
+
+/// Event emitted when tokens are minted or burnt.
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct SupplyEvent {
+    /// The account receiving the minted tokens, or whose tokens are burnt.
+    pub account: Account,
+    /// The amount of tokens minted or burnt.
+    pub value: u64,
+}
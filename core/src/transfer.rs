@@ -44,6 +44,9 @@ pub const TRANSFER_CONTRACT: ContractId = crate::reserved(0x1);
 /// Panic of "Nonce not ready to be used yet"
 pub const PANIC_NONCE_NOT_READY: &str = "Nonce not ready to be used yet";
 
+/// Panic of "Transaction expired"
+pub const PANIC_TX_EXPIRED: &str = "Transaction expired";
+
 /// Topic for the moonlight transaction event.
 pub const MOONLIGHT_TOPIC: &str = "moonlight";
 /// Topic for the phoenix transaction event.
@@ -62,6 +65,12 @@ pub const CONVERT_TOPIC: &str = "convert";
 pub const MINT_TOPIC: &str = "mint";
 /// Topic for the mint to contract event.
 pub const MINT_CONTRACT_TOPIC: &str = "mint_c";
+/// Topic for the contract ownership transfer event.
+pub const OWNERSHIP_TOPIC: &str = "ownership";
+/// Topic for the contract upgrade approval event.
+pub const UPGRADE_APPROVAL_TOPIC: &str = "upgrade_approval";
+/// Topic for the contract upgrade event.
+pub const UPGRADE_TOPIC: &str = "upgrade";
 
 /// The transaction used by the transfer contract.
 #[derive(Debug, Clone, Archive, PartialEq, Eq, Serialize, Deserialize)]
@@ -142,6 +151,24 @@ impl Transaction {
         )?))
     }
 
+    /// Returns the height of the last block the transaction can be included
+    /// in, if any. Only Moonlight transactions can expire.
+    #[must_use]
+    pub fn expiry_height(&self) -> Option<u64> {
+        match self {
+            Self::Phoenix(_) => None,
+            Self::Moonlight(tx) => tx.expiry_height(),
+        }
+    }
+
+    /// Returns whether the transaction can no longer be included in a block
+    /// at `block_height`.
+    #[must_use]
+    pub fn is_expired_at(&self, block_height: u64) -> bool {
+        self.expiry_height()
+            .is_some_and(|expiry_height| block_height > expiry_height)
+    }
+
     /// Return the sender of the account for Moonlight transactions.
     #[must_use]
     pub fn moonlight_sender(&self) -> Option<&AccountPublicKey> {
@@ -420,6 +447,45 @@ pub struct ContractToAccount {
     pub value: u64,
 }
 
+/// The payload sent by the owner of a contract to the transfer contract to
+/// hand the ownership of the contract over to another account.
+#[derive(Debug, Clone, Archive, PartialEq, Eq, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct TransferOwnership {
+    /// Contract to transfer the ownership of.
+    pub contract: ContractId,
+    /// Account becoming the owner of the contract.
+    pub new_owner: AccountPublicKey,
+}
+
+/// The payload sent by the owner of a contract to the transfer contract to
+/// approve the bytecode the contract may be upgraded to.
+///
+/// The upgrade takes place once a contract with the approved bytecode is
+/// deployed by the same owner, and is recorded by the transfer contract as
+/// the successor of the approving contract.
+#[derive(Debug, Clone, Archive, PartialEq, Eq, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct ApproveUpgrade {
+    /// Contract to be upgraded.
+    pub contract: ContractId,
+    /// Hash of the bytecode of the successor contract.
+    pub bytecode_hash: [u8; 32],
+}
+
+/// The payload sent by the VM to the transfer contract after a deployment, to
+/// complete an approved upgrade.
+#[derive(Debug, Clone, Archive, PartialEq, Eq, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct CompleteUpgrade {
+    /// The newly deployed contract.
+    pub contract: ContractId,
+    /// Hash of the bytecode of the newly deployed contract.
+    pub bytecode_hash: [u8; 32],
+    /// Raw owner of the newly deployed contract.
+    pub owner: Vec<u8>,
+}
+
 /// Event data emitted on a withdrawal from a contract.
 #[derive(Debug, Clone, Archive, PartialEq, Serialize, Deserialize)]
 #[archive_attr(derive(CheckBytes))]
@@ -544,3 +610,37 @@ pub struct MoonlightTransactionEvent {
     /// from the sender.
     pub refund_info: Option<(AccountPublicKey, u64)>,
 }
+
+/// Event data emitted on the transfer of the ownership of a contract.
+#[derive(Debug, Clone, Archive, PartialEq, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct OwnershipEvent {
+    /// The contract whose ownership was transferred.
+    pub contract: ContractId,
+    /// The previous owner of the contract.
+    pub previous_owner: AccountPublicKey,
+    /// The new owner of the contract.
+    pub new_owner: AccountPublicKey,
+}
+
+/// Event data emitted when the owner of a contract approves an upgrade.
+#[derive(Debug, Clone, Archive, PartialEq, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct UpgradeApprovalEvent {
+    /// The contract to be upgraded.
+    pub contract: ContractId,
+    /// The owner approving the upgrade.
+    pub owner: AccountPublicKey,
+    /// Hash of the bytecode of the successor contract.
+    pub bytecode_hash: [u8; 32],
+}
+
+/// Event data emitted when a contract is upgraded.
+#[derive(Debug, Clone, Archive, PartialEq, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct UpgradeEvent {
+    /// The upgraded contract.
+    pub contract: ContractId,
+    /// The contract succeeding it.
+    pub successor: ContractId,
+}
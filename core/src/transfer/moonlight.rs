@@ -33,12 +33,52 @@ pub struct AccountData {
     pub balance: u64,
 }
 
+/// Domain separator of the message signed by a transaction sponsor.
+const SPONSOR_DOMAIN: &[u8] = b"dusk-moonlight-sponsor";
+
+/// Domain separator of the message signed for a transaction with an expiry
+/// height.
+const EXPIRY_DOMAIN: &[u8] = b"dusk-moonlight-expiry";
+
+/// Tag preceding the expiry height in the serialized transaction.
+const EXPIRY_TAG: u8 = 0xff;
+
+/// Size of the serialized expiry height, with its tag.
+const EXPIRY_SIZE: usize = 1 + u64::SIZE;
+
 /// Moonlight transaction.
+///
+/// The payload of a transaction is versioned: the original payload can be
+/// extended by an expiry height, signed along with it. Extensions follow the
+/// fields preceding them, leaving their archived layout unchanged for the
+/// contracts predating the extensions, which reject the extended payloads by
+/// failing to verify their signature.
 #[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize, Deserialize)]
 #[archive_attr(derive(CheckBytes))]
 pub struct Transaction {
     payload: Payload,
     signature: AccountSignature,
+    sponsor: Option<Sponsor>,
+    expiry_height: Option<u64>,
+}
+
+/// A third party paying for the gas of a transaction.
+///
+/// The sponsor signs over the transaction payload, committing to pay up to
+/// `gas_limit * gas_price` from its account. For a sponsored transaction the
+/// refund-address must be the sponsor's account, so that any unspent gas is
+/// returned to it.
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct Sponsor {
+    /// Account paying for the gas.
+    pub account: AccountPublicKey,
+    /// Signature of the sponsor over the transaction payload.
+    pub signature: AccountSignature,
+}
+
+impl Sponsor {
+    const SIZE: usize = AccountPublicKey::SIZE + AccountSignature::SIZE;
 }
 
 impl Transaction {
@@ -131,6 +171,29 @@ impl Transaction {
     pub fn sign_payload(
         sender_sk: &AccountSecretKey,
         payload: Payload,
+    ) -> Result<Self, Error> {
+        Self::sign_versioned_payload(sender_sk, payload, None)
+    }
+
+    /// Create a transaction that can only be included in a block up to
+    /// `expiry_height`, by signing a previously generated payload extended
+    /// with it.
+    ///
+    /// # Errors
+    /// The creation of a transaction is not possible and will error if:
+    /// - the payload memo, if given, is too large
+    pub fn sign_expiring_payload(
+        sender_sk: &AccountSecretKey,
+        payload: Payload,
+        expiry_height: u64,
+    ) -> Result<Self, Error> {
+        Self::sign_versioned_payload(sender_sk, payload, Some(expiry_height))
+    }
+
+    fn sign_versioned_payload(
+        sender_sk: &AccountSecretKey,
+        payload: Payload,
+        expiry_height: Option<u64>,
     ) -> Result<Self, Error> {
         if let Some(TransactionData::Memo(memo)) = payload.data.as_ref() {
             if memo.len() > MAX_MEMO_SIZE {
@@ -138,10 +201,53 @@ impl Transaction {
             }
         }
 
-        let digest = payload.signature_message();
+        let digest = signature_message(&payload, expiry_height);
         let signature = sender_sk.sign(&digest);
 
-        Ok(Self { payload, signature })
+        Ok(Self {
+            payload,
+            signature,
+            sponsor: None,
+            expiry_height,
+        })
+    }
+
+    /// Have the gas of this transaction paid by the owner of `sponsor_sk`.
+    ///
+    /// Note that the transaction will be invalid unless its refund-address
+    /// is the sponsor's account.
+    #[must_use]
+    pub fn sponsored(mut self, sponsor_sk: &AccountSecretKey) -> Self {
+        let signature = sponsor_sk.sign(&self.sponsor_signature_message());
+        self.sponsor = Some(Sponsor {
+            account: AccountPublicKey::from(sponsor_sk),
+            signature,
+        });
+        self
+    }
+
+    /// Return the sponsor paying for the gas of the transaction, if any.
+    #[must_use]
+    pub fn sponsor(&self) -> Option<&Sponsor> {
+        self.sponsor.as_ref()
+    }
+
+    /// Return the account paying for the gas of the transaction: the sponsor
+    /// if there is one, the sender otherwise.
+    #[must_use]
+    pub fn gas_payer(&self) -> &AccountPublicKey {
+        match &self.sponsor {
+            Some(sponsor) => &sponsor.account,
+            None => &self.payload.sender,
+        }
+    }
+
+    /// Return the message a sponsor signs over to pay for the transaction.
+    #[must_use]
+    pub fn sponsor_signature_message(&self) -> Vec<u8> {
+        let mut bytes = SPONSOR_DOMAIN.to_vec();
+        bytes.extend(self.signature_message());
+        bytes
     }
 
     /// The proof of the transaction.
@@ -209,6 +315,13 @@ impl Transaction {
         self.payload.chain_id
     }
 
+    /// Returns the height of the last block the transaction can be included
+    /// in, if any.
+    #[must_use]
+    pub fn expiry_height(&self) -> Option<u64> {
+        self.expiry_height
+    }
+
     /// Return the contract call data, if there is any.
     #[must_use]
     pub fn call(&self) -> Option<&ContractCall> {
@@ -278,6 +391,20 @@ impl Transaction {
 
         bytes.extend(self.signature.to_bytes());
 
+        // the sponsor is only appended when present, leaving the encoding of
+        // non-sponsored transactions untouched
+        if let Some(sponsor) = &self.sponsor {
+            bytes.extend(sponsor.account.to_bytes());
+            bytes.extend(sponsor.signature.to_bytes());
+        }
+
+        // the expiry height extending the payload is appended last, when
+        // present
+        if let Some(expiry_height) = self.expiry_height {
+            bytes.push(EXPIRY_TAG);
+            bytes.extend(expiry_height.to_bytes());
+        }
+
         bytes
     }
 
@@ -299,12 +426,46 @@ impl Transaction {
         let payload = Payload::from_slice(payload_buf)?;
         buf = new_buf;
 
+        let mut expiry_height = None;
+        if buf.len() == AccountSignature::SIZE + EXPIRY_SIZE
+            || buf.len() == AccountSignature::SIZE + Sponsor::SIZE + EXPIRY_SIZE
+        {
+            let (rest, mut expiry_buf) = buf.split_at(buf.len() - EXPIRY_SIZE);
+            buf = rest;
+
+            if u8::from_reader(&mut expiry_buf)? != EXPIRY_TAG {
+                return Err(BytesError::InvalidData);
+            }
+            expiry_height = Some(u64::from_reader(&mut expiry_buf)?);
+        }
+
+        let mut sponsor = None;
+        if buf.len() == AccountSignature::SIZE + Sponsor::SIZE {
+            let (sig_buf, mut sponsor_buf) =
+                buf.split_at(AccountSignature::SIZE);
+            buf = sig_buf;
+
+            let account = AccountPublicKey::from_reader(&mut sponsor_buf)?;
+            let signature = AccountSignature::from_bytes(
+                sponsor_buf
+                    .try_into()
+                    .map_err(|_| BytesError::InvalidData)?,
+            )
+            .map_err(|_| BytesError::InvalidData)?;
+            sponsor = Some(Sponsor { account, signature });
+        }
+
         let signature = AccountSignature::from_bytes(
             buf.try_into().map_err(|_| BytesError::InvalidData)?,
         )
         .map_err(|_| BytesError::InvalidData)?;
 
-        Ok(Self { payload, signature })
+        Ok(Self {
+            payload,
+            signature,
+            sponsor,
+            expiry_height,
+        })
     }
 
     /// Return input bytes to hash the payload.
@@ -313,8 +474,12 @@ impl Transaction {
     /// for hashing and *cannot* be used to deserialize the transaction again.
     #[must_use]
     pub fn to_hash_input_bytes(&self) -> Vec<u8> {
-        let mut bytes = self.payload.signature_message();
+        let mut bytes = self.signature_message();
         bytes.extend(self.signature.to_bytes());
+        if let Some(sponsor) = &self.sponsor {
+            bytes.extend(sponsor.account.to_bytes());
+            bytes.extend(sponsor.signature.to_bytes());
+        }
         bytes
     }
 
@@ -322,7 +487,7 @@ impl Transaction {
     /// transaction a valid one.
     #[must_use]
     pub fn signature_message(&self) -> Vec<u8> {
-        self.payload.signature_message()
+        signature_message(&self.payload, self.expiry_height)
     }
 
     /// Create the transaction hash.
@@ -332,6 +497,22 @@ impl Transaction {
     }
 }
 
+/// Return the message signed for `payload`, extended by `expiry_height`.
+fn signature_message(payload: &Payload, expiry_height: Option<u64>) -> Vec<u8> {
+    // the expiry height is committed to under its own domain, so that the
+    // message of a transaction without one is left untouched
+    let mut bytes = match expiry_height {
+        Some(expiry_height) => {
+            let mut bytes = EXPIRY_DOMAIN.to_vec();
+            bytes.extend(expiry_height.to_bytes());
+            bytes
+        }
+        None => Vec::new(),
+    };
+    bytes.extend(payload.signature_message());
+    bytes
+}
+
 /// The payload for a moonlight transaction.
 #[derive(Debug, Clone, PartialEq, Eq, Archive, Serialize, Deserialize)]
 #[archive_attr(derive(CheckBytes))]
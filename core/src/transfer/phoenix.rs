@@ -1000,7 +1000,10 @@ impl TxCircuitVec {
         Ok(circuit)
     }
 
-    const fn size(input_len: usize) -> usize {
+    /// Returns the size of a serialized [`TxCircuitVec`] with `input_len`
+    /// input-notes.
+    #[must_use]
+    pub const fn size(input_len: usize) -> usize {
         u64::SIZE
             + input_len * InputNoteInfo::<NOTES_TREE_DEPTH>::SIZE
             + OUTPUT_NOTES * OutputNoteInfo::SIZE
@@ -10,6 +10,9 @@ use dusk_core::signatures::bls::{
 use dusk_core::transfer::data::{
     ContractBytecode, ContractCall, ContractDeploy, TransactionData,
 };
+use dusk_core::transfer::moonlight::{
+    Fee, Payload as MoonlightPayload, Transaction as MoonlightTransaction,
+};
 use dusk_core::transfer::phoenix::{
     Note, NoteTreeItem, NotesTree, Prove, PublicKey as PhoenixPublicKey,
     SecretKey as PhoenixSecretKey, TxCircuitVec,
@@ -275,6 +278,99 @@ fn moonlight() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn moonlight_sponsored() -> Result<(), Error> {
+    let mut rng = StdRng::seed_from_u64(42);
+
+    let sender_sk = AccountSecretKey::random(&mut rng);
+    let sponsor_sk = AccountSecretKey::random(&mut rng);
+    let sponsor_pk = AccountPublicKey::from(&sponsor_sk);
+
+    let transaction: Transaction = MoonlightTransaction::new_with_refund(
+        &sender_sk,
+        &sponsor_pk,
+        None,
+        0,
+        0,
+        rng.gen(),
+        rng.gen(),
+        rng.gen(),
+        CHAIN_ID,
+        None::<TransactionData>,
+    )?
+    .sponsored(&sponsor_sk)
+    .into();
+
+    let transaction_bytes = transaction.to_var_bytes();
+    let deserialized = Transaction::from_slice(&transaction_bytes)?;
+
+    assert_eq!(transaction, deserialized);
+
+    Ok(())
+}
+
+#[test]
+fn moonlight_with_expiry() -> Result<(), Error> {
+    let mut rng = StdRng::seed_from_u64(42);
+
+    let sender_sk = AccountSecretKey::random(&mut rng);
+    let sender = AccountPublicKey::from(&sender_sk);
+
+    let payload = MoonlightPayload {
+        chain_id: CHAIN_ID,
+        sender,
+        receiver: sender,
+        value: rng.gen(),
+        deposit: 0,
+        fee: Fee {
+            gas_limit: rng.gen(),
+            gas_price: rng.gen(),
+            refund_address: sender,
+        },
+        nonce: rng.gen(),
+        data: Some(TransactionData::Memo(vec![1u8; 64])),
+    };
+    let transaction: Transaction = MoonlightTransaction::sign_expiring_payload(
+        &sender_sk,
+        payload.clone(),
+        1000,
+    )?
+    .into();
+
+    let transaction_bytes = transaction.to_var_bytes();
+    let deserialized = Transaction::from_slice(&transaction_bytes)?;
+
+    assert_eq!(transaction, deserialized);
+    assert_eq!(deserialized.expiry_height(), Some(1000));
+    assert!(!deserialized.is_expired_at(1000));
+    assert!(deserialized.is_expired_at(1001));
+
+    // the expiry height is signed along with the payload
+    let expiring = MoonlightTransaction::sign_expiring_payload(
+        &sender_sk,
+        payload.clone(),
+        1000,
+    )?;
+    let unexpiring =
+        MoonlightTransaction::sign_payload(&sender_sk, payload.clone())?;
+    assert_ne!(expiring.signature_message(), unexpiring.signature_message());
+
+    // and follows the sponsor of a sponsored transaction
+    let sponsor_sk = AccountSecretKey::random(&mut rng);
+    let transaction: Transaction =
+        MoonlightTransaction::sign_expiring_payload(&sender_sk, payload, 1000)?
+            .sponsored(&sponsor_sk)
+            .into();
+
+    let transaction_bytes = transaction.to_var_bytes();
+    let deserialized = Transaction::from_slice(&transaction_bytes)?;
+
+    assert_eq!(transaction, deserialized);
+    assert_eq!(deserialized.expiry_height(), Some(1000));
+
+    Ok(())
+}
+
 #[test]
 fn moonlight_with_call() -> Result<(), Error> {
     let mut rng = StdRng::seed_from_u64(42);
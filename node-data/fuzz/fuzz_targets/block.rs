@@ -0,0 +1,16 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use node_data::ledger::Block;
+use node_data::Serializable;
+
+// Decodes arbitrary blobs as the node does for the blocks in its database
+fuzz_target!(|data: &[u8]| {
+    let _ = Block::read(&mut &data[..]);
+});
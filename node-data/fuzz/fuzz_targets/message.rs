@@ -0,0 +1,18 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use node_data::message::Message;
+
+// Decodes arbitrary payloads as the node does for the Kadcast messages
+/// Above the size of the largest block
+const MAX_MESSAGE_SIZE: usize = 2 * 1024 * 1024;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Message::from_wire(data, MAX_MESSAGE_SIZE);
+});
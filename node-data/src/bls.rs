@@ -7,7 +7,7 @@
 use std::cmp::Ordering;
 use std::fmt::Debug;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use aes::Aes256;
 use base64::engine::general_purpose::STANDARD as BASE64_ENGINE;
@@ -19,8 +19,8 @@ use dusk_core::signatures::bls::{
     PublicKey as BlsPublicKey, SecretKey as BlsSecretKey,
 };
 use rand::rngs::StdRng;
-use rand::SeedableRng;
-use serde::Serialize;
+use rand::{RngCore, SeedableRng};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use sha2::{Digest, Sha256};
 use tracing::warn;
 pub const PUBLIC_BLS_SIZE: usize = BlsPublicKey::SIZE;
@@ -105,10 +105,31 @@ impl std::fmt::Debug for PublicKey {
         f.debug_struct("PublicKey").field("bs58", &bs).finish()
     }
 }
+
+impl Serialize for PublicKey {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.as_bytes.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PublicKey {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let bytes = PublicKeyBytes::deserialize(deserializer)?;
+        PublicKey::try_from(bytes.0).map_err(|e| {
+            serde::de::Error::custom(format!("Invalid public key: {e:?}"))
+        })
+    }
+}
+
 /// A wrapper of 96-sized array
-#[derive(Clone, Copy, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Copy, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub struct PublicKeyBytes(
-    #[serde(serialize_with = "crate::serialize_b58")] pub [u8; PUBLIC_BLS_SIZE],
+    #[serde(with = "crate::b58_bytes")] pub [u8; PUBLIC_BLS_SIZE],
 );
 
 impl Default for PublicKeyBytes {
@@ -152,13 +173,47 @@ pub fn load_keys(
     Ok((sk, PublicKey::new(pk)))
 }
 
+/// Stores consensus keys into an encrypted file readable by [`load_keys`].
+///
+/// The `rng` is used to generate the encryption IV.
+pub fn save_keys<R: RngCore>(
+    path: &Path,
+    sk: &BlsSecretKey,
+    pk: &BlsPublicKey,
+    pwd: &str,
+    rng: &mut R,
+) -> anyhow::Result<()> {
+    type Aes256Cbc = Cbc<Aes256, Pkcs7>;
+
+    let json = serde_json::json!({
+        "secret_key_bls": BASE64_ENGINE.encode(sk.to_bytes()),
+        "public_key_bls": BASE64_ENGINE.encode(pk.to_bytes()),
+    });
+
+    let mut hasher = Sha256::new();
+    hasher.update(pwd.as_bytes());
+    let hashed_pwd = hasher.finalize().to_vec();
+
+    let mut iv = [0u8; 16];
+    rng.fill_bytes(&mut iv);
+
+    let cipher = Aes256Cbc::new_from_slices(&hashed_pwd, &iv)
+        .map_err(|e| anyhow::anyhow!("Invalid encryption key {e}"))?;
+    let enc = cipher.encrypt_vec(json.to_string().as_bytes());
+
+    let ciphertext: Vec<u8> = iv.into_iter().chain(enc).collect();
+    fs::write(path, ciphertext).map_err(|e| {
+        anyhow::anyhow!("{} should be writable {e}", path.display())
+    })?;
+
+    Ok(())
+}
+
 /// Fetches BLS public and secret keys from an encrypted consensus keys file.
 fn read_from_file(
     path: PathBuf,
     pwd: &str,
 ) -> anyhow::Result<(BlsPublicKey, BlsSecretKey)> {
-    use serde::Deserialize;
-
     /// Bls key pair helper structure
     #[derive(Deserialize)]
     struct BlsKeyPair {
@@ -167,7 +222,6 @@ fn read_from_file(
     }
 
     // attempt to load and decode wallet
-    println!("{path:?}");
     let ciphertext = fs::read(&path).map_err(|e| {
         anyhow::anyhow!(
             "{} should be valid consensus keys file {e}",
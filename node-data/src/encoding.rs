@@ -10,8 +10,8 @@ use dusk_core::transfer::Transaction as ProtocolTransaction;
 
 use crate::bls::PublicKeyBytes;
 use crate::ledger::{
-    Attestation, Block, Fault, Header, IterationsInfo, Label, Signature,
-    SpentTransaction, StepVotes, Transaction,
+    Attestation, Block, CallEdge, Fault, Header, IterationsInfo, Label,
+    Signature, SpentTransaction, StepVotes, Transaction,
 };
 use crate::message::payload::{
     QuorumType, Ratification, RatificationResult, ValidationQuorum,
@@ -22,6 +22,9 @@ use crate::message::{
 };
 use crate::Serializable;
 
+/// Maximum number of calls between contracts decoded for a transaction.
+const MAX_CALL_EDGES: usize = 100_000;
+
 impl Serializable for Block {
     fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
         self.header().write(w)?;
@@ -116,10 +119,17 @@ impl Serializable for SpentTransaction {
                 w.write_all(b)?;
             }
             None => {
-                w.write_all(&0_u64.to_le_bytes())?;
+                w.write_all(&0_u32.to_le_bytes())?;
             }
         }
 
+        w.write_all(&(self.call_graph.len() as u32).to_le_bytes())?;
+        for edge in &self.call_graph {
+            w.write_all(&edge.caller)?;
+            w.write_all(&edge.callee)?;
+            w.write_all(&edge.gas_spent.to_le_bytes())?;
+        }
+
         Ok(())
     }
 
@@ -131,42 +141,84 @@ impl Serializable for SpentTransaction {
 
         let block_height = Self::read_u64_le(r)?;
         let gas_spent = Self::read_u64_le(r)?;
-        let error_len = Self::read_u32_le(r)?;
+        let buf = Self::read_var_le_bytes32(r)?;
 
-        let err = if error_len > 0 {
-            let mut buf = vec![0u8; error_len as usize];
-            r.read_exact(&mut buf[..])?;
-
-            Some(String::from_utf8(buf).expect("Cannot from_utf8"))
+        let err = if !buf.is_empty() {
+            let err = String::from_utf8(buf)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            Some(err)
         } else {
             None
         };
 
+        // Transactions stored before the call graph was recorded end with
+        // the error, while a length cut short is a truncated one
+        let mut len = vec![];
+        (&mut *r).take(4).read_to_end(&mut len)?;
+        let edges_len = match len.len() {
+            0 => 0,
+            4 => Self::read_len_u32(&mut &len[..], MAX_CALL_EDGES)?,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "truncated call graph length",
+                ))
+            }
+        };
+        let mut call_graph = Vec::with_capacity(edges_len);
+        for _ in 0..edges_len {
+            call_graph.push(CallEdge {
+                caller: Self::read_bytes(r)?,
+                callee: Self::read_bytes(r)?,
+                gas_spent: Self::read_u64_le(r)?,
+            });
+        }
+
         Ok(Self {
             inner,
             block_height,
             gas_spent,
             err,
+            call_graph,
         })
     }
 }
 
 impl Serializable for Header {
     fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
-        self.marshal_hashable(w)?;
-        self.att.write(w)?;
-        w.write_all(&self.hash)?;
-        w.write_all(self.signature.inner())?;
-
-        Ok(())
+        self.write_votes(w, false)
     }
 
     fn read<R: Read>(r: &mut R) -> io::Result<Self>
     where
         Self: Sized,
     {
-        let mut header = Self::unmarshal_hashable(r)?;
-        header.att = Attestation::read(r)?;
+        Self::read_votes(r, false)
+    }
+}
+
+impl Header {
+    /// Writes the header, the votes of its attestations being encoded
+    /// compactly if `compact` is set.
+    ///
+    /// The hash is written along, as it is computed on the votes in their
+    /// full encoding.
+    pub fn write_votes<W: Write>(
+        &self,
+        w: &mut W,
+        compact: bool,
+    ) -> io::Result<()> {
+        self.marshal_fields(w, compact)?;
+        self.att.write_votes(w, compact)?;
+        w.write_all(&self.hash)?;
+        w.write_all(self.signature.inner())?;
+
+        Ok(())
+    }
+
+    pub fn read_votes<R: Read>(r: &mut R, compact: bool) -> io::Result<Self> {
+        let mut header = Self::unmarshal_fields(r, compact)?;
+        header.att = Attestation::read_votes(r, compact)?;
         header.hash = Self::read_bytes(r)?;
         header.signature = Signature::from(Self::read_bytes(r)?);
         Ok(header)
@@ -175,20 +227,39 @@ impl Serializable for Header {
 
 impl Serializable for Attestation {
     fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
-        self.result.write(w)?;
-        self.validation.write(w)?;
-        self.ratification.write(w)?;
-
-        Ok(())
+        self.write_votes(w, false)
     }
 
     fn read<R: Read>(r: &mut R) -> io::Result<Self>
     where
         Self: Sized,
     {
+        Self::read_votes(r, false)
+    }
+}
+
+impl Attestation {
+    /// Writes the attestation, with its step votes encoded compactly if
+    /// `compact` is set.
+    pub(crate) fn write_votes<W: Write>(
+        &self,
+        w: &mut W,
+        compact: bool,
+    ) -> io::Result<()> {
+        self.result.write(w)?;
+        self.validation.write_votes(w, compact)?;
+        self.ratification.write_votes(w, compact)?;
+
+        Ok(())
+    }
+
+    pub(crate) fn read_votes<R: Read>(
+        r: &mut R,
+        compact: bool,
+    ) -> io::Result<Self> {
         let result = RatificationResult::read(r)?;
-        let validation = StepVotes::read(r)?;
-        let ratification = StepVotes::read(r)?;
+        let validation = StepVotes::read_votes(r, compact)?;
+        let ratification = StepVotes::read_votes(r, compact)?;
 
         Ok(Attestation {
             result,
@@ -200,22 +271,49 @@ impl Serializable for Attestation {
 
 impl Serializable for StepVotes {
     fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
-        w.write_all(&self.bitset.to_le_bytes())?;
-        w.write_all(self.aggregate_signature.inner())?;
-
-        Ok(())
+        self.write_votes(w, false)
     }
 
     fn read<R: Read>(r: &mut R) -> io::Result<Self>
     where
         Self: Sized,
     {
+        Self::read_votes(r, false)
+    }
+}
+
+impl StepVotes {
+    /// Writes the bitset of the voters followed by their aggregated
+    /// signature.
+    ///
+    /// If `compact` is set, the signature is left out when nobody voted,
+    /// as it is then empty.
+    pub(crate) fn write_votes<W: Write>(
+        &self,
+        w: &mut W,
+        compact: bool,
+    ) -> io::Result<()> {
+        w.write_all(&self.bitset.to_le_bytes())?;
+        if !compact || self.bitset != 0 {
+            w.write_all(self.aggregate_signature.inner())?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn read_votes<R: Read>(
+        r: &mut R,
+        compact: bool,
+    ) -> io::Result<Self> {
         let bitset = Self::read_u64_le(r)?;
-        let aggregate_signature = Self::read_bytes(r)?;
+        let aggregate_signature = match (compact, bitset) {
+            (true, 0) => Signature::default(),
+            _ => Self::read_bytes(r)?.into(),
+        };
 
         Ok(StepVotes {
             bitset,
-            aggregate_signature: aggregate_signature.into(),
+            aggregate_signature,
         })
     }
 }
@@ -261,6 +359,23 @@ impl Serializable for RatificationResult {
 
 impl Serializable for IterationsInfo {
     fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        self.write_votes(w, false)
+    }
+
+    fn read<R: Read>(r: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::read_votes(r, false)
+    }
+}
+
+impl IterationsInfo {
+    pub(crate) fn write_votes<W: Write>(
+        &self,
+        w: &mut W,
+        compact: bool,
+    ) -> io::Result<()> {
         let count = self.att_list.len() as u8;
         w.write_all(&count.to_le_bytes())?;
 
@@ -268,7 +383,7 @@ impl Serializable for IterationsInfo {
             match iter {
                 Some((att, pk)) => {
                     w.write_all(&[1])?;
-                    att.write(w)?;
+                    att.write_votes(w, compact)?;
                     w.write_all(pk.inner())?;
                 }
                 None => w.write_all(&[0])?,
@@ -278,10 +393,10 @@ impl Serializable for IterationsInfo {
         Ok(())
     }
 
-    fn read<R: Read>(r: &mut R) -> io::Result<Self>
-    where
-        Self: Sized,
-    {
+    pub(crate) fn read_votes<R: Read>(
+        r: &mut R,
+        compact: bool,
+    ) -> io::Result<Self> {
         let mut att_list = vec![];
 
         let count = Self::read_u8(r)?;
@@ -300,7 +415,7 @@ impl Serializable for IterationsInfo {
             let att = match opt {
                 0 => None,
                 1 => {
-                    let att = Attestation::read(r)?;
+                    let att = Attestation::read_votes(r, compact)?;
                     let pk = Self::read_bytes(r)?;
                     Some((att, PublicKeyBytes(pk)))
                 }
@@ -364,24 +479,41 @@ impl Serializable for Label {
 
 impl Serializable for Ratification {
     fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        self.write_votes(w, false)
+    }
+
+    fn read<R: Read>(r: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::read_votes(r, false)
+    }
+}
+
+impl Ratification {
+    pub(crate) fn write_votes<W: Write>(
+        &self,
+        w: &mut W,
+        compact: bool,
+    ) -> io::Result<()> {
         self.header.write(w)?;
         self.vote.write(w)?;
         w.write_all(&self.timestamp.to_le_bytes())?;
-        self.validation_result.write(w)?;
+        self.validation_result.write_votes(w, compact)?;
         // sign_info at the end
         self.sign_info.write(w)?;
 
         Ok(())
     }
 
-    fn read<R: Read>(r: &mut R) -> io::Result<Self>
-    where
-        Self: Sized,
-    {
+    pub(crate) fn read_votes<R: Read>(
+        r: &mut R,
+        compact: bool,
+    ) -> io::Result<Self> {
         let header = ConsensusHeader::read(r)?;
         let vote = Vote::read(r)?;
         let timestamp = Self::read_u64_le(r)?;
-        let validation_result = ValidationResult::read(r)?;
+        let validation_result = ValidationResult::read_votes(r, compact)?;
         let sign_info = SignInfo::read(r)?;
 
         Ok(Ratification {
@@ -396,18 +528,35 @@ impl Serializable for Ratification {
 
 impl Serializable for ValidationResult {
     fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
-        self.sv.write(w)?;
-        self.vote.write(w)?;
-        self.quorum.write(w)?;
-
-        Ok(())
+        self.write_votes(w, false)
     }
 
     fn read<R: Read>(r: &mut R) -> io::Result<Self>
     where
         Self: Sized,
     {
-        let sv = StepVotes::read(r)?;
+        Self::read_votes(r, false)
+    }
+}
+
+impl ValidationResult {
+    pub(crate) fn write_votes<W: Write>(
+        &self,
+        w: &mut W,
+        compact: bool,
+    ) -> io::Result<()> {
+        self.sv.write_votes(w, compact)?;
+        self.vote.write(w)?;
+        self.quorum.write(w)?;
+
+        Ok(())
+    }
+
+    pub(crate) fn read_votes<R: Read>(
+        r: &mut R,
+        compact: bool,
+    ) -> io::Result<Self> {
+        let sv = StepVotes::read_votes(r, compact)?;
         let vote = Vote::read(r)?;
         let quorum = QuorumType::read(r)?;
 
@@ -417,18 +566,35 @@ impl Serializable for ValidationResult {
 
 impl Serializable for ValidationQuorum {
     fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
-        self.header.write(w)?;
-        self.result.write(w)?;
-
-        Ok(())
+        self.write_votes(w, false)
     }
 
     fn read<R: Read>(r: &mut R) -> io::Result<Self>
     where
         Self: Sized,
     {
+        Self::read_votes(r, false)
+    }
+}
+
+impl ValidationQuorum {
+    pub(crate) fn write_votes<W: Write>(
+        &self,
+        w: &mut W,
+        compact: bool,
+    ) -> io::Result<()> {
+        self.header.write(w)?;
+        self.result.write_votes(w, compact)?;
+
+        Ok(())
+    }
+
+    pub(crate) fn read_votes<R: Read>(
+        r: &mut R,
+        compact: bool,
+    ) -> io::Result<Self> {
         let header = ConsensusHeader::read(r)?;
-        let result = ValidationResult::read(r)?;
+        let result = ValidationResult::read_votes(r, compact)?;
 
         Ok(ValidationQuorum { header, result })
     }
@@ -451,6 +617,8 @@ impl Serializable for QuorumType {
 #[cfg(test)]
 mod tests {
     use fake::{Dummy, Fake, Faker};
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
 
     use super::*;
     use crate::message::payload::{Candidate, Validation};
@@ -464,6 +632,21 @@ mod tests {
         assert!(obj.eq(&S::read(&mut &buf[..]).expect("should be readable")));
     }
 
+    /// Asserts if a type survives a round trip through its JSON
+    /// representation.
+    fn assert_json<S>()
+    where
+        S: Dummy<Faker> + Eq + Serialize + DeserializeOwned,
+    {
+        let obj: S = Faker.fake();
+        let json = serde_json::to_value(&obj).expect("should serialize");
+        let decoded: S =
+            serde_json::from_value(json.clone()).expect("should deserialize");
+
+        assert!(obj.eq(&decoded));
+        assert_eq!(json, serde_json::to_value(&decoded).unwrap());
+    }
+
     #[test]
     fn test_encoding_iterations_info() {
         assert_serializable::<IterationsInfo>();
@@ -504,6 +687,62 @@ mod tests {
         assert_serializable::<ConsensusHeader>();
     }
 
+    #[test]
+    fn test_encoding_spent_transaction_call_graph() {
+        let tx = SpentTransaction {
+            call_graph: vec![CallEdge {
+                caller: [1; 32],
+                callee: [2; 32],
+                gas_spent: 3,
+            }],
+            ..Faker.fake()
+        };
+        let mut buf = vec![];
+        tx.write(&mut buf).unwrap();
+        assert!(SpentTransaction::read(&mut &buf[..]).unwrap() == tx);
+
+        // Transactions stored before the call graph end before its length
+        let legacy_len = buf.len() - 4 - 72;
+        let legacy = SpentTransaction::read(&mut &buf[..legacy_len]).unwrap();
+        assert!(legacy.call_graph.is_empty());
+        assert!(legacy.inner == tx.inner && legacy.err == tx.err);
+
+        // Truncated transactions are rejected
+        for cut in [1, 4, 40] {
+            let truncated = &buf[..buf.len() - cut];
+            assert!(SpentTransaction::read(&mut &truncated[..]).is_err());
+        }
+        assert!(SpentTransaction::read(&mut &buf[..legacy_len + 2]).is_err());
+    }
+
+    #[test]
+    fn test_encoding_compact_header() {
+        let votes = StepVotes::new([7; 48], 0b101);
+        let att = Attestation {
+            validation: votes,
+            ..Default::default()
+        };
+        let header = Header {
+            att,
+            prev_block_cert: att,
+            failed_iterations: IterationsInfo {
+                att_list: vec![None, Some((att, PublicKeyBytes::default()))],
+            },
+            ..Faker.fake()
+        };
+
+        let mut full = vec![];
+        header.write(&mut full).unwrap();
+        let mut compact = vec![];
+        header.write_votes(&mut compact, true).unwrap();
+
+        // The empty ratification of each attestation is left out
+        assert_eq!(full.len(), compact.len() + 3 * 48);
+        let decoded = Header::read_votes(&mut &compact[..], true).unwrap();
+        assert!(decoded == header);
+        assert!(Header::read(&mut &full[..]).unwrap() == header);
+    }
+
     #[test]
     fn test_encoding_block() {
         assert_serializable::<Block>();
@@ -518,4 +757,49 @@ mod tests {
     fn test_encoding_fault() {
         assert_serializable::<Fault>();
     }
+
+    #[test]
+    fn test_json_attestation() {
+        assert_json::<Attestation>();
+    }
+
+    #[test]
+    fn test_json_ratification_result() {
+        assert_json::<RatificationResult>();
+    }
+
+    #[test]
+    fn test_json_ratification() {
+        assert_json::<Ratification>();
+    }
+
+    #[test]
+    fn test_json_validation() {
+        assert_json::<Validation>();
+    }
+
+    #[test]
+    fn test_json_candidate() {
+        assert_json::<Candidate>();
+    }
+
+    #[test]
+    fn test_json_transaction() {
+        assert_json::<Transaction>();
+    }
+
+    #[test]
+    fn test_json_spent_transaction() {
+        assert_json::<SpentTransaction>();
+    }
+
+    #[test]
+    fn test_json_block() {
+        assert_json::<Block>();
+    }
+
+    #[test]
+    fn test_json_fault() {
+        assert_json::<Fault>();
+    }
 }
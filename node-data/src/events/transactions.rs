@@ -25,6 +25,16 @@ use crate::ledger::{Hash, SpentTransaction, Transaction};
 ///
 ///     A transaction has been included in the mempool.
 ///
+/// - `Rejected(Hash, String)`
+///
+///     A transaction has been refused by the mempool, for the given reason.
+///
+/// - `Evicted(Hash, &'static str)`
+///
+///     A transaction has been evicted from the mempool before being included
+///     in a block, for the given reason. It is always followed by a
+///     `Removed` event of the same transaction.
+///
 /// - `Executed(&'t SpentTransaction)`
 ///
 ///     Denotes that a transaction has been executed into an accepted block.
@@ -38,9 +48,22 @@ use crate::ledger::{Hash, SpentTransaction, Transaction};
 pub enum TransactionEvent<'t> {
     Removed(Hash),
     Included(&'t Transaction),
+    Rejected(Hash, String),
+    Evicted(Hash, &'static str),
     Executed(&'t SpentTransaction),
 }
 
+impl TransactionEvent<'_> {
+    /// Reason of the eviction of an expired transaction
+    pub const EXPIRED: &'static str = "expired";
+    /// Reason of the eviction of a transaction replaced by one spending the
+    /// same inputs with a higher gas price
+    pub const REPLACED: &'static str = "replaced";
+    /// Reason of the eviction of the lowest paying transaction of a full
+    /// mempool
+    pub const MEMPOOL_FULL: &'static str = "mempoolFull";
+}
+
 impl EventSource for TransactionEvent<'_> {
     const COMPONENT: &'static str = "transactions";
 
@@ -49,6 +72,8 @@ impl EventSource for TransactionEvent<'_> {
             Self::Removed(_) => "removed",
             Self::Executed(_) => "executed",
             Self::Included(_) => "included",
+            Self::Rejected(..) => "rejected",
+            Self::Evicted(..) => "evicted",
         }
     }
     fn data(&self) -> Option<serde_json::Value> {
@@ -56,6 +81,12 @@ impl EventSource for TransactionEvent<'_> {
             Self::Removed(_) => None,
             Self::Executed(t) => serde_json::to_value(t).ok(),
             Self::Included(t) => serde_json::to_value(t).ok(),
+            Self::Rejected(_, reason) => {
+                Some(serde_json::json!({ "reason": reason }))
+            }
+            Self::Evicted(_, reason) => {
+                Some(serde_json::json!({ "reason": reason }))
+            }
         }
     }
     fn entity(&self) -> String {
@@ -63,6 +94,8 @@ impl EventSource for TransactionEvent<'_> {
             Self::Removed(hash) => *hash,
             Self::Executed(tx) => tx.inner.id(),
             Self::Included(tx) => tx.id(),
+            Self::Rejected(hash, _) => *hash,
+            Self::Evicted(hash, _) => *hash,
         };
         hex::encode(hash)
     }
@@ -71,6 +104,7 @@ use base64::engine::general_purpose::STANDARD as BASE64_ENGINE;
 use base64::Engine;
 use dusk_bytes::Serializable;
 use dusk_core::transfer::Transaction as ProtocolTransaction;
+use serde::de::{Deserialize, Deserializer};
 use serde::ser::{Serialize, SerializeStruct, Serializer};
 
 impl Serialize for Transaction {
@@ -158,10 +192,36 @@ impl Serialize for Transaction {
 
         state.serialize_field("is_deploy", &tx.deploy().is_some())?;
         state.serialize_field("memo", &tx.memo().map(hex::encode))?;
+
+        let mut raw = vec![];
+        crate::Serializable::write(self, &mut raw)
+            .map_err(serde::ser::Error::custom)?;
+        state.serialize_field("raw", &hex::encode(raw))?;
+
         state.end()
     }
 }
 
+/// A transaction is decoded from its `raw` field, the other ones being a
+/// description of its content.
+impl<'de> Deserialize<'de> for Transaction {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[serde_with::serde_as]
+        #[derive(serde::Deserialize)]
+        struct RawTransaction {
+            #[serde_as(as = "serde_with::hex::Hex")]
+            raw: Vec<u8>,
+        }
+
+        let RawTransaction { raw } = RawTransaction::deserialize(deserializer)?;
+        <Transaction as crate::Serializable>::read(&mut &raw[..])
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 struct Note<'a>(&'a dusk_core::transfer::phoenix::Note);
 
 impl<'a> From<&'a dusk_core::transfer::phoenix::Note> for Note<'a> {
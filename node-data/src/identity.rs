@@ -0,0 +1,167 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Identity of the nodes, authenticating the control messages they send to
+//! their peers.
+//!
+//! The identity is an ed25519 key, unrelated to the consensus keys, so that
+//! any node can have one. A signed message is followed on the wire by the
+//! timestamp of the signature, the public key of its sender and the
+//! signature of the message and the timestamp. The timestamp bounds the time
+//! a captured message can be replayed for.
+
+use std::fmt;
+use std::str::FromStr;
+
+use ed25519_dalek::{
+    Signature, Signer, SigningKey, Verifier, VerifyingKey, PUBLIC_KEY_LENGTH,
+    SECRET_KEY_LENGTH, SIGNATURE_LENGTH,
+};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+use crate::get_current_timestamp;
+
+const TIMESTAMP_SIZE: usize = 8;
+
+/// Size of the trailer following a signed message
+pub const NODE_SIGNATURE_SIZE: usize =
+    TIMESTAMP_SIZE + PUBLIC_KEY_LENGTH + SIGNATURE_LENGTH;
+
+/// Maximum difference, in seconds, between the timestamp of a signature and
+/// the local clock for the signature to be accepted
+pub const MAX_SIGNATURE_SKEW: u64 = 30;
+
+/// Public key identifying a node.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(pub [u8; PUBLIC_KEY_LENGTH]);
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", bs58::encode(self.0).into_string())
+    }
+}
+
+impl FromStr for NodeId {
+    type Err = String;
+
+    /// Parses the base58 encoding of an identity.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = bs58::decode(s)
+            .into_vec()
+            .map_err(|e| format!("Invalid node identity {s}: {e}"))?;
+        let key = bytes
+            .try_into()
+            .map_err(|_| format!("Invalid node identity length {s}"))?;
+        Ok(Self(key))
+    }
+}
+
+impl fmt::Debug for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NodeId({self})")
+    }
+}
+
+/// Key signing the control messages sent by the node.
+#[derive(Clone)]
+pub struct NodeIdentity(SigningKey);
+
+impl NodeIdentity {
+    /// Generates a new random identity.
+    pub fn generate() -> Self {
+        let mut secret = [0u8; SECRET_KEY_LENGTH];
+        StdRng::from_entropy().fill_bytes(&mut secret);
+        Self::from_bytes(&secret)
+    }
+
+    pub fn from_bytes(secret: &[u8; SECRET_KEY_LENGTH]) -> Self {
+        Self(SigningKey::from_bytes(secret))
+    }
+
+    pub fn to_bytes(&self) -> [u8; SECRET_KEY_LENGTH] {
+        self.0.to_bytes()
+    }
+
+    pub fn id(&self) -> NodeId {
+        NodeId(self.0.verifying_key().to_bytes())
+    }
+
+    /// Appends to the encoded message `bytes` the current timestamp, the
+    /// identity of the node and the signature of the message.
+    pub fn sign(&self, bytes: &mut Vec<u8>) {
+        self.sign_at(bytes, get_current_timestamp());
+    }
+
+    /// Appends to the encoded message `bytes` the given `timestamp`, the
+    /// identity of the node and the signature of the message.
+    pub fn sign_at(&self, bytes: &mut Vec<u8>, timestamp: u64) {
+        bytes.extend_from_slice(&timestamp.to_le_bytes());
+        let signature = self.0.sign(bytes);
+        bytes.extend_from_slice(&self.id().0);
+        bytes.extend_from_slice(&signature.to_bytes());
+    }
+}
+
+impl fmt::Debug for NodeIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("NodeIdentity").field(&self.id()).finish()
+    }
+}
+
+/// Splits a signed message into the message, the identity of its sender and
+/// the timestamp of the signature, verifying the signature.
+///
+/// Returns `None` if the trailer is missing or the signature is invalid. The
+/// timestamp is left to the caller to check.
+pub fn verify(bytes: &[u8]) -> Option<(&[u8], NodeId, u64)> {
+    let split = bytes.len().checked_sub(NODE_SIGNATURE_SIZE)?;
+    let signed = &bytes[..split + TIMESTAMP_SIZE];
+    let (msg, timestamp) = signed.split_at(split);
+    let (key, signature) = bytes[signed.len()..].split_at(PUBLIC_KEY_LENGTH);
+
+    let key: [u8; PUBLIC_KEY_LENGTH] = key.try_into().ok()?;
+    let signature = Signature::from_slice(signature).ok()?;
+    VerifyingKey::from_bytes(&key)
+        .ok()?
+        .verify(signed, &signature)
+        .ok()?;
+
+    let timestamp = u64::from_le_bytes(timestamp.try_into().ok()?);
+    Some((msg, NodeId(key), timestamp))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_verify() {
+        let identity = NodeIdentity::from_bytes(&[7; SECRET_KEY_LENGTH]);
+        let mut bytes = b"message".to_vec();
+        identity.sign_at(&mut bytes, 42);
+        assert_eq!(bytes.len(), 7 + NODE_SIGNATURE_SIZE);
+
+        let (msg, id, timestamp) =
+            verify(&bytes).expect("signature to be valid");
+        assert_eq!(msg, b"message");
+        assert_eq!(id, identity.id());
+        assert_eq!(timestamp, 42);
+
+        // Tampering with the timestamp invalidates the signature
+        let mut replayed = bytes.clone();
+        replayed[7] ^= 1;
+        assert!(verify(&replayed).is_none());
+
+        // Tampering with the message invalidates the signature
+        bytes[0] ^= 1;
+        assert!(verify(&bytes).is_none());
+        assert!(verify(&bytes[..NODE_SIGNATURE_SIZE - 1]).is_none());
+
+        let parsed: NodeId = id.to_string().parse().expect("id to parse");
+        assert_eq!(parsed, id);
+    }
+}
@@ -11,7 +11,7 @@ mod block;
 pub use block::*;
 
 mod transaction;
-pub use transaction::{SpendingId, SpentTransaction, Transaction};
+pub use transaction::{CallEdge, SpendingId, SpentTransaction, Transaction};
 
 mod faults;
 pub use faults::{Fault, InvalidFault, Slash, SlashType};
@@ -21,6 +21,12 @@ pub use attestation::{
     Attestation, IterationInfo, IterationsInfo, Signature, StepVotes,
 };
 
+mod light_client;
+pub use light_client::{
+    merkle_path, verify_headers, CommitteeMember, HeaderProof,
+    LightClientError, ReceiptProof, TrustedCommittees,
+};
+
 use std::io::{self, Read, Write};
 
 #[cfg(any(feature = "faker", test))]
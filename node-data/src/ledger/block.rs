@@ -4,6 +4,9 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
 use super::*;
 
 pub type Hash = [u8; 32];
@@ -78,6 +81,47 @@ impl Block {
     }
 }
 
+/// The attestation is not part of the JSON representation of the [`Header`],
+/// so it is carried next to it.
+impl Serialize for Block {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Block", 4)?;
+        state.serialize_field("header", &self.header)?;
+        state.serialize_field("att", &self.header.att)?;
+        state.serialize_field("txs", &self.txs)?;
+        state.serialize_field("faults", &self.faults)?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Block {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct BlockJson {
+            header: Header,
+            #[serde(default)]
+            att: Attestation,
+            txs: Vec<Transaction>,
+            faults: Vec<Fault>,
+        }
+
+        let BlockJson {
+            mut header,
+            att,
+            txs,
+            faults,
+        } = BlockJson::deserialize(deserializer)?;
+        header.att = att;
+
+        Block::new(header, txs, faults).map_err(serde::de::Error::custom)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Label {
     Accepted(u64),
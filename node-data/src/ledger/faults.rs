@@ -9,7 +9,7 @@ use dusk_core::signatures::bls::{
     Error as BlsSigError, MultisigPublicKey as BlsMultisigPublicKey,
     MultisigSignature as BlsMultisigSignature,
 };
-use dusk_core::stake::EPOCH;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 use tracing::error;
 
@@ -19,8 +19,9 @@ use crate::message::payload::{
     Candidate, Ratification, RatificationResult, Validation, Vote,
 };
 use crate::message::{ConsensusHeader, SignInfo, SignedStepMessage};
+use crate::StepName;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(any(feature = "faker", test), derive(fake::Dummy, Eq, PartialEq))]
 pub enum Fault {
     DoubleCandidate(FaultData<Hash>, FaultData<Hash>),
@@ -29,6 +30,24 @@ pub enum Fault {
 }
 
 impl Fault {
+    /// Builds the fault of a provisioner that cast two different votes in
+    /// the same validation step.
+    pub fn double_validation(a: &Validation, b: &Validation) -> Self {
+        Fault::DoubleValidationVote(
+            FaultData::new(a.header, a.sign_info.clone(), a.vote),
+            FaultData::new(b.header, b.sign_info.clone(), b.vote),
+        )
+    }
+
+    /// Builds the fault of a provisioner that cast two different votes in
+    /// the same ratification step.
+    pub fn double_ratification(a: &Ratification, b: &Ratification) -> Self {
+        Fault::DoubleRatificationVote(
+            FaultData::new(a.header, a.sign_info.clone(), a.vote),
+            FaultData::new(b.header, b.sign_info.clone(), b.vote),
+        )
+    }
+
     pub fn size(&self) -> usize {
         // prev_block_hash + round + iter
         const FAULT_CONSENSUS_HEADER_SIZE: usize = 32 + u64::SIZE + u8::SIZE;
@@ -128,7 +147,8 @@ impl Fault {
         }
     }
 
-    fn to_culprit(&self) -> PublicKey {
+    /// Returns the provisioner that signed the faulty messages
+    pub fn to_culprit(&self) -> PublicKey {
         match self {
             Fault::DoubleRatificationVote(a, _)
             | Fault::DoubleValidationVote(a, _) => a.sig.signer.clone(),
@@ -136,6 +156,20 @@ impl Fault {
         }
     }
 
+    /// Returns the consensus header of the faulty messages
+    pub fn header(&self) -> &ConsensusHeader {
+        self.consensus_header().0
+    }
+
+    /// Returns the step the faulty messages were cast in
+    pub fn step(&self) -> StepName {
+        match self {
+            Fault::DoubleCandidate(..) => StepName::Proposal,
+            Fault::DoubleValidationVote(..) => StepName::Validation,
+            Fault::DoubleRatificationVote(..) => StepName::Ratification,
+        }
+    }
+
     /// Get the ConsensusHeader related to the inner FaultDatas
     fn consensus_header(&self) -> (&ConsensusHeader, &ConsensusHeader) {
         match self {
@@ -152,6 +186,7 @@ impl Fault {
     pub fn validate(
         &self,
         current_height: u64,
+        epoch: u64,
     ) -> Result<&ConsensusHeader, InvalidFault> {
         let (h1, h2) = self.consensus_header();
         // Check that both consensus headers are the same
@@ -176,7 +211,7 @@ impl Fault {
         }
 
         // Check that fault is not expired. A fault expires after an epoch
-        if h1.round < current_height.saturating_sub(EPOCH) {
+        if h1.round < current_height.saturating_sub(epoch) {
             return Err(InvalidFault::Expired);
         }
 
@@ -232,6 +267,12 @@ impl Fault {
     }
 }
 
+impl<V> FaultData<V> {
+    fn new(header: ConsensusHeader, sig: SignInfo, data: V) -> Self {
+        Self { header, sig, data }
+    }
+}
+
 impl FaultData<Hash> {
     fn get_signed_data(&self, seed: &[u8]) -> Vec<u8> {
         let mut signable = self.header.signable();
@@ -251,15 +292,62 @@ impl FaultData<Vote> {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(any(feature = "faker", test), derive(fake::Dummy, Eq, PartialEq))]
 #[allow(clippy::large_enum_variant)]
+#[serde(bound(serialize = "V: FaultPayload", deserialize = "V: FaultPayload"))]
 pub struct FaultData<V> {
     header: ConsensusHeader,
     sig: SignInfo,
+    #[serde(
+        serialize_with = "FaultPayload::serialize",
+        deserialize_with = "FaultPayload::deserialize"
+    )]
     data: V,
 }
 
+/// JSON representation of the data signed in a [`FaultData`].
+pub trait FaultPayload: Sized {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>;
+
+    fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error>;
+}
+
+impl FaultPayload for Hash {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        crate::serialize_hex(self, serializer)
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        crate::deserialize_hex(deserializer)
+    }
+}
+
+impl FaultPayload for Vote {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Serialize::serialize(self, serializer)
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        Deserialize::deserialize(deserializer)
+    }
+}
+
 impl<V: Serializable> Serializable for FaultData<V> {
     fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
         self.header.write(w)?;
@@ -403,3 +491,61 @@ impl From<&Fault> for Slash {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dusk_core::signatures::bls::{
+        PublicKey as BlsPublicKey, SecretKey as BlsSecretKey,
+    };
+    use dusk_core::stake::EPOCH;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn validation(sk: &BlsSecretKey, vote: Vote) -> Validation {
+        let pk = BlsPublicKey::from(sk);
+        let mut validation = Validation {
+            header: ConsensusHeader {
+                prev_block_hash: [1; 32],
+                round: 10,
+                iteration: 1,
+            },
+            vote,
+            sign_info: SignInfo::default(),
+        };
+        validation.sign(sk, &pk);
+        validation
+    }
+
+    #[test]
+    fn double_validation() {
+        let sk = BlsSecretKey::random(&mut StdRng::seed_from_u64(0));
+        let a = validation(&sk, Vote::Valid([2; 32]));
+        let b = validation(&sk, Vote::NoCandidate);
+
+        let fault = Fault::double_validation(&a, &b);
+        assert_eq!(fault.validate(10, EPOCH).unwrap(), &a.header);
+        assert_eq!(Slash::from(&fault).provisioner, a.sign_info.signer);
+        assert!(fault.same(&Fault::double_validation(&b, &a)));
+
+        // The same vote cast twice is not a fault
+        let fault = Fault::double_validation(&a, &a);
+        assert!(matches!(
+            fault.validate(10, EPOCH),
+            Err(InvalidFault::Duplicated)
+        ));
+    }
+
+    #[test]
+    fn expiry_follows_the_epoch() {
+        let sk = BlsSecretKey::random(&mut StdRng::seed_from_u64(0));
+        let a = validation(&sk, Vote::Valid([2; 32]));
+        let b = validation(&sk, Vote::NoCandidate);
+        let fault = Fault::double_validation(&a, &b);
+
+        assert!(fault.validate(20, 10).is_ok());
+        assert!(matches!(fault.validate(21, 10), Err(InvalidFault::Expired)));
+        assert!(fault.validate(21, EPOCH).is_ok());
+    }
+}
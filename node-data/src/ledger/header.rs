@@ -4,30 +4,30 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use super::*;
 use crate::message::ConsensusHeader;
 
 pub type Seed = Signature;
-#[derive(Eq, PartialEq, Clone, Serialize)]
+#[derive(Eq, PartialEq, Clone, Serialize, Deserialize)]
 #[cfg_attr(any(feature = "faker", test), derive(Dummy))]
 pub struct Header {
     // Hashable fields
     pub version: u8,
     pub height: u64,
     pub timestamp: u64,
-    #[serde(serialize_with = "crate::serialize_hex")]
+    #[serde(with = "crate::hex_bytes")]
     pub prev_block_hash: Hash,
     pub seed: Seed,
-    #[serde(serialize_with = "crate::serialize_hex")]
+    #[serde(with = "crate::hex_bytes")]
     pub state_hash: Hash,
-    #[serde(serialize_with = "crate::serialize_hex")]
+    #[serde(with = "crate::hex_bytes")]
     pub event_bloom: Bloom,
     pub generator_bls_pubkey: PublicKeyBytes,
-    #[serde(serialize_with = "crate::serialize_hex")]
+    #[serde(with = "crate::hex_bytes")]
     pub txroot: Hash,
-    #[serde(serialize_with = "crate::serialize_hex")]
+    #[serde(with = "crate::hex_bytes")]
     pub faultroot: Hash,
     pub gas_limit: u64,
     #[cfg_attr(any(feature = "faker", test), dummy(faker = "0..50"))]
@@ -36,13 +36,13 @@ pub struct Header {
     pub failed_iterations: IterationsInfo,
 
     // Block hash
-    #[serde(serialize_with = "crate::serialize_hex")]
+    #[serde(with = "crate::hex_bytes")]
     pub hash: Hash,
 
     pub signature: Signature,
 
     // Non-hashable fields
-    #[serde(skip_serializing)]
+    #[serde(skip_serializing, default)]
     pub att: Attestation,
 }
 
@@ -118,6 +118,16 @@ impl Header {
     pub(crate) fn marshal_hashable<W: Write>(
         &self,
         w: &mut W,
+    ) -> io::Result<()> {
+        self.marshal_fields(w, false)
+    }
+
+    /// Marshal hashable fields, the votes of the attestations being
+    /// encoded compactly if `compact` is set.
+    pub(crate) fn marshal_fields<W: Write>(
+        &self,
+        w: &mut W,
+        compact: bool,
     ) -> io::Result<()> {
         w.write_all(&self.version.to_le_bytes())?;
         w.write_all(&self.height.to_le_bytes())?;
@@ -133,13 +143,20 @@ impl Header {
         w.write_all(&self.faultroot)?;
         w.write_all(&self.gas_limit.to_le_bytes())?;
         w.write_all(&self.iteration.to_le_bytes())?;
-        self.prev_block_cert.write(w)?;
-        self.failed_iterations.write(w)?;
+        self.prev_block_cert.write_votes(w, compact)?;
+        self.failed_iterations.write_votes(w, compact)?;
 
         Ok(())
     }
 
     pub(crate) fn unmarshal_hashable<R: Read>(r: &mut R) -> io::Result<Self> {
+        Self::unmarshal_fields(r, false)
+    }
+
+    pub(crate) fn unmarshal_fields<R: Read>(
+        r: &mut R,
+        compact: bool,
+    ) -> io::Result<Self> {
         let version = Self::read_u8(r)?;
         let height = Self::read_u64_le(r)?;
         let timestamp = Self::read_u64_le(r)?;
@@ -154,8 +171,8 @@ impl Header {
         let gas_limit = Self::read_u64_le(r)?;
         let iteration = Self::read_u8(r)?;
 
-        let prev_block_cert = Attestation::read(r)?;
-        let failed_iterations = IterationsInfo::read(r)?;
+        let prev_block_cert = Attestation::read_votes(r, compact)?;
+        let failed_iterations = IterationsInfo::read_votes(r, compact)?;
 
         Ok(Header {
             version,
@@ -0,0 +1,590 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Verification of the chain progression from the block headers alone, for
+//! light clients not downloading the block bodies.
+//!
+//! Every header comes with the attestation of its block and the committees
+//! that voted it. The committees served are only accepted if they are the
+//! ones the client draws itself from [`TrustedCommittees`], the provisioners
+//! it trusts at a checkpoint, and the header chain is verified from a
+//! checkpoint header.
+//!
+//! Transactions are proven to be included in a block by the path from their
+//! digest to the tx root of the header.
+
+use dusk_bytes::Serializable as DuskSerializable;
+use dusk_core::signatures::bls::{
+    Error as BlsSigError, MultisigPublicKey as BlsMultisigPublicKey,
+    MultisigSignature as BlsMultisigSignature, PublicKey as BlsPublicKey,
+};
+use dusk_core::transfer::Transaction as ProtocolTransaction;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::*;
+use crate::message::payload::{
+    Ratification, RatificationResult, Validation, Vote,
+};
+use crate::message::SignedStepMessage;
+use crate::StepName;
+
+/// A member of a voting committee, in the order of the committee bitsets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitteeMember {
+    pub key: PublicKeyBytes,
+    pub credits: usize,
+}
+
+/// The provisioners a light client trusts, drawing the committees expected to
+/// vote the headers.
+///
+/// The provisioners are usually the ones at a trusted checkpoint. Since they
+/// change as stakes become eligible or are withdrawn, the committees drawn
+/// from them only match the ones of the chain within the epoch of the
+/// checkpoint, after which the client must trust the provisioners of a newer
+/// checkpoint.
+pub trait TrustedCommittees {
+    /// Returns the committee of `step` for the iteration `header` was
+    /// produced at, `prev` being its parent.
+    fn committee(
+        &self,
+        prev: &Header,
+        header: &Header,
+        step: StepName,
+    ) -> Vec<CommitteeMember>;
+}
+
+/// A block header along with what is needed to verify its attestation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeaderProof {
+    pub header: Header,
+    pub attestation: Attestation,
+    pub validation_committee: Vec<CommitteeMember>,
+    pub ratification_committee: Vec<CommitteeMember>,
+}
+
+#[derive(Debug, Error)]
+pub enum LightClientError {
+    #[error("Header {0} does not match its hash")]
+    HashMismatch(u64),
+    #[error("Header {0} does not follow the previous one")]
+    PrevHashMismatch(u64),
+    #[error("Header {0} does not follow the checkpoint")]
+    CheckpointMismatch(u64),
+    #[error("Header {0} is not attested as valid")]
+    NotAttested(u64),
+    #[error("Invalid {1:?} committee for header {0}")]
+    InvalidCommittee(u64, StepName),
+    #[error("Untrusted {1:?} committee for header {0}")]
+    UntrustedCommittee(u64, StepName),
+    #[error("{1:?} quorum not reached for header {0}: {2} of {3}")]
+    QuorumNotReached(u64, StepName, usize, usize),
+    #[error("Invalid {1:?} signature for header {0}: {2}")]
+    InvalidSignature(u64, StepName, BlsSigError),
+    #[error("Invalid transaction for header {0}")]
+    InvalidTransaction(u64),
+    #[error("Transaction not included in header {0}")]
+    NotIncluded(u64),
+}
+
+/// Proof that a transaction is included in an attested block.
+///
+/// The proof covers the inclusion only: neither the state root nor any other
+/// field of the header commits to the execution result of the transactions.
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceiptProof {
+    pub header: HeaderProof,
+    /// The header of the parent block, the committees of `header` being
+    /// drawn from its seed
+    pub prev_header: Header,
+    /// The transaction, serialized as in the block
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub transaction: Vec<u8>,
+    /// Position of the transaction in the block
+    pub index: usize,
+    /// Siblings from the transaction digest up to the tx root, `None` where
+    /// the sibling is missing and the node is paired with itself
+    #[serde_as(as = "Vec<Option<serde_with::hex::Hex>>")]
+    pub path: Vec<Option<Hash>>,
+}
+
+impl HeaderProof {
+    /// Verifies that the header is consistent with its hash, follows `prev`,
+    /// and that it has been voted as valid by a quorum of both its
+    /// committees, which must be the ones drawn from `trusted`.
+    ///
+    /// `prev` must have been verified already.
+    pub fn verify(
+        &self,
+        prev: &Header,
+        trusted: &impl TrustedCommittees,
+    ) -> Result<(), LightClientError> {
+        let header = &self.header;
+        let height = header.height;
+
+        if !hash_matches(header) {
+            return Err(LightClientError::HashMismatch(height));
+        }
+        if header.prev_block_hash != prev.hash {
+            return Err(LightClientError::PrevHashMismatch(height));
+        }
+
+        for (step, committee) in [
+            (StepName::Validation, &self.validation_committee),
+            (StepName::Ratification, &self.ratification_committee),
+        ] {
+            if *committee != trusted.committee(prev, header, step) {
+                return Err(LightClientError::UntrustedCommittee(height, step));
+            }
+        }
+
+        let expected = RatificationResult::Success(Vote::Valid(header.hash));
+        if self.attestation.result != expected {
+            return Err(LightClientError::NotAttested(height));
+        }
+
+        self.verify_step(StepName::Validation)?;
+        self.verify_step(StepName::Ratification)
+    }
+
+    fn verify_step(&self, step: StepName) -> Result<(), LightClientError> {
+        let height = self.header.height;
+        let (committee, step_votes, sign_seed) = match step {
+            StepName::Validation => (
+                &self.validation_committee,
+                &self.attestation.validation,
+                Validation::SIGN_SEED,
+            ),
+            _ => (
+                &self.ratification_committee,
+                &self.attestation.ratification,
+                Ratification::SIGN_SEED,
+            ),
+        };
+
+        let invalid_committee =
+            || LightClientError::InvalidCommittee(height, step);
+        if committee.len() > u64::BITS as usize
+            || step_votes.bitset >> committee.len() != 0
+        {
+            return Err(invalid_committee());
+        }
+
+        let mut keys = vec![];
+        let mut total = 0;
+        for (pos, member) in committee.iter().enumerate() {
+            if (step_votes.bitset >> pos) & 1 != 0 {
+                let key = BlsPublicKey::from_bytes(member.key.inner())
+                    .map_err(|_| invalid_committee())?;
+                keys.push(key);
+                total += member.credits;
+            }
+        }
+
+        // A valid vote requires a supermajority of the committee credits
+        let credits: usize = committee.iter().map(|m| m.credits).sum();
+        let quorum = (credits * 2).div_ceil(3);
+        if total < quorum {
+            return Err(LightClientError::QuorumNotReached(
+                height, step, total, quorum,
+            ));
+        }
+
+        let invalid_signature =
+            |e| LightClientError::InvalidSignature(height, step, e);
+        let apk = BlsMultisigPublicKey::aggregate(&keys)
+            .map_err(invalid_signature)?;
+        let signature = BlsMultisigSignature::from_bytes(
+            step_votes.aggregate_signature().inner(),
+        )
+        .map_err(invalid_signature)?;
+
+        let mut msg = self.header.to_consensus_header().signable();
+        msg.extend_from_slice(sign_seed);
+        self.attestation
+            .result
+            .vote()
+            .write(&mut msg)
+            .expect("Writing to vec should succeed");
+        apk.verify(&signature, &msg).map_err(invalid_signature)
+    }
+}
+
+impl ReceiptProof {
+    /// Verifies that the transaction is included in the attested header,
+    /// returning its ID.
+    pub fn verify(
+        &self,
+        trusted: &impl TrustedCommittees,
+    ) -> Result<Hash, LightClientError> {
+        // The parent header is authenticated by the hash the attested header
+        // links to
+        if !hash_matches(&self.prev_header) {
+            return Err(LightClientError::HashMismatch(
+                self.prev_header.height,
+            ));
+        }
+        self.header.verify(&self.prev_header, trusted)?;
+
+        let height = self.header.header.height;
+        let tx = ProtocolTransaction::from_slice(&self.transaction)
+            .map_err(|_| LightClientError::InvalidTransaction(height))?;
+
+        let digest: Hash = sha3::Sha3_256::digest(&self.transaction).into();
+        let root = merkle_path_root(digest, self.index, &self.path);
+        if root != Some(self.header.header.txroot) {
+            return Err(LightClientError::NotIncluded(height));
+        }
+
+        Ok(tx.hash().to_bytes())
+    }
+}
+
+/// Returns true if the hash of `header` is the one it holds.
+fn hash_matches(header: &Header) -> bool {
+    let mut hasher = sha3::Sha3_256::new();
+    header
+        .marshal_hashable(&mut hasher)
+        .expect("Writing to a hasher should succeed");
+    let hash: Hash = hasher.finalize().into();
+    hash == header.hash
+}
+
+/// Returns the path from the leaf at `index` to the root of the tree of
+/// `leaves`, as computed by `dusk_consensus::merkle::merkle_root`.
+///
+/// The tree pairs up the nodes of every level, hashing a node left without
+/// a sibling with itself.
+pub fn merkle_path(leaves: &[Hash], index: usize) -> Option<Vec<Option<Hash>>> {
+    if index >= leaves.len() {
+        return None;
+    }
+
+    let mut path = vec![];
+    let mut level = leaves.to_vec();
+    let mut index = index;
+    while level.len() > 1 {
+        path.push(level.get(index ^ 1).copied());
+        level = level
+            .chunks(2)
+            .map(|pair| aggregate(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+            .collect();
+        index /= 2;
+    }
+
+    Some(path)
+}
+
+/// Computes the root of the tree from a leaf and its path, if the path is
+/// consistent with the position of the leaf.
+fn merkle_path_root(
+    leaf: Hash,
+    index: usize,
+    path: &[Option<Hash>],
+) -> Option<Hash> {
+    let mut node = leaf;
+    let mut index = index;
+    for sibling in path {
+        node = match (index % 2, sibling) {
+            (0, sibling) => aggregate(&node, sibling.as_ref().unwrap_or(&node)),
+            (_, Some(sibling)) => aggregate(sibling, &node),
+            // A right node always has a left sibling
+            (_, None) => return None,
+        };
+        index /= 2;
+    }
+    (index == 0).then_some(node)
+}
+
+fn aggregate(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = sha3::Sha3_256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Verifies a segment of consecutive headers starting right after the
+/// trusted `checkpoint` header, with the committees drawn from `trusted`,
+/// returning the last header.
+///
+/// The last header is the checkpoint to verify the next segment from, which
+/// allows to verify the chain one segment at a time.
+pub fn verify_headers(
+    checkpoint: &Header,
+    proofs: &[HeaderProof],
+    trusted: &impl TrustedCommittees,
+) -> Result<Header, LightClientError> {
+    if !hash_matches(checkpoint) {
+        return Err(LightClientError::HashMismatch(checkpoint.height));
+    }
+
+    if let Some(first) = proofs.first() {
+        if first.header.prev_block_hash != checkpoint.hash {
+            let height = first.header.height;
+            return Err(LightClientError::CheckpointMismatch(height));
+        }
+    }
+
+    let mut prev = checkpoint;
+    for proof in proofs {
+        proof.verify(prev, trusted)?;
+        prev = &proof.header;
+    }
+    Ok(prev.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use dusk_core::signatures::bls::SecretKey as BlsSecretKey;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::message::ConsensusHeader;
+
+    const MEMBERS: u64 = 4;
+
+    fn keys() -> Vec<(BlsSecretKey, BlsPublicKey)> {
+        (0..MEMBERS)
+            .map(|i| {
+                let sk = BlsSecretKey::random(&mut StdRng::seed_from_u64(i));
+                let pk = BlsPublicKey::from(&sk);
+                (sk, pk)
+            })
+            .collect()
+    }
+
+    fn step_votes(
+        keys: &[(BlsSecretKey, BlsPublicKey)],
+        header: &ConsensusHeader,
+        sign_seed: &[u8],
+        vote: &Vote,
+        bitset: u64,
+    ) -> StepVotes {
+        let mut msg = header.signable();
+        msg.extend_from_slice(sign_seed);
+        vote.write(&mut msg).unwrap();
+
+        let signatures: Vec<_> = keys
+            .iter()
+            .enumerate()
+            .filter(|(pos, _)| (bitset >> pos) & 1 != 0)
+            .map(|(_, (sk, pk))| sk.sign_multisig(pk, &msg))
+            .collect();
+        let signature = signatures[0].aggregate(&signatures[1..]);
+        StepVotes::new(signature.to_bytes(), bitset)
+    }
+
+    /// Trusts the committee of the test keys for every step.
+    struct Trusted(Vec<CommitteeMember>);
+
+    impl TrustedCommittees for Trusted {
+        fn committee(
+            &self,
+            _: &Header,
+            _: &Header,
+            _: StepName,
+        ) -> Vec<CommitteeMember> {
+            self.0.clone()
+        }
+    }
+
+    fn committee() -> Vec<CommitteeMember> {
+        keys()
+            .iter()
+            .map(|(_, pk)| CommitteeMember {
+                key: PublicKeyBytes(pk.to_bytes()),
+                credits: 16,
+            })
+            .collect()
+    }
+
+    fn checkpoint() -> Header {
+        let block = Block::new(Header::default(), vec![], vec![]).unwrap();
+        block.header().clone()
+    }
+
+    fn proof(prev: &Header, bitset: u64) -> HeaderProof {
+        let keys = keys();
+        let header = Header {
+            height: prev.height + 1,
+            prev_block_hash: prev.hash,
+            ..Default::default()
+        };
+        let block = Block::new(header, vec![], vec![]).unwrap();
+        let header = block.header().clone();
+
+        let vote = Vote::Valid(header.hash);
+        let consensus_header = header.to_consensus_header();
+        let attestation = Attestation {
+            result: RatificationResult::Success(vote),
+            validation: step_votes(
+                &keys,
+                &consensus_header,
+                Validation::SIGN_SEED,
+                &vote,
+                bitset,
+            ),
+            ratification: step_votes(
+                &keys,
+                &consensus_header,
+                Ratification::SIGN_SEED,
+                &vote,
+                bitset,
+            ),
+        };
+
+        HeaderProof {
+            header,
+            attestation,
+            validation_committee: committee(),
+            ratification_committee: committee(),
+        }
+    }
+
+    #[test]
+    fn verify_header_segment() {
+        let checkpoint = checkpoint();
+        let trusted = Trusted(committee());
+        let first = proof(&checkpoint, 0b0111);
+        let second = proof(&first.header, 0b1111);
+        let last = second.header.clone();
+
+        let segment = [first, second];
+        assert_eq!(
+            verify_headers(&checkpoint, &segment, &trusted).unwrap(),
+            last
+        );
+        assert_eq!(
+            verify_headers(&segment[0].header, &segment[1..], &trusted)
+                .unwrap(),
+            last
+        );
+        assert!(matches!(
+            verify_headers(&last, &segment, &trusted),
+            Err(LightClientError::CheckpointMismatch(1))
+        ));
+        assert!(matches!(
+            verify_headers(
+                &segment[0].header,
+                &[segment[1].clone(), segment[0].clone()],
+                &trusted
+            ),
+            Err(LightClientError::PrevHashMismatch(1))
+        ));
+
+        let mut forged = checkpoint.clone();
+        forged.timestamp += 1;
+        assert!(matches!(
+            verify_headers(&forged, &segment, &trusted),
+            Err(LightClientError::HashMismatch(0))
+        ));
+    }
+
+    #[test]
+    fn reject_untrusted_committees() {
+        let checkpoint = checkpoint();
+        let proof = proof(&checkpoint, 0b1111);
+
+        // Committees served with more credits than the trusted ones
+        let mut trusted = Trusted(committee());
+        trusted.0[0].credits -= 1;
+        assert!(matches!(
+            proof.verify(&checkpoint, &trusted),
+            Err(LightClientError::UntrustedCommittee(
+                1,
+                StepName::Validation
+            ))
+        ));
+
+        // Committees signing with keys of other provisioners
+        let mut trusted = Trusted(committee());
+        trusted.0.swap(0, 1);
+        assert!(matches!(
+            proof.verify(&checkpoint, &trusted),
+            Err(LightClientError::UntrustedCommittee(
+                1,
+                StepName::Validation
+            ))
+        ));
+    }
+
+    #[test]
+    fn merkle_paths() {
+        for len in 1..=9u8 {
+            let leaves: Vec<Hash> = (0..len).map(|i| [i; 32]).collect();
+            let root = merkle_path_root(
+                leaves[0],
+                0,
+                &merkle_path(&leaves, 0).unwrap(),
+            )
+            .unwrap();
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let path = merkle_path(&leaves, index).unwrap();
+                assert_eq!(merkle_path_root(*leaf, index, &path), Some(root));
+
+                let other = (index + 1) % leaves.len();
+                if other != index {
+                    assert_ne!(
+                        merkle_path_root(*leaf, other, &path),
+                        Some(root)
+                    );
+                }
+            }
+            assert!(merkle_path(&leaves, leaves.len()).is_none());
+        }
+    }
+
+    #[test]
+    fn reject_invalid_proofs() {
+        let checkpoint = checkpoint();
+        let trusted = Trusted(committee());
+
+        // Two members out of four are below the supermajority
+        let proof = proof(&checkpoint, 0b0011);
+        assert!(matches!(
+            proof.verify(&checkpoint, &trusted),
+            Err(LightClientError::QuorumNotReached(
+                1,
+                StepName::Validation,
+                32,
+                43
+            ))
+        ));
+
+        let mut tampered = self::proof(&checkpoint, 0b0111);
+        tampered.header.timestamp += 1;
+        assert!(matches!(
+            tampered.verify(&checkpoint, &trusted),
+            Err(LightClientError::HashMismatch(1))
+        ));
+
+        // Signatures of the validation step do not verify for ratification
+        let mut swapped = self::proof(&checkpoint, 0b0111);
+        swapped.attestation.ratification = swapped.attestation.validation;
+        assert!(matches!(
+            swapped.verify(&checkpoint, &trusted),
+            Err(LightClientError::InvalidSignature(
+                1,
+                StepName::Ratification,
+                _
+            ))
+        ));
+
+        let mut short = self::proof(&checkpoint, 0b0111);
+        short.validation_committee.truncate(2);
+        short.ratification_committee.truncate(2);
+        let mut trusted = trusted;
+        trusted.0.truncate(2);
+        assert!(matches!(
+            short.verify(&checkpoint, &trusted),
+            Err(LightClientError::InvalidCommittee(1, StepName::Validation))
+        ));
+    }
+}
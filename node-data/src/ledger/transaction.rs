@@ -9,7 +9,7 @@ use std::io;
 use dusk_bytes::Serializable as DuskSerializable;
 use dusk_core::signatures::bls;
 use dusk_core::transfer::Transaction as ProtocolTransaction;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sha3::Digest;
 
 use crate::Serializable;
@@ -46,12 +46,26 @@ impl From<ProtocolTransaction> for Transaction {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpentTransaction {
     pub inner: Transaction,
     pub block_height: u64,
     pub gas_spent: u64,
     pub err: Option<String>,
+    /// Calls between contracts made while executing the transaction
+    #[serde(default)]
+    pub call_graph: Vec<CallEdge>,
+}
+
+/// A call from a contract to another one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallEdge {
+    #[serde(with = "crate::hex_bytes")]
+    pub caller: [u8; 32],
+    #[serde(with = "crate::hex_bytes")]
+    pub callee: [u8; 32],
+    /// Gas spent by the callee, including the one of its own calls
+    pub gas_spent: u64,
 }
 
 impl Transaction {
@@ -182,6 +196,11 @@ pub mod faker {
                 block_height: 0,
                 gas_spent: 3,
                 err: Some("error".to_string()),
+                call_graph: vec![CallEdge {
+                    caller: [1; 32],
+                    callee: [2; 32],
+                    gas_spent: 2,
+                }],
             }
         }
     }
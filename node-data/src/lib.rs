@@ -11,6 +11,7 @@ pub mod archive;
 pub mod bls;
 pub mod encoding;
 pub mod events;
+pub mod identity;
 pub mod ledger;
 pub mod message;
 
@@ -71,14 +72,32 @@ pub trait Serializable {
     }
 
     /// Reads length-prefixed fields
+    ///
+    /// The buffer only grows as the bytes are actually read, so that a forged
+    /// length cannot make it allocate more than the bytes available.
     fn read_var_le_bytes32<R: Read>(r: &mut R) -> io::Result<Vec<u8>> {
         let len = Self::read_u32_le(r)? as usize;
 
-        let mut buf = vec![0u8; len];
-        r.read_exact(&mut buf)?;
+        let mut buf = vec![];
+        r.take(len as u64).read_to_end(&mut buf)?;
+        if buf.len() != len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
 
         Ok(buf)
     }
+
+    /// Reads a length, or a count of items, failing if it exceeds `max`.
+    fn read_len_u32<R: Read>(r: &mut R, max: usize) -> io::Result<usize> {
+        let len = Self::read_u32_le(r)? as usize;
+        if len > max {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Length {len} exceeds the maximum of {max}"),
+            ));
+        }
+        Ok(len)
+    }
 }
 
 impl<const N: usize> Serializable for [u8; N] {
@@ -116,6 +135,55 @@ where
     serializer.serialize_str(&hex)
 }
 
+/// Serde helpers for byte arrays encoded as hex strings, to be used with
+/// `#[serde(with = "crate::hex_bytes")]`.
+pub mod hex_bytes {
+    pub use super::{
+        deserialize_hex as deserialize, serialize_hex as serialize,
+    };
+}
+
+/// Serde helpers for byte arrays encoded as base58 strings, to be used with
+/// `#[serde(with = "crate::b58_bytes")]`.
+pub mod b58_bytes {
+    pub use super::{
+        deserialize_b58 as deserialize, serialize_b58 as serialize,
+    };
+}
+
+pub fn deserialize_hex<'de, const N: usize, D>(
+    deserializer: D,
+) -> Result<[u8; N], D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let hex: String = serde::Deserialize::deserialize(deserializer)?;
+    let bytes = hex::decode(hex).map_err(serde::de::Error::custom)?;
+    to_array(bytes)
+}
+
+pub fn deserialize_b58<'de, const N: usize, D>(
+    deserializer: D,
+) -> Result<[u8; N], D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let b58: String = serde::Deserialize::deserialize(deserializer)?;
+    let bytes = bs58::decode(b58)
+        .into_vec()
+        .map_err(serde::de::Error::custom)?;
+    to_array(bytes)
+}
+
+fn to_array<const N: usize, E: serde::de::Error>(
+    bytes: Vec<u8>,
+) -> Result<[u8; N], E> {
+    let len = bytes.len();
+    bytes
+        .try_into()
+        .map_err(|_| E::invalid_length(len, &format!("{N} bytes").as_str()))
+}
+
 pub fn get_current_timestamp() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
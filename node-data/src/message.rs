@@ -17,16 +17,28 @@ use dusk_core::signatures::bls::{
     SecretKey as BlsSecretKey,
 };
 use payload::{Nonce, ValidationQuorum};
+use serde::{Deserialize, Serialize};
 use tracing::{error, warn};
 
 use self::payload::{Candidate, Ratification, Validation};
 use crate::bls::PublicKey;
+use crate::identity::{self, NodeId};
 use crate::ledger::{to_str, Hash, Signature};
 use crate::{bls, ledger, Serializable, StepName};
 
 /// Topic field position in the message binary representation
-pub const TOPIC_FIELD_POS: usize = 1 + 2 + 2;
-pub const PROTOCOL_VERSION: Version = Version(1, 0, 0);
+pub const TOPIC_FIELD_POS: usize = 1 + 2 + 2 + 4;
+
+/// Topic field position in the binary representation of the messages older
+/// than [`Capabilities`]
+pub const LEGACY_TOPIC_FIELD_POS: usize = 1 + 2 + 2;
+pub const PROTOCOL_VERSION: Version = Version(1, 1, 0);
+
+/// Oldest protocol version of the messages accepted from the network
+pub const MIN_PROTOCOL_VERSION: Version = Version(1, 0, 0);
+
+/// First protocol version carrying the [`Capabilities`] of the sender
+const CAPABILITIES_VERSION: Version = Version(1, 1, 0);
 
 /// Block version
 pub const BLOCK_HEADER_VERSION: u8 = 1;
@@ -34,10 +46,55 @@ pub const BLOCK_HEADER_VERSION: u8 = 1;
 /// Max value for failed iterations.
 pub const MESSAGE_MAX_FAILED_ITERATIONS: u8 = 8;
 
-#[derive(Debug, Clone)]
+/// Error decoding a message received from the network.
+#[derive(Debug, thiserror::Error)]
+pub enum DecodeError {
+    #[error("Message of {size} bytes exceeds the maximum of {max}")]
+    TooLarge { size: usize, max: usize },
+    #[error("Message truncated")]
+    Truncated,
+    #[error("{0} trailing bytes after the message")]
+    TrailingBytes(usize),
+    #[error("Unsupported protocol version {0}")]
+    UnsupportedVersion(Version),
+    #[error("Unknown topic {0}")]
+    UnknownTopic(u8),
+    #[error("Invalid message: {0}")]
+    Invalid(io::Error),
+    #[error("Invalid signature of the sender identity")]
+    InvalidSignature,
+    #[error("Signature timestamp {0} out of the accepted window")]
+    StaleSignature(u64),
+}
+
+impl From<io::Error> for DecodeError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::UnexpectedEof => Self::Truncated,
+            _ => Self::Invalid(err),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// Represent version (major, minor, patch)
 pub struct Version(pub u8, pub u16, pub u16);
 
+impl Version {
+    /// Returns whether messages of this version can be decoded, which is
+    /// the case for the ones sharing the major version of
+    /// [`PROTOCOL_VERSION`] and not older than [`MIN_PROTOCOL_VERSION`].
+    pub fn is_supported(&self) -> bool {
+        self.0 == PROTOCOL_VERSION.0 && *self >= MIN_PROTOCOL_VERSION
+    }
+
+    /// Returns whether messages of this version carry the [`Capabilities`]
+    /// of their sender.
+    pub fn has_capabilities(&self) -> bool {
+        *self >= CAPABILITIES_VERSION
+    }
+}
+
 impl Default for Version {
     fn default() -> Self {
         PROTOCOL_VERSION
@@ -89,15 +146,93 @@ impl From<Ordering> for Status {
     }
 }
 
+/// Optional features of the protocol a node supports, advertised in every
+/// message it sends so that peers can tell which message formats it
+/// understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Capabilities(u32);
+
+impl Capabilities {
+    pub const EMPTY: Self = Self(0);
+
+    /// Candidates can be received as [`payload::CompactCandidate`]
+    pub const COMPACT_CANDIDATE: Self = Self(1);
+
+    /// Messages signed with the [`identity`](crate::identity) of their
+    /// sender can be received
+    pub const IDENTITY: Self = Self(2);
+
+    /// Set on the messages followed by the signature of their sender, rather
+    /// than advertising a capability
+    pub const SIGNED: Self = Self(4);
+
+    /// The step votes of the quorum artifacts carried by the message leave
+    /// out the aggregated signature when nobody voted
+    pub const COMPACT_VOTES: Self = Self(8);
+
+    /// Capabilities supported by this node
+    pub const LOCAL: Self = Self::COMPACT_CANDIDATE
+        .union(Self::IDENTITY)
+        .union(Self::COMPACT_VOTES);
+
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn union(&self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Returns the capabilities supported by both sides.
+    pub const fn intersection(&self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub const fn difference(&self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self::LOCAL
+    }
+}
+
+impl Serializable for Capabilities {
+    fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&self.0.to_le_bytes())
+    }
+
+    fn read<R: Read>(r: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self(Self::read_u32_le(r)?))
+    }
+}
+
 /// Message definition
 #[derive(Debug, Default, Clone)]
 pub struct Message {
     pub version: Version,
+    pub capabilities: Capabilities,
     topic: Topics,
     pub header: ConsensusHeader,
     pub payload: Payload,
 
     pub metadata: Option<Metadata>,
+
+    /// Identity of the node the message was received from, if signed
+    pub identity: Option<NodeId>,
 }
 
 pub trait WireMessage: Into<Payload> {
@@ -111,6 +246,145 @@ pub trait WireMessage: Into<Payload> {
 }
 
 impl Message {
+    /// Decodes a message received from the network, reading the fields
+    /// straight from `bytes`.
+    ///
+    /// Unlike [`Serializable::read`], the whole message is bounded to
+    /// `max_size` and must be consumed entirely. The counts of items it holds
+    /// are not bounded, the items being decoded as they are read, so the
+    /// limits of the consensus are left to its validation.
+    ///
+    /// The signature following a message flagged as
+    /// [`Capabilities::SIGNED`] is verified, and the flag removed once the
+    /// identity of the sender is set. Signatures whose timestamp is more
+    /// than [`identity::MAX_SIGNATURE_SKEW`] away from the local clock are
+    /// rejected.
+    pub fn from_wire(
+        bytes: &[u8],
+        max_size: usize,
+    ) -> Result<Self, DecodeError> {
+        if bytes.len() > max_size {
+            return Err(DecodeError::TooLarge {
+                size: bytes.len(),
+                max: max_size,
+            });
+        }
+
+        let mut reader = bytes;
+        let (version, capabilities, topic) = Self::read_preamble(&mut reader)?;
+        if !version.is_supported() {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        if Topics::from(topic) == Topics::Unknown {
+            return Err(DecodeError::UnknownTopic(topic));
+        }
+
+        let mut identity = None;
+        if capabilities.contains(Capabilities::SIGNED) {
+            let (msg, id, timestamp) =
+                identity::verify(bytes).ok_or(DecodeError::InvalidSignature)?;
+            let skew = timestamp.abs_diff(crate::get_current_timestamp());
+            if skew > identity::MAX_SIGNATURE_SKEW {
+                return Err(DecodeError::StaleSignature(timestamp));
+            }
+            reader = &msg[bytes.len() - reader.len()..];
+            identity = Some(id);
+        }
+
+        let compact = capabilities.contains(Capabilities::COMPACT_VOTES);
+        let mut msg = Self::read_payload(topic.into(), compact, &mut reader)?;
+        if !reader.is_empty() {
+            return Err(DecodeError::TrailingBytes(reader.len()));
+        }
+        msg.identity = identity;
+
+        let capabilities = capabilities.difference(Capabilities::SIGNED);
+        Ok(msg.with_version(version).with_capabilities(capabilities))
+    }
+
+    /// Returns the topic of an encoded message, wherever the layout of its
+    /// version puts it.
+    pub fn wire_topic(bytes: &[u8]) -> Option<u8> {
+        let mut reader = bytes;
+        Self::read_preamble(&mut reader)
+            .ok()
+            .map(|(_, _, topic)| topic)
+    }
+
+    /// Returns the message as sent to a peer speaking `version` and
+    /// supporting `capabilities`.
+    ///
+    /// Peers older than [`Capabilities`] are sent the legacy layout, the
+    /// others only the capabilities both sides support, for the payload to
+    /// be encoded in a format they understand.
+    pub fn for_peer(
+        &self,
+        version: &Version,
+        capabilities: Capabilities,
+    ) -> Self {
+        if version.has_capabilities() {
+            let negotiated = self.capabilities.intersection(capabilities);
+            self.clone().with_capabilities(negotiated)
+        } else {
+            self.clone()
+                .with_version(MIN_PROTOCOL_VERSION)
+                .with_capabilities(Capabilities::EMPTY)
+        }
+    }
+
+    /// Reads the fields preceding the payload: the version, the
+    /// capabilities and the topic.
+    fn read_preamble<R: Read>(
+        r: &mut R,
+    ) -> io::Result<(Version, Capabilities, u8)> {
+        let version = Version::read(r)?;
+        let capabilities = match version.has_capabilities() {
+            true => Capabilities::read(r)?,
+            false => Capabilities::EMPTY,
+        };
+        let topic = Self::read_u8(r)?;
+        Ok((version, capabilities, topic))
+    }
+
+    /// Reads the payload of a message of the given topic, whose step votes
+    /// are encoded compactly if `compact` is set.
+    fn read_payload<R: Read>(
+        topic: Topics,
+        compact: bool,
+        r: &mut R,
+    ) -> io::Result<Self> {
+        let message: Message = match topic {
+            Topics::Candidate => payload::Candidate::read(r)?.into(),
+            Topics::CompactCandidate => {
+                payload::CompactCandidate::read(r)?.into()
+            }
+            Topics::Validation => payload::Validation::read(r)?.into(),
+            Topics::Ratification => {
+                payload::Ratification::read_votes(r, compact)?.into()
+            }
+            Topics::Quorum => payload::Quorum::read_votes(r, compact)?.into(),
+            Topics::ValidationQuorum => {
+                payload::ValidationQuorum::read_votes(r, compact)?.into()
+            }
+
+            Topics::Block => ledger::Block::read(r)?.into(),
+            Topics::Tx => ledger::Transaction::read(r)?.into(),
+            Topics::Fault => ledger::Fault::read(r)?.into(),
+            Topics::GetResource => payload::GetResource::read(r)?.into(),
+            Topics::GetBlocks => payload::GetBlocks::read(r)?.into(),
+            Topics::GetMempool => payload::GetMempool::read(r)?.into(),
+            Topics::Inv => payload::Inv::read(r)?.into(),
+
+            Topics::Unknown => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Unknown topic",
+                ));
+            }
+        };
+        Ok(message)
+    }
+
     pub fn compare(&self, round: u64, iteration: u8, step: StepName) -> Status {
         self.header
             .round
@@ -173,6 +447,11 @@ impl Message {
         self
     }
 
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
     pub fn is_local(&self) -> bool {
         self.metadata.is_none()
     }
@@ -190,17 +469,25 @@ pub struct Metadata {
 impl Serializable for Message {
     fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
         self.version.write(w)?;
+        if self.version.has_capabilities() {
+            self.capabilities.write(w)?;
+        }
         w.write_all(&[self.topic as u8])?;
 
+        // The legacy layout cannot flag the compact votes
+        let compact = self.version.has_capabilities()
+            && self.capabilities.contains(Capabilities::COMPACT_VOTES);
         match &self.payload {
             Payload::Candidate(p) => p.write(w),
+            Payload::CompactCandidate(p) => p.write(w),
             Payload::Validation(p) => p.write(w),
-            Payload::Ratification(p) => p.write(w),
-            Payload::Quorum(p) => p.write(w),
-            Payload::ValidationQuorum(p) => p.write(w),
+            Payload::Ratification(p) => p.write_votes(w, compact),
+            Payload::Quorum(p) => p.write_votes(w, compact),
+            Payload::ValidationQuorum(p) => p.write_votes(w, compact),
 
             Payload::Block(p) => p.write(w),
             Payload::Transaction(p) => p.write(w),
+            Payload::Fault(p) => p.write(w),
             Payload::GetMempool(p) => p.write(w),
             Payload::Inv(p) => p.write(w),
             Payload::GetBlocks(p) => p.write(w),
@@ -214,35 +501,13 @@ impl Serializable for Message {
     where
         Self: Sized,
     {
-        let version = Version::read(r)?;
-
-        // Read topic
-        let topic = Topics::from(Self::read_u8(r)?);
-        let message: Message = match topic {
-            Topics::Candidate => payload::Candidate::read(r)?.into(),
-            Topics::Validation => payload::Validation::read(r)?.into(),
-            Topics::Ratification => payload::Ratification::read(r)?.into(),
-            Topics::Quorum => payload::Quorum::read(r)?.into(),
-            Topics::ValidationQuorum => {
-                payload::ValidationQuorum::read(r)?.into()
-            }
-
-            Topics::Block => ledger::Block::read(r)?.into(),
-            Topics::Tx => ledger::Transaction::read(r)?.into(),
-            Topics::GetResource => payload::GetResource::read(r)?.into(),
-            Topics::GetBlocks => payload::GetBlocks::read(r)?.into(),
-            Topics::GetMempool => payload::GetMempool::read(r)?.into(),
-            Topics::Inv => payload::Inv::read(r)?.into(),
-
-            Topics::Unknown => {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    "Unknown topic",
-                ));
-            }
-        };
+        let (version, capabilities, topic) = Self::read_preamble(r)?;
+        let compact = capabilities.contains(Capabilities::COMPACT_VOTES);
+        let message = Self::read_payload(topic.into(), compact, r)?;
 
-        Ok(message.with_version(version))
+        Ok(message
+            .with_version(version)
+            .with_capabilities(capabilities))
     }
 }
 
@@ -264,6 +529,17 @@ impl WireMessage for Candidate {
     }
 }
 
+impl WireMessage for payload::CompactCandidate {
+    const TOPIC: Topics = Topics::CompactCandidate;
+    fn consensus_header(&self) -> ConsensusHeader {
+        ConsensusHeader {
+            iteration: self.header.iteration,
+            prev_block_hash: self.header.prev_block_hash,
+            round: self.header.height,
+        }
+    }
+}
+
 impl WireMessage for Validation {
     const TOPIC: Topics = Topics::Validation;
     fn consensus_header(&self) -> ConsensusHeader {
@@ -316,6 +592,10 @@ impl WireMessage for ledger::Transaction {
     const TOPIC: Topics = Topics::Tx;
 }
 
+impl WireMessage for ledger::Fault {
+    const TOPIC: Topics = Topics::Fault;
+}
+
 impl WireMessage for payload::ValidationResult {
     const TOPIC: Topics = Topics::Unknown;
 }
@@ -335,9 +615,10 @@ impl Message {
     }
 }
 
-#[derive(Default, Clone, PartialEq, Eq, Copy)]
+#[derive(Default, Clone, PartialEq, Eq, Copy, Serialize, Deserialize)]
 #[cfg_attr(any(feature = "faker", test), derive(fake::Dummy))]
 pub struct ConsensusHeader {
+    #[serde(with = "crate::hex_bytes")]
     pub prev_block_hash: Hash,
     pub round: u64,
     #[cfg_attr(any(feature = "faker", test), dummy(faker = "0..50"))]
@@ -404,11 +685,15 @@ pub enum Payload {
     Ratification(payload::Ratification),
     Validation(payload::Validation),
     Candidate(Box<payload::Candidate>),
+    CompactCandidate(Box<payload::CompactCandidate>),
     Quorum(payload::Quorum),
     ValidationQuorum(Box<payload::ValidationQuorum>),
 
     Block(Box<ledger::Block>),
     Transaction(Box<ledger::Transaction>),
+    /// Evidence of a provisioner's fault, for any generator to include it in
+    /// a block
+    Fault(Box<ledger::Fault>),
     GetMempool(payload::GetMempool),
     Inv(payload::Inv),
     GetBlocks(payload::GetBlocks),
@@ -438,6 +723,11 @@ impl From<payload::Candidate> for Payload {
         Self::Candidate(Box::new(value))
     }
 }
+impl From<payload::CompactCandidate> for Payload {
+    fn from(value: payload::CompactCandidate) -> Self {
+        Self::CompactCandidate(Box::new(value))
+    }
+}
 impl From<payload::Validation> for Payload {
     fn from(value: payload::Validation) -> Self {
         Self::Validation(value)
@@ -470,6 +760,11 @@ impl From<ledger::Transaction> for Payload {
         Self::Transaction(Box::new(value))
     }
 }
+impl From<ledger::Fault> for Payload {
+    fn from(value: ledger::Fault) -> Self {
+        Self::Fault(Box::new(value))
+    }
+}
 impl From<payload::GetMempool> for Payload {
     fn from(value: payload::GetMempool) -> Self {
         Self::GetMempool(value)
@@ -499,19 +794,23 @@ impl From<payload::ValidationResult> for Payload {
 }
 
 pub mod payload {
+    use std::collections::HashMap;
     use std::fmt;
     use std::io::{self, Read, Write};
     use std::net::{
         IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6,
     };
 
-    use serde::Serialize;
+    use serde::{Deserialize, Deserializer, Serialize};
 
     use super::{ConsensusHeader, SignInfo};
     use crate::ledger::{self, to_str, Attestation, Block, Hash, StepVotes};
     use crate::{get_current_timestamp, Serializable};
 
-    #[derive(Debug, Clone)]
+    /// Maximum number of items decoded in an `Inv`.
+    const MAX_INV_ITEMS: usize = u16::MAX as usize;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     #[cfg_attr(
         any(feature = "faker", test),
         derive(fake::Dummy, Eq, PartialEq)
@@ -524,7 +823,7 @@ pub mod payload {
         pub sign_info: SignInfo,
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     #[cfg_attr(
         any(feature = "faker", test),
         derive(fake::Dummy, Eq, PartialEq)
@@ -536,14 +835,23 @@ pub mod payload {
     }
 
     #[derive(
-        Clone, Copy, Hash, Eq, PartialEq, Default, PartialOrd, Ord, Serialize,
+        Clone,
+        Copy,
+        Hash,
+        Eq,
+        PartialEq,
+        Default,
+        PartialOrd,
+        Ord,
+        Serialize,
+        Deserialize,
     )]
     #[cfg_attr(any(feature = "faker", test), derive(fake::Dummy))]
     #[repr(u8)]
     pub enum Vote {
         NoCandidate = 0,
-        Valid(#[serde(serialize_with = "crate::serialize_hex")] Hash) = 1,
-        Invalid(#[serde(serialize_with = "crate::serialize_hex")] Hash) = 2,
+        Valid(#[serde(with = "crate::hex_bytes")] Hash) = 1,
+        Invalid(#[serde(with = "crate::hex_bytes")] Hash) = 2,
 
         #[default]
         NoQuorum = 3,
@@ -638,7 +946,7 @@ pub mod payload {
         }
     }
 
-    #[derive(Clone)]
+    #[derive(Clone, Serialize, Deserialize)]
     #[cfg_attr(any(feature = "faker", test), derive(fake::Dummy))]
     pub struct Candidate {
         pub candidate: Block,
@@ -682,7 +990,169 @@ pub mod payload {
             Ok(Candidate { candidate })
         }
     }
-    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+
+    /// A candidate block referencing its transactions by id, carrying only
+    /// the bodies the sender assumes its peers are missing.
+    ///
+    /// The receiver rebuilds the [`Candidate`] from its mempool, the
+    /// signature of the generator covering the header only.
+    #[derive(Clone)]
+    #[cfg_attr(any(feature = "faker", test), derive(fake::Dummy))]
+    pub struct CompactCandidate {
+        pub header: ledger::Header,
+        /// Ids of the transactions of the block, in order
+        pub tx_ids: Vec<[u8; 32]>,
+        /// Bodies of the transactions the peers are assumed to be missing
+        pub txs: Vec<ledger::Transaction>,
+        pub faults: Vec<ledger::Fault>,
+    }
+
+    impl CompactCandidate {
+        /// Compacts `candidate`, only carrying the bodies of the transactions
+        /// `is_known` does not expect peers to have.
+        pub fn new<F>(candidate: &Candidate, mut is_known: F) -> Self
+        where
+            F: FnMut(&ledger::Transaction) -> bool,
+        {
+            let block = &candidate.candidate;
+            Self {
+                header: block.header().clone(),
+                tx_ids: block.txs().iter().map(|t| t.id()).collect(),
+                txs: block
+                    .txs()
+                    .iter()
+                    .filter(|t| !is_known(t))
+                    .cloned()
+                    .collect(),
+                faults: block.faults().clone(),
+            }
+        }
+
+        /// Rebuilds the candidate, looking up the transactions not carried
+        /// by the message with `lookup`.
+        ///
+        /// Returns the ids of the transactions that could not be found, if
+        /// any.
+        pub fn reconstruct<F>(
+            &self,
+            mut lookup: F,
+        ) -> Result<Candidate, ReconstructError>
+        where
+            F: FnMut(&[u8; 32]) -> Option<ledger::Transaction>,
+        {
+            let carried: HashMap<_, _> =
+                self.txs.iter().map(|t| (t.id(), t)).collect();
+
+            let mut txs = Vec::with_capacity(self.tx_ids.len());
+            let mut missing = vec![];
+            for id in &self.tx_ids {
+                match carried
+                    .get(id)
+                    .map(|t| (*t).clone())
+                    .or_else(|| lookup(id))
+                {
+                    Some(tx) => txs.push(tx),
+                    None => missing.push(*id),
+                }
+            }
+            if !missing.is_empty() {
+                return Err(ReconstructError::MissingTxs(missing));
+            }
+
+            let candidate =
+                Block::new(self.header.clone(), txs, self.faults.clone())
+                    .map_err(ReconstructError::InvalidBlock)?;
+            Ok(Candidate { candidate })
+        }
+    }
+
+    /// Failure to rebuild the candidate of a [`CompactCandidate`].
+    #[derive(Debug, thiserror::Error)]
+    pub enum ReconstructError {
+        #[error("{} transactions missing", .0.len())]
+        MissingTxs(Vec<[u8; 32]>),
+        #[error("invalid block: {0}")]
+        InvalidBlock(io::Error),
+    }
+
+    impl fmt::Debug for CompactCandidate {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("CompactCandidate")
+                .field("hash", &to_str(&self.header.hash))
+                .field("height", &self.header.height)
+                .field("iteration", &self.header.iteration)
+                .field("tx_ids", &self.tx_ids.len())
+                .field("txs", &self.txs.len())
+                .finish()
+        }
+    }
+
+    impl PartialEq<Self> for CompactCandidate {
+        fn eq(&self, other: &Self) -> bool {
+            self.header.hash == other.header.hash
+                && self.tx_ids == other.tx_ids
+                && self.txs == other.txs
+        }
+    }
+
+    impl Eq for CompactCandidate {}
+
+    impl Serializable for CompactCandidate {
+        fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+            self.header.write(w)?;
+
+            let ids_len = self.tx_ids.len() as u32;
+            w.write_all(&ids_len.to_le_bytes())?;
+            for id in &self.tx_ids {
+                w.write_all(id)?;
+            }
+
+            let txs_len = self.txs.len() as u32;
+            w.write_all(&txs_len.to_le_bytes())?;
+            for t in &self.txs {
+                t.write(w)?;
+            }
+
+            let faults_len = self.faults.len() as u32;
+            w.write_all(&faults_len.to_le_bytes())?;
+            for f in &self.faults {
+                f.write(w)?;
+            }
+            Ok(())
+        }
+
+        fn read<R: Read>(r: &mut R) -> io::Result<Self>
+        where
+            Self: Sized,
+        {
+            let header = ledger::Header::read(r)?;
+
+            let ids_len = Self::read_u32_le(r)? as usize;
+            let tx_ids = (0..ids_len)
+                .map(|_| Self::read_bytes(r))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let txs_len = Self::read_len_u32(r, ids_len)?;
+            let txs = (0..txs_len)
+                .map(|_| ledger::Transaction::read(r))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let faults_len = Self::read_u32_le(r)?;
+            let faults = (0..faults_len)
+                .map(|_| ledger::Fault::read(r))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(Self {
+                header,
+                tx_ids,
+                txs,
+                faults,
+            })
+        }
+    }
+    #[derive(
+        Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+    )]
     #[cfg_attr(any(feature = "faker", test), derive(fake::Dummy))]
     pub enum QuorumType {
         /// Supermajority of Valid votes
@@ -707,7 +1177,7 @@ pub mod payload {
         }
     }
 
-    #[derive(Debug, Clone, Default)]
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
     #[cfg_attr(
         any(feature = "faker", test),
         derive(fake::Dummy, Eq, PartialEq)
@@ -717,7 +1187,7 @@ pub mod payload {
         pub result: ValidationResult,
     }
 
-    #[derive(Debug, Clone, Default)]
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
     #[cfg_attr(
         any(feature = "faker", test),
         derive(fake::Dummy, Eq, PartialEq)
@@ -748,7 +1218,6 @@ pub mod payload {
 
     #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
     #[serde(untagged)]
-    #[cfg_attr(any(feature = "faker", test), derive(fake::Dummy))]
     pub enum RatificationResult {
         Fail(Vote),
         Success(Vote),
@@ -771,6 +1240,27 @@ pub mod payload {
         }
     }
 
+    /// The variant is not part of the JSON representation, so it is derived
+    /// from the vote as done by `From<Vote>`.
+    impl<'de> Deserialize<'de> for RatificationResult {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            Vote::deserialize(deserializer).map(Self::from)
+        }
+    }
+
+    #[cfg(any(feature = "faker", test))]
+    impl<T> fake::Dummy<T> for RatificationResult {
+        fn dummy_with_rng<R: rand::Rng + ?Sized>(
+            _config: &T,
+            rng: &mut R,
+        ) -> Self {
+            let vote: Vote = fake::Fake::fake_with_rng(&fake::Faker, rng);
+            vote.into()
+        }
+    }
+
     impl RatificationResult {
         pub fn vote(&self) -> &Vote {
             match self {
@@ -787,7 +1277,7 @@ pub mod payload {
         }
     }
 
-    #[derive(Debug, Clone, Eq, PartialEq)]
+    #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
     pub struct Quorum {
         pub header: ConsensusHeader,
         pub att: Attestation,
@@ -795,24 +1285,39 @@ pub mod payload {
 
     impl Serializable for Quorum {
         fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
-            self.header.write(w)?;
-            self.att.write(w)?;
-
-            Ok(())
+            self.write_votes(w, false)
         }
 
         fn read<R: Read>(r: &mut R) -> io::Result<Self>
         where
             Self: Sized,
         {
+            Self::read_votes(r, false)
+        }
+    }
+
+    impl Quorum {
+        pub(crate) fn write_votes<W: Write>(
+            &self,
+            w: &mut W,
+            compact: bool,
+        ) -> io::Result<()> {
+            self.header.write(w)?;
+            self.att.write_votes(w, compact)?;
+
+            Ok(())
+        }
+
+        pub(crate) fn read_votes<R: Read>(
+            r: &mut R,
+            compact: bool,
+        ) -> io::Result<Self> {
             let header = ConsensusHeader::read(r)?;
-            let att = Attestation::read(r)?;
+            let att = Attestation::read_votes(r, compact)?;
 
             Ok(Quorum { header, att })
         }
-    }
 
-    impl Quorum {
         pub fn vote(&self) -> &Vote {
             self.att.result.vote()
         }
@@ -1089,7 +1594,7 @@ pub mod payload {
         where
             Self: Sized,
         {
-            let items_len = Self::read_u32_le(r)?;
+            let items_len = Self::read_len_u32(r, MAX_INV_ITEMS)?;
 
             let mut inv = Inv::default();
             for _ in 0..items_len {
@@ -1352,6 +1857,7 @@ pub enum Topics {
     // Fire-and-forget messaging
     Tx = 10,
     Block = 11,
+    Fault = 12,
 
     // Consensus main loop topics
     Candidate = 16,
@@ -1359,6 +1865,7 @@ pub enum Topics {
     Ratification = 18,
     Quorum = 19,
     ValidationQuorum = 20,
+    CompactCandidate = 21,
 
     #[default]
     Unknown = 255,
@@ -1373,6 +1880,7 @@ impl Topics {
                 | Topics::Ratification
                 | Topics::Quorum
                 | Topics::ValidationQuorum
+                | Topics::CompactCandidate
         )
     }
 }
@@ -1390,6 +1898,8 @@ impl From<u8> for Topics {
         map_topic!(v, Topics::Ratification);
         map_topic!(v, Topics::Quorum);
         map_topic!(v, Topics::ValidationQuorum);
+        map_topic!(v, Topics::CompactCandidate);
+        map_topic!(v, Topics::Fault);
 
         Topics::Unknown
     }
@@ -1570,6 +2080,41 @@ impl SignedStepMessage for Candidate {
     }
 }
 
+impl StepMessage for payload::CompactCandidate {
+    const STEP_NAME: StepName = StepName::Proposal;
+
+    fn header(&self) -> ConsensusHeader {
+        ConsensusHeader {
+            iteration: self.header.iteration,
+            prev_block_hash: self.header.prev_block_hash,
+            round: self.header.height,
+        }
+    }
+}
+
+impl SignedStepMessage for payload::CompactCandidate {
+    const SIGN_SEED: &'static [u8] = &[];
+    fn sign_info(&self) -> SignInfo {
+        SignInfo {
+            signer: PublicKey::try_from(self.header.generator_bls_pubkey.0)
+                .unwrap_or_default(),
+            signature: self.header.signature,
+        }
+    }
+    fn sign_info_mut(&mut self) -> &mut SignInfo {
+        panic!("sign_info_mut called on CompactCandidate, this is a bug")
+    }
+    fn signable(&self) -> Vec<u8> {
+        self.header.hash.to_vec()
+    }
+
+    fn sign(&mut self, sk: &BlsSecretKey, pk: &BlsPublicKey) {
+        let msg = self.signable();
+        let signature = sk.sign_multisig(pk, &msg).to_bytes();
+        self.header.signature = signature.into();
+    }
+}
+
 impl StepMessage for ValidationQuorum {
     const STEP_NAME: StepName = StepName::Validation;
 
@@ -1578,7 +2123,7 @@ impl StepMessage for ValidationQuorum {
     }
 }
 
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 #[cfg_attr(any(feature = "faker", test), derive(fake::Dummy, Eq, PartialEq))]
 pub struct SignInfo {
     pub signer: bls::PublicKey,
@@ -1626,6 +2171,8 @@ mod tests {
     use crate::ledger::*;
     use crate::{ledger, Serializable};
 
+    const MAX_SIZE: usize = 1024 * 1024;
+
     #[test]
     fn test_serialize() {
         let consensus_header = ConsensusHeader {
@@ -1707,6 +2254,258 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_compact_candidate() {
+        use fake::{Fake, Faker};
+
+        let block: ledger::Block = Faker.fake();
+        let txs = block.txs().clone();
+        let candidate = payload::Candidate { candidate: block };
+
+        // Peers are assumed to miss the second transaction only
+        let compact = payload::CompactCandidate::new(&candidate, |t| {
+            t.id() != txs[1].id()
+        });
+        assert_eq!(compact.tx_ids.len(), 3);
+        assert_eq!(compact.txs, vec![txs[1].clone()]);
+        assert_serialize(compact.clone());
+
+        let rebuilt = compact
+            .reconstruct(|id| txs.iter().find(|t| t.id() == *id).cloned())
+            .expect("candidate to be rebuilt");
+        assert_eq!(rebuilt, candidate);
+        assert_eq!(rebuilt.candidate.txs(), &txs);
+
+        let missing = compact.reconstruct(|_| None).unwrap_err();
+        assert!(matches!(
+            missing,
+            payload::ReconstructError::MissingTxs(ids)
+                if ids == vec![txs[0].id(), txs[2].id()]
+        ));
+    }
+
+    #[test]
+    fn test_compact_candidate_signature() {
+        use fake::{Fake, Faker};
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let sk = BlsSecretKey::random(&mut StdRng::seed_from_u64(0));
+        let pk = BlsPublicKey::from(&sk);
+
+        let header = ledger::Header {
+            generator_bls_pubkey: bls::PublicKeyBytes(pk.to_bytes()),
+            ..Faker.fake()
+        };
+        let block = ledger::Block::new(header, vec![], vec![]).unwrap();
+        let mut candidate = payload::Candidate { candidate: block };
+        candidate.sign(&sk, &pk);
+
+        let mut compact = payload::CompactCandidate::new(&candidate, |_| true);
+        assert!(compact.verify_signature().is_ok());
+
+        compact.header.hash = [1; 32];
+        assert!(compact.verify_signature().is_err());
+    }
+
+    #[test]
+    fn test_fault() {
+        use fake::{Fake, Faker};
+
+        let fault: ledger::Fault = Faker.fake();
+        let msg = Message::from(fault.clone());
+        assert_eq!(msg.topic(), Topics::Fault);
+        assert!(!msg.topic().is_consensus_msg());
+
+        let mut buf = vec![];
+        msg.write(&mut buf).expect("message to be written");
+        let msg = Message::read(&mut &buf[..]).expect("message to be read");
+        match msg.payload {
+            Payload::Fault(read) => assert_eq!(*read, fault),
+            _ => panic!("payload should be a fault"),
+        }
+    }
+
+    #[test]
+    fn test_from_wire() {
+        let msg = Message::from(payload::GetMempool::default());
+        let mut buf = vec![];
+        msg.write(&mut buf).expect("Writing to vec should succeed");
+
+        assert!(Message::from_wire(&buf, MAX_SIZE).is_ok());
+        assert!(matches!(
+            Message::from_wire(&buf[..buf.len() - 1], MAX_SIZE),
+            Err(DecodeError::Truncated)
+        ));
+
+        let mut trailing = buf.clone();
+        trailing.push(0);
+        assert!(matches!(
+            Message::from_wire(&trailing, MAX_SIZE),
+            Err(DecodeError::TrailingBytes(1))
+        ));
+
+        let oversized = vec![0; MAX_SIZE + 1];
+        assert!(matches!(
+            Message::from_wire(&oversized, MAX_SIZE),
+            Err(DecodeError::TooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_from_wire_signed() {
+        use crate::identity::NodeIdentity;
+
+        let identity = NodeIdentity::from_bytes(&[3; 32]);
+        let msg = Message::from(payload::GetMempool::default());
+        let signed = msg
+            .clone()
+            .with_capabilities(Capabilities::LOCAL.union(Capabilities::SIGNED));
+        let mut buf = vec![];
+        signed.write(&mut buf).unwrap();
+        identity.sign(&mut buf);
+
+        let decoded =
+            Message::from_wire(&buf, MAX_SIZE).expect("to be decoded");
+        assert_eq!(decoded.identity, Some(identity.id()));
+        assert_eq!(decoded.capabilities, Capabilities::LOCAL);
+
+        // A message flagged as signed without valid signature is rejected
+        let last = buf.len() - 1;
+        buf[last] ^= 1;
+        assert!(matches!(
+            Message::from_wire(&buf, MAX_SIZE),
+            Err(DecodeError::InvalidSignature)
+        ));
+
+        // A message signed too long ago is rejected
+        let mut buf = vec![];
+        signed.write(&mut buf).unwrap();
+        let timestamp = crate::get_current_timestamp()
+            - 2 * crate::identity::MAX_SIGNATURE_SKEW;
+        identity.sign_at(&mut buf, timestamp);
+        assert!(matches!(
+            Message::from_wire(&buf, MAX_SIZE),
+            Err(DecodeError::StaleSignature(t)) if t == timestamp
+        ));
+
+        let mut buf = vec![];
+        msg.write(&mut buf).unwrap();
+        let decoded =
+            Message::from_wire(&buf, MAX_SIZE).expect("to be decoded");
+        assert_eq!(decoded.identity, None);
+    }
+
+    #[test]
+    fn test_from_wire_compact_votes() {
+        let sv = StepVotes::new([7; 48], 0b101);
+        let quorum = payload::Quorum {
+            header: ConsensusHeader::default(),
+            att: Attestation {
+                validation: sv,
+                ..Default::default()
+            },
+        };
+        let msg = Message::from(quorum.clone());
+
+        let mut compact = vec![];
+        msg.write(&mut compact).unwrap();
+        let decoded =
+            Message::from_wire(&compact, MAX_SIZE).expect("to be decoded");
+        assert!(matches!(decoded.payload, Payload::Quorum(q) if q == quorum));
+
+        // The legacy encoding carries the empty signature of the
+        // ratification
+        let mut legacy = vec![];
+        msg.with_capabilities(Capabilities::IDENTITY)
+            .write(&mut legacy)
+            .unwrap();
+        assert_eq!(legacy.len(), compact.len() + 48);
+        let decoded =
+            Message::from_wire(&legacy, MAX_SIZE).expect("to be decoded");
+        assert!(matches!(decoded.payload, Payload::Quorum(q) if q == quorum));
+    }
+
+    #[test]
+    fn test_from_wire_versions() {
+        let msg = Message::from(payload::GetMempool::default());
+
+        // Messages of older versions carry no capabilities
+        let mut buf = vec![];
+        let legacy = msg.clone().with_version(MIN_PROTOCOL_VERSION);
+        legacy.write(&mut buf).unwrap();
+        let decoded =
+            Message::from_wire(&buf, MAX_SIZE).expect("legacy to be decoded");
+        assert_eq!(decoded.version, MIN_PROTOCOL_VERSION);
+        assert_eq!(decoded.capabilities, Capabilities::EMPTY);
+
+        let mut buf = vec![];
+        msg.clone()
+            .with_version(Version(2, 0, 0))
+            .write(&mut buf)
+            .unwrap();
+        assert!(matches!(
+            Message::from_wire(&buf, MAX_SIZE),
+            Err(DecodeError::UnsupportedVersion(Version(2, 0, 0)))
+        ));
+
+        let mut buf = vec![];
+        msg.write(&mut buf).unwrap();
+        buf[TOPIC_FIELD_POS] = 42;
+        assert!(matches!(
+            Message::from_wire(&buf, MAX_SIZE),
+            Err(DecodeError::UnknownTopic(42))
+        ));
+        assert_eq!(Message::wire_topic(&buf), Some(42));
+    }
+
+    #[test]
+    fn test_for_peer() {
+        let msg = Message::from(payload::GetMempool::default());
+        let topic = msg.topic() as u8;
+
+        // Legacy peers are sent the layout without capabilities
+        let legacy = msg.for_peer(&MIN_PROTOCOL_VERSION, Capabilities::LOCAL);
+        let mut buf = vec![];
+        legacy.write(&mut buf).unwrap();
+        assert_eq!(buf[LEGACY_TOPIC_FIELD_POS], topic);
+        assert_eq!(Message::wire_topic(&buf), Some(topic));
+        let decoded =
+            Message::from_wire(&buf, MAX_SIZE).expect("legacy to be decoded");
+        assert_eq!(decoded.version, MIN_PROTOCOL_VERSION);
+        assert_eq!(decoded.capabilities, Capabilities::EMPTY);
+
+        // Newer peers are only advertised the shared capabilities
+        let peer =
+            Capabilities::IDENTITY.union(Capabilities::from_bits(1 << 31));
+        let current = msg.for_peer(&PROTOCOL_VERSION, peer);
+        let mut buf = vec![];
+        current.write(&mut buf).unwrap();
+        assert_eq!(buf[TOPIC_FIELD_POS], topic);
+        let decoded =
+            Message::from_wire(&buf, MAX_SIZE).expect("message to be decoded");
+        assert_eq!(decoded.capabilities, Capabilities::IDENTITY);
+    }
+
+    #[test]
+    fn test_from_wire_forged_length() {
+        // A transaction claiming to be 4GiB long is found truncated, without
+        // allocating its claimed length
+        let mut buf = vec![];
+        PROTOCOL_VERSION.write(&mut buf).unwrap();
+        Capabilities::LOCAL.write(&mut buf).unwrap();
+        buf.push(Topics::Tx as u8);
+        buf.extend_from_slice(&1u32.to_le_bytes());
+        buf.extend_from_slice(&1u32.to_le_bytes());
+        buf.extend_from_slice(&u32::MAX.to_le_bytes());
+        buf.extend_from_slice(&[0; 64]);
+
+        assert!(matches!(
+            Message::from_wire(&buf, MAX_SIZE),
+            Err(DecodeError::Truncated)
+        ));
+    }
+
     fn assert_serialize<S: Serializable + PartialEq + core::fmt::Debug>(v: S) {
         let mut buf = vec![];
         assert!(v.write(&mut buf).is_ok());
@@ -5,6 +5,7 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 
 use criterion::async_executor::FuturesExecutor;
@@ -14,6 +15,7 @@ use criterion::{
 };
 use dusk_bytes::Serializable;
 use dusk_consensus::commons::RoundUpdate;
+use dusk_consensus::signer::LocalSigner;
 use dusk_consensus::user::cluster::Cluster;
 use dusk_consensus::user::committee::Committee;
 use dusk_consensus::user::provisioners::Provisioners;
@@ -57,41 +59,49 @@ fn create_step_votes(
 
     let committee = Committee::new(provisioners, &sortition_config);
 
+    let runtime = tokio::runtime::Runtime::new().expect("runtime to start");
     let mut signatures = vec![];
     let mut cluster = Cluster::<node_data::bls::PublicKey>::default();
     for (pk, sk) in keys.iter() {
         if let Some(weight) = committee.votes_for(pk) {
             let vote = vote.clone();
+            let signer = LocalSigner::new(sk.clone(), *pk.inner());
             let ru = RoundUpdate::new(
                 pk.clone(),
-                sk.clone(),
+                Arc::new(signer),
                 tip_header,
                 HashMap::default(),
                 vec![],
             );
-            let sig = match step {
-                StepName::Validation => {
-                    dusk_consensus::build_validation_payload(
-                        vote, &ru, iteration,
-                    )
-                    .sign_info
-                    .signature
-                }
-                StepName::Ratification => {
-                    dusk_consensus::build_ratification_payload(
-                        &ru,
-                        iteration,
-                        &ValidationResult::new(
-                            StepVotes::default(),
-                            vote,
-                            QuorumType::Valid,
-                        ),
-                    )
-                    .sign_info
-                    .signature
+            let sig = runtime.block_on(async {
+                match step {
+                    StepName::Validation => {
+                        dusk_consensus::build_validation_payload(
+                            vote, &ru, iteration,
+                        )
+                        .await
+                        .expect("vote to be signed")
+                        .sign_info
+                        .signature
+                    }
+                    StepName::Ratification => {
+                        dusk_consensus::build_ratification_payload(
+                            &ru,
+                            iteration,
+                            &ValidationResult::new(
+                                StepVotes::default(),
+                                vote,
+                                QuorumType::Valid,
+                            ),
+                        )
+                        .await
+                        .expect("vote to be signed")
+                        .sign_info
+                        .signature
+                    }
+                    _ => unreachable!(),
                 }
-                _ => unreachable!(),
-            };
+            });
             signatures
                 .push(BlsMultisigSignature::from_bytes(sig.inner()).unwrap());
             cluster.add(pk, weight);
@@ -148,6 +148,30 @@ impl Archive {
         Ok(records)
     }
 
+    /// Get the finalized events of a contract emitted by the blocks in the
+    /// range of heights, both included, along with the height of their
+    /// block.
+    pub async fn fetch_finalized_events_from_contract_in_range(
+        &self,
+        contract_id: &str,
+        from_height: i64,
+        to_height: i64,
+    ) -> Result<Vec<data::ArchivedBlockEvent>> {
+        let mut conn = self.sqlite_archive.acquire().await?;
+
+        let records = sqlx::query_as!(
+            data::ArchivedBlockEvent,
+            r#"SELECT block_height, origin, topic, source, data FROM finalized_events WHERE source = ? AND block_height BETWEEN ? AND ? ORDER BY block_height"#,
+            contract_id,
+            from_height,
+            to_height
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(records)
+    }
+
     /// Fetch all unfinalized vm events from a given block hash
     pub async fn fetch_unfinalized_events_by_hash(
         &self,
@@ -448,6 +472,19 @@ mod data {
         pub data: Vec<u8>,
     }
 
+    /// Archived ContractTxEvent, along with the height of the block that
+    /// emitted it.
+    #[serde_with::serde_as]
+    #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+    pub struct ArchivedBlockEvent {
+        pub block_height: i64,
+        pub origin: String,
+        pub topic: String,
+        pub source: String,
+        #[serde_as(as = "serde_with::hex::Hex")]
+        pub data: Vec<u8>,
+    }
+
     impl TryFrom<ArchivedEvent> for ContractTxEvent {
         type Error = anyhow::Error;
 
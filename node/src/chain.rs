@@ -5,13 +5,23 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 mod acceptor;
+mod block_space;
+mod compact;
 mod consensus;
 mod fallback;
+mod fault_pool;
 mod fsm;
+mod gas_limit;
 mod genesis;
+mod journal;
 
 mod header_validation;
 mod metrics;
+mod pipeline;
+mod signer;
+mod snapshots;
+mod upgrades;
+mod uptime;
 
 use std::ops::Deref;
 use std::sync::Arc;
@@ -19,31 +29,48 @@ use std::time::Duration;
 
 use anyhow::Result;
 use async_trait::async_trait;
+pub use block_space::{
+    BlockSpace, BlockSpaceError, BlockSpaceReservation, ProtocolCall,
+};
 use dusk_consensus::config::is_emergency_block;
 use dusk_consensus::errors::ConsensusError;
-pub use header_validation::verify_att;
+use dusk_consensus::sign_guard::SigningGuard;
+pub use gas_limit::{GasLimitAdjustment, GasLimitRule};
+pub use header_validation::{verify_att, AttestationCommittees, ChainRules};
 use node_data::events::Event;
 use node_data::ledger::{to_str, BlockWithLabel, Label};
 use node_data::message::payload::RatificationResult;
 use node_data::message::{AsyncQueue, Payload, Topics};
+pub use signer::RemoteSignerConfig;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::RwLock;
 use tokio::time::{sleep_until, Instant};
 use tracing::{debug, error, info, warn};
+pub use upgrades::{
+    UnsupportedUpgrade, Upgrade, UpgradeSchedule, BLOCK_SPACE, GAS_LIMIT,
+    MEDIAN_TIME_PAST, STAKE_CONFIG, SUPPORTED_UPGRADES, TX_EXPIRY,
+};
 
 use self::acceptor::Acceptor;
 use self::fsm::SimpleFSM;
+#[cfg(feature = "chaos")]
+use crate::chaos::Chaos;
 use crate::database::rocksdb::MD_HASH_KEY;
 use crate::database::{Ledger, Metadata};
+use crate::mempool::policy::BlockExclusions;
+use crate::network::resolve_bootstrapping_nodes;
+use crate::switches::RuntimeSwitches;
 use crate::{database, vm, LongLivedService, Message, Network};
 
 const TOPICS: &[u8] = &[
     Topics::Block as u8,
     Topics::Candidate as u8,
+    Topics::CompactCandidate as u8,
     Topics::Validation as u8,
     Topics::Ratification as u8,
     Topics::Quorum as u8,
     Topics::ValidationQuorum as u8,
+    Topics::Fault as u8,
 ];
 
 const HEARTBEAT_SEC: Duration = Duration::from_secs(3);
@@ -57,6 +84,20 @@ pub struct ChainSrv<N: Network, DB: database::DB, VM: vm::VMExecution> {
     /// Sender channel for sending out RUES events
     event_sender: Sender<Event>,
     genesis_timestamp: u64,
+    switches: Arc<RuntimeSwitches>,
+    /// Transactions the policy of the operator excluded from the generated
+    /// blocks
+    block_exclusions: Option<Arc<BlockExclusions>>,
+    /// Whether the node only observes the chain, without the consensus keys
+    watch_only: bool,
+    /// Signer the consensus signatures are delegated to, if any
+    remote_signer: Option<RemoteSignerConfig>,
+    /// Whether candidates are propagated in compact form
+    compact_candidates: bool,
+    /// Protocol upgrades declared for the network
+    upgrades: Arc<UpgradeSchedule>,
+    #[cfg(feature = "chaos")]
+    chaos: Option<Arc<Chaos>>,
 }
 
 #[async_trait]
@@ -80,8 +121,20 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution>
         let provisioners_list = vm.read().await.get_provisioners(state_hash)?;
 
         // Initialize Acceptor
+        let keys = if self.watch_only {
+            None
+        } else {
+            let remote_signer = self.remote_signer.as_ref();
+            Some(consensus::load_keys(&self.keys_path, remote_signer)?)
+        };
+        let signing_guard = if keys.is_some() {
+            let path = consensus::signing_journal_path(&self.keys_path);
+            Some(Arc::new(SigningGuard::open(path)?))
+        } else {
+            None
+        };
         let acc = Acceptor::init_consensus(
-            &self.keys_path,
+            keys,
             tip,
             provisioners_list,
             db,
@@ -89,8 +142,14 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution>
             vm,
             self.max_consensus_queue_size,
             self.event_sender.clone(),
+            self.switches.clone(),
+            self.block_exclusions.clone(),
+            self.upgrades.clone(),
         )
-        .await?;
+        .await?
+        .with_signing_guard(signing_guard);
+        #[cfg(feature = "chaos")]
+        let acc = acc.with_chaos(self.chaos.clone());
 
         self.acceptor = Some(Arc::new(RwLock::new(acc)));
 
@@ -100,7 +159,7 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution>
     async fn execute(
         &mut self,
         network: Arc<RwLock<N>>,
-        _db: Arc<RwLock<DB>>,
+        db: Arc<RwLock<DB>>,
         _vm: Arc<RwLock<VM>>,
     ) -> anyhow::Result<usize> {
         // Register routes
@@ -122,6 +181,7 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution>
         let result_chan = acc.read().await.get_result_chan().await;
 
         let mut heartbeat = Instant::now().checked_add(HEARTBEAT_SEC).unwrap();
+        let reconstructor = compact::Reconstructor::default();
 
         // Message loop for Chain context
         loop {
@@ -153,6 +213,26 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution>
                             self.reroute_acceptor(msg).await;
                         }
 
+                        Payload::CompactCandidate(_) => {
+                            let acc = self.acceptor.as_ref().expect("initialize is called");
+                            reconstructor.spawn(
+                                acc.clone(),
+                                network.clone(),
+                                db.clone(),
+                                self.inbound.clone(),
+                                msg,
+                            );
+                        }
+
+                        Payload::Fault(ref fault) => {
+                            let acc = self.acceptor.as_ref().expect("initialize is called");
+                            if acc.read().await.add_fault(fault).await {
+                                if let Err(e) = network.read().await.broadcast(&msg).await {
+                                    warn!("Unable to broadcast fault {e}");
+                                }
+                            }
+                        }
+
                         Payload::Quorum(ref q) => {
                             fsm.on_quorum(q, msg.metadata.as_ref()).await;
                             self.reroute_acceptor(msg).await;
@@ -203,7 +283,7 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution>
                 },
                 // Re-routes messages originated from Consensus (upper) layer to the network layer.
                 recv = outbound_chan.recv() => {
-                    let msg = recv?;
+                    let mut msg = recv?;
 
                     // Handle quorum messages from Consensus layer.
                     // If the associated candidate block already exists,
@@ -214,6 +294,12 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution>
                       }
                     }
 
+                    if self.compact_candidates {
+                        if let Some(compact) = compact::compact(&network, &db, &msg).await {
+                            msg = compact;
+                        }
+                    }
+
                     if let Payload::GetResource(res) = &msg.payload {
                         if let Err(e) = network.read().await.flood_request(res.get_inv(), None, 16).await {
                             warn!("Unable to re-route message {e}");
@@ -228,6 +314,16 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution>
                     if let Err(err) = fsm.on_heartbeat_event().await {
                         error!(event = "heartbeat_failed", ?err);
                     }
+                    // The host names are resolved without holding the
+                    // network, which the DNS queries could stall
+                    let seeds = network.read().await.seeds_to_refresh().await;
+                    if let Some(seeds) = seeds {
+                        let network = network.clone();
+                        tokio::spawn(async move {
+                            let nodes = resolve_bootstrapping_nodes(&seeds).await;
+                            network.read().await.contact_bootstrap(nodes).await;
+                        });
+                    }
 
                     heartbeat = Instant::now().checked_add(HEARTBEAT_SEC).unwrap();
                 },
@@ -235,6 +331,20 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution>
         }
     }
 
+    async fn shutdown(
+        &mut self,
+        _network: Arc<RwLock<N>>,
+        _db: Arc<RwLock<DB>>,
+        _vm: Arc<RwLock<VM>>,
+    ) -> anyhow::Result<()> {
+        // Cancel the running consensus step and wait for it to terminate,
+        // so that it does not write to the database while it is flushed.
+        if let Some(acc) = &self.acceptor {
+            acc.read().await.abort_consensus().await;
+        }
+        Ok(())
+    }
+
     /// Returns service name.
     fn name(&self) -> &'static str {
         "chain"
@@ -260,9 +370,72 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution> ChainSrv<N, DB, VM> {
             max_consensus_queue_size: max_inbound_size,
             event_sender,
             genesis_timestamp,
+            switches: Arc::default(),
+            block_exclusions: None,
+            watch_only: false,
+            remote_signer: None,
+            compact_candidates: false,
+            upgrades: Arc::default(),
+            #[cfg(feature = "chaos")]
+            chaos: None,
         }
     }
 
+    /// Shares the runtime switches, to allow turning the block production of
+    /// this provisioner on and off while running.
+    pub fn with_switches(mut self, switches: Arc<RuntimeSwitches>) -> Self {
+        self.switches = switches;
+        self
+    }
+
+    /// Leaves the transactions excluded by the policy of the operator out of
+    /// the blocks generated by this provisioner.
+    pub fn with_block_exclusions(
+        mut self,
+        exclusions: Arc<BlockExclusions>,
+    ) -> Self {
+        self.block_exclusions = Some(exclusions);
+        self
+    }
+
+    /// Runs the node as an observer of the chain, not participating in
+    /// consensus. The consensus keys are not loaded.
+    pub fn with_watch_only(mut self, watch_only: bool) -> Self {
+        self.watch_only = watch_only;
+        self
+    }
+
+    /// Delegates the consensus signatures to a remote signer, the consensus
+    /// keys being loaded only if it falls back to local signing.
+    pub fn with_remote_signer(
+        mut self,
+        remote_signer: Option<RemoteSignerConfig>,
+    ) -> Self {
+        self.remote_signer = remote_signer;
+        self
+    }
+
+    /// Propagates the candidates referencing the transactions by id, so
+    /// that peers rebuild them from their mempool.
+    pub fn with_compact_candidates(mut self, compact: bool) -> Self {
+        self.compact_candidates = compact;
+        self
+    }
+
+    /// Injects the faults of `chaos` into the acceptance of the blocks.
+    #[cfg(feature = "chaos")]
+    pub fn with_chaos(mut self, chaos: Arc<Chaos>) -> Self {
+        self.chaos = Some(chaos);
+        self
+    }
+
+    /// Sets the protocol upgrades declared for the network. The node stops
+    /// at the activation height of an upgrade it does not implement.
+    pub fn with_upgrades(mut self, upgrades: Arc<UpgradeSchedule>) -> Self {
+        self.upgrades = upgrades;
+        self
+    }
+
     /// Load both the chain tip and last finalized block from persisted ledger.
     ///
     /// Panics
@@ -18,12 +18,13 @@ use dusk_consensus::config::{
 };
 use dusk_consensus::errors::{ConsensusError, HeaderError};
 use dusk_consensus::operations::Voter;
+use dusk_consensus::sign_guard::SigningGuard;
 use dusk_consensus::user::provisioners::{ContextProvisioners, Provisioners};
 use dusk_consensus::user::stake::Stake;
 use dusk_core::signatures::bls;
-use dusk_core::stake::{SlashEvent, StakeAmount, StakeEvent};
+use dusk_core::stake::{SlashEvent, StakeConfig, StakeEvent};
 use metrics::{counter, gauge, histogram};
-use node_data::bls::PublicKey;
+use node_data::bls::{PublicKey, PublicKeyBytes};
 use node_data::events::contract::ContractEvent;
 use node_data::events::{BlockEvent, BlockState, Event, TransactionEvent};
 use node_data::ledger::{
@@ -33,18 +34,35 @@ use node_data::message::payload::{GetBlocks, Vote};
 use node_data::message::{AsyncQueue, Payload, Status};
 use node_data::{get_current_timestamp, Serializable, StepName};
 use rkyv::{check_archived_root, Deserialize, Infallible};
+use serde_json::json;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::{RwLock, RwLockReadGuard};
-use tracing::{debug, error, info, trace, warn};
-
-use super::consensus::Task;
-use crate::chain::header_validation::{verify_att, verify_faults, Validator};
+use tracing::{debug, error, info, instrument, trace, warn};
+
+use super::consensus::{ConsensusKeys, Task};
+use super::journal::{self, AcceptIntent};
+use super::pipeline::PreverifiedHeader;
+use super::snapshots;
+use super::upgrades::UpgradeSchedule;
+use super::uptime;
+use super::ChainRules;
+use crate::chain::fault_pool::signed_by_committee;
+use crate::chain::header_validation::{
+    verify_att, verify_faults, AttestationCommittees, Validator,
+};
 use crate::chain::metrics::AverageElapsedTime;
+#[cfg(feature = "chaos")]
+use crate::chaos::{Chaos, ChaosTarget};
 use crate::database::rocksdb::{
-    MD_AVG_PROPOSAL, MD_AVG_RATIFICATION, MD_AVG_VALIDATION, MD_HASH_KEY,
-    MD_STATE_ROOT_KEY,
+    MD_AVG_PROPOSAL, MD_AVG_RATIFICATION, MD_AVG_VALIDATION, MD_DIRTY_KEY,
+    MD_HASH_KEY, MD_STATE_ROOT_KEY,
+};
+use crate::database::{
+    self, AuditEntry, AuditLog, ConsensusStorage, Ledger, Mempool, Metadata,
+    ProvisionersStorage,
 };
-use crate::database::{self, ConsensusStorage, Ledger, Mempool, Metadata};
+use crate::mempool::policy::BlockExclusions;
+use crate::switches::RuntimeSwitches;
 use crate::{vm, Message, Network, DUSK_CONSENSUS_KEY};
 
 const CANDIDATES_DELETION_OFFSET: u64 = 10;
@@ -55,6 +73,15 @@ const OFFSET_FUTURE_MSGS: u64 = 5;
 
 pub type RollingFinalityResult = ([u8; 32], BTreeMap<u64, [u8; 32]>);
 
+/// Outcome of the acceptance of a block
+pub(crate) struct AcceptedBlock {
+    /// The block triggered a rolling finality
+    pub finalized: bool,
+
+    /// The block changed the provisioners
+    pub provisioners_changed: bool,
+}
+
 #[allow(dead_code)]
 pub(crate) enum RevertTarget {
     Commit([u8; 32]),
@@ -80,6 +107,10 @@ pub(crate) struct Acceptor<N: Network, DB: database::DB, VM: vm::VMExecution> {
     pub(crate) network: Arc<RwLock<N>>,
     /// Sender channel for sending out RUES events
     event_sender: Sender<Event>,
+    /// Protocol upgrades declared for the network
+    upgrades: Arc<UpgradeSchedule>,
+    #[cfg(feature = "chaos")]
+    chaos: Option<Arc<Chaos>>,
 }
 
 impl<DB: database::DB, VM: vm::VMExecution, N: Network> Drop
@@ -170,10 +201,10 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
     /// The method loads the VM state and verifies consistency between the VM
     /// and Ledger states. If any inconsistencies are found, it reverts to the
     /// last known finalized state. Finally, it initiates a new consensus
-    /// [Task].
+    /// [Task], which never runs if no `keys` are given.
     #[allow(clippy::too_many_arguments)]
     pub async fn init_consensus(
-        keys_path: &str,
+        keys: Option<ConsensusKeys>,
         tip: BlockWithLabel,
         provisioners_list: Provisioners,
         db: Arc<RwLock<DB>>,
@@ -181,10 +212,24 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
         vm: Arc<RwLock<VM>>,
         max_queue_size: usize,
         event_sender: Sender<Event>,
+        switches: Arc<RuntimeSwitches>,
+        block_exclusions: Option<Arc<BlockExclusions>>,
+        upgrades: Arc<UpgradeSchedule>,
     ) -> anyhow::Result<Self> {
         let tip_height = tip.inner().header().height;
         let tip_state_hash = tip.inner().header().state_hash;
 
+        // A tip past an unsupported upgrade cannot be built upon
+        upgrades.check(tip_height)?;
+        for upgrade in upgrades.upcoming(tip_height) {
+            info!(
+                event = "upcoming protocol upgrade",
+                name = upgrade.name,
+                height = upgrade.height,
+                supported = upgrade.is_supported(),
+            );
+        }
+
         let mut provisioners_list = ContextProvisioners::new(provisioners_list);
 
         if tip.inner().header().height > 0 {
@@ -193,17 +238,40 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
             provisioners_list.apply_changes(changed_provisioners);
         }
 
+        // The provisioners of the first epoch are the ones of the genesis
+        // state, the snapshots of the next ones being stored on acceptance
+        if tip_height == 0 {
+            let genesis =
+                snapshots::snapshot(0, 0, provisioners_list.current());
+            db.read()
+                .await
+                .update(|t| t.store_provisioners_snapshot(&genesis))?;
+        }
+
+        let task = match keys {
+            Some(keys) => Task::new_with_keys(
+                keys,
+                max_queue_size,
+                switches,
+                block_exclusions,
+            ),
+            None => {
+                Task::new_watch_only(max_queue_size, switches, block_exclusions)
+            }
+        }
+        .with_upgrades(upgrades.clone());
+
         let acc = Self {
             tip: RwLock::new(tip),
             provisioners_list: RwLock::new(provisioners_list),
             db: db.clone(),
             vm: vm.clone(),
             network: network.clone(),
-            task: RwLock::new(Task::new_with_keys(
-                keys_path.to_string(),
-                max_queue_size,
-            )?),
+            task: RwLock::new(task),
             event_sender,
+            upgrades,
+            #[cfg(feature = "chaos")]
+            chaos: None,
         };
 
         // NB. After restart, state_root returned by VM is always the last
@@ -234,12 +302,45 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
             }
         }
 
+        let intent = journal::pending(&*db.read().await)?;
+        if let Some(intent) = intent {
+            acc.recover_acceptance(&intent).await?;
+        }
+
+        // The database is dirty if the node did not terminate cleanly, in
+        // which case the tip may not be consistent with the ledger.
+        let dirty = db.read().await.view(|t| t.op_read(MD_DIRTY_KEY))?;
+        if dirty.is_some_and(|v| v == [1]) {
+            warn!(event = "Database not closed cleanly, checking integrity");
+            if !acc.check_tip_integrity().await? {
+                warn!("inconsistent tip, revert to last finalized state");
+                acc.try_revert(RevertTarget::LastFinalizedState).await?;
+            }
+            db.read().await.update(|t| t.op_write(MD_DIRTY_KEY, [0]))?;
+        }
+
         let tip_ts = acc.tip.read().await.inner().header().timestamp;
         Self::init_delay(tip_ts).await;
 
         Ok(acc)
     }
 
+    /// Records the messages signed by the consensus in `guard`, refusing the
+    /// ones conflicting with a message already signed.
+    pub(crate) fn with_signing_guard(
+        mut self,
+        guard: Option<Arc<SigningGuard>>,
+    ) -> Self {
+        self.task.get_mut().signing_guard = guard;
+        self
+    }
+
+    #[cfg(feature = "chaos")]
+    pub(crate) fn with_chaos(mut self, chaos: Option<Arc<Chaos>>) -> Self {
+        self.chaos = chaos;
+        self
+    }
+
     pub async fn init_delay(tip_ts: u64) {
         let spin_time: u64 = env::var("RUSK_CONSENSUS_SPIN_TIME")
             .unwrap_or_default()
@@ -292,6 +393,114 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
         env::remove_var("RUSK_CONSENSUS_SPIN_TIME");
     }
 
+    /// Repairs the acceptance of the block of `intent`, interrupted by a
+    /// crash.
+    ///
+    /// If the block is not in the ledger, only its VM commit may have been
+    /// persisted, and the acceptance is rolled back by reverting the VM to the
+    /// state of the tip, leaving the commit to the garbage collection.
+    /// Otherwise the block is the tip, and the acceptance is rolled forward by
+    /// recording the provisioners snapshot and finalizing the VM state up to
+    /// the last final block, merging all the states finalized since the last
+    /// finalization of the VM.
+    async fn recover_acceptance(&self, intent: &AcceptIntent) -> Result<()> {
+        let stored = journal::is_stored(&*self.db.read().await, intent)?;
+        warn!(
+            event = "Interrupted block acceptance",
+            height = intent.height,
+            hash = hex::encode(intent.hash),
+            prev_state_root = hex::encode(intent.prev_state_root),
+            state_root = hex::encode(intent.state_root),
+            stored,
+        );
+
+        let outcome = if stored {
+            let stake_config = self.vm.read().await.get_stake_config()?;
+            let provisioners_list = self.provisioners_list.read().await;
+            self.db.read().await.update(|db| {
+                snapshots::record(
+                    db,
+                    intent.height,
+                    stake_config.epoch,
+                    provisioners_list.current(),
+                )
+            })?;
+            drop(provisioners_list);
+
+            let last_final = self.get_last_final_block().await?;
+            let final_height = last_final.header().height;
+            let final_root = last_final.header().state_hash;
+
+            let vm = self.vm.read().await;
+            let vm_final_root = vm.get_finalized_state_root()?;
+            if vm_final_root != final_root {
+                let to_merge = journal::finals_to_merge(
+                    &*self.db.read().await,
+                    vm_final_root,
+                    final_height,
+                )?;
+                info!(
+                    event = "Finalizing VM state",
+                    height = final_height,
+                    state_root = hex::encode(final_root),
+                    merged = to_merge.len(),
+                );
+                vm.finalize_state(final_root, to_merge)?;
+            }
+            "rolled_forward"
+        } else {
+            let tip_root = self.tip.read().await.inner().header().state_hash;
+            let vm = self.vm.read().await;
+            if vm.get_state_root()? != tip_root {
+                info!(
+                    event = "Reverting VM state",
+                    state_root = hex::encode(tip_root),
+                );
+                vm.revert(tip_root)?;
+            }
+            "rolled_back"
+        };
+
+        self.db.read().await.update(|db| {
+            let entry = AuditEntry::new(
+                "node",
+                "recover_acceptance",
+                json!({
+                    "height": intent.height,
+                    "hash": hex::encode(intent.hash),
+                    "outcome": outcome,
+                }),
+            );
+            db.append_audit_entry(&entry)
+        })?;
+        journal::complete(&*self.db.read().await)?;
+
+        info!(event = "Block acceptance recovered", outcome);
+        Ok(())
+    }
+
+    /// Checks the tip is indexed by the ledger, together with its state root.
+    async fn check_tip_integrity(&self) -> anyhow::Result<bool> {
+        let tip = self.tip.read().await;
+        let header = tip.inner().header();
+
+        self.db.read().await.view(|t| {
+            let indexed = t
+                .block_label_by_height(header.height)?
+                .is_some_and(|(hash, _)| hash == header.hash);
+            let state_root = t
+                .op_read(MD_STATE_ROOT_KEY)?
+                .is_some_and(|root| root == header.state_hash);
+
+            Ok(indexed && state_root && t.block_exists(&header.hash)?)
+        })
+    }
+
+    /// Aborts the running consensus task and waits for its termination.
+    pub(crate) async fn abort_consensus(&self) {
+        self.task.write().await.abort_with_wait().await;
+    }
+
     pub async fn spawn_task(&self) {
         const REDUNDANCY: usize = 16;
         const WAIT_TIMEOUT: Duration = Duration::from_secs(5);
@@ -337,6 +546,24 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
     }
 
     // Re-route a message to Consensus and Network
+    /// Stores a candidate of the next round, received by a node not running
+    /// consensus.
+    async fn store_observed_candidate(&self, candidate: &Block) {
+        let tip_height = self.tip.read().await.inner().header().height;
+        if candidate.header().height != tip_height + 1 {
+            return;
+        }
+
+        let stored = self
+            .db
+            .read()
+            .await
+            .update(|t| t.store_candidate(candidate.clone()));
+        if let Err(err) = stored {
+            warn!(event = "cannot store candidate", ?err);
+        }
+    }
+
     pub(crate) async fn reroute_msg(
         &self,
         msg: Message,
@@ -348,6 +575,15 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
         }
 
         let consensus_task = self.task.read().await;
+
+        // A node only observing the chain keeps the candidates itself, for
+        // the quorums to find them rather than requesting them
+        if consensus_task.keys.is_none() {
+            if let Payload::Candidate(c) = &msg.payload {
+                self.store_observed_candidate(&c.candidate).await;
+            }
+        }
+
         // If we are syncing our chain, we blindly repropagate everything
         // beacuse we cannot verify any future message but do not want to affect
         // propagation
@@ -509,23 +745,31 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
         Ok(())
     }
 
+    /// Applies the provisioners changes of the stake events.
+    ///
+    /// Returns true if any provisioner or the minimum stake changed
     fn selective_update(
         block_height: u64,
+        stake_config: &StakeConfig,
         stake_events: &[ContractEvent],
         provisioners_list: &mut tokio::sync::RwLockWriteGuard<
             '_,
             ContextProvisioners,
         >,
-    ) -> Result<()> {
+    ) -> Result<bool> {
         let src = "selective";
         let changed_prov: Vec<_> = stake_events
             .iter()
             .filter_map(ProvisionerChange::from_event)
             .collect();
-        if changed_prov.is_empty() {
+        let minimum_changed = provisioners_list.current().minimum_stake()
+            != stake_config.minimum_stake;
+        let changed = !changed_prov.is_empty() || minimum_changed;
+        if !changed {
             provisioners_list.remove_previous();
         } else {
             let mut new_prov = provisioners_list.current().clone();
+            new_prov.set_minimum_stake(stake_config.minimum_stake);
             for change in changed_prov {
                 let account = change.to_public_key();
                 let value = change.value();
@@ -544,13 +788,9 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
                             ),
                             Some(stake) => stake.add(stake_event.value),
                             None => {
-                                let amount = StakeAmount::new(
-                                    stake_event.value,
-                                    block_height,
-                                );
                                 let stake = Stake::new(
-                                    amount.value,
-                                    amount.eligibility,
+                                    stake_event.value,
+                                    stake_config.eligibility(block_height),
                                 );
                                 new_prov.add_member_with_stake(account, stake);
                             }
@@ -578,7 +818,7 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
             // Update new prov
             provisioners_list.update_and_swap(new_prov);
         }
-        Ok(())
+        Ok(changed)
     }
 
     /// Updates tip together with provisioners list.
@@ -657,6 +897,31 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
         blk: &Block,
         enable_consensus: bool,
     ) -> anyhow::Result<bool> {
+        self.accept_block(blk, None, enable_consensus)
+            .await
+            .map(|accepted| accepted.finalized)
+    }
+
+    /// Accepts a block, skipping the verification of its attestations if
+    /// `preverified` on top of the current tip.
+    #[instrument(
+        name = "accept_block",
+        skip_all,
+        fields(height = blk.header().height, iter = blk.header().iteration)
+    )]
+    pub(crate) async fn accept_block(
+        &mut self,
+        blk: &Block,
+        preverified: Option<PreverifiedHeader>,
+        enable_consensus: bool,
+    ) -> anyhow::Result<AcceptedBlock> {
+        // A block past the activation of an unsupported upgrade follows rules
+        // this node does not implement
+        if let Err(err) = self.upgrades.check(blk.header().height) {
+            error!(event = "block refused", height = blk.header().height, %err);
+            return Err(err.into());
+        }
+
         let mut events = vec![];
         let mut task = self.task.write().await;
 
@@ -667,14 +932,37 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
             blk.header().timestamp - tip.inner().header().timestamp;
 
         let header_verification_start = std::time::Instant::now();
+        let rules = self.vm.read().await.chain_rules()?;
         // Verify Block Header
-        let (pni, prev_block_voters, tip_block_voters) = verify_block_header(
-            self.db.clone(),
-            &prev_header,
-            &provisioners_list,
-            blk.header(),
-        )
-        .await?;
+        let (pni, prev_block_voters, tip_block_voters, tip_block_committees) =
+            match preverified {
+                Some(p) if p.prev_block_hash == prev_header.hash => {
+                    verify_block_fields(
+                        self.db.clone(),
+                        &prev_header,
+                        &provisioners_list,
+                        &rules,
+                        blk.header(),
+                    )
+                    .await?;
+                    (
+                        p.pni,
+                        p.prev_block_voters,
+                        p.tip_block_voters,
+                        p.tip_block_committees,
+                    )
+                }
+                _ => {
+                    verify_block_header(
+                        self.db.clone(),
+                        &prev_header,
+                        &provisioners_list,
+                        &rules,
+                        blk.header(),
+                    )
+                    .await?
+                }
+            };
 
         // Elapsed time header verification
         histogram!("dusk_block_header_elapsed")
@@ -684,10 +972,37 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
         let mut est_elapsed_time = Duration::default();
         let mut block_size_on_disk = 0;
         let mut slashed_count: usize = 0;
+        let mut provisioners_changed = false;
         // Persist block in consistency with the VM state update
         let (label, finalized) = {
             let header = blk.header();
-            verify_faults(self.db.clone(), header.height, blk.faults()).await?;
+            verify_faults(
+                self.db.clone(),
+                header.height,
+                rules.epoch,
+                blk.faults(),
+            )
+            .await?;
+
+            let participation = uptime::participation(
+                provisioners_list.current(),
+                prev_header.seed,
+                header,
+                tip_block_committees.as_ref(),
+            );
+
+            let intent = AcceptIntent {
+                height: header.height,
+                hash: header.hash,
+                prev_state_root: prev_header.state_hash,
+                state_root: header.state_hash,
+            };
+            journal::begin(&*self.db.read().await, &intent)?;
+
+            #[cfg(feature = "chaos")]
+            if let Some(chaos) = &self.chaos {
+                chaos.delay_db_write().await;
+            }
 
             let vm = self.vm.write().await;
 
@@ -712,6 +1027,10 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
                 // Store block with updated transactions with Error and GasSpent
                 block_size_on_disk =
                     db.store_block(header, &txs, blk.faults(), label)?;
+                uptime::record(db, header.height, participation)?;
+                if let Some((_, new_finals)) = &finality.1 {
+                    uptime::finalize(db, new_finals.keys().copied())?;
+                }
 
                 Ok((stake_events, finality))
             })?;
@@ -733,19 +1052,36 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
                 slashed_count += 1;
             }
 
+            // The eligibility of the new stakes depends on the parameters of
+            // the stake contract
+            let stake_config = vm.get_stake_config()?;
             let selective_update = Self::selective_update(
                 header.height,
+                &stake_config,
                 &stakes,
                 &mut provisioners_list,
             );
 
-            if let Err(e) = selective_update {
-                warn!("Resync provisioners due to {e:?}");
-                let state_hash = blk.header().state_hash;
-                let new_prov = vm.get_provisioners(state_hash)?;
-                provisioners_list.update_and_swap(new_prov)
+            match selective_update {
+                Ok(changed) => provisioners_changed = changed,
+                Err(e) => {
+                    warn!("Resync provisioners due to {e:?}");
+                    let state_hash = blk.header().state_hash;
+                    let new_prov = vm.get_provisioners(state_hash)?;
+                    provisioners_list.update_and_swap(new_prov);
+                    provisioners_changed = true;
+                }
             }
 
+            self.db.read().await.update(|db| {
+                snapshots::record(
+                    db,
+                    header.height,
+                    stake_config.epoch,
+                    provisioners_list.current(),
+                )
+            })?;
+
             let (label, final_results) = finality;
             // Update tip
             *tip = BlockWithLabel::new_with_label(blk.clone(), label);
@@ -761,6 +1097,15 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
                     .collect::<Vec<_>>();
                 vm.finalize_state(new_final_state, old_finals_to_merge)?;
             }
+            // A panic here leaves the acceptance to be repaired from the
+            // journal on restart
+            #[cfg(feature = "chaos")]
+            if let Some(chaos) = &self.chaos {
+                chaos.observe_height(header.height);
+                chaos.check_panic(ChaosTarget::Acceptor);
+            }
+
+            journal::complete(&*self.db.read().await)?;
 
             anyhow::Ok((label, finalized))
         }?;
@@ -769,6 +1114,9 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
         // A fully valid block is accepted, consensus task must be aborted.
         task.abort_with_wait().await;
 
+        // Faults included in the block are no longer pending
+        task.fault_pool.remove(blk.faults());
+
         Self::emit_metrics(
             tip.inner(),
             &label,
@@ -821,6 +1169,27 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
                         }
                     }
                 }
+
+                // Delete from mempool any transaction that can no longer be
+                // included in the next block
+                let next_height = tip.inner().header().height + 1;
+                let expired: Vec<_> = db
+                    .mempool_txs_sorted_by_fee()?
+                    .filter(|tx| tx.inner.is_expired_at(next_height))
+                    .map(|tx| tx.id())
+                    .collect();
+                for tx_id in expired {
+                    for deleted in db
+                        .delete_mempool_tx(tx_id, false)
+                        .map_err(|e| {
+                            warn!("Error while deleting expired tx: {e}")
+                        })
+                        .unwrap_or_default()
+                    {
+                        events.push(TransactionEvent::Removed(deleted).into());
+                    }
+                }
+
                 Ok(db.count_candidates())
             })
             .map_err(|e| warn!("Error while cleaning up the database: {e}"));
@@ -833,6 +1202,7 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
             let round = tip.inner().header().height;
             let mut f = task.future_msg.lock().await;
             f.remove_msgs_out_of_range(round + 1, OFFSET_FUTURE_MSGS);
+            f.remove_expired_msgs();
             histogram!("dusk_future_msg_count").record(f.msg_count() as f64);
         }
 
@@ -857,6 +1227,14 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
 
         events.push(BlockEvent::Accepted(tip.inner()).into());
 
+        for upgrade in self.upgrades.activated_at(tip.inner().header().height) {
+            info!(
+                event = "protocol upgrade activated",
+                name = upgrade.name,
+                height = upgrade.height,
+            );
+        }
+
         for node_event in events {
             if let Err(e) = self.event_sender.try_send(node_event) {
                 warn!("cannot notify event {e}")
@@ -876,7 +1254,10 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
             );
         }
 
-        Ok(finalized)
+        Ok(AcceptedBlock {
+            finalized,
+            provisioners_changed,
+        })
     }
 
     /// Perform the rolling finality checks, updating the database with new
@@ -1075,6 +1456,16 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
                     })?;
 
                 if h.state_hash == target_state_hash {
+                    let entry = AuditEntry::new(
+                        "node",
+                        "revert",
+                        json!({
+                            "from_height": curr_height,
+                            "to_height": h.height,
+                            "state_root": hex::encode(h.state_hash),
+                        }),
+                    );
+                    db.append_audit_entry(&entry)?;
                     return Ok((b, label));
                 }
 
@@ -1103,6 +1494,7 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
 
                 // Delete any rocksdb record related to this block
                 db.delete_block(&b)?;
+                uptime::revert(db, h.height)?;
 
                 let now = get_current_timestamp();
 
@@ -1162,6 +1554,101 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
         );
     }
 
+    /// Adds a fault gossiped by a peer to the ones to be included in a
+    /// candidate block, once verified.
+    ///
+    /// Returns whether the fault is valid and was not already pending, for
+    /// it to be propagated further.
+    pub(crate) async fn add_fault(&self, fault: &ledger::Fault) -> bool {
+        let round = self.get_curr_height().await + 1;
+        let epoch = match self.vm.read().await.get_stake_config() {
+            Ok(config) => config.epoch,
+            Err(err) => {
+                warn!(event = "cannot read stake config", ?err);
+                return false;
+            }
+        };
+        let single = std::slice::from_ref(fault);
+        if let Err(err) =
+            verify_faults(self.db.clone(), round, epoch, single).await
+        {
+            debug!(event = "fault discarded", id = to_str(&fault.id()), ?err);
+            return false;
+        }
+        match self.is_slashable(fault).await {
+            Ok(true) => {}
+            Ok(false) => {
+                debug!(
+                    event = "fault discarded",
+                    id = to_str(&fault.id()),
+                    reason = "culprit not in the committee or not staking",
+                );
+                return false;
+            }
+            Err(err) => {
+                debug!(
+                    event = "fault discarded",
+                    id = to_str(&fault.id()),
+                    ?err
+                );
+                return false;
+            }
+        }
+        self.task.read().await.fault_pool.add(vec![fault.clone()])
+    }
+
+    /// Returns whether the culprit of `fault` was in the committee of the
+    /// faulty step and still has a stake to be slashed.
+    async fn is_slashable(&self, fault: &ledger::Fault) -> Result<bool> {
+        let prev_block_hash = fault.header().prev_block_hash;
+        let prev_header = self
+            .db
+            .read()
+            .await
+            .view(|t| t.block_header(&prev_block_hash))?
+            .ok_or_else(|| anyhow!("unknown previous block"))?;
+
+        // The provisioners of the faulty round are the ones following its
+        // previous block
+        let tip_hash = self.tip_header().await.hash;
+        let provisioners = if prev_header.hash == tip_hash {
+            self.provisioners_list.read().await.to_current()
+        } else {
+            self.vm
+                .read()
+                .await
+                .get_provisioners(prev_header.state_hash)?
+        };
+        if !signed_by_committee(fault, &provisioners, prev_header.seed) {
+            return Ok(false);
+        }
+
+        let culprit = fault.to_culprit();
+        let stake = self.vm.read().await.get_provisioner(culprit.inner())?;
+        Ok(stake.is_some())
+    }
+
+    /// Returns whether `header` is the one of a candidate for the round
+    /// following the tip, generated by the generator of its iteration.
+    pub(crate) async fn is_expected_candidate(
+        &self,
+        header: &ledger::Header,
+    ) -> bool {
+        let tip = self.tip_header().await;
+        if header.height != tip.height + 1 || header.prev_block_hash != tip.hash
+        {
+            return false;
+        }
+
+        let generator = self
+            .provisioners_list
+            .read()
+            .await
+            .current()
+            .get_generator(header.iteration, tip.seed, header.height);
+        generator == header.generator_bls_pubkey
+    }
+
     pub(crate) async fn get_curr_height(&self) -> u64 {
         self.tip.read().await.inner().header().height
     }
@@ -1344,10 +1831,12 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network> Acceptor<N, DB, VM> {
 
         // Ensure header of the new block is valid according to prev_block
         // header
+        let rules = self.vm.read().await.chain_rules()?;
         let _ = verify_block_header(
             self.db.clone(),
             &prev_header,
             &provisioners_list,
+            &rules,
             new,
         )
         .await?;
@@ -1370,31 +1859,62 @@ pub(crate) async fn verify_block_header<DB: database::DB>(
     db: Arc<RwLock<DB>>,
     prev_header: &ledger::Header,
     provisioners: &ContextProvisioners,
+    rules: &ChainRules,
     header: &ledger::Header,
-) -> Result<(u8, Vec<Voter>, Vec<Voter>), HeaderError> {
-    // Set the expected generator to the one extracted by Deterministic
-    // Sortition, or, in case of Emergency Block, to the Dusk Consensus Key
+) -> Result<
+    (u8, Vec<Voter>, Vec<Voter>, Option<AttestationCommittees>),
+    HeaderError,
+> {
     let (expected_generator, check_att) =
-        if is_emergency_block(header.iteration) {
-            let dusk_key = PublicKey::new(*DUSK_CONSENSUS_KEY);
-            let dusk_key_bytes = dusk_key.bytes();
+        expected_generator(prev_header, provisioners, header);
 
-            // We disable the Attestation check since it's not needed to accept
-            // an Emergency Block
-            (*dusk_key_bytes, false)
-        } else {
-            let iter_generator = provisioners.current().get_generator(
-                header.iteration,
-                prev_header.seed,
-                header.height,
-            );
+    // Verify header validity
+    let validator = Validator::new(db, prev_header, provisioners, rules);
+    validator.verify_fields(header, &expected_generator).await?;
+    validator.verify_attestations(header, check_att).await
+}
 
-            (iter_generator, true)
-        };
+/// Performs the verification of block header against prev_block header,
+/// except for the attestations, which are expected to be already verified
+/// (see [`Validator::verify_attestations`])
+pub(crate) async fn verify_block_fields<DB: database::DB>(
+    db: Arc<RwLock<DB>>,
+    prev_header: &ledger::Header,
+    provisioners: &ContextProvisioners,
+    rules: &ChainRules,
+    header: &ledger::Header,
+) -> Result<(), HeaderError> {
+    let (expected_generator, _) =
+        expected_generator(prev_header, provisioners, header);
 
-    // Verify header validity
-    let validator = Validator::new(db, prev_header, provisioners);
-    validator
-        .execute_checks(header, &expected_generator, check_att)
-        .await
+    let validator = Validator::new(db, prev_header, provisioners, rules);
+    validator.verify_fields(header, &expected_generator).await
+}
+
+/// Returns the expected generator of the block, along with whether its
+/// attestation has to be checked.
+///
+/// The expected generator is the one extracted by Deterministic Sortition,
+/// or, in case of Emergency Block, the Dusk Consensus Key
+pub(crate) fn expected_generator(
+    prev_header: &ledger::Header,
+    provisioners: &ContextProvisioners,
+    header: &ledger::Header,
+) -> (PublicKeyBytes, bool) {
+    if is_emergency_block(header.iteration) {
+        let dusk_key = PublicKey::new(*DUSK_CONSENSUS_KEY);
+        let dusk_key_bytes = dusk_key.bytes();
+
+        // We disable the Attestation check since it's not needed to accept
+        // an Emergency Block
+        (*dusk_key_bytes, false)
+    } else {
+        let iter_generator = provisioners.current().get_generator(
+            header.iteration,
+            prev_header.seed,
+            header.height,
+        );
+
+        (iter_generator, true)
+    }
 }
@@ -0,0 +1,220 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::fmt;
+
+use node_data::ledger::Transaction;
+use serde::{Deserialize, Serialize};
+
+/// Share of the block gas limit reserved to the protocol transactions, the
+/// calls to the entrypoints of the protocol contracts such as the ones
+/// submitting slashing evidence or governance operations.
+///
+/// The protocol transactions of a block precede the user ones, which can only
+/// spend the gas left by the reservation.
+///
+/// Being a chain parameter, it must be the same for all the nodes of a
+/// network: changing it is equivalent to forking the chain.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockSpaceReservation {
+    /// Share of the block gas limit reserved, in percent
+    pub reserved_percent: u8,
+
+    /// Entrypoints of the protocol contracts
+    #[serde(default)]
+    pub protocol_calls: Vec<ProtocolCall>,
+}
+
+/// Entrypoint of a protocol contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolCall {
+    /// Hex encoded id of the contract
+    pub contract: String,
+    /// Name of the function called
+    pub fn_name: String,
+}
+
+impl BlockSpaceReservation {
+    /// Returns whether some space is reserved to protocol transactions.
+    pub fn is_enabled(&self) -> bool {
+        !self.protocol_calls.is_empty()
+    }
+
+    /// Returns whether the transaction calls an entrypoint of a protocol
+    /// contract.
+    pub fn is_protocol(&self, tx: &Transaction) -> bool {
+        let Some(call) = tx.inner.call() else {
+            return false;
+        };
+        let contract = hex::encode(call.contract.to_bytes());
+        self.protocol_calls.iter().any(|c| {
+            c.fn_name == call.fn_name
+                && c.contract.eq_ignore_ascii_case(&contract)
+        })
+    }
+
+    /// Returns the gas the user transactions of a block can spend.
+    pub fn user_gas_limit(&self, block_gas_limit: u64) -> u64 {
+        if !self.is_enabled() {
+            return block_gas_limit;
+        }
+        let percent = self.reserved_percent.min(100) as u128;
+        let reserved = block_gas_limit as u128 * percent / 100;
+        block_gas_limit - reserved as u64
+    }
+}
+
+/// Violation of the block space reservation by a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockSpaceError {
+    /// A protocol transaction follows a user transaction
+    ProtocolTxAfterUserTx,
+    /// A user transaction spends gas reserved to protocol transactions
+    ReservedGasSpent,
+}
+
+impl fmt::Display for BlockSpaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ProtocolTxAfterUserTx => {
+                write!(f, "Protocol transaction after a user transaction")
+            }
+            Self::ReservedGasSpent => {
+                write!(f, "User transaction spending the reserved gas")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BlockSpaceError {}
+
+/// Tracks the transactions of a block, in order, against the reservation.
+pub struct BlockSpace<'a> {
+    reservation: &'a BlockSpaceReservation,
+    user_gas_left: u64,
+    has_user_txs: bool,
+}
+
+impl<'a> BlockSpace<'a> {
+    pub fn new(
+        reservation: &'a BlockSpaceReservation,
+        block_gas_limit: u64,
+    ) -> Self {
+        Self {
+            reservation,
+            user_gas_left: reservation.user_gas_limit(block_gas_limit),
+            has_user_txs: false,
+        }
+    }
+
+    /// Checks whether the transaction, spending `gas_spent`, can follow the
+    /// ones included so far.
+    pub fn check(
+        &self,
+        tx: &Transaction,
+        gas_spent: u64,
+    ) -> Result<(), BlockSpaceError> {
+        if self.reservation.is_protocol(tx) {
+            return match self.has_user_txs {
+                true => Err(BlockSpaceError::ProtocolTxAfterUserTx),
+                false => Ok(()),
+            };
+        }
+        if gas_spent > self.user_gas_left {
+            return Err(BlockSpaceError::ReservedGasSpent);
+        }
+        Ok(())
+    }
+
+    /// Includes the transaction, spending `gas_spent`, after the ones
+    /// included so far.
+    pub fn include(
+        &mut self,
+        tx: &Transaction,
+        gas_spent: u64,
+    ) -> Result<(), BlockSpaceError> {
+        self.check(tx, gas_spent)?;
+        if !self.reservation.is_protocol(tx) {
+            self.has_user_txs = true;
+            self.user_gas_left -= gas_spent;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dusk_core::abi::ContractId;
+    use dusk_core::signatures::bls::SecretKey as BlsSecretKey;
+    use dusk_core::transfer::data::ContractCall;
+    use dusk_core::transfer::Transaction as ProtocolTransaction;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    const PROTOCOL_CONTRACT: [u8; 32] = [7; 32];
+
+    fn call(contract: [u8; 32], fn_name: &str) -> Transaction {
+        let mut rng = StdRng::seed_from_u64(0xbeef);
+        let sk = BlsSecretKey::random(&mut rng);
+        let call =
+            ContractCall::new(ContractId::from_bytes(contract), fn_name, &())
+                .expect("call to be built");
+        ProtocolTransaction::moonlight(
+            &sk,
+            None,
+            0,
+            0,
+            1_000,
+            1,
+            1,
+            0,
+            Some(call),
+        )
+        .expect("transaction to be built")
+        .into()
+    }
+
+    #[test]
+    fn reserved_space() {
+        let reservation = BlockSpaceReservation {
+            reserved_percent: 20,
+            protocol_calls: vec![ProtocolCall {
+                contract: hex::encode(PROTOCOL_CONTRACT),
+                fn_name: "slash".into(),
+            }],
+        };
+        assert_eq!(reservation.user_gas_limit(1_000), 800);
+
+        let protocol_tx = call(PROTOCOL_CONTRACT, "slash");
+        let user_tx = call([1; 32], "slash");
+        assert!(reservation.is_protocol(&protocol_tx));
+        assert!(!reservation.is_protocol(&user_tx));
+        // Other entrypoints of the protocol contracts are user calls
+        assert!(!reservation.is_protocol(&call(PROTOCOL_CONTRACT, "stake")));
+
+        let mut space = BlockSpace::new(&reservation, 1_000);
+        assert_eq!(space.include(&protocol_tx, 300), Ok(()));
+        assert_eq!(space.include(&user_tx, 500), Ok(()));
+        assert_eq!(
+            space.check(&user_tx, 301),
+            Err(BlockSpaceError::ReservedGasSpent)
+        );
+        assert_eq!(space.include(&user_tx, 300), Ok(()));
+        assert_eq!(
+            space.check(&protocol_tx, 1),
+            Err(BlockSpaceError::ProtocolTxAfterUserTx)
+        );
+
+        // Without protocol contracts nothing is reserved
+        let reservation = BlockSpaceReservation {
+            reserved_percent: 20,
+            protocol_calls: vec![],
+        };
+        assert_eq!(reservation.user_gas_limit(1_000), 1_000);
+    }
+}
@@ -0,0 +1,249 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Propagation of the candidate blocks in compact form.
+//!
+//! A compact candidate references the transactions by id, so that peers
+//! rebuild the block from their mempool. Only the bodies of the transactions
+//! received recently, and then possibly still propagating, are carried.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use metrics::counter;
+use node_data::get_current_timestamp;
+use node_data::ledger::to_str;
+use node_data::message::payload::{
+    Candidate, CompactCandidate, Inv, ReconstructError,
+};
+use node_data::message::{
+    AsyncQueue, Capabilities, Message, Payload, SignedStepMessage,
+};
+use tokio::sync::{RwLock, Semaphore};
+use tracing::{debug, warn};
+
+use super::acceptor::Acceptor;
+use super::fsm::flood_request;
+use crate::database::{self, Mempool};
+use crate::{vm, Network};
+
+/// Transactions added to the mempool more recently than this are assumed
+/// to be missing from the peers mempool
+const RECENT_TX_SECS: u64 = 5;
+
+/// Time allowed to peers to send the missing transactions, before the full
+/// candidate is requested
+const MISSING_TXS_TIMEOUT: Duration = Duration::from_secs(1);
+
+const MISSING_TXS_POLL: Duration = Duration::from_millis(50);
+
+/// Lifespan in seconds of the requests of missing transactions
+const MISSING_TXS_TTL: u64 = 2;
+
+/// Maximum number of compact candidates rebuilt concurrently
+const MAX_RECONSTRUCTIONS: usize = 4;
+
+/// Converts a Candidate message into its compact form, keeping its
+/// metadata so that it is propagated as the original one.
+///
+/// The candidate is left as it is unless every known peer negotiated the
+/// compact candidates.
+pub(crate) async fn compact<N: Network, DB: database::DB>(
+    network: &Arc<RwLock<N>>,
+    db: &Arc<RwLock<DB>>,
+    msg: &Message,
+) -> Option<Message> {
+    let Payload::Candidate(candidate) = &msg.payload else {
+        return None;
+    };
+    if !network
+        .read()
+        .await
+        .common_capabilities()
+        .contains(Capabilities::COMPACT_CANDIDATE)
+    {
+        return None;
+    }
+
+    let recent = get_current_timestamp().saturating_sub(RECENT_TX_SECS);
+    let compact = db.read().await.view(|t| {
+        CompactCandidate::new(candidate, |tx| {
+            matches!(t.mempool_tx_timestamp(tx), Ok(Some(ts)) if ts < recent)
+        })
+    });
+
+    debug!(
+        event = "compact candidate",
+        hash = to_str(&compact.header.hash),
+        txs = compact.tx_ids.len(),
+        carried = compact.txs.len(),
+    );
+
+    let mut compact_msg = Message::from(compact);
+    compact_msg.metadata = msg.metadata.clone();
+    Some(compact_msg)
+}
+
+/// Rebuilds the compact candidates received from the network, a bounded
+/// number at a time.
+pub(crate) struct Reconstructor {
+    permits: Arc<Semaphore>,
+}
+
+impl Default for Reconstructor {
+    fn default() -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(MAX_RECONSTRUCTIONS)),
+        }
+    }
+}
+
+impl Reconstructor {
+    /// Spawns the rebuilding of the candidate of a compact message, once
+    /// checked to be signed by the generator of the round following the
+    /// tip.
+    ///
+    /// The message is dropped when too many candidates are being rebuilt.
+    pub(crate) fn spawn<N: Network, DB: database::DB, VM: vm::VMExecution>(
+        &self,
+        acc: Arc<RwLock<Acceptor<N, DB, VM>>>,
+        network: Arc<RwLock<N>>,
+        db: Arc<RwLock<DB>>,
+        inbound: AsyncQueue<Message>,
+        msg: Message,
+    ) {
+        let Ok(permit) = self.permits.clone().try_acquire_owned() else {
+            counter!("dusk_compact_candidate_dropped").increment(1);
+            debug!(event = "compact candidate dropped", ?msg);
+            return;
+        };
+
+        tokio::spawn(async move {
+            if is_expected(&acc, &msg).await {
+                reconstruct(network, db, inbound, msg).await;
+            }
+            drop(permit);
+        });
+    }
+}
+
+/// Returns whether the candidate of a compact message is signed by the
+/// generator of the round following the tip.
+async fn is_expected<N: Network, DB: database::DB, VM: vm::VMExecution>(
+    acc: &Arc<RwLock<Acceptor<N, DB, VM>>>,
+    msg: &Message,
+) -> bool {
+    let Payload::CompactCandidate(compact) = &msg.payload else {
+        return false;
+    };
+
+    let expected = acc
+        .read()
+        .await
+        .is_expected_candidate(&compact.header)
+        .await;
+    if !expected || compact.verify_signature().is_err() {
+        counter!("dusk_compact_candidate_rejected").increment(1);
+        debug!(
+            event = "compact candidate rejected",
+            hash = to_str(&compact.header.hash),
+            height = compact.header.height,
+            iteration = compact.header.iteration,
+        );
+        return false;
+    }
+    true
+}
+
+/// Rebuilds the candidate of a compact message and queues it to `inbound`.
+///
+/// Missing transactions are requested to the network. If they are not
+/// received in time, the full candidate is requested instead.
+async fn reconstruct<N: Network, DB: database::DB>(
+    network: Arc<RwLock<N>>,
+    db: Arc<RwLock<DB>>,
+    inbound: AsyncQueue<Message>,
+    msg: Message,
+) {
+    let Payload::CompactCandidate(compact) = &msg.payload else {
+        return;
+    };
+    let hash = compact.header.hash;
+
+    let missing = match rebuild(&db, compact).await {
+        Ok(candidate) => {
+            forward(&inbound, &msg, candidate);
+            return;
+        }
+        Err(ReconstructError::MissingTxs(missing)) => missing,
+        Err(err) => {
+            warn!(
+                event = "cannot rebuild compact candidate",
+                hash = to_str(&hash),
+                %err,
+            );
+            return;
+        }
+    };
+
+    counter!("dusk_compact_candidate_missing_txs")
+        .increment(missing.len() as u64);
+    debug!(
+        event = "requesting missing txs",
+        hash = to_str(&hash),
+        missing = missing.len(),
+    );
+
+    let mut inv = Inv::new(missing.len() as u16);
+    for id in missing {
+        inv.add_tx_id(id);
+    }
+    // The missing transactions are requested to the alive peers only, as
+    // the full candidate is requested to the network if they do not have
+    // them
+    if let Err(err) = network
+        .read()
+        .await
+        .flood_request(&inv, Some(MISSING_TXS_TTL), 1)
+        .await
+    {
+        warn!("could not request missing txs {err}");
+    }
+
+    let deadline = tokio::time::Instant::now() + MISSING_TXS_TIMEOUT;
+    while tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(MISSING_TXS_POLL).await;
+        if let Ok(candidate) = rebuild(&db, compact).await {
+            forward(&inbound, &msg, candidate);
+            return;
+        }
+    }
+
+    counter!("dusk_compact_candidate_fallback").increment(1);
+    warn!(
+        event = "cannot rebuild compact candidate",
+        hash = to_str(&hash),
+    );
+
+    let mut inv = Inv::new(1);
+    inv.add_candidate_from_hash(hash);
+    flood_request(&network, &inv).await;
+}
+
+async fn rebuild<DB: database::DB>(
+    db: &Arc<RwLock<DB>>,
+    compact: &CompactCandidate,
+) -> Result<Candidate, ReconstructError> {
+    db.read()
+        .await
+        .view(|t| compact.reconstruct(|id| t.mempool_tx(*id).ok().flatten()))
+}
+
+fn forward(inbound: &AsyncQueue<Message>, msg: &Message, candidate: Candidate) {
+    let mut candidate_msg = Message::from(candidate);
+    candidate_msg.metadata = msg.metadata.clone();
+    inbound.try_send(candidate_msg);
+}
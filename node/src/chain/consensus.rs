@@ -4,6 +4,7 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -17,24 +18,92 @@ use dusk_consensus::operations::{
     CallParams, Operations, Output, VerificationOutput, Voter,
 };
 use dusk_consensus::queue::MsgRegistry;
+use dusk_consensus::sign_guard::SigningGuard;
+use dusk_consensus::signer::{LocalSigner, Signer};
 use dusk_consensus::user::provisioners::ContextProvisioners;
 use metrics::gauge;
 use node_data::bls::PublicKeyBytes;
-use node_data::ledger::{to_str, Block, Fault, Hash, Header};
+use node_data::ledger::{to_str, Block, Fault, Hash, Header, InvalidFault};
 use node_data::message::{payload, AsyncQueue, ConsensusHeader};
 use node_data::{ledger, Serializable, StepName};
 use tokio::sync::{oneshot, Mutex, RwLock};
 use tokio::task::JoinHandle;
-use tracing::{debug, info, trace, warn};
+use tracing::{debug, error, info, trace, warn};
 
-use crate::chain::header_validation::Validator;
+use crate::chain::fault_pool::FaultPool;
+use crate::chain::gas_limit::next_gas_limit;
+use crate::chain::header_validation::{verify_faults, Validator};
 use crate::chain::metrics::AverageElapsedTime;
+use crate::chain::signer::{RemoteSigner, RemoteSignerConfig};
+use crate::chain::upgrades::UpgradeSchedule;
 use crate::database::rocksdb::{
     MD_AVG_PROPOSAL, MD_AVG_RATIFICATION, MD_AVG_VALIDATION, MD_LAST_ITER,
 };
 use crate::database::{self, ConsensusStorage, Mempool, Metadata};
+use crate::mempool::policy::BlockExclusions;
+use crate::switches::RuntimeSwitches;
 use crate::{vm, Message};
 
+/// Signer of the consensus messages, with the public key of the provisioner
+pub(crate) type ConsensusKeys = (Arc<dyn Signer>, node_data::bls::PublicKey);
+
+/// Returns the path of the journal of the messages signed with the consensus
+/// keys at `keys_path`.
+///
+/// The journal is kept next to the keys rather than in the database, so that
+/// restoring a backup of the database does not roll it back.
+pub(crate) fn signing_journal_path(keys_path: &str) -> PathBuf {
+    Path::new(keys_path).with_extension("journal")
+}
+
+/// Loads the consensus keys at `path`, encrypted with the password from the
+/// env var DUSK_CONSENSUS_KEYS_PASS, unless signing is delegated to a
+/// `remote` signer not falling back to them.
+pub(crate) fn load_keys(
+    path: &str,
+    remote: Option<&RemoteSignerConfig>,
+) -> anyhow::Result<ConsensusKeys> {
+    let load_local = || {
+        let pwd = std::env::var("DUSK_CONSENSUS_KEYS_PASS")
+            .map_err(|_| anyhow::anyhow!("DUSK_CONSENSUS_KEYS_PASS not set"))?;
+        info!(event = "loading consensus keys", path = path);
+        let (sk, pk) = node_data::bls::load_keys(path.to_string(), pwd)?;
+        anyhow::Ok((LocalSigner::new(sk, *pk.inner()), pk))
+    };
+
+    let keys: ConsensusKeys = match remote {
+        None => {
+            let (signer, pk) = load_local()?;
+            (Arc::new(signer), pk)
+        }
+        Some(conf) => {
+            let mut fallback = None;
+            if conf.local_fallback {
+                let (signer, pk) = load_local()?;
+                if pk != conf.public_key {
+                    anyhow::bail!(
+                        "Consensus keys don't match the remote signer"
+                    );
+                }
+                fallback = Some(signer);
+            }
+            info!(
+                event = "signing with remote signer",
+                address = conf.address,
+                fallback = fallback.is_some(),
+            );
+            let signer = RemoteSigner::new(conf, fallback)?;
+            (Arc::new(signer), conf.public_key.clone())
+        }
+    };
+
+    info!(
+        event = "loaded consensus keys",
+        pubkey = format!("{:?}", keys.1)
+    );
+    Ok(keys)
+}
+
 /// Consensus Service Task is responsible for running the consensus layer.
 ///
 /// It manages consensus lifecycle and provides a way to interact with it.
@@ -54,31 +123,54 @@ pub(crate) struct Task {
     /// task id a counter to track consensus tasks
     task_id: u64,
 
-    /// Loaded Consensus keys
-    pub keys: (
-        dusk_core::signatures::bls::SecretKey,
-        node_data::bls::PublicKey,
-    ),
+    /// Loaded Consensus keys, none when the node only observes the chain
+    pub keys: Option<ConsensusKeys>,
+
+    switches: Arc<RuntimeSwitches>,
+
+    block_exclusions: Option<Arc<BlockExclusions>>,
+
+    /// Faults detected by consensus or gossiped by peers, waiting to be
+    /// included in a block
+    pub(crate) fault_pool: Arc<FaultPool>,
+
+    /// Protocol upgrades declared for the network
+    upgrades: Arc<UpgradeSchedule>,
+
+    /// Journal of the messages signed, preventing equivocation across
+    /// restarts
+    pub(crate) signing_guard: Option<Arc<SigningGuard>>,
 }
 
 impl Task {
-    /// Creates a new consensus task with the given keys encrypted with password
-    /// from env var DUSK_CONSENSUS_KEYS_PASS.
+    /// Creates a new consensus task signing with the given keys.
     pub(crate) fn new_with_keys(
-        path: String,
+        keys: ConsensusKeys,
         max_inbound_size: usize,
-    ) -> anyhow::Result<Self> {
-        let pwd = std::env::var("DUSK_CONSENSUS_KEYS_PASS")
-            .map_err(|_| anyhow::anyhow!("DUSK_CONSENSUS_KEYS_PASS not set"))?;
-        info!(event = "loading consensus keys", path = path);
-        let keys = node_data::bls::load_keys(path, pwd)?;
+        switches: Arc<RuntimeSwitches>,
+        block_exclusions: Option<Arc<BlockExclusions>>,
+    ) -> Self {
+        Self::new(Some(keys), max_inbound_size, switches, block_exclusions)
+    }
 
-        info!(
-            event = "loaded consensus keys",
-            pubkey = format!("{:?}", keys.1)
-        );
+    /// Creates a consensus task that never participates in consensus, for
+    /// nodes only observing the chain.
+    pub(crate) fn new_watch_only(
+        max_inbound_size: usize,
+        switches: Arc<RuntimeSwitches>,
+        block_exclusions: Option<Arc<BlockExclusions>>,
+    ) -> Self {
+        info!(event = "watch-only mode, consensus disabled");
+        Self::new(None, max_inbound_size, switches, block_exclusions)
+    }
 
-        Ok(Self {
+    fn new(
+        keys: Option<ConsensusKeys>,
+        max_inbound_size: usize,
+        switches: Arc<RuntimeSwitches>,
+        block_exclusions: Option<Arc<BlockExclusions>>,
+    ) -> Self {
+        Self {
             main_inbound: AsyncQueue::bounded(
                 max_inbound_size,
                 "consensus_inbound",
@@ -92,7 +184,22 @@ impl Task {
             running_task: None,
             task_id: 0,
             keys,
-        })
+            switches,
+            block_exclusions,
+            fault_pool: Arc::new(FaultPool::default()),
+            upgrades: Arc::default(),
+            signing_guard: None,
+        }
+    }
+
+    /// Sets the protocol upgrades, the consensus not running past the
+    /// activation of an unsupported one.
+    pub(crate) fn with_upgrades(
+        mut self,
+        upgrades: Arc<UpgradeSchedule>,
+    ) -> Self {
+        self.upgrades = upgrades;
+        self
     }
 
     pub(crate) fn spawn<D: database::DB, VM: vm::VMExecution>(
@@ -104,6 +211,19 @@ impl Task {
         base_timeout: TimeoutSet,
         voters: Vec<Voter>,
     ) {
+        let Some((signer, pubkey)) = &self.keys else {
+            // Watch-only nodes do not participate in consensus
+            return;
+        };
+
+        // The rounds past an unsupported upgrade follow rules this node does
+        // not implement
+        let round = tip.header().height + 1;
+        if let Err(err) = self.upgrades.check(round) {
+            error!(event = "consensus disabled", round, %err);
+            return;
+        }
+
         let current = provisioners_list.to_current();
         let consensus_task = Consensus::new(
             self.main_inbound.clone(),
@@ -114,17 +234,21 @@ impl Task {
                 vm,
                 tip.header().clone(),
                 provisioners_list, // TODO: Avoid cloning
+                self.switches.clone(),
+                self.block_exclusions.clone(),
+                self.fault_pool.clone(),
             )),
             Arc::new(Mutex::new(CandidateDB::new(db.clone()))),
         );
 
         let ru = RoundUpdate::new(
-            self.keys.1.clone(),
-            self.keys.0.clone(),
+            pubkey.clone(),
+            signer.clone(),
             tip.header(),
             base_timeout.clone(),
             voters,
-        );
+        )
+        .with_signing_guard(self.signing_guard.clone());
 
         self.task_id += 1;
 
@@ -266,6 +390,9 @@ pub struct Executor<DB: database::DB, VM: vm::VMExecution> {
     vm: Arc<RwLock<VM>>,
     tip_header: ledger::Header,
     provisioners: ContextProvisioners,
+    switches: Arc<RuntimeSwitches>,
+    block_exclusions: Option<Arc<BlockExclusions>>,
+    fault_pool: Arc<FaultPool>,
 }
 
 impl<DB: database::DB, VM: vm::VMExecution> Executor<DB, VM> {
@@ -274,12 +401,18 @@ impl<DB: database::DB, VM: vm::VMExecution> Executor<DB, VM> {
         vm: &Arc<RwLock<VM>>,
         tip_header: ledger::Header,
         provisioners: ContextProvisioners,
+        switches: Arc<RuntimeSwitches>,
+        block_exclusions: Option<Arc<BlockExclusions>>,
+        fault_pool: Arc<FaultPool>,
     ) -> Self {
         Executor {
             db: db.clone(),
             vm: vm.clone(),
             tip_header,
             provisioners,
+            switches,
+            block_exclusions,
+            fault_pool,
         }
     }
 }
@@ -291,10 +424,17 @@ impl<DB: database::DB, VM: vm::VMExecution> Operations for Executor<DB, VM> {
         candidate_header: &Header,
         expected_generator: &PublicKeyBytes,
     ) -> Result<(u8, Vec<Voter>, Vec<Voter>), HeaderError> {
+        let rules = self
+            .vm
+            .read()
+            .await
+            .chain_rules()
+            .map_err(|e| HeaderError::Storage("chain rules", e))?;
         let validator = Validator::new(
             self.db.clone(),
             &self.tip_header,
             &self.provisioners,
+            &rules,
         );
 
         validator
@@ -307,10 +447,14 @@ impl<DB: database::DB, VM: vm::VMExecution> Operations for Executor<DB, VM> {
         block_height: u64,
         faults: &[Fault],
     ) -> Result<(), OperationError> {
+        let rules = self.vm.read().await.chain_rules().map_err(|e| {
+            OperationError::InvalidFaults(InvalidFault::Other(format!("{e}")))
+        })?;
         let validator = Validator::new(
             self.db.clone(),
             &self.tip_header,
             &self.provisioners,
+            &rules,
         );
         Ok(validator.verify_faults(block_height, faults).await?)
     }
@@ -323,9 +467,17 @@ impl<DB: database::DB, VM: vm::VMExecution> Operations for Executor<DB, VM> {
     ) -> Result<VerificationOutput, VstError> {
         info!("verifying state");
 
-        let vm = self.vm.read().await;
-
-        vm.verify_state_transition(prev_root, blk, voters)
+        // Verifying only reads the state, so the candidates of different
+        // iterations are verified concurrently, off the async workers. Only
+        // the acceptance of a block takes the VM exclusively.
+        let vm = self.vm.clone().read_owned().await;
+        let blk = blk.clone();
+        let voters = voters.to_vec();
+        tokio::task::spawn_blocking(move || {
+            vm.verify_state_transition(prev_root, &blk, &voters)
+        })
+        .await
+        .map_err(|err| VstError::Generic(format!("VST task failed: {err}")))?
     }
 
     async fn execute_state_transition(
@@ -341,6 +493,36 @@ impl<DB: database::DB, VM: vm::VMExecution> Operations for Executor<DB, VM> {
                 let txs = view.mempool_txs_sorted_by_fee().map_err(|err| {
                     anyhow::anyhow!("failed to get mempool txs: {}", err)
                 })?;
+                // The protocol transactions are included ahead of the user
+                // ones, within the space reserved to them
+                let reservation = vm.block_space_reservation(params.round);
+                let txs: Box<dyn Iterator<Item = ledger::Transaction> + '_> =
+                    match reservation.is_enabled() {
+                        true => {
+                            let (protocol_txs, user_txs): (Vec<_>, Vec<_>) =
+                                txs.partition(|tx| reservation.is_protocol(tx));
+                            Box::new(protocol_txs.into_iter().chain(user_txs))
+                        }
+                        false => Box::new(txs),
+                    };
+                // The transactions excluded by the policy are left in the
+                // mempool, for other provisioners to include
+                let txs = txs.filter(|tx| {
+                    let Some(exclusions) = &self.block_exclusions else {
+                        return true;
+                    };
+                    match exclusions.reason(&tx.id()) {
+                        None => true,
+                        Some(reason) => {
+                            debug!(
+                                event = "tx excluded by policy",
+                                hash = hex::encode(tx.id()),
+                                %reason,
+                            );
+                            false
+                        }
+                    }
+                });
                 let ret = vm.execute_state_transition(&params, txs).map_err(
                     |err| anyhow::anyhow!("failed to call EST {}", err),
                 )?;
@@ -400,6 +582,63 @@ impl<DB: database::DB, VM: vm::VMExecution> Operations for Executor<DB, VM> {
     }
 
     async fn get_block_gas_limit(&self) -> u64 {
-        self.vm.read().await.get_block_gas_limit()
+        let prev = &self.tip_header;
+        let rules = match self.vm.read().await.chain_rules() {
+            Ok(rules) => rules,
+            Err(e) => {
+                warn!(event = "cannot read chain rules", err = ?e);
+                return prev.gas_limit;
+            }
+        };
+        self.db
+            .read()
+            .await
+            .view(|t| next_gas_limit(t, &rules, prev))
+            .unwrap_or_else(|e| {
+                // The candidate would be rejected, but there is nothing
+                // better to propose
+                warn!(event = "cannot compute gas limit", err = ?e);
+                prev.gas_limit
+            })
+    }
+
+    async fn report_faults(&self, faults: Vec<Fault>) {
+        for fault in &faults {
+            info!(event = "fault detected", id = to_str(&fault.id()), ?fault,);
+        }
+        self.fault_pool.add(faults);
+    }
+
+    async fn pending_faults(&self, round: u64) -> Vec<Fault> {
+        let epoch = match self.vm.read().await.get_stake_config() {
+            Ok(config) => config.epoch,
+            Err(e) => {
+                warn!(event = "cannot read stake config", err = ?e);
+                return vec![];
+            }
+        };
+        let mut valid = vec![];
+        let mut invalid = vec![];
+        for fault in self.fault_pool.pending() {
+            let single = std::slice::from_ref(&fault);
+            match verify_faults(self.db.clone(), round, epoch, single).await {
+                Ok(()) => valid.push(fault),
+                Err(err) => {
+                    // Expired, already included or related to a fork
+                    debug!(
+                        event = "fault discarded",
+                        id = to_str(&fault.id()),
+                        ?err,
+                    );
+                    invalid.push(fault);
+                }
+            }
+        }
+        self.fault_pool.remove(&invalid);
+        valid
+    }
+
+    fn block_production_enabled(&self) -> bool {
+        self.switches.block_production()
     }
 }
@@ -0,0 +1,153 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::sync::Mutex;
+
+use dusk_consensus::config::MAX_NUMBER_OF_FAULTS;
+use dusk_consensus::user::committee::Committee;
+use dusk_consensus::user::provisioners::Provisioners;
+use node_data::ledger::{Fault, Seed};
+
+/// Maximum number of faults waiting to be included in a block
+const MAX_PENDING_FAULTS: usize = MAX_NUMBER_OF_FAULTS;
+
+/// Faults detected by the local consensus or gossiped by peers, waiting to be
+/// included in a candidate block for the culprits to be slashed.
+///
+/// It outlives the consensus tasks, as a fault detected in a round can only
+/// be included in a block of a later iteration or round.
+#[derive(Default)]
+pub(crate) struct FaultPool {
+    faults: Mutex<Vec<Fault>>,
+}
+
+impl FaultPool {
+    /// Adds the faults not already pending, dropping the oldest ones when
+    /// the pool is full.
+    ///
+    /// Returns whether any fault was not already pending.
+    pub(crate) fn add(&self, faults: Vec<Fault>) -> bool {
+        let mut pending = self.faults.lock().expect("lock to be acquired");
+        let mut added = false;
+        for fault in faults {
+            if !pending.iter().any(|f| f.same(&fault)) {
+                pending.push(fault);
+                added = true;
+            }
+        }
+        let excess = pending.len().saturating_sub(MAX_PENDING_FAULTS);
+        pending.drain(..excess);
+        added
+    }
+
+    /// Returns the pending faults, oldest first.
+    pub(crate) fn pending(&self) -> Vec<Fault> {
+        self.faults.lock().expect("lock to be acquired").clone()
+    }
+
+    /// Removes the faults matching any of `faults`.
+    pub(crate) fn remove(&self, faults: &[Fault]) {
+        self.faults
+            .lock()
+            .expect("lock to be acquired")
+            .retain(|f| !faults.iter().any(|other| f.same(other)));
+    }
+}
+
+/// Returns whether the culprit of `fault` is a member of the committee of the
+/// step it refers to, drawn from `provisioners` with the `seed` of the
+/// previous block.
+///
+/// The signature of a provisioner out of the committee proves nothing, and
+/// a signer without stake cannot be slashed.
+pub(crate) fn signed_by_committee(
+    fault: &Fault,
+    provisioners: &Provisioners,
+    seed: Seed,
+) -> bool {
+    let header = fault.header();
+    let (committee, _) = Committee::for_step(
+        provisioners,
+        seed,
+        header.round,
+        header.iteration,
+        fault.step(),
+    );
+    committee.is_member(&fault.to_culprit())
+}
+
+#[cfg(test)]
+mod tests {
+    use dusk_consensus::user::provisioners::DUSK;
+    use dusk_core::signatures::bls::{
+        PublicKey as BlsPublicKey, SecretKey as BlsSecretKey,
+    };
+    use node_data::bls::PublicKey;
+    use node_data::message::payload::{Validation, Vote};
+    use node_data::message::{ConsensusHeader, SignInfo, SignedStepMessage};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    const ROUND: u64 = 10;
+    const ITERATION: u8 = 1;
+
+    fn double_validation(sk: &BlsSecretKey) -> Fault {
+        let pk = BlsPublicKey::from(sk);
+        let validation = |vote| {
+            let mut validation = Validation {
+                header: ConsensusHeader {
+                    prev_block_hash: [1; 32],
+                    round: ROUND,
+                    iteration: ITERATION,
+                },
+                vote,
+                sign_info: SignInfo::default(),
+            };
+            validation.sign(sk, &pk);
+            validation
+        };
+        Fault::double_validation(
+            &validation(Vote::Valid([2; 32])),
+            &validation(Vote::NoCandidate),
+        )
+    }
+
+    #[test]
+    fn committee_membership() {
+        let rng = &mut StdRng::seed_from_u64(0xbeef);
+        let seed = Seed::from([3; 48]);
+
+        let sks: Vec<_> = (0..4).map(|_| BlsSecretKey::random(rng)).collect();
+        let mut provisioners = Provisioners::empty();
+        for sk in &sks {
+            let pk = PublicKey::new(BlsPublicKey::from(sk));
+            provisioners.add_member_with_value(pk, 10_000 * DUSK);
+        }
+
+        let generator = provisioners.get_generator(ITERATION, seed, ROUND);
+        let is_generator = |sk: &BlsSecretKey| {
+            PublicKey::new(BlsPublicKey::from(sk)).bytes() == &generator
+        };
+
+        // The generator is excluded from the validation committee
+        let (excluded, members): (Vec<_>, Vec<_>) =
+            sks.iter().partition(|sk| is_generator(*sk));
+        for sk in excluded {
+            let fault = double_validation(sk);
+            assert!(!signed_by_committee(&fault, &provisioners, seed));
+        }
+        assert!(members.iter().any(|sk| {
+            signed_by_committee(&double_validation(sk), &provisioners, seed)
+        }));
+
+        // A key without stake is never in a committee
+        let outsider = BlsSecretKey::random(rng);
+        let fault = double_validation(&outsider);
+        assert!(!signed_by_committee(&fault, &provisioners, seed));
+    }
+}
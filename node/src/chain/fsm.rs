@@ -508,7 +508,10 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution> SimpleFSM<N, DB, VM> {
 
 /// Requests a block by height/hash from the network with so-called
 /// Flood-request approach.
-async fn flood_request<N: Network>(network: &Arc<RwLock<N>>, inv: &Inv) {
+pub(super) async fn flood_request<N: Network>(
+    network: &Arc<RwLock<N>>,
+    inv: &Inv,
+) {
     debug!(event = "flood_request", ?inv);
 
     if let Err(err) = network
@@ -5,6 +5,7 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 use std::collections::BTreeMap;
+use std::iter;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -16,6 +17,7 @@ use tracing::{debug, info, warn};
 
 use super::PresyncInfo;
 use crate::chain::acceptor::Acceptor;
+use crate::chain::pipeline;
 use crate::{database, vm, Network};
 
 const MAX_POOL_BLOCKS_SIZE: usize = 1000;
@@ -265,49 +267,46 @@ impl<DB: database::DB, VM: vm::VMExecution, N: Network>
             self.range.1 = block_height
         }
 
-        // Try accepting consecutive block
+        // Try accepting consecutive block, along with the other consecutive
+        // blocks from the pool, if available
         if block_height == current_height + 1 {
-            acc.try_accept_block(blk, false).await?;
-            // reset expiry_time only if we receive a valid block
-            self.start_time = SystemTime::now();
-            debug!(
-                event = "accepted block",
-                block_height,
-                last_request = self.last_request,
-            );
-            self.range.0 = block_height + 1;
-
-            // Try to accept other consecutive blocks from the pool, if
-            // available
-            for height in self.range.0..=self.range.1 {
-                if let Some(blk) = self.pool.get(&height) {
-                    acc.try_accept_block(blk, false).await?;
-                    // reset expiry_time only if we receive a valid block
-                    self.start_time = SystemTime::now();
-                    self.range.0 += 1;
-                    debug!(
-                        event = "accepted next block",
-                        block_height = height,
-                        last_request = self.last_request,
-                    );
-                } else {
-                    // This means we accepted a block and the next block
-                    // available in the pool is not the next one
-                    if let Some((&h, _)) = self.pool.first_key_value() {
-                        // We then check if the first block in the pool is
-                        // related to something we requested, or is just the
-                        // current cluster tip.
-                        // If it's something we requested, highly probably it
-                        // means that we missed a block we requested (assuming
-                        // that we receive block sequentially)
-                        // If so, we just request the missing block using a
-                        // GetResource to alive peers
-                        if h < self.last_request {
-                            self.request_missing_block(height).await;
-                        }
+            let blocks: Vec<_> = iter::once(blk.clone())
+                .chain(
+                    (block_height + 1..=self.range.1)
+                        .map_while(|height| self.pool.get(&height).cloned()),
+                )
+                .collect();
+
+            let (accepted, res) =
+                pipeline::accept_blocks(&mut *acc, blocks).await;
+            if accepted > 0 {
+                // reset expiry_time only if we receive a valid block
+                self.start_time = SystemTime::now();
+                self.range.0 = block_height + accepted as u64;
+                debug!(
+                    event = "accepted blocks",
+                    block_height,
+                    accepted,
+                    last_request = self.last_request,
+                );
+            }
+            res?;
+
+            // This means we accepted a block and the next block available
+            // in the pool is not the next one
+            if self.range.0 <= self.range.1 {
+                if let Some((&h, _)) = self.pool.first_key_value() {
+                    // We then check if the first block in the pool is
+                    // related to something we requested, or is just the
+                    // current cluster tip.
+                    // If it's something we requested, highly probably it
+                    // means that we missed a block we requested (assuming
+                    // that we receive block sequentially)
+                    // If so, we just request the missing block using a
+                    // GetResource to alive peers
+                    if h < self.last_request {
+                        self.request_missing_block(self.range.0).await;
                     }
-
-                    break;
                 }
             }
             self.pool.retain(|k, _| k >= &self.range.0);
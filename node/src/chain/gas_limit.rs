@@ -0,0 +1,184 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use node_data::ledger::Header;
+use serde::{Deserialize, Serialize};
+
+use crate::chain::header_validation::ChainRules;
+use crate::chain::upgrades::GAS_LIMIT;
+use crate::database::Ledger;
+
+const DEFAULT_MAX_CHANGE_DENOMINATOR: u64 = 1024;
+
+/// Rule setting the gas limit of the blocks.
+///
+/// Being a chain parameter, it must be the same for all the nodes of a
+/// network: changing it is equivalent to forking the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasLimitRule {
+    /// Gas limit of the blocks, or of the first block when adjusted
+    pub limit: u64,
+
+    /// Adjustment of the gas limit to the usage of the previous block
+    #[serde(default)]
+    pub adjustment: Option<GasLimitAdjustment>,
+}
+
+/// Adjustment of the block gas limit toward a target utilization.
+///
+/// A block using more gas than the target raises the limit of the next one,
+/// while a block using less lowers it, proportionally to the distance from
+/// the target as for the base fee of EIP-1559.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasLimitAdjustment {
+    /// Share of their gas limit the blocks are expected to use, in percent
+    pub target_utilization: u8,
+
+    /// Inverse of the maximum change of the limit from a block to the next
+    #[serde(default = "default_max_change_denominator")]
+    pub max_change_denominator: u64,
+
+    /// Lower bound of the gas limit
+    pub min_limit: u64,
+
+    /// Upper bound of the gas limit
+    pub max_limit: u64,
+}
+
+fn default_max_change_denominator() -> u64 {
+    DEFAULT_MAX_CHANGE_DENOMINATOR
+}
+
+impl GasLimitRule {
+    /// A gas limit that is the same for all the blocks.
+    pub const fn fixed(limit: u64) -> Self {
+        Self {
+            limit,
+            adjustment: None,
+        }
+    }
+
+    /// Returns the gas limit of the block following one with the given
+    /// limit and gas usage.
+    pub fn next(&self, prev_limit: u64, prev_gas_used: u64) -> u64 {
+        let Some(adjustment) = &self.adjustment else {
+            return self.limit;
+        };
+        let bounds = (adjustment.min_limit, adjustment.max_limit);
+        let clamp = |limit: u128| {
+            let limit = u64::try_from(limit).unwrap_or(u64::MAX);
+            limit.clamp(bounds.0, bounds.1.max(bounds.0))
+        };
+
+        // The parent was not produced under this rule, as the genesis block
+        if prev_limit == 0 {
+            return clamp(self.limit as u128);
+        }
+
+        let prev_limit = prev_limit as u128;
+        let target = prev_limit * adjustment.target_utilization as u128 / 100;
+        if target == 0 {
+            return clamp(prev_limit);
+        }
+
+        let used = prev_gas_used as u128;
+        let denominator = adjustment.max_change_denominator.max(1) as u128;
+        let delta = prev_limit * used.abs_diff(target) / target / denominator;
+
+        let next = if used > target {
+            // Always grow when above target, not to stall on small limits
+            prev_limit + delta.max(1)
+        } else {
+            prev_limit - delta.min(prev_limit)
+        };
+        clamp(next)
+    }
+
+    /// Returns whether the limit depends on the previous block.
+    pub fn is_dynamic(&self) -> bool {
+        self.adjustment.is_some()
+    }
+}
+
+/// Returns the gas limit of the block built upon `prev`.
+///
+/// Until the gas limit upgrade is activated, the limit is the configured one,
+/// as set by the generators before.
+pub(crate) fn next_gas_limit<L: Ledger>(
+    ledger: &L,
+    rules: &ChainRules,
+    prev: &Header,
+) -> anyhow::Result<u64> {
+    let rule = &rules.gas_limit;
+    if !rule.is_dynamic()
+        || !rules.upgrades.is_active(GAS_LIMIT, prev.height + 1)
+    {
+        return Ok(rule.limit);
+    }
+
+    let gas_used = match ledger.block_gas_used(&prev.hash)? {
+        Some(gas_used) => gas_used,
+        // The gas used by the blocks stored before it was recorded is summed
+        // from their transactions
+        None => {
+            let Some(block) = ledger.light_block(&prev.hash)? else {
+                anyhow::bail!("block {} not found", prev.height);
+            };
+            let mut gas_used = 0u64;
+            for id in &block.transactions_ids {
+                if let Some(spent_tx) = ledger.ledger_tx(id)? {
+                    gas_used = gas_used.saturating_add(spent_tx.gas_spent);
+                }
+            }
+            gas_used
+        }
+    };
+
+    Ok(rule.next(prev.gas_limit, gas_used))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADJUSTED: GasLimitRule = GasLimitRule {
+        limit: 1_000_000,
+        adjustment: Some(GasLimitAdjustment {
+            target_utilization: 50,
+            max_change_denominator: 8,
+            min_limit: 500_000,
+            max_limit: 2_000_000,
+        }),
+    };
+
+    #[test]
+    fn fixed_limit() {
+        let rule = GasLimitRule::fixed(1_000);
+        assert_eq!(rule.next(0, 0), 1_000);
+        assert_eq!(rule.next(2_000, 2_000), 1_000);
+    }
+
+    #[test]
+    fn adjusted_limit() {
+        // From the genesis block
+        assert_eq!(ADJUSTED.next(0, 0), 1_000_000);
+
+        // At target
+        assert_eq!(ADJUSTED.next(1_000_000, 500_000), 1_000_000);
+
+        // Full and empty blocks move the limit by the maximum change
+        assert_eq!(ADJUSTED.next(1_000_000, 1_000_000), 1_125_000);
+        assert_eq!(ADJUSTED.next(1_000_000, 0), 875_000);
+
+        // Proportionally in between
+        assert_eq!(ADJUSTED.next(1_000_000, 750_000), 1_062_500);
+
+        // Within bounds
+        assert_eq!(ADJUSTED.next(1_950_000, 1_950_000), 2_000_000);
+        assert_eq!(ADJUSTED.next(510_000, 0), 500_000);
+        assert_eq!(ADJUSTED.next(5_000_000, 2_500_000), 2_000_000);
+    }
+}
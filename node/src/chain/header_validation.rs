@@ -11,7 +11,8 @@ use std::sync::Arc;
 use dusk_bytes::Serializable;
 use dusk_consensus::config::{
     is_emergency_block, is_emergency_iter, CONSENSUS_MAX_ITER,
-    MINIMUM_BLOCK_TIME, MIN_EMERGENCY_BLOCK_TIME, RELAX_ITERATION_THRESHOLD,
+    MAX_BLOCK_TIME_DRIFT, MEDIAN_TIME_BLOCKS, MINIMUM_BLOCK_TIME,
+    MIN_EMERGENCY_BLOCK_TIME, RELAX_ITERATION_THRESHOLD,
 };
 use dusk_consensus::errors::{
     AttestationError, FailedIterationError, HeaderError,
@@ -19,12 +20,11 @@ use dusk_consensus::errors::{
 use dusk_consensus::operations::Voter;
 use dusk_consensus::quorum::verifiers;
 use dusk_consensus::quorum::verifiers::QuorumResult;
-use dusk_consensus::user::committee::CommitteeSet;
+use dusk_consensus::user::committee::{Committee, CommitteeSet};
 use dusk_consensus::user::provisioners::{ContextProvisioners, Provisioners};
 use dusk_core::signatures::bls::{
     MultisigPublicKey, MultisigSignature, PublicKey as BlsPublicKey,
 };
-use dusk_core::stake::EPOCH;
 use hex;
 use node_data::bls::PublicKeyBytes;
 use node_data::ledger::{Fault, InvalidFault, Seed, Signature};
@@ -35,22 +35,34 @@ use thiserror::Error;
 use tokio::sync::RwLock;
 use tracing::{debug, error};
 
+use crate::chain::gas_limit::{next_gas_limit, GasLimitRule};
+use crate::chain::upgrades::{UpgradeSchedule, GAS_LIMIT, MEDIAN_TIME_PAST};
 use crate::database;
 use crate::database::Ledger;
 
-const MARGIN_TIMESTAMP: u64 = 3;
-
 // TODO: Use thiserror instead of anyhow
 
 #[derive(Debug, Error)]
 enum HeaderVerificationErr {}
 
+/// The rules of the chain the headers are validated against.
+#[derive(Debug, Clone)]
+pub struct ChainRules {
+    /// Rule setting the gas limit of the blocks
+    pub gas_limit: GasLimitRule,
+    /// Protocol upgrades declared for the network
+    pub upgrades: Arc<UpgradeSchedule>,
+    /// Number of blocks in an epoch of the stake contract
+    pub epoch: u64,
+}
+
 /// An implementation of the all validation checks of a candidate block header
 /// according to current context
 pub(crate) struct Validator<'a, DB: database::DB> {
     pub(crate) db: Arc<RwLock<DB>>,
     prev_header: &'a ledger::Header,
     provisioners: &'a ContextProvisioners,
+    rules: &'a ChainRules,
 }
 
 impl<'a, DB: database::DB> Validator<'a, DB> {
@@ -58,11 +70,13 @@ impl<'a, DB: database::DB> Validator<'a, DB> {
         db: Arc<RwLock<DB>>,
         prev_header: &'a ledger::Header,
         provisioners: &'a ContextProvisioners,
+        rules: &'a ChainRules,
     ) -> Self {
         Self {
             db,
             prev_header,
             provisioners,
+            rules,
         }
     }
 
@@ -81,15 +95,48 @@ impl<'a, DB: database::DB> Validator<'a, DB> {
         expected_generator: &PublicKeyBytes,
         check_attestation: bool,
     ) -> Result<(u8, Vec<Voter>, Vec<Voter>), HeaderError> {
+        self.verify_fields(header, expected_generator).await?;
+        let (pni, prev_block_voters, block_voters, _) =
+            self.verify_attestations(header, check_attestation).await?;
+        Ok((pni, prev_block_voters, block_voters))
+    }
+
+    /// Verifies the block signature and any non-attestation field
+    pub async fn verify_fields(
+        &self,
+        header: &ledger::Header,
+        expected_generator: &PublicKeyBytes,
+    ) -> Result<(), HeaderError> {
         let generator =
             self.verify_block_generator(header, expected_generator)?;
-        self.verify_basic_fields(header, &generator).await?;
+        self.verify_basic_fields(header, &generator).await
+    }
 
+    /// Verifies the attestations of the header, these being the previous
+    /// block attestation, the winning one (if `check_attestation`) and the
+    /// failed iterations ones.
+    ///
+    /// Only the header of the block preceding `prev_header` is read from the
+    /// ledger, so that the attestations can be verified before
+    /// `prev_header` is accepted.
+    ///
+    /// Returns the same tuple as [`Self::execute_checks`], along with the
+    /// committees of the winning attestation (if `check_attestation`)
+    pub async fn verify_attestations(
+        &self,
+        header: &ledger::Header,
+        check_attestation: bool,
+    ) -> Result<
+        (u8, Vec<Voter>, Vec<Voter>, Option<AttestationCommittees>),
+        HeaderError,
+    > {
         let prev_block_voters = self.verify_prev_block_cert(header).await?;
 
         let mut block_voters = vec![];
+        let mut block_committees = None;
         if check_attestation {
-            (_, _, block_voters) = verify_att(
+            let committees;
+            (_, _, block_voters, committees) = verify_att(
                 &header.att,
                 header.to_consensus_header(),
                 self.prev_header.seed,
@@ -97,10 +144,11 @@ impl<'a, DB: database::DB> Validator<'a, DB> {
                 Some(RatificationResult::Success(Vote::Valid(header.hash))),
             )
             .await?;
+            block_committees = Some(committees);
         }
 
         let pni = self.verify_failed_iterations(header).await?;
-        Ok((pni, prev_block_voters, block_voters))
+        Ok((pni, prev_block_voters, block_voters, block_committees))
     }
 
     fn verify_block_generator(
@@ -166,12 +214,25 @@ impl<'a, DB: database::DB> Validator<'a, DB> {
         }
 
         // Ensure rule of minimum block time is addressed
-        if candidate_block.timestamp
-            < self.prev_header.timestamp + *MINIMUM_BLOCK_TIME
-        {
+        let min_timestamp = self.prev_header.timestamp + *MINIMUM_BLOCK_TIME;
+        if candidate_block.timestamp < min_timestamp {
             return Err(HeaderError::BlockTimeLess);
         }
 
+        // The median time past only bounds the timestamp further when the
+        // minimum block time does not already exceed it
+        if self
+            .rules
+            .upgrades
+            .is_active(MEDIAN_TIME_PAST, candidate_block.height)
+            && min_timestamp <= self.prev_header.timestamp
+        {
+            let median = self.median_time_past().await?;
+            if candidate_block.timestamp <= median {
+                return Err(HeaderError::BlockTimeBeforeMedian(median));
+            }
+        }
+
         // The Emergency Block can only be produced after all iterations in a
         // round have failed. To ensure Dusk (or anyone in possess of the Dusk
         // private key) is not able to shortcircuit a round with an arbitrary
@@ -189,7 +250,7 @@ impl<'a, DB: database::DB> Validator<'a, DB> {
 
         let local_time = get_current_timestamp();
 
-        if candidate_block.timestamp > local_time + MARGIN_TIMESTAMP {
+        if candidate_block.timestamp > local_time + *MAX_BLOCK_TIME_DRIFT {
             return Err(HeaderError::BlockTimeHigher(
                 candidate_block.timestamp,
             ));
@@ -199,6 +260,29 @@ impl<'a, DB: database::DB> Validator<'a, DB> {
             return Err(HeaderError::PrevBlockHash);
         }
 
+        // Before the upgrade, the gas limit was set by the generator
+        if self
+            .rules
+            .upgrades
+            .is_active(GAS_LIMIT, candidate_block.height)
+        {
+            let gas_limit = self
+                .db
+                .read()
+                .await
+                .view(|db| next_gas_limit(db, self.rules, self.prev_header))
+                .map_err(|e| {
+                    HeaderError::Storage("error computing gas limit", e)
+                })?;
+
+            if candidate_block.gas_limit != gas_limit {
+                return Err(HeaderError::InvalidGasLimit(
+                    gas_limit,
+                    candidate_block.gas_limit,
+                ));
+            }
+        }
+
         // Ensure block is not already in the ledger
         let block_exists = self
             .db
@@ -222,6 +306,38 @@ impl<'a, DB: database::DB> Validator<'a, DB> {
         Ok(())
     }
 
+    /// Returns the median timestamp of the last [`MEDIAN_TIME_BLOCKS`] blocks
+    /// up to `prev_header`.
+    ///
+    /// The blocks are walked back through their parent hashes, so that the
+    /// median is computed on the branch of `prev_header` even before it is
+    /// accepted.
+    async fn median_time_past(&self) -> Result<u64, HeaderError> {
+        let mut timestamps = vec![self.prev_header.timestamp];
+
+        self.db
+            .read()
+            .await
+            .view(|db| -> anyhow::Result<()> {
+                let mut prev_hash = self.prev_header.prev_block_hash;
+                let mut height = self.prev_header.height;
+                while height > 0 && timestamps.len() < MEDIAN_TIME_BLOCKS {
+                    let Some(header) = db.block_header(&prev_hash)? else {
+                        break;
+                    };
+                    timestamps.push(header.timestamp);
+                    prev_hash = header.prev_block_hash;
+                    height = header.height;
+                }
+                Ok(())
+            })
+            .map_err(|e| {
+                HeaderError::Storage("error reading median time past", e)
+            })?;
+
+        Ok(median(&mut timestamps))
+    }
+
     fn verify_seed_field(
         &self,
         seed: &[u8; 48],
@@ -267,7 +383,7 @@ impl<'a, DB: database::DB> Validator<'a, DB> {
             .ok_or(HeaderError::Generic("Header not found"))
             .map(|h| h.seed)?;
 
-        let (_, _, voters) = verify_att(
+        let (_, _, voters, _) = verify_att(
             &candidate_block.prev_block_cert,
             self.prev_header.to_consensus_header(),
             prev_block_seed,
@@ -376,17 +492,19 @@ impl<'a, DB: database::DB> Validator<'a, DB> {
         current_height: u64,
         faults: &[Fault],
     ) -> Result<(), InvalidFault> {
-        verify_faults(self.db.clone(), current_height, faults).await
+        verify_faults(self.db.clone(), current_height, self.rules.epoch, faults)
+            .await
     }
 }
 
 pub async fn verify_faults<DB: database::DB>(
     db: Arc<RwLock<DB>>,
     current_height: u64,
+    epoch: u64,
     faults: &[Fault],
 ) -> Result<(), InvalidFault> {
     for f in faults {
-        let fault_header = f.validate(current_height)?;
+        let fault_header = f.validate(current_height, epoch)?;
         if is_emergency_iter(fault_header.iteration) {
             return Err(InvalidFault::EmergencyIteration);
         }
@@ -405,7 +523,7 @@ pub async fn verify_faults<DB: database::DB>(
                 // FIX_ME: Instead of fetching all store faults, check the fault
                 // id directly This needs the fault id to be
                 // changed into "HEIGHT|TYPE|PROV_KEY"
-                let start_height = fault_header.round.saturating_sub(EPOCH);
+                let start_height = fault_header.round.saturating_sub(epoch);
                 let stored_faults = db.faults_by_block(start_height)?;
                 if stored_faults.iter().any(|other| f.same(other)) {
                     anyhow::bail!("Double fault detected");
@@ -424,7 +542,15 @@ pub async fn verify_att(
     curr_seed: Signature,
     curr_eligible_provisioners: &Provisioners,
     expected_result: Option<RatificationResult>,
-) -> Result<(QuorumResult, QuorumResult, Vec<Voter>), AttestationError> {
+) -> Result<
+    (
+        QuorumResult,
+        QuorumResult,
+        Vec<Voter>,
+        AttestationCommittees,
+    ),
+    AttestationError,
+> {
     // Check expected result
     if let Some(expected) = expected_result {
         match (att.result, expected) {
@@ -457,31 +583,46 @@ pub async fn verify_att(
     let vote = att.result.vote();
 
     // Verify validation
-    let (val_result, validation_voters) = verifiers::verify_step_votes(
-        &consensus_header,
-        vote,
-        &att.validation,
-        &committee,
-        curr_seed,
-        StepName::Validation,
-    )
-    .await
-    .map_err(|s| AttestationError::InvalidVotes(StepName::Validation, s))?;
+    let (val_result, validation_voters, validation_committee) =
+        verifiers::verify_step_votes(
+            &consensus_header,
+            vote,
+            &att.validation,
+            &committee,
+            curr_seed,
+            StepName::Validation,
+        )
+        .await
+        .map_err(|s| AttestationError::InvalidVotes(StepName::Validation, s))?;
 
     // Verify ratification
-    let (rat_result, ratification_voters) = verifiers::verify_step_votes(
-        &consensus_header,
-        vote,
-        &att.ratification,
-        &committee,
-        curr_seed,
-        StepName::Ratification,
-    )
-    .await
-    .map_err(|s| AttestationError::InvalidVotes(StepName::Ratification, s))?;
+    let (rat_result, ratification_voters, ratification_committee) =
+        verifiers::verify_step_votes(
+            &consensus_header,
+            vote,
+            &att.ratification,
+            &committee,
+            curr_seed,
+            StepName::Ratification,
+        )
+        .await
+        .map_err(|s| {
+            AttestationError::InvalidVotes(StepName::Ratification, s)
+        })?;
 
     let voters = merge_voters(validation_voters, ratification_voters);
-    Ok((val_result, rat_result, voters))
+    let committees = AttestationCommittees {
+        validation: validation_committee,
+        ratification: ratification_committee,
+    };
+    Ok((val_result, rat_result, voters, committees))
+}
+
+/// Committees of the steps of an attestation, drawn to verify it.
+#[derive(Debug, Clone, Default)]
+pub struct AttestationCommittees {
+    pub validation: Committee,
+    pub ratification: Committee,
 }
 
 /// Merges two Vec<Voter>, summing up the usize values if the PublicKey is
@@ -496,3 +637,25 @@ fn merge_voters(v1: Vec<Voter>, v2: Vec<Voter>) -> Vec<Voter> {
 
     voter_map.into_iter().collect()
 }
+
+/// Returns the median of `timestamps`, the lower one for an even count.
+fn median(timestamps: &mut [u64]) -> u64 {
+    timestamps.sort_unstable();
+    timestamps
+        .get((timestamps.len().max(1) - 1) / 2)
+        .copied()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::median;
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&mut []), 0);
+        assert_eq!(median(&mut [7]), 7);
+        assert_eq!(median(&mut [30, 10, 20]), 20);
+        assert_eq!(median(&mut [40, 10, 30, 20]), 20);
+    }
+}
@@ -0,0 +1,174 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Write-ahead journal of the block acceptance.
+//!
+//! Accepting a block commits the VM state, stores the block in the ledger
+//! and finalizes the VM state, none of which is atomic with the others. The
+//! intent of accepting a block is recorded before, and cleared after, so that
+//! an acceptance interrupted by a crash is detected and repaired at the next
+//! start.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::database::rocksdb::MD_ACCEPT_INTENT_KEY;
+use crate::database::{Ledger, Metadata, DB};
+
+/// Block being accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct AcceptIntent {
+    pub height: u64,
+    pub hash: [u8; 32],
+    pub prev_state_root: [u8; 32],
+    pub state_root: [u8; 32],
+}
+
+/// Records the intent of accepting a block, before any change is made.
+///
+/// The intent is synced to disk, as the VM commit following it is.
+pub(crate) fn begin<D: DB>(db: &D, intent: &AcceptIntent) -> Result<()> {
+    let intent = serde_json::to_vec(intent)?;
+    db.update_synced(|t| t.op_write(MD_ACCEPT_INTENT_KEY, intent))
+}
+
+/// Clears the intent once the acceptance is complete.
+pub(crate) fn complete<D: DB>(db: &D) -> Result<()> {
+    db.update_synced(|t| t.op_write(MD_ACCEPT_INTENT_KEY, b""))
+}
+
+/// Returns the intent of an acceptance that didn't complete, if any.
+pub(crate) fn pending<D: DB>(db: &D) -> Result<Option<AcceptIntent>> {
+    let intent = db.view(|t| t.op_read(MD_ACCEPT_INTENT_KEY))?;
+    match intent {
+        Some(intent) if !intent.is_empty() => {
+            Ok(Some(serde_json::from_slice(&intent)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Returns whether the block of `intent` is stored in the ledger, in which
+/// case it is the tip, the ledger being updated atomically with it.
+pub(crate) fn is_stored<D: DB>(db: &D, intent: &AcceptIntent) -> Result<bool> {
+    db.view(|t| {
+        Ok(t.block_label_by_height(intent.height)?
+            .is_some_and(|(hash, _)| hash == intent.hash))
+    })
+}
+
+/// Returns the state roots to merge when finalizing the state root of the
+/// last final block, at `final_height`: the ones of the blocks following the
+/// block of `vm_final_root`, the last state root finalized by the VM,
+/// followed by `vm_final_root` itself.
+pub(crate) fn finals_to_merge<D: DB>(
+    db: &D,
+    vm_final_root: [u8; 32],
+    final_height: u64,
+) -> Result<Vec<[u8; 32]>> {
+    db.view(|t| {
+        let mut to_merge = vec![];
+        for height in (0..final_height).rev() {
+            let header = t
+                .block_hash_by_height(height)?
+                .and_then(|hash| t.block_header(&hash).transpose())
+                .transpose()?
+                .ok_or_else(|| anyhow!("missing block at height {height}"))?;
+            if header.state_hash == vm_final_root {
+                to_merge.reverse();
+                to_merge.push(vm_final_root);
+                return Ok(to_merge);
+            }
+            to_merge.push(header.state_hash);
+        }
+        Err(anyhow!("state root finalized by the VM not in the ledger"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::{Fake, Faker};
+    use node_data::ledger::{Header, Label};
+
+    use super::*;
+    use crate::database::rocksdb::Backend;
+    use crate::database::DatabaseOptions;
+
+    fn store_header(db: &Backend, height: u64, hash: u8) -> Header {
+        let mut header: Header = Faker.fake();
+        header.height = height;
+        header.hash = [hash; 32];
+        header.state_hash = [hash + 100; 32];
+        db.update(|t| {
+            t.store_block(&header, &[], &[], Label::Final(0))?;
+            Ok(())
+        })
+        .unwrap();
+        header
+    }
+
+    #[test]
+    fn test_intent_lifecycle() {
+        let dir = tempdir::TempDir::new("test_intent_lifecycle")
+            .expect("Temp directory to be created");
+        let db =
+            Backend::create_or_open(dir.path(), DatabaseOptions::default());
+        assert_eq!(pending(&db).unwrap(), None);
+
+        let intent = AcceptIntent {
+            height: 7,
+            hash: [1; 32],
+            prev_state_root: [2; 32],
+            state_root: [3; 32],
+        };
+        begin(&db, &intent).unwrap();
+        assert_eq!(pending(&db).unwrap(), Some(intent));
+        assert!(!is_stored(&db, &intent).unwrap());
+
+        complete(&db).unwrap();
+        assert_eq!(pending(&db).unwrap(), None);
+    }
+
+    #[test]
+    fn test_interrupted_acceptance() {
+        let dir = tempdir::TempDir::new("test_interrupted_acceptance")
+            .expect("Temp directory to be created");
+        let db =
+            Backend::create_or_open(dir.path(), DatabaseOptions::default());
+        let headers: Vec<_> =
+            (0..4).map(|h| store_header(&db, h, h as u8)).collect();
+
+        // Crash before the block is stored: the acceptance is rolled back
+        let intent = AcceptIntent {
+            height: 4,
+            hash: [4; 32],
+            prev_state_root: headers[3].state_hash,
+            state_root: [104; 32],
+        };
+        begin(&db, &intent).unwrap();
+        assert_eq!(pending(&db).unwrap(), Some(intent));
+        assert!(!is_stored(&db, &intent).unwrap());
+
+        // Crash after the block is stored: the acceptance is rolled forward,
+        // merging all the state roots the VM did not finalize
+        let stored = store_header(&db, 4, 4);
+        assert_eq!(stored.state_hash, intent.state_root);
+        assert_eq!(pending(&db).unwrap(), Some(intent));
+        assert!(is_stored(&db, &intent).unwrap());
+
+        let vm_final_root = headers[1].state_hash;
+        let to_merge = finals_to_merge(&db, vm_final_root, 4).unwrap();
+        assert_eq!(
+            to_merge,
+            vec![headers[2].state_hash, headers[3].state_hash, vm_final_root]
+        );
+        assert_eq!(
+            finals_to_merge(&db, headers[3].state_hash, 4).unwrap(),
+            vec![headers[3].state_hash]
+        );
+        assert!(finals_to_merge(&db, [0; 32], 4).is_err());
+    }
+}
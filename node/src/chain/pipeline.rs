@@ -0,0 +1,208 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Staged acceptance of consecutive blocks, as received while syncing.
+//!
+//! The blocks, already decoded from the wire, flow through a bounded channel
+//! from the verification of their header attestations to the state
+//! transition and commit by the acceptor. This way the attestations of the
+//! next block are verified while the current one is executed by the VM.
+//!
+//! The attestations are verified with the provisioners of the latest
+//! accepted block. They are then reused only if no block in between changed
+//! the provisioners, otherwise the block header is fully verified at
+//! acceptance.
+
+use std::sync::Arc;
+
+use dusk_consensus::errors::HeaderError;
+use dusk_consensus::operations::Voter;
+use dusk_consensus::user::provisioners::ContextProvisioners;
+use metrics::counter;
+use node_data::ledger::{Block, Hash, Header};
+use tokio::sync::{mpsc, watch, RwLock};
+use tracing::debug;
+
+use super::acceptor::{expected_generator, Acceptor};
+use super::header_validation::{AttestationCommittees, Validator};
+use super::ChainRules;
+use crate::{database, vm, Network};
+
+/// Blocks queued between the header verification and the state transition
+const STAGE_CAPACITY: usize = 2;
+
+/// Attestations of a block header, verified on top of `prev_block_hash`
+pub(crate) struct PreverifiedHeader {
+    pub prev_block_hash: Hash,
+    pub pni: u8,
+    pub prev_block_voters: Vec<Voter>,
+    pub tip_block_voters: Vec<Voter>,
+    pub tip_block_committees: Option<AttestationCommittees>,
+}
+
+/// Tip height of the state transition stage, along with the provisioners
+/// needed to verify the next block
+struct Snapshot {
+    height: u64,
+    provisioners: ContextProvisioners,
+}
+
+struct StagedBlock {
+    block: Block,
+    /// Height of the snapshot the attestations are verified with
+    snapshot: u64,
+    preverified: Option<PreverifiedHeader>,
+}
+
+/// Accepts the consecutive `blocks` on top of the tip.
+///
+/// Returns the number of accepted blocks, along with the error that stopped
+/// the acceptance, if any.
+pub(crate) async fn accept_blocks<
+    N: Network,
+    DB: database::DB,
+    VM: vm::VMExecution,
+>(
+    acc: &mut Acceptor<N, DB, VM>,
+    blocks: Vec<Block>,
+) -> (usize, anyhow::Result<()>) {
+    let tip = acc.tip_header().await;
+    let rules = match acc.vm.read().await.chain_rules() {
+        Ok(rules) => rules,
+        Err(e) => return (0, Err(e)),
+    };
+    let (snapshot_tx, snapshot_rx) = watch::channel(Snapshot {
+        height: tip.height,
+        provisioners: acc.provisioners_list.read().await.clone(),
+    });
+    let (staged_tx, mut staged_rx) = mpsc::channel(STAGE_CAPACITY);
+
+    let verifier = tokio::spawn(verify_headers(
+        acc.db.clone(),
+        rules,
+        tip,
+        blocks,
+        snapshot_rx,
+        staged_tx,
+    ));
+
+    let mut accepted = 0;
+    let mut res = Ok(());
+    // Height of the last accepted block changing the provisioners
+    let mut last_change = 0;
+
+    while let Some(staged) = staged_rx.recv().await {
+        let height = staged.block.header().height;
+        let preverified = staged
+            .preverified
+            .filter(|_| staged.snapshot >= last_change);
+        match preverified {
+            Some(_) => counter!("dusk_pipeline_preverified").increment(1),
+            None => counter!("dusk_pipeline_not_preverified").increment(1),
+        }
+
+        match acc.accept_block(&staged.block, preverified, false).await {
+            Ok(outcome) if outcome.provisioners_changed => {
+                accepted += 1;
+                last_change = height;
+                let provisioners = acc.provisioners_list.read().await.clone();
+                snapshot_tx.send_replace(Snapshot {
+                    height,
+                    provisioners,
+                });
+            }
+            Ok(_) => {
+                accepted += 1;
+                snapshot_tx.send_modify(|s| {
+                    s.height = height;
+                    s.provisioners.remove_previous();
+                });
+            }
+            Err(err) => {
+                res = Err(err);
+                break;
+            }
+        }
+    }
+
+    // Stop the header verification, if still running
+    drop(staged_rx);
+    drop(snapshot_tx);
+    let _ = verifier.await;
+
+    (accepted, res)
+}
+
+/// Verifies the attestations of the blocks on top of `prev_header`, one
+/// block ahead of the state transition stage.
+async fn verify_headers<DB: database::DB>(
+    db: Arc<RwLock<DB>>,
+    rules: ChainRules,
+    mut prev_header: Header,
+    blocks: Vec<Block>,
+    mut snapshot_rx: watch::Receiver<Snapshot>,
+    staged_tx: mpsc::Sender<StagedBlock>,
+) {
+    for block in blocks {
+        let height = block.header().height;
+
+        // The header preceding `prev_header` must be in the ledger
+        let (snapshot, provisioners) = {
+            let Ok(s) = snapshot_rx.wait_for(|s| s.height + 2 >= height).await
+            else {
+                break;
+            };
+            // Without changes in between, the provisioners of an earlier
+            // tip are the same for current and previous block
+            let provisioners = if s.height + 1 == height {
+                s.provisioners.clone()
+            } else {
+                ContextProvisioners::new(s.provisioners.to_current())
+            };
+            (s.height, provisioners)
+        };
+
+        let preverified =
+            preverify(&db, &prev_header, &provisioners, &rules, block.header())
+                .await
+                .map_err(|err| {
+                    debug!(event = "header preverify failed", height, ?err)
+                })
+                .ok();
+
+        prev_header = block.header().clone();
+        let staged = StagedBlock {
+            block,
+            snapshot,
+            preverified,
+        };
+        if staged_tx.send(staged).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn preverify<DB: database::DB>(
+    db: &Arc<RwLock<DB>>,
+    prev_header: &Header,
+    provisioners: &ContextProvisioners,
+    rules: &ChainRules,
+    header: &Header,
+) -> Result<PreverifiedHeader, HeaderError> {
+    let (_, check_att) = expected_generator(prev_header, provisioners, header);
+    let validator =
+        Validator::new(db.clone(), prev_header, provisioners, rules);
+    let (pni, prev_block_voters, tip_block_voters, tip_block_committees) =
+        validator.verify_attestations(header, check_att).await?;
+
+    Ok(PreverifiedHeader {
+        prev_block_hash: prev_header.hash,
+        pni,
+        prev_block_voters,
+        tip_block_voters,
+        tip_block_committees,
+    })
+}
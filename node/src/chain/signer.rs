@@ -0,0 +1,311 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Delegation of the consensus signatures to a remote signer, so that the
+//! secret key of the provisioner can be kept by an HSM-backed service.
+//!
+//! Each signature is requested over a new TCP connection. Both the request
+//! and the response are authenticated with a BLAKE3 keyed hash, under a key
+//! shared by the node and the signer:
+//!
+//! ```text
+//! request:  public_key[96] | nonce[32] | len: u32 LE | msg[len] | mac[32]
+//!           mac = keyed_hash(key, public_key | nonce | msg)
+//! response: status: u8 | len: u32 LE | payload[len] | mac[32]
+//!           mac = keyed_hash(key, nonce | status | payload)
+//! ```
+//!
+//! A status of 0 carries the 48 bytes signature, any other an UTF-8 reason.
+//! The signature is verified by the node before being used.
+
+use std::fs;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use dusk_bytes::Serializable;
+use dusk_consensus::signer::{LocalSigner, Signer, SignerError};
+use dusk_core::signatures::bls::{
+    MultisigPublicKey as BlsMultisigPublicKey,
+    MultisigSignature as BlsMultisigSignature,
+};
+use node_data::bls::PublicKey;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tracing::warn;
+
+const MAC_SIZE: usize = 32;
+const MAX_RESPONSE_SIZE: u32 = 1024;
+
+/// Configuration of the remote signer of the consensus messages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteSignerConfig {
+    /// Address of the signer
+    pub address: String,
+    /// Base58 BLS public key of the provisioner
+    pub public_key: PublicKey,
+    /// File holding the hex encoded 32 bytes key shared with the signer
+    pub auth_key_path: PathBuf,
+    /// Maximum time to get a signature
+    #[serde(with = "humantime_serde", default = "default_timeout")]
+    pub timeout: Duration,
+    /// Whether the consensus keys are loaded too, to sign locally when the
+    /// remote signer fails
+    #[serde(default)]
+    pub local_fallback: bool,
+}
+
+fn default_timeout() -> Duration {
+    Duration::from_millis(500)
+}
+
+pub(crate) struct RemoteSigner {
+    addr: SocketAddr,
+    pk: PublicKey,
+    auth_key: [u8; 32],
+    timeout: Duration,
+    fallback: Option<LocalSigner>,
+    requests: AtomicU64,
+}
+
+impl std::fmt::Debug for RemoteSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteSigner")
+            .field("addr", &self.addr)
+            .field("pk", &self.pk)
+            .field("fallback", &self.fallback.is_some())
+            .finish()
+    }
+}
+
+impl RemoteSigner {
+    pub(crate) fn new(
+        conf: &RemoteSignerConfig,
+        fallback: Option<LocalSigner>,
+    ) -> anyhow::Result<Self> {
+        let addr = conf.address.to_socket_addrs()?.next().ok_or_else(|| {
+            anyhow::anyhow!("Invalid address {}", conf.address)
+        })?;
+
+        let auth_key = fs::read_to_string(&conf.auth_key_path)?;
+        let mut key = [0u8; 32];
+        hex::decode_to_slice(auth_key.trim(), &mut key)
+            .map_err(|e| anyhow::anyhow!("Invalid signer auth key: {e}"))?;
+
+        Ok(Self {
+            addr,
+            pk: conf.public_key.clone(),
+            auth_key: key,
+            timeout: conf.timeout,
+            fallback,
+            requests: AtomicU64::new(0),
+        })
+    }
+
+    /// Returns a value unique to the request, binding the response to it.
+    fn nonce(&self) -> [u8; 32] {
+        let count = self.requests.fetch_add(1, Ordering::Relaxed);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&count.to_le_bytes());
+        hasher.update(&now.to_le_bytes());
+        hasher.update(self.pk.bytes().inner());
+        *hasher.finalize().as_bytes()
+    }
+
+    /// Requests the signature of `msg`, within the configured timeout.
+    async fn request(&self, msg: &[u8]) -> Result<[u8; 48], SignerError> {
+        tokio::time::timeout(self.timeout, self.exchange(msg))
+            .await
+            .map_err(|_| {
+                SignerError::Unreachable(format!("{}: timed out", self.addr))
+            })?
+    }
+
+    async fn exchange(&self, msg: &[u8]) -> Result<[u8; 48], SignerError> {
+        let unreachable = |e: std::io::Error| {
+            SignerError::Unreachable(format!("{}: {e}", self.addr))
+        };
+
+        let nonce = self.nonce();
+        let pk = self.pk.bytes().inner();
+
+        let mut mac = blake3::Hasher::new_keyed(&self.auth_key);
+        mac.update(pk).update(&nonce).update(msg);
+
+        let mut request = Vec::with_capacity(msg.len() + 164);
+        request.extend_from_slice(pk);
+        request.extend_from_slice(&nonce);
+        request.extend_from_slice(&(msg.len() as u32).to_le_bytes());
+        request.extend_from_slice(msg);
+        request.extend_from_slice(mac.finalize().as_bytes());
+
+        let mut stream =
+            TcpStream::connect(self.addr).await.map_err(unreachable)?;
+        stream.write_all(&request).await.map_err(unreachable)?;
+
+        let mut head = [0u8; 5];
+        stream.read_exact(&mut head).await.map_err(unreachable)?;
+        let status = head[0];
+        let len = u32::from_le_bytes(head[1..].try_into().expect("4 bytes"));
+        if len > MAX_RESPONSE_SIZE {
+            return Err(SignerError::Refused("response too large".into()));
+        }
+
+        let mut payload = vec![0u8; len as usize];
+        stream.read_exact(&mut payload).await.map_err(unreachable)?;
+        let mut received_mac = [0u8; MAC_SIZE];
+        stream
+            .read_exact(&mut received_mac)
+            .await
+            .map_err(unreachable)?;
+
+        let mut mac = blake3::Hasher::new_keyed(&self.auth_key);
+        mac.update(&nonce).update(&[status]).update(&payload);
+        // The comparison of blake3 hashes is constant time
+        if mac.finalize() != blake3::Hash::from(received_mac) {
+            return Err(SignerError::Refused("invalid response MAC".into()));
+        }
+
+        if status != 0 {
+            let reason = String::from_utf8_lossy(&payload).into_owned();
+            return Err(SignerError::Refused(reason));
+        }
+
+        let signature: [u8; 48] = payload
+            .try_into()
+            .map_err(|_| SignerError::Refused("invalid signature".into()))?;
+        self.verify(msg, &signature)?;
+
+        Ok(signature)
+    }
+
+    /// Checks the signature returned by the signer is valid for our key.
+    fn verify(
+        &self,
+        msg: &[u8],
+        signature: &[u8; 48],
+    ) -> Result<(), SignerError> {
+        let invalid = |_| SignerError::Refused("invalid signature".into());
+
+        let sig =
+            BlsMultisigSignature::from_bytes(signature).map_err(invalid)?;
+        let pk = BlsMultisigPublicKey::aggregate(&[*self.pk.inner()])
+            .map_err(invalid)?;
+        pk.verify(&sig, msg).map_err(invalid)
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    async fn sign_multisig(&self, msg: &[u8]) -> Result<[u8; 48], SignerError> {
+        match (self.request(msg).await, &self.fallback) {
+            (Ok(signature), _) => Ok(signature),
+            (Err(err), Some(fallback)) => {
+                warn!(event = "remote signer failed, signing locally", %err);
+                fallback.sign_multisig(msg).await
+            }
+            (Err(err), None) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use dusk_core::signatures::bls::{
+        PublicKey as BlsPublicKey, SecretKey as BlsSecretKey,
+    };
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    const AUTH_KEY: [u8; 32] = [7; 32];
+
+    /// Serves a single signature request with `sk`.
+    fn serve(listener: TcpListener, signer: LocalSigner) {
+        let (mut stream, _) = listener.accept().unwrap();
+
+        let mut head = [0u8; 96 + 32 + 4];
+        stream.read_exact(&mut head).unwrap();
+        let nonce = &head[96..128];
+        let len = u32::from_le_bytes(head[128..].try_into().unwrap());
+        let mut msg = vec![0u8; len as usize];
+        stream.read_exact(&mut msg).unwrap();
+        let mut mac = [0u8; MAC_SIZE];
+        stream.read_exact(&mut mac).unwrap();
+
+        let mut expected = blake3::Hasher::new_keyed(&AUTH_KEY);
+        expected.update(&head[..96]).update(nonce).update(&msg);
+        assert_eq!(expected.finalize(), blake3::Hash::from(mac));
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let signature = runtime.block_on(signer.sign_multisig(&msg)).unwrap();
+        let mut mac = blake3::Hasher::new_keyed(&AUTH_KEY);
+        mac.update(nonce).update(&[0]).update(&signature);
+
+        let mut response = vec![0];
+        response.extend_from_slice(&48u32.to_le_bytes());
+        response.extend_from_slice(&signature);
+        response.extend_from_slice(mac.finalize().as_bytes());
+        stream.write_all(&response).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer() {
+        let sk = BlsSecretKey::random(&mut StdRng::seed_from_u64(42));
+        let pk = BlsPublicKey::from(&sk);
+        let local = LocalSigner::new(sk, pk);
+
+        let dir = tempdir::TempDir::new("test_remote_signer").unwrap();
+        let auth_key_path = dir.path().join("signer.key");
+        fs::write(&auth_key_path, hex::encode(AUTH_KEY)).unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut conf = RemoteSignerConfig {
+            address: listener.local_addr().unwrap().to_string(),
+            public_key: PublicKey::new(pk),
+            auth_key_path,
+            timeout: Duration::from_secs(5),
+            local_fallback: false,
+        };
+
+        let server = {
+            let local = local.clone();
+            thread::spawn(move || serve(listener, local))
+        };
+        let signer = RemoteSigner::new(&conf, None).unwrap();
+        let signature = signer.sign_multisig(b"vote").await.unwrap();
+        server.join().unwrap();
+        assert_eq!(signature, local.sign_multisig(b"vote").await.unwrap());
+
+        // The signer is no longer listening
+        assert!(matches!(
+            signer.sign_multisig(b"vote").await,
+            Err(SignerError::Unreachable(_))
+        ));
+
+        conf.local_fallback = true;
+        let signer = RemoteSigner::new(&conf, Some(local.clone())).unwrap();
+        assert_eq!(
+            signer.sign_multisig(b"vote").await.unwrap(),
+            local.sign_multisig(b"vote").await.unwrap()
+        );
+    }
+}
@@ -0,0 +1,61 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Snapshots of the provisioner set at the start of every epoch, for light
+//! clients to verify the committees of past blocks without replaying the
+//! stake contract.
+//!
+//! A snapshot only holds for the rounds of the epoch preceding the first
+//! change to the stakes within it: unstakes and slashes take effect at once,
+//! while the new stakes only become eligible from a later epoch. The rounds
+//! following such a change draw their committees from the snapshot updated
+//! by the `unstake`, `slash` and `hard_slash` events of the stake contract
+//! emitted since the start of the epoch, which are not applied here.
+
+use anyhow::Result;
+use dusk_consensus::user::provisioners::Provisioners;
+
+use crate::database::{
+    ProvisionersSnapshot, ProvisionersStorage, SnapshotStake,
+};
+
+/// Returns the snapshot of `provisioners` at `round`, the start of `epoch`.
+pub(crate) fn snapshot(
+    epoch: u64,
+    round: u64,
+    provisioners: &Provisioners,
+) -> ProvisionersSnapshot {
+    let stakes = provisioners
+        .iter()
+        .map(|(pk, stake)| SnapshotStake {
+            key: *pk.bytes(),
+            value: stake.value(),
+            eligible_since: stake.eligible_since,
+        })
+        .collect();
+    ProvisionersSnapshot {
+        epoch,
+        round,
+        stakes,
+    }
+}
+
+/// Stores the provisioner set following the block at `height`, if it is
+/// the last block of an epoch of `epoch_len` blocks, as configured in the
+/// stake contract.
+pub(crate) fn record<T: ProvisionersStorage>(
+    db: &mut T,
+    height: u64,
+    epoch_len: u64,
+    provisioners: &Provisioners,
+) -> Result<()> {
+    let round = height + 1;
+    if round % epoch_len != 0 {
+        return Ok(());
+    }
+    let epoch = round / epoch_len;
+    db.store_provisioners_snapshot(&snapshot(epoch, round, provisioners))
+}
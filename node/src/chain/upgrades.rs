@@ -0,0 +1,168 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Protocol upgrades activated at a block height.
+//!
+//! The rule changes of a hard fork are declared in the genesis, with the
+//! height of the first block following them, so that all the nodes of a
+//! network share the same schedule. A rule is enforced only once its upgrade
+//! is activated, the blocks preceding it having been produced without it.
+//! From the activation height of an upgrade it does not implement, a node
+//! refuses to accept blocks and to take part in consensus, rather than
+//! following the chain under outdated rules.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Gas limit of the blocks set by the chain rule, rather than by the
+/// generator.
+pub const GAS_LIMIT: &str = "gas-limit";
+
+/// Timestamp of the blocks higher than the median time past.
+pub const MEDIAN_TIME_PAST: &str = "median-time-past";
+
+/// Gas reserved in the blocks to the calls of the protocol contracts.
+pub const BLOCK_SPACE: &str = "block-space";
+
+/// Expiry height of the Moonlight transactions.
+pub const TX_EXPIRY: &str = "tx-expiry";
+
+/// Epoch, maturity and minimum stake read from the stake contract.
+pub const STAKE_CONFIG: &str = "stake-config";
+
+/// Upgrades implemented by this version of the node.
+pub const SUPPORTED_UPGRADES: &[&str] = &[
+    GAS_LIMIT,
+    MEDIAN_TIME_PAST,
+    BLOCK_SPACE,
+    TX_EXPIRY,
+    STAKE_CONFIG,
+];
+
+/// A protocol upgrade, activated at a block height.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Upgrade {
+    /// Name identifying the rule changes of the upgrade
+    pub name: String,
+    /// Height of the first block following the rules of the upgrade
+    pub height: u64,
+}
+
+impl Upgrade {
+    /// Returns whether this version of the node implements the upgrade.
+    pub fn is_supported(&self) -> bool {
+        SUPPORTED_UPGRADES.contains(&self.name.as_str())
+    }
+}
+
+/// Error returned for a block following the rules of an upgrade this node
+/// does not implement.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error(
+    "upgrade {name} activated at height {height} is not supported, the node \
+     must be updated"
+)]
+pub struct UnsupportedUpgrade {
+    pub name: String,
+    pub height: u64,
+}
+
+/// The protocol upgrades declared for a network, by activation height.
+#[derive(Debug, Clone, Default)]
+pub struct UpgradeSchedule {
+    upgrades: Vec<Upgrade>,
+}
+
+impl UpgradeSchedule {
+    pub fn new(mut upgrades: Vec<Upgrade>) -> Self {
+        upgrades.sort_by_key(|u| u.height);
+        Self { upgrades }
+    }
+
+    /// Returns all the upgrades, by activation height.
+    pub fn upgrades(&self) -> &[Upgrade] {
+        &self.upgrades
+    }
+
+    /// Returns the upgrades activated after the block at `height`.
+    pub fn upcoming(&self, height: u64) -> impl Iterator<Item = &Upgrade> {
+        self.upgrades.iter().filter(move |u| u.height > height)
+    }
+
+    /// Returns the upgrades activated by the block at `height`.
+    pub fn activated_at(&self, height: u64) -> impl Iterator<Item = &Upgrade> {
+        self.upgrades.iter().filter(move |u| u.height == height)
+    }
+
+    /// Returns whether the rules of the upgrade `name` apply to the block at
+    /// `height`.
+    ///
+    /// An upgrade the network does not declare is never activated.
+    pub fn is_active(&self, name: &str, height: u64) -> bool {
+        self.upgrades
+            .iter()
+            .any(|u| u.name == name && u.height <= height)
+    }
+
+    /// Checks that this node implements the rules followed by a block at
+    /// `height`.
+    pub fn check(&self, height: u64) -> Result<(), UnsupportedUpgrade> {
+        match self
+            .upgrades
+            .iter()
+            .find(|u| u.height <= height && !u.is_supported())
+        {
+            Some(u) => Err(UnsupportedUpgrade {
+                name: u.name.clone(),
+                height: u.height,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsupported_upgrade_gates_height() {
+        let schedule = UpgradeSchedule::new(vec![
+            Upgrade {
+                name: "late".into(),
+                height: 200,
+            },
+            Upgrade {
+                name: "early".into(),
+                height: 100,
+            },
+        ]);
+
+        assert_eq!(schedule.upgrades()[0].name, "early");
+        assert_eq!(schedule.upcoming(100).count(), 1);
+        assert_eq!(schedule.activated_at(200).count(), 1);
+
+        assert!(schedule.check(99).is_ok());
+        let err = schedule.check(150).expect_err("unsupported upgrade");
+        assert_eq!(err.name, "early");
+        assert_eq!(err.height, 100);
+
+        assert!(UpgradeSchedule::default().check(u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn rules_active_from_height() {
+        let schedule = UpgradeSchedule::new(vec![Upgrade {
+            name: GAS_LIMIT.into(),
+            height: 100,
+        }]);
+
+        assert!(schedule.check(u64::MAX).is_ok());
+        assert!(!schedule.is_active(GAS_LIMIT, 99));
+        assert!(schedule.is_active(GAS_LIMIT, 100));
+        assert!(!schedule.is_active(MEDIAN_TIME_PAST, u64::MAX));
+    }
+}
@@ -0,0 +1,178 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::cmp;
+use std::collections::HashMap;
+
+use anyhow::Result;
+use dusk_consensus::config::{is_emergency_block, CONSENSUS_MAX_ITER};
+use dusk_consensus::user::provisioners::Provisioners;
+use node_data::bls::PublicKeyBytes;
+use node_data::ledger::{Header, Seed};
+
+use super::header_validation::AttestationCommittees;
+use crate::database::{ProvisionerUptime, UptimeStorage};
+
+/// Returns the participation of the provisioners extracted for the block of
+/// `header`, drawn from `provisioners` at the state of its parent with seed
+/// `prev_seed`.
+///
+/// The generators of the failed iterations are counted as selected, while
+/// the generator of the block and the voters in its attestation, drawn in
+/// `committees` to verify it, are counted as observed. The committees of the
+/// failed iterations are not counted, since their votes are not part of the
+/// block.
+pub(crate) fn participation(
+    provisioners: &Provisioners,
+    prev_seed: Seed,
+    header: &Header,
+    committees: Option<&AttestationCommittees>,
+) -> HashMap<PublicKeyBytes, ProvisionerUptime> {
+    let mut participation: HashMap<_, ProvisionerUptime> = HashMap::new();
+    let round = header.height;
+
+    // In case of Emergency Block, which is not produced by an extracted
+    // generator, all the iterations up to CONSENSUS_MAX_ITER failed
+    let failed_iters = cmp::min(header.iteration, CONSENSUS_MAX_ITER);
+    for iter in 0..failed_iters {
+        let generator = provisioners.get_generator(iter, prev_seed, round);
+        participation
+            .entry(generator)
+            .or_default()
+            .generator_selected += 1;
+    }
+
+    if is_emergency_block(header.iteration) {
+        return participation;
+    }
+
+    let generator = participation
+        .entry(header.generator_bls_pubkey)
+        .or_default();
+    generator.generator_selected += 1;
+    generator.blocks_produced += 1;
+
+    // The attestation of any block but an Emergency one is verified
+    let Some(committees) = committees else {
+        return participation;
+    };
+    let steps = [
+        (&committees.validation, header.att.validation.bitset),
+        (&committees.ratification, header.att.ratification.bitset),
+    ];
+    for (committee, bitset) in steps {
+        for member in committee.iter() {
+            participation
+                .entry(*member.bytes())
+                .or_default()
+                .committee_selected += 1;
+        }
+        for (voter, _) in committee.intersect(bitset).iter() {
+            participation
+                .entry(*voter.bytes())
+                .or_default()
+                .votes_observed += 1;
+        }
+    }
+
+    participation
+}
+
+/// Adds the participation in the block at `height` to the stored one,
+/// keeping the changes made until the block is final, to revert them if the
+/// block is.
+pub(crate) fn record<T: UptimeStorage>(
+    db: &mut T,
+    height: u64,
+    participation: HashMap<PublicKeyBytes, ProvisionerUptime>,
+) -> Result<()> {
+    let mut changes = Vec::with_capacity(participation.len());
+    for (key, observed) in participation {
+        let mut uptime = db.uptime(&key.0)?.unwrap_or_default();
+        // The change restores the last height it replaces
+        changes.push((
+            key,
+            ProvisionerUptime {
+                last_height: uptime.last_height,
+                ..observed
+            },
+        ));
+        uptime.add(&observed, height);
+        db.store_uptime(&key.0, &uptime)?;
+    }
+    db.store_uptime_changes(height, &changes)
+}
+
+/// Subtracts the participation in the block at `height` from the stored
+/// one, the block being reverted.
+pub(crate) fn revert<T: UptimeStorage>(db: &mut T, height: u64) -> Result<()> {
+    let Some(changes) = db.uptime_changes(height)? else {
+        return Ok(());
+    };
+    for (key, change) in changes {
+        if let Some(mut uptime) = db.uptime(&key.0)? {
+            uptime.sub(&change);
+            db.store_uptime(&key.0, &uptime)?;
+        }
+    }
+    db.delete_uptime_changes(height)
+}
+
+/// Drops the changes made to the participation by the blocks at `heights`,
+/// which are final and can't be reverted anymore.
+pub(crate) fn finalize<T: UptimeStorage>(
+    db: &mut T,
+    heights: impl IntoIterator<Item = u64>,
+) -> Result<()> {
+    for height in heights {
+        db.delete_uptime_changes(height)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::rocksdb::Backend;
+    use crate::database::{DatabaseOptions, DB};
+
+    #[test]
+    fn test_revert_participation() {
+        let dir = tempdir::TempDir::new("test_revert_participation")
+            .expect("Temp directory to be created");
+        let db =
+            Backend::create_or_open(dir.path(), DatabaseOptions::default());
+        let key = PublicKeyBytes([1; 96]);
+        let observed = |votes| ProvisionerUptime {
+            committee_selected: 2,
+            votes_observed: votes,
+            ..Default::default()
+        };
+
+        db.update(|t| {
+            record(t, 10, HashMap::from([(key, observed(2))]))?;
+            record(t, 11, HashMap::from([(key, observed(1))]))
+        })
+        .unwrap();
+        let uptime = db.view(|t| t.uptime(&key.0)).unwrap().unwrap();
+        assert_eq!(uptime.committee_selected, 4);
+        assert_eq!(uptime.votes_observed, 3);
+        assert_eq!(uptime.last_height, 11);
+
+        // The block at 10 is final, the one at 11 is reverted
+        db.update(|t| {
+            finalize(t, [10])?;
+            revert(t, 11)?;
+            revert(t, 10)
+        })
+        .unwrap();
+        let uptime = db.view(|t| t.uptime(&key.0)).unwrap().unwrap();
+        assert_eq!(uptime.committee_selected, 2);
+        assert_eq!(uptime.votes_observed, 2);
+        assert_eq!(uptime.last_height, 10);
+        assert_eq!(db.view(|t| t.uptime_changes(11)).unwrap(), None);
+    }
+}
@@ -0,0 +1,179 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Fault injection, for the recovery paths of the node to be exercised on
+//! CI and devnets.
+//!
+//! The faults are configured at startup: a ratio of the outbound messages is
+//! dropped, the database writes of the acceptor are delayed, and services
+//! panic once the chain reaches a given height. Since a panic terminates the
+//! node, a node restarted with the same faults panics again at the same
+//! height, so the harness is expected to restart it without them.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// Parts per million the loss ratio is stored in
+const PPM: u64 = 1_000_000;
+
+/// A service faults can be injected into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChaosTarget {
+    Kadcast,
+    DataBroker,
+    Acceptor,
+}
+
+/// A panic of a service, once the chain reaches a height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChaosPanic {
+    pub service: ChaosTarget,
+    pub height: u64,
+}
+
+/// Configuration of the faults injected.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChaosConfig {
+    /// Ratio of the outbound messages dropped, between 0 and 1
+    #[serde(default)]
+    pub outbound_loss: f64,
+    /// Delay added before the acceptor persists a block
+    #[serde(with = "humantime_serde", default)]
+    pub db_write_delay: Option<Duration>,
+    /// Services to panic
+    #[serde(default)]
+    pub panics: Vec<ChaosPanic>,
+}
+
+/// The faults injected into the services of the node.
+#[derive(Debug)]
+pub struct Chaos {
+    conf: ChaosConfig,
+    loss_ppm: u64,
+    outbound: AtomicU64,
+    dropped: AtomicU64,
+    /// Height of the last block accepted
+    height: AtomicU64,
+    /// Whether a service already panicked, by [`ChaosTarget`] discriminant
+    panicked: [AtomicBool; 3],
+}
+
+impl Chaos {
+    pub fn new(conf: ChaosConfig) -> anyhow::Result<Self> {
+        let loss = conf.outbound_loss;
+        if !(0.0..=1.0).contains(&loss) {
+            anyhow::bail!("Outbound loss must be between 0 and 1, got {loss}");
+        }
+        warn!(event = "fault injection enabled", ?conf);
+
+        Ok(Self {
+            loss_ppm: (loss * PPM as f64).round() as u64,
+            conf,
+            outbound: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            height: AtomicU64::new(0),
+            panicked: Default::default(),
+        })
+    }
+
+    /// Returns whether the next outbound message is to be dropped.
+    ///
+    /// The drops are spread evenly over the outbound messages rather than
+    /// randomly, so that a run is reproducible.
+    pub fn drop_outbound(&self) -> bool {
+        if self.loss_ppm == 0 {
+            return false;
+        }
+
+        let loss = self.loss_ppm;
+        let n = self.outbound.fetch_add(1, Ordering::Relaxed);
+        let drop = (n + 1) * loss / PPM > n * loss / PPM;
+        if drop {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        drop
+    }
+
+    /// Returns the number of outbound messages dropped.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Waits for the configured delay, if any, before a database write.
+    pub async fn delay_db_write(&self) {
+        if let Some(delay) = self.conf.db_write_delay {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Records the height of the last block accepted.
+    pub fn observe_height(&self, height: u64) {
+        self.height.fetch_max(height, Ordering::Relaxed);
+    }
+
+    /// Panics if `service` is configured to panic at the height reached.
+    ///
+    /// A service panics at most once.
+    pub fn check_panic(&self, service: ChaosTarget) {
+        let height = self.height.load(Ordering::Relaxed);
+        let reached = self
+            .conf
+            .panics
+            .iter()
+            .any(|p| p.service == service && p.height <= height);
+        if !reached {
+            return;
+        }
+
+        if !self.panicked[service as usize].swap(true, Ordering::Relaxed) {
+            info!(event = "injecting panic", ?service, height);
+            panic!("injected panic of {service:?} at height {height}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faults() {
+        let conf: ChaosConfig = serde_json::from_str(
+            r#"{
+                "outbound_loss": 0.5,
+                "panics": [{ "service": "data_broker", "height": 10 }]
+            }"#,
+        )
+        .expect("config to parse");
+        let chaos = Chaos::new(conf).expect("config to be valid");
+
+        let dropped = (0..10).filter(|_| chaos.drop_outbound()).count();
+        assert_eq!(dropped, 5);
+        assert_eq!(chaos.dropped(), 5);
+
+        chaos.observe_height(9);
+        chaos.check_panic(ChaosTarget::DataBroker);
+        chaos.observe_height(10);
+        chaos.check_panic(ChaosTarget::Acceptor);
+
+        let panic = std::panic::catch_unwind(|| {
+            chaos.check_panic(ChaosTarget::DataBroker)
+        });
+        assert!(panic.is_err());
+        // A service panics only once
+        chaos.check_panic(ChaosTarget::DataBroker);
+
+        let invalid = ChaosConfig {
+            outbound_loss: 1.5,
+            ..Default::default()
+        };
+        assert!(Chaos::new(invalid).is_err());
+    }
+}
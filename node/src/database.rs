@@ -10,6 +10,7 @@ use std::path::Path;
 pub mod rocksdb;
 
 use anyhow::Result;
+use node_data::bls::PublicKeyBytes;
 use node_data::ledger::{
     Block, Fault, Header, Label, SpendingId, SpentTransaction, Transaction,
 };
@@ -52,6 +53,15 @@ pub trait DB: Send + Sync + 'static {
     where
         F: for<'a> FnOnce(&mut Self::P<'a>) -> Result<T>;
 
+    /// Same as [`DB::update`], with the transaction synced to disk before
+    /// returning, so that it survives a crash of the machine.
+    fn update_synced<F, T>(&self, f: F) -> Result<T>
+    where
+        F: for<'a> FnOnce(&mut Self::P<'a>) -> Result<T>;
+
+    /// Flushes all pending writes to disk.
+    ///
+    /// Called on shutdown, once all services are stopped.
     fn close(&mut self);
 }
 
@@ -83,6 +93,10 @@ pub trait Ledger {
 
     fn ledger_tx_exists(&self, tx_id: &[u8]) -> Result<bool>;
 
+    /// Returns the gas spent by the transactions of the block `hash`, if
+    /// recorded when it was stored.
+    fn block_gas_used(&self, hash: &[u8]) -> Result<Option<u64>>;
+
     fn block_label_by_height(
         &self,
         height: u64,
@@ -153,6 +167,9 @@ pub trait Mempool {
     /// Checks if a transaction exists in the mempool.
     fn mempool_tx_exists(&self, tx_id: [u8; 32]) -> Result<bool>;
 
+    /// Gets the timestamp a transaction was added to the mempool with.
+    fn mempool_tx_timestamp(&self, tx: &Transaction) -> Result<Option<u64>>;
+
     /// Deletes a transaction from the mempool.
     ///
     /// If `cascade` is true, all dependant transactions are deleted
@@ -203,8 +220,179 @@ pub trait Metadata {
     fn op_read(&self, key: &[u8]) -> Result<Option<Vec<u8>>>;
 }
 
+/// Entry of the log of the administrative actions performed on the node.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// Unix timestamp of the action, in seconds
+    pub timestamp: u64,
+    /// Who performed the action, as authenticated by the node
+    pub principal: String,
+    /// The performed action, e.g. `revert`
+    pub action: String,
+    /// Details of the action
+    pub details: serde_json::Value,
+}
+
+impl AuditEntry {
+    /// Creates the entry of an `action` performed now by `principal`.
+    pub fn new<P: Into<String>, A: Into<String>>(
+        principal: P,
+        action: A,
+        details: serde_json::Value,
+    ) -> Self {
+        Self {
+            timestamp: node_data::get_current_timestamp(),
+            principal: principal.into(),
+            action: action.into(),
+            details,
+        }
+    }
+}
+
+pub trait AuditLog {
+    /// Appends an entry to the audit log, returning its sequence number.
+    ///
+    /// The audit log is append-only, entries are never updated nor deleted.
+    fn append_audit_entry(&mut self, entry: &AuditEntry) -> Result<u64>;
+
+    /// Reads up to `limit` entries of the audit log, starting from the
+    /// sequence number `from`
+    fn audit_entries(
+        &self,
+        from: u64,
+        limit: usize,
+    ) -> Result<Vec<(u64, AuditEntry)>>;
+}
+
+/// Participation of a provisioner in consensus, as observed from the
+/// accepted blocks.
+///
+/// The counts are updated when a block is accepted, and reverted along with
+/// it until it is final.
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvisionerUptime {
+    /// Iterations the provisioner was extracted as generator for
+    pub generator_selected: u64,
+    /// Accepted blocks produced by the provisioner
+    pub blocks_produced: u64,
+    /// Steps the provisioner was a committee member of
+    pub committee_selected: u64,
+    /// Steps the vote of the provisioner was part of the attestation of
+    pub votes_observed: u64,
+    /// Height of the last block the provisioner was extracted for
+    pub last_height: u64,
+}
+
+impl ProvisionerUptime {
+    /// Adds the counts of `other`, observed at `height`.
+    pub fn add(&mut self, other: &Self, height: u64) {
+        self.generator_selected += other.generator_selected;
+        self.blocks_produced += other.blocks_produced;
+        self.committee_selected += other.committee_selected;
+        self.votes_observed += other.votes_observed;
+        self.last_height = height;
+    }
+
+    /// Subtracts the counts of `other`, restoring the height of the last
+    /// block it records.
+    pub fn sub(&mut self, other: &Self) {
+        self.generator_selected = self
+            .generator_selected
+            .saturating_sub(other.generator_selected);
+        self.blocks_produced =
+            self.blocks_produced.saturating_sub(other.blocks_produced);
+        self.committee_selected = self
+            .committee_selected
+            .saturating_sub(other.committee_selected);
+        self.votes_observed =
+            self.votes_observed.saturating_sub(other.votes_observed);
+        self.last_height = other.last_height;
+    }
+}
+
+pub trait UptimeStorage {
+    /// Stores the participation of the provisioner with BLS key `key`.
+    fn store_uptime(
+        &mut self,
+        key: &[u8],
+        uptime: &ProvisionerUptime,
+    ) -> Result<()>;
+
+    /// Reads the participation of the provisioner with BLS key `key`.
+    fn uptime(&self, key: &[u8]) -> Result<Option<ProvisionerUptime>>;
+
+    /// Reads the participation of all the provisioners ever extracted.
+    fn uptimes(&self) -> Result<Vec<(Vec<u8>, ProvisionerUptime)>>;
+
+    /// Stores the changes made to the participation by the block at
+    /// `height`, to revert them if the block is.
+    fn store_uptime_changes(
+        &mut self,
+        height: u64,
+        changes: &[(PublicKeyBytes, ProvisionerUptime)],
+    ) -> Result<()>;
+
+    /// Reads the changes made to the participation by the block at `height`.
+    fn uptime_changes(
+        &self,
+        height: u64,
+    ) -> Result<Option<Vec<(PublicKeyBytes, ProvisionerUptime)>>>;
+
+    /// Deletes the changes made to the participation by the block at
+    /// `height`.
+    fn delete_uptime_changes(&mut self, height: u64) -> Result<()>;
+}
+
+/// Stake of a provisioner, as recorded in a snapshot of the provisioner set.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotStake {
+    pub key: PublicKeyBytes,
+    pub value: u64,
+    /// Round from which the provisioner is eligible
+    pub eligible_since: u64,
+}
+
+/// The provisioner set at the first round of an epoch.
+///
+/// The committees of the rounds of the epoch are drawn from it, until a
+/// stake changes within the epoch.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvisionersSnapshot {
+    pub epoch: u64,
+    /// First round of the epoch, as the length of the epochs is set by the
+    /// stake contract
+    pub round: u64,
+    pub stakes: Vec<SnapshotStake>,
+}
+
+pub trait ProvisionersStorage {
+    /// Stores the provisioner set at the start of an epoch.
+    fn store_provisioners_snapshot(
+        &mut self,
+        snapshot: &ProvisionersSnapshot,
+    ) -> Result<()>;
+
+    /// Reads the provisioner set at the start of `epoch`.
+    fn provisioners_snapshot(
+        &self,
+        epoch: u64,
+    ) -> Result<Option<ProvisionersSnapshot>>;
+}
+
 pub trait Persist:
-    Ledger + ConsensusStorage + Mempool + Metadata + core::fmt::Debug
+    Ledger
+    + ConsensusStorage
+    + Mempool
+    + Metadata
+    + AuditLog
+    + UptimeStorage
+    + ProvisionersStorage
+    + core::fmt::Debug
 {
     // Candidate block functions
 
@@ -12,22 +12,24 @@ use std::sync::Arc;
 use std::{io, vec};
 
 use anyhow::Result;
+use node_data::bls::PublicKeyBytes;
 use node_data::ledger::{
     Block, Fault, Header, Label, SpendingId, SpentTransaction, Transaction,
 };
 use node_data::message::{payload, ConsensusHeader};
 use node_data::Serializable;
 use rocksdb::{
-    AsColumnFamilyRef, BlockBasedOptions, ColumnFamily, ColumnFamilyDescriptor,
-    DBAccess, DBRawIteratorWithThreadMode, IteratorMode, LogLevel,
-    OptimisticTransactionDB, OptimisticTransactionOptions, Options,
-    WriteOptions,
+    properties, AsColumnFamilyRef, BlockBasedOptions, ColumnFamily,
+    ColumnFamilyDescriptor, DBAccess, DBRawIteratorWithThreadMode, Direction,
+    IteratorMode, LogLevel, OptimisticTransactionDB,
+    OptimisticTransactionOptions, Options, WriteOptions,
 };
-use tracing::info;
+use tracing::{info, warn};
 
 use super::{
-    ConsensusStorage, DatabaseOptions, Ledger, LightBlock, Metadata, Persist,
-    DB,
+    AuditEntry, AuditLog, ConsensusStorage, DatabaseOptions, Ledger,
+    LightBlock, Metadata, Persist, ProvisionerUptime, ProvisionersSnapshot,
+    ProvisionersStorage, SnapshotStake, UptimeStorage, DB,
 };
 use crate::database::Mempool;
 
@@ -42,6 +44,26 @@ const CF_MEMPOOL: &str = "cf_mempool";
 const CF_MEMPOOL_SPENDING_ID: &str = "cf_mempool_spending_id";
 const CF_MEMPOOL_FEES: &str = "cf_mempool_fees";
 const CF_METADATA: &str = "cf_metadata";
+const CF_AUDIT_LOG: &str = "cf_audit_log";
+const CF_UPTIME: &str = "cf_uptime";
+const CF_PROVISIONERS: &str = "cf_provisioners";
+
+const COLUMN_FAMILIES: [&str; 14] = [
+    CF_LEDGER_HEADER,
+    CF_LEDGER_TXS,
+    CF_LEDGER_FAULTS,
+    CF_LEDGER_HEIGHT,
+    CF_CANDIDATES,
+    CF_CANDIDATES_HEIGHT,
+    CF_VALIDATION_RESULTS,
+    CF_METADATA,
+    CF_MEMPOOL,
+    CF_MEMPOOL_SPENDING_ID,
+    CF_MEMPOOL_FEES,
+    CF_AUDIT_LOG,
+    CF_UPTIME,
+    CF_PROVISIONERS,
+];
 
 const DB_FOLDER_NAME: &str = "chain.db";
 
@@ -52,16 +74,122 @@ pub const MD_AVG_VALIDATION: &[u8] = b"avg_validation_time";
 pub const MD_AVG_RATIFICATION: &[u8] = b"avg_ratification_time";
 pub const MD_AVG_PROPOSAL: &[u8] = b"avg_proposal_time";
 pub const MD_LAST_ITER: &[u8] = b"consensus_last_iter";
+/// Set when the node terminates abruptly, so that the integrity of the
+/// database is checked at the next start.
+pub const MD_DIRTY_KEY: &[u8] = b"dirty";
+/// Block being accepted, cleared once the acceptance is complete.
+pub const MD_ACCEPT_INTENT_KEY: &[u8] = b"accept_intent";
+/// Prefix of the gas used by each block, followed by its hash.
+const MD_GAS_USED_PREFIX: &[u8] = b"gas_used";
+/// Prefix of the changes made to the participation of the provisioners by
+/// each block not final yet, followed by its height.
+const MD_UPTIME_CHANGES_PREFIX: &[u8] = b"uptime_changes";
+
+/// Leads the ledger header records whose votes are stored compactly, in
+/// place of the header version of the records stored before.
+const COMPACT_LIGHT_BLOCK: u8 = u8::MAX;
+
+fn gas_used_key(hash: &[u8]) -> Vec<u8> {
+    [MD_GAS_USED_PREFIX, hash].concat()
+}
+
+fn uptime_changes_key(height: u64) -> Vec<u8> {
+    [MD_UPTIME_CHANGES_PREFIX, &height.to_be_bytes()].concat()
+}
 
 #[derive(Clone)]
 pub struct Backend {
     rocksdb: Arc<OptimisticTransactionDB>,
 }
 
+/// Statistics of a column family of the database.
+#[derive(Debug, Clone)]
+pub struct ColumnFamilyStats {
+    pub name: &'static str,
+    /// Estimated number of keys
+    pub estimated_keys: u64,
+    /// Total size of the SST files, in bytes
+    pub sst_files_size: u64,
+    /// Approximate size of the memtables, in bytes
+    pub memtables_size: u64,
+}
+
 impl Backend {
-    fn begin_tx(&self) -> DBTransaction<'_, OptimisticTransactionDB> {
+    /// Opens an existing database located at this path, for inspection.
+    ///
+    /// Unlike [`DB::create_or_open`], this fails if the database does not
+    /// exist. Since RocksDB cannot open a transaction database in read-only
+    /// mode, this also fails while the database is in use by a node.
+    pub fn open_existing<T: AsRef<Path>>(path: T) -> Result<Self> {
+        let path = path.as_ref().join(DB_FOLDER_NAME);
+
+        let cfs = COLUMN_FAMILIES.map(|cf_name| {
+            ColumnFamilyDescriptor::new(cf_name, Options::default())
+        });
+        let rocksdb = OptimisticTransactionDB::open_cf_descriptors(
+            &Options::default(),
+            path,
+            cfs,
+        )?;
+
+        Ok(Self {
+            rocksdb: Arc::new(rocksdb),
+        })
+    }
+
+    /// Flushes the WAL and the memtables of all the column families to disk.
+    pub fn flush(&self) {
+        // Persist the mempool column families, which are written without
+        // flushing the WAL, together with the memtables of all the others.
+        if let Err(e) = self.rocksdb.flush_wal(true) {
+            warn!("Unable to flush the WAL: {e}");
+        }
+
+        for cf_name in COLUMN_FAMILIES {
+            let cf = self
+                .rocksdb
+                .cf_handle(cf_name)
+                .expect("column family must exist");
+            if let Err(e) = self.rocksdb.flush_cf(cf) {
+                warn!("Unable to flush {cf_name}: {e}");
+            }
+        }
+    }
+
+    /// Marks the database as dirty, to check its integrity at the next
+    /// start.
+    pub fn mark_dirty(&self) -> Result<()> {
+        self.update(|t| t.op_write(MD_DIRTY_KEY, [1]))
+    }
+
+    /// Returns the statistics of all the column families.
+    pub fn column_families_stats(&self) -> Result<Vec<ColumnFamilyStats>> {
+        let mut stats = Vec::with_capacity(COLUMN_FAMILIES.len());
+        for name in COLUMN_FAMILIES {
+            let cf = self
+                .rocksdb
+                .cf_handle(name)
+                .expect("column family must exist");
+            let property = |property| -> Result<u64> {
+                let value = self.rocksdb.property_int_value_cf(cf, property)?;
+                Ok(value.unwrap_or_default())
+            };
+
+            stats.push(ColumnFamilyStats {
+                name,
+                estimated_keys: property(properties::ESTIMATE_NUM_KEYS)?,
+                sst_files_size: property(properties::TOTAL_SST_FILES_SIZE)?,
+                memtables_size: property(properties::CUR_SIZE_ALL_MEM_TABLES)?,
+            });
+        }
+        Ok(stats)
+    }
+
+    fn begin_tx(
+        &self,
+        write_options: WriteOptions,
+    ) -> DBTransaction<'_, OptimisticTransactionDB> {
         // Create a new RocksDB transaction
-        let write_options = WriteOptions::default();
         let tx_options = OptimisticTransactionOptions::default();
 
         let inner = self.rocksdb.transaction_opt(&write_options, &tx_options);
@@ -122,6 +250,21 @@ impl Backend {
             .cf_handle(CF_METADATA)
             .expect("CF_METADATA column family must exist");
 
+        let audit_log_cf = self
+            .rocksdb
+            .cf_handle(CF_AUDIT_LOG)
+            .expect("CF_AUDIT_LOG column family must exist");
+
+        let uptime_cf = self
+            .rocksdb
+            .cf_handle(CF_UPTIME)
+            .expect("CF_UPTIME column family must exist");
+
+        let provisioners_cf = self
+            .rocksdb
+            .cf_handle(CF_PROVISIONERS)
+            .expect("CF_PROVISIONERS column family must exist");
+
         DBTransaction::<'_, OptimisticTransactionDB> {
             inner,
             candidates_cf,
@@ -135,6 +278,9 @@ impl Backend {
             fees_cf,
             ledger_height_cf,
             metadata_cf,
+            audit_log_cf,
+            uptime_cf,
+            provisioners_cf,
             cumulative_inner_size: RefCell::new(0),
         }
     }
@@ -210,6 +356,12 @@ impl DB for Backend {
                 blocks_cf_opts.clone(),
             ),
             ColumnFamilyDescriptor::new(CF_METADATA, blocks_cf_opts.clone()),
+            ColumnFamilyDescriptor::new(CF_AUDIT_LOG, blocks_cf_opts.clone()),
+            ColumnFamilyDescriptor::new(CF_UPTIME, blocks_cf_opts.clone()),
+            ColumnFamilyDescriptor::new(
+                CF_PROVISIONERS,
+                blocks_cf_opts.clone(),
+            ),
             ColumnFamilyDescriptor::new(CF_MEMPOOL, mp_opts.clone()),
             ColumnFamilyDescriptor::new(
                 CF_MEMPOOL_SPENDING_ID,
@@ -235,7 +387,7 @@ impl DB for Backend {
         F: for<'a> FnOnce(&Self::P<'a>) -> T,
     {
         // Create a new read-only transaction
-        let tx = self.begin_tx();
+        let tx = self.begin_tx(WriteOptions::default());
 
         // Execute all read-only transactions in isolation
         let ret = f(&tx);
@@ -254,24 +406,45 @@ impl DB for Backend {
     where
         F: for<'a> FnOnce(&mut Self::P<'a>) -> Result<T>,
     {
-        // Create read-write transaction
-        let mut tx = self.begin_tx();
-
-        // If f returns err, no commit will be applied into backend
-        // storage
-        let ret = execute(&mut tx)?;
-
-        if dry_run {
-            tx.rollback()?;
-        } else {
-            // Apply changes in atomic way
-            tx.commit()?;
-        }
+        execute_tx(self.begin_tx(WriteOptions::default()), dry_run, execute)
+    }
 
-        Ok(ret)
+    fn update_synced<F, T>(&self, execute: F) -> Result<T>
+    where
+        F: for<'a> FnOnce(&mut Self::P<'a>) -> Result<T>,
+    {
+        let mut write_options = WriteOptions::default();
+        write_options.set_sync(true);
+        execute_tx(self.begin_tx(write_options), false, execute)
     }
 
-    fn close(&mut self) {}
+    fn close(&mut self) {
+        self.flush();
+    }
+}
+
+/// Executes `execute` in the read-write transaction `tx`, committing it
+/// unless `dry_run`.
+fn execute_tx<'db, F, T>(
+    mut tx: DBTransaction<'db, OptimisticTransactionDB>,
+    dry_run: bool,
+    execute: F,
+) -> Result<T>
+where
+    F: FnOnce(&mut DBTransaction<'db, OptimisticTransactionDB>) -> Result<T>,
+{
+    // If f returns err, no commit will be applied into backend
+    // storage
+    let ret = execute(&mut tx)?;
+
+    if dry_run {
+        tx.rollback()?;
+    } else {
+        // Apply changes in atomic way
+        tx.commit()?;
+    }
+
+    Ok(ret)
 }
 
 pub struct DBTransaction<'db, DB: DBAccess> {
@@ -298,6 +471,9 @@ pub struct DBTransaction<'db, DB: DBAccess> {
     fees_cf: &'db ColumnFamily,
 
     metadata_cf: &'db ColumnFamily,
+    audit_log_cf: &'db ColumnFamily,
+    uptime_cf: &'db ColumnFamily,
+    provisioners_cf: &'db ColumnFamily,
 }
 
 impl<'db, DB: DBAccess> Ledger for DBTransaction<'db, DB> {
@@ -329,6 +505,11 @@ impl<'db, DB: DBAccess> Ledger for DBTransaction<'db, DB> {
         self.op_write(MD_HASH_KEY, header.hash)?;
         self.op_write(MD_STATE_ROOT_KEY, header.state_hash)?;
 
+        let gas_used = txs
+            .iter()
+            .fold(0u64, |sum, tx| sum.saturating_add(tx.gas_spent));
+        self.op_write(&gas_used_key(&header.hash), gas_used.to_le_bytes())?;
+
         // COLUMN FAMILY: CF_LEDGER_TXS
         {
             let cf = self.ledger_txs_cf;
@@ -414,10 +595,22 @@ impl<'db, DB: DBAccess> Ledger for DBTransaction<'db, DB> {
         }
 
         self.inner.delete_cf(self.ledger_cf, b.header().hash)?;
+        self.inner
+            .delete_cf(self.metadata_cf, gas_used_key(&b.header().hash))?;
 
         Ok(())
     }
 
+    fn block_gas_used(&self, hash: &[u8]) -> Result<Option<u64>> {
+        let Some(bytes) = self.op_read(&gas_used_key(hash))? else {
+            return Ok(None);
+        };
+        let bytes = bytes
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("invalid gas used record"))?;
+        Ok(Some(u64::from_le_bytes(bytes)))
+    }
+
     fn block_exists(&self, hash: &[u8]) -> Result<bool> {
         Ok(self.inner.get_cf(self.ledger_cf, hash)?.is_some())
     }
@@ -502,8 +695,8 @@ impl<'db, DB: DBAccess> Ledger for DBTransaction<'db, DB> {
     fn block_header(&self, hash: &[u8]) -> Result<Option<Header>> {
         match self.inner.get_cf(self.ledger_cf, hash)? {
             Some(blob) => {
-                let record = Header::read(&mut &blob[..])?;
-                Ok(Some(record))
+                let record = LightBlock::read(&mut &blob[..])?;
+                Ok(Some(record.header))
             }
             None => Ok(None),
         }
@@ -866,6 +1059,19 @@ impl<'db, DB: DBAccess> Mempool for DBTransaction<'db, DB> {
         Ok(self.inner.get_cf(self.mempool_cf, h)?.is_some())
     }
 
+    fn mempool_tx_timestamp(&self, tx: &Transaction) -> Result<Option<u64>> {
+        let key = serialize_key(tx.gas_price(), tx.id())?;
+        match self.inner.get_cf(self.fees_cf, key)? {
+            Some(value) => {
+                let timestamp: [u8; 8] = value.try_into().map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidData, "invalid data")
+                })?;
+                Ok(Some(u64::from_be_bytes(timestamp)))
+            }
+            None => Ok(None),
+        }
+    }
+
     fn delete_mempool_tx(
         &mut self,
         h: [u8; 32],
@@ -1142,6 +1348,129 @@ impl<'db, DB: DBAccess> Metadata for DBTransaction<'db, DB> {
     }
 }
 
+impl<'db, DB: DBAccess> AuditLog for DBTransaction<'db, DB> {
+    fn append_audit_entry(&mut self, entry: &AuditEntry) -> Result<u64> {
+        let last = self
+            .inner
+            .iterator_cf(self.audit_log_cf, IteratorMode::End)
+            .next();
+        let seq = match last {
+            Some(item) => {
+                let (key, _) = item?;
+                u64::from_be_bytes(key[..8].try_into()?) + 1
+            }
+            None => 0,
+        };
+
+        // Track the key, so that a concurrent append of the same sequence
+        // number fails at commit
+        let key = seq.to_be_bytes();
+        self.inner.get_for_update_cf(self.audit_log_cf, key, true)?;
+        self.put_cf(self.audit_log_cf, key, serde_json::to_vec(entry)?)?;
+
+        Ok(seq)
+    }
+
+    fn audit_entries(
+        &self,
+        from: u64,
+        limit: usize,
+    ) -> Result<Vec<(u64, AuditEntry)>> {
+        let from = from.to_be_bytes();
+        self.inner
+            .iterator_cf(
+                self.audit_log_cf,
+                IteratorMode::From(&from, Direction::Forward),
+            )
+            .take(limit)
+            .map(|item| {
+                let (key, value) = item?;
+                let seq = u64::from_be_bytes(key[..8].try_into()?);
+                Ok((seq, serde_json::from_slice(&value)?))
+            })
+            .collect()
+    }
+}
+
+impl<'db, DB: DBAccess> UptimeStorage for DBTransaction<'db, DB> {
+    fn store_uptime(
+        &mut self,
+        key: &[u8],
+        uptime: &ProvisionerUptime,
+    ) -> Result<()> {
+        self.put_cf(self.uptime_cf, key, serde_json::to_vec(uptime)?)
+    }
+
+    fn uptime(&self, key: &[u8]) -> Result<Option<ProvisionerUptime>> {
+        self.inner
+            .get_cf(self.uptime_cf, key)?
+            .map(|value| serde_json::from_slice(&value))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    fn uptimes(&self) -> Result<Vec<(Vec<u8>, ProvisionerUptime)>> {
+        self.inner
+            .iterator_cf(self.uptime_cf, IteratorMode::Start)
+            .map(|item| {
+                let (key, value) = item?;
+                Ok((key.to_vec(), serde_json::from_slice(&value)?))
+            })
+            .collect()
+    }
+
+    fn store_uptime_changes(
+        &mut self,
+        height: u64,
+        changes: &[(PublicKeyBytes, ProvisionerUptime)],
+    ) -> Result<()> {
+        self.put_cf(
+            self.metadata_cf,
+            uptime_changes_key(height),
+            serde_json::to_vec(changes)?,
+        )
+    }
+
+    fn uptime_changes(
+        &self,
+        height: u64,
+    ) -> Result<Option<Vec<(PublicKeyBytes, ProvisionerUptime)>>> {
+        self.inner
+            .get_cf(self.metadata_cf, uptime_changes_key(height))?
+            .map(|value| serde_json::from_slice(&value))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    fn delete_uptime_changes(&mut self, height: u64) -> Result<()> {
+        self.inner
+            .delete_cf(self.metadata_cf, uptime_changes_key(height))?;
+        Ok(())
+    }
+}
+
+impl<'db, DB: DBAccess> ProvisionersStorage for DBTransaction<'db, DB> {
+    fn store_provisioners_snapshot(
+        &mut self,
+        snapshot: &ProvisionersSnapshot,
+    ) -> Result<()> {
+        let mut buf = vec![];
+        snapshot.write(&mut buf)?;
+        self.put_cf(self.provisioners_cf, snapshot.epoch.to_be_bytes(), buf)
+    }
+
+    fn provisioners_snapshot(
+        &self,
+        epoch: u64,
+    ) -> Result<Option<ProvisionersSnapshot>> {
+        self.inner
+            .get_cf(self.provisioners_cf, epoch.to_be_bytes())?
+            .map(|value| ProvisionersSnapshot::read(&mut &value[..]))
+            .transpose()
+            .map_err(Into::into)
+    }
+}
+
 impl<'db, DB: DBAccess> DBTransaction<'db, DB> {
     /// A thin wrapper around inner.put_cf that calculates a db transaction
     /// disk footprint
@@ -1197,10 +1526,47 @@ fn deserialize_iter_key<R: Read>(r: &mut R) -> Result<([u8; 32], u8)> {
     Ok((prev_block_hash, iteration))
 }
 
+impl node_data::Serializable for ProvisionersSnapshot {
+    fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&self.epoch.to_le_bytes())?;
+        w.write_all(&self.round.to_le_bytes())?;
+        w.write_all(&(self.stakes.len() as u32).to_le_bytes())?;
+        for stake in &self.stakes {
+            w.write_all(stake.key.inner())?;
+            w.write_all(&stake.value.to_le_bytes())?;
+            w.write_all(&stake.eligible_since.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn read<R: Read>(r: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        let epoch = Self::read_u64_le(r)?;
+        let round = Self::read_u64_le(r)?;
+        let len = Self::read_u32_le(r)?;
+        let mut stakes = vec![];
+        for _ in 0..len {
+            stakes.push(SnapshotStake {
+                key: PublicKeyBytes(Self::read_bytes(r)?),
+                value: Self::read_u64_le(r)?,
+                eligible_since: Self::read_u64_le(r)?,
+            });
+        }
+        Ok(Self {
+            epoch,
+            round,
+            stakes,
+        })
+    }
+}
+
 impl node_data::Serializable for LightBlock {
     fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
-        // Write block header
-        self.header.write(w)?;
+        // Write block header, leaving out the signatures of the empty votes
+        w.write_all(&[COMPACT_LIGHT_BLOCK])?;
+        self.header.write_votes(w, true)?;
 
         // Write transactions count
         let len = self.transactions_ids.len() as u32;
@@ -1227,8 +1593,12 @@ impl node_data::Serializable for LightBlock {
     where
         Self: Sized,
     {
-        // Read block header
-        let header = Header::read(r)?;
+        // Read block header, in the full encoding if stored before the
+        // compact one
+        let header = match Self::read_u8(r)? {
+            COMPACT_LIGHT_BLOCK => Header::read_votes(r, true)?,
+            version => Header::read(&mut (&[version][..]).chain(r))?,
+        };
 
         // Read transactions count
         let len = Self::read_u32_le(r)?;
@@ -1310,6 +1680,15 @@ mod tests {
                 for pos in 0..b.faults().len() {
                     assert_eq!(db_blk.faults()[pos].id(), b.faults()[pos].id());
                 }
+
+                let gas_used = to_spent_txs(b.txs())
+                    .iter()
+                    .map(|tx| tx.gas_spent)
+                    .sum::<u64>();
+                assert_eq!(
+                    txn.block_gas_used(&hash).expect("gas used to be read"),
+                    Some(gas_used)
+                );
             });
 
             assert!(db
@@ -1328,6 +1707,151 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_light_block_records() {
+        let b: Block = Faker.fake();
+        let light = LightBlock {
+            header: ledger::Header {
+                version: 1,
+                ..b.header().clone()
+            },
+            transactions_ids: b.txs().iter().map(|t| t.id()).collect(),
+            faults_ids: b.faults().iter().map(|f| f.id()).collect(),
+        };
+
+        let mut compact = vec![];
+        light.write(&mut compact).unwrap();
+        let read = LightBlock::read(&mut &compact[..]).unwrap();
+        assert!(read.header == light.header);
+        assert_eq!(read.transactions_ids, light.transactions_ids);
+        assert_eq!(read.faults_ids, light.faults_ids);
+
+        // Records stored before the compact votes start with the header
+        let mut legacy = vec![];
+        light.header.write(&mut legacy).unwrap();
+        legacy.extend_from_slice(
+            &(light.transactions_ids.len() as u32).to_le_bytes(),
+        );
+        light
+            .transactions_ids
+            .iter()
+            .for_each(|id| legacy.extend_from_slice(id));
+        legacy
+            .extend_from_slice(&(light.faults_ids.len() as u32).to_le_bytes());
+        light
+            .faults_ids
+            .iter()
+            .for_each(|id| legacy.extend_from_slice(id));
+
+        let read = LightBlock::read(&mut &legacy[..]).unwrap();
+        assert!(read.header == light.header);
+        assert_eq!(read.transactions_ids, light.transactions_ids);
+        assert_eq!(read.faults_ids, light.faults_ids);
+    }
+
+    #[test]
+    fn test_audit_log() {
+        TestWrapper::new("test_audit_log").run(|path| {
+            let db = Backend::create_or_open(path, DatabaseOptions::default());
+
+            let entries: Vec<_> = (0..3)
+                .map(|i| {
+                    AuditEntry::new(
+                        "admin",
+                        "switch",
+                        serde_json::json!({ "enabled": i % 2 == 0 }),
+                    )
+                })
+                .collect();
+
+            for (i, entry) in entries.iter().enumerate() {
+                let seq = db
+                    .update(|txn| txn.append_audit_entry(entry))
+                    .expect("entry to be appended");
+                assert_eq!(seq, i as u64);
+            }
+
+            db.view(|txn| {
+                let all = txn.audit_entries(0, 10).expect("entries to be read");
+                assert_eq!(all.len(), 3);
+                assert_eq!(all[2], (2, entries[2].clone()));
+
+                let page = txn.audit_entries(1, 1).expect("entries to be read");
+                assert_eq!(page, vec![(1, entries[1].clone())]);
+            });
+        });
+    }
+
+    #[test]
+    fn test_uptime() {
+        TestWrapper::new("test_uptime").run(|path| {
+            let db = Backend::create_or_open(path, DatabaseOptions::default());
+
+            let mut uptime = ProvisionerUptime {
+                generator_selected: 2,
+                blocks_produced: 1,
+                ..Default::default()
+            };
+            db.update(|txn| txn.store_uptime(&[1; 96], &uptime))
+                .expect("uptime to be stored");
+
+            let observed = ProvisionerUptime {
+                committee_selected: 2,
+                votes_observed: 1,
+                ..Default::default()
+            };
+            uptime.add(&observed, 10);
+            db.update(|txn| txn.store_uptime(&[1; 96], &uptime))
+                .expect("uptime to be stored");
+
+            db.view(|txn| {
+                let stored = txn.uptime(&[1; 96]).expect("uptime to be read");
+                assert_eq!(stored, Some(uptime));
+                assert_eq!(stored.unwrap().last_height, 10);
+                assert_eq!(txn.uptime(&[2; 96]).expect("to be read"), None);
+
+                let all = txn.uptimes().expect("uptimes to be read");
+                assert_eq!(all, vec![(vec![1; 96], uptime)]);
+            });
+        });
+    }
+
+    #[test]
+    fn test_provisioners_snapshot() {
+        TestWrapper::new("test_provisioners_snapshot").run(|path| {
+            let db = Backend::create_or_open(path, DatabaseOptions::default());
+
+            let snapshot = ProvisionersSnapshot {
+                epoch: 3,
+                round: 6_480,
+                stakes: vec![
+                    SnapshotStake {
+                        key: PublicKeyBytes([1; 96]),
+                        value: 1_000,
+                        eligible_since: 4_320,
+                    },
+                    SnapshotStake {
+                        key: PublicKeyBytes([2; 96]),
+                        value: 2_000,
+                        eligible_since: 6_480,
+                    },
+                ],
+            };
+            db.update(|txn| txn.store_provisioners_snapshot(&snapshot))
+                .expect("snapshot to be stored");
+
+            db.view(|txn| {
+                let stored =
+                    txn.provisioners_snapshot(3).expect("snapshot to be read");
+                assert_eq!(stored, Some(snapshot));
+                assert_eq!(
+                    txn.provisioners_snapshot(2).expect("to be read"),
+                    None
+                );
+            });
+        });
+    }
+
     #[test]
     fn test_read_only() {
         TestWrapper::new("test_read_only").run(|path| {
@@ -1417,6 +1941,7 @@ mod tests {
 
             db.view(|vq| {
                 assert!(vq.mempool_tx_exists(t.id()).unwrap());
+                assert_eq!(vq.mempool_tx_timestamp(&t).unwrap(), Some(0));
 
                 let fetched_tx = vq
                     .mempool_tx(t.id())
@@ -1588,6 +2113,7 @@ mod tests {
                 block_height: 0,
                 gas_spent: 0,
                 err: None,
+                call_graph: vec![],
             })
             .collect()
     }
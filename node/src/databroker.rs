@@ -4,6 +4,7 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+mod cache;
 pub mod conf;
 
 use std::cmp::min;
@@ -15,9 +16,12 @@ use async_trait::async_trait;
 use node_data::message::payload::{self, GetResource, InvParam, InvType};
 use node_data::message::{AsyncQueue, Payload, Topics};
 use smallvec::SmallVec;
-use tokio::sync::{RwLock, Semaphore};
+use tokio::sync::{watch, RwLock, Semaphore};
 use tracing::{debug, info, warn};
 
+use self::cache::BlockCache;
+#[cfg(feature = "chaos")]
+use crate::chaos::{Chaos, ChaosTarget};
 use crate::database::{ConsensusStorage, Ledger, Mempool};
 use crate::{database, vm, LongLivedService, Message, Network};
 
@@ -73,6 +77,15 @@ pub struct DataBrokerSrv {
     limit_ongoing_requests: Arc<Semaphore>,
 
     conf: conf::Params,
+
+    /// Updates of the configuration to apply while running.
+    conf_updates: Option<watch::Receiver<conf::Params>>,
+
+    /// Recently served blocks
+    blocks: Arc<BlockCache>,
+
+    #[cfg(feature = "chaos")]
+    chaos: Option<Arc<Chaos>>,
 }
 
 impl DataBrokerSrv {
@@ -87,8 +100,60 @@ impl DataBrokerSrv {
             limit_ongoing_requests: Arc::new(Semaphore::new(
                 conf.max_ongoing_requests,
             )),
+            conf_updates: None,
+            blocks: Arc::new(BlockCache::new(conf.block_cache_bytes)),
+            #[cfg(feature = "chaos")]
+            chaos: None,
         }
     }
+
+    /// Applies the configurations received from `updates` while the service
+    /// is running.
+    ///
+    /// A change of `max_queue_size` or `block_cache_bytes` is only applied on
+    /// restart.
+    pub fn with_conf_updates(
+        mut self,
+        updates: watch::Receiver<conf::Params>,
+    ) -> Self {
+        self.conf_updates = Some(updates);
+        self
+    }
+
+    /// Panics the service as configured by `chaos`.
+    #[cfg(feature = "chaos")]
+    pub fn with_chaos(mut self, chaos: Arc<Chaos>) -> Self {
+        self.chaos = Some(chaos);
+        self
+    }
+
+    /// Replaces the current configuration with the latest received update,
+    /// if any.
+    fn apply_conf_updates(&mut self) {
+        let Some(updates) = self.conf_updates.as_mut() else {
+            return;
+        };
+        if !updates.has_changed().unwrap_or_default() {
+            return;
+        }
+
+        let conf = *updates.borrow_and_update();
+        if conf.max_ongoing_requests == 0 {
+            warn!("ignoring databroker conf with max_ongoing_requests 0");
+            return;
+        }
+
+        if conf.max_ongoing_requests != self.conf.max_ongoing_requests {
+            // Ongoing requests release their permits to the previous
+            // semaphore, so the new limit is fully enforced once they are
+            // completed.
+            self.limit_ongoing_requests =
+                Arc::new(Semaphore::new(conf.max_ongoing_requests));
+        }
+
+        info!("DataBrokerSrv conf updated: {conf:?}");
+        self.conf = conf;
+    }
 }
 
 #[async_trait]
@@ -117,6 +182,8 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution>
         info!("data_broker service started");
 
         loop {
+            self.apply_conf_updates();
+
             // Wait until we can process a new request. We limit the number of
             // concurrent requests to mitigate a DoS attack.
             let permit =
@@ -125,14 +192,22 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution>
             // Wait for a request to process.
             let msg = self.inbound.recv().await?;
 
+            #[cfg(feature = "chaos")]
+            if let Some(chaos) = &self.chaos {
+                chaos.check_panic(ChaosTarget::DataBroker);
+            }
+
             let network = network.clone();
             let db = db.clone();
+            let blocks = self.blocks.clone();
             let conf = self.conf;
 
             // Spawn a task to handle the request asynchronously.
             tokio::spawn(async move {
-                match Self::handle_request::<N, DB>(&db, &network, &msg, &conf)
-                    .await
+                match Self::handle_request::<N, DB>(
+                    &db, &network, &blocks, &msg, &conf,
+                )
+                .await
                 {
                     Ok(resp) => {
                         // Send response
@@ -175,6 +250,7 @@ impl DataBrokerSrv {
     async fn handle_request<N: Network, DB: database::DB>(
         db: &Arc<RwLock<DB>>,
         network: &Arc<RwLock<N>>,
+        blocks: &BlockCache,
         msg: &Message,
         conf: &conf::Params,
     ) -> anyhow::Result<Response> {
@@ -214,8 +290,13 @@ impl DataBrokerSrv {
                     return Err(anyhow!("message has expired"));
                 }
 
-                match Self::handle_get_resource(db, m, conf.max_inv_entries)
-                    .await
+                match Self::handle_get_resource(
+                    db,
+                    blocks,
+                    m,
+                    conf.max_inv_entries,
+                )
+                .await
                 {
                     Ok(msg_list) => {
                         Ok(Response::new(msg_list, m.get_addr().unwrap()))
@@ -424,6 +505,7 @@ impl DataBrokerSrv {
     /// which could be either topics.Block or topics.Tx.
     async fn handle_get_resource<DB: database::DB>(
         db: &Arc<RwLock<DB>>,
+        blocks: &BlockCache,
         m: &node_data::message::payload::GetResource,
         max_entries: usize,
     ) -> Result<Vec<Message>> {
@@ -433,6 +515,15 @@ impl DataBrokerSrv {
         }
 
         db.read().await.view(|db| {
+            // Blocks are only served while in the ledger, as a cached one
+            // may have been reverted since
+            let block = |hash: &[u8; 32]| {
+                if !db.block_exists(hash).unwrap_or_default() {
+                    return None;
+                }
+                blocks.get_or_load(hash, || db.block(hash).ok().flatten())
+            };
+
             let res: Vec<Message> = m
                 .get_inv()
                 .inv_list
@@ -440,9 +531,10 @@ impl DataBrokerSrv {
                 .filter_map(|i| match i.inv_type {
                     InvType::BlockFromHeight => {
                         if let InvParam::Height(height) = &i.param {
-                            db.block_by_height(*height)
+                            db.block_hash_by_height(*height)
                                 .ok()
                                 .flatten()
+                                .and_then(|hash| block(&hash))
                                 .map(Message::from)
                         } else {
                             None
@@ -450,16 +542,14 @@ impl DataBrokerSrv {
                     }
                     InvType::BlockFromHash => {
                         if let InvParam::Hash(hash) = &i.param {
-                            db.block(hash).ok().flatten().map(Message::from)
+                            block(hash).map(Message::from)
                         } else {
                             None
                         }
                     }
                     InvType::CandidateFromHash => {
                         if let InvParam::Hash(hash) = &i.param {
-                            db.block(hash)
-                                .ok()
-                                .flatten()
+                            block(hash)
                                 .or_else(|| db.candidate(hash).ok().flatten())
                                 .map(Message::from)
                         } else {
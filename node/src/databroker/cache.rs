@@ -0,0 +1,141 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::sync::Mutex;
+
+use lru::LruCache;
+use metrics::counter;
+use node_data::ledger::Block;
+use node_data::Serializable;
+
+/// Recently served blocks, so that the same blocks requested by many peers
+/// catching up are not loaded from the database each time.
+///
+/// The blocks are kept serialized, so that the memory they take is bounded
+/// by their encoded size.
+pub(super) struct BlockCache {
+    blocks: Option<Mutex<Blocks>>,
+}
+
+struct Blocks {
+    entries: LruCache<[u8; 32], Vec<u8>>,
+    /// Total size of the entries, in bytes
+    size: usize,
+    max_size: usize,
+}
+
+impl BlockCache {
+    /// Creates a cache of at most `max_size` bytes of blocks, disabled if 0.
+    pub fn new(max_size: usize) -> Self {
+        let blocks = (max_size > 0).then(|| {
+            Mutex::new(Blocks {
+                entries: LruCache::unbounded(),
+                size: 0,
+                max_size,
+            })
+        });
+        Self { blocks }
+    }
+
+    /// Returns the block of `hash`, loading it with `load` if not cached.
+    pub fn get_or_load<F>(&self, hash: &[u8; 32], load: F) -> Option<Block>
+    where
+        F: FnOnce() -> Option<Block>,
+    {
+        let Some(blocks) = &self.blocks else {
+            return load();
+        };
+
+        let cached = blocks
+            .lock()
+            .expect("lock to be acquired")
+            .entries
+            .get(hash)
+            .and_then(|bytes| Block::read(&mut &bytes[..]).ok());
+        if let Some(block) = cached {
+            counter!("dusk_databroker_cache_hit").increment(1);
+            return Some(block);
+        }
+
+        counter!("dusk_databroker_cache_miss").increment(1);
+        let block = load()?;
+        let mut bytes = vec![];
+        if block.write(&mut bytes).is_ok() {
+            blocks
+                .lock()
+                .expect("lock to be acquired")
+                .put(*hash, bytes);
+        }
+        Some(block)
+    }
+}
+
+impl Blocks {
+    /// Caches the serialized block, evicting the least recently used ones
+    /// beyond the maximum size. A block larger than it is not cached.
+    fn put(&mut self, hash: [u8; 32], bytes: Vec<u8>) {
+        if bytes.len() > self.max_size {
+            return;
+        }
+
+        self.size += bytes.len();
+        if let Some(replaced) = self.entries.put(hash, bytes) {
+            self.size -= replaced.len();
+        }
+        while self.size > self.max_size {
+            match self.entries.pop_lru() {
+                Some((_, evicted)) => self.size -= evicted.len(),
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::{Fake, Faker};
+
+    use super::*;
+
+    fn size(block: &Block) -> usize {
+        let mut bytes = vec![];
+        block.write(&mut bytes).expect("block to serialize");
+        bytes.len()
+    }
+
+    #[test]
+    fn block_cache() {
+        let a: Block = Faker.fake();
+        let b: Block = Faker.fake();
+        let hash_a = a.header().hash;
+        let hash_b = b.header().hash;
+
+        // Room for the largest of the two blocks only
+        let cache = BlockCache::new(size(&a).max(size(&b)));
+
+        assert_eq!(
+            cache.get_or_load(&hash_a, || Some(a.clone())),
+            Some(a.clone())
+        );
+        // Served from the cache
+        assert_eq!(cache.get_or_load(&hash_a, || None), Some(a.clone()));
+
+        // The least recently used block is evicted
+        assert_eq!(cache.get_or_load(&hash_b, || Some(b.clone())), Some(b));
+        assert_eq!(cache.get_or_load(&hash_a, || None), None);
+
+        // A block larger than the cache is served but not cached
+        let small = BlockCache::new(1);
+        assert_eq!(
+            small.get_or_load(&hash_a, || Some(a.clone())),
+            Some(a.clone())
+        );
+        assert_eq!(small.get_or_load(&hash_a, || None), None);
+
+        let disabled = BlockCache::new(0);
+        assert_eq!(disabled.get_or_load(&hash_a, || None), None);
+    }
+}
@@ -11,10 +11,13 @@
 #[cfg(feature = "archive")]
 pub mod archive;
 pub mod chain;
+#[cfg(feature = "chaos")]
+pub mod chaos;
 pub mod database;
 pub mod databroker;
 pub mod mempool;
 pub mod network;
+pub mod switches;
 pub mod telemetry;
 pub mod vm;
 
@@ -24,14 +27,18 @@ use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use node_data::message::payload::Inv;
-use node_data::message::{AsyncQueue, Message};
+use node_data::message::{AsyncQueue, Capabilities, Message};
 use tokio::signal::unix::{signal, SignalKind};
-use tokio::sync::RwLock;
+use tokio::sync::{oneshot, RwLock};
 use tokio::task::JoinSet;
+use tokio::time::timeout;
 use tracing::{error, info, warn};
 
 pub use rusk_recovery_tools::state::DUSK_CONSENSUS_KEY;
 
+/// Maximum time a service is given to stop on shutdown.
+const SERVICE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Filter is used by Network implementor to filter messages before re-routing
 /// them. It's like the middleware in HTTP pipeline.
 ///
@@ -94,6 +101,22 @@ pub trait Network: Send + Sync + 'static {
     /// Retrieves number of alive nodes
     async fn alive_nodes_count(&self) -> usize;
 
+    /// Returns the capabilities negotiated with every known peer, which
+    /// the broadcast messages may rely on.
+    fn common_capabilities(&self) -> Capabilities {
+        Capabilities::LOCAL
+    }
+
+    /// Returns the bootstrapping host names to resolve again, if the node is
+    /// poorly connected.
+    async fn seeds_to_refresh(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Contacts the bootstrapping nodes resolved that are not in the routing
+    /// table yet.
+    async fn contact_bootstrap(&self, _nodes: Vec<String>) {}
+
     async fn wait_for_alive_nodes(&self, amount: usize, timeout: Duration) {
         let start = Instant::now();
         while self.alive_nodes_count().await < amount {
@@ -131,6 +154,21 @@ pub trait LongLivedService<N: Network, DB: database::DB, VM: vm::VMExecution>:
         vm: Arc<RwLock<VM>>,
     ) -> anyhow::Result<usize>;
 
+    /// Stops the service, once `execute` has been interrupted or has
+    /// terminated.
+    ///
+    /// On shutdown, services are stopped one at a time, in the order they
+    /// were spawned.
+    #[allow(unused_variables)]
+    async fn shutdown(
+        &mut self,
+        network: Arc<RwLock<N>>,
+        database: Arc<RwLock<DB>>,
+        vm: Arc<RwLock<VM>>,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     async fn add_routes(
         &self,
         my_topics: &[u8],
@@ -208,17 +246,19 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution> Node<N, DB, VM> {
     }
 
     /// Sets up and runs a list of services.
+    ///
+    /// On SIGINT or SIGTERM, the services are stopped in the order of
+    /// `service_list` and the database is flushed before returning.
     pub async fn spawn_all(
         &self,
         service_list: Vec<Box<dyn LongLivedService<N, DB, VM>>>,
     ) -> anyhow::Result<()> {
+        let mut interrupt = signal(SignalKind::interrupt())?;
+        let mut terminate = signal(SignalKind::terminate())?;
+
         // Spawn all services and join-wait for their termination.
         let mut set = JoinSet::new();
-        set.spawn(async {
-            signal(SignalKind::interrupt())?.recv().await;
-            // TODO: ResultCode
-            Ok(2)
-        });
+        let mut running = vec![];
 
         for mut s in service_list.into_iter() {
             let n = self.network.clone();
@@ -228,31 +268,66 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution> Node<N, DB, VM> {
             let name = s.name();
             info!("starting service {}", name);
 
-            set.spawn(async move { s.execute(n, d, vm).await });
+            let (stop, stop_recv) = oneshot::channel::<()>();
+            let (stopped_send, stopped) = oneshot::channel::<()>();
+
+            set.spawn(async move {
+                // Dropped, notifying the service is stopped, when the task
+                // terminates or is aborted
+                let _stopped = stopped_send;
+
+                let res = tokio::select! {
+                    res = s.execute(n.clone(), d.clone(), vm.clone()) => res,
+                    _ = stop_recv => Ok(0),
+                };
+
+                if let Err(e) = s.shutdown(n, d, vm).await {
+                    error!("service {name} failed to shutdown: {e}");
+                }
+                res
+            });
+
+            running.push((name, stop, stopped));
         }
 
-        // Wait for all spawned services to terminate with a result code or
-        // an error. Result code 1 means abort all services.
-        // This is usually triggered by SIGINIT signal.
-        while let Some(res) = set.join_next().await {
-            if let Ok(r) = res {
-                match r {
-                    Ok(rcode) => {
-                        // handle SIGTERM signal
-                        if rcode == 2 {
-                            set.abort_all();
-                        }
-                    }
-                    Err(e) => {
+        // Wait for a signal, logging the services terminating in the
+        // meantime.
+        loop {
+            tokio::select! {
+                _ = interrupt.recv() => {
+                    info!("received SIGINT");
+                    break;
+                }
+                _ = terminate.recv() => {
+                    info!("received SIGTERM");
+                    break;
+                }
+                res = set.join_next() => match res {
+                    Some(Ok(Err(e))) => {
                         error!("service terminated with err{}", e);
                     }
-                }
+                    Some(_) => {}
+                    None => break,
+                },
             }
         }
 
         info!("shutdown ...");
 
+        for (name, stop, stopped) in running {
+            info!("stopping service {name}");
+            let _ = stop.send(());
+            if timeout(SERVICE_SHUTDOWN_TIMEOUT, stopped).await.is_err() {
+                warn!("service {name} did not stop in time");
+            }
+        }
+
+        set.abort_all();
+        while set.join_next().await.is_some() {}
+
         // Release DataSource
+        info!("flushing database");
+        self.database.write().await.close();
 
         Ok(())
     }
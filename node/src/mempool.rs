@@ -5,6 +5,7 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 pub mod conf;
+pub mod policy;
 
 use std::sync::Arc;
 use std::time::Duration;
@@ -19,11 +20,15 @@ use node_data::ledger::{SpendingId, Transaction};
 use node_data::message::{payload, AsyncQueue, Payload, Topics};
 use thiserror::Error;
 use tokio::sync::mpsc::Sender;
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
 use tracing::{error, info, warn};
 
-use crate::database::{Ledger, Mempool};
+use crate::chain::TX_EXPIRY;
+use crate::database::rocksdb::MD_HASH_KEY;
+use crate::database::{Ledger, Mempool, Metadata};
 use crate::mempool::conf::Params;
+use crate::mempool::policy::{Admission, AllowAll, BlockExclusions, TxPolicy};
+use crate::switches::RuntimeSwitches;
 use crate::vm::PreverificationResult;
 use crate::{database, vm, LongLivedService, Message, Network};
 
@@ -45,10 +50,35 @@ pub enum TxAcceptanceError {
     GasLimitTooLow(u64),
     #[error("Maximum count of transactions exceeded {0}")]
     MaxTxnCountExceeded(usize),
+    #[error("this transaction is rejected by the policy: {0}")]
+    RejectedByPolicy(String),
+    #[error("this transaction expired at height {0}")]
+    Expired(u64),
+    #[error("transactions with an expiry height are not accepted yet")]
+    ExpiryNotActive,
     #[error("A generic error occurred {0}")]
     Generic(anyhow::Error),
 }
 
+impl TxAcceptanceError {
+    /// Stable identifier of the rejection reason, for the API clients.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::AlreadyExistsInMempool => "already_in_mempool",
+            Self::AlreadyExistsInLedger => "already_in_ledger",
+            Self::SpendIdExistsInMempool => "double_spend",
+            Self::VerificationFailed(_) => "verification_failed",
+            Self::GasPriceTooLow(_) => "gas_price_too_low",
+            Self::GasLimitTooLow(_) => "gas_limit_too_low",
+            Self::MaxTxnCountExceeded(_) => "mempool_full",
+            Self::RejectedByPolicy(_) => "rejected_by_policy",
+            Self::Expired(_) => "expired",
+            Self::ExpiryNotActive => "expiry_not_active",
+            Self::Generic(_) => "internal_error",
+        }
+    }
+}
+
 impl From<anyhow::Error> for TxAcceptanceError {
     fn from(err: anyhow::Error) -> Self {
         Self::Generic(err)
@@ -60,6 +90,14 @@ pub struct MempoolSrv {
     conf: Params,
     /// Sender channel for sending out RUES events
     event_sender: Sender<Event>,
+    /// Updates of the configuration to apply while running.
+    conf_updates: Option<watch::Receiver<Params>>,
+    switches: Arc<RuntimeSwitches>,
+    /// Policy of the operator on the transactions to relay
+    policy: Arc<dyn TxPolicy>,
+    /// Transactions the policy excluded from the generated blocks, if it
+    /// applies to them
+    block_exclusions: Option<Arc<BlockExclusions>>,
 }
 
 impl MempoolSrv {
@@ -72,8 +110,64 @@ impl MempoolSrv {
             ),
             conf,
             event_sender,
+            conf_updates: None,
+            switches: Arc::default(),
+            policy: Arc::new(AllowAll),
+            block_exclusions: None,
         }
     }
+
+    /// Sets the policy deciding which transactions are admitted in the
+    /// mempool, and so relayed.
+    pub fn with_tx_policy(mut self, policy: Arc<dyn TxPolicy>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Applies the policy to the blocks generated by this node as well,
+    /// recording in `exclusions` the transactions admitted in the mempool but
+    /// not allowed in the blocks.
+    pub fn with_block_exclusions(
+        mut self,
+        exclusions: Arc<BlockExclusions>,
+    ) -> Self {
+        self.block_exclusions = Some(exclusions);
+        self
+    }
+
+    /// Shares the runtime switches, to allow turning the broadcast of the
+    /// accepted transactions on and off while running.
+    pub fn with_switches(mut self, switches: Arc<RuntimeSwitches>) -> Self {
+        self.switches = switches;
+        self
+    }
+
+    /// Applies the configurations received from `updates` while the service
+    /// is running.
+    ///
+    /// A change of `max_queue_size` is only applied on restart.
+    pub fn with_conf_updates(
+        mut self,
+        updates: watch::Receiver<Params>,
+    ) -> Self {
+        self.conf_updates = Some(updates);
+        self
+    }
+
+    /// Replaces the current configuration with the latest received update,
+    /// returning `true` if it changed.
+    fn apply_conf_updates(&mut self) -> bool {
+        let Some(updates) = self.conf_updates.as_mut() else {
+            return false;
+        };
+        if !updates.has_changed().unwrap_or_default() {
+            return false;
+        }
+
+        self.conf = *updates.borrow_and_update();
+        info!("MempoolSrv conf updated: {}", self.conf);
+        true
+    }
 }
 
 #[async_trait]
@@ -97,10 +191,10 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution>
         // Request mempool update from N alive peers
         self.request_mempool(&network).await;
 
-        let idle_interval =
+        let mut idle_interval =
             self.conf.idle_interval.unwrap_or(DEFAULT_IDLE_INTERVAL);
 
-        let mempool_expiry = self
+        let mut mempool_expiry = self
             .conf
             .mempool_expiry
             .unwrap_or(DEFAULT_EXPIRY_TIME)
@@ -109,6 +203,20 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution>
         // Mempool service loop
         let mut on_idle_event = tokio::time::interval(idle_interval);
         loop {
+            if self.apply_conf_updates() {
+                let interval =
+                    self.conf.idle_interval.unwrap_or(DEFAULT_IDLE_INTERVAL);
+                if interval != idle_interval {
+                    idle_interval = interval;
+                    on_idle_event = tokio::time::interval(idle_interval);
+                }
+                mempool_expiry = self
+                    .conf
+                    .mempool_expiry
+                    .unwrap_or(DEFAULT_EXPIRY_TIME)
+                    .as_secs();
+            }
+
             tokio::select! {
                 biased;
                 _ = on_idle_event.tick() => {
@@ -131,11 +239,16 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution>
                                 vec![]
                             });
                             for deleted_tx_id in deleted_txs{
-                                let event = TransactionEvent::Removed(deleted_tx_id);
                                 info!(event = "mempool_deleted", hash = hex::encode(deleted_tx_id));
-                                if let Err(e) = self.event_sender.try_send(event.into()) {
-                                    warn!("cannot notify mempool removed transaction {e}")
-                                };
+                                let events = [
+                                    TransactionEvent::Evicted(deleted_tx_id, TransactionEvent::EXPIRED),
+                                    TransactionEvent::Removed(deleted_tx_id),
+                                ];
+                                for event in events {
+                                    if let Err(e) = self.event_sender.try_send(event.into()) {
+                                        warn!("cannot notify mempool removed transaction {e}")
+                                    };
+                                }
                             }
                         }
                         Ok(())
@@ -149,6 +262,11 @@ impl<N: Network, DB: database::DB, VM: vm::VMExecution>
                                 let accept = self.accept_tx(&db, &vm, tx);
                                 if let Err(e) = accept.await {
                                     error!("Tx {} not accepted: {e}", hex::encode(tx.id()));
+                                    self.notify_rejected(tx, &e);
+                                    continue;
+                                }
+
+                                if !self.switches.tx_gossip() {
                                     continue;
                                 }
 
@@ -180,9 +298,21 @@ impl MempoolSrv {
     ) -> Result<(), TxAcceptanceError> {
         let max_mempool_txn_count = self.conf.max_mempool_txn_count;
 
-        let events =
-            MempoolSrv::check_tx(db, vm, tx, false, max_mempool_txn_count)
-                .await?;
+        let events = MempoolSrv::check_admission(
+            self.policy.as_ref(),
+            db,
+            vm,
+            tx,
+            false,
+            max_mempool_txn_count,
+        )
+        .await?;
+
+        if let Some(exclusions) = &self.block_exclusions {
+            if let Err(reason) = self.policy.check(tx, Admission::Block).await {
+                exclusions.exclude(tx.id(), reason);
+            }
+        }
 
         tracing::info!(
             event = "transaction accepted",
@@ -199,6 +329,48 @@ impl MempoolSrv {
         Ok(())
     }
 
+    /// Notifies the rejection of `tx`, unless it was already known.
+    fn notify_rejected(&self, tx: &Transaction, err: &TxAcceptanceError) {
+        // Transactions gossiped more than once are not rejections
+        if matches!(
+            err,
+            TxAcceptanceError::AlreadyExistsInMempool
+                | TxAcceptanceError::AlreadyExistsInLedger
+        ) {
+            return;
+        }
+
+        let event = TransactionEvent::Rejected(tx.id(), err.to_string());
+        if let Err(e) = self.event_sender.try_send(event.into()) {
+            warn!("cannot notify mempool rejected transaction {e}")
+        };
+    }
+
+    /// Checks that `tx` is admitted in the mempool by `policy`.
+    pub async fn check_policy(
+        policy: &dyn TxPolicy,
+        tx: &Transaction,
+    ) -> Result<(), TxAcceptanceError> {
+        policy
+            .check(tx, Admission::Mempool)
+            .await
+            .map_err(TxAcceptanceError::RejectedByPolicy)
+    }
+
+    /// Runs the checks of a transaction submitted to the mempool: the
+    /// admission by `policy`, then the ones of [`Self::check_tx`].
+    pub async fn check_admission<'t, DB: database::DB, VM: vm::VMExecution>(
+        policy: &dyn TxPolicy,
+        db: &Arc<RwLock<DB>>,
+        vm: &Arc<RwLock<VM>>,
+        tx: &'t Transaction,
+        dry_run: bool,
+        max_mempool_txn_count: usize,
+    ) -> Result<Vec<TransactionEvent<'t>>, TxAcceptanceError> {
+        MempoolSrv::check_policy(policy, tx).await?;
+        MempoolSrv::check_tx(db, vm, tx, dry_run, max_mempool_txn_count).await
+    }
+
     pub async fn check_tx<'t, DB: database::DB, VM: vm::VMExecution>(
         db: &Arc<RwLock<DB>>,
         vm: &Arc<RwLock<VM>>,
@@ -236,6 +408,8 @@ impl MempoolSrv {
             }
         }
 
+        let upgrades = vm.read().await.upgrades();
+
         // Perform basic checks on the transaction
         let tx_to_delete = db.read().await.view(|view| {
             // ensure transaction does not exist in the mempool
@@ -248,6 +422,24 @@ impl MempoolSrv {
                 return Err(TxAcceptanceError::AlreadyExistsInLedger);
             }
 
+            // ensure transaction can still be included in the next block
+            if let Some(expiry_height) = tx.inner.expiry_height() {
+                let tip_height = match view.op_read(MD_HASH_KEY)? {
+                    Some(hash) => {
+                        view.block_header(&hash)?.map_or(0, |h| h.height)
+                    }
+                    None => 0,
+                };
+                // Before the upgrade, the payload extended by an expiry
+                // height is rejected by the transfer contract
+                if !upgrades.is_active(TX_EXPIRY, tip_height + 1) {
+                    return Err(TxAcceptanceError::ExpiryNotActive);
+                }
+                if tx.inner.is_expired_at(tip_height + 1) {
+                    return Err(TxAcceptanceError::Expired(expiry_height));
+                }
+            }
+
             let txs_count = view.mempool_txs_count();
             if txs_count >= max_mempool_txn_count {
                 // Get the lowest fee transaction to delete
@@ -310,6 +502,10 @@ impl MempoolSrv {
                 if let Some(m_tx) = db.mempool_tx(m_tx_id)? {
                     if m_tx.inner.gas_price() < tx.inner.gas_price() {
                         for deleted in db.delete_mempool_tx(m_tx_id, false)? {
+                            events.push(TransactionEvent::Evicted(
+                                deleted,
+                                TransactionEvent::REPLACED,
+                            ));
                             events.push(TransactionEvent::Removed(deleted));
                             replaced = true;
                         }
@@ -326,6 +522,10 @@ impl MempoolSrv {
             if !replaced {
                 if let Some(to_delete) = tx_to_delete {
                     for deleted in db.delete_mempool_tx(to_delete, true)? {
+                        events.push(TransactionEvent::Evicted(
+                            deleted,
+                            TransactionEvent::MEMPOOL_FULL,
+                        ));
                         events.push(TransactionEvent::Removed(deleted));
                     }
                 }
@@ -0,0 +1,366 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use lru::LruCache;
+use metrics::counter;
+use node_data::ledger::Transaction;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex as AsyncMutex;
+use tracing::{info, warn};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Maximum number of transactions remembered as excluded from the blocks.
+const MAX_BLOCK_EXCLUSIONS: usize = 100_000;
+
+/// Stage at which a transaction is submitted to a [`TxPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Admission {
+    /// Admission in the mempool, and so relay to the peers
+    Mempool,
+    /// Inclusion in a candidate block generated by this node
+    Block,
+}
+
+impl fmt::Display for Admission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Admission::Mempool => f.write_str("mempool"),
+            Admission::Block => f.write_str("block"),
+        }
+    }
+}
+
+/// Policy of the operator on the transactions this node relays and includes
+/// in the blocks it generates.
+///
+/// A policy only affects what this node does: the transactions it rejects
+/// remain valid for the rest of the network.
+///
+/// Both stages are checked when a transaction is admitted in the mempool, the
+/// decisions for the blocks being kept in [`BlockExclusions`], so that the
+/// policy is never waited for while generating a block.
+#[async_trait]
+pub trait TxPolicy: Send + Sync {
+    /// Checks whether `tx` is allowed at the `admission` stage, returning the
+    /// reason it is rejected otherwise.
+    async fn check(
+        &self,
+        tx: &Transaction,
+        admission: Admission,
+    ) -> Result<(), String>;
+}
+
+/// Policy allowing every transaction.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AllowAll;
+
+#[async_trait]
+impl TxPolicy for AllowAll {
+    async fn check(&self, _: &Transaction, _: Admission) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Transactions of the mempool a policy excluded from the blocks generated by
+/// this node, with the reason.
+///
+/// Only the last [`MAX_BLOCK_EXCLUSIONS`] transactions excluded are
+/// remembered.
+pub struct BlockExclusions {
+    excluded: Mutex<LruCache<[u8; 32], String>>,
+}
+
+impl Default for BlockExclusions {
+    fn default() -> Self {
+        let cap = NonZeroUsize::new(MAX_BLOCK_EXCLUSIONS)
+            .expect("capacity to be non zero");
+        Self {
+            excluded: Mutex::new(LruCache::new(cap)),
+        }
+    }
+}
+
+impl BlockExclusions {
+    /// Excludes the transaction with the given id from the blocks.
+    pub fn exclude(&self, id: [u8; 32], reason: String) {
+        let mut excluded = self.excluded.lock().expect("lock not poisoned");
+        excluded.put(id, reason);
+    }
+
+    /// Returns the reason the transaction with the given id is excluded from
+    /// the blocks, if it is.
+    pub fn reason(&self, id: &[u8; 32]) -> Option<String> {
+        let excluded = self.excluded.lock().expect("lock not poisoned");
+        excluded.peek(id).cloned()
+    }
+}
+
+/// Configuration of an [`ExternalPolicy`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalPolicyConfig {
+    /// Executable of the policy engine
+    pub command: PathBuf,
+
+    /// Arguments passed to the policy engine
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Whether the policy applies to the blocks generated by this node, on
+    /// top of the mempool
+    #[serde(default)]
+    pub blocks: bool,
+
+    /// Maximum time to wait for a decision
+    #[serde(with = "humantime_serde", default)]
+    pub timeout: Option<Duration>,
+
+    /// Whether the transactions are allowed when no decision can be obtained
+    /// from the policy engine
+    #[serde(default)]
+    pub fail_open: bool,
+}
+
+/// Policy delegating the decisions to an external process.
+///
+/// The process is spawned on the first check and receives one JSON request
+/// per line on its standard input:
+///
+/// `{"admission": "mempool", "tx": {..}}`
+///
+/// For each request, it writes on its standard output a line with the
+/// decision: `{"allow": true}` or `{"allow": false, "reason": ".."}`.
+///
+/// If the process does not answer in time, it is killed and spawned again on
+/// the next check.
+pub struct ExternalPolicy {
+    conf: ExternalPolicyConfig,
+    engine: AsyncMutex<Option<Engine>>,
+}
+
+#[derive(Serialize)]
+struct Request<'a> {
+    admission: Admission,
+    tx: &'a Transaction,
+}
+
+#[derive(Deserialize)]
+struct Decision {
+    allow: bool,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// A running policy engine, killed when dropped.
+struct Engine {
+    _child: Child,
+    stdin: ChildStdin,
+    decisions: Lines<BufReader<ChildStdout>>,
+}
+
+impl Engine {
+    fn spawn(conf: &ExternalPolicyConfig) -> anyhow::Result<Self> {
+        let mut child = Command::new(&conf.command)
+            .args(&conf.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let stdin = child.stdin.take().expect("stdin to be piped");
+        let stdout = child.stdout.take().expect("stdout to be piped");
+
+        info!(event = "tx policy engine spawned", cmd = ?conf.command);
+        Ok(Self {
+            _child: child,
+            stdin,
+            decisions: BufReader::new(stdout).lines(),
+        })
+    }
+
+    async fn decide(
+        &mut self,
+        request: &Request<'_>,
+    ) -> anyhow::Result<Decision> {
+        let mut line = serde_json::to_vec(request)?;
+        line.push(b'\n');
+        self.stdin.write_all(&line).await?;
+        self.stdin.flush().await?;
+
+        let decision = self
+            .decisions
+            .next_line()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("policy engine exited"))?;
+        Ok(serde_json::from_str(&decision)?)
+    }
+}
+
+impl ExternalPolicy {
+    pub fn new(conf: ExternalPolicyConfig) -> Self {
+        Self {
+            conf,
+            engine: AsyncMutex::new(None),
+        }
+    }
+
+    async fn decide(&self, request: &Request<'_>) -> anyhow::Result<Decision> {
+        let timeout = self.conf.timeout.unwrap_or(DEFAULT_TIMEOUT);
+
+        // Waiting for the engine busy with another check counts in the
+        // timeout, so that a stuck engine does not pile the checks up
+        let decision = tokio::time::timeout(timeout, async {
+            let mut engine = self.engine.lock().await;
+            if engine.is_none() {
+                *engine = Some(Engine::spawn(&self.conf)?);
+            }
+            let decision = engine
+                .as_mut()
+                .expect("engine to be spawned")
+                .decide(request)
+                .await;
+            if decision.is_err() {
+                *engine = None;
+            }
+            decision
+        })
+        .await;
+
+        match decision {
+            Ok(decision) => decision,
+            Err(_) => {
+                // The engine might be stuck with a pending answer, start
+                // afresh. A check cancelled while holding the engine released
+                // it, so it is free again.
+                if let Ok(mut engine) = self.engine.try_lock() {
+                    *engine = None;
+                }
+                anyhow::bail!("no decision within {timeout:?}")
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl TxPolicy for ExternalPolicy {
+    async fn check(
+        &self,
+        tx: &Transaction,
+        admission: Admission,
+    ) -> Result<(), String> {
+        let request = Request { admission, tx };
+
+        match self.decide(&request).await {
+            Ok(Decision { allow: true, .. }) => Ok(()),
+            Ok(Decision { reason, .. }) => {
+                counter!(format!("dusk_tx_policy_{admission}_rejected"))
+                    .increment(1);
+                Err(reason.unwrap_or_else(|| "rejected by policy".into()))
+            }
+            Err(e) => {
+                counter!("dusk_tx_policy_errors").increment(1);
+                warn!(
+                    event = "tx policy failed",
+                    hash = hex::encode(tx.id()),
+                    %admission,
+                    err = %e,
+                );
+                match self.conf.fail_open {
+                    true => Ok(()),
+                    false => Err(format!("policy engine failed: {e}")),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::{Fake, Faker};
+
+    use super::*;
+
+    fn shell_policy(script: &str, fail_open: bool) -> ExternalPolicy {
+        ExternalPolicy::new(ExternalPolicyConfig {
+            command: "sh".into(),
+            args: vec!["-c".into(), script.into()],
+            blocks: false,
+            timeout: Some(Duration::from_millis(200)),
+            fail_open,
+        })
+    }
+
+    #[tokio::test]
+    async fn external_policy_decisions() {
+        let tx: Transaction = Faker.fake();
+
+        let allow = shell_policy(
+            r#"while read -r _; do echo '{"allow": true}'; done"#,
+            false,
+        );
+        assert_eq!(allow.check(&tx, Admission::Mempool).await, Ok(()));
+        assert_eq!(allow.check(&tx, Admission::Block).await, Ok(()));
+
+        let deny = shell_policy(
+            concat!(
+                "while read -r _; do ",
+                r#"echo '{"allow": false, "reason": "nope"}'; "#,
+                "done"
+            ),
+            false,
+        );
+        assert_eq!(
+            deny.check(&tx, Admission::Mempool).await,
+            Err("nope".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn external_policy_failures() {
+        let tx: Transaction = Faker.fake();
+
+        // Never answers
+        let script = "while read -r _; do :; done";
+        let stuck = shell_policy(script, false);
+        assert!(stuck.check(&tx, Admission::Mempool).await.is_err());
+        // The stuck engine is replaced
+        assert!(stuck.engine.lock().await.is_none());
+        assert_eq!(
+            shell_policy(script, true)
+                .check(&tx, Admission::Mempool)
+                .await,
+            Ok(())
+        );
+
+        let missing = ExternalPolicy::new(ExternalPolicyConfig {
+            command: "/nonexistent/tx-policy".into(),
+            args: vec![],
+            blocks: false,
+            timeout: None,
+            fail_open: false,
+        });
+        assert!(missing.check(&tx, Admission::Block).await.is_err());
+    }
+
+    #[test]
+    fn block_exclusions() {
+        let exclusions = BlockExclusions::default();
+        exclusions.exclude([1; 32], "nope".into());
+        assert_eq!(exclusions.reason(&[1; 32]), Some("nope".into()));
+        assert_eq!(exclusions.reason(&[2; 32]), None);
+    }
+}
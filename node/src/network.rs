@@ -4,31 +4,97 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+#[cfg(feature = "network-sim")]
+use std::collections::BTreeMap;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::net::{AddrParseError, SocketAddr};
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
+use dusk_consensus::config::MAX_BLOCK_SIZE;
 use kadcast::config::Config;
 use kadcast::{MessageInfo, Peer};
 use metrics::counter;
+use node_data::identity::NodeIdentity;
 use node_data::message::payload::{GetResource, Inv, Nonce};
-use node_data::message::{AsyncQueue, Metadata, PROTOCOL_VERSION};
+use node_data::message::{
+    AsyncQueue, Capabilities, DecodeError, Metadata, Payload, Topics,
+    MIN_PROTOCOL_VERSION, PROTOCOL_VERSION,
+};
 use node_data::{get_current_timestamp, Serializable};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, trace, warn};
 
+#[cfg(feature = "chaos")]
+use crate::chaos::{Chaos, ChaosTarget};
 use crate::{BoxedFilter, Message};
 
+mod bandwidth;
+mod peers;
+mod protocol;
+mod seeds;
+#[cfg(feature = "network-sim")]
+mod sim;
+mod snapshot;
+use bandwidth::BandwidthScheduler;
+pub use bandwidth::{BandwidthConfig, Budget, Service};
+use peers::PeerActivity;
+pub use peers::PeerInfo;
+pub use protocol::PeerProtocol;
+use protocol::PeerProtocols;
+pub use seeds::resolve_bootstrapping_nodes;
+#[cfg(feature = "network-sim")]
+use sim::NetworkSim;
+#[cfg(feature = "network-sim")]
+pub use sim::NetworkSimStats;
+pub use snapshot::{
+    PeerSnapshot, PeerSnapshotConfig, SignedPeerSnapshot, SnapshotPeer,
+    DEFAULT_MAX_SNAPSHOT_AGE,
+};
+
 /// Number of alive peers randomly selected which a `flood_request` is sent to
 const REDUNDANCY_PEER_COUNT: usize = 8;
 
+/// Number of alive peers below which the bootstrapping host names are
+/// resolved again
+const MIN_ALIVE_PEERS: usize = 8;
+
+/// Minimum interval between two resolutions of the bootstrapping host names
+const SEEDS_RESOLUTION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Maximum size of a message received from the network: the largest block
+/// allowed by the consensus, with room for its attestations, the envelope of
+/// the message and the signature of the sender.
+const MAX_MESSAGE_SIZE: usize = MAX_BLOCK_SIZE + 64 * 1024;
+
+/// Returns whether messages of `topic` are control messages, signed with
+/// the identity of the node.
+///
+/// Consensus messages, blocks and transactions are authenticated on their
+/// own, so only the requests and inventories are signed.
+fn is_control(topic: Topics) -> bool {
+    matches!(
+        topic,
+        Topics::GetResource
+            | Topics::GetBlocks
+            | Topics::GetMempool
+            | Topics::Inv
+    )
+}
+
 type RoutesList<const N: usize> = [Option<AsyncQueue<Message>>; N];
 type FilterList<const N: usize> = [Option<BoxedFilter>; N];
 
 pub struct Listener<const N: usize> {
     routes: Arc<RwLock<RoutesList<N>>>,
     filters: Arc<RwLock<FilterList<N>>>,
+    protocols: Arc<PeerProtocols>,
+    activity: Arc<PeerActivity>,
+    #[cfg(feature = "network-sim")]
+    sim: Arc<NetworkSim>,
 }
 
 impl<const N: usize> Listener<N> {
@@ -57,9 +123,40 @@ impl<const N: usize> Listener<N> {
 
 impl<const N: usize> kadcast::NetworkListen for Listener<N> {
     fn on_message(&self, blob: Vec<u8>, md: MessageInfo) {
+        #[cfg(feature = "network-sim")]
+        if self.sim.drop_inbound() {
+            return;
+        }
+
         let msg_size = blob.len();
-        match Message::read(&mut &blob.to_vec()[..]) {
+        let decoded = Message::from_wire(&blob, MAX_MESSAGE_SIZE);
+        #[cfg(feature = "network-sim")]
+        self.sim.observe_received(decoded.is_ok());
+
+        match decoded {
             Ok(mut msg) => {
+                let height = match &msg.payload {
+                    Payload::Block(b) => Some(b.header().height),
+                    _ => None,
+                };
+                let control = is_control(msg.topic());
+                if !self.activity.observe(
+                    md.src(),
+                    height,
+                    msg.identity,
+                    control,
+                ) {
+                    debug!(event = "msg discarded", src = ?md.src());
+                    return;
+                }
+                self.protocols.observe(
+                    md.src(),
+                    PeerProtocol {
+                        version: msg.version.clone(),
+                        capabilities: msg.capabilities,
+                    },
+                );
+
                 counter!("dusk_bytes_recv").increment(msg_size as u64);
                 counter!(format!("dusk_inbound_{:?}_size", msg.topic()))
                     .increment(msg_size as u64);
@@ -98,9 +195,33 @@ impl<const N: usize> kadcast::NetworkListen for Listener<N> {
                 // Reroute message to the upper layer
                 self.reroute(msg.topic().into(), msg);
             }
+            Err(DecodeError::UnsupportedVersion(version)) => {
+                self.activity.observe(md.src(), None, None, false);
+                self.protocols.observe(
+                    md.src(),
+                    PeerProtocol {
+                        version,
+                        capabilities: Capabilities::EMPTY,
+                    },
+                );
+            }
+            Err(DecodeError::InvalidSignature) => {
+                counter!("dusk_inbound_invalid_signature").increment(1);
+                warn!(event = "invalid signature", src = ?md.src());
+            }
+            Err(DecodeError::StaleSignature(timestamp)) => {
+                // Either replayed or sent by a peer with a skewed clock
+                counter!("dusk_inbound_stale_signature").increment(1);
+                debug!(event = "stale signature", src = ?md.src(), timestamp);
+            }
+            Err(DecodeError::UnknownTopic(topic)) => {
+                // Sent by a peer running a newer protocol
+                counter!("dusk_inbound_unknown_topic").increment(1);
+                debug!(event = "unknown topic", src = ?md.src(), topic);
+            }
             Err(err) => {
                 // Dump message blob and topic number
-                let topic = blob.get(node_data::message::TOPIC_FIELD_POS);
+                let topic = Message::wire_topic(&blob);
                 error!("err: {err}, msg_topic: {topic:?}",);
             }
         };
@@ -108,19 +229,46 @@ impl<const N: usize> kadcast::NetworkListen for Listener<N> {
 }
 
 pub struct Kadcast<const N: usize> {
-    peer: Peer,
+    peer: Arc<Peer>,
     routes: Arc<RwLock<RoutesList<N>>>,
     filters: Arc<RwLock<FilterList<N>>>,
+    protocols: Arc<PeerProtocols>,
+    activity: Arc<PeerActivity>,
+    bandwidth: BandwidthScheduler,
+    #[cfg(feature = "network-sim")]
+    sim: Arc<NetworkSim>,
+    #[cfg(feature = "chaos")]
+    chaos: Option<Arc<Chaos>>,
     conf: Config,
 
     /// Represents a parsed conf.public_addr
     public_addr: SocketAddr,
 
     counter: AtomicU64,
+
+    /// Bootstrapping entries as configured, before resolution
+    seeds: Vec<String>,
+    last_seeds_resolution: Mutex<Instant>,
+
+    /// Identity the control messages are signed with, if any
+    identity: Option<NodeIdentity>,
 }
 
 impl<const N: usize> Kadcast<N> {
-    pub fn new(mut conf: Config) -> Result<Self, AddrParseError> {
+    /// Creates the network, resolving the bootstrapping host names of `conf`.
+    pub async fn new(conf: Config) -> Result<Self, AddrParseError> {
+        Self::with_known_peers(conf, vec![]).await
+    }
+
+    /// Creates the network, bootstrapping the routing table from the
+    /// `known` peers along with the bootstrapping nodes of `conf`.
+    ///
+    /// The known peers are inserted in the buckets of the routing table as
+    /// they answer, and are not reported as bootstrapping nodes.
+    pub async fn with_known_peers(
+        mut conf: Config,
+        known: Vec<SocketAddr>,
+    ) -> Result<Self, AddrParseError> {
         const INIT: Option<AsyncQueue<Message>> = None;
         let routes = Arc::new(RwLock::new([INIT; N]));
 
@@ -131,30 +279,94 @@ impl<const N: usize> Kadcast<N> {
             "Loading network with public_address {} and private_address {:?}",
             &conf.public_address, &conf.listen_address
         );
+        let protocols = Arc::new(PeerProtocols::default());
+        let activity = Arc::new(PeerActivity::default());
+        #[cfg(feature = "network-sim")]
+        let sim = Arc::new(NetworkSim::default());
         let listener = Listener {
             routes: routes.clone(),
             filters: filters.clone(),
+            protocols: protocols.clone(),
+            activity: activity.clone(),
+            #[cfg(feature = "network-sim")]
+            sim: sim.clone(),
         };
+        let seeds = conf.bootstrapping_nodes.clone();
+        conf.bootstrapping_nodes = resolve_bootstrapping_nodes(&seeds).await;
+        info!("Bootstrapping from {:?}", &conf.bootstrapping_nodes);
+
         conf.version = format!("{PROTOCOL_VERSION}");
-        conf.version_match = format!("{PROTOCOL_VERSION}");
-        let peer = Peer::new(conf.clone(), listener)?;
+        conf.version_match = format!("{MIN_PROTOCOL_VERSION}");
         let public_addr = conf
             .public_address
             .parse::<SocketAddr>()
             .expect("valid kadcast public address");
 
+        let mut peer_conf = conf.clone();
+        let known = known
+            .into_iter()
+            .filter(|addr| addr != &public_addr)
+            .map(|addr| addr.to_string())
+            .filter(|addr| !peer_conf.bootstrapping_nodes.contains(addr))
+            .collect::<Vec<_>>();
+        if !known.is_empty() {
+            info!("Bootstrapping from {} known peers", known.len());
+        }
+        peer_conf.bootstrapping_nodes.extend(known);
+        let peer = Arc::new(Peer::new(peer_conf, listener)?);
+
         let nonce = Nonce::from(public_addr.ip());
 
         Ok(Kadcast {
             routes,
             filters,
+            protocols,
+            activity,
+            bandwidth: BandwidthScheduler::default(),
+            #[cfg(feature = "network-sim")]
+            sim,
+            #[cfg(feature = "chaos")]
+            chaos: None,
             peer,
             conf,
             public_addr,
             counter: AtomicU64::new(nonce.into()),
+            seeds,
+            last_seeds_resolution: Mutex::new(Instant::now()),
+            identity: None,
         })
     }
 
+    /// Sets the outbound bandwidth budgets of the services.
+    pub fn with_bandwidth(mut self, conf: BandwidthConfig) -> Self {
+        self.bandwidth = BandwidthScheduler::new(&conf);
+        self
+    }
+
+    /// Injects the faults of `chaos` into the outbound messages.
+    #[cfg(feature = "chaos")]
+    pub fn with_chaos(mut self, chaos: Arc<Chaos>) -> Self {
+        self.chaos = Some(chaos);
+        self
+    }
+
+    /// Returns whether an outbound message is dropped by the injected faults.
+    fn chaos_drop(&self) -> bool {
+        #[cfg(feature = "chaos")]
+        if let Some(chaos) = &self.chaos {
+            chaos.check_panic(ChaosTarget::Kadcast);
+            return chaos.drop_outbound();
+        }
+        false
+    }
+
+    /// Signs the control messages sent to the peers with `identity`.
+    pub fn with_identity(mut self, identity: NodeIdentity) -> Self {
+        info!("Signing control messages as {}", identity.id());
+        self.identity = Some(identity);
+        self
+    }
+
     pub fn route_internal(&self, msg: Message) {
         let topic = msg.topic() as usize;
         let routes = self.routes.clone();
@@ -179,21 +391,178 @@ impl<const N: usize> Kadcast<N> {
             .collect()
     }
 
+    /// Returns the peers of the routing table, ordered by bucket.
+    pub async fn peers(&self) -> Vec<PeerInfo> {
+        let alive: HashSet<_> = self
+            .peer
+            .alive_nodes(u16::MAX as usize)
+            .await
+            .into_iter()
+            .collect();
+
+        let mut peers: Vec<_> = self
+            .peer
+            .to_route_table()
+            .await
+            .into_iter()
+            .flat_map(|(bucket, nodes)| {
+                nodes.into_iter().map(move |(addr, _)| (bucket, addr))
+            })
+            .map(|(bucket, address)| {
+                let activity = self.activity.get(&address);
+                let protocol = self.protocols.get(&address);
+                PeerInfo {
+                    address,
+                    bucket,
+                    alive: alive.contains(&address),
+                    last_seen: activity.map(|a| a.last_seen),
+                    height: activity.and_then(|a| a.height),
+                    version: protocol.as_ref().map(|p| p.version.to_string()),
+                    compatible: protocol.map(|p| p.version.is_supported()),
+                    identity: activity
+                        .and_then(|a| a.identity)
+                        .map(|id| id.to_string()),
+                    banned_until: self.activity.banned_until(address),
+                }
+            })
+            .collect();
+        peers.sort_by_key(|p| (p.bucket, p.address));
+        peers
+    }
+
+    /// Returns a snapshot of the healthy peers of the routing table, signed
+    /// with the identity of the node.
+    pub async fn peer_snapshot(&self) -> anyhow::Result<SignedPeerSnapshot> {
+        let identity = self.identity.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("A node identity is required to sign snapshots")
+        })?;
+        let peers = self.peers().await;
+        Ok(PeerSnapshot::new(&peers, identity.id()).sign(identity))
+    }
+
+    /// Returns the bucket occupancy, along with the broadcast and decoding
+    /// statistics observed locally.
+    #[cfg(feature = "network-sim")]
+    pub async fn sim_stats(&self) -> NetworkSimStats {
+        let buckets: BTreeMap<_, _> = self
+            .peer
+            .to_route_table()
+            .await
+            .into_iter()
+            .map(|(bucket, nodes)| (bucket, nodes.len()))
+            .collect();
+        self.sim.stats(buckets)
+    }
+
+    /// Drops the given ratio of the inbound messages, for chaos testing.
+    #[cfg(feature = "network-sim")]
+    pub fn set_sim_loss(&self, loss: f64) -> anyhow::Result<()> {
+        self.sim.set_loss(loss)
+    }
+
     pub fn conf(&self) -> &Config {
         &self.conf
     }
 
+    /// Returns the protocol advertised by `peer`, if a message was received
+    /// from it.
+    pub fn peer_protocol(&self, peer: &SocketAddr) -> Option<PeerProtocol> {
+        self.protocols.get(peer)
+    }
+
+    /// Sends `msg` to `recv_addr`, encoded in the layout and with the
+    /// capabilities each peer understands, and signing it for the peers able
+    /// to verify it if it is a control message and the node has an identity.
+    ///
+    /// Peers never heard from are sent the message as is.
+    async fn send_signed(
+        &self,
+        msg: &Message,
+        recv_addr: Vec<SocketAddr>,
+    ) -> anyhow::Result<()> {
+        if self.chaos_drop() {
+            return Ok(());
+        }
+
+        let mut by_protocol: HashMap<_, Vec<_>> = HashMap::new();
+        for addr in recv_addr {
+            let key = match self.protocols.get(&addr) {
+                Some(p) => (p.version.clone(), p.negotiated()),
+                None => (msg.version.clone(), msg.capabilities),
+            };
+            by_protocol.entry(key).or_default().push(addr);
+        }
+
+        let topic = msg.topic();
+        for ((version, capabilities), addrs) in by_protocol {
+            let mut msg = msg.for_peer(&version, capabilities);
+            let identity = match &self.identity {
+                Some(identity)
+                    if is_control(topic)
+                        && msg
+                            .capabilities
+                            .contains(Capabilities::IDENTITY) =>
+                {
+                    msg = msg.with_capabilities(
+                        msg.capabilities.union(Capabilities::SIGNED),
+                    );
+                    Some(identity)
+                }
+                _ => None,
+            };
+
+            let mut encoded = vec![];
+            msg.write(&mut encoded)
+                .map_err(|err| anyhow::anyhow!("failed to encode: {err}"))?;
+            if let Some(identity) = identity {
+                identity.sign(&mut encoded);
+            }
+            self.send_with_metrics(topic, encoded, addrs).await;
+        }
+
+        Ok(())
+    }
+
     async fn send_with_metrics(
         &self,
-        bytes: &Vec<u8>,
+        topic: Topics,
+        bytes: Vec<u8>,
         recv_addr: Vec<SocketAddr>,
     ) {
         if !recv_addr.is_empty() {
             let bytes_sent = bytes.len() * recv_addr.len();
-            counter!("dusk_bytes_sent").increment(bytes_sent as u64);
-            self.peer.send_to_peers(bytes, recv_addr).await;
+            let peer = self.peer.clone();
+            self.throttle(topic, bytes_sent, async move {
+                counter!("dusk_bytes_sent").increment(bytes_sent as u64);
+                peer.send_to_peers(&bytes, recv_addr).await;
+            })
+            .await;
         }
     }
+
+    /// Runs `send` once `bytes` of a message of `topic` fit in the bandwidth
+    /// budgets, returning `false` if the message is dropped instead.
+    ///
+    /// A delayed message is sent from a task of its own, so that the callers
+    /// do not hold the network while it waits.
+    async fn throttle(
+        &self,
+        topic: Topics,
+        bytes: usize,
+        send: impl Future<Output = ()> + Send + 'static,
+    ) -> bool {
+        match self.bandwidth.reserve(topic, bytes) {
+            Some(delay) if delay.is_zero() => send.await,
+            Some(delay) => {
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    send.await;
+                });
+            }
+            None => return false,
+        }
+        true
+    }
 }
 
 #[async_trait]
@@ -215,17 +584,48 @@ impl<const N: usize> crate::Network for Kadcast<N> {
             None => None,
         };
 
+        if self.chaos_drop() {
+            return Ok(());
+        }
+
+        // The peers relay the broadcast messages as they are, so they are
+        // encoded in the protocol every tracked peer understands
+        let common = self.protocols.common();
+        let negotiated;
+        let msg = if common.version != msg.version
+            || !common.capabilities.contains(msg.capabilities)
+        {
+            negotiated = msg.for_peer(&common.version, common.capabilities);
+            &negotiated
+        } else {
+            msg
+        };
+
         let mut encoded = vec![];
         msg.write(&mut encoded).map_err(|err| {
             error!("could not encode message {msg:?}: {err}");
             anyhow::anyhow!("failed to broadcast: {err}")
         })?;
 
-        counter!("dusk_bytes_cast").increment(encoded.len() as u64);
-        counter!(format!("dusk_outbound_{:?}_size", msg.topic()))
-            .increment(encoded.len() as u64);
+        // Kadcast sends the message to a peer of each bucket
+        let buckets = self.peer.to_route_table().await.len().max(1);
+        let bytes_cast = encoded.len() * buckets;
+
+        let topic = msg.topic();
+        let peer = self.peer.clone();
+        let send = async move {
+            counter!("dusk_bytes_cast").increment(encoded.len() as u64);
+            counter!(format!("dusk_outbound_{topic:?}_size"))
+                .increment(encoded.len() as u64);
+
+            peer.broadcast(&encoded, height).await;
+        };
+        if !self.throttle(topic, bytes_cast, send).await {
+            return Ok(());
+        }
 
-        self.peer.broadcast(&encoded, height).await;
+        #[cfg(feature = "network-sim")]
+        self.sim.observe_broadcast(buckets);
 
         Ok(())
     }
@@ -273,21 +673,16 @@ impl<const N: usize> crate::Network for Kadcast<N> {
 
         msg.payload.set_nonce(rnd_count);
 
-        let mut encoded = vec![];
-        msg.write(&mut encoded)
-            .map_err(|err| anyhow::anyhow!("failed to send_to_peer: {err}"))?;
-        let topic = msg.topic();
-
         debug!(
           event = "Sending msg",
-          topic = ?topic,
+          topic = ?msg.topic(),
           info = ?msg.header,
           destination = ?recv_addr
         );
 
-        self.send_with_metrics(&encoded, vec![recv_addr]).await;
-
-        Ok(())
+        self.send_signed(&msg, vec![recv_addr])
+            .await
+            .map_err(|err| anyhow::anyhow!("failed to send_to_peer: {err}"))
     }
 
     /// Sends to random set of alive peers.
@@ -301,9 +696,6 @@ impl<const N: usize> crate::Network for Kadcast<N> {
 
         msg.payload.set_nonce(rnd_count);
 
-        let mut encoded = vec![];
-        msg.write(&mut encoded)
-            .map_err(|err| anyhow::anyhow!("failed to encode: {err}"))?;
         let topic = msg.topic();
 
         counter!(format!("dusk_requests_{:?}", topic)).increment(1);
@@ -332,9 +724,7 @@ impl<const N: usize> crate::Network for Kadcast<N> {
             );
         }
         trace!("sending msg ({topic:?}) to peers {alive_nodes:?}");
-        self.send_with_metrics(&encoded, alive_nodes).await;
-
-        Ok(())
+        self.send_signed(&msg, alive_nodes).await
     }
 
     /// Route any message of the specified type to this queue.
@@ -385,4 +775,55 @@ impl<const N: usize> crate::Network for Kadcast<N> {
         // TODO: This call should be replaced with no-copy Kadcast API
         self.peer.alive_nodes(u16::MAX as usize).await.len()
     }
+
+    fn common_capabilities(&self) -> Capabilities {
+        self.protocols.common().capabilities
+    }
+
+    /// Returns the bootstrapping host names to resolve again when there are
+    /// few alive peers.
+    async fn seeds_to_refresh(&self) -> Option<Vec<String>> {
+        if !seeds::has_host_names(&self.seeds)
+            || self.alive_nodes_count().await >= MIN_ALIVE_PEERS
+        {
+            return None;
+        }
+
+        let mut last = self
+            .last_seeds_resolution
+            .lock()
+            .expect("lock to be acquired");
+        if last.elapsed() < SEEDS_RESOLUTION_INTERVAL {
+            return None;
+        }
+        *last = Instant::now();
+
+        Some(self.seeds.clone())
+    }
+
+    async fn contact_bootstrap(&self, nodes: Vec<String>) {
+        let table = self.table().await;
+        let new_nodes: Vec<SocketAddr> = nodes
+            .iter()
+            .filter_map(|node| node.parse().ok())
+            .filter(|addr| addr != &self.public_addr && !table.contains(addr))
+            .collect();
+        if new_nodes.is_empty() {
+            return;
+        }
+
+        info!(
+            event = "contacting bootstrapping nodes",
+            count = new_nodes.len()
+        );
+
+        // An empty inventory is not answered, but makes the nodes aware of
+        // this one, so that they start propagating to it
+        let msg: Message = Inv::new(0).into();
+        for addr in new_nodes {
+            if let Err(err) = self.send_to_peer(msg.clone(), addr).await {
+                warn!(event = "cannot contact bootstrapping node", %addr, %err);
+            }
+        }
+    }
 }
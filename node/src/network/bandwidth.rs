@@ -0,0 +1,308 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Budgeting of the outbound bandwidth among the services of the node.
+//!
+//! Every message is sent within the budget of the link, shared by all the
+//! services, and the one of its service if any. Consensus messages are sent
+//! as soon as their own budget allows, ahead of the messages of the other
+//! services, so that a node on a capped link keeps consensus flowing while
+//! gossiping transactions and serving blocks with the bandwidth left.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use metrics::counter;
+use node_data::message::Topics;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// Default longest delay of a message waiting for its budget
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(2);
+
+/// Returns whether messages of `topic` are needed by the peers to sync, so
+/// that they are delayed as long as needed rather than dropped.
+fn is_sync_critical(topic: Topics) -> bool {
+    matches!(
+        topic,
+        Topics::Block | Topics::Inv | Topics::GetResource | Topics::GetBlocks
+    )
+}
+
+/// Service the outbound messages are accounted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Service {
+    Consensus,
+    Mempool,
+    Databroker,
+}
+
+impl From<Topics> for Service {
+    fn from(topic: Topics) -> Self {
+        match topic {
+            t if t.is_consensus_msg() => Service::Consensus,
+            Topics::Tx | Topics::GetMempool => Service::Mempool,
+            _ => Service::Databroker,
+        }
+    }
+}
+
+/// Outbound bandwidth a service is allowed to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Budget {
+    /// Sustained rate, in bytes per second
+    pub rate: u64,
+
+    /// Bytes that can be sent at once after an idle period, the rate if not
+    /// set
+    #[serde(default)]
+    pub burst: Option<u64>,
+}
+
+/// Outbound bandwidth budgets of the link and of the services, unlimited if
+/// not set.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct BandwidthConfig {
+    /// Budget of the link, shared by all the services
+    pub total: Option<Budget>,
+    pub consensus: Option<Budget>,
+    pub mempool: Option<Budget>,
+    pub databroker: Option<Budget>,
+
+    /// Longest delay of a message waiting for its budget, after which it is
+    /// dropped unless needed by the peers to sync
+    #[serde(with = "humantime_serde", default)]
+    pub max_delay: Option<Duration>,
+}
+
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl TokenBucket {
+    fn new(budget: &Budget, now: Instant) -> Self {
+        let capacity = budget.burst.unwrap_or(budget.rate).max(1) as f64;
+        Self {
+            rate: budget.rate.max(1) as f64,
+            capacity,
+            tokens: capacity,
+            last: now,
+        }
+    }
+
+    /// Returns how long to wait before `bytes` fit in the bucket.
+    fn delay(&mut self, bytes: usize, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last = now;
+
+        let bytes = bytes as f64;
+        if self.tokens >= bytes {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((bytes - self.tokens) / self.rate)
+        }
+    }
+
+    /// Takes `bytes` from the bucket.
+    ///
+    /// The tokens can go negative, so that messages larger than the burst
+    /// are delayed rather than never sent, and the messages reserved next
+    /// wait for them.
+    fn take(&mut self, bytes: usize) {
+        self.tokens -= bytes as f64;
+    }
+}
+
+/// Schedules the outbound messages within the budgets of the link and of
+/// their services.
+pub(crate) struct BandwidthScheduler {
+    total: Option<Mutex<TokenBucket>>,
+    consensus: Option<Mutex<TokenBucket>>,
+    mempool: Option<Mutex<TokenBucket>>,
+    databroker: Option<Mutex<TokenBucket>>,
+    max_delay: Duration,
+}
+
+impl Default for BandwidthScheduler {
+    fn default() -> Self {
+        Self::new(&BandwidthConfig::default())
+    }
+}
+
+impl BandwidthScheduler {
+    pub fn new(conf: &BandwidthConfig) -> Self {
+        let now = Instant::now();
+        let bucket = |b: &Option<Budget>| {
+            b.map(|b| Mutex::new(TokenBucket::new(&b, now)))
+        };
+        Self {
+            total: bucket(&conf.total),
+            consensus: bucket(&conf.consensus),
+            mempool: bucket(&conf.mempool),
+            databroker: bucket(&conf.databroker),
+            max_delay: conf.max_delay.unwrap_or(DEFAULT_MAX_DELAY),
+        }
+    }
+
+    /// Reserves `bytes` of a message of `topic` in the budgets, returning
+    /// how long to wait before sending it, or `None` if it should be dropped
+    /// as it would wait longer than allowed.
+    ///
+    /// Nothing is reserved for the messages dropped. The wait is left to the
+    /// caller, so that it is not spent holding the network.
+    pub fn reserve(&self, topic: Topics, bytes: usize) -> Option<Duration> {
+        self.reserve_at(topic, bytes, Instant::now())
+    }
+
+    fn reserve_at(
+        &self,
+        topic: Topics,
+        bytes: usize,
+        now: Instant,
+    ) -> Option<Duration> {
+        let service = Service::from(topic);
+        let bucket = match service {
+            Service::Consensus => &self.consensus,
+            Service::Mempool => &self.mempool,
+            Service::Databroker => &self.databroker,
+        };
+        let lock = |b: &Option<Mutex<TokenBucket>>| {
+            b.as_ref().map(|b| b.lock().expect("lock to be acquired"))
+        };
+        // Always locked in the same order
+        let mut bucket = lock(bucket);
+        let mut total = lock(&self.total);
+
+        let own_delay = bucket
+            .as_mut()
+            .map_or(Duration::ZERO, |b| b.delay(bytes, now));
+        // Consensus messages go ahead of the others, which wait for the
+        // bandwidth they take from the link
+        let link_delay = match (&mut total, service) {
+            (Some(total), Service::Consensus) => {
+                // Refills the link budget before taking from it
+                let _ = total.delay(bytes, now);
+                Duration::ZERO
+            }
+            (Some(total), _) => total.delay(bytes, now),
+            (None, _) => Duration::ZERO,
+        };
+        let delay = own_delay.max(link_delay);
+
+        if delay > self.max_delay && !is_sync_critical(topic) {
+            counter!(format!("dusk_bandwidth_{service:?}_dropped"))
+                .increment(1);
+            debug!(event = "outbound msg dropped", ?topic, bytes);
+            return None;
+        }
+
+        for b in [bucket.as_mut(), total.as_mut()].into_iter().flatten() {
+            b.take(bytes);
+        }
+        if !delay.is_zero() {
+            counter!(format!("dusk_bandwidth_{service:?}_delayed"))
+                .increment(1);
+        }
+        Some(delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_bucket() {
+        let start = Instant::now();
+        let budget = Budget {
+            rate: 1_000,
+            burst: Some(2_000),
+        };
+        let mut bucket = TokenBucket::new(&budget, start);
+
+        // The burst is available at once
+        assert_eq!(bucket.delay(2_000, start), Duration::ZERO);
+        bucket.take(2_000);
+
+        // Then the rate applies
+        assert_eq!(bucket.delay(500, start), Duration::from_millis(500));
+        bucket.take(500);
+        let later = start + Duration::from_millis(500);
+        assert_eq!(bucket.delay(1_000, later), Duration::from_secs(1));
+    }
+
+    fn scheduler(conf: BandwidthConfig) -> BandwidthScheduler {
+        BandwidthScheduler::new(&BandwidthConfig {
+            max_delay: Some(Duration::from_secs(2)),
+            ..conf
+        })
+    }
+
+    fn budget(rate: u64) -> Option<Budget> {
+        Some(Budget { rate, burst: None })
+    }
+
+    #[test]
+    fn services_share_the_link() {
+        let now = Instant::now();
+        let scheduler = scheduler(BandwidthConfig {
+            total: budget(1_000),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            scheduler.reserve_at(Topics::Tx, 1_000, now),
+            Some(Duration::ZERO)
+        );
+        // The link budget spent by the mempool delays the databroker
+        assert_eq!(
+            scheduler.reserve_at(Topics::Block, 1_000, now),
+            Some(Duration::from_secs(1))
+        );
+
+        // Consensus messages are not delayed by the other services, but
+        // delay them
+        assert_eq!(
+            scheduler.reserve_at(Topics::Validation, 1_000, now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(scheduler.reserve_at(Topics::Tx, 1_000, now), None);
+    }
+
+    #[test]
+    fn sync_critical_messages_are_never_dropped() {
+        let now = Instant::now();
+        let scheduler = scheduler(BandwidthConfig {
+            databroker: budget(1_000),
+            mempool: budget(1_000),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            scheduler.reserve_at(Topics::Block, 5_000, now),
+            Some(Duration::from_secs(4))
+        );
+        for topic in [Topics::Inv, Topics::GetResource, Topics::GetBlocks] {
+            assert!(scheduler.reserve_at(topic, 1_000, now).is_some());
+        }
+
+        assert_eq!(
+            scheduler.reserve_at(Topics::Tx, 1_000, now),
+            Some(Duration::ZERO)
+        );
+        // Messages dropped are not accounted
+        assert_eq!(scheduler.reserve_at(Topics::Tx, 5_000, now), None);
+        let later = now + Duration::from_secs(1);
+        assert_eq!(
+            scheduler.reserve_at(Topics::Tx, 1_000, later),
+            Some(Duration::ZERO)
+        );
+    }
+}
@@ -0,0 +1,388 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::net::SocketAddr;
+use std::num::NonZeroUsize;
+use std::sync::{Mutex, RwLock};
+
+use lru::LruCache;
+use metrics::counter;
+use node_data::get_current_timestamp;
+use node_data::identity::NodeId;
+use serde::Serialize;
+use tracing::warn;
+
+/// A peer of the Kadcast routing table, as known by this node.
+#[derive(Debug, Clone, Serialize)]
+pub struct PeerInfo {
+    pub address: SocketAddr,
+    /// Bucket of the routing table, that is the distance from this node
+    pub bucket: u8,
+    pub alive: bool,
+    /// Timestamp of the last message received from the peer
+    pub last_seen: Option<u64>,
+    /// Height of the last block received from the peer
+    pub height: Option<u64>,
+    /// Protocol version advertised by the peer
+    pub version: Option<String>,
+    /// Whether the protocol of the peer is supported, its messages being
+    /// discarded otherwise
+    pub compatible: Option<bool>,
+    /// Base58 identity the last signed message of the peer was signed with
+    pub identity: Option<String>,
+    /// Timestamp the ban of the peer ends at, if its messages are discarded
+    /// for flooding
+    pub banned_until: Option<u64>,
+}
+
+/// Time, in seconds, after which an address whose identity stopped signing
+/// messages can be bound to another identity
+const IDENTITY_REBIND_AFTER: u64 = 300;
+
+/// Maximum number of control messages a peer can send in a second before
+/// being banned
+const MAX_CONTROL_RATE: u32 = 1000;
+
+/// Time, in seconds, a peer flooding control messages is banned for
+const BAN_DURATION: u64 = 600;
+
+/// Maximum number of peers, rates and bans tracked. As the source addresses
+/// can be spoofed, the least recently updated ones are evicted beyond it.
+const MAX_TRACKED_PEERS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Activity {
+    pub last_seen: u64,
+    pub height: Option<u64>,
+    /// Identity bound to the address, set by the first signed message
+    pub identity: Option<NodeId>,
+    /// Timestamp of the last message signed with `identity`
+    last_signed: u64,
+}
+
+/// Key of a ban: the identity of the peer if it signs its messages, as
+/// addresses can be spoofed, its address otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum BanKey {
+    Identity(NodeId),
+    Address(SocketAddr),
+}
+
+impl BanKey {
+    fn new(peer: SocketAddr, identity: Option<NodeId>) -> Self {
+        match identity {
+            Some(identity) => Self::Identity(identity),
+            None => Self::Address(peer),
+        }
+    }
+}
+
+/// Control messages received from a peer in the current second.
+#[derive(Default)]
+struct Rate {
+    second: u64,
+    count: u32,
+}
+
+/// Activity of the peers a message was received from.
+///
+/// The activity is recorded at the granularity of a second, so that the
+/// messages of a peer already seen in the current second only take a read
+/// lock.
+pub(crate) struct PeerActivity {
+    peers: RwLock<LruCache<SocketAddr, Activity>>,
+    rates: Mutex<LruCache<BanKey, Rate>>,
+    /// Timestamp each ban ends at
+    bans: RwLock<LruCache<BanKey, u64>>,
+}
+
+impl Default for PeerActivity {
+    fn default() -> Self {
+        let cap = NonZeroUsize::new(MAX_TRACKED_PEERS)
+            .expect("capacity to be non zero");
+        Self {
+            peers: RwLock::new(LruCache::new(cap)),
+            rates: Mutex::new(LruCache::new(cap)),
+            bans: RwLock::new(LruCache::new(cap)),
+        }
+    }
+}
+
+impl PeerActivity {
+    /// Records a message received from `peer`, carrying a block at `height`
+    /// if any, and signed with `identity` if any.
+    ///
+    /// Returns whether the message is accepted. Messages are discarded if
+    /// their sender is banned, if they are signed with another identity than
+    /// the one bound to the address, or if they are unsigned `control`
+    /// messages from an address whose peer signs them.
+    pub fn observe(
+        &self,
+        peer: SocketAddr,
+        height: Option<u64>,
+        identity: Option<NodeId>,
+        control: bool,
+    ) -> bool {
+        let now = get_current_timestamp();
+        self.observe_at(peer, height, identity, control, now)
+    }
+
+    fn observe_at(
+        &self,
+        peer: SocketAddr,
+        height: Option<u64>,
+        identity: Option<NodeId>,
+        control: bool,
+        now: u64,
+    ) -> bool {
+        if self.is_banned(BanKey::new(peer, identity), now) {
+            counter!("dusk_inbound_banned").increment(1);
+            return false;
+        }
+
+        let unchanged = self
+            .peers
+            .read()
+            .expect("lock not to be poisoned")
+            .peek(&peer)
+            .is_some_and(|a| a.unchanged_by(height, identity, control, now));
+        if !unchanged && !self.record(peer, height, identity, control, now) {
+            return false;
+        }
+
+        if control && self.exceeds_rate(BanKey::new(peer, identity), now) {
+            warn!(event = "peer banned", src = ?peer, ?identity);
+            counter!("dusk_peer_banned").increment(1);
+            return false;
+        }
+
+        true
+    }
+
+    /// Records the message in the activity of `peer`, returning whether it
+    /// is accepted.
+    fn record(
+        &self,
+        peer: SocketAddr,
+        height: Option<u64>,
+        identity: Option<NodeId>,
+        control: bool,
+        now: u64,
+    ) -> bool {
+        let mut peers = self.peers.write().expect("lock not to be poisoned");
+        let activity = peers.get_or_insert_mut(peer, || Activity {
+            last_seen: 0,
+            height: None,
+            identity: None,
+            last_signed: 0,
+        });
+
+        match (identity, activity.identity) {
+            (Some(identity), Some(known)) if identity != known => {
+                // Either the peer changed its identity or the address is
+                // spoofed, so only a silent identity is replaced
+                if now.saturating_sub(activity.last_signed)
+                    < IDENTITY_REBIND_AFTER
+                {
+                    counter!("dusk_peer_identity_mismatch").increment(1);
+                    warn!(
+                        event = "peer identity mismatch",
+                        src = ?peer,
+                        known = %known,
+                        received = %identity,
+                    );
+                    return false;
+                }
+                counter!("dusk_peer_identity_changed").increment(1);
+                warn!(
+                    event = "peer identity changed",
+                    src = ?peer,
+                    from = %known,
+                    to = %identity,
+                );
+                activity.identity = Some(identity);
+                activity.last_signed = now;
+            }
+            (Some(identity), _) => {
+                activity.identity = Some(identity);
+                activity.last_signed = now;
+            }
+            (None, Some(_)) if control => {
+                counter!("dusk_inbound_unsigned_control").increment(1);
+                return false;
+            }
+            (None, _) => {}
+        }
+
+        activity.last_seen = now;
+        if height > activity.height {
+            activity.height = height;
+        }
+
+        true
+    }
+
+    /// Counts a control message of `key`, banning it if it sends more than
+    /// [`MAX_CONTROL_RATE`] per second.
+    fn exceeds_rate(&self, key: BanKey, now: u64) -> bool {
+        let mut rates = self.rates.lock().expect("lock not to be poisoned");
+        let rate = rates.get_or_insert_mut(key, Rate::default);
+        if rate.second != now {
+            *rate = Rate {
+                second: now,
+                count: 0,
+            };
+        }
+        rate.count += 1;
+        if rate.count <= MAX_CONTROL_RATE {
+            return false;
+        }
+
+        rates.pop(&key);
+        self.bans
+            .write()
+            .expect("lock not to be poisoned")
+            .put(key, now + BAN_DURATION);
+        true
+    }
+
+    /// Returns the timestamp the ban of `key` ends at, if it is banned.
+    ///
+    /// Expired bans are left to be evicted.
+    fn ban_end(&self, key: BanKey, now: u64) -> Option<u64> {
+        let bans = self.bans.read().expect("lock not to be poisoned");
+        bans.peek(&key).copied().filter(|until| *until > now)
+    }
+
+    fn is_banned(&self, key: BanKey, now: u64) -> bool {
+        self.ban_end(key, now).is_some()
+    }
+
+    /// Returns the timestamp the ban of `peer` ends at, if either its
+    /// address or the identity bound to it is banned.
+    pub fn banned_until(&self, peer: SocketAddr) -> Option<u64> {
+        let now = get_current_timestamp();
+        let identity = self.get(&peer).and_then(|a| a.identity);
+        let by_identity =
+            identity.and_then(|id| self.ban_end(BanKey::Identity(id), now));
+        by_identity.max(self.ban_end(BanKey::Address(peer), now))
+    }
+
+    pub fn get(&self, peer: &SocketAddr) -> Option<Activity> {
+        self.peers
+            .read()
+            .expect("lock not to be poisoned")
+            .peek(peer)
+            .copied()
+    }
+}
+
+impl Activity {
+    /// Returns whether a message leaves the activity as it is, so that it
+    /// doesn't need to be recorded.
+    fn unchanged_by(
+        &self,
+        height: Option<u64>,
+        identity: Option<NodeId>,
+        control: bool,
+        now: u64,
+    ) -> bool {
+        let identity_unchanged = match identity {
+            Some(identity) => {
+                self.identity == Some(identity) && self.last_signed == now
+            }
+            // Unsigned control messages of a signing peer are discarded
+            None => !control || self.identity.is_none(),
+        };
+        identity_unchanged && self.last_seen == now && height <= self.height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_000_000;
+
+    fn peer(port: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], port))
+    }
+
+    #[test]
+    fn identity_bound_to_address() {
+        let activity = PeerActivity::default();
+        let (a, b) = (NodeId([1; 32]), NodeId([2; 32]));
+
+        assert!(activity.observe_at(peer(1), None, None, true, NOW));
+        assert!(activity.observe_at(peer(1), None, Some(a), true, NOW));
+
+        // The address can't be taken over by another identity
+        assert!(!activity.observe_at(peer(1), None, Some(b), true, NOW));
+        assert_eq!(activity.get(&peer(1)).and_then(|a| a.identity), Some(a));
+
+        // Once a peer signs, its unsigned control messages are spoofed
+        assert!(!activity.observe_at(peer(1), None, None, true, NOW));
+        assert!(activity.observe_at(peer(1), Some(1), None, false, NOW));
+        assert!(activity.observe_at(peer(2), None, None, true, NOW));
+
+        // A silent identity can be replaced
+        let later = NOW + IDENTITY_REBIND_AFTER;
+        assert!(activity.observe_at(peer(1), None, Some(b), true, later));
+        assert_eq!(activity.get(&peer(1)).and_then(|a| a.identity), Some(b));
+    }
+
+    #[test]
+    fn bans_follow_identity() {
+        let activity = PeerActivity::default();
+        let id = NodeId([1; 32]);
+
+        for _ in 0..MAX_CONTROL_RATE {
+            activity.observe_at(peer(1), None, Some(id), true, NOW);
+        }
+        assert!(!activity.observe_at(peer(1), None, Some(id), true, NOW));
+
+        // Changing address doesn't evade the ban, and the address isn't
+        // banned for other identities
+        assert!(!activity.observe_at(peer(2), None, Some(id), true, NOW));
+        assert!(activity.observe_at(peer(3), None, None, true, NOW));
+        assert!(activity.observe_at(peer(1), None, None, false, NOW));
+
+        let later = NOW + BAN_DURATION;
+        assert!(activity.observe_at(peer(2), None, Some(id), true, later));
+    }
+
+    #[test]
+    fn tracked_peers_are_bounded() {
+        let activity = PeerActivity::default();
+
+        for i in 0..=MAX_TRACKED_PEERS as u32 {
+            let addr = SocketAddr::from((i.to_be_bytes(), 9000));
+            assert!(activity.observe_at(addr, None, None, false, NOW));
+        }
+
+        let peers = activity.peers.read().unwrap();
+        assert_eq!(peers.len(), MAX_TRACKED_PEERS);
+        // The least recently seen peer is evicted
+        assert!(peers.peek(&SocketAddr::from(([0; 4], 9000))).is_none());
+    }
+
+    #[test]
+    fn repeated_messages_are_recorded_once_a_second() {
+        let activity = PeerActivity::default();
+        let id = NodeId([1; 32]);
+
+        assert!(activity.observe_at(peer(1), Some(5), Some(id), false, NOW));
+        let seen = activity.get(&peer(1)).unwrap();
+        assert!(seen.unchanged_by(Some(5), Some(id), true, NOW));
+        assert!(seen.unchanged_by(None, Some(id), false, NOW));
+
+        // A higher block, a new second or a spoofed message are recorded
+        assert!(!seen.unchanged_by(Some(6), Some(id), false, NOW));
+        assert!(!seen.unchanged_by(Some(5), Some(id), false, NOW + 1));
+        assert!(!seen.unchanged_by(None, None, true, NOW));
+        assert!(!activity.observe_at(peer(1), None, None, true, NOW));
+    }
+}
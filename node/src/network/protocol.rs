@@ -0,0 +1,246 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::RwLock;
+
+use metrics::counter;
+use node_data::message::{
+    Capabilities, Version, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION,
+};
+use tracing::{debug, info, warn};
+
+/// Protocol spoken by a peer, as advertised in its messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerProtocol {
+    pub version: Version,
+    pub capabilities: Capabilities,
+}
+
+impl PeerProtocol {
+    /// Returns the capabilities both this node and the peer support.
+    pub fn negotiated(&self) -> Capabilities {
+        Capabilities::LOCAL.intersection(self.capabilities)
+    }
+}
+
+/// Maximum number of peers whose protocol is tracked, the ones contacted
+/// first being evicted first
+const MAX_TRACKED_PEERS: usize = 4096;
+
+/// Protocols of the peers a message was received from, recorded on first
+/// contact and updated whenever a peer advertises a different one.
+#[derive(Default)]
+pub(crate) struct PeerProtocols {
+    peers: RwLock<Tracked>,
+}
+
+#[derive(Default)]
+struct Tracked {
+    protocols: HashMap<SocketAddr, PeerProtocol>,
+    /// Tracked peers, in order of first contact
+    order: VecDeque<SocketAddr>,
+    /// Number of tracked peers speaking a version older than the
+    /// capabilities
+    legacy: usize,
+    /// Number of tracked peers speaking the capabilities without
+    /// negotiating the compact votes
+    verbose: usize,
+}
+
+impl Tracked {
+    fn insert(&mut self, peer: SocketAddr, protocol: PeerProtocol) {
+        self.count(&protocol, true);
+        match self.protocols.insert(peer, protocol) {
+            Some(previous) => self.count(&previous, false),
+            None => self.order.push_back(peer),
+        }
+
+        while self.order.len() > MAX_TRACKED_PEERS {
+            let evicted =
+                self.order.pop_front().expect("order not to be empty");
+            if let Some(previous) = self.protocols.remove(&evicted) {
+                self.count(&previous, false);
+            }
+        }
+    }
+
+    /// Accounts for a protocol entering or leaving the tracked ones.
+    fn count(&mut self, protocol: &PeerProtocol, entering: bool) {
+        let counter = if is_legacy(protocol) {
+            &mut self.legacy
+        } else if is_verbose(protocol) {
+            &mut self.verbose
+        } else {
+            return;
+        };
+        if entering {
+            *counter += 1;
+        } else {
+            *counter -= 1;
+        }
+    }
+}
+
+/// Returns whether `protocol` is supported but older than the capabilities.
+fn is_legacy(protocol: &PeerProtocol) -> bool {
+    protocol.version.is_supported() && !protocol.version.has_capabilities()
+}
+
+/// Returns whether `protocol` speaks the capabilities without negotiating
+/// the compact votes.
+fn is_verbose(protocol: &PeerProtocol) -> bool {
+    protocol.version.has_capabilities()
+        && !protocol.negotiated().contains(Capabilities::COMPACT_VOTES)
+}
+
+impl PeerProtocols {
+    /// Records the protocol advertised by `peer` in its last message.
+    pub fn observe(&self, peer: SocketAddr, protocol: PeerProtocol) {
+        let known = self
+            .peers
+            .read()
+            .expect("lock not to be poisoned")
+            .protocols
+            .get(&peer)
+            .cloned();
+
+        match known {
+            Some(known) if known == protocol => return,
+            Some(known) => debug!(
+                event = "peer protocol changed",
+                src = ?peer,
+                from = %known.version,
+                to = %protocol.version,
+                capabilities = protocol.capabilities.bits(),
+            ),
+            None if protocol.version.is_supported() => info!(
+                event = "new peer",
+                src = ?peer,
+                version = %protocol.version,
+                capabilities = protocol.capabilities.bits(),
+                negotiated = protocol.negotiated().bits(),
+            ),
+            None => {
+                counter!("dusk_incompatible_peers").increment(1);
+                warn!(
+                    event = "incompatible peer",
+                    src = ?peer,
+                    version = %protocol.version,
+                );
+            }
+        }
+
+        self.peers
+            .write()
+            .expect("lock not to be poisoned")
+            .insert(peer, protocol);
+    }
+
+    pub fn get(&self, peer: &SocketAddr) -> Option<PeerProtocol> {
+        self.peers
+            .read()
+            .expect("lock not to be poisoned")
+            .protocols
+            .get(peer)
+            .cloned()
+    }
+
+    /// Returns the protocol every tracked peer understands, in which the
+    /// messages relayed as they are must be encoded.
+    pub fn common(&self) -> PeerProtocol {
+        let peers = self.peers.read().expect("lock not to be poisoned");
+        if peers.legacy > 0 {
+            return PeerProtocol {
+                version: MIN_PROTOCOL_VERSION,
+                capabilities: Capabilities::EMPTY,
+            };
+        }
+
+        let capabilities = match peers.verbose {
+            0 => Capabilities::LOCAL,
+            _ => Capabilities::LOCAL.difference(Capabilities::COMPACT_VOTES),
+        };
+        PeerProtocol {
+            version: PROTOCOL_VERSION,
+            capabilities,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer(i: usize) -> SocketAddr {
+        SocketAddr::from(([10, 0, (i >> 8) as u8, i as u8], 9000))
+    }
+
+    #[test]
+    fn bounded_and_counting_legacy_peers() {
+        let protocols = PeerProtocols::default();
+        let legacy = PeerProtocol {
+            version: MIN_PROTOCOL_VERSION,
+            capabilities: Capabilities::EMPTY,
+        };
+        let current = PeerProtocol {
+            version: PROTOCOL_VERSION,
+            capabilities: Capabilities::LOCAL,
+        };
+
+        protocols.observe(peer(0), legacy.clone());
+        assert_eq!(protocols.common().version, MIN_PROTOCOL_VERSION);
+
+        // Upgrading the only legacy peer
+        protocols.observe(peer(0), current.clone());
+        assert_eq!(protocols.common().version, PROTOCOL_VERSION);
+
+        protocols.observe(peer(0), legacy);
+        for i in 1..=MAX_TRACKED_PEERS {
+            protocols.observe(peer(i), current.clone());
+        }
+
+        // The first peer is evicted along with its legacy protocol
+        assert!(protocols.get(&peer(0)).is_none());
+        assert_eq!(protocols.common().version, PROTOCOL_VERSION);
+        assert_eq!(protocols.get(&peer(MAX_TRACKED_PEERS)), Some(current));
+    }
+
+    #[test]
+    fn common_protocol() {
+        let protocols = PeerProtocols::default();
+        let current = PeerProtocol {
+            version: PROTOCOL_VERSION,
+            capabilities: Capabilities::LOCAL,
+        };
+        let verbose = PeerProtocol {
+            version: PROTOCOL_VERSION,
+            capabilities: Capabilities::LOCAL
+                .difference(Capabilities::COMPACT_VOTES),
+        };
+
+        protocols.observe(peer(0), current.clone());
+        assert_eq!(protocols.common(), current);
+
+        protocols.observe(peer(1), verbose.clone());
+        assert_eq!(protocols.common(), verbose);
+
+        protocols.observe(
+            peer(2),
+            PeerProtocol {
+                version: MIN_PROTOCOL_VERSION,
+                capabilities: Capabilities::EMPTY,
+            },
+        );
+        assert_eq!(protocols.common().version, MIN_PROTOCOL_VERSION);
+
+        // Once the peers upgrade, the votes are compacted again
+        protocols.observe(peer(1), current.clone());
+        protocols.observe(peer(2), current.clone());
+        assert_eq!(protocols.common(), current);
+    }
+}
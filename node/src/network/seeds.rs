@@ -0,0 +1,119 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Resolution of the bootstrapping nodes, which can be given by host name.
+//!
+//! A host name without port is a DNS seed: all the addresses it resolves to
+//! are bootstrapping nodes listening on the default Kadcast port.
+
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+
+use tracing::warn;
+
+/// Port of the nodes resolved from a DNS seed
+pub const DEFAULT_KADCAST_PORT: u16 = 9000;
+
+/// Returns the host and port to resolve for a bootstrapping entry, or `None`
+/// if it is already an address.
+fn to_host_port(entry: &str) -> Option<(&str, u16)> {
+    if entry.parse::<SocketAddr>().is_ok() {
+        return None;
+    }
+    match entry.rsplit_once(':') {
+        Some((host, port)) => match port.parse() {
+            Ok(port) => Some((host, port)),
+            Err(_) => Some((entry, DEFAULT_KADCAST_PORT)),
+        },
+        None => Some((entry, DEFAULT_KADCAST_PORT)),
+    }
+}
+
+/// Returns whether any of the entries has to be resolved.
+pub fn has_host_names(entries: &[String]) -> bool {
+    entries.iter().any(|entry| to_host_port(entry).is_some())
+}
+
+/// Resolves the bootstrapping entries into addresses.
+///
+/// Entries that cannot be resolved are skipped, as the other ones may still
+/// allow to join the network.
+pub async fn resolve_bootstrapping_nodes(entries: &[String]) -> Vec<String> {
+    resolve_with(entries, |host, port| async move {
+        let addrs = tokio::net::lookup_host((host, port)).await?;
+        Ok(addrs.collect())
+    })
+    .await
+}
+
+/// Resolves the bootstrapping entries with `lookup`.
+async fn resolve_with<F, R>(entries: &[String], lookup: F) -> Vec<String>
+where
+    F: Fn(String, u16) -> R,
+    R: Future<Output = io::Result<Vec<SocketAddr>>>,
+{
+    let mut nodes = vec![];
+    for entry in entries {
+        let Some((host, port)) = to_host_port(entry) else {
+            nodes.push(entry.clone());
+            continue;
+        };
+        match lookup(host.to_string(), port).await {
+            Ok(addrs) => {
+                nodes.extend(addrs.iter().map(|addr| addr.to_string()))
+            }
+            Err(err) => {
+                warn!(event = "cannot resolve bootstrapping node", %entry, %err)
+            }
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    nodes.retain(|node| seen.insert(node.clone()));
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn bootstrapping_entries() {
+        assert_eq!(to_host_port("10.0.0.1:9000"), None);
+        assert_eq!(to_host_port("[::1]:9000"), None);
+        assert_eq!(
+            to_host_port("node.dusk.network:9001"),
+            Some(("node.dusk.network", 9001))
+        );
+        assert_eq!(
+            to_host_port("dnsseed.dusk.network"),
+            Some(("dnsseed.dusk.network", DEFAULT_KADCAST_PORT))
+        );
+
+        let entries = vec![
+            "10.0.0.1:9000".to_string(),
+            "seed.test:9001".to_string(),
+            "unknown.test".to_string(),
+            "10.0.0.1:9000".to_string(),
+        ];
+        assert!(has_host_names(&entries));
+
+        let nodes = resolve_with(&entries, |host, port| async move {
+            match host.as_str() {
+                "seed.test" => Ok(vec![
+                    SocketAddr::from(([10, 0, 0, 2], port)),
+                    SocketAddr::from(([10, 0, 0, 1], 9000)),
+                ]),
+                _ => Err(io::ErrorKind::NotFound.into()),
+            }
+        })
+        .await;
+        assert_eq!(nodes, vec!["10.0.0.1:9000", "10.0.0.2:9001"]);
+
+        assert!(!has_host_names(&entries[..1]));
+    }
+}
@@ -0,0 +1,140 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Kadcast statistics observed locally, along with a synthetic loss of the
+//! inbound messages, to simulate degraded networks on devnets.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Serialize;
+use tracing::info;
+
+/// Parts per million the loss ratio is stored in
+const PPM: u64 = 1_000_000;
+
+/// Statistics of the Kadcast network, as observed by this node.
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkSimStats {
+    /// Number of peers of the routing table per bucket
+    pub buckets: BTreeMap<u8, usize>,
+    /// Number of messages broadcast by this node
+    pub broadcasts: u64,
+    /// Average number of peers a broadcast was sent to
+    pub avg_fanout: f64,
+    /// Number of messages delivered by Kadcast, once reassembled from their
+    /// RaptorQ chunks
+    pub received: u64,
+    /// Ratio of the delivered messages decoded successfully
+    pub decode_rate: f64,
+    /// Number of inbound messages dropped by the synthetic loss
+    pub dropped: u64,
+    /// Ratio of the inbound messages currently dropped
+    pub loss: f64,
+}
+
+#[derive(Default)]
+pub(crate) struct NetworkSim {
+    broadcasts: AtomicU64,
+    fanout: AtomicU64,
+    received: AtomicU64,
+    decoded: AtomicU64,
+    inbound: AtomicU64,
+    dropped: AtomicU64,
+    loss_ppm: AtomicU64,
+}
+
+impl NetworkSim {
+    /// Records a message broadcast to `fanout` peers.
+    pub fn observe_broadcast(&self, fanout: usize) {
+        self.broadcasts.fetch_add(1, Ordering::Relaxed);
+        self.fanout.fetch_add(fanout as u64, Ordering::Relaxed);
+    }
+
+    /// Records a message delivered by Kadcast, whether it could be decoded
+    /// or not.
+    pub fn observe_received(&self, decoded: bool) {
+        self.received.fetch_add(1, Ordering::Relaxed);
+        if decoded {
+            self.decoded.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns whether the next inbound message is to be dropped.
+    ///
+    /// The drops are spread evenly over the inbound messages rather than
+    /// randomly, so that a chaos test is reproducible.
+    pub fn drop_inbound(&self) -> bool {
+        let loss = self.loss_ppm.load(Ordering::Relaxed);
+        if loss == 0 {
+            return false;
+        }
+
+        let n = self.inbound.fetch_add(1, Ordering::Relaxed);
+        let drop = (n + 1) * loss / PPM > n * loss / PPM;
+        if drop {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        drop
+    }
+
+    /// Sets the ratio of the inbound messages to drop, between 0 and 1.
+    pub fn set_loss(&self, loss: f64) -> anyhow::Result<()> {
+        if !(0.0..=1.0).contains(&loss) {
+            anyhow::bail!("Loss must be between 0 and 1, got {loss}");
+        }
+        info!(event = "synthetic loss set", loss);
+        let loss_ppm = (loss * PPM as f64).round() as u64;
+        self.loss_ppm.store(loss_ppm, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub fn stats(&self, buckets: BTreeMap<u8, usize>) -> NetworkSimStats {
+        let broadcasts = self.broadcasts.load(Ordering::Relaxed);
+        let fanout = self.fanout.load(Ordering::Relaxed);
+        let received = self.received.load(Ordering::Relaxed);
+        let decoded = self.decoded.load(Ordering::Relaxed);
+
+        NetworkSimStats {
+            buckets,
+            broadcasts,
+            avg_fanout: ratio(fanout, broadcasts),
+            received,
+            decode_rate: ratio(decoded, received),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            loss: self.loss_ppm.load(Ordering::Relaxed) as f64 / PPM as f64,
+        }
+    }
+}
+
+fn ratio(num: u64, den: u64) -> f64 {
+    if den == 0 {
+        return 0.0;
+    }
+    num as f64 / den as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loss_is_spread_evenly() {
+        let sim = NetworkSim::default();
+        assert!(!sim.drop_inbound());
+
+        sim.set_loss(0.25).expect("loss to be valid");
+        let dropped = (0..100).filter(|_| sim.drop_inbound()).count();
+        assert_eq!(dropped, 25);
+        assert_eq!(sim.stats(BTreeMap::new()).dropped, 25);
+
+        sim.set_loss(1.0).expect("loss to be valid");
+        assert!((0..10).all(|_| sim.drop_inbound()));
+
+        assert!(sim.set_loss(1.5).is_err());
+        assert!(sim.set_loss(-0.1).is_err());
+    }
+}
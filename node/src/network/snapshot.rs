@@ -0,0 +1,286 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Signed snapshots of the healthy peers of a node.
+//!
+//! A node with an identity exports the peers of its routing table, signed
+//! with its identity key. A new node imports the snapshot of a signer it
+//! trusts at startup, contacting the peers along with the bootstrapping
+//! nodes, so that its buckets fill without walking the network from the
+//! bootstrapping nodes alone.
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use node_data::get_current_timestamp;
+use node_data::identity::{self, NodeId, NodeIdentity};
+use serde::{Deserialize, Serialize};
+
+use super::PeerInfo;
+
+/// Default age beyond which a snapshot is not imported
+pub const DEFAULT_MAX_SNAPSHOT_AGE: Duration = Duration::from_secs(24 * 3600);
+
+/// Maximum number of peers imported from a snapshot
+const MAX_IMPORTED_PEERS: usize = 256;
+
+/// A peer of the routing table of the node exporting a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotPeer {
+    pub address: SocketAddr,
+    /// Bucket of the peer in the routing table of the exporting node
+    pub distance: u8,
+    /// Timestamp of the last message received from the peer
+    pub last_seen: u64,
+}
+
+/// The healthy peers of a node at a point in time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerSnapshot {
+    /// Timestamp the snapshot was taken at
+    pub created_at: u64,
+    /// Base58 identity of the node exporting the snapshot
+    pub signer: String,
+    pub peers: Vec<SnapshotPeer>,
+}
+
+/// A snapshot along with the signature of its JSON encoding.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedPeerSnapshot {
+    pub snapshot: PeerSnapshot,
+    /// Hex encoded signature of the snapshot by its signer
+    pub signature: String,
+}
+
+impl PeerSnapshot {
+    /// Takes a snapshot of the peers alive and running a supported protocol.
+    pub fn new(peers: &[PeerInfo], signer: NodeId) -> Self {
+        let peers = peers
+            .iter()
+            .filter(|p| p.alive && p.compatible != Some(false))
+            .filter_map(|p| {
+                Some(SnapshotPeer {
+                    address: p.address,
+                    distance: p.bucket,
+                    last_seen: p.last_seen?,
+                })
+            })
+            .collect();
+
+        Self {
+            created_at: get_current_timestamp(),
+            signer: signer.to_string(),
+            peers,
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("snapshot to serialize")
+    }
+
+    /// Signs the snapshot with `identity`, which must be its signer.
+    pub fn sign(self, identity: &NodeIdentity) -> SignedPeerSnapshot {
+        let mut bytes = self.to_bytes();
+        // The signature follows the message, the creation timestamp and the
+        // identity of the signer
+        let signature_pos = bytes.len() + 8 + identity.id().0.len();
+        identity.sign_at(&mut bytes, self.created_at);
+        let signature = hex::encode(&bytes[signature_pos..]);
+
+        SignedPeerSnapshot {
+            snapshot: self,
+            signature,
+        }
+    }
+}
+
+impl SignedPeerSnapshot {
+    /// Verifies that the snapshot was signed by one of the `trusted`
+    /// identities, and is not older than `max_age`.
+    pub fn verify(
+        &self,
+        trusted: &[NodeId],
+        max_age: Duration,
+    ) -> anyhow::Result<&PeerSnapshot> {
+        let snapshot = &self.snapshot;
+        let signer: NodeId = snapshot
+            .signer
+            .parse()
+            .map_err(|e: String| anyhow::anyhow!(e))?;
+        if !trusted.contains(&signer) {
+            anyhow::bail!("Untrusted snapshot signer {signer}");
+        }
+
+        let mut bytes = snapshot.to_bytes();
+        bytes.extend_from_slice(&snapshot.created_at.to_le_bytes());
+        bytes.extend_from_slice(&signer.0);
+        bytes.extend_from_slice(&hex::decode(&self.signature)?);
+        let verified = identity::verify(&bytes).map(|(_, id, _)| id);
+        if verified != Some(signer) {
+            anyhow::bail!("Invalid snapshot signature");
+        }
+
+        let age = get_current_timestamp().saturating_sub(snapshot.created_at);
+        if age > max_age.as_secs() {
+            anyhow::bail!("Snapshot taken {age}s ago");
+        }
+
+        Ok(snapshot)
+    }
+}
+
+/// Import of a peer snapshot at startup.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PeerSnapshotConfig {
+    /// File holding the signed snapshot, as exported
+    pub path: Option<PathBuf>,
+
+    /// Base58 identities of the nodes whose snapshots are imported
+    #[serde(default)]
+    pub trusted_signers: Vec<String>,
+
+    /// Age beyond which a snapshot is not imported
+    #[serde(with = "humantime_serde", default)]
+    pub max_age: Option<Duration>,
+}
+
+impl PeerSnapshotConfig {
+    /// Reads and verifies the snapshot, returning the addresses of the peers
+    /// to import, or `None` if no snapshot is configured.
+    pub fn load(&self) -> anyhow::Result<Option<Vec<SocketAddr>>> {
+        let Some(path) = &self.path else {
+            return Ok(None);
+        };
+
+        let trusted = self
+            .trusted_signers
+            .iter()
+            .map(|s| s.parse().map_err(|e: String| anyhow::anyhow!(e)))
+            .collect::<anyhow::Result<Vec<NodeId>>>()?;
+
+        let signed: SignedPeerSnapshot =
+            serde_json::from_slice(&std::fs::read(path)?)?;
+        let snapshot = signed.verify(
+            &trusted,
+            self.max_age.unwrap_or(DEFAULT_MAX_SNAPSHOT_AGE),
+        )?;
+
+        Ok(Some(select_peers(
+            snapshot.peers.clone(),
+            MAX_IMPORTED_PEERS,
+        )))
+    }
+}
+
+/// Selects at most `max` of the peers, spread over the buckets of the
+/// exporting node and the most recently seen first in each, so that the
+/// routing table is filled at every distance rather than at the most
+/// populated one.
+fn select_peers(peers: Vec<SnapshotPeer>, max: usize) -> Vec<SocketAddr> {
+    let mut buckets = BTreeMap::<_, Vec<_>>::new();
+    for peer in peers {
+        buckets.entry(peer.distance).or_default().push(peer);
+    }
+    let mut buckets: Vec<_> = buckets
+        .into_values()
+        .map(|mut peers| {
+            peers.sort_by_key(|p| p.last_seen);
+            peers
+        })
+        .collect();
+
+    let mut selected = vec![];
+    while selected.len() < max && !buckets.is_empty() {
+        buckets.retain_mut(|peers| match peers.pop() {
+            Some(peer) if selected.len() < max => {
+                selected.push(peer.address);
+                true
+            }
+            _ => false,
+        });
+    }
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer(address: &str, alive: bool, last_seen: Option<u64>) -> PeerInfo {
+        PeerInfo {
+            address: address.parse().expect("valid address"),
+            bucket: 3,
+            alive,
+            last_seen,
+            height: None,
+            version: None,
+            compatible: Some(true),
+            identity: None,
+            banned_until: None,
+        }
+    }
+
+    #[test]
+    fn signed_snapshot() {
+        let identity = NodeIdentity::from_bytes(&[3; 32]);
+        let other = NodeIdentity::from_bytes(&[4; 32]);
+        let now = get_current_timestamp();
+
+        let peers = [
+            peer("10.0.0.1:9000", true, Some(now)),
+            peer("10.0.0.2:9000", false, Some(now)),
+            peer("10.0.0.3:9000", true, None),
+        ];
+        let snapshot = PeerSnapshot::new(&peers, identity.id());
+        assert_eq!(snapshot.peers.len(), 1);
+        assert_eq!(snapshot.peers[0].distance, 3);
+
+        let signed = snapshot.clone().sign(&identity);
+        let max_age = DEFAULT_MAX_SNAPSHOT_AGE;
+        let verified = signed.verify(&[identity.id()], max_age);
+        assert_eq!(verified.expect("snapshot to verify"), &snapshot);
+
+        assert!(signed.verify(&[other.id()], max_age).is_err());
+
+        let mut tampered = signed.clone();
+        tampered.snapshot.peers[0].address =
+            "10.0.0.9:9000".parse().expect("valid address");
+        assert!(tampered.verify(&[identity.id()], max_age).is_err());
+
+        let mut stale = snapshot;
+        stale.created_at = now - max_age.as_secs() - 1;
+        let stale = stale.sign(&identity);
+        assert!(stale.verify(&[identity.id()], max_age).is_err());
+    }
+
+    #[test]
+    fn peers_spread_over_buckets() {
+        let peer = |port: u16, distance, last_seen| SnapshotPeer {
+            address: SocketAddr::from(([10, 0, 0, 1], port)),
+            distance,
+            last_seen,
+        };
+        let peers = vec![
+            peer(1, 5, 10),
+            peer(2, 5, 30),
+            peer(3, 5, 20),
+            peer(4, 2, 5),
+            peer(5, 7, 1),
+        ];
+        let ports = |max| -> Vec<_> {
+            select_peers(peers.clone(), max)
+                .iter()
+                .map(|addr| addr.port())
+                .collect()
+        };
+
+        assert_eq!(ports(3), vec![4, 2, 5]);
+        assert_eq!(ports(5), vec![4, 2, 5, 3, 1]);
+        assert_eq!(ports(10), vec![4, 2, 5, 3, 1]);
+    }
+}
@@ -0,0 +1,97 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use metrics::{counter, gauge};
+use tracing::info;
+
+/// A behavior of the node that can be turned on and off while it is running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Switch {
+    /// Broadcast of the transactions accepted in the mempool
+    TxGossip,
+    /// Generation of candidate blocks when this provisioner is selected
+    BlockProduction,
+}
+
+impl Switch {
+    pub const ALL: [Switch; 2] = [Switch::TxGossip, Switch::BlockProduction];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Switch::TxGossip => "tx_gossip",
+            Switch::BlockProduction => "block_production",
+        }
+    }
+}
+
+impl fmt::Display for Switch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Switch {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|switch| switch.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("Unknown switch {s}"))
+    }
+}
+
+/// The runtime switches of the node, all on by default.
+#[derive(Debug)]
+pub struct RuntimeSwitches {
+    tx_gossip: AtomicBool,
+    block_production: AtomicBool,
+}
+
+impl Default for RuntimeSwitches {
+    fn default() -> Self {
+        Self {
+            tx_gossip: AtomicBool::new(true),
+            block_production: AtomicBool::new(true),
+        }
+    }
+}
+
+impl RuntimeSwitches {
+    fn flag(&self, switch: Switch) -> &AtomicBool {
+        match switch {
+            Switch::TxGossip => &self.tx_gossip,
+            Switch::BlockProduction => &self.block_production,
+        }
+    }
+
+    pub fn is_enabled(&self, switch: Switch) -> bool {
+        self.flag(switch).load(Ordering::Relaxed)
+    }
+
+    /// Turns `switch` on or off, returning whether it was on.
+    pub fn set(&self, switch: Switch, enabled: bool) -> bool {
+        let was_enabled = self.flag(switch).swap(enabled, Ordering::Relaxed);
+        if was_enabled != enabled {
+            info!(event = "runtime switch toggled", %switch, enabled);
+            counter!(format!("dusk_switch_{switch}_toggles")).increment(1);
+        }
+        gauge!(format!("dusk_switch_{switch}")).set(f64::from(enabled as u8));
+        was_enabled
+    }
+
+    pub fn tx_gossip(&self) -> bool {
+        self.is_enabled(Switch::TxGossip)
+    }
+
+    pub fn block_production(&self) -> bool {
+        self.is_enabled(Switch::BlockProduction)
+    }
+}
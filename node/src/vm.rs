@@ -9,10 +9,17 @@ use dusk_consensus::operations::{CallParams, VerificationOutput, Voter};
 use dusk_consensus::user::provisioners::Provisioners;
 use dusk_consensus::user::stake::Stake;
 use dusk_core::signatures::bls::PublicKey as BlsPublicKey;
+use dusk_core::stake::StakeConfig;
 use dusk_core::transfer::moonlight::AccountData;
 use node_data::events::contract::ContractEvent;
 use node_data::ledger::{Block, SpentTransaction, Transaction};
 
+use std::sync::Arc;
+
+use crate::chain::{
+    BlockSpaceReservation, ChainRules, GasLimitRule, UpgradeSchedule,
+};
+
 #[derive(Default)]
 pub struct Config {}
 
@@ -71,6 +78,9 @@ pub trait VMExecution: Send + Sync + 'static {
         pk: &BlsPublicKey,
     ) -> anyhow::Result<Option<Stake>>;
 
+    /// Returns the parameters of the stake contract at the current state
+    fn get_stake_config(&self) -> anyhow::Result<StakeConfig>;
+
     fn get_state_root(&self) -> anyhow::Result<[u8; 32]>;
 
     fn move_to_commit(&self, commit: [u8; 32]) -> anyhow::Result<()>;
@@ -78,8 +88,27 @@ pub trait VMExecution: Send + Sync + 'static {
     /// Returns last finalized state root
     fn get_finalized_state_root(&self) -> anyhow::Result<[u8; 32]>;
 
-    /// Returns block gas limit
-    fn get_block_gas_limit(&self) -> u64;
+    /// Returns the rule setting the block gas limit
+    fn gas_limit_rule(&self) -> GasLimitRule;
+
+    /// Returns the protocol upgrades declared by the genesis
+    fn upgrades(&self) -> Arc<UpgradeSchedule>;
+
+    /// Returns the rules the block headers are validated against
+    fn chain_rules(&self) -> anyhow::Result<ChainRules> {
+        Ok(ChainRules {
+            gas_limit: self.gas_limit_rule(),
+            upgrades: self.upgrades(),
+            epoch: self.get_stake_config()?.epoch,
+        })
+    }
+
+    /// Returns the share of the block gas reserved to protocol transactions
+    /// at `block_height`
+    fn block_space_reservation(
+        &self,
+        block_height: u64,
+    ) -> BlockSpaceReservation;
 
     fn revert(&self, state_hash: [u8; 32]) -> anyhow::Result<[u8; 32]>;
     fn revert_to_finalized(&self) -> anyhow::Result<[u8; 32]>;
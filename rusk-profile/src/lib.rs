@@ -150,6 +150,13 @@ pub fn to_rusk_state_id_path<P: AsRef<Path>>(dir: P) -> PathBuf {
     dir.join("state.id")
 }
 
+/// Returns the path of the protocol upgrades declared by the genesis of the
+/// state in `dir`.
+pub fn to_rusk_upgrades_path<P: AsRef<Path>>(dir: P) -> PathBuf {
+    let dir = dir.as_ref();
+    dir.join("upgrades.toml")
+}
+
 pub fn get_common_reference_string() -> io::Result<Vec<u8>> {
     let crs = get_rusk_profile_dir()?.join(CRS_FNAME);
     read(crs)
@@ -0,0 +1,58 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! An LRU cache of the computed proofs, keyed by the digest of the circuit
+//! inputs.
+//!
+//! Identical re-submissions of a circuit (e.g. a wallet retrying a
+//! transaction) are answered from the cache instead of running the prover
+//! again.
+
+use std::num::NonZeroUsize;
+use std::sync::{Mutex, OnceLock};
+use std::vec::Vec;
+
+use lru::LruCache;
+
+type Digest = [u8; blake2b_simd::OUTBYTES];
+
+/// Number of proofs kept unless set with [`set_cache_size`].
+pub const DEFAULT_CACHE_SIZE: NonZeroUsize = match NonZeroUsize::new(256) {
+    Some(size) => size,
+    None => unreachable!(),
+};
+
+static CACHE: OnceLock<Mutex<LruCache<Digest, Vec<u8>>>> = OnceLock::new();
+
+fn cache() -> &'static Mutex<LruCache<Digest, Vec<u8>>> {
+    CACHE.get_or_init(|| Mutex::new(LruCache::new(DEFAULT_CACHE_SIZE)))
+}
+
+/// Sets the number of proofs kept, evicting the least recently used ones
+/// beyond it.
+pub fn set_cache_size(size: NonZeroUsize) {
+    if let Ok(mut cache) = cache().lock() {
+        cache.resize(size);
+    }
+}
+
+/// Computes the cache key of the given serialized circuit.
+pub(crate) fn digest(tx_circuit_vec_bytes: &[u8]) -> Digest {
+    *blake2b_simd::blake2b(tx_circuit_vec_bytes).as_array()
+}
+
+/// Returns the proof cached for the given circuit digest, if any.
+pub(crate) fn get(digest: &Digest) -> Option<Vec<u8>> {
+    // A poisoned cache is just a cache miss
+    cache().lock().ok()?.get(digest).cloned()
+}
+
+/// Caches the proof computed for the given circuit digest.
+pub(crate) fn put(digest: Digest, proof: Vec<u8>) {
+    if let Ok(mut cache) = cache().lock() {
+        cache.put(digest, proof);
+    }
+}
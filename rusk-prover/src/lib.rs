@@ -12,6 +12,12 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(feature = "std")]
+mod cache;
+
+#[cfg(feature = "std")]
+pub use cache::{set_cache_size, DEFAULT_CACHE_SIZE};
+
 use alloc::format;
 use alloc::vec::Vec;
 
@@ -40,6 +46,13 @@ pub struct LocalProver;
 
 impl Prove for LocalProver {
     fn prove(&self, tx_circuit_vec_bytes: &[u8]) -> Result<Vec<u8>, Error> {
+        #[cfg(feature = "std")]
+        let digest = cache::digest(tx_circuit_vec_bytes);
+        #[cfg(feature = "std")]
+        if let Some(proof) = cache::get(&digest) {
+            return Ok(proof);
+        }
+
         let tx_circuit_vec = TxCircuitVec::from_slice(tx_circuit_vec_bytes)?;
 
         #[cfg(not(feature = "no_random"))]
@@ -72,7 +85,12 @@ impl Prove for LocalProver {
             _ => return Err(Error::InvalidData),
         };
 
-        Ok(proof.to_bytes().to_vec())
+        let proof = proof.to_bytes().to_vec();
+
+        #[cfg(feature = "std")]
+        cache::put(digest, proof.clone());
+
+        Ok(proof)
     }
 }
 
@@ -119,4 +137,16 @@ mod tests {
             hex::decode(include_str!("../tests/tx_circuit_vec.hex")).unwrap();
         let _proof = LocalProver.prove(&tx_circuit_vec_bytes).unwrap();
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_cached_proof() {
+        let tx_circuit_vec_bytes =
+            hex::decode(include_str!("../tests/tx_circuit_vec.hex")).unwrap();
+        let proof = LocalProver.prove(&tx_circuit_vec_bytes).unwrap();
+
+        let digest = cache::digest(&tx_circuit_vec_bytes);
+        assert_eq!(cache::get(&digest), Some(proof.clone()));
+        assert_eq!(LocalProver.prove(&tx_circuit_vec_bytes).unwrap(), proof);
+    }
 }
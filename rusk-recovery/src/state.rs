@@ -5,13 +5,15 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 use std::error::Error;
-use std::fs;
 use std::path::Path;
+use std::{fs, io};
 
-use dusk_bytes::DeserializableSlice;
+use dusk_bytes::{DeserializableSlice, Serializable};
 use dusk_core::abi::ContractId;
 use dusk_core::signatures::bls::PublicKey as AccountPublicKey;
-use dusk_core::stake::{StakeAmount, StakeData, StakeKeys, STAKE_CONTRACT};
+use dusk_core::stake::{
+    StakeAmount, StakeConfig, StakeData, StakeKeys, STAKE_CONTRACT,
+};
 use dusk_core::transfer::phoenix::{Note, PublicKey, Sender};
 use dusk_core::transfer::TRANSFER_CONTRACT;
 use dusk_core::JubJubScalar;
@@ -20,17 +22,22 @@ use ff::Field;
 use once_cell::sync::Lazy;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
+use sha2::{Digest, Sha256};
 
-use tracing::info;
+use tracing::{info, warn};
 use url::Url;
 
 use crate::Theme;
+use manifest::{Channel, Manifest, MANIFEST_EXTENSION};
 
 mod http;
+pub mod manifest;
 mod zip;
 
 mod snapshot;
-pub use snapshot::{GenesisStake, PhoenixBalance, Snapshot};
+pub use snapshot::{
+    GenesisStake, GenesisStakeConfig, GenesisUpgrade, PhoenixBalance, Snapshot,
+};
 
 pub mod tar;
 
@@ -40,6 +47,12 @@ pub const DEFAULT_SNAPSHOT: &str =
 const GENESIS_BLOCK_HEIGHT: u64 = 0;
 const GENESIS_CHAIN_ID: u8 = 0xFA;
 
+/// The root (commit id) of a network state.
+pub type Root = [u8; 32];
+
+/// Domain of the seeds derived for the phoenix balances without one.
+const BALANCE_SEED_DOMAIN: &[u8] = b"rusk-recovery/phoenix-balance";
+
 pub static FAUCET_PHOENIX_KEY: Lazy<PublicKey> = Lazy::new(|| {
     let addr = include_str!("../assets/faucet.address");
     let bytes = bs58::decode(addr).into_vec().expect("valid bs58");
@@ -62,6 +75,7 @@ pub static DUSK_CONSENSUS_KEY: Lazy<AccountPublicKey> = Lazy::new(|| {
 fn generate_transfer_state(
     session: &mut Session,
     snapshot: &Snapshot,
+    deterministic: bool,
 ) -> Result<(), Box<dyn Error>> {
     let theme = Theme::default();
 
@@ -76,6 +90,9 @@ fn generate_transfer_state(
 
             let mut rng = match balance.seed {
                 Some(seed) => StdRng::seed_from_u64(seed),
+                None if deterministic => {
+                    StdRng::from_seed(derive_seed(balance.address(), idx))
+                }
                 None => StdRng::from_entropy(),
             };
 
@@ -126,6 +143,20 @@ fn generate_stake_state(
     snapshot: &Snapshot,
 ) -> Result<(), Box<dyn Error>> {
     let theme = Theme::default();
+
+    if let Some(genesis_config) = snapshot.stake_config() {
+        info!("{} stake config", theme.action("Generating"));
+
+        let mut config = session
+            .call::<_, StakeConfig>(STAKE_CONTRACT, "get_config", &(), u64::MAX)
+            .expect("stake config to be queried")
+            .data;
+        genesis_config.apply(&mut config);
+        session
+            .call::<_, ()>(STAKE_CONTRACT, "set_config", &config, u64::MAX)
+            .expect("stake config to be set");
+    }
+
     snapshot.stakes().enumerate().for_each(|(idx, staker)| {
         info!("{} provisioner #{}", theme.action("Generating"), idx);
 
@@ -167,10 +198,24 @@ fn generate_stake_state(
     Ok(())
 }
 
+/// Derives the seed of the phoenix balance at `idx` in the snapshot from its
+/// address and index.
+///
+/// The seed is hashed within its own domain, so that it does not collide
+/// with the one of another balance, nor with the explicit seeds which are
+/// expanded with `seed_from_u64`.
+fn derive_seed(address: &PublicKey, idx: usize) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(BALANCE_SEED_DOMAIN);
+    hasher.update(address.to_bytes());
+    hasher.update((idx as u64).to_le_bytes());
+    hasher.finalize().into()
+}
+
 fn generate_empty_state<P: AsRef<Path>>(
     state_dir: P,
     snapshot: &Snapshot,
-) -> Result<(VM, [u8; 32]), Box<dyn Error>> {
+) -> Result<(VM, Root), Box<dyn Error>> {
     let theme = Theme::default();
     info!("{} new network state", theme.action("Generating"));
 
@@ -237,6 +282,21 @@ fn generate_empty_state<P: AsRef<Path>>(
     Ok((vm, commit_id))
 }
 
+/// Builds the genesis state described by `snapshot` into `state_dir`,
+/// returning its root.
+///
+/// Unlike [`deploy`], the phoenix balances without an explicit seed get one
+/// derived from their address and index in the snapshot, so that building the
+/// same snapshot twice yields the same root, provided its base state, if any,
+/// is the same.
+pub fn build_genesis<P: AsRef<Path>>(
+    snapshot: &Snapshot,
+    state_dir: P,
+) -> Result<Root, Box<dyn Error>> {
+    let (_, root) = build(state_dir, snapshot, true, |_| {})?;
+    Ok(root)
+}
+
 /// Deploys a snapshot.
 /// note: deploy consumes session as it produces commit id so it gives
 /// the caller a possibility of providing a closure to perform additional
@@ -246,7 +306,19 @@ pub fn deploy<P: AsRef<Path>, F>(
     state_dir: P,
     snapshot: &Snapshot,
     closure: F,
-) -> Result<(VM, [u8; 32]), Box<dyn Error>>
+) -> Result<(VM, Root), Box<dyn Error>>
+where
+    F: FnOnce(&mut Session),
+{
+    build(state_dir, snapshot, false, closure)
+}
+
+fn build<P: AsRef<Path>, F>(
+    state_dir: P,
+    snapshot: &Snapshot,
+    deterministic: bool,
+    closure: F,
+) -> Result<(VM, Root), Box<dyn Error>>
 where
     F: FnOnce(&mut Session),
 {
@@ -256,14 +328,14 @@ where
     let state_id_path = rusk_profile::to_rusk_state_id_path(state_dir);
 
     let (vm, old_commit_id) = match snapshot.base_state() {
-        Some(state) => load_state(state_dir, state),
+        Some(state) => load_state(state_dir, state, snapshot.channel()),
         None => generate_empty_state(state_dir, snapshot),
     }?;
 
     let mut session =
         vm.session(old_commit_id, GENESIS_CHAIN_ID, GENESIS_BLOCK_HEIGHT)?;
 
-    generate_transfer_state(&mut session, snapshot)?;
+    generate_transfer_state(&mut session, snapshot, deterministic)?;
     generate_stake_state(&mut session, snapshot)?;
 
     closure(&mut session);
@@ -272,6 +344,14 @@ where
     let commit_id = session.commit()?;
     fs::write(state_id_path, commit_id)?;
 
+    // The upgrades of a base state are kept, unless the snapshot declares
+    // its own
+    if !snapshot.upgrades().is_empty() {
+        info!("{} protocol upgrades", theme.success("Storing"));
+        let upgrades_path = rusk_profile::to_rusk_upgrades_path(state_dir);
+        fs::write(upgrades_path, snapshot.upgrades_toml()?)?;
+    }
+
     if old_commit_id != commit_id {
         info!(
             "{} {}",
@@ -289,7 +369,7 @@ where
 /// Restore a state from the given directory.
 pub fn restore_state<P: AsRef<Path>>(
     state_dir: P,
-) -> Result<(VM, [u8; 32]), Box<dyn Error>> {
+) -> Result<(VM, Root), Box<dyn Error>> {
     let state_dir = state_dir.as_ref();
     let state_id_path = rusk_profile::to_rusk_state_id_path(state_dir);
 
@@ -305,7 +385,7 @@ pub fn restore_state<P: AsRef<Path>>(
         )
         .into());
     }
-    let mut commit_id = [0u8; 32];
+    let mut commit_id = Root::default();
     commit_id.copy_from_slice(&commit_id_bytes);
 
     let vm = VM::new(state_dir)?;
@@ -313,10 +393,15 @@ pub fn restore_state<P: AsRef<Path>>(
 }
 
 /// Load a state file and save it into the rusk state directory.
+///
+/// If a `channel` is given and a manifest is published next to the state
+/// (at `<url>.manifest`), the state must be signed for that channel. States
+/// published without a manifest are loaded unverified.
 fn load_state<P: AsRef<Path>>(
     state_dir: P,
     url: &str,
-) -> Result<(VM, [u8; 32]), Box<dyn Error>> {
+    channel: Option<Channel>,
+) -> Result<(VM, Root), Box<dyn Error>> {
     let state_dir = state_dir.as_ref();
     let state_id_path = rusk_profile::to_rusk_state_id_path(state_dir);
 
@@ -329,12 +414,51 @@ fn load_state<P: AsRef<Path>>(
         Theme::default().action("Retrieving"),
     );
     let url = Url::parse(url)?;
-    let buffer = match url.scheme() {
-        "http" | "https" => http::download(url)?,
-        "file" => fs::read(url.path())?,
+    let (buffer, manifest) = match url.scheme() {
+        "http" | "https" => {
+            let manifest = match channel {
+                Some(_) => {
+                    let url = format!("{url}{MANIFEST_EXTENSION}");
+                    http::download_optional(url)?
+                }
+                None => None,
+            };
+            (http::download(url)?, manifest)
+        }
+        "file" => {
+            let manifest = match channel {
+                Some(_) => {
+                    let path = format!("{}{MANIFEST_EXTENSION}", url.path());
+                    match fs::read(path) {
+                        Ok(manifest) => Some(manifest),
+                        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+                        Err(e) => Err(e)?,
+                    }
+                }
+                None => None,
+            };
+            (fs::read(url.path())?, manifest)
+        }
         _ => Err("Unsupported scheme for base state")?,
     };
 
+    if let Some(channel) = channel {
+        match manifest {
+            Some(manifest) => {
+                let manifest = String::from_utf8(manifest)?;
+                Manifest::from_toml(&manifest)?.verify(&buffer, channel)?;
+                info!(
+                    "{} base state signature for {channel}",
+                    Theme::default().success("Verified"),
+                );
+            }
+            None => warn!(
+                "{} base state for {channel} has no manifest",
+                Theme::default().warn("Unverified"),
+            ),
+        }
+    }
+
     tar::unarchive(&buffer, state_dir)?;
 
     let (vm, commit) = restore_state(state_dir)?;
@@ -346,3 +470,27 @@ fn load_state<P: AsRef<Path>>(
 
     Ok((vm, commit))
 }
+
+#[cfg(test)]
+mod tests {
+    use dusk_core::transfer::phoenix::SecretKey;
+    use rand::RngCore;
+
+    use super::*;
+
+    #[test]
+    fn derived_seeds_do_not_collide() {
+        let rng = &mut StdRng::seed_from_u64(0xbeef);
+        let a = PublicKey::from(&SecretKey::random(rng));
+        let b = PublicKey::from(&SecretKey::random(rng));
+
+        assert_eq!(derive_seed(&a, 0), derive_seed(&a, 0));
+        assert_ne!(derive_seed(&a, 0), derive_seed(&a, 1));
+        assert_ne!(derive_seed(&a, 0), derive_seed(&b, 0));
+
+        // An explicit seed equal to the index yields a different stream
+        let derived = StdRng::from_seed(derive_seed(&a, 1)).next_u64();
+        let explicit = StdRng::seed_from_u64(1).next_u64();
+        assert_ne!(derived, explicit);
+    }
+}
@@ -9,18 +9,31 @@ use std::error::Error;
 use http_req::request;
 
 const MAX_REDIRECT: usize = 3;
+const NOT_FOUND: u16 = 404;
 
 pub(super) fn download<T>(uri: T) -> Result<Vec<u8>, Box<dyn Error>>
 where
     T: AsRef<str>,
 {
-    download_with_redirect(uri, MAX_REDIRECT)
+    download_with_redirect(uri, MAX_REDIRECT, false)?
+        .ok_or_else(|| "State download error: not found".into())
+}
+
+/// Downloads the resource at `uri`, returning `None` if it is not found.
+pub(super) fn download_optional<T>(
+    uri: T,
+) -> Result<Option<Vec<u8>>, Box<dyn Error>>
+where
+    T: AsRef<str>,
+{
+    download_with_redirect(uri, MAX_REDIRECT, true)
 }
 
 fn download_with_redirect<T>(
     uri: T,
     redirect_left: usize,
-) -> Result<Vec<u8>, Box<dyn Error>>
+    optional: bool,
+) -> Result<Option<Vec<u8>>, Box<dyn Error>>
 where
     T: AsRef<str>,
 {
@@ -29,11 +42,14 @@ where
     let response = request::get(uri, &mut buffer)?;
     let sc = response.status_code();
     if sc.is_success() {
-        return Ok(buffer);
+        return Ok(Some(buffer));
+    }
+    if optional && u16::from(sc) == NOT_FOUND {
+        return Ok(None);
     }
     if sc.is_redirect() && redirect_left > 1 {
         if let Some(uri) = response.headers().get("location") {
-            return download_with_redirect(uri, redirect_left - 1);
+            return download_with_redirect(uri, redirect_left - 1, optional);
         }
     }
 
@@ -0,0 +1,271 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Signed manifests for published state archives.
+//!
+//! A manifest is a small TOML document that may be published next to a
+//! state archive (at `<archive url>.manifest`). It binds the archive digest
+//! to a release channel and carries an Ed25519 signature from one of the
+//! keys embedded in this crate for that channel.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use once_cell::sync::Lazy;
+use serde_derive::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Domain separator for the signed manifest message.
+const SIGNATURE_DOMAIN: &[u8] = b"dusk-state-manifest";
+
+/// Extension appended to an archive location to obtain its manifest.
+pub const MANIFEST_EXTENSION: &str = ".manifest";
+
+static TRUSTED_SIGNERS: Lazy<BTreeMap<Channel, Vec<VerifyingKey>>> =
+    Lazy::new(|| {
+        let toml = include_str!("../../assets/state-signers.toml");
+        let signers: BTreeMap<Channel, Vec<String>> =
+            toml::from_str(toml).expect("valid signers file");
+        signers
+            .into_iter()
+            .map(|(channel, keys)| {
+                let keys = keys
+                    .iter()
+                    .map(|k| {
+                        let bytes = decode_hex::<32>(k)
+                            .expect("signer key should be valid hex");
+                        VerifyingKey::from_bytes(&bytes)
+                            .expect("signer key should be valid")
+                    })
+                    .collect();
+                (channel, keys)
+            })
+            .collect()
+    });
+
+/// Release channel a state archive is published for.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    Mainnet,
+    Testnet,
+    Devnet,
+}
+
+impl Channel {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Channel::Mainnet => "mainnet",
+            Channel::Testnet => "testnet",
+            Channel::Devnet => "devnet",
+        }
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Channel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mainnet" => Ok(Channel::Mainnet),
+            "testnet" => Ok(Channel::Testnet),
+            "devnet" => Ok(Channel::Devnet),
+            _ => Err(format!("unknown channel {s}")),
+        }
+    }
+}
+
+/// The manifest of a published state archive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Channel the archive is published for.
+    pub channel: Channel,
+    /// SHA-256 digest of the archive (hex).
+    pub digest: String,
+    /// Ed25519 public key of the signer (hex).
+    pub signer: String,
+    /// Ed25519 signature over the channel and the digest (hex).
+    pub signature: String,
+}
+
+impl Manifest {
+    /// Signs `archive` for the given `channel`.
+    pub fn sign(archive: &[u8], channel: Channel, key: &SigningKey) -> Self {
+        let digest: [u8; 32] = Sha256::digest(archive).into();
+        let signature = key.sign(&message(channel, &digest));
+
+        Self {
+            channel,
+            digest: hex::encode(digest),
+            signer: hex::encode(key.verifying_key().to_bytes()),
+            signature: hex::encode(signature.to_bytes()),
+        }
+    }
+
+    /// Verifies that this manifest was issued for `channel` by one of the
+    /// trusted signers of that channel, and that it matches `archive`.
+    pub fn verify(
+        &self,
+        archive: &[u8],
+        channel: Channel,
+    ) -> Result<(), Box<dyn Error>> {
+        self.verify_with(archive, channel, trusted_signers(channel))
+    }
+
+    fn verify_with(
+        &self,
+        archive: &[u8],
+        channel: Channel,
+        trusted: &[VerifyingKey],
+    ) -> Result<(), Box<dyn Error>> {
+        if self.channel != channel {
+            return Err(format!(
+                "State published for {}, expected {channel}",
+                self.channel
+            )
+            .into());
+        }
+
+        let digest: [u8; 32] = Sha256::digest(archive).into();
+        if decode_hex::<32>(&self.digest)? != digest {
+            return Err("State archive digest mismatch".into());
+        }
+
+        let signer = VerifyingKey::from_bytes(&decode_hex(&self.signer)?)?;
+        if !trusted.contains(&signer) {
+            return Err(format!(
+                "Untrusted signer {} for {channel}",
+                self.signer
+            )
+            .into());
+        }
+
+        let signature = Signature::from_bytes(&decode_hex(&self.signature)?);
+        signer.verify(&message(channel, &digest), &signature)?;
+
+        Ok(())
+    }
+
+    /// Parses a manifest from its TOML representation.
+    pub fn from_toml(toml: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// Serializes the manifest to TOML.
+    pub fn to_toml(&self) -> Result<String, Box<dyn Error>> {
+        Ok(toml::to_string(self)?)
+    }
+}
+
+/// Returns the keys trusted to sign states for the given channel.
+pub fn trusted_signers(channel: Channel) -> &'static [VerifyingKey] {
+    TRUSTED_SIGNERS
+        .get(&channel)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Reads an Ed25519 signing key from its hex representation.
+pub fn signing_key_from_hex(hex: &str) -> Result<SigningKey, Box<dyn Error>> {
+    Ok(SigningKey::from_bytes(&decode_hex(hex.trim())?))
+}
+
+fn message(channel: Channel, digest: &[u8; 32]) -> Vec<u8> {
+    let channel = channel.as_str().as_bytes();
+    let mut msg =
+        Vec::with_capacity(SIGNATURE_DOMAIN.len() + channel.len() + 32);
+    msg.extend_from_slice(SIGNATURE_DOMAIN);
+    msg.extend_from_slice(channel);
+    msg.extend_from_slice(digest);
+    msg
+}
+
+fn decode_hex<const N: usize>(s: &str) -> Result<[u8; N], Box<dyn Error>> {
+    hex::decode(s)?
+        .try_into()
+        .map_err(|_| format!("Expected {N} bytes").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARCHIVE: &[u8] = b"state archive";
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    #[test]
+    fn sign_and_verify() -> Result<(), Box<dyn Error>> {
+        let key = key(1);
+        let trusted = [key.verifying_key()];
+
+        let manifest = Manifest::sign(ARCHIVE, Channel::Testnet, &key);
+        let manifest = Manifest::from_toml(&manifest.to_toml()?)?;
+
+        manifest.verify_with(ARCHIVE, Channel::Testnet, &trusted)?;
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid() {
+        let key = key(1);
+        let trusted = [key.verifying_key()];
+        let manifest = Manifest::sign(ARCHIVE, Channel::Testnet, &key);
+
+        manifest
+            .verify_with(ARCHIVE, Channel::Mainnet, &trusted)
+            .expect_err("wrong channel should fail");
+        manifest
+            .verify_with(b"tampered", Channel::Testnet, &trusted)
+            .expect_err("wrong archive should fail");
+        manifest
+            .verify_with(ARCHIVE, Channel::Testnet, &[key(2).verifying_key()])
+            .expect_err("untrusted signer should fail");
+
+        let mut forged = manifest.clone();
+        forged.channel = Channel::Mainnet;
+        forged
+            .verify_with(ARCHIVE, Channel::Mainnet, &trusted)
+            .expect_err("forged channel should fail");
+    }
+
+    #[test]
+    fn embedded_signers() {
+        for channel in [Channel::Mainnet, Channel::Testnet, Channel::Devnet] {
+            // Parses the embedded signers, panicking on invalid keys
+            let trusted = trusted_signers(channel);
+
+            let manifest = Manifest::sign(ARCHIVE, channel, &key(1));
+            if !trusted.contains(&key(1).verifying_key()) {
+                manifest
+                    .verify(ARCHIVE, channel)
+                    .expect_err("untrusted signer should fail");
+            }
+        }
+    }
+}
@@ -13,9 +13,11 @@ use dusk_core::Dusk;
 use serde_derive::{Deserialize, Serialize};
 
 use crate::state;
+use crate::state::manifest::Channel;
 
+mod amount;
 mod stake;
-pub use stake::GenesisStake;
+pub use stake::{GenesisStake, GenesisStakeConfig};
 mod wrapper;
 use wrapper::Wrapper;
 
@@ -23,7 +25,11 @@ use wrapper::Wrapper;
 pub struct PhoenixBalance {
     address: Wrapper<PhoenixPublicKey, { PhoenixPublicKey::SIZE }>,
     pub seed: Option<u64>,
-    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::new")]
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        default = "Vec::new",
+        deserialize_with = "amount::deserialize_vec"
+    )]
     pub notes: Vec<Dusk>,
 }
 
@@ -36,6 +42,7 @@ impl PhoenixBalance {
 #[derive(Serialize, Deserialize, PartialEq, Eq)]
 pub struct MoonlightAccount {
     address: Wrapper<AccountPublicKey, { AccountPublicKey::SIZE }>,
+    #[serde(deserialize_with = "amount::deserialize")]
     pub balance: Dusk,
 }
 
@@ -45,9 +52,25 @@ impl MoonlightAccount {
     }
 }
 
+/// A protocol upgrade declared by the genesis, activated at a block height.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GenesisUpgrade {
+    /// Name identifying the rule changes of the upgrade
+    pub name: String,
+    /// Height of the first block following the rules of the upgrade
+    pub height: u64,
+}
+
+/// The upgrades file written in the state directory, read by the node.
+#[derive(Serialize)]
+struct GenesisUpgrades<'a> {
+    upgrade: &'a [GenesisUpgrade],
+}
+
 #[derive(Serialize, Deserialize, Default, PartialEq, Eq)]
 pub struct Snapshot {
     base_state: Option<String>,
+    channel: Option<Channel>,
     owner: Option<Wrapper<AccountPublicKey, { AccountPublicKey::SIZE }>>,
 
     // This "serde skip" workaround seems needed as per https://github.com/toml-rs/toml-rs/issues/384
@@ -57,6 +80,10 @@ pub struct Snapshot {
     moonlight_account: Vec<MoonlightAccount>,
     #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::new")]
     stake: Vec<GenesisStake>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    stake_config: Option<GenesisStakeConfig>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::new")]
+    upgrade: Vec<GenesisUpgrade>,
 }
 
 impl Debug for Snapshot {
@@ -87,6 +114,25 @@ impl Snapshot {
         self.stake.iter()
     }
 
+    /// Returns the parameters of the stake contract, if any differs from the
+    /// defaults.
+    pub fn stake_config(&self) -> Option<&GenesisStakeConfig> {
+        self.stake_config.as_ref()
+    }
+
+    /// Returns the protocol upgrades declared for the network.
+    pub fn upgrades(&self) -> &[GenesisUpgrade] {
+        &self.upgrade
+    }
+
+    /// Serializes the protocol upgrades, as written in the state directory
+    /// for the node to read them.
+    pub fn upgrades_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(&GenesisUpgrades {
+            upgrade: &self.upgrade,
+        })
+    }
+
     /// Return the owner of the smart contract.
     pub fn owner(&self) -> [u8; AccountPublicKey::SIZE] {
         let dusk = Wrapper::from(*state::DUSK_CONSENSUS_KEY);
@@ -96,6 +142,18 @@ impl Snapshot {
     pub fn base_state(&self) -> Option<&str> {
         self.base_state.as_deref()
     }
+
+    /// Returns the release channel the manifest of the base state, if
+    /// published, must be signed for.
+    pub fn channel(&self) -> Option<Channel> {
+        self.channel
+    }
+
+    /// Sets the release channel the manifest of the base state, if
+    /// published, must be signed for.
+    pub fn set_channel(&mut self, channel: Channel) {
+        self.channel = Some(channel);
+    }
 }
 
 #[cfg(test)]
@@ -103,7 +161,7 @@ mod tests {
 
     use std::error::Error;
 
-    use dusk_core::stake::DEFAULT_MINIMUM_STAKE;
+    use dusk_core::stake::{StakeConfig, DEFAULT_MINIMUM_STAKE, EPOCH};
 
     use super::*;
     use crate::state;
@@ -142,6 +200,46 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn stake_config_toml() -> Result<(), Box<dyn Error>> {
+        let snapshot: Snapshot = toml::from_str(
+            r#"
+            [stake_config]
+            minimum_stake = "500"
+            maturity = 100
+            "#,
+        )?;
+
+        let mut config = StakeConfig::new();
+        snapshot
+            .stake_config()
+            .expect("stake config to be set")
+            .apply(&mut config);
+        assert_eq!(config.minimum_stake, DEFAULT_MINIMUM_STAKE / 2);
+        assert_eq!(config.epoch, EPOCH);
+        assert_eq!(config.maturity, 100);
+
+        Ok(())
+    }
+
+    #[test]
+    fn upgrades_toml() -> Result<(), Box<dyn Error>> {
+        let snapshot: Snapshot = toml::from_str(
+            r#"
+            [[upgrade]]
+            name = "gas-limit"
+            height = 1000
+            "#,
+        )?;
+
+        assert_eq!(snapshot.upgrades()[0].height, 1000);
+        let written = snapshot.upgrades_toml()?;
+        let read: Snapshot = toml::from_str(&written)?;
+        assert_eq!(read.upgrades(), snapshot.upgrades());
+
+        Ok(())
+    }
+
     #[test]
     fn empty_toml() -> Result<(), Box<dyn Error>> {
         let str = toml::to_string_pretty(&Snapshot::default())?;
@@ -0,0 +1,97 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Amounts of a snapshot, given either as an integer of LUX or as a string of
+//! decimal DUSK, such as `"1000.5"`.
+
+use dusk_core::{parse_dusk, Dusk};
+use serde::de::Error;
+use serde::Deserializer;
+use serde_derive::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Amount {
+    Lux(u64),
+    Dusk(String),
+}
+
+impl Amount {
+    fn to_lux<E: Error>(self) -> Result<Dusk, E> {
+        match self {
+            Amount::Lux(lux) => Ok(lux),
+            Amount::Dusk(dusk) => parse_dusk(&dusk)
+                .map_err(|e| E::custom(format!("Invalid amount {dusk}: {e}"))),
+        }
+    }
+}
+
+pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Dusk, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    <Amount as serde::Deserialize>::deserialize(deserializer)?.to_lux()
+}
+
+pub(crate) fn deserialize_opt<'de, D>(
+    deserializer: D,
+) -> Result<Option<Dusk>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    <Option<Amount> as serde::Deserialize>::deserialize(deserializer)?
+        .map(Amount::to_lux)
+        .transpose()
+}
+
+pub(crate) fn deserialize_vec<'de, D>(
+    deserializer: D,
+) -> Result<Vec<Dusk>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    <Vec<Amount> as serde::Deserialize>::deserialize(deserializer)?
+        .into_iter()
+        .map(Amount::to_lux)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Amounts {
+        #[serde(deserialize_with = "deserialize")]
+        balance: Dusk,
+        #[serde(deserialize_with = "deserialize_vec")]
+        notes: Vec<Dusk>,
+        #[serde(deserialize_with = "deserialize_opt", default)]
+        reward: Option<Dusk>,
+    }
+
+    #[test]
+    fn lux_or_dusk() -> Result<(), toml::de::Error> {
+        let amounts: Amounts = toml::from_str(
+            r#"
+            balance = "1000.5"
+            notes = [1_000_000_000, "0.3"]
+            "#,
+        )?;
+        assert_eq!(amounts.balance, 1_000_500_000_000);
+        assert_eq!(amounts.notes, vec![1_000_000_000, 300_000_000]);
+        assert_eq!(amounts.reward, None);
+
+        let invalid = toml::from_str::<Amounts>(
+            r#"
+            balance = "1,5"
+            notes = []
+            "#,
+        );
+        assert!(invalid.is_err());
+        Ok(())
+    }
+}
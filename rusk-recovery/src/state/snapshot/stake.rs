@@ -6,18 +6,21 @@
 
 use dusk_bytes::Serializable;
 use dusk_core::signatures::bls::PublicKey as BlsPublicKey;
-use dusk_core::stake::StakeKeys;
+use dusk_core::stake::{StakeConfig, StakeKeys};
 use dusk_core::Dusk;
 use serde_derive::{Deserialize, Serialize};
 
+use super::amount;
 use super::wrapper::Wrapper;
 
 #[derive(Serialize, Deserialize, PartialEq, Eq)]
 pub struct GenesisStake {
     address: Wrapper<BlsPublicKey, { BlsPublicKey::SIZE }>,
     owner: Option<Wrapper<BlsPublicKey, { BlsPublicKey::SIZE }>>,
+    #[serde(deserialize_with = "amount::deserialize")]
     pub amount: Dusk,
     pub eligibility: Option<u64>,
+    #[serde(deserialize_with = "amount::deserialize_opt", default)]
     pub reward: Option<Dusk>,
 }
 
@@ -31,3 +34,30 @@ impl GenesisStake {
         StakeKeys::new(*self.address(), *owner)
     }
 }
+
+/// Parameters of the stake contract set at genesis, overriding the defaults.
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct GenesisStakeConfig {
+    #[serde(deserialize_with = "amount::deserialize_opt", default)]
+    pub minimum_stake: Option<Dusk>,
+    /// Number of blocks in an epoch
+    pub epoch: Option<u64>,
+    /// Number of blocks a stake takes to mature, once the epoch it is created
+    /// in ends
+    pub maturity: Option<u64>,
+}
+
+impl GenesisStakeConfig {
+    /// Applies the parameters given to `config`.
+    pub fn apply(&self, config: &mut StakeConfig) {
+        if let Some(minimum_stake) = self.minimum_stake {
+            config.minimum_stake = minimum_stake;
+        }
+        if let Some(epoch) = self.epoch {
+            config.epoch = epoch;
+        }
+        if let Some(maturity) = self.maturity {
+            config.maturity = maturity;
+        }
+    }
+}
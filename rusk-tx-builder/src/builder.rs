@@ -18,7 +18,7 @@ use dusk_core::transfer::moonlight::{
     AccountData, Transaction as MoonlightTransaction,
 };
 use dusk_core::transfer::phoenix::{
-    Note, NoteLeaf, NoteOpening, PublicKey as PhoenixPublicKey,
+    Note, NoteLeaf, NoteOpening, Prove, PublicKey as PhoenixPublicKey,
     SecretKey as PhoenixSecretKey, ViewKey as PhoenixViewKey,
 };
 use dusk_core::transfer::Transaction;
@@ -86,6 +86,23 @@ pub enum Error<S: Store, SC: StateClient> {
         /// Information about the key's stake.
         stake: StakeData,
     },
+    /// The key doesn't have enough stake. This happens when the user tries to
+    /// unstake more than the funds staked for a key.
+    NotEnoughStake {
+        /// The key that is staked.
+        key: BlsPublicKey,
+        /// Information about the key's stake.
+        stake: StakeData,
+    },
+    /// The stake left is lower than the minimum stake. This happens when the
+    /// user tries to unstake part of the funds staked for a key, leaving less
+    /// than the minimum stake behind.
+    StakeBelowMinimum {
+        /// The key that is staked.
+        key: BlsPublicKey,
+        /// Information about the key's stake.
+        stake: StakeData,
+    },
     /// The key has no reward. This happens when a key has no reward in the
     /// stake contract and the user tries to make a stake withdraw transaction.
     NoReward {
@@ -145,19 +162,34 @@ impl<S: Store, SC: StateClient> From<ExecutionError> for Error<S, SC> {
     }
 }
 
-/// A wallet implementation.
+/// Builds transactions spending the funds of the keys held in a [`Store`].
 ///
-/// This is responsible for holding the keys, and performing operations like
-/// creating transactions.
-pub struct Wallet<S, SC> {
+/// The notes, accounts and stakes needed to build them are fetched through
+/// the [`StateClient`], and the phoenix transactions are proven with `P`,
+/// which defaults to proving them locally.
+pub struct TxBuilder<S, SC, P = LocalProver> {
     store: S,
     state: SC,
+    prover: P,
 }
 
-impl<S, SC> Wallet<S, SC> {
-    /// Create a new wallet given the underlying store and node client.
+impl<S, SC> TxBuilder<S, SC> {
+    /// Create a new builder given the underlying store and node client,
+    /// proving the transactions locally.
     pub const fn new(store: S, state: SC) -> Self {
-        Self { store, state }
+        Self::with_prover(store, state, LocalProver)
+    }
+}
+
+impl<S, SC, P> TxBuilder<S, SC, P> {
+    /// Create a new builder given the underlying store, node client and
+    /// prover.
+    pub const fn with_prover(store: S, state: SC, prover: P) -> Self {
+        Self {
+            store,
+            state,
+            prover,
+        }
     }
 
     /// Return the inner Store reference
@@ -171,10 +203,11 @@ impl<S, SC> Wallet<S, SC> {
     }
 }
 
-impl<S, SC> Wallet<S, SC>
+impl<S, SC, P> TxBuilder<S, SC, P>
 where
     S: Store,
     SC: StateClient,
+    P: Prove,
 {
     /// Retrieve the secret key with the given index.
     pub fn phoenix_secret_key(
@@ -338,6 +371,38 @@ where
         Ok(notes_openings)
     }
 
+    /// Returns the value to unstake from the `stake` of `key`: the whole
+    /// unlocked stake if `value` is `None`, otherwise `value`, as long as the
+    /// stake left is either zero or above the minimum stake.
+    fn unstake_value(
+        &self,
+        key: BlsPublicKey,
+        stake: StakeData,
+        value: Option<u64>,
+    ) -> Result<u64, Error<S, SC>> {
+        let amount = stake.amount.ok_or(Error::NotStaked { key, stake })?;
+        let Some(value) = value else {
+            return Ok(amount.value);
+        };
+
+        let total = amount.total_funds();
+        if value > total {
+            return Err(Error::NotEnoughStake { key, stake });
+        }
+        let left = total - value;
+        if left != 0 {
+            let minimum_stake = self
+                .state
+                .fetch_minimum_stake()
+                .map_err(Error::from_state_err)?;
+            if left < minimum_stake {
+                return Err(Error::StakeBelowMinimum { key, stake });
+            }
+        }
+
+        Ok(value)
+    }
+
     /// Executes a generic contract call, paying gas from a public account.
     #[allow(clippy::too_many_arguments)]
     pub fn moonlight_execute(
@@ -423,7 +488,7 @@ where
             gas_price,
             chain_id,
             Some(data),
-            &LocalProver,
+            &self.prover,
         )?;
 
         sender_sk.zeroize();
@@ -474,7 +539,7 @@ where
             gas_price,
             chain_id,
             data,
-            &LocalProver,
+            &self.prover,
         )?;
 
         sender_sk.zeroize();
@@ -517,7 +582,7 @@ where
             gas_price,
             chain_id,
             stake_value,
-            &LocalProver,
+            &self.prover,
         )?;
 
         stake_sk.zeroize();
@@ -527,11 +592,16 @@ where
     }
 
     /// Unstakes a key from the stake contract, using Phoenix notes.
+    ///
+    /// If `value` is `None` the whole unlocked stake is withdrawn, otherwise
+    /// only the given amount is, as long as the stake left is either zero or
+    /// above the minimum stake.
     pub fn phoenix_unstake<Rng: RngCore + CryptoRng>(
         &self,
         rng: &mut Rng,
         sender_index: u8,
         staker_index: u8,
+        value: Option<u64>,
         gas_limit: u64,
         gas_price: u64,
     ) -> Result<Transaction, Error<S, SC>> {
@@ -552,13 +622,7 @@ where
             .fetch_stake(&stake_pk)
             .map_err(Error::from_state_err)?;
 
-        let staked_amount = stake
-            .amount
-            .ok_or(Error::NotStaked {
-                key: stake_pk,
-                stake,
-            })?
-            .value;
+        let unstake_value = self.unstake_value(stake_pk, stake, value)?;
 
         let chain_id =
             self.state.fetch_chain_id().map_err(Error::from_state_err)?;
@@ -570,11 +634,11 @@ where
             &stake_sk,
             inputs,
             root,
-            staked_amount,
+            unstake_value,
             gas_limit,
             gas_price,
             chain_id,
-            &LocalProver,
+            &self.prover,
         )?;
 
         stake_sk.zeroize();
@@ -625,7 +689,7 @@ where
             gas_limit,
             gas_price,
             chain_id,
-            &LocalProver,
+            &self.prover,
         )?;
 
         stake_sk.zeroize();
@@ -669,7 +733,7 @@ where
             gas_limit,
             gas_price,
             chain_id,
-            &LocalProver,
+            &self.prover,
         )?;
 
         phoenix_sender_sk.zeroize();
@@ -714,7 +778,7 @@ where
             gas_limit,
             gas_price,
             chain_id,
-            &LocalProver,
+            &self.prover,
         )?;
 
         phoenix_sender_sk.zeroize();
@@ -836,11 +900,16 @@ where
     }
 
     /// Unstakes a key from the stake contract, using a Moonlight account.
+    ///
+    /// If `value` is `None` the whole unlocked stake is withdrawn, otherwise
+    /// only the given amount is, as long as the stake left is either zero or
+    /// above the minimum stake.
     pub fn moonlight_unstake<Rng: RngCore + CryptoRng>(
         &self,
         rng: &mut Rng,
         sender_index: u8,
         staker_index: u8,
+        value: Option<u64>,
         gas_limit: u64,
         gas_price: u64,
     ) -> Result<Transaction, Error<S, SC>> {
@@ -859,13 +928,8 @@ where
             .fetch_stake(&staker_pk)
             .map_err(Error::from_state_err)?;
 
-        let unstake_value = staker_data
-            .amount
-            .ok_or(Error::NotStaked {
-                key: staker_pk,
-                stake: staker_data,
-            })?
-            .value;
+        let unstake_value =
+            self.unstake_value(staker_pk, staker_data, value)?;
         let chain_id =
             self.state.fetch_chain_id().map_err(Error::from_state_err)?;
 
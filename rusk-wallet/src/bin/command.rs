@@ -5,10 +5,13 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 mod history;
+mod rewards;
 
 pub use history::TransactionHistory;
+pub use rewards::EpochRewards;
 
 use std::fmt;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
 use clap::Subcommand;
@@ -22,12 +25,13 @@ use rusk_wallet::gas::{
     DEFAULT_PRICE, MIN_PRICE_DEPLOYMENT,
 };
 use rusk_wallet::{
-    Address, Error, Profile, Wallet, EPOCH, MAX_CONTRACT_INIT_ARG_SIZE,
-    MAX_PROFILES,
+    Address, Error, Profile, Wallet, MAX_CONTRACT_INIT_ARG_SIZE, MAX_PROFILES,
 };
+use serde_json::{json, Value};
 use wallet_core::BalanceInfo;
 
 use crate::io::prompt;
+use crate::seeds::SeedCommand;
 use crate::settings::Settings;
 use crate::{WalletFile, WalletPath};
 
@@ -104,6 +108,11 @@ pub(crate) enum Command {
         /// Optional memo to attach to the transaction
         #[arg(long)]
         memo: Option<String>,
+
+        /// Height of the last block the transfer can be included in, for
+        /// public transfers only [default: never expires]
+        #[arg(long)]
+        expiry_height: Option<u64>,
     },
 
     /// Convert shielded DUSK to public DUSK
@@ -156,6 +165,25 @@ pub(crate) enum Command {
         reward: bool,
     },
 
+    /// Show the rewards of your stake earned, withdrawn and slashed per epoch
+    Rewards {
+        /// Profile index of the stake [default: 0]
+        #[arg(long)]
+        profile_idx: Option<u8>,
+
+        /// First epoch of the report [default: 0]
+        #[arg(long)]
+        from_epoch: Option<u64>,
+
+        /// Last epoch of the report [default: last finalized epoch]
+        #[arg(long)]
+        to_epoch: Option<u64>,
+
+        /// Output the report as CSV, with the amounts in LUX
+        #[arg(long)]
+        csv: bool,
+    },
+
     /// Stake DUSK
     Stake {
         /// Address from which to stake DUSK [default: first address]
@@ -186,6 +214,10 @@ pub(crate) enum Command {
         #[arg(short, long)]
         address: Option<Address>,
 
+        /// Amount of DUSK to unstake [default: the whole stake]
+        #[arg(long)]
+        amt: Option<Dusk>,
+
         /// Max amount of gas for this transaction
         #[arg(short = 'l', long, default_value_t = DEFAULT_LIMIT_CALL)]
         gas_limit: u64,
@@ -211,6 +243,25 @@ pub(crate) enum Command {
         gas_price: Lux,
     },
 
+    /// Register the address receiving the rewards of a stake
+    StakeBeneficiary {
+        /// Profile index of the stake [default: 0]
+        #[arg(long)]
+        profile_idx: Option<u8>,
+
+        /// Address receiving the rewards [default: clear the beneficiary]
+        #[arg(long)]
+        beneficiary: Option<Address>,
+
+        /// Max amount of gas for this transaction
+        #[arg(short = 'l', long, default_value_t = DEFAULT_LIMIT_CALL)]
+        gas_limit: u64,
+
+        /// Price you're going to pay for each gas unit (in LUX)
+        #[arg(short = 'p', long, default_value_t = DEFAULT_PRICE)]
+        gas_price: Lux,
+    },
+
     /// Call a contract
     ContractCall {
         /// Address that pays the gas for the contract call [default: first]
@@ -301,6 +352,26 @@ pub(crate) enum Command {
         export_pwd: Option<String>,
     },
 
+    /// Serve a JSON-RPC API on a local address, for third-party
+    /// integrations
+    Daemon {
+        /// Address to listen on, which must be a loopback one
+        #[arg(long, default_value = "127.0.0.1:8090")]
+        listen: SocketAddr,
+
+        /// File holding the token authorizing the requests, sent as
+        /// `Authorization: Bearer <token>` [default:
+        /// env(RUSK_WALLET_DAEMON_TOKEN)]
+        #[arg(long)]
+        token_file: Option<PathBuf>,
+    },
+
+    /// Manage the seeds stored in the wallet directory
+    Seed {
+        #[command(subcommand)]
+        cmd: SeedCommand,
+    },
+
     /// Show current settings
     Settings,
 }
@@ -342,10 +413,9 @@ impl Command {
             Command::Profiles { new } => {
                 if new {
                     if wallet.profiles().len() >= MAX_PROFILES {
-                        println!(
-                            "Cannot create more profiles, this wallet only supports up to {MAX_PROFILES} profiles. You have {} profiles already.", wallet.profiles().len()
+                        return Err(
+                            Error::ProfilesExhausted(MAX_PROFILES).into()
                         );
-                        std::process::exit(0);
                     }
 
                     let new_addr_idx = wallet.add_profile();
@@ -368,6 +438,7 @@ impl Command {
                 gas_limit,
                 gas_price,
                 memo,
+                expiry_height,
             } => {
                 let sender_idx = match sender {
                     Some(addr) => {
@@ -382,6 +453,9 @@ impl Command {
                 let memo = memo.filter(|m| !m.trim().is_empty());
                 let tx = match rcvr {
                     Address::Shielded(_) => {
+                        if expiry_height.is_some() {
+                            return Err(Error::ShieldedExpiry.into());
+                        }
                         wallet.sync().await?;
                         let rcvr_pk = rcvr.shielded_key()?;
                         wallet
@@ -394,7 +468,12 @@ impl Command {
                         let rcvr_pk = rcvr.public_key()?;
                         wallet
                             .moonlight_transfer(
-                                sender_idx, rcvr_pk, memo, amt, gas,
+                                sender_idx,
+                                rcvr_pk,
+                                memo,
+                                amt,
+                                gas,
+                                expiry_height,
                             )
                             .await?
                     }
@@ -433,6 +512,7 @@ impl Command {
             }
             Command::Unstake {
                 address,
+                amt,
                 gas_limit,
                 gas_price,
             } => {
@@ -443,10 +523,10 @@ impl Command {
                 let tx = match address {
                     Address::Shielded(_) => {
                         wallet.sync().await?;
-                        wallet.phoenix_unstake(addr_idx, gas).await
+                        wallet.phoenix_unstake(addr_idx, amt, gas).await
                     }
                     Address::Public(_) => {
-                        wallet.moonlight_unstake(addr_idx, gas).await
+                        wallet.moonlight_unstake(addr_idx, amt, gas).await
                     }
                 }?;
 
@@ -473,6 +553,21 @@ impl Command {
 
                 Ok(RunResult::Tx(tx.hash()))
             }
+            Command::StakeBeneficiary {
+                profile_idx,
+                beneficiary,
+                gas_limit,
+                gas_price,
+            } => {
+                let profile_idx = profile_idx.unwrap_or_default();
+
+                let gas = Gas::new(gas_limit).with_price(gas_price);
+                let tx = wallet
+                    .set_stake_beneficiary(profile_idx, beneficiary, gas)
+                    .await?;
+
+                Ok(RunResult::Tx(tx.hash()))
+            }
             Command::StakeInfo {
                 profile_idx,
                 reward,
@@ -482,8 +577,28 @@ impl Command {
                     .stake_info(profile_idx)
                     .await?
                     .ok_or(Error::NotStaked)?;
+                let epoch = wallet.epoch().await?;
+
+                Ok(RunResult::StakeInfo(stake_info, epoch, reward))
+            }
+            Command::Rewards {
+                profile_idx,
+                from_epoch,
+                to_epoch,
+                csv,
+            } => {
+                let profile_idx = profile_idx.unwrap_or_default();
+                let pk = wallet.public_key(profile_idx)?;
 
-                Ok(RunResult::StakeInfo(stake_info, reward))
+                let rewards = rewards::reward_history(
+                    settings,
+                    pk,
+                    from_epoch.unwrap_or_default(),
+                    to_epoch,
+                )
+                .await?;
+
+                Ok(RunResult::Rewards(rewards, csv))
             }
             Command::Export {
                 profile_idx,
@@ -675,6 +790,8 @@ impl Command {
             Command::Create { .. } => Ok(RunResult::Create()),
             Command::Restore { .. } => Ok(RunResult::Restore()),
             Command::Settings => Ok(RunResult::Settings()),
+            Command::Seed { .. } => Ok(RunResult::Seed()),
+            Command::Daemon { .. } => Ok(RunResult::Daemon()),
         }
     }
 }
@@ -684,7 +801,9 @@ pub enum RunResult<'a> {
     Tx(BlsScalar),
     PhoenixBalance(BalanceInfo, bool),
     MoonlightBalance(Dusk),
-    StakeInfo(StakeData, bool),
+    /// The stake, the number of blocks in an epoch, and whether to show
+    /// only the reward
+    StakeInfo(StakeData, u64, bool),
     Profile((u8, &'a Profile)),
     Profiles(&'a Vec<Profile>),
     ContractId([u8; CONTRACT_ID_BYTES]),
@@ -692,7 +811,10 @@ pub enum RunResult<'a> {
     Create(),
     Restore(),
     Settings(),
+    Seed(),
+    Daemon(),
     PhoenixHistory(Vec<TransactionHistory>),
+    Rewards(Vec<EpochRewards>, bool),
 }
 
 impl fmt::Display for RunResult<'_> {
@@ -741,12 +863,12 @@ impl fmt::Display for RunResult<'_> {
                 let hash = hex::encode(hash.to_bytes());
                 write!(f, "> Transaction sent: {hash}",)
             }
-            StakeInfo(data, _) => {
+            StakeInfo(data, epoch, _) => {
                 if let Some(amt) = data.amount {
                     let amount = Dusk::from(amt.value);
                     let locked = Dusk::from(amt.locked);
                     let eligibility = amt.eligibility;
-                    let epoch = amt.eligibility / EPOCH;
+                    let epoch = amt.eligibility / epoch;
 
                     writeln!(f, "> Eligible stake: {amount} DUSK")?;
                     writeln!(f, "> Reclaimable slashed stake: {locked} DUSK")?;
@@ -781,7 +903,133 @@ impl fmt::Display for RunResult<'_> {
                 }
                 Ok(())
             }
-            Create() | Restore() | Settings() => unreachable!(),
+            Rewards(rewards, csv) => {
+                if *csv {
+                    writeln!(f, "{}", EpochRewards::csv_header())?;
+                    for epoch in rewards {
+                        writeln!(f, "{}", epoch.to_csv())?;
+                    }
+                } else {
+                    writeln!(f, "{}", EpochRewards::header())?;
+                    for epoch in rewards {
+                        writeln!(f, "{epoch}")?;
+                    }
+                }
+                Ok(())
+            }
+            Create() | Restore() | Settings() | Seed() | Daemon() => {
+                unreachable!()
+            }
+        }
+    }
+}
+
+impl RunResult<'_> {
+    /// Returns the result as JSON, with the amounts in LUX
+    pub fn to_json(&self) -> Value {
+        use RunResult::*;
+        match self {
+            PhoenixBalance(balance, _) => json!({
+                "shielded": balance.value,
+                "spendable": balance.spendable,
+            }),
+            MoonlightBalance(balance) => json!({ "public": **balance }),
+            Profile((profile_idx, profile)) => {
+                profile_json(*profile_idx, profile)
+            }
+            Profiles(profiles) => json!({
+                "profiles": profiles
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, profile)| profile_json(idx as u8, profile))
+                    .collect::<Vec<_>>(),
+            }),
+            Tx(hash) => json!({ "tx_id": hex::encode(hash.to_bytes()) }),
+            StakeInfo(data, epoch, _) => json!({
+                "amount": data.amount.map(|amt| json!({
+                    "value": amt.value,
+                    "locked": amt.locked,
+                    "eligibility": amt.eligibility,
+                    "epoch": amt.eligibility / epoch,
+                })),
+                "faults": data.faults,
+                "hard_faults": data.hard_faults,
+                "reward": data.reward,
+            }),
+            ContractId(bytes) => json!({ "contract_id": hex::encode(bytes) }),
+            ExportedKeys(pk, kp) => json!({
+                "public_key": pk,
+                "key_pair": kp,
+            }),
+            PhoenixHistory(transactions) => json!({
+                "transactions": transactions
+                    .iter()
+                    .map(|th| th.to_json())
+                    .collect::<Vec<_>>(),
+            }),
+            Rewards(rewards, _) => json!({
+                "epochs": rewards
+                    .iter()
+                    .map(|epoch| epoch.to_json())
+                    .collect::<Vec<_>>(),
+            }),
+            Create() | Restore() | Settings() | Seed() | Daemon() => json!({}),
         }
     }
 }
+
+fn profile_json(profile_idx: u8, profile: &Profile) -> Value {
+    json!({
+        "profile_idx": profile_idx,
+        "shielded": Address::Shielded(profile.shielded_addr).to_string(),
+        "public": Address::Public(profile.public_addr).to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use dusk_core::stake::StakeAmount;
+
+    use super::*;
+
+    #[test]
+    fn stake_info_json_uses_network_epoch() {
+        let data = StakeData {
+            amount: Some(StakeAmount {
+                value: 1_000,
+                locked: 10,
+                eligibility: 4_320,
+            }),
+            reward: 5,
+            faults: 1,
+            hard_faults: 0,
+        };
+
+        let json = RunResult::StakeInfo(data, 10, false).to_json();
+        assert_eq!(
+            json,
+            json!({
+                "amount": {
+                    "value": 1_000,
+                    "locked": 10,
+                    "eligibility": 4_320,
+                    "epoch": 432,
+                },
+                "faults": 1,
+                "hard_faults": 0,
+                "reward": 5,
+            })
+        );
+
+        let json =
+            RunResult::StakeInfo(StakeData::default(), 10, false).to_json();
+        assert_eq!(json["amount"], Value::Null);
+    }
+
+    #[test]
+    fn tx_json() {
+        let json = RunResult::Tx(BlsScalar::from(1u64)).to_json();
+        let tx_id = hex::encode(BlsScalar::from(1u64).to_bytes());
+        assert_eq!(json, json!({ "tx_id": tx_id }));
+    }
+}
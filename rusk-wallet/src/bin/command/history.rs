@@ -9,7 +9,7 @@ use std::collections::HashMap;
 use std::fmt::{self, Display};
 
 use dusk_core::transfer::Transaction;
-use dusk_core::{dusk, from_dusk};
+use dusk_core::{checked_fee, DuskDisplay};
 use rusk_wallet::{BlockTransaction, DecodedNote, GraphQL};
 
 use crate::io::{self};
@@ -18,13 +18,35 @@ use crate::settings::Settings;
 pub struct TransactionHistory {
     direction: TransactionDirection,
     height: u64,
-    amount: f64,
+    /// Change of the balance, in LUX
+    amount: i64,
     fee: u64,
     pub tx: Transaction,
     id: String,
 }
 
 impl TransactionHistory {
+    /// Returns the transaction as JSON, with the amounts in LUX
+    pub fn to_json(&self) -> serde_json::Value {
+        let method = match self.tx.call() {
+            None => "transfer",
+            Some(call) => &call.fn_name,
+        };
+        let direction = match self.direction {
+            TransactionDirection::In => "in",
+            TransactionDirection::Out => "out",
+        };
+
+        serde_json::json!({
+            "height": self.height,
+            "tx_id": self.id,
+            "method": method,
+            "direction": direction,
+            "amount": self.amount,
+            "fee": self.fee,
+        })
+    }
+
     pub fn header() -> String {
         format!(
             "{: ^9} | {: ^64} | {: ^8} | {: ^17} | {: ^12}",
@@ -35,7 +57,7 @@ impl TransactionHistory {
 
 impl Display for TransactionHistory {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let dusk = self.amount / dusk(1.0) as f64;
+        let dusk = DuskDisplay::signed(self.amount);
         let contract = match self.tx.call() {
             None => "transfer",
             Some(call) => &call.fn_name,
@@ -44,8 +66,7 @@ impl Display for TransactionHistory {
         let fee = match self.direction {
             TransactionDirection::In => "".into(),
             TransactionDirection::Out => {
-                let fee = self.fee;
-                let fee = from_dusk(fee);
+                let fee = DuskDisplay::new(self.fee);
                 format!("{: >12.9}", fee)
             }
         };
@@ -83,7 +104,7 @@ pub(crate) async fn transaction_from_notes(
         // in the tx
         decoded_note.note.set_pos(u64::MAX);
 
-        let note_amount = decoded_note.amount as f64;
+        let note_amount = decoded_note.amount as i64;
 
         let txs = match block_txs.entry(decoded_note.block_height) {
             Entry::Occupied(o) => o.into_mut(),
@@ -104,7 +125,7 @@ pub(crate) async fn transaction_from_notes(
                 })
         });
         if let Some(BlockTransaction { tx, id, gas_spent }) = note_creator {
-            let inputs_amount: f64 = tx
+            let inputs_amount = tx
                 .nullifiers()
                 .iter()
                 .filter_map(|input| {
@@ -112,9 +133,9 @@ pub(crate) async fn transaction_from_notes(
                         nullifier.eq(input).then_some(gas)
                     })
                 })
-                .sum::<u64>() as f64;
+                .sum::<u64>() as i64;
 
-            let direction = match inputs_amount > 0f64 {
+            let direction = match inputs_amount > 0 {
                 true => TransactionDirection::Out,
                 false => TransactionDirection::In,
             };
@@ -125,7 +146,8 @@ pub(crate) async fn transaction_from_notes(
                     direction,
                     height: decoded_note.block_height,
                     amount: note_amount - inputs_amount,
-                    fee: *gas_spent * tx.gas_price(),
+                    fee: checked_fee(*gas_spent, tx.gas_price())
+                        .unwrap_or(u64::MAX),
                     tx: tx.clone(),
                     id: id.clone(),
                 }),
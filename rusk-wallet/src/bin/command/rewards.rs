@@ -0,0 +1,252 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use dusk_core::from_dusk;
+use dusk_core::signatures::bls::PublicKey as BlsPublicKey;
+use dusk_core::stake::{Reward, SlashEvent, StakeEvent, STAKE_CONTRACT};
+use rusk_wallet::{BlockEvent, GraphQL};
+
+use crate::io::{self};
+use crate::settings::Settings;
+
+/// Rewards of a stake earned, withdrawn and slashed over an epoch, in LUX.
+#[derive(Default)]
+pub struct EpochRewards {
+    epoch: u64,
+    earned: u64,
+    withdrawn: u64,
+    slashed: u64,
+}
+
+impl EpochRewards {
+    /// Returns the rewards as a CSV record, with the amounts in LUX
+    pub fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{}",
+            self.epoch, self.earned, self.withdrawn, self.slashed
+        )
+    }
+
+    /// Returns the rewards as JSON, with the amounts in LUX
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "epoch": self.epoch,
+            "earned": self.earned,
+            "withdrawn": self.withdrawn,
+            "slashed": self.slashed,
+        })
+    }
+
+    pub fn csv_header() -> &'static str {
+        "epoch,earned,withdrawn,slashed"
+    }
+
+    pub fn header() -> String {
+        format!(
+            "{: ^7} | {: ^17} | {: ^17} | {: ^17}",
+            "EPOCH", "EARNED", "WITHDRAWN", "SLASHED"
+        )
+    }
+}
+
+impl Display for EpochRewards {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let epoch = self.epoch;
+        let earned = from_dusk(self.earned);
+        let withdrawn = from_dusk(self.withdrawn);
+        let slashed = from_dusk(self.slashed);
+
+        write!(
+            f,
+            "{epoch: >7} | {earned: >17.9} | {withdrawn: >17.9} | {slashed: >17.9}"
+        )
+    }
+}
+
+/// Maximum number of heights the node serves the events of in a query.
+const MAX_EVENTS_RANGE: u64 = 100_000;
+
+/// Reconstructs the rewards of the stake of `pk` per epoch, from the events
+/// of the stake contract in the finalized blocks of the epochs in range.
+///
+/// The epochs are the ones of the stake configuration of the node, and the
+/// range is capped at the tip of its chain.
+pub(crate) async fn reward_history(
+    settings: &Settings,
+    pk: &BlsPublicKey,
+    from_epoch: u64,
+    to_epoch: Option<u64>,
+) -> anyhow::Result<Vec<EpochRewards>> {
+    let gql =
+        GraphQL::new(settings.state.to_string(), io::status::interactive)?;
+
+    let epoch = gql.stake_epoch().await?;
+    if epoch == 0 {
+        anyhow::bail!("Invalid epoch in the stake configuration");
+    }
+    let tip = gql.tip_height().await?;
+    let Some((from_height, to_height)) =
+        height_range(from_epoch, to_epoch, epoch, tip)
+    else {
+        return Ok(vec![]);
+    };
+
+    let contract = hex::encode(STAKE_CONTRACT);
+    let mut events = vec![];
+    let mut from = from_height;
+    loop {
+        let to = to_height.min(from.saturating_add(MAX_EVENTS_RANGE - 1));
+        events.extend(gql.finalized_events(&contract, from, to).await?);
+        if to == to_height {
+            break;
+        }
+        from = to + 1;
+    }
+
+    epoch_rewards(events, pk, epoch)
+}
+
+/// Returns the range of heights, both included, of the epochs in range,
+/// capped at the `tip` of the chain, or `None` if none of them started.
+fn height_range(
+    from_epoch: u64,
+    to_epoch: Option<u64>,
+    epoch: u64,
+    tip: u64,
+) -> Option<(u64, u64)> {
+    let from_height = from_epoch.saturating_mul(epoch);
+    let to_height = match to_epoch {
+        Some(to_epoch) => to_epoch.saturating_add(1).saturating_mul(epoch) - 1,
+        None => tip,
+    };
+    let to_height = to_height.min(tip);
+
+    (from_height <= to_height).then_some((from_height, to_height))
+}
+
+/// Aggregates the rewards of the stake of `pk` per epoch from the events of
+/// the stake contract.
+fn epoch_rewards(
+    events: Vec<BlockEvent>,
+    pk: &BlsPublicKey,
+    epoch: u64,
+) -> anyhow::Result<Vec<EpochRewards>> {
+    let mut epochs = BTreeMap::<u64, EpochRewards>::new();
+    for event in events {
+        let epoch = event.block_height / epoch;
+        let data = &event.data;
+
+        let (earned, withdrawn, slashed) = match event.topic.as_str() {
+            "reward" => {
+                let rewards: Vec<Reward> = rkyv::from_bytes(data)
+                    .map_err(|_| anyhow::anyhow!("Invalid reward event"))?;
+                let earned = rewards
+                    .iter()
+                    .filter(|r| &r.account == pk)
+                    .map(|r| r.value)
+                    .sum();
+                (earned, 0, 0)
+            }
+            "withdraw" => {
+                let withdraw: StakeEvent = rkyv::from_bytes(data)
+                    .map_err(|_| anyhow::anyhow!("Invalid withdraw event"))?;
+                if &withdraw.keys.account != pk {
+                    continue;
+                }
+                (0, withdraw.value, 0)
+            }
+            "slash" | "hard_slash" => {
+                let slash: SlashEvent = rkyv::from_bytes(data)
+                    .map_err(|_| anyhow::anyhow!("Invalid slash event"))?;
+                if &slash.account != pk {
+                    continue;
+                }
+                (0, 0, slash.value)
+            }
+            _ => continue,
+        };
+
+        if earned == 0 && withdrawn == 0 && slashed == 0 {
+            continue;
+        }
+
+        let rewards = epochs.entry(epoch).or_insert_with(|| EpochRewards {
+            epoch,
+            ..Default::default()
+        });
+        rewards.earned += earned;
+        rewards.withdrawn += withdrawn;
+        rewards.slashed += slashed;
+    }
+
+    Ok(epochs.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use dusk_core::signatures::bls::SecretKey as BlsSecretKey;
+    use dusk_core::stake::RewardReason;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn event<T>(block_height: u64, topic: &str, data: &T) -> BlockEvent
+    where
+        T: rkyv::Serialize<rkyv::ser::serializers::AllocSerializer<256>>,
+    {
+        BlockEvent {
+            block_height,
+            topic: topic.into(),
+            data: rkyv::to_bytes::<_, 256>(data).unwrap().to_vec(),
+        }
+    }
+
+    #[test]
+    fn height_range_is_capped_at_tip() {
+        assert_eq!(height_range(2, Some(3), 10, 100), Some((20, 39)));
+        assert_eq!(height_range(2, Some(3), 10, 25), Some((20, 25)));
+        assert_eq!(height_range(2, None, 10, 25), Some((20, 25)));
+        assert_eq!(height_range(2, Some(u64::MAX), 10, 25), Some((20, 25)));
+        assert_eq!(height_range(3, None, 10, 25), None);
+    }
+
+    #[test]
+    fn rewards_are_aggregated_per_epoch() {
+        let mut rng = StdRng::seed_from_u64(0xbeef);
+        let pk = BlsPublicKey::from(&BlsSecretKey::random(&mut rng));
+        let other = BlsPublicKey::from(&BlsSecretKey::random(&mut rng));
+
+        let reward = |account, value| Reward {
+            account,
+            value,
+            reason: RewardReason::GeneratorExtra,
+        };
+        let slash = |account, value| SlashEvent {
+            account,
+            value,
+            next_eligibility: 0,
+        };
+
+        let events = vec![
+            event(5, "reward", &vec![reward(pk, 10), reward(other, 7)]),
+            event(9, "reward", &vec![reward(pk, 5)]),
+            event(12, "slash", &slash(pk, 3)),
+            event(13, "hard_slash", &slash(other, 4)),
+            event(14, "stake", &slash(pk, 100)),
+        ];
+
+        let rewards = epoch_rewards(events, &pk, 10).unwrap();
+        let rewards: Vec<_> = rewards
+            .iter()
+            .map(|r| (r.epoch, r.earned, r.withdrawn, r.slashed))
+            .collect();
+        assert_eq!(rewards, vec![(0, 15, 0, 0), (1, 0, 0, 3)]);
+    }
+}
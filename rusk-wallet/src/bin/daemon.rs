@@ -0,0 +1,457 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Daemon mode of the wallet, serving a JSON-RPC 2.0 API on a loopback
+//! address, so that third parties can integrate with the wallet without
+//! linking its crates.
+//!
+//! Every request is a `POST` carrying the daemon token in the
+//! `Authorization: Bearer <token>` header. The connections are served
+//! concurrently, while the commands operating on the wallet run one at a
+//! time.
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use futures::stream::{FuturesUnordered, StreamExt};
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use rusk_wallet::currency::Lux;
+use rusk_wallet::gas::{DEFAULT_LIMIT_TRANSFER, DEFAULT_PRICE};
+use rusk_wallet::{Wallet, MAX_PROFILES};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::settings::Settings;
+use crate::{Command, WalletFile};
+
+/// Largest body accepted for a request
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+/// Longest time spent serving a request, including the proving of the
+/// transactions
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Environment variable holding the daemon token, unless it is read from a
+/// file
+pub(crate) const TOKEN_ENV: &str = "RUSK_WALLET_DAEMON_TOKEN";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const WALLET_ERROR: i64 = -32000;
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new<M: ToString>(code: i64, message: M) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct BalanceParams {
+    address: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SendParams {
+    sender: Option<String>,
+    receiver: String,
+    /// Amount in DUSK
+    amount: String,
+    gas_limit: Option<u64>,
+    /// Gas price in LUX
+    gas_price: Option<Lux>,
+    memo: Option<String>,
+    expiry_height: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct HistoryParams {
+    profile_idx: Option<u8>,
+}
+
+struct Daemon {
+    wallet: Mutex<Wallet<WalletFile>>,
+    settings: Settings,
+    token: String,
+}
+
+/// Returns the daemon token, read from `file` if given, or from the
+/// [`TOKEN_ENV`] environment variable otherwise, so that it never shows in
+/// the command line of the process.
+pub(crate) fn read_token(file: Option<&Path>) -> anyhow::Result<String> {
+    let token = match file {
+        Some(file) => std::fs::read_to_string(file)?,
+        None => std::env::var(TOKEN_ENV).map_err(|_| {
+            anyhow::anyhow!(
+                "Set the daemon token in {TOKEN_ENV}, or in a file given with --token-file"
+            )
+        })?,
+    };
+    Ok(token.trim().to_string())
+}
+
+/// Serves the JSON-RPC API on `listen` until interrupted, authorizing the
+/// requests carrying `token`.
+pub(crate) async fn run(
+    wallet: Wallet<WalletFile>,
+    settings: Settings,
+    listen: SocketAddr,
+    token: String,
+) -> anyhow::Result<()> {
+    if !listen.ip().is_loopback() {
+        anyhow::bail!("The daemon can only listen on a loopback address");
+    }
+    if token.is_empty() {
+        anyhow::bail!("The daemon token cannot be empty");
+    }
+
+    let listener = TcpListener::bind(listen).await?;
+    info!("Wallet daemon listening on {listen}");
+
+    let daemon = Daemon {
+        wallet: Mutex::new(wallet),
+        settings,
+        token,
+    };
+
+    let shutdown = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    serve(listener, shutdown, |req| daemon.handle(req)).await;
+
+    info!("Wallet daemon stopped");
+    daemon.wallet.into_inner().close();
+
+    Ok(())
+}
+
+/// Serves the connections of `listener` concurrently with `handler`, until
+/// `shutdown` completes. The requests in flight are completed before
+/// returning.
+async fn serve<H, F>(
+    listener: TcpListener,
+    shutdown: impl Future<Output = ()>,
+    handler: H,
+) where
+    H: Fn(Request<Incoming>) -> F,
+    F: Future<Output = Result<Response<Full<Bytes>>, Infallible>>,
+{
+    tokio::pin!(shutdown);
+    let mut connections = FuturesUnordered::new();
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            Some(()) = connections.next(), if !connections.is_empty() => {}
+            conn = listener.accept() => match conn {
+                Ok((stream, _)) => {
+                    connections.push(serve_connection(stream, &handler))
+                }
+                Err(e) => warn!("Unable to accept connection: {e}"),
+            },
+        }
+    }
+
+    while connections.next().await.is_some() {}
+}
+
+async fn serve_connection<H, F>(stream: TcpStream, handler: &H)
+where
+    H: Fn(Request<Incoming>) -> F,
+    F: Future<Output = Result<Response<Full<Bytes>>, Infallible>>,
+{
+    let service = service_fn(handler);
+    let conn = http1::Builder::new()
+        .keep_alive(false)
+        .serve_connection(TokioIo::new(stream), service);
+
+    match tokio::time::timeout(REQUEST_TIMEOUT, conn).await {
+        Ok(Err(e)) => warn!("Error serving connection: {e}"),
+        Err(_) => warn!("Request timed out"),
+        Ok(Ok(())) => {}
+    }
+}
+
+impl Daemon {
+    async fn handle(
+        &self,
+        req: Request<Incoming>,
+    ) -> Result<Response<Full<Bytes>>, Infallible> {
+        if req.method() != Method::POST {
+            return Ok(status_response(StatusCode::METHOD_NOT_ALLOWED));
+        }
+        if !self.is_authorized(&req) {
+            return Ok(status_response(StatusCode::UNAUTHORIZED));
+        }
+
+        let body = match Limited::new(req.into_body(), MAX_BODY_SIZE)
+            .collect()
+            .await
+        {
+            Ok(body) => body.to_bytes(),
+            Err(_) => {
+                return Ok(status_response(StatusCode::PAYLOAD_TOO_LARGE))
+            }
+        };
+
+        let response = match serde_json::from_slice::<RpcRequest>(&body) {
+            Err(e) => {
+                rpc_response(Value::Null, Err(RpcError::new(PARSE_ERROR, e)))
+            }
+            Ok(request) if request.jsonrpc != "2.0" => rpc_response(
+                request.id,
+                Err(RpcError::new(INVALID_REQUEST, "Unsupported version")),
+            ),
+            Ok(request) => {
+                let result = self.call(&request.method, request.params).await;
+                rpc_response(request.id, result)
+            }
+        };
+
+        let response = Response::builder()
+            .header(CONTENT_TYPE, "application/json")
+            .body(Full::new(Bytes::from(response.to_string())))
+            .expect("response to be valid");
+        Ok(response)
+    }
+
+    fn is_authorized(&self, req: &Request<Incoming>) -> bool {
+        let header = req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok());
+        is_bearer(header, &self.token)
+    }
+
+    async fn call(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, RpcError> {
+        let mut wallet = self.wallet.lock().await;
+
+        let cmd = match method {
+            "balance" => {
+                let params: BalanceParams = parse_params(params)?;
+                Command::Balance {
+                    address: params.address.map(parse_field).transpose()?,
+                    spendable: false,
+                }
+            }
+            "send" => {
+                let params: SendParams = parse_params(params)?;
+                Command::Transfer {
+                    sender: params.sender.map(parse_field).transpose()?,
+                    rcvr: parse_field(params.receiver)?,
+                    amt: parse_field(params.amount)?,
+                    gas_limit: params
+                        .gas_limit
+                        .unwrap_or(DEFAULT_LIMIT_TRANSFER),
+                    gas_price: params.gas_price.unwrap_or(DEFAULT_PRICE),
+                    memo: params.memo,
+                    expiry_height: params.expiry_height,
+                }
+            }
+            "history" => {
+                let params: HistoryParams = parse_params(params)?;
+                Command::History {
+                    profile_idx: params.profile_idx,
+                }
+            }
+            "new_address" => {
+                if wallet.profiles().len() >= MAX_PROFILES {
+                    return Err(RpcError::new(
+                        WALLET_ERROR,
+                        format!(
+                            "Cannot create more than {MAX_PROFILES} profiles"
+                        ),
+                    ));
+                }
+                let idx = wallet.add_profile();
+                wallet.save().map_err(wallet_error)?;
+
+                let shielded =
+                    wallet.shielded_account(idx).map_err(wallet_error)?;
+                let public =
+                    wallet.public_address(idx).map_err(wallet_error)?;
+                return Ok(json!({
+                    "profile_idx": idx,
+                    "shielded": shielded.to_string(),
+                    "public": public.to_string(),
+                }));
+            }
+            "sync_status" => {
+                let online = wallet.is_online().await;
+                let synced = match online {
+                    true => wallet.is_synced().await.ok(),
+                    false => None,
+                };
+                return Ok(json!({ "online": online, "synced": synced }));
+            }
+            _ => {
+                return Err(RpcError::new(
+                    METHOD_NOT_FOUND,
+                    format!("Unknown method {method}"),
+                ))
+            }
+        };
+
+        let result = cmd.run(&mut wallet, &self.settings).await;
+        Ok(result.map_err(wallet_error)?.to_json())
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    // Omitted params are the same as no param set
+    let params = match params {
+        Value::Null => json!({}),
+        params => params,
+    };
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e))
+}
+
+fn parse_field<T>(value: String) -> Result<T, RpcError>
+where
+    T: FromStr,
+    T::Err: ToString,
+{
+    value
+        .parse()
+        .map_err(|e: T::Err| RpcError::new(INVALID_PARAMS, e))
+}
+
+fn wallet_error<E: ToString>(e: E) -> RpcError {
+    RpcError::new(WALLET_ERROR, e)
+}
+
+fn rpc_response(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": e.code, "message": e.message },
+        }),
+    }
+}
+
+/// Returns whether the `Authorization` header carries `token`.
+fn is_bearer(header: Option<&str>, token: &str) -> bool {
+    let Some(received) = header.and_then(|v| v.strip_prefix("Bearer ")) else {
+        return false;
+    };
+
+    // Compare in constant time to avoid leaking the token
+    received.len() == token.len()
+        && received
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+fn status_response(status: StatusCode) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .body(Full::default())
+        .expect("response to be valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    #[test]
+    fn bearer_token() {
+        assert!(is_bearer(Some("Bearer secret"), "secret"));
+        assert!(!is_bearer(Some("Bearer secreT"), "secret"));
+        assert!(!is_bearer(Some("Bearer secret2"), "secret"));
+        assert!(!is_bearer(Some("secret"), "secret"));
+        assert!(!is_bearer(None, "secret"));
+    }
+
+    #[test]
+    fn token_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("token");
+        std::fs::write(&file, "secret\n").unwrap();
+
+        assert_eq!(read_token(Some(&file)).unwrap(), "secret");
+        assert!(read_token(Some(&dir.path().join("missing"))).is_err());
+    }
+
+    #[tokio::test]
+    async fn stalled_client_blocks_no_one() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve(
+            listener,
+            async {
+                let _ = stopped.await;
+            },
+            |_| async { Ok(status_response(StatusCode::OK)) },
+        ));
+
+        // A client connecting without ever sending its request
+        let stalled = TcpStream::connect(addr).await.unwrap();
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client
+            .write_all(
+                b"POST / HTTP/1.1\r\nHost: x\r\nContent-Length: 0\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        let mut response = vec![];
+        let read = client.read_to_end(&mut response);
+        tokio::time::timeout(Duration::from_secs(5), read)
+            .await
+            .expect("request to be served")
+            .unwrap();
+        assert!(response.starts_with(b"HTTP/1.1 200"));
+
+        drop(stalled);
+        stop.send(()).unwrap();
+        server.await.unwrap();
+    }
+}
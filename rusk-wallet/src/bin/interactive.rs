@@ -355,6 +355,7 @@ fn confirm(cmd: &Command, wallet: &Wallet<WalletFile>) -> anyhow::Result<bool> {
             gas_limit,
             gas_price,
             memo,
+            expiry_height,
         } => {
             let sender = sender.as_ref().ok_or(Error::BadAddress)?;
             sender.same_transaction_model(rcvr)?;
@@ -365,6 +366,9 @@ fn confirm(cmd: &Command, wallet: &Wallet<WalletFile>) -> anyhow::Result<bool> {
             if let Some(memo) = memo {
                 println!("   > Memo = {memo}");
             }
+            if let Some(height) = expiry_height {
+                println!("   > Expires after block {height}");
+            }
             println!("   > Max fee = {} DUSK", Dusk::from(max_fee));
             if let Address::Public(_) = sender {
                 println!("   > ALERT: THIS IS A PUBLIC TRANSACTION");
@@ -394,6 +398,7 @@ fn confirm(cmd: &Command, wallet: &Wallet<WalletFile>) -> anyhow::Result<bool> {
         }
         Command::Unstake {
             address,
+            amt,
             gas_limit,
             gas_price,
         } => {
@@ -404,6 +409,10 @@ fn confirm(cmd: &Command, wallet: &Wallet<WalletFile>) -> anyhow::Result<bool> {
 
             println!("   > Pay with {}", sender.preview());
             println!("   > Unstake from {}", unstake_from.preview());
+            match amt {
+                Some(amt) => println!("   > Amount to unstake = {} DUSK", amt),
+                None => println!("   > Amount to unstake = whole stake"),
+            }
             println!("   > Receive stake at {}", sender.preview());
             println!("   > Max fee = {} DUSK", Dusk::from(max_fee));
             if let Address::Public(_) = sender {
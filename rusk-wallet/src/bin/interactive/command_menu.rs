@@ -140,6 +140,7 @@ pub(crate) async fn online(
                     DEFAULT_PRICE,
                     mempool_gas_prices,
                 )?,
+                expiry_height: None,
             }))
         }
         MenuItem::Stake => {
@@ -161,6 +162,7 @@ pub(crate) async fn online(
             }
 
             let mempool_gas_prices = wallet.get_mempool_gas_prices().await?;
+            let minimum_stake = wallet.minimum_stake().await?;
 
             let stake_idx = wallet
                 .find_index(&addr)
@@ -185,7 +187,7 @@ pub(crate) async fn online(
             ProfileOp::Run(Box::new(Command::Stake {
                 address: Some(addr),
                 owner: Some(owner),
-                amt: prompt::request_stake_token_amt(balance)?,
+                amt: prompt::request_stake_token_amt(balance, minimum_stake)?,
                 gas_limit: prompt::request_gas_limit(gas::DEFAULT_LIMIT_CALL)?,
                 gas_price: prompt::request_gas_price(
                     DEFAULT_PRICE,
@@ -211,10 +213,22 @@ pub(crate) async fn online(
                 return Ok(ProfileOp::Stay);
             }
 
+            let staked = wallet
+                .stake_info(profile_idx)
+                .await?
+                .and_then(|s| s.amount)
+                .map(|s| s.total_funds())
+                .unwrap_or_default();
+            if staked == 0 {
+                println!("No stake available for this profile");
+                return Ok(ProfileOp::Stay);
+            }
+
             let mempool_gas_prices = wallet.get_mempool_gas_prices().await?;
 
             ProfileOp::Run(Box::new(Command::Unstake {
                 address: Some(addr),
+                amt: Some(prompt::request_unstake_token_amt(staked.into())?),
                 gas_limit: prompt::request_gas_limit(gas::DEFAULT_LIMIT_CALL)?,
                 gas_price: prompt::request_gas_price(
                     DEFAULT_PRICE,
@@ -6,6 +6,7 @@
 
 mod args;
 
+pub(crate) mod output;
 pub(crate) mod prompt;
 pub(crate) mod status;
 
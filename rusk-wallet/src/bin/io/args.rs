@@ -26,6 +26,10 @@ pub(crate) struct WalletArgs {
     #[arg(short, long)]
     pub network: Option<String>,
 
+    /// Seed to use [default: the active one]
+    #[arg(long, env = "RUSK_WALLET_SEED")]
+    pub seed: Option<String>,
+
     /// Set the password for wallet's creation
     #[arg(long, env = "RUSK_WALLET_PWD")]
     pub password: Option<String>,
@@ -46,6 +50,11 @@ pub(crate) struct WalletArgs {
     #[arg(long, value_enum, default_value_t = LogFormat::Coloured)]
     pub log_type: LogFormat,
 
+    /// Print the result or the error of the command as JSON, failing
+    /// instead of prompting
+    #[arg(long, global = true)]
+    pub json: bool,
+
     /// Command
     #[command(subcommand)]
     pub command: Option<Command>,
@@ -0,0 +1,66 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Machine-readable output of the `--json` mode, printing a single JSON
+//! document on stdout per command.
+
+use rusk_wallet::Error;
+use serde_json::{json, Value};
+
+use super::prompt::PromptDisabled;
+
+/// Prints the result of a command
+pub(crate) fn result(value: &Value) {
+    println!("{value}");
+}
+
+/// Prints the error a command failed with
+pub(crate) fn error(err: &anyhow::Error) {
+    let error = json!({
+        "error": {
+            "code": error_code(err),
+            "message": err.to_string(),
+        }
+    });
+    println!("{error}");
+}
+
+fn error_code(err: &anyhow::Error) -> &'static str {
+    if err.is::<PromptDisabled>() {
+        "prompt_disabled"
+    } else if let Some(e) = err.downcast_ref::<Error>() {
+        e.code()
+    } else if err.is::<std::io::Error>() {
+        "io"
+    } else {
+        "internal"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_are_coded() {
+        let code = |err: anyhow::Error| error_code(&err);
+
+        assert_eq!(
+            code(PromptDisabled("a password").into()),
+            "prompt_disabled"
+        );
+        assert_eq!(code(Error::NotStaked.into()), "not_staked");
+        assert_eq!(
+            code(Error::ProfilesExhausted(2).into()),
+            "profiles_exhausted"
+        );
+        assert_eq!(
+            code(std::io::Error::from(std::io::ErrorKind::Other).into()),
+            "io"
+        );
+        assert_eq!(code(anyhow::anyhow!("unexpected")), "internal");
+    }
+}
@@ -7,6 +7,7 @@
 use std::fmt::Display;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::{io::stdout, println};
 
 use crossterm::{
@@ -16,7 +17,6 @@ use crossterm::{
 
 use anyhow::Result;
 use bip39::{ErrorKind, Language, Mnemonic};
-use dusk_core::stake::DEFAULT_MINIMUM_STAKE;
 
 use inquire::ui::RenderConfig;
 use inquire::validator::Validation;
@@ -32,6 +32,26 @@ use rusk_wallet::{
 };
 use sha2::{Digest, Sha256};
 
+/// Whether the prompts fail rather than wait for the user
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Error returned in place of a prompt, while the prompts are disabled
+#[derive(Debug, thiserror::Error)]
+#[error("Cannot ask for {0} in non-interactive mode")]
+pub(crate) struct PromptDisabled(pub &'static str);
+
+/// Makes the prompts fail rather than wait for the user, for scripts
+pub(crate) fn disable() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+fn ensure_enabled(what: &'static str) -> Result<(), PromptDisabled> {
+    match DISABLED.load(Ordering::Relaxed) {
+        true => Err(PromptDisabled(what)),
+        false => Ok(()),
+    }
+}
+
 pub(crate) fn ask_pwd(msg: &str) -> Result<String, InquireError> {
     let pwd = Password::new(msg)
         .with_display_toggle_enabled()
@@ -62,7 +82,10 @@ pub(crate) fn request_auth(
     let pwd = match password.as_ref() {
         Some(p) => p.to_string(),
 
-        None => ask_pwd(msg)?,
+        None => {
+            ensure_enabled("the wallet password")?;
+            ask_pwd(msg)?
+        }
     };
 
     Ok(hash(file_version, &pwd))
@@ -75,7 +98,10 @@ pub(crate) fn create_password(
 ) -> anyhow::Result<Vec<u8>> {
     let pwd = match password.as_ref() {
         Some(p) => p.to_string(),
-        None => create_new_password()?,
+        None => {
+            ensure_enabled("a new wallet password")?;
+            create_new_password()?
+        }
     };
 
     Ok(hash(file_version, &pwd))
@@ -86,6 +112,8 @@ pub(crate) fn confirm_mnemonic_phrase<S>(phrase: &S) -> anyhow::Result<()>
 where
     S: std::fmt::Display,
 {
+    ensure_enabled("the backup of the mnemonic phrase")?;
+
     // inform the user about the mnemonic phrase
     let msg = format!("The following phrase is essential for you to regain access to your wallet\nin case you lose access to this computer. Please print it or write it down and store it somewhere safe.\n> {} \nHave you backed up this phrase?", phrase);
 
@@ -105,6 +133,8 @@ where
 
 /// Request the user to input the mnemonic phrase
 pub(crate) fn request_mnemonic_phrase() -> anyhow::Result<String> {
+    ensure_enabled("the mnemonic phrase")?;
+
     // let the user input the mnemonic phrase
     let mut attempt = 1;
     loop {
@@ -180,6 +210,7 @@ pub(crate) fn ask_confirm() -> anyhow::Result<bool> {
 
 /// Asks the user for confirmation before deleting cache
 pub(crate) fn ask_confirm_erase_cache(msg: &str) -> anyhow::Result<bool> {
+    ensure_enabled("the deletion of the cache")?;
     Ok(Confirm::new(msg).prompt()?)
 }
 
@@ -206,14 +237,14 @@ fn request_token(
     action: &str,
     min: Dusk,
     balance: Dusk,
-    default: Option<f64>,
+    default: Option<Dusk>,
 ) -> Result<Dusk, Error> {
     // Checks if the value is larger than the given min and smaller than the
     // min of the balance and `MAX_CONVERTIBLE`.
-    let validator = move |value: &f64| {
+    let validator = move |value: &Dusk| {
         let max = std::cmp::min(balance, MAX_CONVERTIBLE);
 
-        match (min..=max).contains(&Dusk::try_from(*value)?) {
+        match (min..=max).contains(value) {
             true => Ok(Validation::Valid),
             false => Ok(Validation::Invalid(
                 format!("The amount has to be between {} and {}", min, max)
@@ -224,7 +255,7 @@ fn request_token(
 
     let msg = format!("Introduce dusk amount for {}:", action);
 
-    let amount_prompt: CustomType<f64> = CustomType {
+    let amount_prompt: CustomType<Dusk> = CustomType {
         message: &msg,
         starting_input: None,
         formatter: &|i| format!("{} DUSK", i),
@@ -235,14 +266,11 @@ fn request_token(
         error_message: "Please type a valid number.".into(),
         help_message: "The number should use a dot as the decimal separator."
             .into(),
-        parser: &|i| match i.parse::<f64>() {
-            Ok(val) => Ok(val),
-            Err(_) => Err(()),
-        },
+        parser: &|i| i.parse::<Dusk>().map_err(|_| ()),
         render_config: RenderConfig::default(),
     };
 
-    amount_prompt.prompt()?.try_into()
+    Ok(amount_prompt.prompt()?)
 }
 
 /// Request a positive amount of tokens
@@ -265,13 +293,21 @@ pub(crate) fn request_optional_token_amt(
     request_token(action, min, balance, None).map_err(Error::from)
 }
 
-/// Request amount of tokens that can't be lower than MINIMUM_STAKE
-pub(crate) fn request_stake_token_amt(balance: Dusk) -> Result<Dusk, Error> {
-    let min: Dusk = DEFAULT_MINIMUM_STAKE.into();
-
+/// Request amount of tokens that can't be lower than the minimum stake
+pub(crate) fn request_stake_token_amt(
+    balance: Dusk,
+    min: Dusk,
+) -> Result<Dusk, Error> {
     request_token("stake", min, balance, None).map_err(Error::from)
 }
 
+/// Request amount of tokens to unstake, defaulting to the whole stake
+pub(crate) fn request_unstake_token_amt(staked: Dusk) -> Result<Dusk, Error> {
+    let min = MIN_CONVERTIBLE;
+
+    request_token("unstake", min, staked, Some(staked)).map_err(Error::from)
+}
+
 /// Request gas limit
 pub(crate) fn request_gas_limit(default_gas_limit: u64) -> anyhow::Result<u64> {
     Ok(
@@ -437,3 +473,20 @@ pub(crate) fn hide_cursor() -> anyhow::Result<()> {
     stdout.execute(Hide)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prompts_fail_once_disabled() {
+        assert!(ensure_enabled("a password").is_ok());
+
+        disable();
+        let err = ensure_enabled("a password").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cannot ask for a password in non-interactive mode"
+        );
+    }
+}
@@ -6,8 +6,10 @@
 
 mod command;
 mod config;
+mod daemon;
 mod interactive;
 mod io;
+mod seeds;
 mod settings;
 
 pub(crate) use command::{Command, RunResult};
@@ -22,15 +24,17 @@ use rocksdb::ErrorKind;
 use rusk_wallet::currency::Dusk;
 use rusk_wallet::dat::{self, LATEST_VERSION};
 use rusk_wallet::{
-    Error, GraphQL, Profile, SecureWalletFile, Wallet, WalletPath, EPOCH,
+    Error, GraphQL, Profile, SecureWalletFile, Wallet, WalletPath,
 };
 use tracing::{error, info, warn, Level};
 
-use crate::command::TransactionHistory;
+use crate::command::{EpochRewards, TransactionHistory};
 use crate::settings::{LogFormat, Settings};
 
 use config::Config;
-use io::{prompt, status, WalletArgs};
+use io::prompt::PromptDisabled;
+use io::{output, prompt, status, WalletArgs};
+use seeds::{SeedCommand, Seeds};
 
 #[derive(Debug, Clone)]
 pub(crate) struct WalletFile {
@@ -50,7 +54,21 @@ impl SecureWalletFile for WalletFile {
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> anyhow::Result<()> {
-    if let Err(err) = exec().await {
+    // parse user args
+    let args = WalletArgs::parse();
+
+    // scripts get a JSON document on stdout, and a failure rather than a
+    // prompt waiting forever
+    let json = args.json;
+    if json {
+        prompt::disable();
+    }
+
+    if let Err(err) = exec(args).await {
+        if json {
+            output::error(&err);
+            std::process::exit(1);
+        }
         // display the error message (if any)
         match err.downcast_ref::<InquireError>() {
             Some(InquireError::OperationInterrupted) => {
@@ -123,11 +141,10 @@ where
     Ok(wallet)
 }
 
-async fn exec() -> anyhow::Result<()> {
-    // parse user args
-    let args = WalletArgs::parse();
+async fn exec(args: WalletArgs) -> anyhow::Result<()> {
     // get the subcommand, if it is `None` we run the wallet in interactive mode
     let cmd = args.command.clone();
+    let seed = args.seed.clone();
 
     // Get the initial settings from the args
     let settings_builder = Settings::args(args)?;
@@ -137,14 +154,10 @@ async fn exec() -> anyhow::Result<()> {
 
     fs::create_dir_all(wallet_dir.as_path())?;
 
-    // prepare wallet path
-    let mut wallet_path =
-        WalletPath::from(wallet_dir.as_path().join("wallet.dat"));
-
     // load configuration (or use default)
     let cfg = Config::load(&wallet_dir)?;
 
-    wallet_path.set_network_name(settings_builder.args.network.clone());
+    let network_name = settings_builder.args.network.clone();
 
     // Finally complete the settings by setting the network
     let settings = settings_builder
@@ -186,13 +199,59 @@ async fn exec() -> anyhow::Result<()> {
 
     let is_headless = cmd.is_some();
 
+    // the interactive mode is made of prompts only
+    if !is_headless && settings.json {
+        return Err(PromptDisabled("a command").into());
+    }
+
     let password = &settings.password;
 
     if let Some(Command::Settings) = cmd {
-        println!("{}", &settings);
+        match settings.json {
+            true => output::result(&settings.to_json()),
+            false => println!("{}", &settings),
+        }
         return Ok(());
     };
 
+    // open the seeds, migrating a wallet directory holding a single one
+    let seeds = Seeds::open(&wallet_dir)?;
+
+    // creating and importing a seed is the same as creating and restoring a
+    // wallet, in the directory of the new seed
+    let (seed, cmd) = match cmd {
+        Some(Command::Seed { cmd }) => match cmd {
+            SeedCommand::Create {
+                name,
+                skip_recovery,
+                seed_file,
+            } => {
+                seeds.check_new(&name)?;
+                let cmd = Command::Create {
+                    skip_recovery,
+                    seed_file,
+                };
+                (name, Some(cmd))
+            }
+            SeedCommand::Import { name, file } => {
+                seeds.check_new(&name)?;
+                (name, Some(Command::Restore { file }))
+            }
+            cmd => return cmd.run(&seeds, settings.json),
+        },
+        cmd => {
+            let seed = match seed {
+                Some(seed) => seed,
+                None => seeds.active()?,
+            };
+            (seed, cmd)
+        }
+    };
+
+    // prepare wallet path
+    let mut wallet_path = seeds.wallet_path(&seed)?;
+    wallet_path.set_network_name(network_name);
+
     let file_version = dat::read_file_version(&wallet_path);
 
     // get our wallet ready
@@ -321,6 +380,25 @@ async fn exec() -> anyhow::Result<()> {
             wallet.register_sync().await?;
             interactive::run_loop(&mut wallet, &settings).await?;
         }
+        // in daemon mode the wallet is served until interrupted, and closed
+        // afterwards
+        Some(Command::Daemon { listen, token_file }) => {
+            let token = daemon::read_token(token_file.as_deref())?;
+            wallet.register_sync().await?;
+            return daemon::run(wallet, settings, listen, token).await;
+        }
+        // in JSON mode the result is printed as a single document
+        Some(cmd) if settings.json => {
+            let result = cmd.run(&mut wallet, &settings).await?;
+            if let RunResult::Tx(hash) = &result {
+                // Wait for transaction confirmation from network
+                let tx_id = hex::encode(hash.to_bytes());
+                let gql =
+                    GraphQL::new(settings.state.as_str(), status::headless)?;
+                gql.wait_for(&tx_id).await?;
+            }
+            output::result(&result.to_json());
+        }
         // else we run the given command and print the result
         Some(cmd) => {
             match cmd.run(&mut wallet, &settings).await? {
@@ -361,7 +439,7 @@ async fn exec() -> anyhow::Result<()> {
 
                     println!("{tx_id}");
                 }
-                RunResult::StakeInfo(info, reward) => {
+                RunResult::StakeInfo(info, epoch, reward) => {
                     let rewards = Dusk::from(info.reward);
                     if reward {
                         println!("{rewards}");
@@ -370,7 +448,7 @@ async fn exec() -> anyhow::Result<()> {
                             let amount = Dusk::from(amt.value);
                             let locked = Dusk::from(amt.locked);
                             let eligibility = amt.eligibility;
-                            let epoch = amt.eligibility / EPOCH;
+                            let epoch = amt.eligibility / epoch;
 
                             println!("Eligible stake: {amount} DUSK");
                             println!(
@@ -398,10 +476,25 @@ async fn exec() -> anyhow::Result<()> {
                         println!("{th}");
                     }
                 }
+                RunResult::Rewards(rewards, csv) => {
+                    if csv {
+                        println!("{}", EpochRewards::csv_header());
+                        for epoch in rewards {
+                            println!("{}", epoch.to_csv());
+                        }
+                    } else {
+                        println!("{}", EpochRewards::header());
+                        for epoch in rewards {
+                            println!("{epoch}");
+                        }
+                    }
+                }
                 RunResult::ContractId(id) => {
                     println!("Contract ID: {:?}", id);
                 }
-                RunResult::Settings() => {}
+                RunResult::Settings()
+                | RunResult::Seed()
+                | RunResult::Daemon() => {}
                 RunResult::Create() | RunResult::Restore() => {}
             }
         }
@@ -0,0 +1,333 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Management of the seeds stored in a wallet directory.
+//!
+//! Every seed is stored in its own directory under `seeds/`, along with its
+//! caches, while the name of the seed in use is stored in `seeds/active`.
+//! Wallet directories holding a single `wallet.dat` at their root are
+//! migrated to a seed named `default`, which is assembled in `seeds/` and
+//! then renamed at once, so that an interrupted migration is resumed.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use clap::Subcommand;
+use rusk_wallet::WalletPath;
+use serde_json::json;
+use tracing::{info, warn};
+
+use crate::io::output;
+
+const SEEDS_DIR: &str = "seeds";
+const ACTIVE_FILE: &str = "active";
+const WALLET_FILE: &str = "wallet.dat";
+/// Directory the default seed is assembled in while migrating, which is not
+/// a valid seed name
+const MIGRATION_DIR: &str = ".migrating";
+const MAX_NAME_LEN: usize = 64;
+
+/// Name of the seed used if none was switched to
+pub(crate) const DEFAULT_SEED: &str = "default";
+
+/// Operations on the seeds of the wallet directory
+#[derive(PartialEq, Eq, Hash, Clone, Subcommand, Debug)]
+pub(crate) enum SeedCommand {
+    /// List the seeds, marking the active one
+    List,
+
+    /// Create a new seed
+    Create {
+        /// Name of the seed
+        name: String,
+
+        /// Skip wallet mnemonic phrase (useful for headless seed creation)
+        #[arg(long)]
+        skip_recovery: bool,
+
+        /// Save mnemonic phrase to file (useful for headless seed creation)
+        #[arg(long)]
+        seed_file: Option<PathBuf>,
+    },
+
+    /// Import a seed from its mnemonic phrase, or from a wallet file
+    Import {
+        /// Name of the seed
+        name: String,
+
+        /// Set the wallet .dat file to import from
+        #[arg(short, long)]
+        file: Option<WalletPath>,
+    },
+
+    /// Rename a seed
+    Rename {
+        /// Current name of the seed
+        from: String,
+
+        /// New name of the seed
+        to: String,
+    },
+
+    /// Switch the seed used by the other commands
+    Switch {
+        /// Name of the seed
+        name: String,
+    },
+}
+
+impl SeedCommand {
+    /// Runs the operations on the seeds which do not load a wallet
+    pub fn run(self, seeds: &Seeds, json: bool) -> anyhow::Result<()> {
+        match self {
+            SeedCommand::List if json => {
+                let active = seeds.active()?;
+                output::result(&json!({
+                    "active": active,
+                    "seeds": seeds.list()?,
+                }));
+            }
+            SeedCommand::List => {
+                let active = seeds.active()?;
+                for name in seeds.list()? {
+                    let marker = if name == active { "*" } else { " " };
+                    println!("{marker} {name}");
+                }
+            }
+            SeedCommand::Rename { from, to } => {
+                seeds.rename(&from, &to)?;
+                match json {
+                    true => output::result(&json!({})),
+                    false => println!("Seed {from} renamed to {to}"),
+                }
+            }
+            SeedCommand::Switch { name } => {
+                seeds.switch(&name)?;
+                match json {
+                    true => output::result(&json!({})),
+                    false => println!("Switched to seed {name}"),
+                }
+            }
+            SeedCommand::Create { .. } | SeedCommand::Import { .. } => {
+                unreachable!("seeds are created by loading a wallet")
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The seeds stored in a wallet directory.
+pub(crate) struct Seeds {
+    dir: PathBuf,
+}
+
+impl Seeds {
+    /// Opens the seeds of `wallet_dir`, migrating a single-seed layout if
+    /// found.
+    pub fn open(wallet_dir: &Path) -> anyhow::Result<Self> {
+        let seeds = Self {
+            dir: wallet_dir.join(SEEDS_DIR),
+        };
+        fs::create_dir_all(&seeds.dir)?;
+        seeds.migrate(wallet_dir)?;
+        Ok(seeds)
+    }
+
+    /// Moves the wallet file at the root of `wallet_dir`, together with its
+    /// caches, to the default seed.
+    fn migrate(&self, wallet_dir: &Path) -> anyhow::Result<()> {
+        let legacy = wallet_dir.join(WALLET_FILE);
+        let staging = self.dir.join(MIGRATION_DIR);
+        if !legacy.exists() && !staging.join(WALLET_FILE).exists() {
+            return Ok(());
+        }
+
+        if self.exists(DEFAULT_SEED) {
+            warn!(
+                "Cannot migrate {}, the {DEFAULT_SEED} seed already exists",
+                legacy.display()
+            );
+            return Ok(());
+        }
+
+        info!("Migrating {} to the {DEFAULT_SEED} seed", legacy.display());
+        fs::create_dir_all(&staging)?;
+
+        // The caches are named `cache`, or `cache_<network>`. They are moved
+        // before the wallet file, whose presence tells the migration is to
+        // be resumed
+        for entry in fs::read_dir(wallet_dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            if entry.file_type()?.is_dir()
+                && name.to_string_lossy().starts_with("cache")
+            {
+                fs::rename(entry.path(), staging.join(name))?;
+            }
+        }
+        if legacy.exists() {
+            fs::rename(&legacy, staging.join(WALLET_FILE))?;
+        }
+
+        fs::rename(&staging, self.dir.join(DEFAULT_SEED))?;
+
+        if self.active_name()?.is_none() {
+            self.switch(DEFAULT_SEED)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the names of the seeds, in alphabetical order
+    pub fn list(&self) -> anyhow::Result<Vec<String>> {
+        let mut names = vec![];
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.file_type()?.is_dir() && self.exists(&name) {
+                names.push(name);
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Returns the name of the seed in use
+    pub fn active(&self) -> anyhow::Result<String> {
+        let active = self.active_name()?;
+        let active = active.unwrap_or_else(|| DEFAULT_SEED.to_string());
+        check_name(&active)?;
+        Ok(active)
+    }
+
+    fn active_name(&self) -> io::Result<Option<String>> {
+        match fs::read_to_string(self.dir.join(ACTIVE_FILE)) {
+            Ok(name) => Ok(Some(name.trim().to_string())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns whether the seed `name` exists
+    pub fn exists(&self, name: &str) -> bool {
+        self.wallet_path(name)
+            .is_ok_and(|path| path.inner().exists())
+    }
+
+    /// Returns the path of the wallet file of the seed `name`, its caches
+    /// being stored in the same directory
+    pub fn wallet_path(&self, name: &str) -> anyhow::Result<WalletPath> {
+        check_name(name)?;
+        Ok(WalletPath::from(self.dir.join(name).join(WALLET_FILE)))
+    }
+
+    /// Checks that a new seed can be stored as `name`
+    pub fn check_new(&self, name: &str) -> anyhow::Result<()> {
+        check_name(name)?;
+        if self.exists(name) {
+            anyhow::bail!("Seed {name} already exists");
+        }
+        Ok(())
+    }
+
+    /// Sets the seed used by the other commands
+    pub fn switch(&self, name: &str) -> anyhow::Result<()> {
+        check_name(name)?;
+        if !self.exists(name) {
+            anyhow::bail!("Seed {name} not found");
+        }
+        fs::write(self.dir.join(ACTIVE_FILE), name)?;
+        Ok(())
+    }
+
+    /// Renames the seed `from` as `to`, along with its caches
+    pub fn rename(&self, from: &str, to: &str) -> anyhow::Result<()> {
+        check_name(from)?;
+        if !self.exists(from) {
+            anyhow::bail!("Seed {from} not found");
+        }
+        self.check_new(to)?;
+
+        let active = self.active()?;
+        fs::rename(self.dir.join(from), self.dir.join(to))?;
+        if active == from {
+            self.switch(to)?;
+        }
+        Ok(())
+    }
+}
+
+fn check_name(name: &str) -> anyhow::Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        anyhow::bail!(
+            "Invalid seed name {name:?}, only up to {MAX_NAME_LEN} \
+             alphanumeric characters, `-` and `_` are allowed"
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wallet_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(WALLET_FILE), b"wallet").unwrap();
+        fs::create_dir(dir.path().join("cache_testnet")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn names_cannot_escape_the_seeds() {
+        let dir = tempfile::tempdir().unwrap();
+        let seeds = Seeds::open(dir.path()).unwrap();
+
+        for name in ["..", "../other", "a/b", "", MIGRATION_DIR] {
+            assert!(seeds.wallet_path(name).is_err(), "{name:?}");
+            assert!(seeds.check_new(name).is_err(), "{name:?}");
+            assert!(seeds.switch(name).is_err(), "{name:?}");
+        }
+        assert!(seeds.wallet_path("trading-1").is_ok());
+    }
+
+    #[test]
+    fn single_seed_is_migrated() {
+        let dir = wallet_dir();
+        let seeds = Seeds::open(dir.path()).unwrap();
+
+        assert_eq!(seeds.list().unwrap(), vec![DEFAULT_SEED]);
+        assert_eq!(seeds.active().unwrap(), DEFAULT_SEED);
+        let seed_dir = dir.path().join(SEEDS_DIR).join(DEFAULT_SEED);
+        assert!(seed_dir.join("cache_testnet").is_dir());
+        assert!(!dir.path().join(WALLET_FILE).exists());
+        assert!(!dir.path().join(SEEDS_DIR).join(MIGRATION_DIR).exists());
+    }
+
+    #[test]
+    fn interrupted_migration_is_resumed() {
+        let dir = wallet_dir();
+
+        // The migration stopped once the wallet file was moved
+        let staging = dir.path().join(SEEDS_DIR).join(MIGRATION_DIR);
+        fs::create_dir_all(&staging).unwrap();
+        fs::rename(dir.path().join(WALLET_FILE), staging.join(WALLET_FILE))
+            .unwrap();
+
+        let seeds = Seeds::open(dir.path()).unwrap();
+        assert_eq!(seeds.list().unwrap(), vec![DEFAULT_SEED]);
+        let seed_dir = dir.path().join(SEEDS_DIR).join(DEFAULT_SEED);
+        assert_eq!(fs::read(seed_dir.join(WALLET_FILE)).unwrap(), b"wallet");
+        assert!(seed_dir.join("cache_testnet").is_dir());
+        assert!(!staging.exists());
+    }
+}
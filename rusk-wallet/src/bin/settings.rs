@@ -54,6 +54,9 @@ pub(crate) struct Settings {
 
     pub(crate) wallet_dir: PathBuf,
     pub(crate) password: Option<String>,
+
+    /// Whether the output is printed as JSON
+    pub(crate) json: bool,
 }
 
 pub(crate) struct SettingsBuilder {
@@ -105,6 +108,7 @@ impl SettingsBuilder {
             args.wallet_dir.as_ref().cloned().unwrap_or(self.wallet_dir);
 
         let password = args.password;
+        let json = args.json;
 
         let logging = Logging {
             level: args.log_level,
@@ -118,6 +122,7 @@ impl SettingsBuilder {
             logging,
             wallet_dir,
             password,
+            json,
         })
     }
 }
@@ -136,6 +141,19 @@ impl Settings {
         Ok(SettingsBuilder { wallet_dir, args })
     }
 
+    /// Returns the settings as JSON, without the password
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "wallet_dir": self.wallet_dir,
+            "password": self.password.is_some(),
+            "state": self.state.as_str(),
+            "prover": self.prover.as_str(),
+            "explorer": self.explorer.as_ref().map(Url::as_str),
+            "log_level": self.logging.level.to_string(),
+            "log_type": self.logging.format.to_string(),
+        })
+    }
+
     pub async fn check_state_con(&self) -> Result<(), Error> {
         RuesHttpClient::new(self.state.as_ref())?
             .check_connection()
@@ -9,7 +9,7 @@ use std::path::Path;
 
 use dusk_bytes::{DeserializableSlice, Serializable};
 use dusk_core::transfer::phoenix::NoteLeaf;
-use rocksdb::{DBWithThreadMode, MultiThreaded, Options};
+use rocksdb::{DBWithThreadMode, MultiThreaded, Options, WriteBatch};
 
 use super::*;
 use crate::clients::TREE_LEAF;
@@ -17,6 +17,47 @@ use crate::error::Error;
 
 type DB = DBWithThreadMode<MultiThreaded>;
 
+/// Prefix of the keys of the checkpoints, followed by their height
+const CHECKPOINT_PREFIX: &[u8] = b"checkpoint_";
+
+/// Number of checkpoints kept, the oldest being dropped first
+const MAX_CHECKPOINTS: usize = 64;
+
+/// A block the cache is in sync with, to detect chain reorganizations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Checkpoint {
+    pub height: u64,
+    pub hash: [u8; 32],
+    /// Position of the last note up to the block
+    pub last_pos: u64,
+}
+
+impl Checkpoint {
+    fn key(height: u64) -> Vec<u8> {
+        [CHECKPOINT_PREFIX, &height.to_be_bytes()].concat()
+    }
+
+    fn from_bytes(value: &[u8], height: u64) -> Result<Self, Error> {
+        if value.len() != 40 {
+            return Err(Error::CacheDatabaseCorrupted);
+        }
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&value[..32]);
+        let last_pos = u64::from_be_bytes(
+            value[32..].try_into().expect("slice to be 8 bytes"),
+        );
+        Ok(Self {
+            height,
+            hash,
+            last_pos,
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        [&self.hash[..], &self.last_pos.to_be_bytes()].concat()
+    }
+}
+
 /// A cache of notes received from Rusk.
 ///
 /// path is the path of the rocks db database
@@ -161,6 +202,121 @@ impl Cache {
         }
     }
 
+    /// Records a checkpoint, dropping the oldest ones beyond
+    /// [`MAX_CHECKPOINTS`].
+    pub(crate) fn insert_checkpoint(
+        &self,
+        checkpoint: &Checkpoint,
+    ) -> Result<(), Error> {
+        self.db
+            .put(Checkpoint::key(checkpoint.height), checkpoint.to_bytes())?;
+
+        for stale in self.checkpoints()?.iter().skip(MAX_CHECKPOINTS) {
+            self.db.delete(Checkpoint::key(stale.height))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the checkpoints, the most recent first.
+    pub(crate) fn checkpoints(&self) -> Result<Vec<Checkpoint>, Error> {
+        let mut checkpoints = vec![];
+
+        let iterator = self.db.iterator(rocksdb::IteratorMode::From(
+            CHECKPOINT_PREFIX,
+            rocksdb::Direction::Forward,
+        ));
+        for i in iterator {
+            let (key, value) = i?;
+            let Some(height) = key.strip_prefix(CHECKPOINT_PREFIX) else {
+                break;
+            };
+            let height = height
+                .try_into()
+                .map_err(|_| Error::CacheDatabaseCorrupted)?;
+            let height = u64::from_be_bytes(height);
+            checkpoints.push(Checkpoint::from_bytes(&value, height)?);
+        }
+
+        checkpoints.reverse();
+        Ok(checkpoints)
+    }
+
+    /// Rolls the notes of the `pks` back to `checkpoint`, so that the notes
+    /// after it are synced again, or clears them all if there is no
+    /// checkpoint to roll back to.
+    ///
+    /// The spent notes are moved back to the unspent ones, to be spent again
+    /// by the sync if their nullifiers are still on the chain.
+    ///
+    /// The roll-back is written at once, so that an interrupted one leaves
+    /// the cache as it was.
+    pub(crate) fn rollback(
+        &self,
+        checkpoint: Option<&Checkpoint>,
+        pks: &[PhoenixPublicKey],
+    ) -> Result<(), Error> {
+        let max_height = checkpoint.map(|c| c.height);
+        let keep = |leaf: &NoteLeaf| {
+            max_height.is_some_and(|height| leaf.block_height <= height)
+        };
+
+        let mut batch = WriteBatch::default();
+
+        for pk in pks {
+            let pk_bs58 = bs58::encode(pk.to_bytes()).into_string();
+            let spent_cf_name = format!("spent_{pk_bs58}");
+
+            let cf = self
+                .db
+                .cf_handle(&pk_bs58)
+                .ok_or(Error::CacheDatabaseCorrupted)?;
+            let spent_cf = self
+                .db
+                .cf_handle(&spent_cf_name)
+                .ok_or(Error::CacheDatabaseCorrupted)?;
+
+            for i in self.db.iterator_cf(&cf, rocksdb::IteratorMode::Start) {
+                let (key, data) = i?;
+                let leaf: NoteLeaf = rkyv::from_bytes(&data)
+                    .map_err(|_| Error::CacheDatabaseCorrupted)?;
+                if !keep(&leaf) {
+                    batch.delete_cf(&cf, key);
+                }
+            }
+
+            let iterator =
+                self.db.iterator_cf(&spent_cf, rocksdb::IteratorMode::Start);
+            for i in iterator {
+                let (key, data) = i?;
+                let leaf: NoteLeaf = rkyv::from_bytes(&data)
+                    .map_err(|_| Error::CacheDatabaseCorrupted)?;
+                if keep(&leaf) {
+                    batch.put_cf(&cf, &key, data);
+                }
+                batch.delete_cf(&spent_cf, key);
+            }
+        }
+
+        for stale in self.checkpoints()? {
+            if max_height.is_some_and(|height| stale.height <= height) {
+                break;
+            }
+            batch.delete(Checkpoint::key(stale.height));
+        }
+
+        match checkpoint {
+            Some(checkpoint) => {
+                batch.put(b"last_pos", checkpoint.last_pos.to_be_bytes())
+            }
+            None => batch.delete(b"last_pos"),
+        }
+
+        self.db.write(batch)?;
+
+        Ok(())
+    }
+
     /// Returns an iterator over all unspent notes nullifier for the given pk.
     pub(crate) fn unspent_notes_id(
         &self,
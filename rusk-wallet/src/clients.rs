@@ -4,6 +4,7 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+mod queue;
 mod sync;
 
 use std::path::Path;
@@ -11,26 +12,29 @@ use std::sync::{Arc, Mutex};
 
 use dusk_bytes::Serializable;
 use dusk_core::signatures::bls::PublicKey as BlsPublicKey;
-use dusk_core::stake::{StakeFundOwner, StakeKeys};
+use dusk_core::stake::{StakeFundOwner, StakeKeys, DEFAULT_MINIMUM_STAKE};
 use dusk_core::transfer::moonlight::AccountData;
 use dusk_core::transfer::phoenix::{Note, NoteLeaf, Prove};
 use dusk_core::transfer::Transaction;
 use dusk_core::Error as ExecutionCoreError;
 use flume::Receiver;
 use rues::RuesHttpClient;
+use tokio::sync::{Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard};
 use tokio::task::JoinHandle;
-use tokio::time::{sleep, Duration};
+use tokio::time::{timeout, Duration};
 use wallet_core::keys::{
     derive_phoenix_pk, derive_phoenix_sk, derive_phoenix_vk,
 };
 use wallet_core::pick_notes;
 use zeroize::Zeroize;
 
+use self::queue::{Pending, TxQueue};
 use self::sync::sync_db;
 use super::cache::Cache;
 use super::*;
+use crate::gql::{GraphQL, TxStatus};
 use crate::store::LocalStore;
-use crate::{Error, MAX_PROFILES};
+use crate::{Error, EPOCH, MAX_PROFILES};
 
 const TRANSFER_CONTRACT: &str =
     "0100000000000000000000000000000000000000000000000000000000000000";
@@ -38,8 +42,13 @@ const TRANSFER_CONTRACT: &str =
 const STAKE_CONTRACT: &str =
     "0200000000000000000000000000000000000000000000000000000000000000";
 
-// Sync every 3 seconds for now
-const SYNC_INTERVAL_SECONDS: u64 = 3;
+/// Interval between two syncs when the head of the chain is not changing,
+/// or cannot be followed
+const SYNC_INTERVAL_SECONDS: u64 = 30;
+
+/// Time after which a propagated transaction that is neither in the mempool
+/// nor in a block is considered dropped by the node
+const DROPPED_AFTER: Duration = Duration::from_secs(30);
 
 /// SIZE of the tree leaf
 pub const TREE_LEAF: usize = std::mem::size_of::<ArchivedNoteLeaf>();
@@ -67,12 +76,15 @@ pub struct State {
     store: LocalStore,
     pub sync_rx: Option<Receiver<String>>,
     sync_join_handle: Option<JoinHandle<()>>,
+    queue: TxQueue,
+    outbound: AsyncMutex<()>,
 }
 
 impl State {
     /// Creates a new state instance. Should only be called once.
     pub(crate) fn new(
         data_dir: &Path,
+        queue_file: &Path,
         status: fn(&str),
         client: RuesHttpClient,
         prover: RuesHttpClient,
@@ -99,6 +111,8 @@ impl State {
             status,
             client,
             sync_join_handle: None,
+            queue: TxQueue::open(queue_file)?,
+            outbound: AsyncMutex::new(()),
         })
     }
 
@@ -136,15 +150,21 @@ impl State {
         status("Starting Sync..");
 
         let handle = tokio::spawn(async move {
+            // Sync as soon as a block is accepted or reverted
+            let mut head = HeadWatcher::start(client.clone());
             loop {
                 let _ = sync_tx.send("Syncing..".to_string());
 
                 let _ = match sync_db(&client, &cache, &store, status).await {
-                    Ok(_) => sync_tx.send("Syncing Complete".to_string()),
+                    Ok(Some(height)) => sync_tx.send(format!(
+                        "Chain reorganized, cache rolled back to block {height}"
+                    )),
+                    Ok(None) => sync_tx.send("Syncing Complete".to_string()),
                     Err(e) => sync_tx.send(format!("Error during sync:.. {e}")),
                 };
 
-                sleep(Duration::from_secs(SYNC_INTERVAL_SECONDS)).await;
+                let interval = Duration::from_secs(SYNC_INTERVAL_SECONDS);
+                let _ = timeout(interval, head.changed()).await;
             }
         });
 
@@ -154,7 +174,9 @@ impl State {
     }
 
     pub async fn sync(&self) -> Result<(), Error> {
-        sync_db(&self.client, &self.cache(), &self.store, self.status).await
+        sync_db(&self.client, &self.cache(), &self.store, self.status)
+            .await
+            .map(|_| ())
     }
 
     /// Requests that a node prove the given shielded transaction.
@@ -182,11 +204,105 @@ impl State {
         Ok(tx)
     }
 
-    /// Propagate a transaction to a node.
+    /// Serializes the transactions sent by the wallet, returning a guard to
+    /// hold until the transaction is propagated.
+    ///
+    /// The transactions previously propagated are settled first: the ones
+    /// included in a block are removed from the queue, and the ones dropped
+    /// by the node are propagated again. A failure to settle them is
+    /// reported, but never holds back the transaction to send.
+    pub(crate) async fn outbound(&self) -> AsyncMutexGuard<'_, ()> {
+        let guard = self.outbound.lock().await;
+        if let Err(e) = self.settle_queue().await {
+            let status = self.status;
+            status(&format!("Cannot settle the pending transactions: {e}"));
+        }
+        guard
+    }
+
+    async fn settle_queue(&self) -> Result<(), Error> {
+        let pending = self.queue.pending();
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let gql = GraphQL::from_client(self.client.clone(), self.status);
+        let unspent = self.unspent_nullifiers()?;
+
+        for Pending { id, tx, age } in pending {
+            if gql.in_mempool(&id).await? {
+                continue;
+            }
+            match gql.tx_status(&id).await? {
+                TxStatus::NotFound if age < DROPPED_AFTER => {}
+                TxStatus::NotFound => self.resend(&id, &tx).await?,
+                // The notes are released once the cache knows them spent
+                _ if tx.nullifiers().iter().any(|n| unspent.contains(n)) => {}
+                _ => self.queue.remove(&id)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Propagates again, unchanged, a transaction dropped by the node.
+    ///
+    /// The transaction is never signed again: with a new nonce it could be
+    /// executed along with the original one. If the node rejects it, e.g.
+    /// because its nonce or notes were taken meanwhile, it is discarded.
+    async fn resend(&self, id: &str, tx: &Transaction) -> Result<(), Error> {
+        let status = self.status;
+        status(&format!(
+            "Transaction {id} dropped, propagating it again..."
+        ));
+
+        match self.send(tx).await {
+            Ok(()) => self.queue.touch(id),
+            Err(e) => {
+                status(&format!("Transaction {id} discarded: {e}"));
+                self.queue.remove(id)
+            }
+        }
+    }
+
+    /// Returns the nullifiers of the unspent notes of all the profiles.
+    fn unspent_nullifiers(&self) -> Result<Vec<BlsScalar>, Error> {
+        let cache = self.cache();
+        let mut nullifiers = vec![];
+        for index in 0..MAX_PROFILES as u8 {
+            let pk = derive_phoenix_pk(self.store.get_seed(), index);
+            nullifiers.extend(cache.unspent_notes_id(&pk)?);
+        }
+        Ok(nullifiers)
+    }
+
+    /// Returns the nonce of the next transaction of the account `pk`,
+    /// following the ones still pending.
+    pub(crate) async fn next_nonce(
+        &self,
+        pk: &BlsPublicKey,
+    ) -> Result<u64, Error> {
+        let nonce = self.fetch_account(pk).await?.nonce;
+        let pending = self.queue.last_nonce(pk).unwrap_or_default();
+        Ok(nonce.max(pending) + 1)
+    }
+
+    /// Propagate a transaction to a node, queueing it until it is settled.
     pub async fn propagate(
         &self,
         tx: Transaction,
     ) -> Result<Transaction, Error> {
+        self.send(&tx).await?;
+        // The transaction is sent, failing to queue it is only reported
+        if let Err(e) = self.queue.push(tx.clone()) {
+            let status = self.status;
+            status(&format!("Cannot queue the transaction: {e}"));
+        }
+
+        Ok(tx)
+    }
+
+    async fn send(&self, tx: &Transaction) -> Result<(), Error> {
         let status = self.status;
         let tx_bytes = tx.to_var_bytes();
 
@@ -204,7 +320,7 @@ impl State {
             .await?;
         status("Transaction propagated!");
 
-        Ok(tx)
+        Ok(())
     }
 
     /// Selects up to MAX_INPUT_NOTES unspent input notes from the cache. The
@@ -218,7 +334,9 @@ impl State {
         let mut sk = derive_phoenix_sk(self.store().get_seed(), index);
         let pk = derive_phoenix_pk(self.store().get_seed(), index);
 
-        // fetch the cached unspent notes
+        // fetch the cached unspent notes, skipping the ones spent by the
+        // pending transactions
+        let pending = self.queue.nullifiers();
         let cached_notes: Vec<_> = self
             .cache()
             .notes(&pk)?
@@ -227,6 +345,7 @@ impl State {
                 let nullifier = note_leaf.note.gen_nullifier(&sk);
                 (nullifier, note_leaf)
             })
+            .filter(|(nullifier, _)| !pending.contains(nullifier))
             .collect();
 
         // pick up to MAX_INPUT_NOTES input-notes that cover the tx-cost
@@ -350,10 +469,69 @@ impl State {
         Ok(stake_owner)
     }
 
+    /// Get the nonce of the last beneficiary registration of a stake.
+    pub(crate) async fn fetch_beneficiary_nonce(
+        &self,
+        pk: &BlsPublicKey,
+    ) -> Result<u64, Error> {
+        let status = self.status;
+        status("Fetching beneficiary nonce...");
+
+        // the target type of the deserialization has to match the return type
+        // of the contract-query
+        let nonce: u64 = rkyv::from_bytes(
+            &self
+                .client
+                .contract_query::<_, _, 1024>(
+                    STAKE_CONTRACT,
+                    "beneficiary_nonce",
+                    pk,
+                )
+                .await?,
+        )
+        .map_err(|_| Error::Rkyv)?;
+
+        Ok(nonce)
+    }
+
     pub(crate) fn store(&self) -> &LocalStore {
         &self.store
     }
 
+    /// Fetches the minimum amount of a new stake on the network.
+    ///
+    /// Nodes not exposing the parameters of the stake contract use the
+    /// default minimum.
+    pub(crate) async fn fetch_minimum_stake(&self) -> Result<u64, Error> {
+        let status = self.status;
+        status("Fetching minimum stake...");
+
+        let gql = GraphQL::from_client(self.client.clone(), status);
+        let minimum_stake = match gql.minimum_stake().await {
+            Ok(minimum_stake) => minimum_stake,
+            Err(e @ Error::Reqwest(_)) => return Err(e),
+            Err(_) => DEFAULT_MINIMUM_STAKE,
+        };
+
+        status("Minimum stake received!");
+
+        Ok(minimum_stake)
+    }
+
+    /// Fetches the number of blocks in an epoch of the stake operations on
+    /// the network.
+    ///
+    /// Nodes not exposing the parameters of the stake contract use the
+    /// default epoch.
+    pub(crate) async fn fetch_epoch(&self) -> Result<u64, Error> {
+        let gql = GraphQL::from_client(self.client.clone(), self.status);
+        match gql.stake_epoch().await {
+            Ok(epoch) => Ok(epoch),
+            Err(e @ Error::Reqwest(_)) => Err(e),
+            Err(_) => Ok(EPOCH),
+        }
+    }
+
     pub(crate) async fn fetch_chain_id(&self) -> Result<u8, Error> {
         let status = self.status;
         status("Fetching chain_id...");
@@ -0,0 +1,252 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Outbound queue of the transactions propagated by the wallet and not yet
+//! settled, so that the following ones neither spend the same notes nor
+//! reuse the same nonce.
+//!
+//! The queue is persisted in a file of the profile, so that the transactions
+//! still pending are settled across restarts of the wallet.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use dusk_bytes::Serializable;
+use dusk_core::signatures::bls::PublicKey as BlsPublicKey;
+use dusk_core::transfer::Transaction;
+use dusk_core::BlsScalar;
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+/// A transaction propagated by the wallet.
+#[derive(Debug, Clone)]
+pub(crate) struct Pending {
+    /// Hex encoded hash of the transaction
+    pub id: String,
+    pub tx: Transaction,
+    /// Time elapsed since the transaction was last propagated
+    pub age: Duration,
+}
+
+struct Entry {
+    id: String,
+    tx: Transaction,
+    propagated: SystemTime,
+}
+
+/// An [`Entry`] as stored in the queue file.
+#[derive(Serialize, Deserialize)]
+struct StoredEntry {
+    /// Hex encoded transaction
+    tx: String,
+    /// Unix time, in seconds, of the last propagation
+    propagated: u64,
+}
+
+impl From<&Entry> for StoredEntry {
+    fn from(entry: &Entry) -> Self {
+        let propagated = entry
+            .propagated
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Self {
+            tx: hex::encode(entry.tx.to_var_bytes()),
+            propagated,
+        }
+    }
+}
+
+impl TryFrom<StoredEntry> for Entry {
+    type Error = Error;
+
+    fn try_from(stored: StoredEntry) -> Result<Self, Error> {
+        let bytes = hex::decode(stored.tx)
+            .map_err(|_| Error::Bytes(dusk_bytes::Error::InvalidData))?;
+        let tx = Transaction::from_slice(&bytes)?;
+        Ok(Self {
+            id: hex::encode(tx.hash().to_bytes()),
+            tx,
+            propagated: UNIX_EPOCH + Duration::from_secs(stored.propagated),
+        })
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct TxQueue {
+    /// File the queue is persisted in, if any
+    path: Option<PathBuf>,
+    entries: Mutex<Vec<Entry>>,
+}
+
+impl TxQueue {
+    /// Opens the queue persisted in the file at `path`, creating it empty if
+    /// the file doesn't exist.
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        let entries = match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice::<Vec<StoredEntry>>(&bytes)?
+                .into_iter()
+                .map(Entry::try_from)
+                .collect::<Result<_, _>>()?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![],
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            entries: Mutex::new(entries),
+        })
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, Vec<Entry>> {
+        // The entries are never left half-updated, so a poisoned lock can be
+        // recovered
+        match self.entries.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Writes the entries to the queue file, replacing it atomically.
+    fn persist(&self, entries: &[Entry]) -> Result<(), Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let stored: Vec<StoredEntry> =
+            entries.iter().map(StoredEntry::from).collect();
+
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(&stored)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Adds a transaction just propagated to the queue.
+    pub fn push(&self, tx: Transaction) -> Result<(), Error> {
+        let id = hex::encode(tx.hash().to_bytes());
+        let mut entries = self.entries();
+        entries.push(Entry {
+            id,
+            tx,
+            propagated: SystemTime::now(),
+        });
+        self.persist(&entries)
+    }
+
+    /// Records that the transaction `id` was propagated again.
+    pub fn touch(&self, id: &str) -> Result<(), Error> {
+        let mut entries = self.entries();
+        if let Some(entry) = entries.iter_mut().find(|e| e.id == id) {
+            entry.propagated = SystemTime::now();
+        }
+        self.persist(&entries)
+    }
+
+    /// Removes the transaction `id`, releasing its notes and nonce.
+    pub fn remove(&self, id: &str) -> Result<(), Error> {
+        let mut entries = self.entries();
+        entries.retain(|e| e.id != id);
+        self.persist(&entries)
+    }
+
+    /// Returns the pending transactions, in the order they were propagated.
+    pub fn pending(&self) -> Vec<Pending> {
+        self.entries()
+            .iter()
+            .map(|e| Pending {
+                id: e.id.clone(),
+                tx: e.tx.clone(),
+                age: e.propagated.elapsed().unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// Returns the nullifiers of the notes spent by the pending
+    /// transactions.
+    pub fn nullifiers(&self) -> Vec<BlsScalar> {
+        self.entries()
+            .iter()
+            .flat_map(|e| e.tx.nullifiers().to_vec())
+            .collect()
+    }
+
+    /// Returns the highest nonce used by the pending transactions of the
+    /// account `pk`, if any.
+    pub fn last_nonce(&self, pk: &BlsPublicKey) -> Option<u64> {
+        self.entries()
+            .iter()
+            .filter_map(|e| match &e.tx {
+                Transaction::Moonlight(tx) if tx.sender() == pk => {
+                    Some(tx.nonce())
+                }
+                _ => None,
+            })
+            .max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dusk_core::signatures::bls::SecretKey as BlsSecretKey;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn transfer(sk: &BlsSecretKey, nonce: u64) -> Transaction {
+        Transaction::moonlight(sk, None, 1, 0, 1, 1, nonce, 0, None::<Vec<u8>>)
+            .expect("transaction to be built")
+    }
+
+    #[test]
+    fn nonces_are_tracked_per_account() {
+        let mut rng = StdRng::seed_from_u64(0xbeef);
+        let alice = BlsSecretKey::random(&mut rng);
+        let bob = BlsSecretKey::random(&mut rng);
+        let alice_pk = BlsPublicKey::from(&alice);
+        let bob_pk = BlsPublicKey::from(&bob);
+
+        let queue = TxQueue::default();
+        assert_eq!(queue.last_nonce(&alice_pk), None);
+
+        queue.push(transfer(&alice, 1)).unwrap();
+        queue.push(transfer(&alice, 2)).unwrap();
+        queue.push(transfer(&bob, 7)).unwrap();
+        assert_eq!(queue.last_nonce(&alice_pk), Some(2));
+        assert_eq!(queue.last_nonce(&bob_pk), Some(7));
+        assert!(queue.nullifiers().is_empty());
+
+        let last = queue.pending()[1].id.clone();
+        queue.remove(&last).unwrap();
+        assert_eq!(queue.last_nonce(&alice_pk), Some(1));
+        assert_eq!(queue.pending().len(), 2);
+    }
+
+    #[test]
+    fn queue_is_persisted() {
+        let mut rng = StdRng::seed_from_u64(0xbeef);
+        let alice = BlsSecretKey::random(&mut rng);
+        let alice_pk = BlsPublicKey::from(&alice);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("outbound.json");
+
+        let queue = TxQueue::open(&path).unwrap();
+        queue.push(transfer(&alice, 1)).unwrap();
+        queue.push(transfer(&alice, 2)).unwrap();
+        let first = queue.pending()[0].id.clone();
+        queue.remove(&first).unwrap();
+
+        let reopened = TxQueue::open(&path).unwrap();
+        let pending = reopened.pending();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, queue.pending()[0].id);
+        assert_eq!(reopened.last_nonce(&alice_pk), Some(2));
+    }
+}
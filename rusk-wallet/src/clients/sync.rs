@@ -6,17 +6,22 @@
 
 use futures::StreamExt;
 use rues::CONTRACTS_TARGET;
+use serde::Deserialize;
 
 use super::*;
+use crate::cache::Checkpoint;
 use crate::clients::{Cache, TRANSFER_CONTRACT};
+use crate::gql::GraphQLError;
 use crate::Error;
 
+/// Syncs the cache with the chain of the node, returning the height the cache
+/// was rolled back to if the chain reorganized since the last sync.
 pub(crate) async fn sync_db(
     client: &RuesHttpClient,
     cache: &Cache,
     store: &LocalStore,
     status: fn(&str),
-) -> Result<(), Error> {
+) -> Result<Option<u64>, Error> {
     let seed = store.get_seed();
 
     let keys: Vec<(PhoenixSecretKey, PhoenixViewKey, PhoenixPublicKey)> = (0
@@ -31,6 +36,11 @@ pub(crate) async fn sync_db(
         })
         .collect();
 
+    status("Checking for chain reorganizations...");
+
+    let pks: Vec<_> = keys.iter().map(|(_, _, pk)| *pk).collect();
+    let rolled_back = rollback_reorg(client, cache, &pks, status).await?;
+
     status("Getting cached note position...");
 
     let last_pos = cache.last_pos()?;
@@ -62,6 +72,7 @@ pub(crate) async fn sync_db(
     // spliting of chunks according to it's own buffer
     let mut buffer = vec![];
     let mut note_data = Vec::new();
+    let mut last_height = None;
 
     while let Some(http_chunk) = stream.next().await {
         buffer.extend_from_slice(&http_chunk?);
@@ -73,6 +84,7 @@ pub(crate) async fn sync_db(
                 rkyv::from_bytes(leaf_bytes).map_err(|_| Error::Rkyv)?;
 
             last_pos = std::cmp::max(last_pos, *note.pos());
+            last_height = std::cmp::max(last_height, Some(block_height));
 
             note_data.push((block_height, note));
         }
@@ -121,7 +133,120 @@ pub(crate) async fn sync_db(
     // to prevent false reporting of sync completion
     cache.insert_last_pos(last_pos)?;
 
-    Ok(())
+    // All the notes up to the last block streamed have been received, so the
+    // cache can be rolled back to it
+    if let Some(height) = last_height {
+        if let Some((_, hash)) = fetch_block(client, Some(height)).await? {
+            cache.insert_checkpoint(&Checkpoint {
+                height,
+                hash,
+                last_pos,
+            })?;
+        }
+    }
+
+    Ok(rolled_back)
+}
+
+/// Rolls the cache back to the most recent checkpoint still on the chain of
+/// the node, if the block of the last checkpoint is no longer there,
+/// returning the height rolled back to.
+///
+/// If none of the checkpoints is on the chain, the cache is cleared and
+/// synced from scratch. The checkpoints above the tip of the node are not
+/// checked, the node being behind rather than on another branch, and any
+/// error reaching the node fails the sync without touching the cache.
+async fn rollback_reorg(
+    client: &RuesHttpClient,
+    cache: &Cache,
+    pks: &[PhoenixPublicKey],
+    status: fn(&str),
+) -> Result<Option<u64>, Error> {
+    let Some((tip, _)) = fetch_block(client, None).await? else {
+        return Ok(None);
+    };
+    let checkpoints: Vec<_> = cache
+        .checkpoints()?
+        .into_iter()
+        .filter(|checkpoint| checkpoint.height <= tip)
+        .collect();
+
+    let Some(last) = checkpoints.first() else {
+        return Ok(None);
+    };
+    if is_on_chain(client, last).await? {
+        return Ok(None);
+    }
+
+    let mut fork = None;
+    for checkpoint in &checkpoints[1..] {
+        if is_on_chain(client, checkpoint).await? {
+            fork = Some(checkpoint);
+            break;
+        }
+    }
+
+    let height = fork.map(|c| c.height).unwrap_or_default();
+    status(&format!(
+        "Chain reorganized after block {height}, rolling back the cache..."
+    ));
+    cache.rollback(fork, pks)?;
+
+    Ok(Some(height))
+}
+
+/// Returns whether the block of `checkpoint` is on the chain of the node.
+async fn is_on_chain(
+    client: &RuesHttpClient,
+    checkpoint: &Checkpoint,
+) -> Result<bool, Error> {
+    let block = fetch_block(client, Some(checkpoint.height)).await?;
+    Ok(block.is_some_and(|(_, hash)| hash == checkpoint.hash))
+}
+
+#[derive(Deserialize)]
+struct BlockHeader {
+    height: u64,
+    hash: String,
+}
+
+#[derive(Deserialize)]
+struct HeaderBlock {
+    header: BlockHeader,
+}
+
+#[derive(Deserialize)]
+struct HeaderResponse {
+    block: Option<HeaderBlock>,
+}
+
+/// Returns the height and the hash of the block at `height` on the chain of
+/// the node, or of its tip if `None`, or `None` if there is no such block.
+///
+/// A response not listing the block is an error, not to be mistaken for a
+/// block missing from the chain.
+async fn fetch_block(
+    client: &RuesHttpClient,
+    height: Option<u64>,
+) -> Result<Option<(u64, [u8; 32])>, Error> {
+    // A negative height selects the tip
+    let height = height.map_or("-1".to_string(), |h| h.to_string());
+    let query = format!(
+        "query {{ block(height: {height}) {{ header {{ height hash }} }} }}"
+    );
+    let response = client
+        .call("graphql", None, "query", query.as_bytes())
+        .await?;
+    let response: HeaderResponse =
+        serde_json::from_slice(&response).map_err(GraphQLError::Generic)?;
+
+    let Some(block) = response.block else {
+        return Ok(None);
+    };
+    let mut hash = [0u8; 32];
+    hex::decode_to_slice(&block.header.hash, &mut hash)
+        .map_err(|_| GraphQLError::BlockInfo)?;
+    Ok(Some((block.header.height, hash)))
 }
 
 /// Asks the node to return the nullifiers that already exist from the given
@@ -9,10 +9,11 @@
 use core::cmp::Ordering;
 use std::fmt;
 use std::hash::{Hash, Hasher};
-use std::num::ParseFloatError;
 use std::ops::{Add, Deref, Div, Mul, Sub};
 use std::str::FromStr;
 
+use dusk_core::{parse_dusk, DuskDisplay, LUX_PER_DUSK};
+
 use super::*;
 
 /// The underlying unit of Dusk
@@ -26,7 +27,7 @@ impl Dusk {
     /// The smallest value that can be represented by Dusk currency
     pub const MIN: Dusk = Dusk(0);
     /// The largest value that can be represented by Dusk currency
-    pub const MAX: Dusk = Dusk(dusk(f64::MAX / dusk(1.0) as f64));
+    pub const MAX: Dusk = Dusk(Lux::MAX);
 
     /// Returns a new Dusk based on the [Lux] given
     pub const fn new(lux: Lux) -> Dusk {
@@ -68,37 +69,40 @@ impl Sub<Lux> for Dusk {
     }
 }
 
-/// Multiplication
+/// Multiplication, in exact arithmetic, panicking on overflow like the
+/// other operators
 impl Mul for Dusk {
     type Output = Self;
     fn mul(self, other: Self) -> Self {
-        let a = from_dusk(self.0);
-        let b = from_dusk(other.0);
-        Self(dusk(a * b))
+        let product = self.0 as u128 * other.0 as u128 / LUX_PER_DUSK as u128;
+        Self(
+            product
+                .try_into()
+                .expect("Dusk multiplication to not overflow"),
+        )
     }
 }
 
 impl Mul<Lux> for Dusk {
     type Output = Self;
     fn mul(self, other: Lux) -> Self {
-        let a = from_dusk(self.0);
-        let b = from_dusk(other);
-        Self(dusk(a * b))
+        self * Dusk(other)
     }
 }
 
-/// Division
+/// Division, in exact arithmetic, rounding down
 impl Div for Dusk {
     type Output = Self;
     fn div(self, other: Self) -> Self {
-        Self(dusk(self.0 as f64 / other.0 as f64))
+        let quotient = self.0 as u128 * LUX_PER_DUSK as u128 / other.0 as u128;
+        Self(quotient.try_into().expect("Dusk division to not overflow"))
     }
 }
 
 impl Div<Lux> for Dusk {
     type Output = Self;
     fn div(self, other: Lux) -> Self {
-        Self(dusk(self.0 as f64 / other as f64))
+        self / Dusk(other)
     }
 }
 
@@ -182,19 +186,19 @@ impl From<Lux> for Dusk {
     }
 }
 
-/// Strings are parsed as Dusk values (floats)
+/// Strings are parsed as exact decimal Dusk values
 impl FromStr for Dusk {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parse_result = f64::from_str(s).map_err(|e: ParseFloatError| {
+        let lux = parse_dusk(s).map_err(|e| {
             Error::Conversion(format!(
                 "Failed to parse Dusk from string: {}",
                 e
             ))
         })?;
 
-        Dusk::try_from(parse_result)
+        Ok(Self(lux))
     }
 }
 
@@ -210,8 +214,7 @@ impl Deref for Dusk {
 /// Let the user print stuff
 impl fmt::Display for Dusk {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let v: f64 = self.into();
-        f64::fmt(&v, f)
+        fmt::Display::fmt(&DuskDisplay::new(self.0), f)
     }
 }
 
@@ -301,6 +304,19 @@ mod tests {
     #[test]
     fn negative_dusk() {
         assert!(Dusk::try_from(-1.0).is_err());
+        assert!(Dusk::from_str("-1").is_err());
+    }
+
+    #[test]
+    fn exact_strings() {
+        // Not representable exactly as a float
+        let dusk = Dusk::from_str("0.3").unwrap();
+        assert_eq!(*dusk, 300_000_000);
+        assert_eq!(dusk.to_string(), "0.3");
+        assert_eq!(Dusk::MAX.to_string(), "18446744073.709551615");
+        assert_eq!(Dusk::from_str("18446744073.709551615").unwrap(), Dusk::MAX);
+        assert!(Dusk::from_str("18446744073.709551616").is_err());
+        assert!(Dusk::from_str("1.0000000001").is_err());
     }
 
     #[test]
@@ -73,6 +73,13 @@ pub enum Error {
     /// A stake does not exist for this key
     #[error("A stake does not exist for this key")]
     NotStaked,
+    /// The amount to unstake is higher than the staked amount
+    #[error("The amount to unstake is higher than the staked amount")]
+    NotEnoughStake,
+    /// A new stake, or the stake left by a partial unstake, would be lower
+    /// than the minimum stake of the network
+    #[error("The stake would be lower than the minimum stake")]
+    StakeBelowMinimum,
     /// No reward available for this key
     #[error("No reward available for this key")]
     NoReward,
@@ -82,6 +89,9 @@ pub enum Error {
     /// Address does not belong to this wallet
     #[error("Address does not belong to this wallet")]
     AddressNotOwned,
+    /// The wallet has as many profiles as it supports
+    #[error("Cannot create more profiles, this wallet only supports up to {0} profiles")]
+    ProfilesExhausted(usize),
     /// No menu item selected
     #[error("No menu item selected")]
     NoMenuItemSelected,
@@ -150,6 +160,9 @@ pub enum Error {
     /// Contract file location not found
     #[error("Invalid WASM contract path provided")]
     InvalidWasmContractPath,
+    /// Expiry height given for a shielded transaction
+    #[error("Only public transactions can have an expiry height")]
+    ShieldedExpiry,
     /// Invalid environment variable value
     #[error("Invalid environment variable value {0}")]
     InvalidEnvVar(String),
@@ -164,6 +177,66 @@ pub enum Error {
     InquireError(String),
 }
 
+impl Error {
+    /// Returns a stable identifier of the error, for scripts to match on.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Offline => "offline",
+            Self::Unauthorized => "unauthorized",
+            Self::Rusk(..) => "rusk",
+            Self::IO(..) => "io",
+            Self::Json(..) => "json",
+            Self::Bytes(..) => "bytes",
+            Self::Base58(..) => "base58",
+            Self::Rkyv => "rkyv",
+            Self::HttpClient => "http_client",
+            Self::Reqwest(..) => "reqwest",
+            Self::Utf8(..) => "utf8",
+            Self::Rng(..) => "rng",
+            Self::NotEnoughBalance => "not_enough_balance",
+            Self::AmountIsZero => "amount_is_zero",
+            Self::NoteCombinationProblem => "note_combination_problem",
+            Self::NoteNotFound => "note_not_found",
+            Self::WrongViewKey => "wrong_view_key",
+            Self::NotEnoughGas => "not_enough_gas",
+            Self::NotStaked => "not_staked",
+            Self::NotEnoughStake => "not_enough_stake",
+            Self::StakeBelowMinimum => "stake_below_minimum",
+            Self::NoReward => "no_reward",
+            Self::BadAddress => "bad_address",
+            Self::AddressNotOwned => "address_not_owned",
+            Self::ProfilesExhausted(..) => "profiles_exhausted",
+            Self::NoMenuItemSelected => "no_menu_item_selected",
+            Self::InvalidMnemonicPhrase => "invalid_mnemonic_phrase",
+            Self::NotDirectory => "not_directory",
+            Self::OsNotSupported => "os_not_supported",
+            Self::WalletFileCorrupted => "wallet_file_corrupted",
+            Self::UnknownFileVersion(..) => "unknown_file_version",
+            Self::WalletFileExists => "wallet_file_exists",
+            Self::WalletFileMissing => "wallet_file_missing",
+            Self::BlockMode(..) => "block_mode",
+            Self::AttemptsExhausted => "attempts_exhausted",
+            Self::StatusWalletConnected => "status_wallet_connected",
+            Self::Transaction(..) => "transaction",
+            Self::RocksDB(..) => "rocksdb",
+            Self::NetworkNotFound => "network_not_found",
+            Self::CacheDatabaseCorrupted => "cache_database_corrupted",
+            Self::ProverError(..) => "prover",
+            Self::MemoTooLarge(..) => "memo_too_large",
+            Self::ExpectedBlsPublicKey => "expected_bls_public_key",
+            Self::ExpectedPhoenixPublicKey => "expected_phoenix_public_key",
+            Self::DifferentTransactionModels => "different_transaction_models",
+            Self::InvalidContractId => "invalid_contract_id",
+            Self::InvalidWasmContractPath => "invalid_wasm_contract_path",
+            Self::ShieldedExpiry => "shielded_expiry",
+            Self::InvalidEnvVar(..) => "invalid_env_var",
+            Self::Conversion(..) => "conversion",
+            Self::GraphQLError(..) => "graph_ql",
+            Self::InquireError(..) => "inquire",
+        }
+    }
+}
+
 impl From<dusk_bytes::Error> for Error {
     fn from(e: dusk_bytes::Error) -> Self {
         Self::Bytes(e)
@@ -9,6 +9,7 @@
 //! graphql schema
 
 use dusk_core::transfer::Transaction;
+use serde::de::IgnoredAny;
 use serde::Deserialize;
 use tokio::time::{sleep, Duration};
 
@@ -42,7 +43,7 @@ struct SpentTx {
     pub raw: String,
     pub err: Option<String>,
     #[serde(alias = "gasSpent", default)]
-    pub gas_spent: f64,
+    pub gas_spent: u64,
 }
 
 #[derive(Deserialize)]
@@ -55,11 +56,84 @@ struct BlockResponse {
     pub block: Option<Block>,
 }
 
+#[derive(Deserialize)]
+struct TipHeader {
+    pub height: u64,
+}
+
+#[derive(Deserialize)]
+struct TipBlock {
+    pub header: TipHeader,
+}
+
+#[derive(Deserialize)]
+struct TipResponse {
+    pub block: Option<TipBlock>,
+}
+
 #[derive(Deserialize)]
 struct SpentTxResponse {
     pub tx: Option<SpentTx>,
 }
 
+/// An event emitted by a contract in a finalized block
+#[derive(Debug)]
+pub struct BlockEvent {
+    /// Height of the block the event was emitted in
+    pub block_height: u64,
+    /// Topic of the event
+    pub topic: String,
+    /// Serialized data of the event
+    pub data: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct ArchivedEvent {
+    pub block_height: u64,
+    pub topic: String,
+    pub data: String,
+}
+
+#[derive(Deserialize)]
+struct ContractEvents {
+    pub json: Vec<ArchivedEvent>,
+}
+
+#[derive(Deserialize)]
+struct EventsResponse {
+    #[serde(alias = "finalizedEventsInRange")]
+    pub events: Option<ContractEvents>,
+}
+
+#[derive(Deserialize)]
+struct MempoolTxResponse {
+    #[serde(alias = "mempoolTx")]
+    pub mempool_tx: Option<IgnoredAny>,
+}
+
+#[derive(Deserialize)]
+struct StakeConfig {
+    #[serde(alias = "minimumStake")]
+    pub minimum_stake: u64,
+}
+
+#[derive(Deserialize)]
+struct StakeConfigResponse {
+    #[serde(alias = "stakeConfig")]
+    pub stake_config: StakeConfig,
+}
+
+#[derive(Deserialize)]
+struct StakeEpoch {
+    pub epoch: u64,
+}
+
+#[derive(Deserialize)]
+struct StakeEpochResponse {
+    #[serde(alias = "stakeConfig")]
+    pub stake_config: StakeEpoch,
+}
+
 /// Transaction status
 #[derive(Debug)]
 pub enum TxStatus {
@@ -80,6 +154,14 @@ impl GraphQL {
         })
     }
 
+    /// Create a GraphQL client querying the node `client` is connected to
+    pub(crate) fn from_client(
+        client: RuesHttpClient,
+        status: fn(&str),
+    ) -> Self {
+        Self { client, status }
+    }
+
     /// Wait for a transaction to be confirmed (included in a block)
     pub async fn wait_for(&self, tx_id: &str) -> anyhow::Result<()> {
         loop {
@@ -100,7 +182,10 @@ impl GraphQL {
     }
 
     /// Obtain transaction status
-    async fn tx_status(&self, tx_id: &str) -> Result<TxStatus, Error> {
+    pub(crate) async fn tx_status(
+        &self,
+        tx_id: &str,
+    ) -> Result<TxStatus, Error> {
         let query =
             "query { tx(hash: \"####\") { id, err }}".replace("####", tx_id);
         let response = self.query(&query).await?;
@@ -113,6 +198,17 @@ impl GraphQL {
         }
     }
 
+    /// Checks whether a transaction is in the mempool of the node
+    pub(crate) async fn in_mempool(&self, tx_id: &str) -> Result<bool, Error> {
+        let query =
+            "query { mempoolTx(hash: \"####\") { id }}".replace("####", tx_id);
+        let response = self.query(&query).await?;
+        let response =
+            serde_json::from_slice::<MempoolTxResponse>(&response)?.mempool_tx;
+
+        Ok(response.is_some())
+    }
+
     /// Obtain transactions inside a block
     pub async fn txs_for_block(
         &self,
@@ -135,13 +231,73 @@ impl GraphQL {
             ret.push(BlockTransaction {
                 tx: ph_tx,
                 id: spent_tx.id,
-                gas_spent: spent_tx.gas_spent as u64,
+                gas_spent: spent_tx.gas_spent,
+            });
+        }
+
+        Ok(ret)
+    }
+
+    /// Obtain the events of a contract emitted by the finalized blocks in the
+    /// range of heights, both included
+    pub async fn finalized_events(
+        &self,
+        contract_id: &str,
+        from_height: u64,
+        to_height: u64,
+    ) -> Result<Vec<BlockEvent>, Error> {
+        let query = format!(
+            "query {{ finalizedEventsInRange(contractId: \"{contract_id}\", \
+             fromHeight: {from_height}, toHeight: {to_height}) {{ json }}}}"
+        );
+
+        let response = self.query(&query).await?;
+        let response = serde_json::from_slice::<EventsResponse>(&response)?;
+        let events = response.events.map(|e| e.json).unwrap_or_default();
+
+        let mut ret = Vec::with_capacity(events.len());
+        for event in events {
+            let data = hex::decode(&event.data)
+                .map_err(|_| GraphQLError::BytesError)?;
+            ret.push(BlockEvent {
+                block_height: event.block_height,
+                topic: event.topic,
+                data,
             });
         }
 
         Ok(ret)
     }
 
+    /// Obtain the height of the tip of the chain of the node
+    pub async fn tip_height(&self) -> Result<u64, Error> {
+        let query = "query { block(height: -1) { header { height }}}";
+        let response = self.query(query).await?;
+        let response = serde_json::from_slice::<TipResponse>(&response)?;
+        let block = response.block.ok_or(GraphQLError::BlockInfo)?;
+
+        Ok(block.header.height)
+    }
+
+    /// Obtain the minimum amount, in LUX, of a new stake
+    pub async fn minimum_stake(&self) -> Result<u64, Error> {
+        let query = "query { stakeConfig { minimumStake }}";
+        let response = self.query(query).await?;
+        let response =
+            serde_json::from_slice::<StakeConfigResponse>(&response)?;
+
+        Ok(response.stake_config.minimum_stake)
+    }
+
+    /// Obtain the number of blocks in an epoch of the stake operations
+    pub async fn stake_epoch(&self) -> Result<u64, Error> {
+        let query = "query { stakeConfig { epoch }}";
+        let response = self.query(query).await?;
+        let response = serde_json::from_slice::<StakeEpochResponse>(&response)?;
+
+        Ok(response.stake_config.epoch)
+    }
+
     /// Sends an empty body to url to check if its available
     pub async fn check_connection(&self) -> Result<(), Error> {
         self.query("").await.map(|_| ())
@@ -0,0 +1,190 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Watcher of the head of the chain of a node, following its `chain/feed`
+//! RUES stream rather than polling it.
+
+use std::time::Duration;
+
+use futures::StreamExt;
+use serde::Deserialize;
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use tracing::debug;
+use wallet_core::head::{BlockHash, FeedRecord, HeadTracker, INTERRUPTED};
+pub use wallet_core::head::{Head, HeadEvent};
+
+use crate::gql::GraphQLError;
+use crate::{Error, RuesHttpClient};
+
+/// Delay before following the chain again once the feed ended
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// Capacity of the channel of the head events
+const EVENTS_CAPACITY: usize = 64;
+
+/// Watches the head of the chain of a node: its tip, its last finalized
+/// block, and the reorganizations of the blocks not final yet, as tracked
+/// by a [`HeadTracker`] fed with the chain feed of the node.
+///
+/// The watcher reconnects by itself whenever the node becomes unreachable,
+/// and stops once dropped.
+pub struct HeadWatcher {
+    head: watch::Receiver<Option<Head>>,
+    events: broadcast::Sender<HeadEvent>,
+    handle: JoinHandle<()>,
+}
+
+impl HeadWatcher {
+    /// Starts watching the head of the chain of the node reachable through
+    /// `client`.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn start(client: RuesHttpClient) -> Self {
+        let (head_sender, head) = watch::channel(None);
+        let (events, _) = broadcast::channel(EVENTS_CAPACITY);
+
+        let handle = tokio::spawn(run(client, head_sender, events.clone()));
+
+        Self {
+            head,
+            events,
+            handle,
+        }
+    }
+
+    /// Returns the head of the chain, once known.
+    pub fn head(&self) -> Option<Head> {
+        self.head.borrow().clone()
+    }
+
+    /// Returns a receiver of the changes to the head of the chain.
+    pub fn subscribe(&self) -> broadcast::Receiver<HeadEvent> {
+        self.events.subscribe()
+    }
+
+    /// Waits for the head of the chain to change.
+    pub async fn changed(&mut self) {
+        // The sender lives as long as the watcher
+        let _ = self.head.changed().await;
+    }
+}
+
+impl Drop for HeadWatcher {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn run(
+    client: RuesHttpClient,
+    head: watch::Sender<Option<Head>>,
+    events: broadcast::Sender<HeadEvent>,
+) {
+    let mut tracker = None;
+    loop {
+        let result = match tracker.as_mut() {
+            Some(tracker) => follow(&client, tracker, &head, &events).await,
+            None => match fetch_head(&client).await {
+                Ok(fetched) => {
+                    head.send_replace(Some(fetched.clone()));
+                    tracker = Some(HeadTracker::new(fetched));
+                    continue;
+                }
+                Err(e) => Err(e),
+            },
+        };
+        if let Err(e) = result {
+            debug!("Chain head feed ended: {e}");
+        }
+        sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// Follows the chain feed of the node until it ends, from the height the
+/// tracker resumes at.
+async fn follow(
+    client: &RuesHttpClient,
+    tracker: &mut HeadTracker,
+    head: &watch::Sender<Option<Head>>,
+    events: &broadcast::Sender<HeadEvent>,
+) -> Result<(), Error> {
+    let from = tracker.resume_height();
+
+    let mut stream = client
+        .call_raw("chain", None, "feed", from.to_string().as_bytes(), false)
+        .await?
+        .bytes_stream();
+
+    let mut buffer = vec![];
+    while let Some(chunk) = stream.next().await {
+        buffer.extend_from_slice(&chunk?);
+
+        while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<_> = buffer.drain(..=end).collect();
+            let record: Record = serde_json::from_slice(&line)?;
+            if record.kind == INTERRUPTED {
+                return Ok(());
+            }
+            let hash = decode_hash(&record.hash).unwrap_or_default();
+            let Some(record) =
+                FeedRecord::new(&record.kind, record.height, hash)
+            else {
+                continue;
+            };
+
+            let changes = tracker.apply(record);
+            if !changes.is_empty() {
+                head.send_replace(Some(tracker.head().clone()));
+            }
+            for change in changes {
+                let _ = events.send(change);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the head of the chain, as reported by the node.
+async fn fetch_head(client: &RuesHttpClient) -> Result<Head, Error> {
+    let query = "query { lastBlockPair { json } }";
+    let response = client
+        .call("graphql", None, "query", query.as_bytes())
+        .await?;
+    let response: serde_json::Value = serde_json::from_slice(&response)?;
+
+    let pair = &response["lastBlockPair"]["json"];
+    let (height, hash): (u64, String) =
+        serde_json::from_value(pair["last_block"].clone())?;
+    let (finalized_height, finalized_hash): (u64, String) =
+        serde_json::from_value(pair["last_finalized_block"].clone())?;
+
+    Ok(Head {
+        height,
+        hash: decode_hash(&hash)?,
+        finalized_height,
+        finalized_hash: decode_hash(&finalized_hash)?,
+    })
+}
+
+fn decode_hash(hash: &str) -> Result<BlockHash, Error> {
+    let mut bytes = BlockHash::default();
+    hex::decode_to_slice(hash, &mut bytes)
+        .map_err(|_| GraphQLError::BlockInfo)?;
+    Ok(bytes)
+}
+
+/// A record of the chain feed.
+#[derive(Deserialize)]
+struct Record {
+    #[serde(rename = "type")]
+    kind: String,
+    height: u64,
+    #[serde(default)]
+    hash: String,
+}
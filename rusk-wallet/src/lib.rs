@@ -19,6 +19,7 @@ mod clients;
 mod crypto;
 mod error;
 mod gql;
+mod head;
 mod rues;
 mod store;
 mod wallet;
@@ -28,7 +29,8 @@ pub mod dat;
 pub mod gas;
 
 pub use error::Error;
-pub use gql::{BlockTransaction, GraphQL};
+pub use gql::{BlockEvent, BlockTransaction, GraphQL};
+pub use head::{Head, HeadEvent, HeadWatcher};
 pub use rues::RuesHttpClient;
 pub use wallet::{
     Address, DecodedNote, Profile, SecureWalletFile, Wallet, WalletPath,
@@ -244,10 +244,16 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
         }
 
         let cache_dir = self.cache_path()?;
+        let queue_file = self
+            .file
+            .as_ref()
+            .map(|file| file.path().outbound_file())
+            .ok_or(Error::WalletFileMissing)?;
 
         // create a state client
         self.state = Some(State::new(
             &cache_dir,
+            &queue_file,
             status,
             http_state,
             http_prover,
@@ -467,6 +473,17 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
             .await
     }
 
+    /// Returns the minimum amount of a new stake on the network
+    pub async fn minimum_stake(&self) -> Result<Dusk, Error> {
+        Ok(self.state()?.fetch_minimum_stake().await?.into())
+    }
+
+    /// Returns the number of blocks in an epoch of the stake operations on
+    /// the network
+    pub async fn epoch(&self) -> Result<u64, Error> {
+        self.state()?.fetch_epoch().await
+    }
+
     /// Returns BLS key-pair for provisioner nodes
     pub fn provisioner_keys(
         &self,
@@ -80,6 +80,19 @@ impl WalletPath {
 
         cache
     }
+
+    /// Returns the file of the outbound queue of the wallet for the network.
+    pub fn outbound_file(&self) -> PathBuf {
+        let mut file = self.profile_dir.clone();
+
+        if let Some(network) = &self.network {
+            file.push(format!("outbound_{network}.json"));
+        } else {
+            file.push("outbound.json");
+        }
+
+        file
+    }
 }
 
 impl FromStr for WalletPath {
@@ -7,14 +7,15 @@
 use std::fmt::Debug;
 
 use dusk_core::signatures::bls::PublicKey as BlsPublicKey;
-use dusk_core::stake::StakeFundOwner;
+use dusk_core::stake::{Beneficiary, StakeAmount, StakeFundOwner};
 use dusk_core::transfer::data::TransactionData;
 use dusk_core::transfer::phoenix::PublicKey as PhoenixPublicKey;
 use dusk_core::transfer::Transaction;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use wallet_core::transaction::{
-    moonlight, moonlight_deployment, moonlight_stake, moonlight_stake_reward,
+    moonlight, moonlight_deployment, moonlight_expiring,
+    moonlight_set_stake_beneficiary, moonlight_stake, moonlight_stake_reward,
     moonlight_to_phoenix, moonlight_unstake, phoenix, phoenix_deployment,
     phoenix_stake, phoenix_stake_reward, phoenix_to_moonlight, phoenix_unstake,
 };
@@ -47,6 +48,7 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
         }
 
         let state = self.state()?;
+        let _outbound = state.outbound().await;
 
         let mut rng = StdRng::from_entropy();
         let amt = *amt;
@@ -89,6 +91,9 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
     }
 
     /// Transfers funds between public accounts.
+    ///
+    /// With an `expiry_height`, the transfer can only be included in a block
+    /// up to that height, and never confirms afterwards.
     pub async fn moonlight_transfer(
         &self,
         sender_idx: u8,
@@ -96,6 +101,7 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
         memo: Option<String>,
         amt: Dusk,
         gas: Gas,
+        expiry_height: Option<u64>,
     ) -> Result<Transaction, Error> {
         // make sure amount is positive
         if amt == 0 && memo.is_none() {
@@ -111,20 +117,36 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
         let amt = *amt;
 
         let state = self.state()?;
-        let nonce = state.fetch_account(sender_pk).await?.nonce + 1;
+        let _outbound = state.outbound().await;
+        let nonce = state.next_nonce(sender_pk).await?;
         let chain_id = state.fetch_chain_id().await?;
 
-        let tx = moonlight(
-            &sender_sk,
-            Some(*rcvr),
-            amt,
-            0,
-            gas.limit,
-            gas.price,
-            nonce,
-            chain_id,
-            memo,
-        )?;
+        let tx = match expiry_height {
+            Some(expiry_height) => moonlight_expiring(
+                &sender_sk,
+                Some(*rcvr),
+                amt,
+                0,
+                gas.limit,
+                gas.price,
+                nonce,
+                chain_id,
+                expiry_height,
+                memo,
+            ),
+            None => moonlight(
+                &sender_sk,
+                Some(*rcvr),
+                amt,
+                0,
+                gas.limit,
+                gas.price,
+                nonce,
+                chain_id,
+                memo,
+            ),
+        };
+        let tx = tx?;
 
         sender_sk.zeroize();
 
@@ -145,6 +167,7 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
         }
 
         let state = self.state()?;
+        let _outbound = state.outbound().await;
         let deposit = *deposit;
 
         let mut rng = StdRng::from_entropy();
@@ -203,16 +226,13 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
         }
 
         let state = self.state()?;
+        let _outbound = state.outbound().await;
         let deposit = *deposit;
 
         let mut sender_sk = self.derive_bls_sk(sender_idx);
         let sender = self.public_key(sender_idx)?;
 
-        let account = state.fetch_account(sender).await?;
-
-        // technically this check is not necessary, but it's nice to not spam
-        // the network with transactions that are unspendable.
-        let nonce = account.nonce + 1;
+        let nonce = state.next_nonce(sender).await?;
 
         let chain_id = state.fetch_chain_id().await?;
 
@@ -251,6 +271,7 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
         }
 
         let state = self.state()?;
+        let _outbound = state.outbound().await;
 
         let mut rng = StdRng::from_entropy();
         let amt = *amt;
@@ -267,7 +288,13 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
                 }
                 state_idx
             }
-            Err(Error::NotStaked) => owner_idx.unwrap_or(profile_idx),
+            Err(Error::NotStaked) => {
+                // A new stake is checked against the minimum of the network
+                if amt < state.fetch_minimum_stake().await? {
+                    return Err(Error::StakeBelowMinimum);
+                }
+                owner_idx.unwrap_or(profile_idx)
+            }
             Err(e) => {
                 return Err(e);
             }
@@ -325,12 +352,12 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
         }
 
         let state = self.state()?;
+        let _outbound = state.outbound().await;
         let amt = *amt;
         let mut stake_sk = self.derive_bls_sk(profile_idx);
         let stake_pk = self.public_key(profile_idx)?;
         let chain_id = state.fetch_chain_id().await?;
-        let moonlight_current_nonce =
-            state.fetch_account(stake_pk).await?.nonce + 1;
+        let moonlight_current_nonce = state.next_nonce(stake_pk).await?;
 
         let stake_owner_idx = match self.find_stake_owner_idx(stake_pk).await {
             Ok(state_idx) => {
@@ -341,7 +368,13 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
                 }
                 state_idx
             }
-            Err(Error::NotStaked) => owner_idx.unwrap_or(profile_idx),
+            Err(Error::NotStaked) => {
+                // A new stake is checked against the minimum of the network
+                if amt < state.fetch_minimum_stake().await? {
+                    return Err(Error::StakeBelowMinimum);
+                }
+                owner_idx.unwrap_or(profile_idx)
+            }
             Err(e) => {
                 return Err(e);
             }
@@ -366,14 +399,18 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
     }
 
     /// Unstakes Dusk into shielded notes.
+    ///
+    /// The whole stake is unstaked if no amount is given.
     pub async fn phoenix_unstake(
         &self,
         profile_idx: u8,
+        amt: Option<Dusk>,
         gas: Gas,
     ) -> Result<Transaction, Error> {
         let mut rng = StdRng::from_entropy();
 
         let state = self.state()?;
+        let _outbound = state.outbound().await;
 
         let mut sender_sk = self.derive_phoenix_sk(profile_idx);
         let mut stake_sk = self.derive_bls_sk(profile_idx);
@@ -382,16 +419,9 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
         let stake_owner_idx = self.find_stake_owner_idx(&stake_pk).await?;
         let mut stake_owner_sk = self.derive_bls_sk(stake_owner_idx);
 
-        let unstake_value = state
-            .fetch_stake(&stake_pk)
-            .await?
-            .and_then(|s| s.amount)
-            .map(|s| s.total_funds())
-            .unwrap_or_default();
-
-        if unstake_value == 0 {
-            return Err(Error::NotStaked);
-        }
+        let stake = state.fetch_stake(&stake_pk).await?.and_then(|s| s.amount);
+        let minimum_stake = state.fetch_minimum_stake().await?;
+        let unstake_value = unstake_value(stake, amt, minimum_stake)?;
 
         let tx_cost = gas.limit * gas.price;
         let inputs = state.tx_input_notes(profile_idx, tx_cost).await?;
@@ -422,30 +452,27 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
     }
 
     /// Unstakes Dusk onto a public account.
+    ///
+    /// The whole stake is unstaked if no amount is given.
     pub async fn moonlight_unstake(
         &self,
         profile_idx: u8,
+        amt: Option<Dusk>,
         gas: Gas,
     ) -> Result<Transaction, Error> {
         let mut rng = StdRng::from_entropy();
         let state = self.state()?;
+        let _outbound = state.outbound().await;
         let mut stake_sk = self.derive_bls_sk(profile_idx);
 
         let stake_pk = self.public_key(profile_idx)?;
 
         let chain_id = state.fetch_chain_id().await?;
-        let account_nonce = state.fetch_account(stake_pk).await?.nonce + 1;
+        let account_nonce = state.next_nonce(stake_pk).await?;
 
-        let unstake_value = state
-            .fetch_stake(stake_pk)
-            .await?
-            .and_then(|s| s.amount)
-            .map(|s| s.total_funds())
-            .unwrap_or_default();
-
-        if unstake_value == 0 {
-            return Err(Error::NotStaked);
-        }
+        let stake = state.fetch_stake(stake_pk).await?.and_then(|s| s.amount);
+        let minimum_stake = state.fetch_minimum_stake().await?;
+        let unstake_value = unstake_value(stake, amt, minimum_stake)?;
 
         let stake_owner_idx = self.find_stake_owner_idx(stake_pk).await?;
         let mut stake_owner_sk = self.derive_bls_sk(stake_owner_idx);
@@ -475,6 +502,7 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
         gas: Gas,
     ) -> Result<Transaction, Error> {
         let state = self.state()?;
+        let _outbound = state.outbound().await;
         let mut rng = StdRng::from_entropy();
 
         let mut sender_sk = self.derive_phoenix_sk(sender_idx);
@@ -527,9 +555,10 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
     ) -> Result<Transaction, Error> {
         let mut rng = StdRng::from_entropy();
         let state = self.state()?;
+        let _outbound = state.outbound().await;
 
         let pk = self.public_key(sender_idx)?;
-        let nonce = state.fetch_account(pk).await?.nonce + 1;
+        let nonce = state.next_nonce(pk).await?;
         let chain_id = state.fetch_chain_id().await?;
         let stake_info = state.fetch_stake(pk).await?;
         let reward = stake_info.map(|s| s.reward).ok_or(Error::NoReward)?;
@@ -559,6 +588,51 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
         state.propagate(withdraw).await
     }
 
+    /// Registers the address receiving the rewards of the stake of the given
+    /// profile, or clears it if `beneficiary` is `None`.
+    pub async fn set_stake_beneficiary(
+        &self,
+        profile_idx: u8,
+        beneficiary: Option<Address>,
+        gas: Gas,
+    ) -> Result<Transaction, Error> {
+        let state = self.state()?;
+        let _outbound = state.outbound().await;
+
+        let stake_pk = self.public_key(profile_idx)?;
+        let nonce = state.next_nonce(stake_pk).await?;
+        let chain_id = state.fetch_chain_id().await?;
+        let beneficiary_nonce =
+            state.fetch_beneficiary_nonce(stake_pk).await? + 1;
+
+        let beneficiary = beneficiary.map(|address| match address {
+            Address::Shielded(pk) => Beneficiary::Phoenix(pk),
+            Address::Public(pk) => Beneficiary::Moonlight(pk),
+        });
+
+        let mut sender_sk = self.derive_bls_sk(profile_idx);
+
+        let stake_owner_idx = self.find_stake_owner_idx(stake_pk).await?;
+        let mut stake_owner_sk = self.derive_bls_sk(stake_owner_idx);
+
+        let set_beneficiary = moonlight_set_stake_beneficiary(
+            &sender_sk,
+            stake_pk,
+            &stake_owner_sk,
+            beneficiary,
+            beneficiary_nonce,
+            gas.limit,
+            gas.price,
+            nonce,
+            chain_id,
+        )?;
+
+        sender_sk.zeroize();
+        stake_owner_sk.zeroize();
+
+        state.propagate(set_beneficiary).await
+    }
+
     /// Converts Dusk from a shielded account to a public account.
     pub async fn phoenix_to_moonlight(
         &self,
@@ -568,6 +642,7 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
     ) -> Result<Transaction, Error> {
         let mut rng = StdRng::from_entropy();
         let state = self.state()?;
+        let _outbound = state.outbound().await;
         let tx_cost = *amt + gas.limit * gas.price;
         let inputs = state.tx_input_notes(profile_idx, tx_cost).await?;
 
@@ -606,10 +681,11 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
     ) -> Result<Transaction, Error> {
         let mut rng = StdRng::from_entropy();
         let state = self.state()?;
+        let _outbound = state.outbound().await;
 
         let moonlight_pk = self.public_key(profile_idx)?;
 
-        let nonce = state.fetch_account(moonlight_pk).await?.nonce + 1;
+        let nonce = state.next_nonce(moonlight_pk).await?;
         let chain_id = state.fetch_chain_id().await?;
 
         let mut phoenix_sk = self.derive_phoenix_sk(profile_idx);
@@ -643,6 +719,7 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
     ) -> Result<Transaction, Error> {
         let mut rng = StdRng::from_entropy();
         let state = self.state()?;
+        let _outbound = state.outbound().await;
 
         let chain_id = state.fetch_chain_id().await?;
         let root = state.fetch_root().await?;
@@ -684,9 +761,10 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
         gas: Gas,
     ) -> Result<Transaction, Error> {
         let state = self.state()?;
+        let _outbound = state.outbound().await;
 
         let pk = self.public_key(sender_idx)?;
-        let moonlight_nonce = state.fetch_account(pk).await?.nonce + 1;
+        let moonlight_nonce = state.next_nonce(pk).await?;
         let chain_id = state.fetch_chain_id().await?;
 
         let mut sender_sk = self.derive_bls_sk(sender_idx);
@@ -735,3 +813,66 @@ impl<F: SecureWalletFile + Debug> Wallet<F> {
         }
     }
 }
+
+/// Returns the value to unstake from `stake`, making sure a partial unstake
+/// doesn't leave less than the minimum stake behind.
+fn unstake_value(
+    stake: Option<StakeAmount>,
+    amt: Option<Dusk>,
+    minimum_stake: u64,
+) -> Result<u64, Error> {
+    let total = stake.map(|s| s.total_funds()).unwrap_or_default();
+    if total == 0 {
+        return Err(Error::NotStaked);
+    }
+
+    let Some(amt) = amt else {
+        return Ok(total);
+    };
+    let amt = *amt;
+
+    if amt == 0 {
+        return Err(Error::AmountIsZero);
+    }
+    if amt > total {
+        return Err(Error::NotEnoughStake);
+    }
+    let left = total - amt;
+    if left != 0 && left < minimum_stake {
+        return Err(Error::StakeBelowMinimum);
+    }
+
+    Ok(amt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_unstake() {
+        let stake = Some(StakeAmount {
+            value: 1_000,
+            locked: 0,
+            eligibility: 0,
+        });
+        let amt = |lux: u64| Some(Dusk::from(lux));
+
+        assert_eq!(unstake_value(stake, None, 500).unwrap(), 1_000);
+        assert_eq!(unstake_value(stake, amt(500), 500).unwrap(), 500);
+        assert_eq!(unstake_value(stake, amt(1_000), 500).unwrap(), 1_000);
+
+        assert!(matches!(
+            unstake_value(stake, amt(501), 500),
+            Err(Error::StakeBelowMinimum)
+        ));
+        assert!(matches!(
+            unstake_value(stake, amt(1_001), 500),
+            Err(Error::NotEnoughStake)
+        ));
+        assert!(matches!(
+            unstake_value(None, None, 500),
+            Err(Error::NotStaked)
+        ));
+    }
+}
@@ -14,5 +14,21 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Get crate version + commit + toolchain for `-v` arg support.
     rustc_tools_util::setup_version_info!();
 
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/chain.proto");
+        println!("cargo:rerun-if-env-changed=PROTOC");
+
+        // Use the vendored `protoc` unless one is provided, so that building
+        // with all the features needs no system dependency
+        if std::env::var_os("PROTOC").is_none() {
+            std::env::set_var(
+                "PROTOC",
+                protoc_bin_vendored::protoc_bin_path()?,
+            );
+        }
+        tonic_build::compile_protos("proto/chain.proto")?;
+    }
+
     Ok(())
 }
@@ -13,7 +13,9 @@ use std::path::PathBuf;
 use clap::builder::PossibleValuesParser;
 use clap::Parser;
 
-#[derive(Parser, Debug)]
+use crate::config::network::Network;
+
+#[derive(Parser, Debug, Clone)]
 #[command(
     author="Dusk Network B.V. All Rights Reserved.",
     version = &rusk::VERSION_BUILD[..],
@@ -24,6 +26,10 @@ pub struct Args {
     #[clap(long, short, env = "RUSK_CONFIG_TOML", value_parser)]
     pub config: Option<PathBuf>,
 
+    /// Network to join, providing the defaults of the configuration
+    #[clap(long, env = "RUSK_NETWORK", value_enum)]
+    pub network: Option<Network>,
+
     /// Output log level
     #[clap(long)]
     pub log_level: Option<tracing::Level>,
@@ -10,6 +10,20 @@ pub mod recovery;
 #[cfg(feature = "chain")]
 pub mod chain;
 
+#[cfg(feature = "chain")]
+pub mod db;
+
+#[cfg(feature = "chain")]
+pub mod keys;
+
+#[cfg(feature = "ephemeral")]
+pub mod localnet;
+
+#[cfg(feature = "debug-state")]
+pub mod state;
+
+pub mod check_config;
+
 use clap::Subcommand;
 
 #[allow(clippy::large_enum_variant)]
@@ -22,4 +36,23 @@ pub enum Command {
     #[cfg(feature = "chain")]
     #[clap(subcommand)]
     Chain(chain::ChainCommand),
+
+    #[cfg(feature = "chain")]
+    #[clap(subcommand)]
+    Db(db::DbCommand),
+
+    #[cfg(feature = "chain")]
+    #[clap(subcommand)]
+    Keys(keys::KeysCommand),
+
+    #[cfg(feature = "debug-state")]
+    #[clap(subcommand)]
+    State(state::StateCommand),
+
+    /// Validate the configuration and exit with an error if it is invalid
+    CheckConfig(check_config::CheckConfigCommand),
+
+    #[cfg(feature = "ephemeral")]
+    /// Run a local multi-node network from a state archive
+    Localnet(localnet::LocalnetCommand),
 }
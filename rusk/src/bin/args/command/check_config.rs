@@ -0,0 +1,398 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::fs;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::Path;
+
+use clap::Args;
+use serde::Serialize;
+
+use crate::config::Config;
+
+#[derive(PartialEq, Eq, Hash, Clone, Args, Debug)]
+pub struct CheckConfigCommand {
+    /// Genesis snapshot to validate together with the configuration
+    #[cfg(feature = "recovery-state")]
+    #[clap(long, value_parser)]
+    pub genesis: Option<std::path::PathBuf>,
+
+    /// Password of the consensus keys, to check they can be decrypted
+    #[cfg(feature = "chain")]
+    #[clap(long, env = "DUSK_CONSENSUS_KEYS_PASS", hide_env_values = true)]
+    pub keys_password: Option<String>,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Serialize)]
+struct Check {
+    check: &'static str,
+    status: Status,
+    message: String,
+}
+
+/// Outcome of all the checks, printed as JSON.
+#[derive(Serialize, Default)]
+struct Report {
+    valid: bool,
+    checks: Vec<Check>,
+}
+
+impl Report {
+    fn push(&mut self, check: &'static str, status: Status, message: String) {
+        self.checks.push(Check {
+            check,
+            status,
+            message,
+        });
+    }
+
+    fn ok(&mut self, check: &'static str, message: impl Into<String>) {
+        self.push(check, Status::Ok, message.into());
+    }
+
+    fn warning(&mut self, check: &'static str, message: impl Into<String>) {
+        self.push(check, Status::Warning, message.into());
+    }
+
+    fn error(&mut self, check: &'static str, message: impl Into<String>) {
+        self.push(check, Status::Error, message.into());
+    }
+}
+
+impl CheckConfigCommand {
+    /// Validates the configuration resulting from `args`, printing the
+    /// report. Fails if any check reports an error.
+    pub fn run(
+        &self,
+        args: &crate::args::Args,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut report = Report::default();
+
+        match Config::load(args) {
+            Ok(config) => self.check(&config, &mut report),
+            Err(e) => report.error("config", format!("Cannot load: {e}")),
+        }
+
+        #[cfg(feature = "recovery-state")]
+        if let Some(genesis) = &self.genesis {
+            check_genesis(genesis, &mut report);
+        }
+
+        report.valid = report.checks.iter().all(|c| c.status != Status::Error);
+        println!("{}", serde_json::to_string_pretty(&report)?);
+
+        if !report.valid {
+            return Err("Invalid configuration".into());
+        }
+        Ok(())
+    }
+
+    fn check(&self, config: &Config, report: &mut Report) {
+        match config.try_log_level() {
+            Ok(level) => report.ok("log_level", level.to_string()),
+            Err(e) => report.error("log_level", e),
+        }
+        if let Some(file) = &config.log.file {
+            match file.path.parent() {
+                Some(dir) => check_writable_dir("log.file", dir, report),
+                None => report.error("log.file", "Invalid log file path"),
+            }
+        }
+
+        match config.runtime.validate() {
+            Ok(()) => report.ok(
+                "runtime.worker_threads",
+                config.runtime.worker_threads().to_string(),
+            ),
+            Err(e) => report.error("runtime.worker_threads", e),
+        }
+
+        check_listeners(config, report);
+
+        for (check, path) in [
+            ("http.cert", &config.http.cert),
+            ("http.key", &config.http.key),
+        ] {
+            if let Some(path) = path {
+                check_readable_file(check, path, report);
+            }
+        }
+
+        #[cfg(feature = "chain")]
+        {
+            check_writable_dir(
+                "chain.db_path",
+                &config.chain.db_path(),
+                report,
+            );
+
+            let remote_signer = config.chain.remote_signer();
+            if let Some(signer) = &remote_signer {
+                check_readable_file(
+                    "chain.remote_signer.auth_key_path",
+                    &signer.auth_key_path,
+                    report,
+                );
+            }
+
+            let keys_path = config.chain.consensus_keys_path();
+            let local_keys =
+                remote_signer.map_or(true, |signer| signer.local_fallback);
+            if local_keys
+                && check_readable_file(
+                    "chain.consensus_keys_path",
+                    keys_path.as_ref(),
+                    report,
+                )
+            {
+                check_consensus_keys(
+                    keys_path,
+                    self.keys_password.clone(),
+                    report,
+                );
+            }
+
+            check_databroker(config, report);
+            check_kadcast(config, report);
+        }
+    }
+}
+
+/// Checks the TCP listeners are valid addresses not sharing a port.
+fn check_listeners(config: &Config, report: &mut Report) {
+    let mut listeners = vec![];
+    if config.http.listen {
+        listeners.push(("http.listen_address", config.http.listen_addr()));
+    }
+    #[cfg(feature = "chain")]
+    if let Some(addr) = config.telemetry.listen_addr() {
+        listeners.push(("telemetry.listen_address", addr));
+    }
+
+    let mut bound: Vec<(&'static str, SocketAddr)> = vec![];
+    for (check, addr) in listeners {
+        let Some(addr) = resolve(&addr) else {
+            report.error(check, format!("Invalid address {addr}"));
+            continue;
+        };
+
+        let collision = bound.iter().find(|(_, other)| {
+            other.port() == addr.port()
+                && (other.ip() == addr.ip()
+                    || other.ip().is_unspecified()
+                    || addr.ip().is_unspecified())
+        });
+        match collision {
+            Some((other, _)) => report.error(
+                check,
+                format!("Port {} is also used by {other}", addr.port()),
+            ),
+            None => report.ok(check, addr.to_string()),
+        }
+        bound.push((check, addr));
+    }
+}
+
+fn resolve(addr: &str) -> Option<SocketAddr> {
+    addr.to_socket_addrs().ok()?.next()
+}
+
+/// Checks the directory exists, or can be created, and is writable.
+fn check_writable_dir(check: &'static str, dir: &Path, report: &mut Report) {
+    // The directory is checked through its closest existing ancestor, in
+    // case it is yet to be created.
+    let Some(existing) = dir.ancestors().find(|p| p.exists()) else {
+        report.error(check, format!("{} cannot be created", dir.display()));
+        return;
+    };
+    if !existing.is_dir() {
+        report
+            .error(check, format!("{} is not a directory", existing.display()));
+        return;
+    }
+
+    let probe = existing.join(".rusk-check-config");
+    match fs::write(&probe, []) {
+        Ok(()) => {
+            let _ = fs::remove_file(probe);
+            report.ok(check, dir.display().to_string());
+        }
+        Err(e) => report.error(
+            check,
+            format!("{} is not writable: {e}", existing.display()),
+        ),
+    }
+}
+
+/// Checks the file can be read, returning whether it can.
+fn check_readable_file(
+    check: &'static str,
+    path: &Path,
+    report: &mut Report,
+) -> bool {
+    match fs::File::open(path) {
+        Ok(_) => {
+            report.ok(check, path.display().to_string());
+            true
+        }
+        Err(e) => {
+            report.error(check, format!("Cannot read {}: {e}", path.display()));
+            false
+        }
+    }
+}
+
+#[cfg(feature = "chain")]
+fn check_consensus_keys(
+    path: String,
+    password: Option<String>,
+    report: &mut Report,
+) {
+    const CHECK: &str = "consensus_keys";
+
+    let Some(password) = password else {
+        report.warning(
+            CHECK,
+            "DUSK_CONSENSUS_KEYS_PASS not set, decryption not checked",
+        );
+        return;
+    };
+
+    match node_data::bls::load_keys(path, password) {
+        Ok((_, pk)) => report.ok(CHECK, pk.to_bs58()),
+        Err(e) => report.error(CHECK, e.to_string()),
+    }
+}
+
+#[cfg(feature = "chain")]
+fn check_databroker(config: &Config, report: &mut Report) {
+    let params =
+        node::databroker::conf::Params::from(config.databroker.clone());
+
+    for (check, value) in [
+        ("databroker.max_inv_entries", params.max_inv_entries),
+        (
+            "databroker.max_ongoing_requests",
+            params.max_ongoing_requests,
+        ),
+        ("databroker.max_queue_size", params.max_queue_size),
+    ] {
+        match value {
+            0 => report.error(check, "Must be greater than 0"),
+            value => report.ok(check, value.to_string()),
+        }
+    }
+
+    if let Some(delay) = params.delay_on_resp_msg {
+        report.warning(
+            "databroker.delay_on_resp_msg",
+            format!("Responses are delayed by {delay}ms, meant for localnet"),
+        );
+    }
+}
+
+#[cfg(feature = "chain")]
+fn check_kadcast(config: &Config, report: &mut Report) {
+    let kadcast = kadcast::config::Config::from(config.kadcast.clone());
+
+    match kadcast.public_address.parse::<SocketAddr>() {
+        Ok(addr) if addr.ip().is_unspecified() => report.error(
+            "kadcast.public_address",
+            format!("{addr} is not reachable by other peers"),
+        ),
+        Ok(addr) => report.ok("kadcast.public_address", addr.to_string()),
+        Err(e) => report.error(
+            "kadcast.public_address",
+            format!("Invalid address {}: {e}", kadcast.public_address),
+        ),
+    }
+
+    if let Some(listen) = &kadcast.listen_address {
+        match listen.parse::<SocketAddr>() {
+            Ok(addr) => report.ok("kadcast.listen_address", addr.to_string()),
+            Err(e) => report.error(
+                "kadcast.listen_address",
+                format!("Invalid address {listen}: {e}"),
+            ),
+        }
+    }
+
+    if kadcast.bootstrapping_nodes.is_empty() {
+        report.warning(
+            "kadcast.bootstrapping_nodes",
+            "No bootstrapping node, the node will not join a network",
+        );
+    }
+    // The check runs before the runtime of the node is built
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime to be built");
+    for node in &kadcast.bootstrapping_nodes {
+        let resolved =
+            runtime.block_on(node::network::resolve_bootstrapping_nodes(&[
+                node.clone(),
+            ]));
+        if resolved.is_empty() {
+            report.warning(
+                "kadcast.bootstrapping_nodes",
+                format!("Cannot resolve {node}"),
+            );
+        }
+    }
+
+    if kadcast.channel_size == 0 {
+        report.error("kadcast.channel_size", "Must be greater than 0");
+    }
+}
+
+#[cfg(feature = "recovery-state")]
+fn check_genesis(path: &Path, report: &mut Report) {
+    use dusk_bytes::Serializable;
+    use dusk_core::stake::MINIMUM_STAKE;
+    use rusk_recovery_tools::state::Snapshot;
+
+    const CHECK: &str = "genesis";
+
+    let snapshot = match fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|toml| {
+            toml::from_str::<Snapshot>(&toml).map_err(|e| e.to_string())
+        }) {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            report.error(CHECK, format!("Cannot load {}: {e}", path.display()));
+            return;
+        }
+    };
+
+    let stakes = snapshot.stakes().count();
+    if stakes == 0 && snapshot.base_state().is_none() {
+        report.error(CHECK, "No genesis stake, no block can be produced");
+        return;
+    }
+    for stake in snapshot.stakes() {
+        if stake.amount < MINIMUM_STAKE {
+            report.warning(
+                CHECK,
+                format!(
+                    "Stake of {} is below the minimum stake",
+                    bs58::encode(stake.address().to_bytes()).into_string()
+                ),
+            );
+        }
+    }
+
+    report.ok(CHECK, format!("{stakes} genesis stakes"));
+}
@@ -0,0 +1,125 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::path::Path;
+
+use clap::Subcommand;
+use node::database::rocksdb::{Backend, MD_HASH_KEY};
+use node::database::{Ledger, Metadata, DB};
+use node_data::ledger::Block;
+use serde_json::{json, Value};
+
+/// Inspection commands of a stopped node's database. The output is printed
+/// as JSON.
+#[derive(PartialEq, Eq, Hash, Clone, Subcommand, Debug)]
+pub enum DbCommand {
+    /// Print the tip and the statistics of the column families
+    Info,
+
+    /// Print the header of the tip
+    Tip,
+
+    /// Print a block and its transactions
+    GetBlock {
+        /// Height or hex encoded hash of the block
+        block: String,
+    },
+}
+
+impl DbCommand {
+    pub fn run(self, db_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let db = Backend::open_existing(db_path)?;
+
+        let output = match self {
+            Self::Info => {
+                let tip = tip(&db)?.map(|block| {
+                    json!({
+                        "height": block.header().height,
+                        "hash": hex::encode(block.header().hash),
+                    })
+                });
+
+                let column_families: Vec<_> = db
+                    .column_families_stats()?
+                    .into_iter()
+                    .map(|cf| {
+                        json!({
+                            "name": cf.name,
+                            "estimatedKeys": cf.estimated_keys,
+                            "sstFilesSize": cf.sst_files_size,
+                            "memtablesSize": cf.memtables_size,
+                        })
+                    })
+                    .collect();
+
+                json!({
+                    "path": db_path,
+                    "tip": tip,
+                    "columnFamilies": column_families,
+                })
+            }
+            Self::Tip => {
+                let block = tip(&db)?.ok_or("No tip found")?;
+                serde_json::to_value(block.header())?
+            }
+            Self::GetBlock { block } => {
+                let block = get_block(&db, &block)?.ok_or("Block not found")?;
+                block_json(&db, &block)?
+            }
+        };
+
+        println!("{}", serde_json::to_string_pretty(&output)?);
+
+        Ok(())
+    }
+}
+
+fn tip(db: &Backend) -> anyhow::Result<Option<Block>> {
+    db.view(|t| match t.op_read(MD_HASH_KEY)? {
+        Some(hash) => t.block(&hash),
+        None => Ok(None),
+    })
+}
+
+/// Fetches a block by height, or by hash if `id` is 32 hex encoded bytes.
+fn get_block(db: &Backend, id: &str) -> anyhow::Result<Option<Block>> {
+    let hash = match hex::decode(id) {
+        Ok(hash) if hash.len() == 32 => Some(hash),
+        _ => None,
+    };
+
+    db.view(|t| match hash {
+        Some(hash) => t.block(&hash),
+        None => t.block_by_height(id.parse()?),
+    })
+}
+
+fn block_json(db: &Backend, block: &Block) -> anyhow::Result<Value> {
+    db.view(|t| {
+        let label = t
+            .block_label_by_height(block.header().height)?
+            .filter(|(hash, _)| *hash == block.header().hash)
+            .map(|(_, label)| format!("{label:?}"));
+
+        let mut txs = Vec::with_capacity(block.txs().len());
+        for tx in block.txs() {
+            let id = tx.id();
+            let spent = t.ledger_tx(&id)?;
+            txs.push(json!({
+                "id": hex::encode(id),
+                "gasSpent": spent.as_ref().map(|s| s.gas_spent),
+                "error": spent.and_then(|s| s.err),
+            }));
+        }
+
+        Ok(json!({
+            "header": block.header(),
+            "label": label,
+            "transactions": txs,
+            "faults": block.faults().len(),
+        }))
+    })
+}
@@ -0,0 +1,135 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::Subcommand;
+use dusk_bytes::Serializable;
+use dusk_core::signatures::bls::{
+    PublicKey as BlsPublicKey, SecretKey as BlsSecretKey,
+};
+use dusk_core::stake::DEFAULT_MINIMUM_STAKE;
+use dusk_core::{dusk, from_dusk, Dusk};
+use dusk_vm::VM;
+use rand::rngs::OsRng;
+
+#[derive(PartialEq, Eq, Hash, Clone, Subcommand, Debug)]
+pub enum KeysCommand {
+    /// Generate the consensus keys of a new provisioner
+    NewProvisioner {
+        /// Path of the encrypted consensus keys file [default: the
+        /// `consensus_keys_path` of the configuration]
+        #[clap(short, long, value_parser)]
+        output: Option<PathBuf>,
+
+        /// Password used to encrypt the consensus keys
+        #[clap(long, env = "DUSK_CONSENSUS_KEYS_PASS", hide_env_values = true)]
+        password: String,
+
+        /// Overwrites the output file if it exists
+        #[clap(short, long)]
+        force: bool,
+
+        /// Prints, instead of generating keys no wallet can stake, the wallet
+        /// commands exporting the keys of a wallet profile to the output path
+        /// and staking this amount of DUSK with them
+        #[clap(long)]
+        stake: Option<u64>,
+    },
+}
+
+impl KeysCommand {
+    pub fn run(
+        self,
+        default_path: &Path,
+        state_dir: &Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            Self::NewProvisioner {
+                output,
+                password,
+                force,
+                stake,
+            } => {
+                let path = output.unwrap_or_else(|| default_path.into());
+                if path.exists() && !force {
+                    return Err(format!(
+                        "{} already exists, use --force to overwrite it",
+                        path.display()
+                    )
+                    .into());
+                }
+
+                // A stake is signed by the key staked, so the provisioner
+                // keys must be the ones of a wallet profile
+                if let Some(stake) = stake {
+                    let minimum_stake = minimum_stake(state_dir)?;
+                    if dusk(stake as f64) < minimum_stake {
+                        return Err(format!(
+                            "The stake must be at least {} DUSK",
+                            from_dusk(minimum_stake)
+                        )
+                        .into());
+                    }
+
+                    let dir = path.parent().unwrap_or(Path::new("."));
+                    let name = path
+                        .file_stem()
+                        .ok_or("The output must be a file path")?
+                        .to_string_lossy();
+                    println!(
+                        "Export the keys of a wallet profile and stake with them:"
+                    );
+                    println!(
+                        "  rusk-wallet export --profile-idx 0 --dir {} --name {name}",
+                        dir.display()
+                    );
+                    println!("  rusk-wallet stake --amt {stake}");
+                    return Ok(());
+                }
+
+                if password.is_empty() {
+                    return Err("The password must not be empty".into());
+                }
+
+                let sk = BlsSecretKey::random(&mut OsRng);
+                let pk = BlsPublicKey::from(&sk);
+
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                node_data::bls::save_keys(
+                    &path, &sk, &pk, &password, &mut OsRng,
+                )?;
+
+                let pk = bs58::encode(pk.to_bytes()).into_string();
+                println!("Consensus keys written to {}", path.display());
+                println!("Public key: {pk}");
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Returns the minimum stake configured in the local state of the network,
+/// or the default one if the node has no state yet.
+fn minimum_stake(state_dir: &Path) -> Result<Dusk, Box<dyn std::error::Error>> {
+    let id_path = rusk_profile::to_rusk_state_id_path(state_dir);
+    if !id_path.exists() {
+        return Ok(DEFAULT_MINIMUM_STAKE);
+    }
+
+    let commit: [u8; 32] = fs::read(id_path)?
+        .try_into()
+        .map_err(|_| "Invalid persisted commit")?;
+    let vm = VM::new(state_dir)?;
+    // The chain ID and block height are not read by the query
+    let mut session = vm.session(commit, 0, 0)?;
+
+    Ok(rusk::node::stake_config(&mut session)?.minimum_stake)
+}
@@ -0,0 +1,32 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+#[derive(PartialEq, Eq, Hash, Clone, Args, Debug)]
+pub struct LocalnetCommand {
+    /// State file (archive) shared by all the nodes
+    #[clap(short, long = "state", value_parser)]
+    pub state_path: PathBuf,
+
+    /// Number of nodes to spawn
+    #[clap(short, long, default_value_t = 4)]
+    pub nodes: u16,
+
+    /// Kadcast port of the first node, node `i` uses `kadcast_port + i`
+    #[clap(long, default_value_t = 7000)]
+    pub kadcast_port: u16,
+
+    /// HTTP port of the first node, node `i` uses `http_port + i`
+    #[clap(long, default_value_t = 8080)]
+    pub http_port: u16,
+
+    /// Password used to encrypt the generated consensus keys
+    #[clap(long, env = "DUSK_CONSENSUS_KEYS_PASS", default_value = "password")]
+    pub keys_password: String,
+}
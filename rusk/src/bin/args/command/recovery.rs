@@ -44,6 +44,17 @@ pub enum RecoveryCommand {
         /// of save the state in the profile path.
         #[clap(short, long, value_parser, num_args(1))]
         output: Option<std::path::PathBuf>,
+
+        /// Release channel (mainnet, testnet, devnet) the manifest of the base
+        /// state, if published, must be signed for. Overrides the channel of
+        /// the init config.
+        #[clap(long, env = "RUSK_STATE_CHANNEL")]
+        channel: Option<rusk_recovery_tools::state::manifest::Channel>,
+
+        /// Path to an hex encoded Ed25519 key used to sign the output state
+        /// for the given channel. The manifest is written next to the output.
+        #[clap(long, value_parser, requires = "output", requires = "channel")]
+        sign_key: Option<std::path::PathBuf>,
     },
 }
 
@@ -72,7 +83,11 @@ impl RecoveryCommand {
                 force,
                 init,
                 output,
-            } => crate::args::state::recovery_state(init, force, output),
+                channel,
+                sign_key,
+            } => crate::args::state::recovery_state(
+                init, force, output, channel, sign_key,
+            ),
             #[cfg(feature = "recovery-keys")]
             Self::Keys { keep, crs_url } => {
                 rusk_recovery_tools::keys::exec(keep, crs_url)
@@ -0,0 +1,102 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::fs;
+use std::path::Path;
+
+use clap::Subcommand;
+use dusk_core::abi::ContractId;
+use dusk_core::stake::STAKE_CONTRACT;
+use dusk_core::transfer::TRANSFER_CONTRACT;
+use dusk_vm::VM;
+use rusk::node::inspect::{contract_pages, known_contract_state, MAX_PAGES};
+use serde_json::json;
+
+/// Inspection commands of a stopped node's contracts state. The output is
+/// printed as JSON.
+#[derive(PartialEq, Eq, Hash, Clone, Subcommand, Debug)]
+pub enum StateCommand {
+    /// Print the state of a contract, decoded for the genesis contracts
+    Contract {
+        /// `transfer`, `stake` or the hex encoded ID of the contract
+        contract: String,
+
+        /// Hex encoded commit, the last persisted one if not given
+        #[clap(long)]
+        commit: Option<String>,
+
+        /// Print the raw memory pages, starting from the given one
+        #[clap(long)]
+        pages: Option<usize>,
+
+        /// Number of memory pages printed
+        #[clap(long, default_value_t = MAX_PAGES)]
+        count: usize,
+    },
+}
+
+impl StateCommand {
+    pub fn run(
+        self,
+        state_dir: &Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let output = match self {
+            Self::Contract {
+                contract,
+                commit,
+                pages,
+                count,
+            } => {
+                let contract = parse_contract(&contract)?;
+                let commit = match commit {
+                    Some(commit) => decode_32(&commit)?,
+                    None => {
+                        let path =
+                            rusk_profile::to_rusk_state_id_path(state_dir);
+                        fs::read(path)?
+                            .try_into()
+                            .map_err(|_| "Invalid persisted commit")?
+                    }
+                };
+
+                let vm = VM::new(state_dir)?;
+                // The chain ID and block height are not read by the queries
+                let mut session = vm.session(commit, 0, 0)?;
+
+                let pages = pages
+                    .map(|from| contract_pages(&session, contract, from, count))
+                    .transpose()?;
+                let state = known_contract_state(&mut session, contract)?;
+
+                json!({
+                    "commit": hex::encode(commit),
+                    "contract": hex::encode(contract.as_bytes()),
+                    "state": state,
+                    "pages": pages.map(|p| p.pages),
+                })
+            }
+        };
+
+        println!("{}", serde_json::to_string_pretty(&output)?);
+
+        Ok(())
+    }
+}
+
+fn parse_contract(contract: &str) -> Result<ContractId, String> {
+    match contract {
+        "transfer" => Ok(TRANSFER_CONTRACT),
+        "stake" => Ok(STAKE_CONTRACT),
+        id => Ok(ContractId::from_bytes(decode_32(id)?)),
+    }
+}
+
+fn decode_32(data: &str) -> Result<[u8; 32], String> {
+    let mut bytes = [0u8; 32];
+    hex::decode_to_slice(data, &mut bytes)
+        .map_err(|e| format!("Invalid hex {data}: {e}"))?;
+    Ok(bytes)
+}
@@ -8,7 +8,10 @@ use super::*;
 
 use std::{env, fs, io};
 
-use rusk_recovery_tools::state::{deploy, restore_state, tar};
+use rusk_recovery_tools::state::manifest::{
+    self, Channel, Manifest, MANIFEST_EXTENSION,
+};
+use rusk_recovery_tools::state::{build_genesis, restore_state, tar, Snapshot};
 use rusk_recovery_tools::Theme;
 use tracing::info;
 
@@ -16,13 +19,18 @@ pub fn recovery_state(
     init: Option<PathBuf>,
     force: bool,
     output_file: Option<PathBuf>,
+    channel: Option<Channel>,
+    sign_key: Option<PathBuf>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let config = match &init {
         Some(path) => fs::read_to_string(path)
             .map_err(|_| format!("file {path:?} not found"))?,
         None => rusk_recovery_tools::state::DEFAULT_SNAPSHOT.into(),
     };
-    let init = toml::from_str(&config)?;
+    let mut init: Snapshot = toml::from_str(&config)?;
+    if let Some(channel) = channel {
+        init.set_channel(channel);
+    }
 
     let theme = Theme::default();
     info!("{} Network state", theme.action("Checking"));
@@ -63,7 +71,7 @@ pub fn recovery_state(
 
     info!("{} new state", theme.info("Building"));
 
-    let (_, commit_id) = deploy(&state_dir, &init, |_| {})?;
+    let commit_id = build_genesis(&init, &state_dir)?;
 
     info!("{} {}", theme.action("Final Root"), hex::encode(commit_id));
 
@@ -86,6 +94,21 @@ pub fn recovery_state(
             output.display()
         );
         tar::archive(&state_folder, &output)?;
+
+        if let (Some(key), Some(channel)) = (sign_key, channel) {
+            let key =
+                manifest::signing_key_from_hex(&fs::read_to_string(key)?)?;
+            let manifest = Manifest::sign(&fs::read(&output)?, channel, &key);
+
+            let mut manifest_path = output.into_os_string();
+            manifest_path.push(MANIFEST_EXTENSION);
+            fs::write(&manifest_path, manifest.to_toml()?)?;
+            info!(
+                "{} manifest for {channel} into {}",
+                theme.success("Signed"),
+                PathBuf::from(manifest_path).display()
+            );
+        }
     }
 
     Ok(())
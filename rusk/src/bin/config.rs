@@ -8,6 +8,10 @@
 pub mod chain;
 #[cfg(feature = "chain")]
 pub mod databroker;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "chain")]
+pub mod identity;
 #[cfg(feature = "chain")]
 pub mod kadcast;
 #[cfg(feature = "chain")]
@@ -16,28 +20,58 @@ pub mod mempool;
 pub mod telemetry;
 
 pub mod http;
+pub mod log;
+pub mod network;
+#[cfg(feature = "prover")]
+pub mod prover;
+pub mod runtime;
 
 use std::env;
 use std::str::FromStr;
 
+#[cfg(feature = "grpc")]
+use self::grpc::GrpcConfig;
 #[cfg(feature = "chain")]
 use self::{
-    chain::ChainConfig, databroker::DataBrokerConfig, kadcast::KadcastConfig,
-    mempool::MempoolConfig, telemetry::TelemetryConfig,
+    chain::ChainConfig, databroker::DataBrokerConfig, identity::IdentityConfig,
+    kadcast::KadcastConfig, mempool::MempoolConfig, telemetry::TelemetryConfig,
 };
+#[cfg(feature = "chaos")]
+use node::chaos::ChaosConfig;
+#[cfg(feature = "chain")]
+use node::network::{BandwidthConfig, PeerSnapshotConfig};
+#[cfg(feature = "faucet")]
+use rusk::http::FaucetConfig;
+#[cfg(feature = "chain")]
+use rusk::node::ArtifactsConfig;
 
 use serde::{Deserialize, Serialize};
 
 use crate::args::Args;
 
 use self::http::HttpConfig;
+use self::log::LogConfig;
+use self::network::Network;
+#[cfg(feature = "prover")]
+use self::prover::ProverConfig;
+use self::runtime::RuntimeConfig;
 
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub(crate) struct Config {
+    /// Network whose profile the configuration is built upon
+    #[serde(default)]
+    pub(crate) network: Network,
+
     log_level: Option<String>,
     log_type: Option<String>,
     log_filter: Option<String>,
 
+    #[serde(default = "LogConfig::default")]
+    pub(crate) log: LogConfig,
+
+    #[serde(default = "RuntimeConfig::default")]
+    pub(crate) runtime: RuntimeConfig,
+
     #[cfg(feature = "chain")]
     #[serde(default = "DataBrokerConfig::default")]
     pub(crate) databroker: DataBrokerConfig,
@@ -46,6 +80,18 @@ pub(crate) struct Config {
     #[serde(default = "KadcastConfig::default")]
     pub(crate) kadcast: KadcastConfig,
 
+    #[cfg(feature = "chain")]
+    #[serde(default = "BandwidthConfig::default")]
+    pub(crate) bandwidth: BandwidthConfig,
+
+    #[cfg(feature = "chain")]
+    #[serde(default = "IdentityConfig::default")]
+    pub(crate) identity: IdentityConfig,
+
+    #[cfg(feature = "chain")]
+    #[serde(default = "PeerSnapshotConfig::default")]
+    pub(crate) peer_snapshot: PeerSnapshotConfig,
+
     #[cfg(feature = "chain")]
     #[serde(default = "ChainConfig::default")]
     pub(crate) chain: ChainConfig,
@@ -53,6 +99,14 @@ pub(crate) struct Config {
     #[serde(default = "HttpConfig::default")]
     pub(crate) http: HttpConfig,
 
+    #[cfg(feature = "grpc")]
+    #[serde(default = "GrpcConfig::default")]
+    pub(crate) grpc: GrpcConfig,
+
+    #[cfg(feature = "prover")]
+    #[serde(default = "ProverConfig::default")]
+    pub(crate) prover: ProverConfig,
+
     #[cfg(feature = "chain")]
     #[serde(default = "TelemetryConfig::default")]
     pub(crate) telemetry: TelemetryConfig,
@@ -60,6 +114,18 @@ pub(crate) struct Config {
     #[cfg(feature = "chain")]
     #[serde(default = "MempoolConfig::default")]
     pub(crate) mempool: MempoolConfig,
+
+    #[cfg(feature = "chain")]
+    #[serde(default)]
+    pub(crate) artifacts: Option<ArtifactsConfig>,
+
+    #[cfg(feature = "faucet")]
+    #[serde(default)]
+    pub(crate) faucet: Option<FaucetConfig>,
+
+    #[cfg(feature = "chaos")]
+    #[serde(default)]
+    pub(crate) chaos: Option<ChaosConfig>,
 }
 
 /// Default log_level.
@@ -70,11 +136,39 @@ const DEFAULT_LOG_TYPE: &str = "coloured";
 
 impl From<&Args> for Config {
     fn from(args: &Args) -> Self {
-        let mut rusk_config =
-            args.config.as_ref().map_or(Config::default(), |conf_path| {
-                let toml = std::fs::read_to_string(conf_path).unwrap();
-                toml::from_str(&toml).unwrap()
-            });
+        Self::load(args).unwrap()
+    }
+}
+
+impl Config {
+    /// Loads the profile of the selected network, overwrites it with the
+    /// file given in the arguments, and then with the arguments themselves.
+    pub(crate) fn load(
+        args: &Args,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let file_config: toml::Table = match args.config.as_ref() {
+            Some(conf_path) => {
+                let toml = std::fs::read_to_string(conf_path)?;
+                toml::from_str(&toml)?
+            }
+            None => toml::Table::new(),
+        };
+
+        // The network given in the arguments takes precedence over the one
+        // of the file
+        let network = match args.network {
+            Some(network) => network,
+            None => match file_config.get("network") {
+                Some(network) => network.clone().try_into()?,
+                None => Network::default(),
+            },
+        };
+
+        let mut config = network.profile_table();
+        network::merge_tables(&mut config, file_config);
+
+        let mut rusk_config: Config = toml::Value::Table(config).try_into()?;
+        rusk_config.network = network;
 
         // Overwrite config log-level
         if let Some(log_level) = args.log_level {
@@ -103,16 +197,15 @@ impl From<&Args> for Config {
         #[cfg(feature = "chain")]
         {
             rusk_config.kadcast.merge(args);
+            rusk_config.chain.set_network(network);
             rusk_config.chain.merge(args);
             rusk_config.databroker.merge(args);
             rusk_config.telemetry.merge(args);
         }
 
-        rusk_config
+        Ok(rusk_config)
     }
-}
 
-impl Config {
     pub(crate) fn log_type(&self) -> String {
         match &self.log_type {
             None => DEFAULT_LOG_TYPE.into(),
@@ -121,16 +214,32 @@ impl Config {
     }
 
     pub(crate) fn log_level(&self) -> tracing::Level {
+        self.try_log_level().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub(crate) fn try_log_level(&self) -> Result<tracing::Level, String> {
         let log_level = match &self.log_level {
             None => DEFAULT_LOG_LEVEL,
             Some(log_level) => log_level,
         };
-        tracing::Level::from_str(log_level).unwrap_or_else(|e| {
-            panic!("Invalid log-level specified '{log_level}' - {e}")
+        tracing::Level::from_str(log_level).map_err(|e| {
+            format!("Invalid log-level specified '{log_level}' - {e}")
         })
     }
 
     pub(crate) fn log_filter(&self) -> String {
         self.log_filter.clone().unwrap_or_default()
     }
+
+    /// Returns the part of the configuration that can be applied to a
+    /// running node.
+    #[cfg(feature = "chain")]
+    pub(crate) fn runtime(&self) -> rusk::RuntimeConfig {
+        rusk::RuntimeConfig {
+            databroker: self.databroker.clone().into(),
+            mempool: self.mempool.clone().into(),
+            feeder_call_gas: self.http.feeder_call_gas,
+            ws_event_channel_cap: self.http.ws_event_channel_cap,
+        }
+    }
 }
@@ -5,15 +5,20 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 use std::{
+    env,
     path::PathBuf,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+use node::chain::{
+    BlockSpaceReservation, GasLimitAdjustment, RemoteSignerConfig,
+};
 use node::database::DatabaseOptions;
 use serde::{Deserialize, Serialize};
 
 pub const DEFAULT_BLOCK_GAS_LIMIT: u64 = 5 * 1_000_000_000;
 
+use super::network::Network;
 use crate::args::Args;
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -22,23 +27,57 @@ pub(crate) struct ChainConfig {
     db_options: Option<DatabaseOptions>,
 
     consensus_keys_path: Option<PathBuf>,
+    #[serde(default)]
+    watch_only: bool,
+    #[serde(default)]
+    replica: bool,
+    remote_signer: Option<RemoteSignerConfig>,
+    #[serde(default)]
+    compact_candidates: bool,
     #[serde(with = "humantime_serde")]
     #[serde(default)]
     generation_timeout: Option<Duration>,
 
     max_queue_size: Option<usize>,
 
-    // NB: changing the gas_per_deploy_byte/block_gas_limit is equivalent to
+    // NB: changing the gas_per_deploy_byte/block_gas_limit, the
+    // gas_limit_adjustment or the block_space_reservation is equivalent to
     // forking the chain.
     gas_per_deploy_byte: Option<u64>,
     min_deployment_gas_price: Option<u64>,
     min_deploy_points: Option<u64>,
     min_gas_limit: Option<u64>,
     block_gas_limit: Option<u64>,
+    gas_limit_adjustment: Option<GasLimitAdjustment>,
+    block_space_reservation: Option<BlockSpaceReservation>,
 
     #[serde(with = "humantime_serde")]
     #[serde(default)]
     genesis_timestamp: Option<SystemTime>,
+
+    /// Number of accepted states kept beyond the non-final ones
+    commit_retention: Option<usize>,
+    /// Interval between the deletions of the unreachable states
+    #[serde(with = "humantime_serde")]
+    #[serde(default)]
+    commit_gc_interval: Option<Duration>,
+
+    /// Maximum time a message timestamp can be ahead of the local time
+    #[serde(with = "humantime_serde")]
+    #[serde(default)]
+    max_clock_skew: Option<Duration>,
+    /// Time after which the queued messages of future rounds are discarded
+    #[serde(with = "humantime_serde")]
+    #[serde(default)]
+    future_msg_ttl: Option<Duration>,
+    /// Maximum time a block timestamp can be ahead of the local time
+    #[serde(with = "humantime_serde")]
+    #[serde(default)]
+    max_block_time_drift: Option<Duration>,
+
+    /// Network the default paths are derived from
+    #[serde(skip)]
+    network: Network,
 }
 
 impl ChainConfig {
@@ -54,11 +93,25 @@ impl ChainConfig {
         }
     }
 
+    pub(crate) fn set_network(&mut self, network: Network) {
+        self.network = network;
+    }
+
+    #[cfg(feature = "ephemeral")]
+    pub(crate) fn set_db_path(&mut self, db_path: PathBuf) {
+        self.db_path = Some(db_path);
+    }
+
+    #[cfg(feature = "ephemeral")]
+    pub(crate) fn set_consensus_keys_path(&mut self, path: PathBuf) {
+        self.consensus_keys_path = Some(path);
+    }
+
     pub(crate) fn db_path(&self) -> PathBuf {
         self.db_path.clone().unwrap_or_else(|| {
             let mut path = dirs::home_dir().expect("OS not supported");
             path.push(".dusk");
-            path.push(env!("CARGO_BIN_NAME"));
+            path.push(self.network.data_dir_name());
             path
         })
     }
@@ -69,7 +122,7 @@ impl ChainConfig {
             .unwrap_or_else(|| {
                 let mut path = dirs::home_dir().expect("OS not supported");
                 path.push(".dusk");
-                path.push(env!("CARGO_BIN_NAME"));
+                path.push(self.network.data_dir_name());
                 path.push("consensus.keys");
                 path
             })
@@ -78,6 +131,22 @@ impl ChainConfig {
             .to_string()
     }
 
+    pub(crate) fn watch_only(&self) -> bool {
+        self.watch_only
+    }
+
+    pub(crate) fn replica(&self) -> bool {
+        self.replica
+    }
+
+    pub(crate) fn remote_signer(&self) -> Option<RemoteSignerConfig> {
+        self.remote_signer.clone()
+    }
+
+    pub(crate) fn compact_candidates(&self) -> bool {
+        self.compact_candidates
+    }
+
     pub(crate) fn db_options(&self) -> DatabaseOptions {
         self.db_options.clone().unwrap_or_default()
     }
@@ -110,6 +179,41 @@ impl ChainConfig {
         self.block_gas_limit.unwrap_or(DEFAULT_BLOCK_GAS_LIMIT)
     }
 
+    pub(crate) fn gas_limit_adjustment(&self) -> Option<GasLimitAdjustment> {
+        self.gas_limit_adjustment
+    }
+
+    pub(crate) fn block_space_reservation(&self) -> BlockSpaceReservation {
+        self.block_space_reservation.clone().unwrap_or_default()
+    }
+
+    pub(crate) fn commit_retention(&self) -> Option<usize> {
+        self.commit_retention
+    }
+
+    pub(crate) fn commit_gc_interval(&self) -> Option<Duration> {
+        self.commit_gc_interval
+    }
+
+    /// Exports the message and block time bounds to the variables consensus
+    /// reads them from, unless they are already set.
+    ///
+    /// Must be called before any other thread is spawned.
+    pub(crate) fn export_time_bounds(&self) {
+        let bounds = [
+            ("RUSK_MAX_CLOCK_SKEW", self.max_clock_skew),
+            ("RUSK_FUTURE_MSG_TTL", self.future_msg_ttl),
+            ("RUSK_MAX_BLOCK_TIME_DRIFT", self.max_block_time_drift),
+        ];
+        for (var, bound) in bounds {
+            if let Some(bound) = bound {
+                if env::var_os(var).is_none() {
+                    env::set_var(var, bound.as_secs().to_string());
+                }
+            }
+        }
+    }
+
     pub(crate) fn genesis_timestamp(&self) -> u64 {
         self.genesis_timestamp
             .map(|t| {
@@ -0,0 +1,32 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct GrpcConfig {
+    /// Address the gRPC server listens to, disabled if not set
+    listen_address: Option<String>,
+
+    /// Token the requests must carry as `authorization: Bearer <token>`.
+    /// Required to listen to an address other than a loopback one.
+    auth_token: Option<String>,
+}
+
+impl GrpcConfig {
+    pub fn listen_addr(&self) -> Option<String> {
+        self.listen_address.clone()
+    }
+
+    pub fn auth_token(&self) -> Option<String> {
+        self.auth_token.clone()
+    }
+
+    #[cfg(feature = "ephemeral")]
+    pub(crate) fn set_listen_addr(&mut self, listen_addr: Option<String>) {
+        self.listen_address = listen_addr;
+    }
+}
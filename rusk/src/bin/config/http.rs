@@ -29,8 +29,18 @@ pub struct HttpConfig {
     pub ws_sub_channel_cap: usize,
     #[serde(default = "default_ws_event_channel_cap")]
     pub ws_event_channel_cap: usize,
+    /// Number of the most recent RUES events kept to be replayed to the
+    /// sessions resuming after a reconnection
+    #[serde(default = "default_event_store_capacity")]
+    pub event_store_capacity: usize,
     #[serde(with = "vec_header_map", default = "default_http_headers")]
     pub headers: HeaderMap,
+    /// Token authorizing the `admin` RUES requests, which are disabled if
+    /// not set.
+    pub admin_token: Option<String>,
+    /// Endpoints of the query replicas advertised to the clients
+    #[serde(default)]
+    pub replicas: Vec<String>,
 }
 
 // Custom deserialization function for `feeder_call_gas`.
@@ -74,6 +84,9 @@ impl Default for HttpConfig {
             listen_address: None,
             ws_sub_channel_cap: default_ws_sub_channel_cap(),
             ws_event_channel_cap: default_ws_event_channel_cap(),
+            event_store_capacity: default_event_store_capacity(),
+            admin_token: None,
+            replicas: vec![],
         }
     }
 }
@@ -94,6 +107,10 @@ const fn default_ws_event_channel_cap() -> usize {
     1024
 }
 
+const fn default_event_store_capacity() -> usize {
+    256
+}
+
 fn default_http_headers() -> HeaderMap {
     HeaderMap::new()
 }
@@ -105,6 +122,11 @@ impl HttpConfig {
             .unwrap_or("127.0.0.1:8080".into())
     }
 
+    #[cfg(feature = "ephemeral")]
+    pub(crate) fn set_listen_addr(&mut self, listen_addr: String) {
+        self.listen_address = Some(listen_addr);
+    }
+
     pub(crate) fn merge(&mut self, args: &Args) {
         // Overwrite config ws-listen-addr
         if let Some(http_listen_addr) = &args.http_listen_addr {
@@ -0,0 +1,57 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use node_data::identity::NodeIdentity;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+/// Identity the node signs its control messages to the peers with.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub(crate) struct IdentityConfig {
+    #[serde(default)]
+    sign_messages: bool,
+    /// File holding the secret key, `node_identity.key` in the database
+    /// directory if not given
+    key_path: Option<PathBuf>,
+}
+
+impl IdentityConfig {
+    /// Returns the identity of the node if signing is enabled, generating
+    /// and storing a new one if the key file doesn't exist.
+    pub(crate) fn identity(
+        &self,
+        db_path: &Path,
+    ) -> anyhow::Result<Option<NodeIdentity>> {
+        if !self.sign_messages {
+            return Ok(None);
+        }
+
+        let path = self
+            .key_path
+            .clone()
+            .unwrap_or_else(|| db_path.join("node_identity.key"));
+
+        let identity = if path.exists() {
+            let secret = fs::read(&path)?.try_into().map_err(|_| {
+                anyhow::anyhow!("Invalid identity key {}", path.display())
+            })?;
+            NodeIdentity::from_bytes(&secret)
+        } else {
+            let identity = NodeIdentity::generate();
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&path, identity.to_bytes())?;
+            info!("Generated node identity in {}", path.display());
+            identity
+        };
+
+        Ok(Some(identity))
+    }
+}
@@ -33,4 +33,15 @@ impl KadcastConfig {
             self.0.kadcast_id = Some(network_id)
         };
     }
+
+    #[cfg(feature = "ephemeral")]
+    pub(crate) fn set_addresses(
+        &mut self,
+        public_address: String,
+        bootstrapping_nodes: Vec<String>,
+    ) {
+        self.0.public_address = public_address;
+        self.0.listen_address = None;
+        self.0.bootstrapping_nodes = bootstrapping_nodes;
+    }
 }
@@ -0,0 +1,56 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Default size, in bytes, a log file is rotated at.
+const DEFAULT_MAX_SIZE: usize = 100 * 1024 * 1024;
+
+/// Default number of rotated log files to keep.
+const DEFAULT_MAX_FILES: usize = 7;
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub(crate) struct LogConfig {
+    /// Write the log to a rotating file as well, in JSON format
+    pub(crate) file: Option<LogFileConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct LogFileConfig {
+    pub(crate) path: PathBuf,
+
+    #[serde(default)]
+    pub(crate) rotation: LogRotation,
+
+    /// Size, in bytes, the file is rotated at when rotating by size
+    #[serde(default = "default_max_size")]
+    pub(crate) max_size: usize,
+
+    /// Number of rotated files to keep, the oldest being deleted
+    #[serde(default = "default_max_files")]
+    pub(crate) max_files: usize,
+}
+
+/// When the log file is rotated.
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum LogRotation {
+    Hourly,
+    #[default]
+    Daily,
+    /// Once the file exceeds `max_size` bytes
+    Size,
+}
+
+fn default_max_size() -> usize {
+    DEFAULT_MAX_SIZE
+}
+
+fn default_max_files() -> usize {
+    DEFAULT_MAX_FILES
+}
@@ -4,13 +4,27 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use node::mempool::policy::ExternalPolicyConfig;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Default)]
-pub(crate) struct MempoolConfig(node::mempool::conf::Params);
+pub(crate) struct MempoolConfig {
+    #[serde(flatten)]
+    params: node::mempool::conf::Params,
+
+    /// External engine deciding which transactions are relayed and included
+    /// in the generated blocks
+    policy: Option<ExternalPolicyConfig>,
+}
+
+impl MempoolConfig {
+    pub(crate) fn policy(&self) -> Option<ExternalPolicyConfig> {
+        self.policy.clone()
+    }
+}
 
 impl From<MempoolConfig> for node::mempool::conf::Params {
     fn from(conf: MempoolConfig) -> Self {
-        conf.0
+        conf.params
     }
 }
@@ -0,0 +1,145 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// Network the node joins, selecting the profile the configuration is
+/// built upon.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    ValueEnum,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Network {
+    Mainnet,
+    Testnet,
+    Devnet,
+    /// No profile, everything comes from the configuration file
+    #[default]
+    Custom,
+}
+
+impl Network {
+    fn profile(&self) -> &'static str {
+        match self {
+            Network::Mainnet => include_str!("network/mainnet.toml"),
+            Network::Testnet => include_str!("network/testnet.toml"),
+            Network::Devnet => include_str!("network/devnet.toml"),
+            Network::Custom => "",
+        }
+    }
+
+    /// Returns the configuration embedded for the network.
+    pub(crate) fn profile_table(&self) -> toml::Table {
+        toml::from_str(self.profile()).expect("embedded profile to be valid")
+    }
+
+    /// Name of the directory, in `~/.dusk`, holding the node data.
+    ///
+    /// Mainnet and custom networks keep using the directory named after the
+    /// binary, so existing installations are unaffected.
+    pub(crate) fn data_dir_name(&self) -> String {
+        match self {
+            Network::Mainnet | Network::Custom => {
+                env!("CARGO_BIN_NAME").to_string()
+            }
+            network => format!("{}-{network}", env!("CARGO_BIN_NAME")),
+        }
+    }
+
+    /// Directory holding the state of the network, when it is not the one of
+    /// `rusk-profile`.
+    ///
+    /// The state of the networks having their own data directory is kept in
+    /// it, so that a node switching network never opens the state of another.
+    pub(crate) fn state_dir(&self) -> Option<PathBuf> {
+        match self {
+            Network::Mainnet | Network::Custom => None,
+            network => {
+                let mut path = dirs::home_dir()?;
+                path.push(".dusk");
+                path.push(network.data_dir_name());
+                path.push("state");
+                Some(path)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Network::Mainnet => "mainnet",
+            Network::Testnet => "testnet",
+            Network::Devnet => "devnet",
+            Network::Custom => "custom",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Merges `overrides` into `base`, recursing into the tables present in both.
+pub(crate) fn merge_tables(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(value)) => {
+                merge_tables(base, value)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_are_valid() {
+        for network in Network::value_variants() {
+            network.profile_table();
+        }
+    }
+
+    #[test]
+    fn overrides_take_precedence() {
+        let mut base = Network::Testnet.profile_table();
+        let overrides = toml::from_str(
+            "[kadcast]\nbootstrapping_nodes = ['127.0.0.1:9000']",
+        )
+        .unwrap();
+        merge_tables(&mut base, overrides);
+
+        let kadcast = base["kadcast"].as_table().unwrap();
+        assert_eq!(kadcast["kadcast_id"].as_integer(), Some(0x02));
+        assert_eq!(kadcast["bootstrapping_nodes"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn networks_keep_their_state_apart() {
+        assert_eq!(Network::Mainnet.state_dir(), None);
+        assert_eq!(Network::Custom.state_dir(), None);
+
+        let testnet = Network::Testnet.state_dir().unwrap();
+        let devnet = Network::Devnet.state_dir().unwrap();
+        assert_ne!(testnet, devnet);
+        assert!(testnet.ends_with("rusk-testnet/state"));
+    }
+}
@@ -0,0 +1,33 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::num::NonZeroUsize;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub(crate) struct ProverConfig {
+    #[serde(default)]
+    remote_urls: Vec<String>,
+    #[serde(default)]
+    async_jobs: bool,
+    workers: Option<usize>,
+    queue_size: Option<usize>,
+    cache_size: Option<NonZeroUsize>,
+}
+
+impl From<ProverConfig> for rusk::http::ProverConfig {
+    fn from(conf: ProverConfig) -> Self {
+        let default = Self::default();
+        Self {
+            remote_urls: conf.remote_urls,
+            async_jobs: conf.async_jobs,
+            workers: conf.workers.unwrap_or(default.workers),
+            queue_size: conf.queue_size.unwrap_or(default.queue_size),
+            cache_size: conf.cache_size.unwrap_or(default.cache_size),
+        }
+    }
+}
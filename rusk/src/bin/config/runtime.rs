@@ -0,0 +1,81 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::io;
+
+use serde::{Deserialize, Serialize};
+use tokio::runtime::{Builder, Runtime};
+
+/// Minimum number of worker threads, below which tokio cannot build the
+/// runtime.
+pub(crate) const MIN_WORKER_THREADS: usize = 1;
+
+const DEFAULT_WORKER_THREADS: usize = 8;
+
+/// Sizing of the threads the node runs on.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub(crate) struct RuntimeConfig {
+    /// Number of threads running the asynchronous tasks
+    worker_threads: Option<usize>,
+    /// Maximum number of threads running the blocking operations, such as
+    /// the execution of the VM and the proving, the tokio default if not
+    /// given
+    max_blocking_threads: Option<usize>,
+}
+
+impl RuntimeConfig {
+    pub(crate) fn worker_threads(&self) -> usize {
+        self.worker_threads.unwrap_or(DEFAULT_WORKER_THREADS)
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        let workers = self.worker_threads();
+        if workers < MIN_WORKER_THREADS {
+            return Err(format!(
+                "worker_threads is {workers}, at least {MIN_WORKER_THREADS} \
+                 is required"
+            ));
+        }
+        if self.max_blocking_threads == Some(0) {
+            return Err("max_blocking_threads cannot be 0".into());
+        }
+        Ok(())
+    }
+
+    /// Builds the multi-threaded runtime the node runs on, whose threads are
+    /// the critical ones of the panic hook.
+    pub(crate) fn build(&self) -> io::Result<Runtime> {
+        self.validate()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        let mut builder = Builder::new_multi_thread();
+        builder.enable_all().worker_threads(self.worker_threads());
+        #[cfg(feature = "chain")]
+        builder.thread_name(rusk::node::NODE_THREAD_NAME);
+        if let Some(max) = self.max_blocking_threads {
+            builder.max_blocking_threads(max);
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn worker_threads_are_validated() {
+        assert!(RuntimeConfig::default().validate().is_ok());
+
+        let config: RuntimeConfig = toml::from_str("worker_threads = 0")
+            .expect("config to be valid TOML");
+        assert!(config.validate().is_err());
+
+        let config: RuntimeConfig = toml::from_str("max_blocking_threads = 0")
+            .expect("config to be valid TOML");
+        assert!(config.validate().is_err());
+    }
+}
@@ -4,6 +4,9 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+#[cfg(feature = "telemetry")]
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 use crate::args::Args;
@@ -11,6 +14,36 @@ use crate::args::Args;
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct TelemetryConfig {
     listen_address: Option<String>,
+
+    #[cfg(feature = "telemetry")]
+    otlp: Option<OtlpConfig>,
+}
+
+/// Export of the node spans to an OpenTelemetry collector.
+#[cfg(feature = "telemetry")]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct OtlpConfig {
+    /// gRPC endpoint of the collector
+    #[serde(default = "default_otlp_endpoint")]
+    pub endpoint: String,
+
+    /// Name the node is identified with, as the `service.name` resource
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+
+    /// Additional resource attributes, such as the network or the host
+    #[serde(default)]
+    pub resource: BTreeMap<String, String>,
+}
+
+#[cfg(feature = "telemetry")]
+fn default_otlp_endpoint() -> String {
+    "http://localhost:4317".into()
+}
+
+#[cfg(feature = "telemetry")]
+fn default_service_name() -> String {
+    env!("CARGO_BIN_NAME").into()
 }
 
 impl TelemetryConfig {
@@ -18,6 +51,16 @@ impl TelemetryConfig {
         self.listen_address.clone()
     }
 
+    #[cfg(feature = "telemetry")]
+    pub fn otlp(&self) -> Option<&OtlpConfig> {
+        self.otlp.as_ref()
+    }
+
+    #[cfg(feature = "ephemeral")]
+    pub(crate) fn set_listen_addr(&mut self, listen_addr: Option<String>) {
+        self.listen_address = listen_addr;
+    }
+
     pub(crate) fn merge(&mut self, args: &Args) {
         if let Some(listen_addr) = &args.telemetry_listen_addr {
             self.listen_address = Some(listen_addr.into());
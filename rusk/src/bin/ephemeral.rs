@@ -4,31 +4,182 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use dusk_core::signatures::bls::{
+    PublicKey as BlsPublicKey, SecretKey as BlsSecretKey,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use rusk_recovery_tools::state::tar;
 use std::env;
-use std::fs::File;
-use std::io::{Read, Result};
-use std::path::PathBuf;
+use std::fs::{self, File};
+use std::io::{Error, ErrorKind, Read, Result};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::time::Duration;
 use tempfile::TempDir;
-use tracing::error;
+use tokio::time::sleep;
+use tracing::{error, info};
+
+use crate::args::command::localnet::LocalnetCommand;
+use crate::config::Config;
+
+/// Interval at which the localnet checks the status of its nodes
+const LOCALNET_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 pub(crate) fn configure(state_zip: &PathBuf) -> Result<Option<TempDir>> {
     let tmpdir = tempfile::tempdir()?;
 
     let state_dir = tmpdir.path().join("state");
+    unpack_state(state_zip, &state_dir)?;
+
+    env::set_var("RUSK_STATE_PATH", state_dir.as_os_str());
 
+    Ok(Some(tmpdir))
+}
+
+fn unpack_state(state_zip: &Path, state_dir: &Path) -> Result<()> {
     let mut f = File::open(state_zip)?;
     let mut data = Vec::new();
     f.read_to_end(&mut data)?;
 
-    let unarchive = tar::unarchive(&data[..], state_dir.as_path());
-
-    if let Err(e) = unarchive {
+    tar::unarchive(&data[..], state_dir).map_err(|e| {
         error!("Invalid state input {}", e);
-        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, ""));
+        Error::new(ErrorKind::InvalidInput, "")
+    })
+}
+
+/// Spawns a local network of `nodes` interlinked nodes sharing the same
+/// state archive, and waits for them to exit.
+///
+/// Every node gets its own temporary directory holding the unpacked state,
+/// its database, a distinct consensus key derived from the node index (as
+/// `node_data::bls::PublicKey::from_sk_seed_u64` does) and a config file
+/// bootstrapping it to all the other nodes.
+pub(crate) async fn localnet(
+    cmd: &LocalnetCommand,
+    config: &Config,
+) -> Result<()> {
+    if cmd.nodes == 0 {
+        return Err(Error::new(ErrorKind::InvalidInput, "No node to spawn"));
     }
 
-    env::set_var("RUSK_STATE_PATH", state_dir.as_os_str());
+    let kadcast_addrs = local_addrs(cmd.kadcast_port, cmd.nodes)?;
+    let http_addrs = local_addrs(cmd.http_port, cmd.nodes)?;
 
-    Ok(Some(tmpdir))
+    let tmpdir = tempfile::tempdir()?;
+    info!("Localnet folder: {}", tmpdir.path().display());
+
+    let exe = env::current_exe()?;
+
+    let mut children = Vec::with_capacity(cmd.nodes as usize);
+    for i in 0..cmd.nodes {
+        let node_dir = tmpdir.path().join(format!("node_{i}"));
+        let state_dir = node_dir.join("state");
+        unpack_state(&cmd.state_path, &state_dir)?;
+
+        let keys_path = node_dir.join("consensus.keys");
+        let rng = &mut StdRng::seed_from_u64(i as u64);
+        let sk = BlsSecretKey::random(rng);
+        let pk = BlsPublicKey::from(&sk);
+        node_data::bls::save_keys(
+            &keys_path,
+            &sk,
+            &pk,
+            &cmd.keys_password,
+            rng,
+        )
+        .map_err(|e| Error::new(ErrorKind::Other, e))?;
+
+        let kadcast_addr = &kadcast_addrs[i as usize];
+        let bootstrap = kadcast_addrs
+            .iter()
+            .filter(|&addr| addr != kadcast_addr)
+            .cloned()
+            .collect();
+
+        let mut node_config = config.clone();
+        node_config
+            .kadcast
+            .set_addresses(kadcast_addr.clone(), bootstrap);
+        node_config
+            .http
+            .set_listen_addr(http_addrs[i as usize].clone());
+        node_config.telemetry.set_listen_addr(None);
+        #[cfg(feature = "grpc")]
+        node_config.grpc.set_listen_addr(None);
+        node_config.chain.set_db_path(node_dir.join("db"));
+        node_config.chain.set_consensus_keys_path(keys_path);
+
+        let config_path = node_dir.join("rusk.toml");
+        let toml = toml::to_string(&node_config)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        fs::write(&config_path, toml)?;
+
+        let child = Command::new(&exe)
+            .arg("--config")
+            .arg(&config_path)
+            .env("RUSK_STATE_PATH", &state_dir)
+            .env("DUSK_CONSENSUS_KEYS_PASS", &cmd.keys_password)
+            .env_remove("RUSK_CONFIG_TOML")
+            .env_remove("KADCAST_BOOTSTRAP")
+            .env_remove("KADCAST_PUBLIC_ADDRESS")
+            .env_remove("KADCAST_LISTEN_ADDRESS")
+            .spawn()?;
+
+        info!(
+            "Started node {i} (pid {}) on {} with key {}",
+            child.id(),
+            kadcast_addr,
+            node_data::bls::PublicKey::new(pk).to_bs58(),
+        );
+        children.push(child);
+    }
+
+    let result = wait_nodes(&mut children).await;
+    kill_nodes(&mut children);
+    result
+}
+
+/// Local addresses of `nodes` nodes, on consecutive ports from `first_port`.
+fn local_addrs(first_port: u16, nodes: u16) -> Result<Vec<String>> {
+    (0..nodes)
+        .map(|i| {
+            let port = first_port.checked_add(i).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("No port left for node {i} from {first_port}"),
+                )
+            })?;
+            Ok(format!("127.0.0.1:{port}"))
+        })
+        .collect()
+}
+
+/// Waits until any of the nodes exits, returning an error if it failed.
+async fn wait_nodes(children: &mut [Child]) -> Result<()> {
+    loop {
+        for (i, child) in children.iter_mut().enumerate() {
+            if let Some(status) = child.try_wait()? {
+                if status.success() {
+                    info!("Node {i} exited");
+                    return Ok(());
+                }
+                error!("Node {i} exited with {status}");
+                return Err(Error::new(
+                    ErrorKind::Other,
+                    format!("Node {i} failed"),
+                ));
+            }
+        }
+        sleep(LOCALNET_POLL_INTERVAL).await;
+    }
+}
+
+fn kill_nodes(children: &mut [Child]) {
+    for child in children {
+        if let Ok(None) = child.try_wait() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
 }
@@ -4,19 +4,51 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use std::sync::Arc;
+
+use file_rotate::compression::Compression;
+use file_rotate::suffix::{AppendTimestamp, FileLimit};
+use file_rotate::{ContentLimit, FileRotate, TimeFrequency};
 use tracing::subscriber::SetGlobalDefaultError;
-use tracing_subscriber::{
-    fmt::{
-        format::{DefaultFields, Format},
-        SubscriberBuilder,
-    },
-    EnvFilter,
-};
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+use crate::config::log::{LogFileConfig, LogRotation};
 
 pub struct Log {
     level: tracing::Level,
     filter: String,
     format: Option<String>,
+    file: Option<LogFileConfig>,
+    #[cfg(feature = "telemetry")]
+    tracer: Option<opentelemetry_sdk::trace::Tracer>,
+}
+
+/// Handle to change the log level and filter of the registered subscriber.
+///
+/// It also keeps the writer of the log file alive, flushing it when the last
+/// handle is dropped.
+#[derive(Clone)]
+pub struct LogHandle {
+    filter: reload::Handle<EnvFilter, Registry>,
+    _file_guard: Option<Arc<WorkerGuard>>,
+}
+
+impl LogHandle {
+    pub fn reload(
+        &self,
+        level: tracing::Level,
+        filter: &str,
+    ) -> Result<(), reload::Error> {
+        self.filter.reload(env_filter(level, filter))
+    }
+}
+
+/// Generate a filter with the desired default log level and optional log
+/// filter.
+fn env_filter(level: tracing::Level, filter: &str) -> EnvFilter {
+    EnvFilter::new(filter).add_directive(level.into())
 }
 
 impl Log {
@@ -25,71 +57,111 @@ impl Log {
             level,
             filter,
             format: None,
+            file: None,
+            #[cfg(feature = "telemetry")]
+            tracer: None,
         }
     }
 
-    pub fn with_format(mut self, format: String) -> Self {
-        self.format = Some(format);
+    /// Writes the log to a rotating file too, in JSON format.
+    pub fn with_file(mut self, file: LogFileConfig) -> Self {
+        self.file = Some(file);
         self
     }
 
-    fn subscriber(
-        &self,
-    ) -> SubscriberBuilder<DefaultFields, Format, EnvFilter> {
-        // Generate a subscriber with the desired default log level and optional
-        // log filter.
-        tracing_subscriber::fmt::Subscriber::builder().with_env_filter(
-            EnvFilter::new(self.filter.as_str())
-                .add_directive(self.level.into()),
-        )
+    /// Exports the spans through the given tracer, in addition to logging
+    /// the events.
+    #[cfg(feature = "telemetry")]
+    pub fn with_tracer(
+        mut self,
+        tracer: opentelemetry_sdk::trace::Tracer,
+    ) -> Self {
+        self.tracer = Some(tracer);
+        self
     }
 
-    pub fn register(self) -> Result<(), SetGlobalDefaultError> {
-        match self.format.clone() {
-            Some(format) => self.register_format(&format),
-            None => self.register_simple(),
-        }
+    pub fn with_format(mut self, format: String) -> Self {
+        self.format = Some(format);
+        self
     }
 
-    #[allow(dead_code)]
-    fn register_simple(self) -> Result<(), SetGlobalDefaultError> {
-        let subscriber = self
-            .subscriber()
-            .with_level(false)
-            .without_time()
-            .with_target(false)
-            .finish();
-        tracing::subscriber::set_global_default(subscriber)
-    }
+    /// Registers the subscriber as global, so it will be used as the default
+    /// in all threads for the remainder of the duration of the program,
+    /// similar to how `loggers` work in the `log` crate.
+    pub fn register(self) -> Result<LogHandle, SetGlobalDefaultError> {
+        let (filter, handle) =
+            reload::Layer::new(env_filter(self.level, &self.filter));
+        let registry = tracing_subscriber::registry().with(filter);
 
-    fn register_format(
-        self,
-        log_format: &str,
-    ) -> Result<(), SetGlobalDefaultError> {
-        let subscriber = self.subscriber();
-        // Set the subscriber as global.
-        // so this subscriber will be used as the default in all threads for the
-        // remainder of the duration of the program, similar to how `loggers`
-        // work in the `log` crate.
-        match log_format {
-            "json" => {
-                let subscriber = subscriber
-                    .json()
-                    .with_current_span(false)
-                    .flatten_event(true)
-                    .finish();
+        let (file_writer, file_guard) = match &self.file {
+            Some(file) => {
+                let (writer, guard) = file_writer(file);
+                (Some(writer), Some(Arc::new(guard)))
+            }
+            None => (None, None),
+        };
+        let registry = registry.with(file_writer.map(|writer| {
+            fmt::layer()
+                .json()
+                .with_current_span(false)
+                .flatten_event(true)
+                .with_writer(writer)
+        }));
 
-                tracing::subscriber::set_global_default(subscriber)
+        #[cfg(feature = "telemetry")]
+        let registry = registry.with(
+            self.tracer
+                .map(|t| tracing_opentelemetry::layer().with_tracer(t)),
+        );
+
+        match self.format.as_deref() {
+            None => {
+                let layer = fmt::layer()
+                    .with_level(false)
+                    .without_time()
+                    .with_target(false);
+                tracing::subscriber::set_global_default(registry.with(layer))
             }
-            "plain" => {
-                let subscriber = subscriber.with_ansi(false).finish();
-                tracing::subscriber::set_global_default(subscriber)
+            Some("json") => {
+                let layer = fmt::layer()
+                    .json()
+                    .with_current_span(false)
+                    .flatten_event(true);
+                tracing::subscriber::set_global_default(registry.with(layer))
             }
-            "coloured" => {
-                let subscriber = subscriber.finish();
-                tracing::subscriber::set_global_default(subscriber)
+            Some("plain") => {
+                let layer = fmt::layer().with_ansi(false);
+                tracing::subscriber::set_global_default(registry.with(layer))
             }
+            Some("coloured") => tracing::subscriber::set_global_default(
+                registry.with(fmt::layer()),
+            ),
             _ => unreachable!(),
-        }
+        }?;
+
+        Ok(LogHandle {
+            filter: handle,
+            _file_guard: file_guard,
+        })
     }
 }
+
+/// Creates a non-blocking writer to the rotating log file.
+fn file_writer(file: &LogFileConfig) -> (NonBlocking, WorkerGuard) {
+    let content_limit = match file.rotation {
+        LogRotation::Hourly => ContentLimit::Time(TimeFrequency::Hourly),
+        LogRotation::Daily => ContentLimit::Time(TimeFrequency::Daily),
+        LogRotation::Size => ContentLimit::BytesSurpassed(file.max_size),
+    };
+
+    let writer = FileRotate::new(
+        &file.path,
+        AppendTimestamp::default(FileLimit::MaxFiles(file.max_files)),
+        content_limit,
+        Compression::None,
+        #[cfg(unix)]
+        None,
+    );
+
+    tracing_appender::non_blocking(writer)
+}
@@ -9,6 +9,9 @@ mod config;
 #[cfg(feature = "ephemeral")]
 mod ephemeral;
 mod log;
+#[cfg(feature = "telemetry")]
+mod otlp;
+mod reload;
 
 #[cfg(feature = "chain")]
 use tracing::info;
@@ -23,16 +26,61 @@ use rusk::http::HttpServerConfig;
 use rusk::Result;
 
 use crate::config::Config;
+use crate::reload::ConfigWatcher;
 
-// Number of workers should be at least `ACCUMULATOR_WORKERS_AMOUNT` from
-// `dusk_consensus::config`.
-#[tokio::main(flavor = "multi_thread", worker_threads = 8)]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = args::Args::parse();
 
+    // The configuration is checked before being loaded, which panics if it is
+    // invalid.
+    if let Some(args::command::Command::CheckConfig(check)) = &args.command {
+        return check.run(&args);
+    }
+
     let config = Config::from(&args);
 
-    let log = Log::new(config.log_level(), config.log_filter());
+    // The state of each network is kept apart, unless its path is forced.
+    // The variable is set before any other thread is spawned
+    if std::env::var_os("RUSK_STATE_PATH").is_none() {
+        if let Some(state_dir) = config.network.state_dir() {
+            std::env::set_var("RUSK_STATE_PATH", state_dir);
+        }
+    }
+    #[cfg(feature = "chain")]
+    config.chain.export_time_bounds();
+
+    // The runtime is sized by the configuration, so it is built only once
+    // the configuration is loaded
+    config.runtime.build()?.block_on(run(args, config))
+}
+
+async fn run(
+    args: args::Args,
+    config: Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Database inspection prints JSON only, so the log is not registered
+    #[cfg(feature = "chain")]
+    if let Some(args::command::Command::Db(db)) = args.command.clone() {
+        db.run(&config.chain.db_path())?;
+        return Ok(());
+    }
+
+    #[cfg(feature = "chain")]
+    if let Some(args::command::Command::Keys(keys)) = args.command.clone() {
+        keys.run(
+            config.chain.consensus_keys_path().as_ref(),
+            &rusk_profile::get_rusk_state_dir()?,
+        )?;
+        return Ok(());
+    }
+
+    #[cfg(feature = "debug-state")]
+    if let Some(args::command::Command::State(state)) = args.command.clone() {
+        state.run(&rusk_profile::get_rusk_state_dir()?)?;
+        return Ok(());
+    }
+
+    let mut log = Log::new(config.log_level(), config.log_filter());
 
     #[cfg(any(feature = "recovery-state", feature = "recovery-keys"))]
     if let Some(args::command::Command::Recovery(recovery)) =
@@ -44,7 +92,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
-    log.with_format(config.log_type()).register()?;
+    if let Some(file) = config.log.file.clone() {
+        if let Some(dir) = file.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        log = log.with_file(file);
+    }
+
+    #[cfg(feature = "telemetry")]
+    let otlp = config
+        .telemetry
+        .otlp()
+        .map(otlp::OtlpExporter::install)
+        .transpose()?;
+    #[cfg(feature = "telemetry")]
+    let log = match &otlp {
+        Some(otlp) => log.with_tracer(otlp.tracer()),
+        None => log,
+    };
+
+    let log = log.with_format(config.log_type()).register()?;
+
+    #[cfg(feature = "ephemeral")]
+    if let Some(args::command::Command::Localnet(localnet)) = &args.command {
+        ephemeral::localnet(localnet, &config).await?;
+        return Ok(());
+    }
 
     #[cfg(feature = "ephemeral")]
     let tempdir = match args.state_path {
@@ -52,10 +125,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         None => None,
     };
 
+    let config_watcher = ConfigWatcher::new(
+        &args,
+        log,
+        #[cfg(feature = "chain")]
+        &config,
+    );
+
     let mut node_builder = Builder::default();
 
     #[cfg(feature = "chain")]
     {
+        info!("Using the {} network profile", config.network);
+
         let state_dir = rusk_profile::get_rusk_state_dir()?;
         info!("Using state from {state_dir:?}");
 
@@ -68,16 +150,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         #[cfg(not(feature = "ephemeral"))]
         let db_path = config.chain.db_path();
 
+        let identity = config.identity.identity(&db_path)?;
+
         node_builder = node_builder
             .with_feeder_call_gas(config.http.feeder_call_gas)
             .with_db_path(db_path)
             .with_db_options(config.chain.db_options())
             .with_kadcast(config.kadcast)
+            .with_bandwidth(config.bandwidth)
+            .with_identity(identity)
+            .with_peer_snapshot(config.peer_snapshot)
             .with_consensus_keys(config.chain.consensus_keys_path())
+            .with_watch_only(config.chain.watch_only())
+            .with_replica(config.chain.replica())
+            .with_replicas(config.http.replicas.clone())
+            .with_remote_signer(config.chain.remote_signer())
+            .with_compact_candidates(config.chain.compact_candidates())
             .with_databroker(config.databroker)
             .with_telemetry(config.telemetry.listen_addr())
             .with_chain_queue_size(config.chain.max_queue_size())
             .with_genesis_timestamp(config.chain.genesis_timestamp())
+            .with_tx_policy(config.mempool.policy())
             .with_mempool(config.mempool.into())
             .with_state_dir(state_dir)
             .with_generation_timeout(config.chain.generation_timeout())
@@ -87,9 +180,45 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             )
             .with_min_deploy_points(config.chain.min_deploy_points())
             .with_min_gas_limit(config.chain.min_gas_limit())
-            .with_block_gas_limit(config.chain.block_gas_limit());
+            .with_block_gas_limit(config.chain.block_gas_limit())
+            .with_gas_limit_adjustment(config.chain.gas_limit_adjustment())
+            .with_block_space_reservation(
+                config.chain.block_space_reservation(),
+            )
+            .with_commit_gc(
+                config.chain.commit_retention(),
+                config.chain.commit_gc_interval(),
+            )
+            .with_artifacts(config.artifacts)
+            .with_config_reload(config_watcher.node_reload());
+
+        #[cfg(feature = "grpc")]
+        {
+            node_builder = node_builder
+                .with_grpc(config.grpc.listen_addr(), config.grpc.auth_token());
+        }
+
+        #[cfg(feature = "faucet")]
+        {
+            node_builder = node_builder.with_faucet(config.faucet);
+        }
+
+        #[cfg(feature = "chaos")]
+        {
+            if config.chaos.is_some()
+                && config.network == config::network::Network::Mainnet
+            {
+                return Err("Faults cannot be injected on mainnet".into());
+            }
+            node_builder = node_builder.with_chaos(config.chaos);
+        }
     };
 
+    #[cfg(feature = "prover")]
+    {
+        node_builder = node_builder.with_prover(config.prover.into());
+    }
+
     if config.http.listen {
         let http_builder = HttpServerConfig {
             address: config.http.listen_addr(),
@@ -97,6 +226,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             key: config.http.key,
             headers: config.http.headers,
             ws_event_channel_cap: config.http.ws_event_channel_cap,
+            event_store_capacity: config.http.event_store_capacity,
+            admin_token: config.http.admin_token,
         };
         node_builder = node_builder.with_http(http_builder)
     }
@@ -109,7 +240,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         node_builder = node_builder.with_revert();
     }
 
-    if let Err(e) = node_builder.build_and_run().await {
+    config_watcher.spawn();
+
+    let result = node_builder.build_and_run().await;
+
+    #[cfg(feature = "telemetry")]
+    if let Some(otlp) = otlp {
+        otlp.shutdown();
+    }
+
+    if let Err(e) = result {
         tracing::error!("node terminated with err: {}", e);
         return Err(e.into());
     }
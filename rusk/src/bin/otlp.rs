@@ -0,0 +1,58 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use opentelemetry::trace::{TraceError, TracerProvider as _};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::{Config, Tracer, TracerProvider};
+use opentelemetry_sdk::{runtime, Resource};
+
+use crate::config::telemetry::OtlpConfig;
+
+/// Exports the spans of the node to an OpenTelemetry collector.
+pub(crate) struct OtlpExporter {
+    provider: TracerProvider,
+}
+
+impl OtlpExporter {
+    /// Installs a batch exporter sending the spans to the configured
+    /// collector. Must be called from within the tokio runtime.
+    pub fn install(config: &OtlpConfig) -> Result<Self, TraceError> {
+        let mut attributes =
+            vec![KeyValue::new("service.name", config.service_name.clone())];
+        attributes.extend(
+            config
+                .resource
+                .iter()
+                .map(|(k, v)| KeyValue::new(k.clone(), v.clone())),
+        );
+
+        let provider = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .tonic()
+                    .with_endpoint(&config.endpoint),
+            )
+            .with_trace_config(
+                Config::default().with_resource(Resource::new(attributes)),
+            )
+            .install_batch(runtime::Tokio)?;
+
+        Ok(Self { provider })
+    }
+
+    pub fn tracer(&self) -> Tracer {
+        self.provider.tracer(env!("CARGO_BIN_NAME"))
+    }
+
+    /// Flushes the pending spans and stops the exporter.
+    pub fn shutdown(self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Cannot shutdown the OTLP exporter: {e}");
+        }
+    }
+}
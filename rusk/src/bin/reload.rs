@@ -0,0 +1,95 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::error::Error;
+use std::sync::Arc;
+
+use tokio::signal::unix::{signal, SignalKind};
+#[cfg(feature = "chain")]
+use tokio::sync::watch;
+use tokio::sync::Notify;
+use tracing::{error, info, warn};
+
+use crate::args::Args;
+use crate::config::Config;
+use crate::log::LogHandle;
+
+/// Reloads the configuration on SIGHUP, or when requested through the `admin`
+/// RUES component, and applies the log level and filter, together with the
+/// runtime configuration of the node, without restarting.
+pub(crate) struct ConfigWatcher {
+    args: Args,
+    log: LogHandle,
+    requests: Arc<Notify>,
+    #[cfg(feature = "chain")]
+    updates: watch::Sender<rusk::RuntimeConfig>,
+}
+
+impl ConfigWatcher {
+    pub fn new(
+        args: &Args,
+        log: LogHandle,
+        #[cfg(feature = "chain")] config: &Config,
+    ) -> Self {
+        Self {
+            args: args.clone(),
+            log,
+            requests: Arc::new(Notify::new()),
+            #[cfg(feature = "chain")]
+            updates: watch::channel(config.runtime()).0,
+        }
+    }
+
+    /// Returns the channels connecting the node to this watcher.
+    #[cfg(feature = "chain")]
+    pub fn node_reload(&self) -> rusk::ConfigReload {
+        rusk::ConfigReload {
+            requests: self.requests.clone(),
+            updates: self.updates.subscribe(),
+        }
+    }
+
+    /// Spawns the task waiting for reload requests.
+    pub fn spawn(self) {
+        tokio::spawn(async move {
+            if let Err(e) = self.run().await {
+                error!("Configuration watcher terminated with err: {e}");
+            }
+        });
+    }
+
+    async fn run(self) -> std::io::Result<()> {
+        let mut hangup = signal(SignalKind::hangup())?;
+
+        loop {
+            tokio::select! {
+                _ = hangup.recv() => {
+                    info!("Received SIGHUP, reloading configuration");
+                }
+                _ = self.requests.notified() => {
+                    info!("Received request to reload configuration");
+                }
+            }
+
+            match self.reload() {
+                Ok(()) => info!("Configuration reloaded"),
+                Err(e) => warn!("Cannot reload configuration: {e}"),
+            }
+        }
+    }
+
+    fn reload(&self) -> Result<(), Box<dyn Error>> {
+        let config = Config::load(&self.args)?;
+
+        self.log
+            .reload(config.try_log_level()?, &config.log_filter())?;
+
+        #[cfg(feature = "chain")]
+        self.updates.send_replace(config.runtime());
+
+        Ok(())
+    }
+}
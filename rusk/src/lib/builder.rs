@@ -7,7 +7,7 @@
 #[cfg(feature = "chain")]
 mod node;
 #[cfg(feature = "chain")]
-pub use node::RuskNodeBuilder as Builder;
+pub use node::{ConfigReload, RuntimeConfig, RuskNodeBuilder as Builder};
 
 #[cfg(not(feature = "chain"))]
 mod http_only;
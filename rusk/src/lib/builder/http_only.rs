@@ -8,10 +8,14 @@ use tokio::sync::broadcast;
 use tracing::info;
 
 use crate::http::{DataSources, HttpServer, HttpServerConfig};
+#[cfg(feature = "prover")]
+use crate::http::{ProverConfig, ProverService};
 
 #[derive(Default)]
 pub struct RuskHttpBuilder {
     http: Option<HttpServerConfig>,
+    #[cfg(feature = "prover")]
+    prover: ProverConfig,
 }
 
 impl RuskHttpBuilder {
@@ -20,8 +24,19 @@ impl RuskHttpBuilder {
         self
     }
 
+    #[cfg(feature = "prover")]
+    pub fn with_prover(mut self, prover: ProverConfig) -> Self {
+        self.prover = prover;
+        self
+    }
+
     pub async fn build_and_run(self) -> anyhow::Result<()> {
-        let (_rues_sender, rues_receiver) = broadcast::channel(1);
+        let channel_cap = self
+            .http
+            .as_ref()
+            .map(|h| h.ws_event_channel_cap)
+            .unwrap_or(1);
+        let (_rues_sender, rues_receiver) = broadcast::channel(channel_cap);
 
         let mut _ws_server = None;
         if let Some(http) = self.http {
@@ -31,7 +46,9 @@ impl RuskHttpBuilder {
             let mut handler = DataSources::default();
 
             #[cfg(feature = "prover")]
-            handler.sources.push(Box::new(rusk_prover::LocalProver));
+            handler
+                .sources
+                .push(Box::new(ProverService::new(self.prover, _rues_sender)));
 
             let cert_and_key = match (http.cert, http.key) {
                 (Some(cert), Some(key)) => Some((cert, key)),
@@ -43,6 +60,7 @@ impl RuskHttpBuilder {
                     handler,
                     rues_receiver,
                     http.ws_event_channel_cap,
+                    http.event_store_capacity,
                     http.address,
                     http.headers,
                     cert_and_key,
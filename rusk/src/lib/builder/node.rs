@@ -5,35 +5,67 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::time::Duration;
 
 use kadcast::config::Config as KadcastConfig;
-use node::chain::ChainSrv;
+use node::chain::{
+    BlockSpaceReservation, ChainSrv, GasLimitAdjustment, GasLimitRule,
+    RemoteSignerConfig,
+};
+#[cfg(feature = "chaos")]
+use node::chaos::{Chaos, ChaosConfig};
 use node::database::rocksdb;
-use node::database::{DatabaseOptions, DB};
+use node::database::{AuditEntry, AuditLog, DatabaseOptions, DB};
 use node::databroker::conf::Params as BrokerParam;
 use node::databroker::DataBrokerSrv;
 use node::mempool::conf::Params as MempoolParam;
+use node::mempool::policy::{
+    BlockExclusions, ExternalPolicy, ExternalPolicyConfig, TxPolicy,
+};
 use node::mempool::MempoolSrv;
-use node::network::Kadcast;
+use node::network::{BandwidthConfig, Kadcast, PeerSnapshotConfig};
+use node::switches::RuntimeSwitches;
 use node::telemetry::TelemetrySrv;
 use node::{LongLivedService, Node};
+use node_data::identity::NodeIdentity;
 
-use tokio::sync::{broadcast, mpsc};
-use tracing::info;
+use serde_json::json;
+use tokio::sync::{broadcast, mpsc, watch, Notify};
+use tracing::{info, warn};
 #[cfg(feature = "archive")]
 use {node::archive::Archive, node::archive::ArchivistSrv};
 
-use crate::http::{DataSources, HttpServer, HttpServerConfig};
-use crate::node::{ChainEventStreamer, RuskNode, Services};
+#[cfg(feature = "grpc")]
+use crate::grpc::GrpcServer;
+
+use crate::http::{AdminService, DataSources, HttpServer, HttpServerConfig};
+#[cfg(feature = "faucet")]
+use crate::http::{FaucetConfig, FaucetService};
+#[cfg(feature = "prover")]
+use crate::http::{ProverConfig, ProverService};
+use crate::node::{
+    install_panic_hook, ArtifactsConfig, ArtifactsWriter, ChainEventStreamer,
+    RuskNode, Services,
+};
 use crate::{Rusk, VERSION};
 
 #[derive(Default)]
 pub struct RuskNodeBuilder {
     consensus_keys_path: String,
+    watch_only: bool,
+    replica: bool,
+    replicas: Vec<String>,
+    remote_signer: Option<RemoteSignerConfig>,
+    compact_candidates: bool,
     databroker: BrokerParam,
     kadcast: KadcastConfig,
+    bandwidth: BandwidthConfig,
+    identity: Option<NodeIdentity>,
+    peer_snapshot: PeerSnapshotConfig,
     mempool: MempoolParam,
+    tx_policy: Option<ExternalPolicyConfig>,
     telemetry_address: Option<String>,
     db_path: PathBuf,
     db_options: DatabaseOptions,
@@ -46,18 +78,56 @@ pub struct RuskNodeBuilder {
     min_gas_limit: Option<u64>,
     min_deploy_points: Option<u64>,
     block_gas_limit: u64,
+    gas_limit_adjustment: Option<GasLimitAdjustment>,
+    block_space_reservation: BlockSpaceReservation,
     feeder_call_gas: u64,
     state_dir: PathBuf,
+    commit_retention: Option<usize>,
+    commit_gc_interval: Option<Duration>,
+    artifacts: Option<ArtifactsConfig>,
 
     http: Option<HttpServerConfig>,
+    #[cfg(feature = "grpc")]
+    grpc_address: Option<String>,
+    #[cfg(feature = "grpc")]
+    grpc_token: Option<String>,
+    #[cfg(feature = "prover")]
+    prover: ProverConfig,
+    #[cfg(feature = "faucet")]
+    faucet: Option<FaucetConfig>,
+    #[cfg(feature = "chaos")]
+    chaos: Option<ChaosConfig>,
 
     command_revert: bool,
+
+    config_reload: Option<ConfigReload>,
+}
+
+/// The part of the node configuration that can be changed while the node is
+/// running.
+#[derive(Clone)]
+pub struct RuntimeConfig {
+    pub databroker: BrokerParam,
+    pub mempool: MempoolParam,
+    pub feeder_call_gas: u64,
+    /// Capacity of the channels of the WebSocket sessions opened afterwards.
+    pub ws_event_channel_cap: usize,
+}
+
+/// Connects the node to the watcher reloading its configuration.
+pub struct ConfigReload {
+    /// Notified when a reload of the configuration is requested through the
+    /// `admin` RUES component.
+    pub requests: Arc<Notify>,
+    /// Receives the reloaded configurations to apply.
+    pub updates: watch::Receiver<RuntimeConfig>,
 }
 
 const DEFAULT_GAS_PER_DEPLOY_BYTE: u64 = 100;
 const DEFAULT_MIN_DEPLOYMENT_GAS_PRICE: u64 = 2000;
 const DEFAULT_MIN_GAS_LIMIT: u64 = 75000;
 const DEFAULT_MIN_DEPLOY_POINTS: u64 = 5_000_000;
+const DEFAULT_COMMIT_GC_INTERVAL: Duration = Duration::from_secs(10 * 60);
 
 impl RuskNodeBuilder {
     pub fn with_consensus_keys(mut self, consensus_keys_path: String) -> Self {
@@ -65,6 +135,69 @@ impl RuskNodeBuilder {
         self
     }
 
+    /// Runs the node without participating in consensus, so that no
+    /// consensus keys are needed.
+    pub fn with_watch_only(mut self, watch_only: bool) -> Self {
+        self.watch_only = watch_only;
+        self
+    }
+
+    /// Runs the node as a query replica: it follows the chain without
+    /// participating in consensus nor running the mempool, and only serves
+    /// reads.
+    pub fn with_replica(mut self, replica: bool) -> Self {
+        self.replica = replica;
+        self
+    }
+
+    /// Advertises the query replicas the clients can send their reads to,
+    /// through `node/info`.
+    pub fn with_replicas(mut self, replicas: Vec<String>) -> Self {
+        self.replicas = replicas;
+        self
+    }
+
+    /// Delegates the consensus signatures to a remote signer.
+    pub fn with_remote_signer(
+        mut self,
+        remote_signer: Option<RemoteSignerConfig>,
+    ) -> Self {
+        self.remote_signer = remote_signer;
+        self
+    }
+
+    /// Propagates the candidate blocks referencing the transactions by id,
+    /// peers rebuilding them from their mempool.
+    pub fn with_compact_candidates(mut self, compact: bool) -> Self {
+        self.compact_candidates = compact;
+        self
+    }
+
+    /// Sets the outbound bandwidth budgets of the link and of the consensus,
+    /// mempool and databroker messages.
+    pub fn with_bandwidth(mut self, bandwidth: BandwidthConfig) -> Self {
+        self.bandwidth = bandwidth;
+        self
+    }
+
+    /// Sets the identity the control messages sent to the peers are signed
+    /// with, leaving them unsigned if `None`.
+    pub fn with_identity(mut self, identity: Option<NodeIdentity>) -> Self {
+        self.identity = identity;
+        self
+    }
+
+    /// Imports at startup the signed snapshot of peers set in
+    /// `peer_snapshot`, bootstrapping the routing table from them along with
+    /// the bootstrapping nodes.
+    pub fn with_peer_snapshot(
+        mut self,
+        peer_snapshot: PeerSnapshotConfig,
+    ) -> Self {
+        self.peer_snapshot = peer_snapshot;
+        self
+    }
+
     pub fn with_databroker<P: Into<BrokerParam>>(
         mut self,
         databroker: P,
@@ -105,6 +238,16 @@ impl RuskNodeBuilder {
         self
     }
 
+    /// Sets the external engine deciding which transactions are relayed and
+    /// included in the generated blocks.
+    pub fn with_tx_policy(
+        mut self,
+        tx_policy: Option<ExternalPolicyConfig>,
+    ) -> Self {
+        self.tx_policy = tx_policy;
+        self
+    }
+
     pub fn with_chain_queue_size(mut self, max_queue_size: usize) -> Self {
         self.max_chain_queue_size = max_queue_size;
         self
@@ -157,11 +300,43 @@ impl RuskNodeBuilder {
         self
     }
 
+    /// Adjusts the block gas limit to the usage of the blocks, starting from
+    /// the one set with [`Self::with_block_gas_limit`].
+    pub fn with_gas_limit_adjustment(
+        mut self,
+        adjustment: Option<GasLimitAdjustment>,
+    ) -> Self {
+        self.gas_limit_adjustment = adjustment;
+        self
+    }
+
+    /// Reserves a share of the block gas limit to the transactions calling
+    /// the protocol contracts, included ahead of the user ones.
+    pub fn with_block_space_reservation(
+        mut self,
+        reservation: BlockSpaceReservation,
+    ) -> Self {
+        self.block_space_reservation = reservation;
+        self
+    }
+
     pub fn with_feeder_call_gas(mut self, feeder_call_gas: u64) -> Self {
         self.feeder_call_gas = feeder_call_gas;
         self
     }
 
+    /// Sets the number of accepted states kept beyond the non-final ones and
+    /// the interval between the deletions of the unreachable states.
+    pub fn with_commit_gc(
+        mut self,
+        retention: Option<usize>,
+        interval: Option<Duration>,
+    ) -> Self {
+        self.commit_retention = retention;
+        self.commit_gc_interval = interval;
+        self
+    }
+
     pub fn with_state_dir(mut self, state_dir: PathBuf) -> Self {
         self.state_dir = state_dir;
         self
@@ -172,11 +347,61 @@ impl RuskNodeBuilder {
         self
     }
 
+    /// Serves the chain data over gRPC on the given address, if any, to the
+    /// requests carrying the `token` as bearer.
+    ///
+    /// Without a token, the server can only listen to a loopback address.
+    #[cfg(feature = "grpc")]
+    pub fn with_grpc(
+        mut self,
+        grpc_address: Option<String>,
+        token: Option<String>,
+    ) -> Self {
+        self.grpc_address = grpc_address;
+        self.grpc_token = token;
+        self
+    }
+
+    #[cfg(feature = "prover")]
+    pub fn with_prover(mut self, prover: ProverConfig) -> Self {
+        self.prover = prover;
+        self
+    }
+
+    /// Serves the `faucet` RUES component, dispensing DUSK as configured.
+    #[cfg(feature = "faucet")]
+    pub fn with_faucet(mut self, faucet: Option<FaucetConfig>) -> Self {
+        self.faucet = faucet;
+        self
+    }
+
+    /// Injects the configured faults into the network, the data broker and
+    /// the acceptance of the blocks.
+    #[cfg(feature = "chaos")]
+    pub fn with_chaos(mut self, chaos: Option<ChaosConfig>) -> Self {
+        self.chaos = chaos;
+        self
+    }
+
+    /// Writes the execution artifacts of each accepted block as configured.
+    pub fn with_artifacts(
+        mut self,
+        artifacts: Option<ArtifactsConfig>,
+    ) -> Self {
+        self.artifacts = artifacts;
+        self
+    }
+
     pub fn with_revert(mut self) -> Self {
         self.command_revert = true;
         self
     }
 
+    pub fn with_config_reload(mut self, config_reload: ConfigReload) -> Self {
+        self.config_reload = Some(config_reload);
+        self
+    }
+
     /// Build the RuskNode and corresponding services
     pub async fn build_and_run(self) -> anyhow::Result<()> {
         let channel_cap = self
@@ -208,7 +433,11 @@ impl RuskNodeBuilder {
             min_deployment_gas_price,
             min_gas_limit,
             min_deploy_points,
-            self.block_gas_limit,
+            GasLimitRule {
+                limit: self.block_gas_limit,
+                adjustment: self.gas_limit_adjustment,
+            },
+            self.block_space_reservation,
             self.feeder_call_gas,
             rues_sender.clone(),
             #[cfg(feature = "archive")]
@@ -217,28 +446,92 @@ impl RuskNodeBuilder {
         .map_err(|e| anyhow::anyhow!("Cannot instantiate VM {e}"))?;
         info!("Rusk VM loaded");
 
+        let rusk = match self.artifacts {
+            Some(conf) => rusk.with_artifacts(ArtifactsWriter::spawn(conf)?),
+            None => rusk,
+        };
+
+        if let Some(retention) = self.commit_retention {
+            rusk.set_commit_retention(retention);
+        }
+
         #[cfg(feature = "archive")]
         let archive = Archive::create_or_open(self.db_path.clone()).await;
 
+        let upgrades = rusk.upgrades();
+
+        #[cfg(feature = "chaos")]
+        let chaos = self.chaos.map(Chaos::new).transpose()?.map(Arc::new);
+
+        let tx_policy = self.tx_policy.map(|conf| {
+            let exclusions = conf.blocks.then(Arc::<BlockExclusions>::default);
+            let policy: Arc<dyn TxPolicy> = Arc::new(ExternalPolicy::new(conf));
+            (policy, exclusions)
+        });
+
         let node = {
             let db = rocksdb::Backend::create_or_open(
                 self.db_path.clone(),
                 self.db_options.clone(),
             );
-            let net = Kadcast::new(self.kadcast)?;
-            RuskNode::new(
+            install_panic_hook(db.clone(), rusk.clone());
+            let known_peers = match self.peer_snapshot.load() {
+                Ok(Some(peers)) => {
+                    info!("Importing {} peers from snapshot", peers.len());
+                    peers
+                }
+                Ok(None) => vec![],
+                Err(e) => {
+                    warn!("Cannot import peer snapshot: {e}");
+                    vec![]
+                }
+            };
+            let mut net = Kadcast::with_known_peers(self.kadcast, known_peers)
+                .await?
+                .with_bandwidth(self.bandwidth);
+            if let Some(identity) = self.identity {
+                net = net.with_identity(identity);
+            }
+            #[cfg(feature = "chaos")]
+            if let Some(chaos) = &chaos {
+                net = net.with_chaos(chaos.clone());
+            }
+            let node = RuskNode::new(
                 Node::new(net, db, rusk.clone()),
                 #[cfg(feature = "archive")]
                 archive.clone(),
             )
+            .with_replica(self.replica)
+            .with_replicas(self.replicas)
+            .with_upgrades(upgrades.clone());
+            match &tx_policy {
+                Some((policy, _)) => node.with_tx_policy(policy.clone()),
+                None => node,
+            }
         };
 
+        let switches = Arc::new(RuntimeSwitches::default());
+
         let mut chain_srv = ChainSrv::new(
             self.consensus_keys_path,
             self.max_chain_queue_size,
             node_sender.clone(),
             self.genesis_timestamp,
-        );
+        )
+        .with_switches(switches.clone())
+        .with_watch_only(self.watch_only || self.replica)
+        .with_remote_signer(self.remote_signer)
+        .with_compact_candidates(self.compact_candidates)
+        .with_upgrades(upgrades);
+
+        if let Some((_, Some(exclusions))) = &tx_policy {
+            chain_srv = chain_srv.with_block_exclusions(exclusions.clone());
+        }
+        #[cfg(feature = "chaos")]
+        if let Some(chaos) = &chaos {
+            chain_srv = chain_srv.with_chaos(chaos.clone());
+        }
+
         if self.command_revert {
             chain_srv
                 .initialize(
@@ -250,20 +543,48 @@ impl RuskNodeBuilder {
             return chain_srv.revert_last_final().await;
         }
 
+        let (mempool_updates, mempool_receiver) = watch::channel(self.mempool);
+        let (databroker_updates, databroker_receiver) =
+            watch::channel(self.databroker);
+
+        let mut mempool_srv =
+            MempoolSrv::new(self.mempool, node_sender.clone())
+                .with_conf_updates(mempool_receiver)
+                .with_switches(switches.clone());
+        if let Some((policy, exclusions)) = tx_policy {
+            mempool_srv = mempool_srv.with_tx_policy(policy);
+            if let Some(exclusions) = exclusions {
+                mempool_srv = mempool_srv.with_block_exclusions(exclusions);
+            }
+        }
+
+        let databroker_srv = DataBrokerSrv::new(self.databroker)
+            .with_conf_updates(databroker_receiver);
+        #[cfg(feature = "chaos")]
+        let databroker_srv = match &chaos {
+            Some(chaos) => databroker_srv.with_chaos(chaos.clone()),
+            None => databroker_srv,
+        };
+
         let mut service_list: Vec<Box<Services>> = vec![
-            Box::new(MempoolSrv::new(self.mempool, node_sender.clone())),
             Box::new(chain_srv),
-            Box::new(DataBrokerSrv::new(self.databroker)),
+            Box::new(databroker_srv),
             Box::new(TelemetrySrv::new(self.telemetry_address)),
         ];
+        // A query replica leaves the transactions to the other nodes
+        if self.replica {
+            info!("Running as a query replica");
+        } else {
+            service_list.insert(0, Box::new(mempool_srv));
+        }
 
-        let mut _ws_server = None;
+        let mut ws_server = None;
         if let Some(http) = self.http {
             info!("Configuring HTTP");
 
             service_list.push(Box::new(ChainEventStreamer {
                 node_receiver,
-                rues_sender,
+                rues_sender: rues_sender.clone(),
                 #[cfg(feature = "archive")]
                 archivist_sender: archive_sender,
             }));
@@ -273,18 +594,41 @@ impl RuskNodeBuilder {
             handler.sources.push(Box::new(node.clone()));
 
             #[cfg(feature = "prover")]
-            handler.sources.push(Box::new(rusk_prover::LocalProver));
+            handler
+                .sources
+                .push(Box::new(ProverService::new(self.prover, rues_sender)));
+
+            #[cfg(feature = "faucet")]
+            if let Some(faucet) = self.faucet {
+                info!("Serving the faucet");
+                let faucet =
+                    FaucetService::new(faucet, rusk.clone(), node.clone())?;
+                handler.sources.push(Box::new(faucet));
+            }
+
+            if let (Some(token), Some(reload)) =
+                (http.admin_token, &self.config_reload)
+            {
+                let admin = AdminService::new(token, reload.requests.clone())
+                    .with_switches(switches)
+                    .with_audit_log(node.db())
+                    .with_network(node.network());
+                #[cfg(feature = "archive")]
+                let admin = admin.with_replay(node.clone());
+                handler.sources.push(Box::new(admin));
+            }
 
             let cert_and_key = match (http.cert, http.key) {
                 (Some(cert), Some(key)) => Some((cert, key)),
                 _ => None,
             };
 
-            _ws_server = Some(
+            ws_server = Some(
                 HttpServer::bind(
                     handler,
                     rues_receiver,
                     http.ws_event_channel_cap,
+                    http.event_store_capacity,
                     http.address,
                     http.headers,
                     cert_and_key,
@@ -293,12 +637,92 @@ impl RuskNodeBuilder {
             );
         }
 
+        if let Some(mut reload) = self.config_reload {
+            let rusk = rusk.clone();
+            let db = node.db();
+            let ws_event_channel_cap =
+                ws_server.as_ref().map(HttpServer::ws_event_channel_cap);
+
+            tokio::spawn(async move {
+                while reload.updates.changed().await.is_ok() {
+                    let config = reload.updates.borrow_and_update().clone();
+
+                    databroker_updates.send_replace(config.databroker);
+                    mempool_updates.send_replace(config.mempool);
+                    rusk.set_feeder_gas_limit(config.feeder_call_gas);
+                    if let Some(cap) = &ws_event_channel_cap {
+                        cap.store(
+                            config.ws_event_channel_cap,
+                            Ordering::Relaxed,
+                        );
+                    }
+
+                    info!("Applied reloaded configuration");
+
+                    let entry = AuditEntry::new(
+                        "node",
+                        "config-reloaded",
+                        json!({
+                            "databroker": config.databroker,
+                            "mempool": config.mempool,
+                            "feeder_call_gas": config.feeder_call_gas,
+                            "ws_event_channel_cap": config.ws_event_channel_cap,
+                        }),
+                    );
+                    if let Err(e) =
+                        db.read().await.update(|t| t.append_audit_entry(&entry))
+                    {
+                        warn!("Cannot record configuration reload: {e}");
+                    }
+                }
+            });
+        }
+
+        {
+            let rusk = rusk.clone();
+            let interval = self
+                .commit_gc_interval
+                .unwrap_or(DEFAULT_COMMIT_GC_INTERVAL);
+
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(interval);
+                loop {
+                    interval.tick().await;
+                    let rusk = rusk.clone();
+                    match tokio::task::spawn_blocking(move || {
+                        rusk.collect_commits()
+                    })
+                    .await
+                    {
+                        Ok(0) => {}
+                        Ok(deleted) => info!("Deleted {deleted} stale commits"),
+                        Err(e) => warn!("Commit collection failed: {e}"),
+                    }
+                }
+            });
+        }
+
         #[cfg(feature = "archive")]
         service_list.push(Box::new(ArchivistSrv {
             archive_receiver,
             archivist: archive,
         }));
 
+        #[cfg(feature = "grpc")]
+        if let Some(address) = self.grpc_address {
+            info!("Configuring gRPC");
+            let grpc_server =
+                GrpcServer::bind(node.clone(), address, self.grpc_token)
+                    .await?;
+            service_list.insert(0, Box::new(grpc_server));
+        }
+
+        // The HTTP server is stopped first on shutdown, so that no request is
+        // served while the other services are stopping.
+        if let Some(ws_server) = ws_server {
+            service_list.insert(0, Box::new(ws_server));
+        }
+
         node.inner().initialize(&mut service_list).await?;
         node.inner().spawn_all(service_list).await?;
 
@@ -12,6 +12,8 @@ use dusk_core::{
     BlsScalar, Error as ExecErr,
 };
 use dusk_vm::Error as VMError;
+#[cfg(feature = "chain")]
+use node::chain::BlockSpaceError;
 
 #[derive(Debug)]
 pub enum Error {
@@ -23,6 +25,9 @@ pub enum Error {
     ProofVerification,
     /// Out of gas in block execution
     OutOfGas,
+    /// Block space reservation violated in block execution
+    #[cfg(feature = "chain")]
+    BlockSpace(BlockSpaceError),
     /// Repeated nullifier in transaction verification
     RepeatingNullifiers(Vec<BlsScalar>),
     /// Repeated nullifier in the same transaction
@@ -58,6 +63,11 @@ pub enum Error {
     InvalidCreditsCount(u64, usize),
     /// Memo too large
     MemoTooLarge(usize),
+    /// Transaction included past its expiry height
+    TransactionExpired(u64),
+    /// Transaction with an expiry height included before the upgrade
+    /// accepting them
+    ExpiryNotActive,
     /// Chain tip different from the expected one
     TipChanged,
 }
@@ -113,6 +123,13 @@ impl From<dusk_core::Error> for Error {
     }
 }
 
+#[cfg(feature = "chain")]
+impl From<BlockSpaceError> for Error {
+    fn from(err: BlockSpaceError) -> Self {
+        Self::BlockSpace(err)
+    }
+}
+
 impl From<dusk_bytes::Error> for Error {
     fn from(err: dusk_bytes::Error) -> Self {
         Self::Serialization(err)
@@ -159,6 +176,8 @@ impl fmt::Display for Error {
             Error::Other(err) => write!(f, "Other error: {err}"),
             Error::ProofVerification => write!(f, "Proof verification failure"),
             Error::OutOfGas => write!(f, "Out of gas"),
+            #[cfg(feature = "chain")]
+            Error::BlockSpace(err) => write!(f, "Block space: {err}"),
             Error::RepeatingNullifiers(n) => {
                 write!(f, "Nullifiers already spent: {n:?}")
             }
@@ -184,6 +203,12 @@ impl fmt::Display for Error {
             Error::MemoTooLarge(size) => {
                 write!(f, "The memo size {size} is too large")
             }
+            Error::TransactionExpired(height) => {
+                write!(f, "Transaction expired at height {height}")
+            }
+            Error::ExpiryNotActive => {
+                write!(f, "Transaction expiry not active")
+            }
             Error::TipChanged => {
                 write!(f, "Chain tip different from the expected one")
             }
@@ -0,0 +1,357 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! gRPC surface of the chain data, defined in `proto/chain.proto`, for the
+//! integrations whose tooling is gRPC-first. It is served alongside RUES,
+//! from the same node handles.
+//!
+//! The requests are authorized by a bearer token, without which the server
+//! only listens to a loopback address.
+
+pub mod proto {
+    tonic::include_proto!("rusk.chain.v1");
+}
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use dusk_core::transfer::Transaction as ProtocolTransaction;
+use node::database::rocksdb::MD_HASH_KEY;
+use node::database::{Ledger, LightBlock, Mempool, Metadata, DB};
+use node::Network;
+use node_data::ledger;
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::sync::oneshot;
+use tokio::task::{self, JoinHandle};
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::metadata::MetadataValue;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+use crate::node::RuskNode;
+use proto::chain_server::{Chain, ChainServer};
+use proto::get_block_request::Id;
+use proto::{
+    Block, BlockHeader, GetBlockRequest, GetMempoolRequest, GetTipRequest,
+    GetTransactionRequest, MempoolTransactions, SpentTransaction,
+    SubmitTransactionRequest, SubmitTransactionResponse, Transaction,
+};
+
+/// Number of mempool transactions returned when the request sets no maximum.
+const DEFAULT_MEMPOOL_TXS: usize = 100;
+/// Maximum number of mempool transactions returned.
+const MAX_MEMPOOL_TXS: usize = 1000;
+
+pub struct GrpcServer {
+    handle: JoinHandle<Result<(), tonic::transport::Error>>,
+    local_addr: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl GrpcServer {
+    /// Binds the server to `addr`, serving the requests carrying `token` as
+    /// bearer.
+    ///
+    /// Without a token, only a loopback address can be listened to.
+    pub async fn bind<A: ToSocketAddrs>(
+        node: RuskNode,
+        addr: A,
+        token: Option<String>,
+    ) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+
+        let authorization = match token {
+            Some(token) => Some(
+                MetadataValue::try_from(format!("Bearer {token}")).map_err(
+                    |_| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidInput,
+                            "Invalid gRPC token",
+                        )
+                    },
+                )?,
+            ),
+            None if local_addr.ip().is_loopback() => None,
+            None => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!(
+                        "A token is required to serve gRPC on {local_addr}"
+                    ),
+                ))
+            }
+        };
+
+        info!("Starting gRPC Listener to {local_addr}");
+
+        let service = ChainServer::with_interceptor(
+            ChainService { node },
+            move |request: Request<()>| {
+                let Some(authorization) = &authorization else {
+                    return Ok(request);
+                };
+                match request.metadata().get("authorization") {
+                    Some(value) if authorized(value, authorization) => {
+                        Ok(request)
+                    }
+                    _ => Err(Status::unauthenticated("Invalid token")),
+                }
+            },
+        );
+
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel();
+        let server = Server::builder()
+            .add_service(service)
+            .serve_with_incoming_shutdown(
+                TcpListenerStream::new(listener),
+                async {
+                    let _ = shutdown_receiver.await;
+                },
+            );
+
+        Ok(Self {
+            handle: task::spawn(server),
+            local_addr,
+            shutdown: Some(shutdown_sender),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stops accepting connections, and waits for the requests in flight to
+    /// be served.
+    pub async fn shutdown(&mut self) -> anyhow::Result<()> {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if self.handle.is_finished() {
+            return Ok(());
+        }
+        (&mut self.handle).await??;
+        Ok(())
+    }
+}
+
+/// Runs the server as a node service, so that it stops accepting requests
+/// before the other services are stopped.
+#[async_trait]
+impl<N, DB, VM> node::LongLivedService<N, DB, VM> for GrpcServer
+where
+    N: node::Network,
+    DB: node::database::DB,
+    VM: node::vm::VMExecution,
+{
+    async fn execute(
+        &mut self,
+        _: Arc<tokio::sync::RwLock<N>>,
+        _: Arc<tokio::sync::RwLock<DB>>,
+        _: Arc<tokio::sync::RwLock<VM>>,
+    ) -> anyhow::Result<usize> {
+        (&mut self.handle).await??;
+        anyhow::bail!("gRPC listener terminated")
+    }
+
+    async fn shutdown(
+        &mut self,
+        _: Arc<tokio::sync::RwLock<N>>,
+        _: Arc<tokio::sync::RwLock<DB>>,
+        _: Arc<tokio::sync::RwLock<VM>>,
+    ) -> anyhow::Result<()> {
+        GrpcServer::shutdown(self).await
+    }
+
+    fn name(&self) -> &'static str {
+        "grpc"
+    }
+}
+
+struct ChainService {
+    node: RuskNode,
+}
+
+#[tonic::async_trait]
+impl Chain for ChainService {
+    async fn get_tip(
+        &self,
+        _: Request<GetTipRequest>,
+    ) -> Result<Response<Block>, Status> {
+        let block = self.node.db().read().await.view(|t| {
+            match t.op_read(MD_HASH_KEY)? {
+                Some(hash) => t.light_block(&hash),
+                None => Ok(None),
+            }
+        });
+
+        let block = block.map_err(internal)?;
+        let block = block.ok_or_else(|| Status::not_found("No tip"))?;
+        Ok(Response::new(block.into()))
+    }
+
+    async fn get_block(
+        &self,
+        request: Request<GetBlockRequest>,
+    ) -> Result<Response<Block>, Status> {
+        let id = request
+            .into_inner()
+            .id
+            .ok_or_else(|| Status::invalid_argument("Missing block id"))?;
+
+        let block = self.node.db().read().await.view(|t| match &id {
+            Id::Height(height) => match t.block_hash_by_height(*height)? {
+                Some(hash) => t.light_block(&hash),
+                None => Ok(None),
+            },
+            Id::Hash(hash) => t.light_block(hash),
+        });
+
+        let block = block.map_err(internal)?;
+        let block = block.ok_or_else(|| Status::not_found("Unknown block"))?;
+        Ok(Response::new(block.into()))
+    }
+
+    async fn get_transaction(
+        &self,
+        request: Request<GetTransactionRequest>,
+    ) -> Result<Response<SpentTransaction>, Status> {
+        let id = request.into_inner().id;
+        let tx = self.node.db().read().await.view(|t| t.ledger_tx(&id));
+
+        let tx = tx.map_err(internal)?;
+        let tx = tx.ok_or_else(|| Status::not_found("Unknown transaction"))?;
+        Ok(Response::new(tx.into()))
+    }
+
+    async fn get_mempool(
+        &self,
+        request: Request<GetMempoolRequest>,
+    ) -> Result<Response<MempoolTransactions>, Status> {
+        let max = request
+            .into_inner()
+            .max
+            .map_or(DEFAULT_MEMPOOL_TXS, |m| m as usize)
+            .min(MAX_MEMPOOL_TXS);
+
+        let transactions = self.node.db().read().await.view(|t| {
+            let txs = t.mempool_txs_sorted_by_fee()?;
+            Ok::<_, anyhow::Error>(
+                txs.take(max).map(|tx| (&tx).into()).collect(),
+            )
+        });
+
+        let transactions = transactions.map_err(internal)?;
+        Ok(Response::new(MempoolTransactions { transactions }))
+    }
+
+    async fn submit_transaction(
+        &self,
+        request: Request<SubmitTransactionRequest>,
+    ) -> Result<Response<SubmitTransactionResponse>, Status> {
+        if self.node.is_replica() {
+            return Err(Status::failed_precondition(
+                "Transactions are not accepted by a query replica",
+            ));
+        }
+
+        let tx = request.into_inner().transaction;
+        let tx: ledger::Transaction = ProtocolTransaction::from_slice(&tx)
+            .map_err(|e| Status::invalid_argument(format!("{e:?}")))?
+            .into();
+        let id = tx.id().to_vec();
+
+        let network = self.node.network();
+        network.read().await.route_internal(tx.into());
+
+        Ok(Response::new(SubmitTransactionResponse { id }))
+    }
+}
+
+/// Compares the authorization of a request to the expected one in constant
+/// time, to avoid leaking the token.
+fn authorized<T>(value: &MetadataValue<T>, expected: &MetadataValue<T>) -> bool
+where
+    T: tonic::metadata::ValueEncoding,
+{
+    let (value, expected) = (value.as_bytes(), expected.as_bytes());
+    value.len() == expected.len()
+        && value
+            .iter()
+            .zip(expected)
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+fn internal<E: std::fmt::Display>(e: E) -> Status {
+    Status::internal(e.to_string())
+}
+
+impl From<LightBlock> for Block {
+    fn from(block: LightBlock) -> Self {
+        let header = block.header;
+        Self {
+            header: Some(BlockHeader {
+                version: header.version.into(),
+                height: header.height,
+                timestamp: header.timestamp,
+                hash: header.hash.to_vec(),
+                prev_block_hash: header.prev_block_hash.to_vec(),
+                state_hash: header.state_hash.to_vec(),
+                generator: header.generator_bls_pubkey.inner().to_vec(),
+                gas_limit: header.gas_limit,
+                iteration: header.iteration.into(),
+            }),
+            transaction_ids: block
+                .transactions_ids
+                .iter()
+                .map(|id| id.to_vec())
+                .collect(),
+        }
+    }
+}
+
+impl From<&ledger::Transaction> for Transaction {
+    fn from(tx: &ledger::Transaction) -> Self {
+        Self {
+            id: tx.id().to_vec(),
+            raw: tx.inner.to_var_bytes(),
+            gas_limit: tx.inner.gas_limit(),
+            gas_price: tx.inner.gas_price(),
+        }
+    }
+}
+
+impl From<ledger::SpentTransaction> for SpentTransaction {
+    fn from(tx: ledger::SpentTransaction) -> Self {
+        Self {
+            transaction: Some((&tx.inner).into()),
+            block_height: tx.block_height,
+            gas_spent: tx.gas_spent,
+            error: tx.err,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authorization_must_match() {
+        let expected = MetadataValue::from_static("Bearer secret");
+
+        let valid = MetadataValue::from_static("Bearer secret");
+        assert!(authorized(&valid, &expected));
+
+        let wrong = MetadataValue::from_static("Bearer secreT");
+        assert!(!authorized(&wrong, &expected));
+        let prefix = MetadataValue::from_static("Bearer secre");
+        assert!(!authorized(&prefix, &expected));
+    }
+}
@@ -6,15 +6,22 @@
 
 #![allow(unused)]
 
+mod admin;
 #[cfg(feature = "chain")]
 mod chain;
+mod error;
 mod event;
+#[cfg(feature = "faucet")]
+mod faucet;
 #[cfg(feature = "prover")]
 mod prover;
 #[cfg(feature = "chain")]
 mod rusk;
+mod session;
 mod stream;
 
+#[cfg(feature = "chain")]
+pub(crate) use chain::{FeedHub, MAX_RAW_STREAMS};
 pub(crate) use event::{
     BinaryWrapper, DataType, ExecutionError, MessageResponse as EventResponse,
     RequestData, Target,
@@ -22,7 +29,7 @@ pub(crate) use event::{
 
 use dusk_core::abi::Event;
 use tokio::task::JoinError;
-use tracing::{debug, info, warn};
+use tracing::{debug, info, info_span, warn, Instrument};
 
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
@@ -32,6 +39,7 @@ use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc as std_mpsc;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -59,7 +67,6 @@ use hyper_util::server::conn::auto::Builder as HttpBuilder;
 use tungstenite::protocol::frame::coding::CloseCode;
 use tungstenite::protocol::{CloseFrame, Message};
 
-use futures_util::stream::iter as stream_iter;
 use futures_util::{SinkExt, TryStreamExt};
 
 use anyhow::Error as AnyhowError;
@@ -72,9 +79,20 @@ use node_data::events::contract::ContractEvent;
 use crate::http::event::FullOrStreamBody;
 use crate::VERSION;
 
+pub use self::admin::{AdminService, ADMIN_TOKEN_HEADER};
+pub use self::error::{ApiError, ErrorCategory, ErrorCode};
 pub use self::event::{RuesDispatchEvent, RuesEvent, RUES_LOCATION_PREFIX};
+#[cfg(feature = "faucet")]
+pub use self::faucet::{FaucetConfig, FaucetService};
+#[cfg(feature = "prover")]
+pub use self::prover::{ProverConfig, ProverService};
+pub use self::session::{LAST_SEEN_HEADER, SEQUENCE_HEADER, SESSION_TTL};
 
-use self::event::{MessageRequest, ResponseData, RuesEventUri, SessionId};
+use self::event::{
+    etag_matches, MessageRequest, ResponseData, RuesEventUri, SessionId,
+    GQL_VAR_PREFIX,
+};
+use self::session::{Resumed, Sessions};
 use self::stream::{Listener, Stream};
 
 const RUSK_VERSION_HEADER: &str = "Rusk-Version";
@@ -82,7 +100,8 @@ const RUSK_VERSION_HEADER: &str = "Rusk-Version";
 pub struct HttpServer {
     handle: task::JoinHandle<()>,
     local_addr: SocketAddr,
-    _shutdown: broadcast::Sender<Infallible>,
+    ws_event_channel_cap: Arc<AtomicUsize>,
+    shutdown: Option<broadcast::Sender<Infallible>>,
 }
 
 pub struct HttpServerConfig {
@@ -91,6 +110,12 @@ pub struct HttpServerConfig {
     pub key: Option<PathBuf>,
     pub headers: HeaderMap,
     pub ws_event_channel_cap: usize,
+    /// Number of the most recent events kept to be replayed to the resumed
+    /// sessions.
+    pub event_store_capacity: usize,
+    /// Token authorizing the `admin` RUES requests. If `None`, they are
+    /// disabled.
+    pub admin_token: Option<String>,
 }
 
 impl HttpServer {
@@ -98,10 +123,17 @@ impl HttpServer {
         self.handle.await
     }
 
+    /// Returns the capacity of the channels created for new WebSocket
+    /// sessions, which can be changed while the server is running.
+    pub fn ws_event_channel_cap(&self) -> Arc<AtomicUsize> {
+        self.ws_event_channel_cap.clone()
+    }
+
     pub async fn bind<A, H, P1, P2>(
         handler: H,
         event_receiver: broadcast::Receiver<RuesEvent>,
         ws_event_channel_cap: usize,
+        event_store_capacity: usize,
         addr: A,
         headers: HeaderMap,
         cert_and_key: Option<(P1, P2)>,
@@ -123,21 +155,71 @@ impl HttpServer {
 
         info!("Starting HTTP Listener to {local_addr}");
 
+        let ws_event_channel_cap =
+            Arc::new(AtomicUsize::new(ws_event_channel_cap));
+
         let handle = task::spawn(listening_loop(
             handler,
             listener,
             event_receiver,
             shutdown_receiver,
             headers,
-            ws_event_channel_cap,
+            ws_event_channel_cap.clone(),
+            event_store_capacity,
         ));
 
         Ok(Self {
             handle,
             local_addr,
-            _shutdown: shutdown_sender,
+            ws_event_channel_cap,
+            shutdown: Some(shutdown_sender),
         })
     }
+
+    /// Stops accepting connections, closes the open WebSocket sessions, and
+    /// waits for the listener to terminate.
+    pub async fn shutdown(&mut self) -> Result<(), JoinError> {
+        self.shutdown.take();
+        if self.handle.is_finished() {
+            return Ok(());
+        }
+        (&mut self.handle).await
+    }
+}
+
+/// Runs the server as a node service, so that it stops accepting requests
+/// before the other services are stopped.
+#[cfg(feature = "chain")]
+#[async_trait]
+impl<N, DB, VM> node::LongLivedService<N, DB, VM> for HttpServer
+where
+    N: node::Network,
+    DB: node::database::DB,
+    VM: node::vm::VMExecution,
+{
+    async fn execute(
+        &mut self,
+        _: Arc<tokio::sync::RwLock<N>>,
+        _: Arc<tokio::sync::RwLock<DB>>,
+        _: Arc<tokio::sync::RwLock<VM>>,
+    ) -> anyhow::Result<usize> {
+        (&mut self.handle).await?;
+        anyhow::bail!("HTTP listener terminated")
+    }
+
+    async fn shutdown(
+        &mut self,
+        _: Arc<tokio::sync::RwLock<N>>,
+        _: Arc<tokio::sync::RwLock<DB>>,
+        _: Arc<tokio::sync::RwLock<VM>>,
+    ) -> anyhow::Result<()> {
+        HttpServer::shutdown(self).await?;
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "http"
+    }
 }
 
 #[derive(Default)]
@@ -164,7 +246,11 @@ impl HandleRequest for DataSources {
                 return h.handle(request).await;
             }
         }
-        Err(anyhow::anyhow!("unsupported target type"))
+        Err(ApiError::new(
+            ErrorCode::UnsupportedLocation,
+            "unsupported target type",
+        )
+        .into())
     }
 
     fn can_handle_rues(&self, event: &RuesDispatchEvent) -> bool {
@@ -182,7 +268,11 @@ impl HandleRequest for DataSources {
                 return h.handle_rues(event).await;
             }
         }
-        Err(anyhow::anyhow!("unsupported location"))
+        Err(ApiError::new(
+            ErrorCode::UnsupportedLocation,
+            "unsupported location",
+        )
+        .into())
     }
 }
 
@@ -205,20 +295,38 @@ async fn listening_loop<H>(
     events: broadcast::Receiver<RuesEvent>,
     mut shutdown: broadcast::Receiver<Infallible>,
     headers: HeaderMap,
-    ws_event_channel_cap: usize,
+    ws_event_channel_cap: Arc<AtomicUsize>,
+    event_store_capacity: usize,
 ) where
     H: HandleRequest,
 {
+    // A broadcast channel cannot be created empty
+    let event_store_capacity = event_store_capacity.max(1);
+
     let sources = Arc::new(handler);
     let sockets_map = Arc::new(RwLock::new(HashMap::new()));
+    let sessions = Arc::new(Sessions::new(event_store_capacity));
+
+    // The events are numbered before reaching the WebSocket sessions, so
+    // that a resumed session can tell which ones it missed.
+    let (sequenced_sender, sequenced) =
+        broadcast::channel(event_store_capacity);
+    let sequencer = {
+        let sessions = sessions.clone();
+        task::spawn(
+            async move { sessions.sequence(events, sequenced_sender).await },
+        )
+    };
 
     let service = ExecutionService {
         sources: sources.clone(),
         sockets_map: sockets_map.clone(),
-        events: events.resubscribe(),
+        sessions,
+        events: sequenced,
         shutdown: shutdown.resubscribe(),
         headers: Arc::new(headers),
         ws_event_channel_cap,
+        client_addr: None,
     };
 
     let runtime = tokio::runtime::Builder::new_multi_thread()
@@ -231,18 +339,20 @@ async fn listening_loop<H>(
         tokio::select! {
             _ = shutdown.recv() => {
                 runtime.shutdown_background();
+                sequencer.abort();
                 break;
             }
             r = listener.accept() => {
-                let stream = match r {
-                    Ok(stream) => stream,
+                let (stream, client_addr) = match r {
+                    Ok(accepted) => accepted,
                     Err(_) => break,
                 };
 
                 let http = HttpBuilder::new(TokioExecutor);
 
                 let stream = TokioIo::new(stream);
-                let service = service.clone();
+                let mut service = service.clone();
+                service.client_addr = Some(client_addr);
 
                 runtime.spawn(async move {
                     let conn = http.serve_connection_with_upgrades(stream, service);
@@ -292,10 +402,17 @@ async fn handle_stream<H: HandleRequest>(
 
             rsp = responses.recv() => {
                 // `responder` is never dropped so this can never be `None`
-                let rsp = rsp.unwrap();
+                let mut rsp = rsp.unwrap();
+
+                let streamed = match rsp.data {
+                    DataType::Channel(_) | DataType::Stream(_) => {
+                        std::mem::take(&mut rsp.data).into_stream()
+                    }
+                    _ => None,
+                };
 
-                if let DataType::Channel(c) = rsp.data {
-                    let mut datas = stream_iter(c).map(|e| {
+                if let Some(c) = streamed {
+                    let mut datas = c.map(|e| {
                         EventResponse {
                             data: e.into(),
                             headers: rsp.headers.clone(),
@@ -305,9 +422,9 @@ async fn handle_stream<H: HandleRequest>(
                     while let Some(c) = datas.next().await {
                         let rsp = serde_json::to_string(&c).unwrap_or_else(|err| {
                             serde_json::to_string(
-                                &EventResponse::from_error(
+                                &EventResponse::from_error(ApiError::internal(
                                     format!("Failed serializing response: {err}")
-                                )).expect("serializing error response should succeed")
+                                ))).expect("serializing error response should succeed")
                             });
 
                         // If we error in sending the message we send a close frame
@@ -327,9 +444,9 @@ async fn handle_stream<H: HandleRequest>(
                     // we simply serialize an error response.
                     let rsp = serde_json::to_string(&rsp).unwrap_or_else(|err| {
                         serde_json::to_string(
-                            &EventResponse::from_error(
+                            &EventResponse::from_error(ApiError::internal(
                                 format!("Failed serializing response: {err}")
-                            )).expect("serializing error response should succeed")
+                            ))).expect("serializing error response should succeed")
                         });
 
                     // If we error in sending the message we send a close frame
@@ -406,10 +523,13 @@ struct ExecutionService<H> {
     sources: Arc<H>,
     sockets_map:
         Arc<RwLock<HashMap<SessionId, mpsc::Sender<SubscriptionAction>>>>,
+    sessions: Arc<Sessions>,
     events: broadcast::Receiver<RuesEvent>,
     shutdown: broadcast::Receiver<Infallible>,
     headers: Arc<HeaderMap>,
-    ws_event_channel_cap: usize,
+    ws_event_channel_cap: Arc<AtomicUsize>,
+    /// Address of the client of the connection served
+    client_addr: Option<SocketAddr>,
 }
 
 impl<H> Clone for ExecutionService<H> {
@@ -417,10 +537,12 @@ impl<H> Clone for ExecutionService<H> {
         Self {
             sources: self.sources.clone(),
             sockets_map: self.sockets_map.clone(),
+            sessions: self.sessions.clone(),
             events: self.events.resubscribe(),
             shutdown: self.shutdown.resubscribe(),
             headers: self.headers.clone(),
-            ws_event_channel_cap: self.ws_event_channel_cap,
+            ws_event_channel_cap: self.ws_event_channel_cap.clone(),
+            client_addr: self.client_addr,
         }
     }
 }
@@ -447,16 +569,22 @@ where
     fn call(&self, mut req: Request<Incoming>) -> Self::Future {
         let sources = self.sources.clone();
         let sockets_map = self.sockets_map.clone();
+        let sessions = self.sessions.clone();
         let events = self.events.resubscribe();
         let shutdown = self.shutdown.resubscribe();
-        let ws_event_channel_cap = self.ws_event_channel_cap;
+        let ws_event_channel_cap =
+            self.ws_event_channel_cap.load(Ordering::Relaxed);
         let headers = self.headers.clone();
+        if let Some(client_addr) = self.client_addr {
+            req.extensions_mut().insert(client_addr);
+        }
 
         Box::pin(async move {
             let mut rsp = handle_request(
                 req,
                 sources,
                 sockets_map,
+                sessions,
                 events,
                 shutdown,
                 ws_event_channel_cap,
@@ -470,11 +598,12 @@ where
                 rsp
             })
             .or_else(|error| {
-                Ok(response(
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    error.to_string(),
-                )
-                .expect("Failed to build response"))
+                let error = match &error {
+                    ExecutionError::Generic(e) => ApiError::from(e),
+                    e => ApiError::internal(e.to_string()),
+                };
+                Ok(error_response(error.status(), &error)
+                    .expect("Failed to build response"))
             })
         })
     }
@@ -485,8 +614,10 @@ enum SubscriptionAction {
     Unsubscribe(RuesEventUri),
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_stream_rues<H: HandleRequest>(
     sid: SessionId,
+    resumed: Option<Resumed>,
     websocket: HyperWebsocket,
     events: broadcast::Receiver<RuesEvent>,
     mut subscriptions: mpsc::Receiver<SubscriptionAction>,
@@ -495,6 +626,7 @@ async fn handle_stream_rues<H: HandleRequest>(
     sockets_map: Arc<
         RwLock<HashMap<SessionId, mpsc::Sender<SubscriptionAction>>>,
     >,
+    sessions: Arc<Sessions>,
 ) {
     let mut stream = match websocket.await {
         Ok(stream) => stream,
@@ -515,6 +647,35 @@ async fn handle_stream_rues<H: HandleRequest>(
     const DISPATCH_BUFFER_SIZE: usize = 16;
 
     let mut subscription_set = HashSet::new();
+    // Sequence number of the last event sent, so the events already replayed
+    // to a resumed session are not sent twice
+    let mut last_sent = 0;
+
+    if let Some(resumed) = resumed {
+        subscription_set = resumed.subscriptions;
+        for (sequence, mut event) in resumed.missed {
+            last_sent = sequence;
+            if !subscription_set.iter().any(|sub| sub.matches(&event)) {
+                continue;
+            }
+            event.add_header("Content-Location", event.uri.to_string());
+            if stream
+                .send(Message::Binary(event.to_bytes()))
+                .await
+                .is_err()
+            {
+                let _ = stream
+                    .close(Some(CloseFrame {
+                        code: CloseCode::Error,
+                        reason: Cow::from("Failed sending event"),
+                    }))
+                    .await;
+                sockets_map.write().await.remove(&sid);
+                sessions.detach(sid, subscription_set).await;
+                return;
+            }
+        }
+    }
 
     let mut events = BroadcastStream::new(events);
 
@@ -594,6 +755,16 @@ async fn handle_stream_rues<H: HandleRequest>(
                     }
                 };
 
+                let sequence = event
+                    .headers
+                    .get(SEQUENCE_HEADER)
+                    .and_then(|s| s.as_u64())
+                    .unwrap_or_default();
+                if sequence <= last_sent {
+                    continue;
+                }
+                last_sent = sequence;
+
                 // The event is subscribed to if it matches any of the subscriptions.
                 let mut is_subscribed = false;
                 for sub in &subscription_set {
@@ -624,6 +795,7 @@ async fn handle_stream_rues<H: HandleRequest>(
 
     let mut sockets = sockets_map.write().await;
     sockets.remove(&sid);
+    sessions.detach(sid, subscription_set).await;
 }
 
 async fn handle_dispatch<H: HandleRequest>(
@@ -672,12 +844,25 @@ fn response(
         .expect("Failed to build response"))
 }
 
+fn error_response(
+    status: StatusCode,
+    error: &ApiError,
+) -> Result<Response<FullOrStreamBody>, ExecutionError> {
+    let mut response = response(status, error.to_json())?;
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    Ok(response)
+}
+
 async fn handle_request_rues<H: HandleRequest>(
     mut req: Request<Incoming>,
     handler: Arc<H>,
     sockets_map: Arc<
         RwLock<HashMap<SessionId, mpsc::Sender<SubscriptionAction>>>,
     >,
+    sessions: Arc<Sessions>,
     events: broadcast::Receiver<RuesEvent>,
     shutdown: broadcast::Receiver<Infallible>,
     ws_event_channel_cap: usize,
@@ -686,31 +871,60 @@ async fn handle_request_rues<H: HandleRequest>(
         let (subscription_sender, subscriptions) =
             mpsc::channel(ws_event_channel_cap);
 
+        // A client reconnecting may resume its previous session, provided it
+        // tells the last event it received.
+        let resume = SessionId::parse_from_req(&req).zip(
+            req.headers()
+                .get(LAST_SEEN_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok()),
+        );
+
         let (response, websocket) = hyper_tungstenite::upgrade(&mut req, None)?;
 
         let mut sockets = sockets_map.write().await;
 
-        // This is a new WebSocket connection, so we generate a new random ID
-        // and create a new channel for it.
-        let mut sid = rand::random();
-        while sockets.contains_key(&sid) {
-            sid = rand::random();
-        }
+        let resumed = match resume {
+            Some((sid, last_seen)) if !sockets.contains_key(&sid) => sessions
+                .resume(sid, last_seen)
+                .await
+                .map(|resumed| (sid, resumed)),
+            _ => None,
+        };
+
+        // Otherwise this is a new session, so we generate a new random ID.
+        // The client learns whether its session was resumed from the ID sent
+        // as the first message.
+        let (sid, resumed) = match resumed {
+            Some((sid, resumed)) => (sid, Some(resumed)),
+            None => {
+                let mut sid = rand::random();
+                while sockets.contains_key(&sid) {
+                    sid = rand::random();
+                }
+                (sid, None)
+            }
+        };
         sockets.insert(sid, subscription_sender);
 
         task::spawn(handle_stream_rues(
             sid,
+            resumed,
             websocket,
             events,
             subscriptions,
             shutdown,
             handler.clone(),
             sockets_map.clone(),
+            sessions,
         ));
 
         Ok(response.map(Into::into))
     } else if req.method() == Method::POST {
-        let (event, binary_resp) = RuesDispatchEvent::from_request(req).await?;
+        let (event, binary_resp) =
+            RuesDispatchEvent::from_request(req)
+                .await
+                .map_err(|e| ApiError::invalid_request(format!("{e:#}")))?;
         let is_binary = event.is_binary();
         let mut resp_headers = event.x_headers();
         let (responder, mut receiver) = mpsc::unbounded_channel();
@@ -733,15 +947,62 @@ async fn handle_request_rues<H: HandleRequest>(
             resp.headers_mut().append(k, v);
         }
 
+        Ok(resp)
+    } else if req.method() == Method::GET && is_graphql_get(&req) {
+        let event = RuesDispatchEvent::from_graphql_get(&req)
+            .map_err(|e| ApiError::invalid_request(format!("{e:#}")))?;
+        let if_none_match = req
+            .headers()
+            .get(hyper::header::IF_NONE_MATCH)
+            .and_then(|v| v.to_str().ok())
+            .map(ToString::to_string);
+
+        let mut resp_headers = event.x_headers();
+        let (responder, mut receiver) = mpsc::unbounded_channel();
+        handle_execution_rues(handler, event, responder).await;
+
+        let execution_response = receiver
+            .recv()
+            .await
+            .expect("An execution should always return a response");
+        resp_headers.extend(execution_response.headers.clone());
+        let mut resp = execution_response.into_http(false)?;
+
+        for (k, v) in resp_headers {
+            let k = HeaderName::from_str(&k)?;
+            let v = match v {
+                serde_json::Value::String(s) => HeaderValue::from_str(&s),
+                serde_json::Value::Null => HeaderValue::from_str(""),
+                _ => HeaderValue::from_str(&v.to_string()),
+            }?;
+            resp.headers_mut().append(k, v);
+        }
+
+        // The query is not executed again for a client holding the response
+        // at the current tip
+        let etag = resp
+            .headers()
+            .get(hyper::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim_matches('"'));
+        if let (Some(if_none_match), Some(etag)) = (if_none_match, etag) {
+            if etag_matches(&if_none_match, etag) {
+                *resp.status_mut() = StatusCode::NOT_MODIFIED;
+            }
+        }
+
         Ok(resp)
     } else {
         let headers = req.headers();
 
         let sid = match SessionId::parse_from_req(&req) {
             None => {
-                return response(
+                return error_response(
                     StatusCode::FAILED_DEPENDENCY,
-                    "{\"error\":\"Session ID not provided or invalid\"}",
+                    &ApiError::new(
+                        ErrorCode::InvalidSession,
+                        "Session ID not provided or invalid",
+                    ),
                 );
             }
             Some(sid) => sid,
@@ -749,9 +1010,12 @@ async fn handle_request_rues<H: HandleRequest>(
 
         let uri = match RuesEventUri::parse_from_path(req.uri().path()) {
             None => {
-                return response(
+                return error_response(
                     StatusCode::NOT_FOUND,
-                    "{{\"error\":\"Invalid URL path\n\"}}",
+                    &ApiError::new(
+                        ErrorCode::UnsupportedLocation,
+                        "Invalid URL path",
+                    ),
                 );
             }
             Some(s) => s,
@@ -760,9 +1024,12 @@ async fn handle_request_rues<H: HandleRequest>(
         let action_sender = match sockets_map.read().await.get(&sid) {
             Some(sender) => sender.clone(),
             None => {
-                return response(
+                return error_response(
                     StatusCode::FAILED_DEPENDENCY,
-                    "{\"error\":\"Session ID not provided or invalid\"}",
+                    &ApiError::new(
+                        ErrorCode::InvalidSession,
+                        "Session ID not provided or invalid",
+                    ),
                 );
             }
         };
@@ -771,17 +1038,17 @@ async fn handle_request_rues<H: HandleRequest>(
             Method::GET => SubscriptionAction::Subscribe(uri),
             Method::DELETE => SubscriptionAction::Unsubscribe(uri),
             _ => {
-                return response(
+                return error_response(
                     StatusCode::METHOD_NOT_ALLOWED,
-                    "{\"error\":\"Method not allowed\"}",
+                    &ApiError::invalid_request("Method not allowed"),
                 );
             }
         };
 
         if action_sender.send(action).await.is_err() {
-            return response(
+            return error_response(
                 StatusCode::INTERNAL_SERVER_ERROR,
-                "{\"error\":\"Failed consuming request\"}",
+                &ApiError::internal("Failed consuming request"),
             );
         }
 
@@ -789,12 +1056,21 @@ async fn handle_request_rues<H: HandleRequest>(
     }
 }
 
+/// Returns whether a request is a GraphQL query sent in the parameters of the
+/// URL, rather than a subscription to the `graphql` component.
+fn is_graphql_get<B>(req: &Request<B>) -> bool {
+    let is_query = RuesEventUri::parse_from_path(req.uri().path())
+        .is_some_and(|uri| uri.inner() == ("graphql", None, "query"));
+    is_query && req.uri().query().is_some()
+}
+
 async fn handle_request<H>(
     mut req: Request<Incoming>,
     sources: Arc<H>,
     sockets_map: Arc<
         RwLock<HashMap<SessionId, mpsc::Sender<SubscriptionAction>>>,
     >,
+    sessions: Arc<Sessions>,
     events: broadcast::Receiver<RuesEvent>,
     shutdown: broadcast::Receiver<Infallible>,
     ws_event_channel_cap: usize,
@@ -810,6 +1086,7 @@ where
             req,
             sources.clone(),
             sockets_map,
+            sessions,
             events,
             shutdown,
             ws_event_channel_cap,
@@ -887,7 +1164,7 @@ async fn handle_execution<H>(
                 headers,
             }
         })
-        .unwrap_or_else(|e| request.to_error(e.to_string()));
+        .unwrap_or_else(|e| request.to_error(ApiError::from(&e)));
 
     rsp.set_header(RUSK_VERSION_HEADER, serde_json::json!(*VERSION));
     let _ = responder.send(rsp);
@@ -900,8 +1177,10 @@ async fn handle_execution_rues<H>(
 ) where
     H: HandleRequest,
 {
+    let span = info_span!("rues_request", uri = %event.uri);
     let mut rsp = sources
         .handle_rues(&event)
+        .instrument(span)
         .await
         .map(|data| {
             let (data, mut headers) = data.into_inner();
@@ -915,7 +1194,7 @@ async fn handle_execution_rues<H>(
         .unwrap_or_else(|e| EventResponse {
             headers: event.x_headers(),
             data: DataType::None,
-            error: Some(e.to_string()),
+            error: Some(ApiError::from(&e)),
         });
 
     rsp.set_header(RUSK_VERSION_HEADER, serde_json::json!(*VERSION));
@@ -1008,6 +1287,7 @@ mod tests {
             TestHandle,
             event_receiver,
             ws_event_channel_cap,
+            16,
             "localhost:0",
             HeaderMap::new(),
             cert_and_key,
@@ -1063,6 +1343,7 @@ mod tests {
             TestHandle,
             event_receiver,
             ws_event_channel_cap,
+            16,
             "localhost:0",
             HeaderMap::new(),
             Some((cert_path, key_path)),
@@ -1121,6 +1402,7 @@ mod tests {
             TestHandle,
             event_receiver,
             ws_event_channel_cap,
+            16,
             "localhost:0",
             HeaderMap::new(),
             cert_and_key,
@@ -1205,6 +1487,7 @@ mod tests {
             TestHandle,
             event_receiver,
             ws_event_channel_cap,
+            16,
             "localhost:0",
             HeaderMap::new(),
             cert_and_key,
@@ -1359,6 +1642,10 @@ mod tests {
     pub fn from_bytes(data: &[u8]) -> anyhow::Result<RuesEvent> {
         let (mut headers, data) = crate::http::event::parse_header(data)?;
 
+        headers
+            .remove(SEQUENCE_HEADER)
+            .ok_or(anyhow::anyhow!("Sequence is not set"))?;
+
         let path = headers
             .remove("Content-Location")
             .ok_or(anyhow::anyhow!("Content location is not set"))?
@@ -0,0 +1,395 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+#[cfg(feature = "chain")]
+use node::database::rocksdb::Backend;
+#[cfg(feature = "chain")]
+use node::database::{AuditEntry, AuditLog, DB};
+#[cfg(feature = "chain")]
+use node::network::Kadcast;
+#[cfg(feature = "chain")]
+use node::switches::{RuntimeSwitches, Switch};
+use serde_json::json;
+use tokio::sync::Notify;
+use tracing::info;
+#[cfg(feature = "chain")]
+use tracing::warn;
+
+use super::*;
+#[cfg(feature = "archive")]
+use crate::node::RuskNode;
+
+/// Header carrying the token authorizing `admin` requests.
+pub const ADMIN_TOKEN_HEADER: &str = "Rusk-Admin-Token";
+
+/// Principal recorded in the audit log for the requests authorized by the
+/// admin token.
+#[cfg(feature = "chain")]
+const ADMIN_PRINCIPAL: &str = "admin";
+
+/// Entries returned by an `audit-log` request, if no limit is given.
+#[cfg(feature = "chain")]
+const DEFAULT_AUDIT_LIMIT: usize = 100;
+
+/// Maximum number of entries returned by an `audit-log` request.
+#[cfg(feature = "chain")]
+const MAX_AUDIT_LIMIT: usize = 1000;
+
+/// The data source serving the `admin` RUES component.
+pub struct AdminService {
+    token: String,
+    reload_requests: Arc<Notify>,
+    #[cfg(feature = "chain")]
+    switches: Option<Arc<RuntimeSwitches>>,
+    #[cfg(feature = "chain")]
+    audit_log: Option<Arc<RwLock<Backend>>>,
+    #[cfg(feature = "chain")]
+    network: Option<Arc<RwLock<Kadcast<255>>>>,
+    #[cfg(feature = "archive")]
+    node: Option<RuskNode>,
+}
+
+/// Features the binary is compiled with.
+const COMPILED_FEATURES: [(&str, bool); 6] = [
+    ("chain", cfg!(feature = "chain")),
+    ("ephemeral", cfg!(feature = "ephemeral")),
+    ("prover", cfg!(feature = "prover")),
+    ("recovery-state", cfg!(feature = "recovery-state")),
+    ("recovery-keys", cfg!(feature = "recovery-keys")),
+    ("archive", cfg!(feature = "archive")),
+];
+
+/// Body of a `switch` request.
+#[cfg(feature = "chain")]
+#[derive(serde::Deserialize)]
+struct SwitchRequest {
+    switch: String,
+    enabled: bool,
+}
+
+/// Body of a `sim-loss` request.
+#[cfg(feature = "network-sim")]
+#[derive(serde::Deserialize)]
+struct SimLossRequest {
+    /// Ratio of the inbound messages to drop
+    loss: f64,
+}
+
+/// Body of an `audit-log` request.
+#[cfg(feature = "chain")]
+#[derive(serde::Deserialize, Default)]
+struct AuditLogRequest {
+    #[serde(default)]
+    from: u64,
+    limit: Option<usize>,
+}
+
+impl AdminService {
+    /// Creates the admin service, authorizing the requests carrying `token`.
+    ///
+    /// `reload_requests` is notified for every request to reload the node
+    /// configuration.
+    pub fn new(token: String, reload_requests: Arc<Notify>) -> Self {
+        Self {
+            token,
+            reload_requests,
+            #[cfg(feature = "chain")]
+            switches: None,
+            #[cfg(feature = "chain")]
+            audit_log: None,
+            #[cfg(feature = "chain")]
+            network: None,
+            #[cfg(feature = "archive")]
+            node: None,
+        }
+    }
+
+    /// Allows replaying the records of the stored blocks and their contract
+    /// events through the `Chain/replay_events` route.
+    #[cfg(feature = "archive")]
+    pub fn with_replay(mut self, node: RuskNode) -> Self {
+        self.node = Some(node);
+        self
+    }
+
+    /// Records the admin actions in the audit log of `db`, and allows
+    /// querying it.
+    #[cfg(feature = "chain")]
+    pub fn with_audit_log(mut self, db: Arc<RwLock<Backend>>) -> Self {
+        self.audit_log = Some(db);
+        self
+    }
+
+    /// Appends an action performed through the admin token to the audit
+    /// log, if any.
+    #[cfg(feature = "chain")]
+    async fn audit(&self, action: &str, details: serde_json::Value) {
+        let Some(db) = &self.audit_log else {
+            return;
+        };
+        let entry = AuditEntry::new(ADMIN_PRINCIPAL, action, details);
+        if let Err(e) = db.read().await.update(|t| t.append_audit_entry(&entry))
+        {
+            warn!("Cannot record admin action {action}: {e}");
+        }
+    }
+
+    #[cfg(feature = "chain")]
+    async fn audit_entries(
+        &self,
+        data: &[u8],
+    ) -> anyhow::Result<serde_json::Value> {
+        let db = self
+            .audit_log
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No audit log available"))?;
+
+        let request: AuditLogRequest = if data.is_empty() {
+            AuditLogRequest::default()
+        } else {
+            serde_json::from_slice(data)?
+        };
+        let limit = request
+            .limit
+            .unwrap_or(DEFAULT_AUDIT_LIMIT)
+            .min(MAX_AUDIT_LIMIT);
+
+        let entries = db
+            .read()
+            .await
+            .view(|t| t.audit_entries(request.from, limit))?;
+        let entries: Vec<_> = entries
+            .into_iter()
+            .map(|(seq, entry)| {
+                json!({
+                    "seq": seq,
+                    "timestamp": entry.timestamp,
+                    "principal": entry.principal,
+                    "action": entry.action,
+                    "details": entry.details,
+                })
+            })
+            .collect();
+
+        Ok(json!({ "entries": entries }))
+    }
+
+    /// Allows exporting signed snapshots of the peers of `network`, for new
+    /// nodes to bootstrap from, and setting its synthetic loss with the
+    /// `network-sim` feature.
+    #[cfg(feature = "chain")]
+    pub fn with_network(mut self, network: Arc<RwLock<Kadcast<255>>>) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    #[cfg(feature = "chain")]
+    async fn peer_snapshot(&self) -> anyhow::Result<serde_json::Value> {
+        let network = self
+            .network
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No network available"))?;
+        let snapshot = network.read().await.peer_snapshot().await?;
+        Ok(serde_json::to_value(snapshot)?)
+    }
+
+    #[cfg(feature = "network-sim")]
+    async fn set_sim_loss(
+        &self,
+        data: &[u8],
+    ) -> anyhow::Result<serde_json::Value> {
+        let network = self
+            .network
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No network available"))?;
+
+        let request: SimLossRequest = serde_json::from_slice(data)?;
+        network.read().await.set_sim_loss(request.loss)?;
+        info!(event = "admin sim loss request", loss = request.loss);
+
+        Ok(json!({ "loss": request.loss }))
+    }
+
+    /// Allows listing and flipping the runtime `switches` of the node.
+    #[cfg(feature = "chain")]
+    pub fn with_switches(mut self, switches: Arc<RuntimeSwitches>) -> Self {
+        self.switches = Some(switches);
+        self
+    }
+
+    fn features(&self) -> serde_json::Value {
+        let compiled: serde_json::Map<_, _> = COMPILED_FEATURES
+            .iter()
+            .map(|(name, enabled)| (name.to_string(), json!(enabled)))
+            .collect();
+
+        #[allow(unused_mut)]
+        let mut runtime = serde_json::Map::new();
+        #[cfg(feature = "chain")]
+        if let Some(switches) = &self.switches {
+            for switch in Switch::ALL {
+                runtime.insert(
+                    switch.to_string(),
+                    json!(switches.is_enabled(switch)),
+                );
+            }
+        }
+
+        json!({ "compiled": compiled, "runtime": runtime })
+    }
+
+    #[cfg(feature = "chain")]
+    fn set_switch(&self, data: &[u8]) -> anyhow::Result<serde_json::Value> {
+        let switches = self
+            .switches
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No runtime switch available"))?;
+
+        let request: SwitchRequest = serde_json::from_slice(data)?;
+        let switch: Switch = request.switch.parse()?;
+
+        let previous = switches.set(switch, request.enabled);
+        info!(
+            event = "admin switch request",
+            %switch,
+            enabled = request.enabled,
+            previous
+        );
+
+        Ok(json!({
+            "switch": switch.to_string(),
+            "enabled": request.enabled,
+            "previous": previous,
+        }))
+    }
+
+    fn check_token(
+        &self,
+        token: Option<&serde_json::Value>,
+    ) -> anyhow::Result<()> {
+        let token = token.and_then(|v| v.as_str()).ok_or_else(|| {
+            ApiError::new(ErrorCode::Unauthorized, "Missing admin token")
+        })?;
+
+        // Compare in constant time to avoid leaking the token
+        let matches = token.len() == self.token.len()
+            && token
+                .bytes()
+                .zip(self.token.bytes())
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0;
+        if !matches {
+            anyhow::bail!(ApiError::new(
+                ErrorCode::Unauthorized,
+                "Invalid admin token"
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HandleRequest for AdminService {
+    fn can_handle(&self, request: &MessageRequest) -> bool {
+        #[cfg(feature = "archive")]
+        if self.node.is_some() {
+            return matches!(
+                request.event.to_route(),
+                (Target::Host(_), "Chain", "replay_events")
+            );
+        }
+        false
+    }
+
+    fn can_handle_rues(&self, request: &RuesDispatchEvent) -> bool {
+        matches!(
+            request.uri.inner(),
+            (
+                "admin",
+                None,
+                "reload-config"
+                    | "features"
+                    | "switch"
+                    | "audit-log"
+                    | "peer-snapshot"
+                    | "sim-loss"
+            )
+        )
+    }
+
+    async fn handle_rues(
+        &self,
+        request: &RuesDispatchEvent,
+    ) -> anyhow::Result<ResponseData> {
+        self.check_token(request.header(ADMIN_TOKEN_HEADER))?;
+        match request.uri.inner() {
+            ("admin", None, "reload-config") => {
+                info!(event = "admin reload request");
+                #[cfg(feature = "chain")]
+                self.audit("reload-config", json!({})).await;
+                self.reload_requests.notify_one();
+                Ok(ResponseData::new(json!({ "reload": "requested" })))
+            }
+            ("admin", None, "features") => {
+                Ok(ResponseData::new(self.features()))
+            }
+            #[cfg(feature = "chain")]
+            ("admin", None, "switch") => {
+                let response = self.set_switch(request.data.as_bytes())?;
+                self.audit("switch", response.clone()).await;
+                Ok(ResponseData::new(response))
+            }
+            #[cfg(feature = "chain")]
+            ("admin", None, "audit-log") => {
+                let response =
+                    self.audit_entries(request.data.as_bytes()).await?;
+                Ok(ResponseData::new(response))
+            }
+            #[cfg(feature = "chain")]
+            ("admin", None, "peer-snapshot") => {
+                let snapshot = self.peer_snapshot().await?;
+                info!(
+                    event = "admin peer snapshot request",
+                    peers = snapshot["snapshot"]["peers"]
+                        .as_array()
+                        .map_or(0, Vec::len)
+                );
+                Ok(ResponseData::new(snapshot))
+            }
+            #[cfg(feature = "network-sim")]
+            ("admin", None, "sim-loss") => {
+                let response =
+                    self.set_sim_loss(request.data.as_bytes()).await?;
+                self.audit("sim-loss", response.clone()).await;
+                Ok(ResponseData::new(response))
+            }
+            _ => anyhow::bail!(ApiError::unsupported_location()),
+        }
+    }
+
+    async fn handle(
+        &self,
+        request: &MessageRequest,
+    ) -> anyhow::Result<ResponseData> {
+        self.check_token(request.header(ADMIN_TOKEN_HEADER))?;
+        match request.event.to_route() {
+            #[cfg(feature = "archive")]
+            (Target::Host(_), "Chain", "replay_events") => {
+                let node = self
+                    .node
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("No replay available"))?;
+                let data = request.event.data.as_string();
+                let from = super::chain::feed_start(data)?;
+                info!(event = "admin replay request", from);
+                self.audit("replay-events", json!({ "from": from })).await;
+                node.replay_events(from).await
+            }
+            _ => anyhow::bail!(ApiError::unsupported_location()),
+        }
+    }
+}
@@ -4,10 +4,23 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+mod committees;
+#[cfg(feature = "debug-state")]
+mod contract_raw;
+mod epoch;
+mod feed;
 mod geo;
+mod gql_get;
 pub mod graphql;
-
-use std::collections::HashMap;
+mod headers;
+mod preverify;
+mod raw_blocks;
+mod receipts;
+#[cfg(feature = "network-sim")]
+mod sim;
+mod snapshots;
+
+use std::collections::{BTreeMap, HashMap};
 use std::net::SocketAddr;
 use std::sync::Arc;
 
@@ -20,7 +33,17 @@ use node::Network;
 use node_data::ledger::Transaction;
 use node_data::message::Message;
 
+use committees::CommitteeRequest;
+#[cfg(feature = "debug-state")]
+use contract_raw::ContractRawRequest;
+use epoch::parse_provisioner;
+pub(crate) use feed::{feed_start, FeedHub};
 use graphql::{DBContext, Query};
+use headers::HeadersRequest;
+use raw_blocks::RawBlocksRequest;
+pub(crate) use raw_blocks::MAX_RAW_STREAMS;
+use receipts::parse_tx_id;
+use snapshots::parse_epoch;
 
 use async_graphql::{
     EmptyMutation, EmptySubscription, Name, Schema, Variables,
@@ -32,8 +55,6 @@ use super::*;
 use crate::node::RuskNode;
 use crate::{VERSION, VERSION_BUILD};
 
-const GQL_VAR_PREFIX: &str = "rusk-gqlvar-";
-
 fn variables_from_headers(headers: &Map<String, Value>) -> Variables {
     let mut var = Variables::default();
     headers
@@ -58,6 +79,10 @@ impl HandleRequest for RuskNode {
         if matches!(route, (Target::Host(_), "rusk", "preverify")) {
             return true;
         }
+        // Served by the admin service, which authorizes it
+        if matches!(route, (Target::Host(_), "Chain", "replay_events")) {
+            return false;
+        }
         matches!(route, (Target::Host(_), "Chain", _))
     }
 
@@ -66,11 +91,21 @@ impl HandleRequest for RuskNode {
         match request.uri.inner() {
             ("graphql", _, "query") => true,
             ("transactions", _, "preverify") => true,
+            ("transactions", _, "verdict") => true,
             ("transactions", _, "propagate") => true,
             ("network", _, "peers") => true,
             ("network", _, "peers_location") => true,
+            #[cfg(feature = "network-sim")]
+            ("network", _, "sim") => true,
             ("node", _, "info") => true,
             ("blocks", _, "gas-price") => true,
+            ("chain", _, "feed") => true,
+            ("blocks", _, "headers") => true,
+            ("blocks", _, "raw") => true,
+            ("chain", _, "epoch") => true,
+            ("chain", _, "committee") => true,
+            ("chain", _, "provisioners") => true,
+            ("transactions", _, "receipt") => true,
             _ => false,
         }
     }
@@ -79,21 +114,32 @@ impl HandleRequest for RuskNode {
         request: &RuesDispatchEvent,
     ) -> anyhow::Result<ResponseData> {
         match request.uri.inner() {
+            ("graphql", _, "query") if request.method == Method::GET => {
+                self.handle_gql_get(request).await
+            }
             ("graphql", _, "query") => {
                 self.handle_gql(&request.data, &request.headers).await
             }
             ("transactions", _, "preverify") => {
                 self.handle_preverify(request.data.as_bytes()).await
             }
+            ("transactions", _, "verdict") => {
+                self.preverify_verdict(request.data.as_bytes()).await
+            }
             ("transactions", _, "propagate") => {
                 self.propagate_tx(request.data.as_bytes()).await
             }
             ("network", _, "peers") => {
-                let amount = request.data.as_string().trim().parse()?;
-                self.alive_nodes(amount).await
+                let data = request.data.as_string();
+                match data.trim() {
+                    "" => self.peers_inventory().await,
+                    amount => self.alive_nodes(amount.parse()?).await,
+                }
             }
 
             ("network", _, "peers_location") => self.peers_location().await,
+            #[cfg(feature = "network-sim")]
+            ("network", _, "sim") => self.network_sim().await,
             ("node", _, "info") => self.get_info().await,
             ("blocks", _, "gas-price") => {
                 let max_transactions = request
@@ -104,7 +150,36 @@ impl HandleRequest for RuskNode {
                     .unwrap_or(usize::MAX);
                 self.get_gas_price(max_transactions).await
             }
-            _ => anyhow::bail!("Unsupported"),
+            ("chain", _, "feed") => {
+                let from = feed_start(request.data.as_string())?;
+                self.chain_feed(from).await
+            }
+            ("blocks", _, "headers") => {
+                let request = HeadersRequest::parse(&request.data.as_string())?;
+                self.header_proofs(request).await
+            }
+            ("blocks", _, "raw") => {
+                let data = request.data.as_string();
+                self.raw_blocks(RawBlocksRequest::parse(&data)?).await
+            }
+            ("chain", _, "epoch") => {
+                let provisioner = parse_provisioner(request.data.as_string())?;
+                self.epoch_info(provisioner).await
+            }
+            ("chain", _, "committee") => {
+                let data = request.data.as_string();
+                let (round, iteration, step) = CommitteeRequest::parse(&data)?;
+                self.committee_proof(round, iteration, step).await
+            }
+            ("chain", _, "provisioners") => {
+                let epoch = parse_epoch(request.data.as_string())?;
+                self.provisioners_snapshot(epoch).await
+            }
+            ("transactions", _, "receipt") => {
+                let tx_id = parse_tx_id(request.data.as_string())?;
+                self.receipt_proof(tx_id).await
+            }
+            _ => anyhow::bail!(ApiError::unsupported_location()),
         }
     }
     async fn handle(
@@ -118,6 +193,9 @@ impl HandleRequest for RuskNode {
             (Target::Host(_), "rusk", "preverify") => {
                 self.handle_preverify(request.event_data()).await
             }
+            (Target::Host(_), "Chain", "preverify") => {
+                self.preverify_verdict(request.event_data()).await
+            }
             (Target::Host(_), "Chain", "propagate_tx") => {
                 self.propagate_tx(request.event_data()).await
             }
@@ -136,10 +214,28 @@ impl HandleRequest for RuskNode {
                     .unwrap_or(usize::MAX);
                 self.get_gas_price(max_transactions).await
             }
-            _ => anyhow::bail!("Unsupported"),
+            (Target::Host(_), "Chain", "feed") => {
+                let from = feed_start(request.event.data.as_string())?;
+                self.chain_feed(from).await
+            }
+            (Target::Host(_), "Chain", "headers") => {
+                let data = request.event.data.as_string();
+                self.header_proofs(HeadersRequest::parse(&data)?).await
+            }
+            (Target::Host(_), "Chain", "epoch") => {
+                let data = request.event.data.as_string();
+                self.epoch_info(parse_provisioner(data)?).await
+            }
+            #[cfg(feature = "debug-state")]
+            (Target::Host(_), "Chain", "contract_raw") => {
+                let data = request.event.data.as_string();
+                self.contract_raw(ContractRawRequest::parse(&data)?).await
+            }
+            _ => anyhow::bail!(ApiError::unsupported_location()),
         }
     }
 }
+
 impl RuskNode {
     async fn handle_gql(
         &self,
@@ -147,14 +243,20 @@ impl RuskNode {
         headers: &serde_json::Map<String, Value>,
     ) -> anyhow::Result<ResponseData> {
         let gql_query = data.as_string();
+        let vm = self.inner().vm_handler();
+        let contract_stats = vm.read().await.contract_stats.clone();
 
         #[cfg(feature = "archive")]
         let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
             .data((self.db(), self.archive()))
+            .data(contract_stats)
+            .data(vm)
             .finish();
         #[cfg(not(feature = "archive"))]
         let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
             .data((self.db(), ()))
+            .data(contract_stats)
+            .data(vm)
             .finish();
 
         if gql_query.trim().is_empty() {
@@ -168,7 +270,16 @@ impl RuskNode {
         let gql_res = schema.execute(gql_query).await;
         let async_graphql::Response { data, errors, .. } = gql_res;
         if !errors.is_empty() {
-            return Err(anyhow::anyhow!(serde_json::to_value(errors)?));
+            let code = match errors.iter().any(|e| !e.path.is_empty()) {
+                true => ErrorCode::QueryFailed,
+                false => ErrorCode::InvalidQuery,
+            };
+            let message = errors
+                .iter()
+                .map(|e| e.message.as_str())
+                .collect::<Vec<_>>()
+                .join("; ");
+            return Err(ApiError::new(code, message).into());
         }
         let data = serde_json::to_value(&data)
             .map_err(|e| anyhow::anyhow!("Cannot parse response {e}"))?;
@@ -179,8 +290,14 @@ impl RuskNode {
         &self,
         data: &[u8],
     ) -> anyhow::Result<ResponseData> {
-        let tx = dusk_core::transfer::Transaction::from_slice(data)
-            .map_err(|e| anyhow::anyhow!("Invalid Data {e:?}"))?;
+        let tx = dusk_core::transfer::Transaction::from_slice(data).map_err(
+            |e| {
+                ApiError::new(
+                    ErrorCode::InvalidTransaction,
+                    format!("Invalid Data {e:?}"),
+                )
+            },
+        )?;
         let db = self.inner().database();
         let vm = self.inner().vm_handler();
         let tx = tx.into();
@@ -189,15 +306,27 @@ impl RuskNode {
             .await
             .map_err(|e| {
                 error!("Tx {} not accepted: {e}", hex::encode(tx.id()));
-                e
+                ApiError::new(ErrorCode::TransactionRejected, e.to_string())
             })?;
 
         Ok(ResponseData::new(DataType::None))
     }
 
     async fn propagate_tx(&self, tx: &[u8]) -> anyhow::Result<ResponseData> {
+        if self.is_replica() {
+            anyhow::bail!(ApiError::new(
+                ErrorCode::ReadOnlyReplica,
+                "Transactions are not accepted by a query replica"
+            ));
+        }
+
         let tx: Transaction = ProtocolTransaction::from_slice(tx)
-            .map_err(|e| anyhow::anyhow!("Invalid Data {e:?}"))?
+            .map_err(|e| {
+                ApiError::new(
+                    ErrorCode::InvalidTransaction,
+                    format!("Invalid Data {e:?}"),
+                )
+            })?
             .into();
         let tx_message = tx.into();
 
@@ -213,6 +342,25 @@ impl RuskNode {
         Ok(ResponseData::new(serde_json::to_value(nodes)?))
     }
 
+    /// Lists the peers of the routing table, along with their count per
+    /// bucket.
+    async fn peers_inventory(&self) -> anyhow::Result<ResponseData> {
+        let peers = self.network().read().await.peers().await;
+
+        let mut buckets = BTreeMap::<u8, usize>::new();
+        for peer in &peers {
+            *buckets.entry(peer.bucket).or_default() += 1;
+        }
+        let alive = peers.iter().filter(|p| p.alive).count();
+
+        Ok(ResponseData::new(json!({
+            "total": peers.len(),
+            "alive": alive,
+            "buckets": buckets,
+            "peers": peers,
+        })))
+    }
+
     async fn get_info(&self) -> anyhow::Result<ResponseData> {
         let mut info: HashMap<&str, serde_json::Value> = HashMap::new();
         info.insert("version", VERSION.as_str().into());
@@ -222,6 +370,22 @@ impl RuskNode {
         info.insert("bootstrapping_nodes", n_conf.bootstrapping_nodes.into());
         info.insert("chain_id", n_conf.kadcast_id.into());
         info.insert("kadcast_address", n_conf.public_address.into());
+        info.insert("replica", self.is_replica().into());
+        info.insert("replicas", self.replicas().into());
+
+        let upgrades: Vec<_> = self
+            .upgrades()
+            .upgrades()
+            .iter()
+            .map(|u| {
+                serde_json::json!({
+                    "name": u.name,
+                    "height": u.height,
+                    "supported": u.is_supported(),
+                })
+            })
+            .collect();
+        info.insert("upgrades", upgrades.into());
 
         Ok(ResponseData::new(serde_json::to_value(&info)?))
     }
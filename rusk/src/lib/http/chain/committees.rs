@@ -0,0 +1,142 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Committees of the consensus steps, along with the inputs of the
+//! deterministic sortition they are drawn with, for auditors to recompute
+//! them.
+
+use dusk_consensus::user::committee::Committee;
+use node::database::Ledger;
+use node::vm::VMExecution;
+use node_data::bls::PublicKeyBytes;
+use node_data::ledger::CommitteeMember;
+use node_data::StepName;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct CommitteeRequest {
+    round: u64,
+    #[serde(default)]
+    iteration: u8,
+    step: String,
+}
+
+impl CommitteeRequest {
+    pub(super) fn parse(data: &str) -> anyhow::Result<(u64, u8, StepName)> {
+        let request: Self = serde_json::from_str(data).map_err(|e| {
+            ApiError::invalid_request(format!("Invalid committee request: {e}"))
+        })?;
+        let step = match request.step.to_lowercase().as_str() {
+            "proposal" => StepName::Proposal,
+            "validation" => StepName::Validation,
+            "ratification" => StepName::Ratification,
+            step => anyhow::bail!(ApiError::invalid_request(format!(
+                "Invalid committee step: {step}"
+            ))),
+        };
+        Ok((request.round, request.iteration, step))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CommitteeProof {
+    round: u64,
+    iteration: u8,
+    /// Step number hashed by the sortition, `iteration * 3 + step`
+    sortition_step: u8,
+    /// Hex seed of the block preceding the round
+    seed: String,
+    committee_credits: usize,
+    /// Provisioners left out of the sortition
+    excluded: Vec<PublicKeyBytes>,
+    /// Provisioners eligible at the round, in sortition order
+    provisioners: Vec<EligibleStake>,
+    members: Vec<CommitteeMember>,
+}
+
+#[derive(Serialize)]
+struct EligibleStake {
+    key: PublicKeyBytes,
+    /// Stake weighting the provisioner in the sortition
+    value: u64,
+}
+
+impl RuskNode {
+    /// Returns the committee of `step` at `iteration` of `round`, along with
+    /// the sortition inputs.
+    ///
+    /// The committees of a round are drawn from the state of the block
+    /// preceding it, so no committee is returned once that state is no
+    /// longer available.
+    pub(super) async fn committee_proof(
+        &self,
+        round: u64,
+        iteration: u8,
+        step: StepName,
+    ) -> anyhow::Result<ResponseData> {
+        let Some(height) = round.checked_sub(1) else {
+            anyhow::bail!(ApiError::not_found(
+                "The genesis block has no committees"
+            ));
+        };
+        let prev = self.db().read().await.view(|t| -> anyhow::Result<_> {
+            t.block_hash_by_height(height)?
+                .map(|hash| t.block_header(&hash))
+                .transpose()?
+                .flatten()
+                .ok_or_else(|| {
+                    ApiError::not_found(format!("Block {height} not found"))
+                })
+        })?;
+
+        let provisioners = self
+            .inner()
+            .vm_handler()
+            .read()
+            .await
+            .get_provisioners(prev.state_hash)
+            .map_err(|e| {
+                anyhow::anyhow!("Cannot get the committees of {round}: {e}")
+            })?;
+
+        let (committee, cfg) = Committee::for_step(
+            &provisioners,
+            prev.seed,
+            round,
+            iteration,
+            step,
+        );
+
+        let proof = CommitteeProof {
+            round,
+            iteration,
+            sortition_step: cfg.step(),
+            seed: hex::encode(cfg.seed().inner()),
+            committee_credits: cfg.committee_credits(),
+            excluded: cfg.exclusion().clone(),
+            provisioners: provisioners
+                .eligibles(round)
+                .map(|(pk, stake)| EligibleStake {
+                    key: *pk.bytes(),
+                    value: stake.value(),
+                })
+                .collect(),
+            members: committee
+                .members()
+                .iter()
+                .map(|(pk, credits)| CommitteeMember {
+                    key: *pk.bytes(),
+                    credits: *credits,
+                })
+                .collect(),
+        };
+
+        Ok(ResponseData::new(serde_json::to_value(proof)?))
+    }
+}
@@ -0,0 +1,77 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Raw state of the contracts, to debug state divergences on devnets.
+
+use dusk_core::abi::ContractId;
+use serde::Deserialize;
+
+use super::*;
+use crate::node::inspect::MAX_PAGES;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ContractRawRequest {
+    /// Hex encoded ID of the contract
+    contract: String,
+    /// Hex encoded commit, the tip if not given
+    #[serde(default)]
+    commit: Option<String>,
+    #[serde(default)]
+    from: usize,
+    #[serde(default)]
+    count: Option<usize>,
+}
+
+impl ContractRawRequest {
+    pub(super) fn parse(data: &str) -> anyhow::Result<Self> {
+        let request = serde_json::from_str(data).map_err(|e| {
+            ApiError::invalid_request(format!(
+                "Invalid contract raw request: {e}"
+            ))
+        })?;
+        Ok(request)
+    }
+}
+
+fn decode_32(name: &str, data: &str) -> anyhow::Result<[u8; 32]> {
+    let mut bytes = [0u8; 32];
+    hex::decode_to_slice(data.trim(), &mut bytes).map_err(|e| {
+        ApiError::invalid_request(format!("Invalid {name}: {e}"))
+    })?;
+    Ok(bytes)
+}
+
+impl RuskNode {
+    /// Returns the memory pages of a contract at a commit, along with its
+    /// decoded state if it is a genesis contract.
+    pub(super) async fn contract_raw(
+        &self,
+        request: ContractRawRequest,
+    ) -> anyhow::Result<ResponseData> {
+        let contract =
+            ContractId::from_bytes(decode_32("contract", &request.contract)?);
+        let commit = request
+            .commit
+            .as_deref()
+            .map(|commit| decode_32("commit", commit))
+            .transpose()?;
+        let count = request.count.unwrap_or(MAX_PAGES);
+        if count > MAX_PAGES {
+            anyhow::bail!(ApiError::invalid_request(format!(
+                "Cannot return more than {MAX_PAGES} pages"
+            )));
+        }
+
+        let state = self.inner().vm_handler().read().await.inspect_contract(
+            contract,
+            commit,
+            request.from,
+            count,
+        )?;
+
+        Ok(ResponseData::new(state))
+    }
+}
@@ -0,0 +1,149 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! The epoch calendar of the stake contract, as seen from the chain tip.
+
+use async_graphql::SimpleObject;
+use dusk_bytes::Serializable;
+use dusk_core::signatures::bls::PublicKey as BlsPublicKey;
+use dusk_core::stake::{StakeConfig, StakeData};
+use node::database::rocksdb::MD_HASH_KEY;
+use node::database::{Ledger, Metadata};
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+use super::*;
+use crate::node::Rusk;
+
+#[derive(Serialize, SimpleObject)]
+pub struct EpochInfo {
+    /// Height of the chain tip
+    height: u64,
+    epoch: u64,
+    /// Height of the first block of the current epoch
+    epoch_start: u64,
+    /// Height of the first block of the next epoch
+    next_epoch: u64,
+    blocks_remaining: u64,
+    /// Height a stake created in the next block becomes eligible at
+    new_stake_eligibility: u64,
+    /// Stake of the requested provisioner, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provisioner: Option<ProvisionerEpoch>,
+}
+
+/// Unstaked funds are returned in the same block, so only the eligibility
+/// of the stake is scheduled.
+#[derive(Serialize, SimpleObject)]
+pub struct ProvisionerEpoch {
+    key: String,
+    /// Whether the provisioner has a stake in the contract
+    staked: bool,
+    /// Height the stake is eligible from, shifted forward when suspended
+    eligibility: Option<u64>,
+    /// Whether the stake is eligible for the next block
+    eligible: bool,
+    blocks_to_eligibility: u64,
+    faults: u8,
+    hard_faults: u8,
+}
+
+/// Parses the optional base58 key of the provisioner to report about.
+pub(super) fn parse_provisioner(
+    data: String,
+) -> anyhow::Result<Option<BlsPublicKey>> {
+    let data = data.trim();
+    if data.is_empty() {
+        return Ok(None);
+    }
+    let mut bytes = [0u8; BlsPublicKey::SIZE];
+    bs58::decode(data).into(&mut bytes).map_err(|e| {
+        ApiError::invalid_request(format!("Invalid provisioner key: {e}"))
+    })?;
+    let key = BlsPublicKey::from_bytes(&bytes).map_err(|e| {
+        ApiError::invalid_request(format!("Invalid provisioner key: {e:?}"))
+    })?;
+    Ok(Some(key))
+}
+
+/// Returns the epoch calendar of the stake contract at the chain tip, with
+/// the stake of the `provisioner`, if any.
+pub(super) async fn epoch_info(
+    db: &RwLock<Backend>,
+    vm: &RwLock<Rusk>,
+    provisioner: Option<BlsPublicKey>,
+) -> anyhow::Result<EpochInfo> {
+    let height = db.read().await.view(|t| -> anyhow::Result<_> {
+        let hash = t
+            .op_read(MD_HASH_KEY)?
+            .ok_or_else(|| anyhow::anyhow!("Cannot find the tip"))?;
+        let header = t
+            .block_header(&hash)?
+            .ok_or_else(|| anyhow::anyhow!("Cannot find the tip"))?;
+        Ok(header.height)
+    })?;
+
+    let vm = vm.read().await;
+    let config = vm.stake_config()?;
+    let provisioner = match provisioner {
+        Some(key) => {
+            let stake = vm.provisioner(&key)?;
+            Some(ProvisionerEpoch::new(&key, stake, height))
+        }
+        None => None,
+    };
+
+    Ok(EpochInfo::new(height, &config, provisioner))
+}
+
+impl RuskNode {
+    pub(super) async fn epoch_info(
+        &self,
+        provisioner: Option<BlsPublicKey>,
+    ) -> anyhow::Result<ResponseData> {
+        let vm = self.inner().vm_handler();
+        let info = epoch_info(&self.db(), &vm, provisioner).await?;
+        Ok(ResponseData::new(serde_json::to_value(info)?))
+    }
+}
+
+impl EpochInfo {
+    fn new(
+        height: u64,
+        config: &StakeConfig,
+        provisioner: Option<ProvisionerEpoch>,
+    ) -> Self {
+        let next_epoch = config.next_epoch(height);
+        Self {
+            height,
+            epoch: height / config.epoch,
+            epoch_start: next_epoch - config.epoch,
+            next_epoch,
+            blocks_remaining: next_epoch - height,
+            new_stake_eligibility: config.eligibility(height + 1),
+            provisioner,
+        }
+    }
+}
+
+impl ProvisionerEpoch {
+    fn new(key: &BlsPublicKey, stake: Option<StakeData>, height: u64) -> Self {
+        let stake = stake.unwrap_or_default();
+        let next_height = height + 1;
+        let eligibility = stake.amount.map(|amount| amount.eligibility);
+        Self {
+            key: bs58::encode(key.to_bytes()).into_string(),
+            staked: stake.amount.is_some(),
+            eligibility,
+            eligible: stake.is_valid(next_height),
+            blocks_to_eligibility: eligibility
+                .map(|e| e.saturating_sub(next_height))
+                .unwrap_or_default(),
+            faults: stake.faults,
+            hard_faults: stake.hard_faults,
+        }
+    }
+}
@@ -0,0 +1,635 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Ordered and resumable feed of the changes to the chain, for indexers
+//! mirroring it.
+//!
+//! The feed replays the blocks stored from a given height and then follows
+//! the chain as it changes, writing one JSON record per line:
+//!
+//! ```json
+//! {"seq":0,"type":"blockAccepted","height":1,"hash":"..","data":{..}}
+//! ```
+//!
+//! The records are, in order of appearance for every block:
+//! - `blockAccepted`, with the header, the transaction ids and the contract
+//!   events not originating from a transaction
+//! - `txExecuted`, for every transaction of the block, with its contract events
+//! - `blockFinalized`, once the block reached finality
+//!
+//! A `reorg` record reports the blocks reverted from the chain, which are
+//! followed by the `blockAccepted` records of the new branch.
+//!
+//! The changes to the chain are turned into records once for the whole node,
+//! and `seq` numbers them node-wide: a record carries the same `seq` on every
+//! feed following the chain, and increases by one with every record of the
+//! node. A feed skips the records of the blocks it replayed, so its `seq` can
+//! jump. The replayed records are rebuilt from the database for every feed,
+//! and their `seq` is `null`.
+//!
+//! At most [`MAX_REPLAY_BLOCKS`] blocks are replayed by a feed. If the node
+//! cannot keep up with the chain, or more blocks are left to replay, the feed
+//! ends with an `interrupted` record whose `height` is the one to resume from.
+//!
+//! The same records are replayed by the `Chain/replay_events` admin route,
+//! which ends at the tip instead of following the chain, so that an indexer
+//! that lost its database can rebuild it. It replays at most
+//! [`MAX_REBUILD_BLOCKS`] blocks, and ends with an `interrupted` record if
+//! more are left.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use node::database::Ledger;
+use node_data::events::{
+    BlockEvent, BlockState, EventSource, TransactionEvent,
+};
+use node_data::ledger::{Block, Label, SpentTransaction};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc as async_mpsc;
+use tracing::debug;
+
+use super::*;
+
+const BLOCK_ACCEPTED: &str = "blockAccepted";
+const TX_EXECUTED: &str = "txExecuted";
+const BLOCK_FINALIZED: &str = "blockFinalized";
+const REORG: &str = "reorg";
+const INTERRUPTED: &str = "interrupted";
+
+/// Header carrying the id of the transaction a contract event originates
+/// from.
+const ORIGIN_HEADER: &str = "Rusk-Origin";
+
+/// Maximum number of blocks replayed by a feed before it is interrupted.
+pub(crate) const MAX_REPLAY_BLOCKS: u64 = 10_000;
+
+/// Maximum number of blocks replayed by the `Chain/replay_events` admin route
+/// before it is interrupted.
+#[cfg(feature = "archive")]
+pub(crate) const MAX_REBUILD_BLOCKS: u64 = 100_000;
+
+/// Number of records written ahead of a client before the feed waits for it
+/// to read them.
+const FEED_BUFFER: usize = 256;
+
+/// Number of live records kept for the feeds lagging behind.
+const LIVE_BUFFER: usize = 4096;
+
+/// Parses the height the chain feed starts at, the genesis if not given.
+pub(crate) fn feed_start(data: String) -> anyhow::Result<u64> {
+    let data = data.trim();
+    if data.is_empty() {
+        return Ok(0);
+    }
+    data.parse()
+        .map_err(|e| anyhow::anyhow!("Invalid feed height {data}: {e}"))
+}
+
+impl RuskNode {
+    /// Streams the records of the chain feed, starting at the block at
+    /// `from`.
+    pub async fn chain_feed(&self, from: u64) -> anyhow::Result<ResponseData> {
+        let hub = self
+            .feed_hub()
+            .get_or_init(|| async {
+                let events = self
+                    .inner()
+                    .vm_handler()
+                    .read()
+                    .await
+                    .event_sender
+                    .subscribe();
+                FeedHub::start(events)
+            })
+            .await;
+        // Subscribe before replaying, so that no change happening in the
+        // meantime is missed
+        let live = hub.subscribe();
+
+        let (sender, receiver) = async_mpsc::channel(FEED_BUFFER);
+        let mut feed = ChainFeed::new(self.clone(), sender);
+
+        tokio::spawn(async move {
+            if let Err(e) = feed.run(from, live).await {
+                debug!("chain feed from {from} ended: {e}");
+            }
+        });
+
+        Ok(ResponseData::new(receiver))
+    }
+
+    /// Streams the records of the blocks stored from `from` to the tip, along
+    /// with their archived contract events, and ends there.
+    ///
+    /// The replay is interrupted after [`MAX_REBUILD_BLOCKS`] blocks.
+    #[cfg(feature = "archive")]
+    pub async fn replay_events(
+        &self,
+        from: u64,
+    ) -> anyhow::Result<ResponseData> {
+        let (sender, receiver) = async_mpsc::channel(FEED_BUFFER);
+        let mut feed = ChainFeed::new(self.clone(), sender);
+
+        tokio::spawn(async move {
+            if let Err(e) = feed.rebuild(from).await {
+                debug!("events replay from {from} ended: {e}");
+            }
+        });
+
+        Ok(ResponseData::new(receiver))
+    }
+}
+
+/// A record of the feed.
+#[derive(Debug, Clone, PartialEq)]
+struct Record {
+    /// Node-wide number of a live record
+    seq: Option<u64>,
+    kind: &'static str,
+    height: u64,
+    hash: String,
+    data: Value,
+}
+
+impl Record {
+    fn new(kind: &'static str, height: u64, hash: &str, data: Value) -> Self {
+        Self {
+            seq: None,
+            kind,
+            height,
+            hash: hash.to_string(),
+            data,
+        }
+    }
+
+    fn to_line(&self) -> anyhow::Result<Vec<u8>> {
+        let record = json!({
+            "seq": self.seq,
+            "type": self.kind,
+            "height": self.height,
+            "hash": self.hash,
+            "data": self.data,
+        });
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+        Ok(line)
+    }
+}
+
+/// Turns the changes to the chain of the node into the live records of the
+/// feed, once for all the feeds following it.
+pub(crate) struct FeedHub {
+    live: broadcast::Sender<Arc<Record>>,
+}
+
+impl FeedHub {
+    fn start(mut events: broadcast::Receiver<RuesEvent>) -> Self {
+        let (live, _) = broadcast::channel(LIVE_BUFFER);
+        let sender = live.clone();
+
+        tokio::spawn(async move {
+            let mut records = Records::default();
+            let mut seq = 0;
+
+            loop {
+                let follows = match events.recv().await {
+                    Ok(event) => match records.follow(event) {
+                        Ok(follows) => follows,
+                        Err(e) => {
+                            debug!("cannot follow chain event: {e}");
+                            continue;
+                        }
+                    },
+                    Err(RecvError::Lagged(missed)) => {
+                        // The feeds following the chain resume on their own
+                        debug!("chain feed lagged behind by {missed} events");
+                        records = Records::default();
+                        vec![Record::new(INTERRUPTED, 0, "", Value::Null)]
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+
+                for mut record in follows {
+                    record.seq = Some(seq);
+                    seq += 1;
+                    // There might be no feed following the chain
+                    let _ = sender.send(Arc::new(record));
+                }
+            }
+        });
+
+        Self { live }
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<Arc<Record>> {
+        self.live.subscribe()
+    }
+}
+
+/// Assembles the records of the blocks from the changes to the chain.
+#[derive(Default)]
+struct Records {
+    /// Transactions executed by the block being accepted, by id
+    executed: HashMap<String, Value>,
+    /// Contract events emitted by the block being accepted, by origin
+    events: HashMap<String, Vec<Value>>,
+}
+
+impl Records {
+    /// Turns a live event into records.
+    ///
+    /// The executed transactions and the contract events precede the block
+    /// they belong to, so they are held until the block is accepted.
+    fn follow(&mut self, event: RuesEvent) -> anyhow::Result<Vec<Record>> {
+        let data = match &event.data {
+            DataType::Json(data) => data.clone(),
+            data => serde_json::to_value(data)?,
+        };
+
+        let record = match event.uri.inner() {
+            ("contracts", Some(target), topic) => {
+                let origin = event
+                    .headers
+                    .get(ORIGIN_HEADER)
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                self.events.entry(origin.to_string()).or_default().push(
+                    json!({ "target": target, "topic": topic, "data": data }),
+                );
+                return Ok(vec![]);
+            }
+            ("transactions", Some(id), "executed") => {
+                self.executed.insert(id.clone(), data);
+                return Ok(vec![]);
+            }
+            ("blocks", Some(hash), "accepted") => {
+                return Ok(self.accept(hash, data));
+            }
+            ("blocks", Some(hash), "statechange") => {
+                let height = data["atHeight"].as_u64().unwrap_or_default();
+                let finalized = data["state"].as_str()
+                    == Some(BlockState::Finalized.as_str());
+                if !finalized {
+                    return Ok(vec![]);
+                }
+                Record::new(BLOCK_FINALIZED, height, hash, Value::Null)
+            }
+            ("blocks", Some(hash), "reverted") => {
+                let height = data["atHeight"].as_u64().unwrap_or_default();
+                Record::new(REORG, height, hash, Value::Null)
+            }
+            _ => return Ok(vec![]),
+        };
+
+        Ok(vec![record])
+    }
+
+    /// Returns the records of an accepted block and of its transactions.
+    fn accept(&mut self, hash: &str, mut data: Value) -> Vec<Record> {
+        let height = data["header"]["height"].as_u64().unwrap_or_default();
+        let tx_ids: Vec<String> =
+            serde_json::from_value(data["transactions"].take())
+                .unwrap_or_default();
+
+        let mut executed = std::mem::take(&mut self.executed);
+        let mut events = std::mem::take(&mut self.events);
+
+        // Events not originating from a transaction, such as the rewards,
+        // belong to the block
+        let block_events: Vec<_> = events
+            .iter_mut()
+            .filter(|(origin, _)| !tx_ids.contains(origin))
+            .flat_map(|(_, events)| events.drain(..))
+            .collect();
+        data["transactions"] = json!(tx_ids);
+        data["events"] = json!(block_events);
+
+        let mut records = Vec::with_capacity(tx_ids.len() + 1);
+        records.push(Record::new(BLOCK_ACCEPTED, height, hash, data));
+        for id in tx_ids {
+            let mut tx = executed.remove(&id).unwrap_or_default();
+            tx["events"] = json!(events.remove(&id).unwrap_or_default());
+            records.push(Record::new(TX_EXECUTED, height, &id, tx));
+        }
+        records
+    }
+}
+
+/// Position of a feed in the chain, deciding which live records it writes.
+#[derive(Debug, Default)]
+struct Position {
+    /// Height of the last block replayed from the database
+    replayed: Option<u64>,
+    /// Height of the last block reported as finalized
+    finalized: Option<u64>,
+}
+
+impl Position {
+    /// Returns whether the live record is to be written, updating the
+    /// position accordingly.
+    fn admit(&mut self, record: &Record) -> bool {
+        let height = record.height;
+        match record.kind {
+            BLOCK_ACCEPTED | TX_EXECUTED => {
+                // Already replayed from the database
+                !self.replayed.is_some_and(|replayed| height <= replayed)
+            }
+            BLOCK_FINALIZED => {
+                let admitted = self.finalized.map_or(true, |f| height > f);
+                if admitted {
+                    self.finalized = Some(height);
+                }
+                admitted
+            }
+            REORG => {
+                // The blocks of the new branch must not be mistaken for
+                // replayed ones
+                if let Some(replayed) = self.replayed {
+                    self.replayed =
+                        height.checked_sub(1).map(|h| h.min(replayed));
+                }
+                true
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns the height a feed interrupted at this position resumes from.
+    ///
+    /// Everything after the last finalized block might have changed while
+    /// the feed was not following the chain.
+    fn resume_height(&self, from: u64) -> u64 {
+        self.finalized.map_or(from, |f| from.max(f + 1))
+    }
+}
+
+struct ChainFeed {
+    node: RuskNode,
+    sender: async_mpsc::Sender<Vec<u8>>,
+    position: Position,
+    records: Records,
+}
+
+impl ChainFeed {
+    fn new(node: RuskNode, sender: async_mpsc::Sender<Vec<u8>>) -> Self {
+        Self {
+            node,
+            sender,
+            position: Position::default(),
+            records: Records::default(),
+        }
+    }
+
+    async fn run(
+        &mut self,
+        from: u64,
+        mut live: broadcast::Receiver<Arc<Record>>,
+    ) -> anyhow::Result<()> {
+        if let Some(next) = self.replay(from, MAX_REPLAY_BLOCKS).await? {
+            let record = Record::new(INTERRUPTED, next, "", Value::Null);
+            return self.emit(&record).await;
+        }
+
+        loop {
+            match live.recv().await {
+                Ok(record) if record.kind == INTERRUPTED => {
+                    self.interrupt(from).await?;
+                    anyhow::bail!("chain feed interrupted");
+                }
+                Ok(record) => {
+                    if self.position.admit(&record) {
+                        self.emit(&record).await?;
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    self.interrupt(from).await?;
+                    anyhow::bail!("lagged behind by {missed} records");
+                }
+                Err(RecvError::Closed) => return Ok(()),
+            }
+        }
+    }
+
+    /// Writes the records of the blocks stored from `from` to the tip, or the
+    /// `interrupted` record to resume from if there are too many.
+    #[cfg(feature = "archive")]
+    async fn rebuild(&mut self, from: u64) -> anyhow::Result<()> {
+        if let Some(next) = self.replay(from, MAX_REBUILD_BLOCKS).await? {
+            let record = Record::new(INTERRUPTED, next, "", Value::Null);
+            self.emit(&record).await?;
+        }
+        Ok(())
+    }
+
+    /// Writes the `interrupted` record of a feed that could not keep up with
+    /// the chain.
+    async fn interrupt(&mut self, from: u64) -> anyhow::Result<()> {
+        let height = self.position.resume_height(from);
+        self.emit(&Record::new(INTERRUPTED, height, "", Value::Null))
+            .await
+    }
+
+    /// Writes the records of at most `max_blocks` blocks stored from `from`
+    /// on, returning the height of the next block if the tip was not reached.
+    async fn replay(
+        &mut self,
+        from: u64,
+        max_blocks: u64,
+    ) -> anyhow::Result<Option<u64>> {
+        let to = from.saturating_add(max_blocks);
+        for height in from..to {
+            let stored =
+                self.node.db().read().await.view(|t| -> anyhow::Result<_> {
+                    let Some(block) = t.block_by_height(height)? else {
+                        return Ok(None);
+                    };
+                    let mut spent_txs = Vec::with_capacity(block.txs().len());
+                    for tx in block.txs() {
+                        if let Some(spent_tx) = t.ledger_tx(&tx.id())? {
+                            spent_txs.push(spent_tx);
+                        }
+                    }
+                    let label = t.block_label_by_height(height)?;
+                    Ok(Some((block, spent_txs, label)))
+                })?;
+
+            let Some((block, spent_txs, label)) = stored else {
+                return Ok(None);
+            };
+
+            self.replay_block(&block, &spent_txs).await?;
+            if let Some((_, Label::Final(_))) = label {
+                let hash = hex::encode(block.header().hash);
+                let record =
+                    Record::new(BLOCK_FINALIZED, height, &hash, Value::Null);
+                self.emit(&record).await?;
+                self.position.finalized = Some(height);
+            }
+            self.position.replayed = Some(height);
+        }
+
+        // Whether the tip was reached is only known by looking for the next
+        // block
+        let next = self.node.db().read().await.view(|t| {
+            t.block_hash_by_height(to).map(|hash| hash.map(|_| to))
+        })?;
+        Ok(next)
+    }
+
+    async fn replay_block(
+        &mut self,
+        block: &Block,
+        spent_txs: &[SpentTransaction],
+    ) -> anyhow::Result<()> {
+        self.records.events =
+            self.archived_events(block.header().height).await?;
+        for spent_tx in spent_txs {
+            let event = TransactionEvent::Executed(spent_tx);
+            if let Some(data) = event.data() {
+                self.records.executed.insert(event.entity(), data);
+            }
+        }
+
+        let event = BlockEvent::Accepted(block);
+        let records = self
+            .records
+            .accept(&event.entity(), event.data().unwrap_or_default());
+        for record in records {
+            self.emit(&record).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the contract events of the block at `height`, by origin.
+    ///
+    /// The events are not stored in the ledger, so they are only available
+    /// for the blocks replayed by nodes running an archive.
+    #[cfg(feature = "archive")]
+    async fn archived_events(
+        &self,
+        height: u64,
+    ) -> anyhow::Result<HashMap<String, Vec<Value>>> {
+        let archived = self
+            .node
+            .archive()
+            .fetch_json_events_by_height(height as i64)
+            .await?;
+        let archived: Vec<Value> = serde_json::from_str(&archived)?;
+
+        let mut events: HashMap<_, Vec<_>> = HashMap::new();
+        for event in archived {
+            let origin = event["origin"].as_str().unwrap_or_default();
+            events.entry(origin.to_string()).or_default().push(json!({
+                "target": event["source"],
+                "topic": event["topic"],
+                "data": event["data"],
+            }));
+        }
+        Ok(events)
+    }
+
+    #[cfg(not(feature = "archive"))]
+    async fn archived_events(
+        &self,
+        _height: u64,
+    ) -> anyhow::Result<HashMap<String, Vec<Value>>> {
+        Ok(HashMap::new())
+    }
+
+    /// Writes a record, waiting for the client to read the ones written
+    /// before if it is behind.
+    async fn emit(&self, record: &Record) -> anyhow::Result<()> {
+        self.sender
+            .send(record.to_line()?)
+            .await
+            .map_err(|_| anyhow::anyhow!("client disconnected"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(
+        component: &str,
+        entity: &str,
+        topic: &str,
+        data: Value,
+    ) -> RuesEvent {
+        RuesEvent {
+            uri: RuesEventUri {
+                component: component.into(),
+                entity: Some(entity.into()),
+                topic: topic.into(),
+            },
+            headers: serde_json::Map::new(),
+            data: DataType::Json(data),
+        }
+    }
+
+    fn accepted(height: u64) -> RuesEvent {
+        let data = json!({
+            "header": { "height": height },
+            "transactions": ["aa"],
+        });
+        event("blocks", &format!("{height:02x}"), "accepted", data)
+    }
+
+    #[tokio::test]
+    async fn live_records_are_numbered_node_wide() {
+        let (events, receiver) = broadcast::channel(16);
+        let hub = FeedHub::start(receiver);
+        let mut first = hub.subscribe();
+        let mut second = hub.subscribe();
+
+        events
+            .send(event("transactions", "aa", "executed", json!({})))
+            .unwrap();
+        events.send(accepted(1)).unwrap();
+
+        let mut records = vec![];
+        for _ in 0..2 {
+            let record = first.recv().await.unwrap();
+            assert_eq!(record, second.recv().await.unwrap());
+            records.push(record);
+        }
+        assert_eq!(records[0].kind, BLOCK_ACCEPTED);
+        assert_eq!(records[0].data["transactions"], json!(["aa"]));
+        assert_eq!(records[1].kind, TX_EXECUTED);
+        assert_eq!(records[1].hash, "aa");
+        assert_eq!(records[0].seq, Some(0));
+        assert_eq!(records[1].seq, Some(1));
+
+        // A feed subscribing later sees the same numbers
+        let mut third = hub.subscribe();
+        events.send(accepted(2)).unwrap();
+        let record = first.recv().await.unwrap();
+        assert_eq!(record.seq, Some(2));
+        assert_eq!(third.recv().await.unwrap().seq, Some(2));
+    }
+
+    #[test]
+    fn replayed_records_are_skipped() {
+        let mut position = Position {
+            replayed: Some(10),
+            finalized: Some(8),
+        };
+        let record = |kind, height| Record::new(kind, height, "", Value::Null);
+
+        assert!(!position.admit(&record(BLOCK_ACCEPTED, 10)));
+        assert!(!position.admit(&record(TX_EXECUTED, 10)));
+        assert!(position.admit(&record(BLOCK_ACCEPTED, 11)));
+
+        assert!(!position.admit(&record(BLOCK_FINALIZED, 8)));
+        assert!(position.admit(&record(BLOCK_FINALIZED, 9)));
+        assert_eq!(position.resume_height(0), 10);
+
+        // The new branch is written again
+        assert!(position.admit(&record(REORG, 10)));
+        assert!(position.admit(&record(BLOCK_ACCEPTED, 10)));
+    }
+}
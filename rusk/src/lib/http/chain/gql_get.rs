@@ -0,0 +1,112 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! GraphQL queries sent as `GET` requests, for browsers and CDNs to cache.
+//!
+//! The response to a query reading the chain only changes with the tip, so
+//! it is tagged with the hash of the tip. A client presenting that tag in
+//! `If-None-Match` is answered `304 Not Modified`, without executing the
+//! query again.
+
+use async_graphql::parser::parse_query;
+use async_graphql::parser::types::{Selection, SelectionSet};
+use hyper::header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH};
+use node::database::rocksdb::MD_HASH_KEY;
+use node::database::Metadata;
+
+use super::*;
+
+/// Seconds a cached response is used for, before being revalidated
+const MAX_AGE: u64 = 5;
+
+/// Root fields whose value does not follow the chain tip
+const UNCACHEABLE_FIELDS: &[&str] = &["mempoolTxs", "mempoolTx"];
+
+/// Returns whether the response to a GraphQL query only changes with the
+/// chain tip.
+fn is_cacheable(query: &str) -> bool {
+    // An invalid query is rejected the same on every tip
+    let Ok(doc) = parse_query(query) else {
+        return true;
+    };
+    let operations =
+        doc.operations.iter().map(|(_, op)| &op.node.selection_set);
+    let fragments = doc.fragments.values().map(|f| &f.node.selection_set);
+    !operations
+        .chain(fragments)
+        .any(|set| selects_uncacheable(&set.node))
+}
+
+fn selects_uncacheable(set: &SelectionSet) -> bool {
+    set.items.iter().any(|item| match &item.node {
+        Selection::Field(field) => {
+            UNCACHEABLE_FIELDS.contains(&field.node.name.node.as_str())
+        }
+        Selection::InlineFragment(fragment) => {
+            selects_uncacheable(&fragment.node.selection_set.node)
+        }
+        Selection::FragmentSpread(_) => false,
+    })
+}
+
+impl RuskNode {
+    pub(super) async fn handle_gql_get(
+        &self,
+        request: &RuesDispatchEvent,
+    ) -> anyhow::Result<ResponseData> {
+        if !is_cacheable(&request.data.as_string()) {
+            let rsp = self.handle_gql(&request.data, &request.headers).await?;
+            return Ok(rsp.with_header(CACHE_CONTROL.as_str(), "no-store"));
+        }
+
+        let tip = self.db().read().await.view(|t| -> anyhow::Result<_> {
+            t.op_read(MD_HASH_KEY)?
+                .ok_or_else(|| anyhow::anyhow!("Cannot find the tip"))
+        })?;
+        let etag = hex::encode(tip);
+
+        // Headers are parsed as JSON where possible, which unquotes a single
+        // entity tag
+        let if_none_match = request.header(IF_NONE_MATCH.as_str()).map(|v| {
+            v.as_str()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| v.to_string())
+        });
+        let rsp = match if_none_match {
+            Some(tags) if etag_matches(&tags, &etag) => {
+                ResponseData::new(DataType::None)
+            }
+            _ => self.handle_gql(&request.data, &request.headers).await?,
+        };
+
+        Ok(rsp
+            .with_header(ETAG.as_str(), format!("\"{etag}\""))
+            .with_header(
+                CACHE_CONTROL.as_str(),
+                format!("public, max-age={MAX_AGE}"),
+            ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mempool_queries_are_not_cached() {
+        assert!(is_cacheable(
+            "query { block(height: 1) { header { hash } } }"
+        ));
+        assert!(is_cacheable("not a query"));
+        assert!(!is_cacheable("query { mempoolTxs { id } }"));
+        assert!(!is_cacheable(
+            "{ ... on Query { mempoolTx(hash: \"00\") { id } } }"
+        ));
+        assert!(!is_cacheable(
+            "query { ...Pending } fragment Pending on Query { mempoolTxs { id } }"
+        ));
+    }
+}
@@ -33,6 +33,9 @@ use {
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use super::epoch::{epoch_info, parse_provisioner, EpochInfo};
+use crate::node::{ContractStats, Rusk};
+
 #[cfg(feature = "archive")]
 pub type DBContext = (Arc<RwLock<Backend>>, Archive);
 #[cfg(not(feature = "archive"))]
@@ -238,6 +241,93 @@ impl Query {
         }
     }
 
+    /// Get the gas spent by and the calls made to each contract, over the
+    /// last `days` days with activity (1 by default).
+    async fn contract_stats(
+        &self,
+        ctx: &Context<'_>,
+        days: Option<u64>,
+    ) -> FieldResult<Vec<ContractDailyUsage>> {
+        let stats = ctx.data::<Arc<ContractStats>>()?;
+        let days = days.unwrap_or(1) as usize;
+
+        Ok(stats
+            .daily(days)
+            .into_iter()
+            .map(|(day, contract_id, usage)| ContractDailyUsage {
+                day,
+                contract_id,
+                gas_spent: usage.gas_spent,
+                calls: usage.calls,
+            })
+            .collect())
+    }
+
+    /// Get the stakes changed by the block at `height`, each with its value
+    /// before and after the block.
+    ///
+    /// The changes are read from the state resulting from the block, so they
+    /// are only available while that state is kept by the node.
+    async fn provisioner_changes(
+        &self,
+        ctx: &Context<'_>,
+        height: u64,
+    ) -> FieldResult<Vec<ProvisionerChange>> {
+        provisioner_changes(ctx, height).await
+    }
+
+    /// Get the parameters of the stake contract, for stake amounts to be
+    /// validated against the network rather than against the defaults.
+    async fn stake_config(
+        &self,
+        ctx: &Context<'_>,
+    ) -> FieldResult<StakeConfig> {
+        let vm = ctx.data::<Arc<RwLock<Rusk>>>()?;
+        let config = vm.read().await.stake_config()?;
+        Ok(config.into())
+    }
+
+    /// Get the epoch calendar of the stake contract at the chain tip, with
+    /// the stake eligibility of the provisioner with the given base58 BLS
+    /// key, if any.
+    async fn epoch_info(
+        &self,
+        ctx: &Context<'_>,
+        provisioner: Option<String>,
+    ) -> FieldResult<EpochInfo> {
+        let provisioner = parse_provisioner(provisioner.unwrap_or_default())?;
+        let (db, _) = ctx.data::<DBContext>()?;
+        let vm = ctx.data::<Arc<RwLock<Rusk>>>()?;
+        Ok(epoch_info(db, vm, provisioner).await?)
+    }
+
+    /// Get the distribution of the transactions, gas spent and block times
+    /// of the blocks in the range of heights, both included.
+    ///
+    /// At most 1000 blocks can be aggregated by a single query.
+    async fn block_stats(
+        &self,
+        ctx: &Context<'_>,
+        from_height: u64,
+        to_height: u64,
+    ) -> FieldResult<BlockStats> {
+        block_stats(ctx, from_height, to_height).await
+    }
+
+    /// Get how often the provisioners were extracted as generator or
+    /// committee member, against how often their blocks and votes were
+    /// observed in the accepted blocks.
+    ///
+    /// Returns the provisioner with the given base58 BLS key, or all of them
+    /// if not given.
+    async fn provisioners_uptime(
+        &self,
+        ctx: &Context<'_>,
+        key: Option<String>,
+    ) -> FieldResult<Vec<ProvisionerUptime>> {
+        provisioners_uptime(ctx, key).await
+    }
+
     /// Get all finalized contract events from a specific contract id.
     #[cfg(feature = "archive")]
     async fn finalized_events(
@@ -248,6 +338,22 @@ impl Query {
         finalized_events_by_contractid(ctx, contract_id).await
     }
 
+    /// Get the finalized events of a contract emitted by the blocks in the
+    /// range of heights, both included, each with the height of its block.
+    ///
+    /// The range can span at most 100000 heights.
+    #[cfg(feature = "archive")]
+    async fn finalized_events_in_range(
+        &self,
+        ctx: &Context<'_>,
+        contract_id: String,
+        from_height: u64,
+        to_height: u64,
+    ) -> OptResult<ContractEvents> {
+        finalized_events_in_range(ctx, contract_id, from_height, to_height)
+            .await
+    }
+
     /// Check if a given block height matches a given block hash.
     ///
     /// If `only_finalized` is set to `true`, only finalized blocks will be
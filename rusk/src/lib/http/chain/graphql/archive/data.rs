@@ -50,6 +50,12 @@ impl ContractEvents {
 pub mod deserialized_archive_data {
     use super::*;
     use dusk_core::stake::STAKE_CONTRACT;
+    use dusk_core::token::{
+        Account as TokenAccount, ApproveEvent as TokenApproveEvent,
+        SupplyEvent as TokenSupplyEvent, TransferEvent as TokenTransferEvent,
+        APPROVE_TOPIC as TOKEN_APPROVE_TOPIC, BURN_TOPIC as TOKEN_BURN_TOPIC,
+        MINT_TOPIC as TOKEN_MINT_TOPIC, TRANSFER_TOPIC as TOKEN_TRANSFER_TOPIC,
+    };
     use dusk_core::transfer::withdraw::WithdrawReceiver;
     use dusk_core::transfer::{
         ConvertEvent, DepositEvent, MoonlightTransactionEvent, WithdrawEvent,
@@ -207,6 +213,104 @@ pub mod deserialized_archive_data {
         }
     }
 
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct DeserializedTokenAccount(pub TokenAccount);
+
+    impl Serialize for DeserializedTokenAccount {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            match &self.0 {
+                TokenAccount::External(pk) => serializer
+                    .serialize_newtype_variant(
+                        "Account",
+                        0,
+                        "External",
+                        &bs58::encode(pk.to_bytes()).into_string(),
+                    ),
+                TokenAccount::Contract(contract) => serializer
+                    .serialize_newtype_variant(
+                        "Account",
+                        1,
+                        "Contract",
+                        &WrappedContractId(*contract),
+                    ),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct DeserializedTokenTransferEvent(pub TokenTransferEvent);
+
+    impl Serialize for DeserializedTokenTransferEvent {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            let transfer_event = &self.0;
+            let mut state =
+                serializer.serialize_struct("TokenTransferEvent", 3)?;
+            state.serialize_field(
+                "sender",
+                &DeserializedTokenAccount(transfer_event.sender),
+            )?;
+            state.serialize_field(
+                "receiver",
+                &DeserializedTokenAccount(transfer_event.receiver),
+            )?;
+            state.serialize_field("value", &transfer_event.value)?;
+
+            state.end()
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct DeserializedTokenApproveEvent(pub TokenApproveEvent);
+
+    impl Serialize for DeserializedTokenApproveEvent {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            let approve_event = &self.0;
+            let mut state =
+                serializer.serialize_struct("TokenApproveEvent", 3)?;
+            state.serialize_field(
+                "owner",
+                &DeserializedTokenAccount(approve_event.owner),
+            )?;
+            state.serialize_field(
+                "spender",
+                &DeserializedTokenAccount(approve_event.spender),
+            )?;
+            state.serialize_field("value", &approve_event.value)?;
+
+            state.end()
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct DeserializedTokenSupplyEvent(pub TokenSupplyEvent);
+
+    impl Serialize for DeserializedTokenSupplyEvent {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            let supply_event = &self.0;
+            let mut state =
+                serializer.serialize_struct("TokenSupplyEvent", 2)?;
+            state.serialize_field(
+                "account",
+                &DeserializedTokenAccount(supply_event.account),
+            )?;
+            state.serialize_field("value", &supply_event.value)?;
+
+            state.end()
+        }
+    }
+
     #[derive(Debug, Clone, PartialEq, Serialize)]
     pub struct DeserializedContractEvent {
         pub target: WrappedContractId,
@@ -259,7 +363,42 @@ pub mod deserialized_archive_data {
                     _ => serde_json::to_value(hex::encode(event.data)),
                 }
             } else {
-                serde_json::to_value(hex::encode(event.data))
+                // Token events are recognized by their topic, since any
+                // contract can be an instance of the reference token
+                match event.topic.as_str() {
+                    TOKEN_TRANSFER_TOPIC => rkyv::from_bytes::<
+                        TokenTransferEvent,
+                    >(&event.data)
+                    .map(|e| {
+                        serde_json::to_value(DeserializedTokenTransferEvent(e))
+                    })
+                    .unwrap_or_else(|_| {
+                        serde_json::to_value(hex::encode(&event.data))
+                    }),
+                    TOKEN_APPROVE_TOPIC => {
+                        rkyv::from_bytes::<TokenApproveEvent>(&event.data)
+                            .map(|e| {
+                                serde_json::to_value(
+                                    DeserializedTokenApproveEvent(e),
+                                )
+                            })
+                            .unwrap_or_else(|_| {
+                                serde_json::to_value(hex::encode(&event.data))
+                            })
+                    }
+                    TOKEN_MINT_TOPIC | TOKEN_BURN_TOPIC => {
+                        rkyv::from_bytes::<TokenSupplyEvent>(&event.data)
+                            .map(|e| {
+                                serde_json::to_value(
+                                    DeserializedTokenSupplyEvent(e),
+                                )
+                            })
+                            .unwrap_or_else(|_| {
+                                serde_json::to_value(hex::encode(&event.data))
+                            })
+                    }
+                    _ => serde_json::to_value(hex::encode(event.data)),
+                }
             }
             .unwrap_or_else(|e| serde_json::Value::String(e.to_string()));
 
@@ -13,6 +13,9 @@ use node_data::events::contract::WrappedContractId;
 use super::data::ContractEvents;
 use crate::http::chain::graphql::{DBContext, OptResult};
 
+/// Maximum number of heights the events are fetched of in a single query.
+const MAX_EVENTS_RANGE: u64 = 100_000;
+
 pub async fn events_by_height(
     ctx: &Context<'_>,
     height: i64,
@@ -68,3 +71,36 @@ pub async fn finalized_events_by_contractid(
 
     Ok(Some(ContractEvents(serde_json::to_value(events)?)))
 }
+
+pub async fn finalized_events_in_range(
+    ctx: &Context<'_>,
+    hex_contract_id: String,
+    from_height: u64,
+    to_height: u64,
+) -> OptResult<ContractEvents> {
+    let (_, archive) = ctx.data::<DBContext>()?;
+
+    // shallow check if contract id is valid
+    if hex_contract_id.len() != CONTRACT_ID_BYTES * 2 {
+        return Err(FieldError::new("Invalid contract_id"));
+    }
+    if from_height > to_height {
+        return Err(FieldError::new("fromHeight must not exceed toHeight"));
+    }
+    if to_height - from_height >= MAX_EVENTS_RANGE {
+        return Err(FieldError::new(format!(
+            "Cannot fetch events of more than {MAX_EVENTS_RANGE} heights"
+        )));
+    }
+
+    let events = archive
+        .fetch_finalized_events_from_contract_in_range(
+            &hex_contract_id,
+            from_height.min(i64::MAX as u64) as i64,
+            to_height.min(i64::MAX as u64) as i64,
+        )
+        .await
+        .map_err(|e| FieldError::new(format!("Cannot fetch events: {}", e)))?;
+
+    Ok(Some(ContractEvents(serde_json::to_value(events)?)))
+}
@@ -5,8 +5,9 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 use super::*;
+use dusk_bytes::Serializable;
 use node::database::rocksdb::MD_HASH_KEY;
-use node::database::{into_array, Metadata};
+use node::database::{into_array, Metadata, UptimeStorage};
 
 pub async fn block_by_height(
     ctx: &Context<'_>,
@@ -28,6 +29,142 @@ pub async fn block_by_height(
     Ok(None)
 }
 
+pub async fn provisioner_changes(
+    ctx: &Context<'_>,
+    height: u64,
+) -> FieldResult<Vec<ProvisionerChange>> {
+    let (db, _) = ctx.data::<DBContext>()?;
+    let header = db.read().await.view(|t| -> anyhow::Result<_> {
+        match t.block_hash_by_height(height)? {
+            Some(hash) => t.block_header(&hash),
+            None => Ok(None),
+        }
+    })?;
+    let header = header
+        .ok_or_else(|| FieldError::new(format!("Block {height} not found")))?;
+
+    let vm = ctx.data::<Arc<RwLock<Rusk>>>()?;
+    let changes = vm.read().await.provisioners_diff(header.state_hash)?;
+
+    Ok(changes
+        .into_iter()
+        .map(|(pk, before, after)| ProvisionerChange {
+            key: bs58::encode(pk.to_bytes()).into_string(),
+            before: before.map(StakeValue::from),
+            after: after.map(StakeValue::from),
+        })
+        .collect())
+}
+
+pub async fn provisioners_uptime(
+    ctx: &Context<'_>,
+    key: Option<String>,
+) -> FieldResult<Vec<ProvisionerUptime>> {
+    let (db, _) = ctx.data::<DBContext>()?;
+
+    let uptimes = match key {
+        Some(key) => {
+            let key = bs58::decode(&key).into_vec()?;
+            let uptime = db.read().await.view(|t| t.uptime(&key))?;
+            uptime.map(|uptime| vec![(key, uptime)]).unwrap_or_default()
+        }
+        None => db.read().await.view(|t| t.uptimes())?,
+    };
+
+    Ok(uptimes
+        .into_iter()
+        .map(|(key, uptime)| ProvisionerUptime::new(&key, uptime))
+        .collect())
+}
+
+/// Maximum number of blocks aggregated by a single query
+const MAX_STATS_BLOCKS: u64 = 1_000;
+
+pub async fn block_stats(
+    ctx: &Context<'_>,
+    from: u64,
+    to: u64,
+) -> FieldResult<BlockStats> {
+    if from > to {
+        return Err(FieldError::new("fromHeight must not exceed toHeight"));
+    }
+    if to - from >= MAX_STATS_BLOCKS {
+        return Err(FieldError::new(format!(
+            "At most {MAX_STATS_BLOCKS} blocks can be aggregated"
+        )));
+    }
+
+    // The blocks are read on a blocking thread, not to stall the runtime
+    let (db, _) = ctx.data::<DBContext>()?;
+    let db = db.clone().read_owned().await;
+    let stats = tokio::task::spawn_blocking(move || {
+        db.view(|t| -> anyhow::Result<_> {
+            let mut txs = vec![];
+            let mut gas_spent = vec![];
+            let mut block_time = vec![];
+            let mut gas_limit = 0u64;
+
+            // The block preceding the range gives the time of the first one
+            let mut prev_timestamp = match from.checked_sub(1) {
+                Some(height) => match t.block_hash_by_height(height)? {
+                    Some(hash) => t.block_header(&hash)?.map(|h| h.timestamp),
+                    None => None,
+                },
+                None => None,
+            };
+
+            for height in from..=to {
+                let Some(hash) = t.block_hash_by_height(height)? else {
+                    break;
+                };
+                let block = t
+                    .light_block(&hash)?
+                    .ok_or_else(|| anyhow::anyhow!("Block to be found"))?;
+
+                let mut spent = 0;
+                for id in &block.transactions_ids {
+                    if let Some(tx) = t.ledger_tx(id)? {
+                        spent += tx.gas_spent;
+                    }
+                }
+
+                let header = block.header;
+                if let Some(prev) = prev_timestamp {
+                    block_time.push(header.timestamp.saturating_sub(prev));
+                }
+                prev_timestamp = Some(header.timestamp);
+
+                txs.push(block.transactions_ids.len() as u64);
+                gas_spent.push(spent);
+                gas_limit += header.gas_limit;
+            }
+
+            Ok((txs, gas_spent, block_time, gas_limit))
+        })
+    })
+    .await?;
+    let (txs, gas_spent, block_time, gas_limit) = stats?;
+
+    let blocks = txs.len() as u64;
+    let total_gas: u64 = gas_spent.iter().sum();
+    let fullness = match gas_limit {
+        0 => 0.0,
+        limit => total_gas as f64 / limit as f64,
+    };
+
+    Ok(BlockStats {
+        from_height: from,
+        to_height: from + blocks.saturating_sub(1),
+        blocks,
+        empty_blocks: txs.iter().filter(|&&count| count == 0).count() as u64,
+        txs: txs.iter().sum(),
+        txs_per_block: Distribution::new(txs),
+        gas_spent: Distribution::new(gas_spent),
+        fullness,
+        block_time: Distribution::new(block_time),
+    })
+}
+
 pub async fn last_block(ctx: &Context<'_>) -> FieldResult<Block> {
     let (db, _) = ctx.data::<DBContext>()?;
     let block = db.read().await.view(|t| {
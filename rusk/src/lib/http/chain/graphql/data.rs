@@ -7,6 +7,7 @@
 use std::ops::Deref;
 
 use async_graphql::{FieldError, FieldResult, Object, SimpleObject};
+use dusk_core::checked_fee;
 use node::database::{Ledger, LightBlock, DB};
 use serde::{Deserialize, Serialize};
 
@@ -116,8 +117,11 @@ impl Block {
             .transactions(ctx)
             .await?
             .iter()
-            .map(|t| t.0.gas_spent * t.0.inner.gas_price())
-            .sum();
+            .try_fold(0u64, |fees, t| {
+                checked_fee(t.0.gas_spent, t.0.inner.gas_price())
+                    .and_then(|fee| fees.checked_add(fee))
+            })
+            .ok_or_else(|| FieldError::new("Block fees overflow"))?;
         Ok(fees)
     }
 
@@ -201,6 +205,20 @@ impl SpentTransaction {
         self.0.gas_spent
     }
 
+    /// Calls between contracts made while executing the transaction, empty
+    /// for the transactions accepted before they were recorded.
+    pub async fn call_graph(&self) -> Vec<CallEdge> {
+        self.0
+            .call_graph
+            .iter()
+            .map(|edge| CallEdge {
+                caller: hex::encode(edge.caller),
+                callee: hex::encode(edge.callee),
+                gas_spent: edge.gas_spent,
+            })
+            .collect()
+    }
+
     pub async fn block_hash(
         &self,
         ctx: &async_graphql::Context<'_>,
@@ -303,9 +321,163 @@ impl Transaction<'_> {
     }
 }
 
+/// Usage of a contract over a day.
+#[derive(SimpleObject)]
+pub struct ContractDailyUsage {
+    /// Day since the UNIX epoch
+    pub day: u64,
+    /// Id of the contract, or `other` for the usage of the contracts called
+    /// after the first 1000 of the day
+    pub contract_id: String,
+    pub gas_spent: u64,
+    pub calls: u64,
+}
+
+/// A stake changed by a block, before and after the block.
+#[derive(SimpleObject)]
+pub struct ProvisionerChange {
+    pub key: String,
+    pub before: Option<StakeValue>,
+    pub after: Option<StakeValue>,
+}
+
+/// Participation of a provisioner in consensus, as observed from the
+/// accepted blocks.
+#[derive(SimpleObject)]
+pub struct ProvisionerUptime {
+    pub key: String,
+    pub generator_selected: u64,
+    pub blocks_produced: u64,
+    pub committee_selected: u64,
+    pub votes_observed: u64,
+    pub last_height: u64,
+}
+
+impl ProvisionerUptime {
+    pub fn new(key: &[u8], uptime: node::database::ProvisionerUptime) -> Self {
+        Self {
+            key: bs58::encode(key).into_string(),
+            generator_selected: uptime.generator_selected,
+            blocks_produced: uptime.blocks_produced,
+            committee_selected: uptime.committee_selected,
+            votes_observed: uptime.votes_observed,
+            last_height: uptime.last_height,
+        }
+    }
+}
+
+/// Aggregates of the blocks in a range of heights.
+#[derive(SimpleObject)]
+pub struct BlockStats {
+    pub from_height: u64,
+    pub to_height: u64,
+    pub blocks: u64,
+    pub empty_blocks: u64,
+    pub txs: u64,
+    pub txs_per_block: Distribution,
+    pub gas_spent: Distribution,
+    /// Ratio of the gas spent to the gas limit of the blocks
+    pub fullness: f64,
+    /// Seconds elapsed since the previous block
+    pub block_time: Distribution,
+}
+
+/// Distribution of a value over the blocks.
+#[derive(SimpleObject, Default)]
+pub struct Distribution {
+    pub avg: f64,
+    pub min: u64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+impl Distribution {
+    pub fn new(mut values: Vec<u64>) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        values.sort_unstable();
+
+        // Nearest-rank percentile
+        let percentile = |p: usize| {
+            let rank = (p * values.len()).div_ceil(100);
+            values[rank.saturating_sub(1)]
+        };
+        let sum: u64 = values.iter().sum();
+
+        Self {
+            avg: sum as f64 / values.len() as f64,
+            min: values[0],
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: values[values.len() - 1],
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct StakeValue {
+    pub amount: Option<u64>,
+    pub locked: Option<u64>,
+    pub eligibility: Option<u64>,
+    pub reward: u64,
+    pub faults: u8,
+    pub hard_faults: u8,
+}
+
+impl From<dusk_core::stake::StakeData> for StakeValue {
+    fn from(stake: dusk_core::stake::StakeData) -> Self {
+        Self {
+            amount: stake.amount.map(|a| a.value),
+            locked: stake.amount.map(|a| a.locked),
+            eligibility: stake.amount.map(|a| a.eligibility),
+            reward: stake.reward,
+            faults: stake.faults,
+            hard_faults: stake.hard_faults,
+        }
+    }
+}
+
+/// Parameters of the stake contract.
+#[derive(SimpleObject)]
+pub struct StakeConfig {
+    /// Minimum amount, in LUX, of a new stake
+    pub minimum_stake: u64,
+    /// Number of blocks in an epoch
+    pub epoch: u64,
+    /// Number of blocks a stake takes to mature, once the epoch it is created
+    /// in ends
+    pub maturity: u64,
+    /// Number of faults before a provisioner is penalized
+    pub warnings: u8,
+}
+
+impl From<dusk_core::stake::StakeConfig> for StakeConfig {
+    fn from(config: dusk_core::stake::StakeConfig) -> Self {
+        Self {
+            minimum_stake: config.minimum_stake,
+            epoch: config.epoch,
+            maturity: config.maturity,
+            warnings: config.warnings,
+        }
+    }
+}
+
 #[derive(SimpleObject)]
 pub struct CallData {
     contract_id: String,
     fn_name: String,
     data: String,
 }
+
+/// A call from a contract to another one.
+#[derive(SimpleObject)]
+pub struct CallEdge {
+    pub caller: String,
+    pub callee: String,
+    /// Gas spent by the callee, including the one of its own calls
+    pub gas_spent: u64,
+}
@@ -0,0 +1,119 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Segments of the header chain for light clients, to be verified with
+//! [`node_data::ledger::verify_headers`] from a trusted checkpoint.
+
+use dusk_consensus::user::provisioners::Provisioners;
+use node::database::Ledger;
+use node::vm::VMExecution;
+use node_data::ledger::{Header, HeaderProof, TrustedCommittees};
+use node_data::StepName;
+use serde::Deserialize;
+use tracing::debug;
+
+use super::*;
+
+/// Maximum number of headers served at once
+const MAX_HEADERS: u64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct HeadersRequest {
+    from: u64,
+    #[serde(default)]
+    count: Option<u64>,
+}
+
+impl HeadersRequest {
+    pub(super) fn parse(data: &str) -> anyhow::Result<Self> {
+        let request = serde_json::from_str(data).map_err(|e| {
+            ApiError::invalid_request(format!("Invalid headers request: {e}"))
+        })?;
+        Ok(request)
+    }
+}
+
+impl RuskNode {
+    /// Returns the headers of the blocks from the requested height, each with
+    /// its attestation and the committees that voted it.
+    ///
+    /// The committees are drawn from the state the block was built upon, so
+    /// the segment stops at the first block whose parent state is no longer
+    /// available.
+    pub(super) async fn header_proofs(
+        &self,
+        request: HeadersRequest,
+    ) -> anyhow::Result<ResponseData> {
+        // The genesis block has no attestation
+        let from = request.from.max(1);
+        let count = request.count.unwrap_or(MAX_HEADERS).min(MAX_HEADERS);
+
+        // The committees of a block are drawn from the state of its parent
+        let headers =
+            self.db().read().await.view(|t| -> anyhow::Result<_> {
+                let mut headers = vec![];
+                for height in from - 1..from.saturating_add(count) {
+                    let Some(hash) = t.block_hash_by_height(height)? else {
+                        break;
+                    };
+                    let Some(header) = t.block_header(&hash)? else {
+                        break;
+                    };
+                    headers.push(header);
+                }
+                Ok(headers)
+            })?;
+
+        let vm = self.inner().vm_handler();
+
+        let mut proofs = vec![];
+        for pair in headers.windows(2) {
+            let [prev, header] = pair else { unreachable!() };
+            // The VM is locked for each block only, not to hold back the
+            // acceptance of blocks for the whole segment
+            let provisioners =
+                vm.read().await.get_provisioners(prev.state_hash);
+            let provisioners = match provisioners {
+                Ok(provisioners) => provisioners,
+                Err(e) if !proofs.is_empty() => {
+                    debug!("header proofs stop at {}: {e}", header.height);
+                    break;
+                }
+                Err(e) => anyhow::bail!(
+                    "Cannot get the committees of block {}: {e}",
+                    header.height
+                ),
+            };
+
+            proofs.push(header_proof(&provisioners, prev, header));
+        }
+
+        Ok(ResponseData::new(serde_json::to_value(proofs)?))
+    }
+}
+
+/// Returns the proof of `header`, whose committees are drawn from
+/// `provisioners` at the state of `prev`.
+pub(super) fn header_proof(
+    provisioners: &Provisioners,
+    prev: &Header,
+    header: &Header,
+) -> HeaderProof {
+    HeaderProof {
+        header: header.clone(),
+        attestation: header.att,
+        validation_committee: provisioners.committee(
+            prev,
+            header,
+            StepName::Validation,
+        ),
+        ratification_committee: provisioners.committee(
+            prev,
+            header,
+            StepName::Ratification,
+        ),
+    }
+}
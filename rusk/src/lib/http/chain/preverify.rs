@@ -0,0 +1,145 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Verdict on a transaction, as the mempool would give it, without the
+//! transaction being inserted nor propagated.
+
+use dusk_core::transfer::Transaction as ProtocolTransaction;
+use node::mempool::{MempoolSrv, TxAcceptanceError};
+use node_data::ledger::Transaction;
+use serde::Serialize;
+
+use super::*;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Verdict {
+    valid: bool,
+    /// Hex encoded ID of the transaction, if it could be decoded
+    id: Option<String>,
+    /// `phoenix` or `moonlight`
+    kind: Option<&'static str>,
+    gas_limit: Option<u64>,
+    gas_price: Option<u64>,
+    /// Stable identifier of the rejection reason
+    reason: Option<&'static str>,
+    message: Option<String>,
+}
+
+impl Verdict {
+    fn new(tx: &Transaction, result: Result<(), TxAcceptanceError>) -> Self {
+        let kind = match &tx.inner {
+            ProtocolTransaction::Phoenix(_) => "phoenix",
+            ProtocolTransaction::Moonlight(_) => "moonlight",
+        };
+        Self {
+            valid: result.is_ok(),
+            id: Some(hex::encode(tx.id())),
+            kind: Some(kind),
+            gas_limit: Some(tx.inner.gas_limit()),
+            gas_price: Some(tx.inner.gas_price()),
+            reason: result.as_ref().err().map(|e| e.code()),
+            message: result.err().map(|e| e.to_string()),
+        }
+    }
+
+    fn rejected(reason: &'static str, message: String) -> Self {
+        Self {
+            valid: false,
+            id: None,
+            kind: None,
+            gas_limit: None,
+            gas_price: None,
+            reason: Some(reason),
+            message: Some(message),
+        }
+    }
+}
+
+impl RuskNode {
+    /// Runs the policy, stateless and stateful checks of the mempool on a raw
+    /// transaction, returning the verdict.
+    ///
+    /// The checks are those of the mempool against the current tip, so a
+    /// valid verdict doesn't guarantee the transaction is included in a
+    /// block.
+    pub(super) async fn preverify_verdict(
+        &self,
+        data: &[u8],
+    ) -> anyhow::Result<ResponseData> {
+        let tx = match ProtocolTransaction::from_slice(data) {
+            Ok(tx) => Transaction::from(tx),
+            Err(e) => {
+                let verdict = Verdict::rejected("malformed", format!("{e:?}"));
+                return Ok(ResponseData::new(serde_json::to_value(verdict)?));
+            }
+        };
+
+        let db = self.inner().database();
+        let vm = self.inner().vm_handler();
+        let result = MempoolSrv::check_admission(
+            self.tx_policy(),
+            &db,
+            &vm,
+            &tx,
+            true,
+            usize::MAX,
+        )
+        .await
+        .map(|_| ());
+        let verdict = Verdict::new(&tx, result);
+
+        Ok(ResponseData::new(serde_json::to_value(verdict)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use dusk_core::signatures::bls::SecretKey as BlsSecretKey;
+    use node::mempool::policy::{Admission, TxPolicy};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    struct DenyAll;
+
+    #[async_trait]
+    impl TxPolicy for DenyAll {
+        async fn check(
+            &self,
+            _: &Transaction,
+            _: Admission,
+        ) -> Result<(), String> {
+            Err("denied".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn policy_denied_transaction_is_not_valid() {
+        let sk = BlsSecretKey::random(&mut StdRng::seed_from_u64(0xbeef));
+        let tx: Transaction = ProtocolTransaction::moonlight(
+            &sk,
+            None,
+            0,
+            0,
+            1_000,
+            1,
+            1,
+            0,
+            None::<Vec<u8>>,
+        )
+        .expect("transaction to be built")
+        .into();
+
+        let result = MempoolSrv::check_policy(&DenyAll, &tx).await;
+        let verdict = Verdict::new(&tx, result);
+        assert!(!verdict.valid);
+        assert_eq!(verdict.reason, Some("rejected_by_policy"));
+        assert_eq!(verdict.kind, Some("moonlight"));
+    }
+}
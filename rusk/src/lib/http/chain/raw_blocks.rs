@@ -0,0 +1,153 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Stream of the serialized blocks of a range of heights, for backups and
+//! for bootstrapping indexers.
+//!
+//! Every block is written as its length, a little-endian `u32`, followed by
+//! the block serialized as on the wire. The stream ends at the last block of
+//! the range or at the tip, whichever comes first.
+//!
+//! A range spans at most [`MAX_RAW_BLOCKS`] blocks, which is also the range
+//! streamed when no end is given, and at most [`MAX_RAW_STREAMS`] ranges are
+//! streamed at once by the node.
+//!
+//! The blocks are sent as binary when the request has the
+//! `application/octet-stream` content type, and hex encoded otherwise.
+
+use node::database::Ledger;
+use node_data::Serializable;
+use serde::Deserialize;
+use tokio::sync::mpsc as async_mpsc;
+use tracing::debug;
+
+use super::*;
+
+/// Number of blocks buffered ahead of the client
+const BUFFERED_BLOCKS: usize = 16;
+
+/// Maximum number of blocks of a range
+pub(crate) const MAX_RAW_BLOCKS: u64 = 10_000;
+
+/// Maximum number of ranges streamed at once
+pub(crate) const MAX_RAW_STREAMS: usize = 8;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct RawBlocksRequest {
+    from: u64,
+    #[serde(default)]
+    to: Option<u64>,
+}
+
+impl RawBlocksRequest {
+    pub(super) fn parse(data: &str) -> anyhow::Result<Self> {
+        let request: Self = serde_json::from_str(data).map_err(|e| {
+            ApiError::invalid_request(format!(
+                "Invalid raw blocks request: {e}"
+            ))
+        })?;
+        if request.to.is_some_and(|to| to < request.from) {
+            anyhow::bail!(ApiError::invalid_request(
+                "Invalid raw blocks request: empty range"
+            ));
+        }
+        if request
+            .to
+            .is_some_and(|to| to - request.from >= MAX_RAW_BLOCKS)
+        {
+            anyhow::bail!(ApiError::invalid_request(format!(
+                "Invalid raw blocks request: more than {MAX_RAW_BLOCKS} blocks"
+            )));
+        }
+        Ok(request)
+    }
+
+    /// Returns the last height of the range.
+    fn to(&self) -> u64 {
+        self.to
+            .unwrap_or_else(|| self.from.saturating_add(MAX_RAW_BLOCKS - 1))
+    }
+}
+
+impl RuskNode {
+    /// Streams the serialized blocks from `request.from` up to `request.to`,
+    /// both included.
+    pub(super) async fn raw_blocks(
+        &self,
+        request: RawBlocksRequest,
+    ) -> anyhow::Result<ResponseData> {
+        let permit =
+            self.raw_streams()
+                .clone()
+                .try_acquire_owned()
+                .map_err(|_| {
+                    ApiError::new(
+                        ErrorCode::Overloaded,
+                        "Too many raw blocks streams, retry later",
+                    )
+                })?;
+        let db = self.db();
+        let to = request.to();
+
+        // The channel is bounded so that slow clients do not make the whole
+        // range pile up in memory
+        let (sender, receiver) = async_mpsc::channel(BUFFERED_BLOCKS);
+
+        tokio::spawn(async move {
+            // Held until the stream ends
+            let _permit = permit;
+            for height in request.from..=to {
+                let block = db
+                    .read()
+                    .await
+                    .view(|t| t.block_by_height(height))
+                    .map_err(|e| debug!("raw blocks ended at {height}: {e}"));
+                let Ok(Some(block)) = block else {
+                    break;
+                };
+
+                let mut buf = vec![0u8; 4];
+                if let Err(e) = block.write(&mut buf) {
+                    debug!("raw blocks ended at {height}: {e}");
+                    break;
+                }
+                let len = (buf.len() - 4) as u32;
+                buf[..4].copy_from_slice(&len.to_le_bytes());
+
+                if sender.send(buf).await.is_err() {
+                    // The client disconnected
+                    break;
+                }
+            }
+        });
+
+        Ok(ResponseData::new(receiver))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_are_capped() {
+        let request = RawBlocksRequest::parse(r#"{"from":5}"#).unwrap();
+        assert_eq!(request.to(), 5 + MAX_RAW_BLOCKS - 1);
+
+        let request = RawBlocksRequest::parse(r#"{"from":5,"to":9}"#).unwrap();
+        assert_eq!(request.to(), 9);
+
+        let to = MAX_RAW_BLOCKS;
+        let data = format!(r#"{{"from":0,"to":{to}}}"#);
+        assert!(RawBlocksRequest::parse(&data).is_err());
+        assert!(RawBlocksRequest::parse(r#"{"from":9,"to":5}"#).is_err());
+
+        let request =
+            RawBlocksRequest::parse(&format!(r#"{{"from":{}}}"#, u64::MAX))
+                .unwrap();
+        assert_eq!(request.to(), u64::MAX);
+    }
+}
@@ -0,0 +1,124 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Receipts of the accepted transactions, whose inclusion is to be verified
+//! with [`node_data::ledger::ReceiptProof::verify`].
+
+use node::database::Ledger;
+use node::vm::VMExecution;
+use node_data::ledger::{merkle_path, ReceiptProof};
+use serde::Serialize;
+
+use super::headers::header_proof;
+use super::*;
+
+/// Parses the hex ID of the transaction to prove.
+pub(super) fn parse_tx_id(data: String) -> anyhow::Result<[u8; 32]> {
+    let mut tx_id = [0u8; 32];
+    hex::decode_to_slice(data.trim(), &mut tx_id).map_err(|e| {
+        ApiError::invalid_request(format!("Invalid transaction id: {e}"))
+    })?;
+    Ok(tx_id)
+}
+
+/// A transaction included in a block, with its execution result.
+#[derive(Serialize)]
+struct Receipt {
+    proof: ReceiptProof,
+    /// The result of the execution, as recorded by the node: the header the
+    /// transaction is proven to be included in does not commit to it
+    result: ExecutionResult,
+}
+
+#[derive(Serialize)]
+struct ExecutionResult {
+    gas_spent: u64,
+    error: Option<String>,
+}
+
+impl RuskNode {
+    /// Returns the proof that the transaction is included in its block,
+    /// along with its execution result.
+    ///
+    /// The committees of the block are drawn from the state it was built
+    /// upon, so no proof can be made once that state is no longer available.
+    pub(super) async fn receipt_proof(
+        &self,
+        tx_id: [u8; 32],
+    ) -> anyhow::Result<ResponseData> {
+        let (spent, block, prev) =
+            self.db().read().await.view(|t| -> anyhow::Result<_> {
+                let spent = t.ledger_tx(&tx_id)?.ok_or_else(|| {
+                    ApiError::not_found("Transaction not found")
+                })?;
+                let height = spent.block_height;
+
+                let block = t
+                    .block_hash_by_height(height)?
+                    .map(|hash| t.block(&hash))
+                    .transpose()?
+                    .flatten()
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Block {height} not found")
+                    })?;
+                // The genesis block has no attestation to prove it with
+                let prev_height = height.checked_sub(1).ok_or_else(|| {
+                    ApiError::not_found("No receipt for genesis transactions")
+                })?;
+                let prev = t
+                    .block_hash_by_height(prev_height)?
+                    .map(|hash| t.block_header(&hash))
+                    .transpose()?
+                    .flatten()
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Block {prev_height} not found")
+                    })?;
+
+                Ok((spent, block, prev))
+            })?;
+
+        let header = block.header();
+        let index = block
+            .txs()
+            .iter()
+            .position(|tx| tx.id() == tx_id)
+            .ok_or_else(|| anyhow::anyhow!("Transaction not in its block"))?;
+        let digests: Vec<_> =
+            block.txs().iter().map(|tx| tx.digest()).collect();
+        let path =
+            merkle_path(&digests, index).expect("index to be in the block");
+
+        let provisioners = self
+            .inner()
+            .vm_handler()
+            .read()
+            .await
+            .get_provisioners(prev.state_hash)
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Cannot get the committees of block {}: {e}",
+                    header.height
+                )
+            })?;
+
+        let proof = ReceiptProof {
+            header: header_proof(&provisioners, &prev, header),
+            prev_header: prev,
+            transaction: spent.inner.inner.to_var_bytes(),
+            index,
+            path,
+        };
+        let receipt = Receipt {
+            proof,
+            result: ExecutionResult {
+                gas_spent: spent.gas_spent,
+                error: spent.err,
+            },
+        };
+
+        Ok(ResponseData::new(serde_json::to_value(receipt)?))
+    }
+}
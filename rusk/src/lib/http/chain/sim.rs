@@ -0,0 +1,19 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Kadcast statistics, to exercise ephemeral devnets.
+//!
+//! The synthetic loss is set through the `admin/sim-loss` route.
+
+use super::*;
+
+impl RuskNode {
+    /// Returns the statistics of the network observed by the node.
+    pub(super) async fn network_sim(&self) -> anyhow::Result<ResponseData> {
+        let stats = self.network().read().await.sim_stats().await;
+        Ok(ResponseData::new(serde_json::to_value(stats)?))
+    }
+}
@@ -0,0 +1,53 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Provisioner sets at the start of every epoch, for light clients and
+//! certificate verifiers to check the committees of past blocks without the
+//! state they were drawn from.
+
+use node::database::ProvisionersStorage;
+
+use super::*;
+
+pub(super) fn parse_epoch(data: String) -> anyhow::Result<u64> {
+    data.trim().parse::<u64>().map_err(|e| {
+        ApiError::invalid_request(format!("Invalid epoch: {e}")).into()
+    })
+}
+
+impl RuskNode {
+    /// Returns the provisioner set at the start of `epoch`.
+    ///
+    /// The snapshots are stored as the blocks are accepted, so none is
+    /// returned for the epochs preceding the first block accepted by the
+    /// node.
+    ///
+    /// The unstakes and slashes within the epoch are not reflected: the
+    /// committees of the rounds following them are to be checked against
+    /// the snapshot updated by the events of the stake contract since the
+    /// start of the epoch.
+    pub(super) async fn provisioners_snapshot(
+        &self,
+        epoch: u64,
+    ) -> anyhow::Result<ResponseData> {
+        let snapshot = self
+            .db()
+            .read()
+            .await
+            .view(|t| t.provisioners_snapshot(epoch))?
+            .ok_or_else(|| {
+                ApiError::not_found(format!(
+                    "No provisioners snapshot for epoch {epoch}"
+                ))
+            })?;
+
+        Ok(ResponseData::new(json!({
+            "epoch": snapshot.epoch,
+            "round": snapshot.round,
+            "provisioners": snapshot.stakes,
+        })))
+    }
+}
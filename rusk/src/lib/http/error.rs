@@ -0,0 +1,201 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Error envelope returned by the RUES routes, so that wallets and SDKs can
+//! branch on the kind of a failure rather than on its message.
+//!
+//! The handlers keep returning `anyhow` errors, and raise an [`ApiError`]
+//! wherever the kind of the failure is known. Any other error is reported as
+//! [`ErrorCode::Internal`].
+
+use std::fmt::{self, Display, Formatter};
+
+use hyper::StatusCode;
+use serde::{Deserialize, Serialize};
+
+/// Category of a failed request, which tells the client how to react to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// The request is malformed, or its data is invalid
+    InvalidRequest,
+    /// The request lacks valid credentials
+    Unauthorized,
+    /// The requested resource does not exist
+    NotFound,
+    /// The request is not served by this node
+    Unsupported,
+    /// The request is well formed, but was refused by the node
+    Rejected,
+    /// The node cannot serve the request at the moment
+    Unavailable,
+    /// The node failed unexpectedly
+    Internal,
+}
+
+impl ErrorCategory {
+    /// HTTP status the errors of the category are returned with.
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::InvalidRequest => StatusCode::BAD_REQUEST,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::Unsupported => StatusCode::NOT_IMPLEMENTED,
+            Self::Rejected => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Stable numeric codes of the errors. The thousands give the category of an
+/// error, and the codes are never reassigned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u16)]
+pub enum ErrorCode {
+    InvalidRequest = 1000,
+    MismatchedVersion = 1001,
+    InvalidTransaction = 1002,
+    InvalidQuery = 1003,
+    InvalidSession = 1004,
+    Unauthorized = 2000,
+    NotFound = 3000,
+    UnsupportedLocation = 4000,
+    ReadOnlyReplica = 4001,
+    FeatureDisabled = 4002,
+    TransactionRejected = 5000,
+    QuotaExceeded = 5001,
+    Overloaded = 6000,
+    Internal = 9000,
+    QueryFailed = 9001,
+}
+
+impl ErrorCode {
+    pub fn category(&self) -> ErrorCategory {
+        match *self as u16 {
+            1000..=1999 => ErrorCategory::InvalidRequest,
+            2000..=2999 => ErrorCategory::Unauthorized,
+            3000..=3999 => ErrorCategory::NotFound,
+            4000..=4999 => ErrorCategory::Unsupported,
+            5000..=5999 => ErrorCategory::Rejected,
+            6000..=6999 => ErrorCategory::Unavailable,
+            _ => ErrorCategory::Internal,
+        }
+    }
+
+    /// Whether the same request may succeed if sent again later.
+    pub fn retriable(&self) -> bool {
+        matches!(self, Self::Overloaded | Self::Internal | Self::QueryFailed)
+    }
+}
+
+/// The error envelope, as serialized in the body of a failed request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiError {
+    pub code: u16,
+    pub category: ErrorCategory,
+    pub message: String,
+    pub retriable: bool,
+}
+
+impl ApiError {
+    pub fn new<S: Into<String>>(code: ErrorCode, message: S) -> Self {
+        Self {
+            code: code as u16,
+            category: code.category(),
+            message: message.into(),
+            retriable: code.retriable(),
+        }
+    }
+
+    pub fn invalid_request<S: Into<String>>(message: S) -> Self {
+        Self::new(ErrorCode::InvalidRequest, message)
+    }
+
+    pub fn not_found<S: Into<String>>(message: S) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn unsupported_location() -> Self {
+        Self::new(ErrorCode::UnsupportedLocation, "Unsupported")
+    }
+
+    pub fn internal<S: Into<String>>(message: S) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.category.status()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("error envelope to serialize")
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<&anyhow::Error> for ApiError {
+    /// Classifies an error returned by a handler, keeping the message of the
+    /// whole chain of contexts.
+    ///
+    /// An error that was not classified is reported as internal but not
+    /// retriable, since nothing tells that sending the request again can
+    /// succeed.
+    fn from(err: &anyhow::Error) -> Self {
+        let message = format!("{err:#}");
+        match err.chain().find_map(|e| e.downcast_ref::<ApiError>()) {
+            Some(api_err) => Self {
+                message,
+                ..api_err.clone()
+            },
+            None => Self {
+                retriable: false,
+                ..Self::new(ErrorCode::Internal, message)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Context;
+
+    use super::*;
+
+    #[test]
+    fn classify_errors() {
+        let err = anyhow::Error::from(ApiError::not_found("Block 3 not found"))
+            .context("Cannot get the committees");
+        let api_err = ApiError::from(&err);
+        assert_eq!(api_err.code, 3000);
+        assert_eq!(api_err.category, ErrorCategory::NotFound);
+        assert_eq!(
+            api_err.message,
+            "Cannot get the committees: Block 3 not found"
+        );
+        assert!(!api_err.retriable);
+        assert_eq!(api_err.status(), StatusCode::NOT_FOUND);
+
+        let api_err = ApiError::from(&anyhow::anyhow!("disk failure"));
+        assert_eq!(api_err.code, ErrorCode::Internal as u16);
+        assert_eq!(api_err.category, ErrorCategory::Internal);
+        assert!(!api_err.retriable);
+        assert!(ApiError::internal("database busy").retriable);
+
+        let json = ApiError::new(ErrorCode::Overloaded, "busy").to_json();
+        assert_eq!(
+            json,
+            r#"{"code":6000,"category":"unavailable","message":"busy","retriable":true}"#
+        );
+    }
+}
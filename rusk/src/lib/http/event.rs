@@ -4,19 +4,20 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use super::error::{ApiError, ErrorCode};
 use super::RUSK_VERSION_HEADER;
 
 use base64::engine::{general_purpose::STANDARD as BASE64, Engine};
 use bytecheck::CheckBytes;
 use dusk_core::abi::ContractId;
-use futures_util::stream::Iter as StreamIter;
+use futures_util::stream::BoxStream;
 use futures_util::{stream, Stream, StreamExt};
 use http_body_util::{BodyExt, Either, Full, StreamBody};
 use hyper::body::{Buf, Frame};
 use hyper::header::{InvalidHeaderName, InvalidHeaderValue};
 use hyper::{
     body::{Body, Bytes, Incoming},
-    Request, Response,
+    Method, Request, Response,
 };
 use pin_project::pin_project;
 use rand::distributions::{Distribution, Standard};
@@ -28,11 +29,14 @@ use serde::ser::SerializeMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
 use std::pin::Pin;
 use std::str::FromStr;
 use std::str::Split;
 use std::sync::mpsc;
 use std::task::{Context, Poll};
+use tokio::sync::mpsc as async_mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 use tungstenite::http::HeaderValue;
 
 /// A request sent by the websocket client.
@@ -58,14 +62,11 @@ pub struct MessageRequest {
 }
 
 impl MessageRequest {
-    pub fn to_error<S>(&self, err: S) -> MessageResponse
-    where
-        S: AsRef<str>,
-    {
+    pub fn to_error(&self, err: ApiError) -> MessageResponse {
         MessageResponse {
             headers: self.x_headers(),
             data: DataType::None,
-            error: Some(err.as_ref().to_string()),
+            error: Some(err),
         }
     }
 
@@ -174,9 +175,13 @@ impl MessageRequest {
 
             let current = Version::from_str(&crate::VERSION)?;
             if !req.matches(&current) {
-                return Err(anyhow::anyhow!(
-                    "Mismatched rusk version: requested {req} - current {current}",
-                ));
+                return Err(ApiError::new(
+                    ErrorCode::MismatchedVersion,
+                    format!(
+                        "Mismatched rusk version: requested {req} - current {current}"
+                    ),
+                )
+                .into());
             }
         }
         Ok(())
@@ -191,11 +196,11 @@ pub struct MessageResponse {
     pub data: DataType,
 
     /// A possible error happening during the contract call.
-    pub error: Option<String>,
+    pub error: Option<ApiError>,
 }
 
 impl MessageResponse {
-    pub fn from_error(error: String) -> Self {
+    pub fn from_error(error: ApiError) -> Self {
         Self {
             headers: serde_json::Map::default(),
             data: DataType::None,
@@ -209,8 +214,9 @@ impl MessageResponse {
     ) -> anyhow::Result<Response<FullOrStreamBody>> {
         if let Some(error) = &self.error {
             return Ok(hyper::Response::builder()
-                .status(hyper::StatusCode::INTERNAL_SERVER_ERROR)
-                .body(Full::new(error.to_string().into()).into())?);
+                .status(error.status())
+                .header(CONTENT_TYPE, CONTENT_TYPE_JSON.clone())
+                .body(Full::new(error.to_json().into()).into())?);
         }
 
         let mut headers = HashMap::new();
@@ -229,14 +235,14 @@ impl MessageResponse {
                     headers.insert(CONTENT_TYPE, CONTENT_TYPE_JSON.clone());
                     Full::from(Bytes::from(value.to_string())).into()
                 }
-                DataType::Channel(receiver) => FullOrStreamBody {
-                    either: Either::Right(StreamBody::new(
-                        BinaryOrTextStream {
-                            is_binary,
-                            stream: stream::iter(receiver),
-                        },
-                    )),
-                },
+                DataType::Channel(receiver) => FullOrStreamBody::stream(
+                    is_binary,
+                    stream::iter(receiver).boxed(),
+                ),
+                DataType::Stream(receiver) => FullOrStreamBody::stream(
+                    is_binary,
+                    ReceiverStream::new(receiver).boxed(),
+                ),
                 DataType::None => Full::new(Bytes::new()).into(),
             }
         };
@@ -269,6 +275,17 @@ pub struct FullOrStreamBody {
     either: Either<Full<Bytes>, StreamBody<BinaryOrTextStream>>,
 }
 
+impl FullOrStreamBody {
+    fn stream(is_binary: bool, stream: BoxStream<'static, Vec<u8>>) -> Self {
+        Self {
+            either: Either::Right(StreamBody::new(BinaryOrTextStream {
+                is_binary,
+                stream,
+            })),
+        }
+    }
+}
+
 impl From<Full<Bytes>> for FullOrStreamBody {
     fn from(body: Full<Bytes>) -> Self {
         Self {
@@ -296,7 +313,7 @@ impl Body for FullOrStreamBody {
 pub struct BinaryOrTextStream {
     is_binary: bool,
     #[pin]
-    stream: StreamIter<<mpsc::Receiver<Vec<u8>> as IntoIterator>::IntoIter>,
+    stream: BoxStream<'static, Vec<u8>>,
 }
 
 impl Stream for BinaryOrTextStream {
@@ -405,6 +422,10 @@ pub enum DataType {
     Json(serde_json::Value),
     #[serde(skip)]
     Channel(mpsc::Receiver<Vec<u8>>),
+    /// Chunks sent through a bounded channel by an async producer, which
+    /// waits for the client to keep up
+    #[serde(skip)]
+    Stream(async_mpsc::Receiver<Vec<u8>>),
     #[default]
     None,
 }
@@ -415,6 +436,7 @@ impl PartialEq for DataType {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Channel(_), Self::Channel(_)) => true,
+            (Self::Stream(_), Self::Stream(_)) => true,
             (Self::Text(a), Self::Text(b)) => a == b,
             (Self::Json(a), Self::Json(b)) => a == b,
             (Self::Binary(a), Self::Binary(b)) => a == b,
@@ -436,6 +458,18 @@ impl Clone for DataType {
 }
 
 impl DataType {
+    /// Turns streamed data into the stream of its chunks, `None` if the data
+    /// is not streamed.
+    pub fn into_stream(self) -> Option<BoxStream<'static, Vec<u8>>> {
+        match self {
+            Self::Channel(receiver) => Some(stream::iter(receiver).boxed()),
+            Self::Stream(receiver) => {
+                Some(ReceiverStream::new(receiver).boxed())
+            }
+            _ => None,
+        }
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         match self {
             Self::Binary(b) => b.inner.clone(),
@@ -470,6 +504,12 @@ impl From<mpsc::Receiver<Vec<u8>>> for DataType {
     }
 }
 
+impl From<async_mpsc::Receiver<Vec<u8>>> for DataType {
+    fn from(receiver: async_mpsc::Receiver<Vec<u8>>) -> Self {
+        Self::Stream(receiver)
+    }
+}
+
 #[serde_with::serde_as]
 #[derive(Debug, Deserialize, Serialize, Eq, PartialEq)]
 #[serde(transparent)]
@@ -609,6 +649,12 @@ impl From<anyhow::Error> for ExecutionError {
     }
 }
 
+impl From<ApiError> for ExecutionError {
+    fn from(err: ApiError) -> Self {
+        Self::Generic(err.into())
+    }
+}
+
 impl From<hyper::http::Error> for ExecutionError {
     fn from(err: hyper::http::Error) -> Self {
         Self::Http(err)
@@ -797,6 +843,10 @@ pub struct RuesDispatchEvent {
     pub uri: RuesEventUri,
     pub headers: serde_json::Map<String, serde_json::Value>,
     pub data: RequestData,
+    /// Address of the client sending the request, if known
+    pub client_addr: Option<SocketAddr>,
+    /// Method of the HTTP request, `GET` requests being cacheable
+    pub method: Method,
 }
 
 impl RuesDispatchEvent {
@@ -821,9 +871,13 @@ impl RuesDispatchEvent {
 
             let current = Version::from_str(&crate::VERSION)?;
             if !req.matches(&current) {
-                return Err(anyhow::anyhow!(
-                    "Mismatched rusk version: requested {req} - current {current}",
-                ));
+                return Err(ApiError::new(
+                    ErrorCode::MismatchedVersion,
+                    format!(
+                        "Mismatched rusk version: requested {req} - current {current}"
+                    ),
+                )
+                .into());
             }
         }
         Ok(())
@@ -843,22 +897,8 @@ impl RuesDispatchEvent {
 
         let uri = RuesEventUri::parse_from_path(parts.uri.path())
             .ok_or(anyhow::anyhow!("Invalid URL path"))?;
-
-        let headers = parts
-            .headers
-            .iter()
-            .map(|(k, v)| {
-                let v = if v.is_empty() {
-                    serde_json::Value::Null
-                } else {
-                    serde_json::from_slice::<serde_json::Value>(v.as_bytes())
-                        .unwrap_or(serde_json::Value::String(
-                            v.to_str().unwrap().to_string(),
-                        ))
-                };
-                (k.to_string().to_lowercase(), v)
-            })
-            .collect();
+        let client_addr = parts.extensions.get::<SocketAddr>().copied();
+        let headers = parse_headers(&parts.headers);
 
         // HTTP REQUEST
         let content_type = parts
@@ -895,10 +935,91 @@ impl RuesDispatchEvent {
             }
         };
 
-        let ret = RuesDispatchEvent { headers, data, uri };
+        let ret = RuesDispatchEvent {
+            headers,
+            data,
+            uri,
+            client_addr,
+            method: parts.method,
+        };
 
         Ok((ret, binary_response))
     }
+
+    /// Parses a GraphQL query sent as a `GET` request, in the `query`
+    /// parameter of the URL. The variables of the query are given as a JSON
+    /// object in the `variables` parameter, and passed on as `rusk-gqlvar-`
+    /// headers.
+    ///
+    /// The `rusk-gqlvar-` headers of the request are ignored, since the
+    /// response is cached by its URL only.
+    pub fn from_graphql_get<B>(req: &Request<B>) -> anyhow::Result<Self> {
+        let uri = RuesEventUri::parse_from_path(req.uri().path())
+            .ok_or(anyhow::anyhow!("Invalid URL path"))?;
+        let client_addr = req.extensions().get::<SocketAddr>().copied();
+        let mut headers = parse_headers(req.headers());
+        headers.retain(|name, _| !name.starts_with(GQL_VAR_PREFIX));
+
+        let mut query = String::new();
+        let params = req.uri().query().unwrap_or_default().as_bytes();
+        for (key, value) in url::form_urlencoded::parse(params) {
+            match key.as_ref() {
+                "query" => query = value.into_owned(),
+                "variables" => {
+                    let variables: serde_json::Map<_, _> =
+                        serde_json::from_str(&value).map_err(|e| {
+                            anyhow::anyhow!("Invalid variables {e}")
+                        })?;
+                    for (name, value) in variables {
+                        let name = format!("{GQL_VAR_PREFIX}{name}");
+                        headers.insert(name.to_lowercase(), value);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(RuesDispatchEvent {
+            headers,
+            data: query.into(),
+            uri,
+            client_addr,
+            method: Method::GET,
+        })
+    }
+}
+
+/// Prefix of the headers carrying the variables of a GraphQL query
+pub(crate) const GQL_VAR_PREFIX: &str = "rusk-gqlvar-";
+
+/// Returns whether the value of an `If-None-Match` header matches the entity
+/// tag `etag`, given without quotes.
+pub(crate) fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(|tag| tag.trim().trim_start_matches("W/").trim_matches('"'))
+        .any(|tag| tag == "*" || tag == etag)
+}
+
+/// Parses the headers of an HTTP request, the values being JSON where
+/// possible.
+fn parse_headers(
+    headers: &hyper::HeaderMap,
+) -> serde_json::Map<String, serde_json::Value> {
+    headers
+        .iter()
+        .map(|(k, v)| {
+            let v = if v.is_empty() {
+                serde_json::Value::Null
+            } else {
+                serde_json::from_slice::<serde_json::Value>(v.as_bytes())
+                    .unwrap_or(serde_json::Value::String(
+                        v.to_str().unwrap().to_string(),
+                    ))
+            };
+            (k.to_string().to_lowercase(), v)
+        })
+        .collect()
 }
 
 impl RuesEvent {
@@ -982,4 +1103,34 @@ mod tests {
         let data = hex::decode(data).unwrap();
         let event = Event::parse(&data).unwrap();
     }
+
+    #[test]
+    fn etag() {
+        assert!(etag_matches("\"abc\"", "abc"));
+        assert!(etag_matches("abc", "abc"));
+        assert!(etag_matches("\"def\", W/\"abc\"", "abc"));
+        assert!(etag_matches("*", "abc"));
+        assert!(!etag_matches("\"def\"", "abc"));
+    }
+
+    #[test]
+    fn graphql_get() {
+        let req = Request::get(
+            "/on/graphql/query?query=query%20(%24h%3A%20Float)%20%7B%20block(height%3A%20%24h)%20%7B%20id%20%7D%20%7D&variables=%7B%22h%22%3A1%7D",
+        )
+        .header("rusk-gqlvar-h", "2")
+        .header("rusk-gqlvar-other", "3")
+        .body(())
+        .unwrap();
+        let event = RuesDispatchEvent::from_graphql_get(&req).unwrap();
+
+        assert_eq!(event.uri.inner(), ("graphql", None, "query"));
+        assert_eq!(
+            event.data.as_string(),
+            "query ($h: Float) { block(height: $h) { id } }"
+        );
+        assert_eq!(event.header("rusk-gqlvar-h"), Some(&serde_json::json!(1)));
+        assert_eq!(event.header("rusk-gqlvar-other"), None);
+        assert_eq!(event.method, Method::GET);
+    }
 }
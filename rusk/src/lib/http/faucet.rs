@@ -0,0 +1,395 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Faucet dispensing DUSK on development and test networks.
+//!
+//! The faucet transfers a fixed amount from a funded account to the account
+//! requesting it, through Moonlight transactions signed by the node. Each
+//! client address and each receiving account are granted a limited number of
+//! transfers per window.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use dusk_bytes::{DeserializableSlice, Serializable};
+use dusk_core::signatures::bls::{
+    PublicKey as AccountPublicKey, SecretKey as AccountSecretKey,
+};
+use dusk_core::transfer::Transaction as ProtocolTransaction;
+use dusk_core::Dusk;
+use node::mempool::MempoolSrv;
+use node_data::ledger::Transaction;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::*;
+use crate::node::{Rusk, RuskNode};
+
+/// Header carrying the address of the client, as set by a reverse proxy.
+const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+
+/// Chain id of the Dusk mainnet, which the faucet refuses to run on.
+const MAINNET_CHAIN_ID: u8 = 1;
+
+const DEFAULT_AMOUNT: Dusk = dusk_core::dusk(1_000.0);
+const DEFAULT_GAS_LIMIT: u64 = 2_500_000;
+const DEFAULT_GAS_PRICE: u64 = 1;
+const DEFAULT_WINDOW: Duration = Duration::from_secs(24 * 3600);
+const DEFAULT_MAX_PER_IP: u32 = 5;
+const DEFAULT_MAX_PER_ADDRESS: u32 = 1;
+
+/// Configuration of the faucet RUES component.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FaucetConfig {
+    /// File holding the hex encoded secret key of the funded account
+    pub key_path: PathBuf,
+    /// Amount of Lux transferred per request
+    #[serde(default = "default_amount")]
+    pub amount: Dusk,
+    #[serde(default = "default_gas_limit")]
+    pub gas_limit: u64,
+    #[serde(default = "default_gas_price")]
+    pub gas_price: u64,
+    /// Window the quotas apply to
+    #[serde(with = "humantime_serde", default = "default_window")]
+    pub window: Duration,
+    /// Transfers granted to a client address per window
+    #[serde(default = "default_max_per_ip")]
+    pub max_per_ip: u32,
+    /// Transfers granted to a receiving account per window
+    #[serde(default = "default_max_per_address")]
+    pub max_per_address: u32,
+    /// Whether the client address is taken from the `X-Forwarded-For`
+    /// header, as the last address appended to it. Only to be set behind a
+    /// single reverse proxy appending the address of its client.
+    #[serde(default)]
+    pub trust_forwarded_for: bool,
+}
+
+fn default_amount() -> Dusk {
+    DEFAULT_AMOUNT
+}
+
+fn default_gas_limit() -> u64 {
+    DEFAULT_GAS_LIMIT
+}
+
+fn default_gas_price() -> u64 {
+    DEFAULT_GAS_PRICE
+}
+
+fn default_window() -> Duration {
+    DEFAULT_WINDOW
+}
+
+fn default_max_per_ip() -> u32 {
+    DEFAULT_MAX_PER_IP
+}
+
+fn default_max_per_address() -> u32 {
+    DEFAULT_MAX_PER_ADDRESS
+}
+
+/// Returns the address of the client appended to an `X-Forwarded-For` header
+/// by the proxy in front of the node.
+///
+/// The entries before the last one are sent by the client itself, so they
+/// cannot be trusted.
+fn forwarded_ip(header: &str) -> Option<IpAddr> {
+    header.rsplit(',').next()?.trim().parse().ok()
+}
+
+/// Transfers granted per key within the current window of the key.
+struct Quota<K> {
+    max: u32,
+    granted: HashMap<K, (Instant, u32)>,
+}
+
+impl<K: Eq + Hash> Quota<K> {
+    fn new(max: u32) -> Self {
+        Self {
+            max,
+            granted: HashMap::new(),
+        }
+    }
+
+    fn prune(&mut self, now: Instant, window: Duration) {
+        self.granted
+            .retain(|_, (start, _)| now.duration_since(*start) < window);
+    }
+
+    fn exhausted(&self, key: &K) -> bool {
+        self.granted
+            .get(key)
+            .is_some_and(|(_, count)| *count >= self.max)
+    }
+
+    fn grant(&mut self, key: K, now: Instant) {
+        self.granted.entry(key).or_insert((now, 0)).1 += 1;
+    }
+}
+
+struct Quotas {
+    window: Duration,
+    per_ip: Quota<IpAddr>,
+    per_address: Quota<String>,
+}
+
+impl Quotas {
+    fn new(config: &FaucetConfig) -> Self {
+        Self {
+            window: config.window,
+            per_ip: Quota::new(config.max_per_ip),
+            per_address: Quota::new(config.max_per_address),
+        }
+    }
+
+    fn check(
+        &mut self,
+        ip: Option<IpAddr>,
+        address: &str,
+        now: Instant,
+    ) -> Result<(), ApiError> {
+        self.per_ip.prune(now, self.window);
+        self.per_address.prune(now, self.window);
+
+        if ip.is_some_and(|ip| self.per_ip.exhausted(&ip)) {
+            return Err(ApiError::new(
+                ErrorCode::QuotaExceeded,
+                "Too many requests from this client",
+            ));
+        }
+        if self.per_address.exhausted(&address.to_string()) {
+            return Err(ApiError::new(
+                ErrorCode::QuotaExceeded,
+                "Too many requests for this account",
+            ));
+        }
+        Ok(())
+    }
+
+    fn grant(&mut self, ip: Option<IpAddr>, address: &str, now: Instant) {
+        if let Some(ip) = ip {
+            self.per_ip.grant(ip, now);
+        }
+        self.per_address.grant(address.to_string(), now);
+    }
+}
+
+/// The data source serving the `faucet` RUES component.
+pub struct FaucetService {
+    config: FaucetConfig,
+    sk: AccountSecretKey,
+    pk: AccountPublicKey,
+    rusk: Rusk,
+    node: RuskNode,
+    quotas: Mutex<Quotas>,
+    /// Nonce of the last transfer propagated, held while a transfer is
+    /// built so that the transfers are numbered in sequence.
+    last_nonce: tokio::sync::Mutex<u64>,
+}
+
+impl FaucetService {
+    /// Creates the faucet, reading the key of the funded account.
+    ///
+    /// The faucet is refused on the mainnet.
+    pub fn new(
+        config: FaucetConfig,
+        rusk: Rusk,
+        node: RuskNode,
+    ) -> anyhow::Result<Self> {
+        if rusk.chain_id == MAINNET_CHAIN_ID {
+            anyhow::bail!("The faucet cannot run on mainnet");
+        }
+
+        let key = std::fs::read_to_string(&config.key_path).map_err(|e| {
+            anyhow::anyhow!(
+                "Cannot read the faucet key {}: {e}",
+                config.key_path.display()
+            )
+        })?;
+        let sk = AccountSecretKey::from_slice(&hex::decode(key.trim())?)
+            .map_err(|e| anyhow::anyhow!("Invalid faucet key: {e:?}"))?;
+        let pk = AccountPublicKey::from(&sk);
+
+        Ok(Self {
+            quotas: Mutex::new(Quotas::new(&config)),
+            config,
+            sk,
+            pk,
+            rusk,
+            node,
+            last_nonce: tokio::sync::Mutex::new(0),
+        })
+    }
+
+    fn client_ip(&self, request: &RuesDispatchEvent) -> Option<IpAddr> {
+        if self.config.trust_forwarded_for {
+            let forwarded = request
+                .header(FORWARDED_FOR_HEADER)
+                .and_then(|v| v.as_str())
+                .and_then(forwarded_ip);
+            if forwarded.is_some() {
+                return forwarded;
+            }
+        }
+        request.client_addr.map(|addr| addr.ip())
+    }
+
+    fn status(&self) -> anyhow::Result<serde_json::Value> {
+        let account = self.rusk.account(&self.pk)?;
+        Ok(json!({
+            "address": bs58::encode(self.pk.to_bytes()).into_string(),
+            "balance": account.balance,
+            "amount": self.config.amount,
+            "window_secs": self.config.window.as_secs(),
+            "max_per_ip": self.config.max_per_ip,
+            "max_per_address": self.config.max_per_address,
+        }))
+    }
+
+    async fn dispense(
+        &self,
+        request: &RuesDispatchEvent,
+    ) -> anyhow::Result<serde_json::Value> {
+        if self.node.is_replica() {
+            anyhow::bail!(ApiError::new(
+                ErrorCode::ReadOnlyReplica,
+                "Transactions are not accepted by a query replica"
+            ));
+        }
+
+        let address = request.data.as_string();
+        let address = address.trim();
+        let receiver = bs58::decode(address)
+            .into_vec()
+            .ok()
+            .and_then(|bytes| AccountPublicKey::from_slice(&bytes).ok())
+            .ok_or_else(|| ApiError::invalid_request("Invalid account"))?;
+        let ip = self.client_ip(request);
+
+        let mut last_nonce = self.last_nonce.lock().await;
+        self.quotas.lock().check(ip, address, Instant::now())?;
+
+        let state_nonce = self.rusk.account(&self.pk)?.nonce;
+        let nonce = state_nonce.max(*last_nonce) + 1;
+        let tx: Transaction = ProtocolTransaction::moonlight(
+            &self.sk,
+            Some(receiver),
+            self.config.amount,
+            0,
+            self.config.gas_limit,
+            self.config.gas_price,
+            nonce,
+            self.rusk.chain_id,
+            None::<Vec<u8>>,
+        )?
+        .into();
+        let tx_id = hex::encode(tx.id());
+
+        let db = self.node.inner().database();
+        let vm = self.node.inner().vm_handler();
+        if let Err(e) =
+            MempoolSrv::check_tx(&db, &vm, &tx, true, usize::MAX).await
+        {
+            // The transfers numbered after a dropped one are rejected, so
+            // the next one is numbered after the state again
+            *last_nonce = state_nonce;
+            anyhow::bail!(ApiError::new(
+                ErrorCode::TransactionRejected,
+                e.to_string()
+            ));
+        }
+
+        self.node.network().read().await.route_internal(tx.into());
+        *last_nonce = nonce;
+        self.quotas.lock().grant(ip, address, Instant::now());
+
+        info!(event = "faucet transfer", tx_id, receiver = address, ?ip);
+        Ok(json!({ "hash": tx_id, "amount": self.config.amount }))
+    }
+}
+
+#[async_trait]
+impl HandleRequest for FaucetService {
+    fn can_handle(&self, _request: &MessageRequest) -> bool {
+        false
+    }
+
+    fn can_handle_rues(&self, request: &RuesDispatchEvent) -> bool {
+        matches!(request.uri.inner(), ("faucet", None, "status" | "dispense"))
+    }
+
+    async fn handle_rues(
+        &self,
+        request: &RuesDispatchEvent,
+    ) -> anyhow::Result<ResponseData> {
+        match request.uri.inner() {
+            ("faucet", None, "status") => Ok(ResponseData::new(self.status()?)),
+            ("faucet", None, "dispense") => {
+                Ok(ResponseData::new(self.dispense(request).await?))
+            }
+            _ => anyhow::bail!(ApiError::unsupported_location()),
+        }
+    }
+
+    async fn handle(
+        &self,
+        _request: &MessageRequest,
+    ) -> anyhow::Result<ResponseData> {
+        anyhow::bail!(ApiError::unsupported_location())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotas() {
+        let config: FaucetConfig = toml::from_str(
+            r#"
+            key_path = "faucet.key"
+            window = "1h"
+            max_per_ip = 2
+            "#,
+        )
+        .expect("config to parse");
+        assert_eq!(config.amount, DEFAULT_AMOUNT);
+        assert_eq!(config.max_per_address, DEFAULT_MAX_PER_ADDRESS);
+
+        let mut quotas = Quotas::new(&config);
+        let ip = Some(IpAddr::from([10, 0, 0, 1]));
+        let now = Instant::now();
+
+        assert!(quotas.check(ip, "alice", now).is_ok());
+        quotas.grant(ip, "alice", now);
+        let err = quotas.check(ip, "alice", now).expect_err("account quota");
+        assert_eq!(err.code, ErrorCode::QuotaExceeded as u16);
+
+        assert!(quotas.check(ip, "bob", now).is_ok());
+        quotas.grant(ip, "bob", now);
+        assert!(quotas.check(ip, "carol", now).is_err());
+        assert!(quotas.check(None, "carol", now).is_ok());
+
+        let later = now + config.window;
+        assert!(quotas.check(ip, "alice", later).is_ok());
+        assert!(quotas.per_ip.granted.is_empty());
+    }
+
+    #[test]
+    fn forwarded_client() {
+        let proxy = IpAddr::from([10, 0, 0, 1]);
+        assert_eq!(forwarded_ip("10.0.0.1"), Some(proxy));
+        // The entries set by the client are ignored
+        assert_eq!(forwarded_ip("1.2.3.4, 5.6.7.8,10.0.0.1"), Some(proxy));
+        assert_eq!(forwarded_ip("1.2.3.4, not-an-ip"), None);
+    }
+}
@@ -4,33 +4,188 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+mod jobs;
+mod pool;
+mod workers;
+
+use pool::RemoteProverPool;
+use workers::{LocalProverPool, TX_CIRCUITS};
+
+use std::num::NonZeroUsize;
+
 use anyhow::anyhow;
 
-use dusk_core::transfer::phoenix::Prove;
+use dusk_core::transfer::phoenix::{Prove, TxCircuitVec};
 use rusk_prover::LocalProver;
+use serde_json::json;
+
+use self::jobs::ProverJobs;
 
 use super::*;
 
+/// Configuration of the prover endpoints.
+#[derive(Debug, Clone)]
+pub struct ProverConfig {
+    /// URLs of remote provers to delegate proofs to. If empty, proofs are
+    /// computed locally.
+    pub remote_urls: Vec<String>,
+    /// If set, `prove` requests are queued as jobs and answered with a job
+    /// id, to be polled through `prover:<id>/status`.
+    pub async_jobs: bool,
+    /// Number of threads computing proofs locally.
+    pub workers: usize,
+    /// Number of proofs waiting for a local worker before new ones are
+    /// rejected.
+    pub queue_size: usize,
+    /// Number of computed proofs kept to answer identical requests.
+    pub cache_size: NonZeroUsize,
+}
+
+impl Default for ProverConfig {
+    fn default() -> Self {
+        Self {
+            remote_urls: vec![],
+            async_jobs: false,
+            workers: 1,
+            queue_size: 64,
+            cache_size: rusk_prover::DEFAULT_CACHE_SIZE,
+        }
+    }
+}
+
+/// A backend able to compute Phoenix proofs.
+#[async_trait]
+pub trait ProofBackend: Send + Sync + 'static {
+    async fn prove(&self, circuit: &[u8]) -> anyhow::Result<Vec<u8>>;
+}
+
+#[async_trait]
+impl ProofBackend for LocalProver {
+    async fn prove(&self, circuit: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let circuit = circuit.to_vec();
+        task::spawn_blocking(move || Prove::prove(&LocalProver, &circuit))
+            .await?
+            .map_err(|e| anyhow!(e))
+    }
+}
+
+/// The data source serving the `prover` RUES component.
+pub struct ProverService {
+    backend: Arc<dyn ProofBackend>,
+    local: Arc<LocalProverPool>,
+    jobs: Option<ProverJobs>,
+}
+
+impl ProverService {
+    /// Creates the prover service for the given configuration.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn new(
+        config: ProverConfig,
+        rues_sender: broadcast::Sender<RuesEvent>,
+    ) -> Self {
+        rusk_prover::set_cache_size(config.cache_size);
+
+        let local =
+            Arc::new(LocalProverPool::new(config.workers, config.queue_size));
+        let backend: Arc<dyn ProofBackend> = if config.remote_urls.is_empty() {
+            local.clone()
+        } else {
+            info!("Delegating proofs to {:?}", config.remote_urls);
+            Arc::new(RemoteProverPool::new(config.remote_urls, local.clone()))
+        };
+
+        let jobs = config
+            .async_jobs
+            .then(|| ProverJobs::new(backend.clone(), rues_sender));
+
+        Self {
+            backend,
+            local,
+            jobs,
+        }
+    }
+
+    async fn prove(&self, circuit: &[u8]) -> anyhow::Result<ResponseData> {
+        match &self.jobs {
+            Some(jobs) => {
+                let id = jobs.submit(circuit.to_vec()).await;
+                Ok(ResponseData::new(json!({ "id": id })))
+            }
+            None => Ok(ResponseData::new(self.backend.prove(circuit).await?)),
+        }
+    }
+
+    /// Describes the circuits supported by this prover, so clients can
+    /// detect a mismatch before submitting work.
+    ///
+    /// Average proving times only account for the proofs computed locally.
+    fn info(&self) -> serde_json::Value {
+        let timings = self.local.timings();
+
+        let circuits: Vec<_> = TX_CIRCUITS
+            .iter()
+            .enumerate()
+            .map(|(idx, &name)| {
+                let inputs = idx + 1;
+                let profile = rusk_profile::Circuit::from_name(name).ok();
+                let timings = timings.get(name).copied().unwrap_or_default();
+                json!({
+                    "name": name,
+                    "inputs": inputs,
+                    "id": profile.as_ref().map(|c| c.id_str().to_string()),
+                    "plonk_version": profile
+                        .as_ref()
+                        .and_then(|c| c.plonk_version().map(str::to_string)),
+                    "input_size": TxCircuitVec::size(inputs),
+                    "proofs": timings.proofs,
+                    "avg_proving_time_ms": timings
+                        .average()
+                        .map(|avg| avg.as_millis() as u64),
+                })
+            })
+            .collect();
+
+        json!({
+            "version": *crate::VERSION,
+            "async_jobs": self.jobs.is_some(),
+            "circuits": circuits,
+        })
+    }
+}
+
 #[async_trait]
-impl HandleRequest for LocalProver {
+impl HandleRequest for ProverService {
     fn can_handle(&self, request: &MessageRequest) -> bool {
         matches!(request.event.to_route(), (_, "rusk", topic) | (_, "prover", topic) if topic.starts_with("prove_"))
     }
     fn can_handle_rues(&self, request: &RuesDispatchEvent) -> bool {
-        matches!(request.uri.inner(), ("prover", _, "prove"))
+        matches!(
+            request.uri.inner(),
+            ("prover", _, "prove")
+                | ("prover", None, "info")
+                | ("prover", Some(_), "status")
+        )
     }
     async fn handle_rues(
         &self,
         request: &RuesDispatchEvent,
     ) -> anyhow::Result<ResponseData> {
         let data = request.data.as_bytes();
-        let response = match request.uri.inner() {
-            ("prover", _, "prove") => {
-                LocalProver.prove(data).map_err(|e| anyhow!(e))?
+        match request.uri.inner() {
+            ("prover", _, "prove") => self.prove(data).await,
+            ("prover", None, "info") => Ok(ResponseData::new(self.info())),
+            ("prover", Some(id), "status") => {
+                let jobs = self.jobs.as_ref().ok_or_else(|| {
+                    ApiError::new(
+                        ErrorCode::FeatureDisabled,
+                        "Prover jobs are disabled",
+                    )
+                })?;
+                Ok(ResponseData::new(jobs.status(id).await?))
             }
-            _ => anyhow::bail!("Unsupported"),
-        };
-        Ok(ResponseData::new(response))
+            _ => anyhow::bail!(ApiError::unsupported_location()),
+        }
     }
 
     async fn handle(
@@ -39,10 +194,8 @@ impl HandleRequest for LocalProver {
     ) -> anyhow::Result<ResponseData> {
         let topic = request.event.topic.as_str();
         let response = match topic {
-            "prove_execute" => LocalProver
-                .prove(request.event_data())
-                .map_err(|e| anyhow!(e))?,
-            _ => anyhow::bail!("Unsupported"),
+            "prove_execute" => self.backend.prove(request.event_data()).await?,
+            _ => anyhow::bail!(ApiError::unsupported_location()),
         };
         Ok(ResponseData::new(response))
     }
@@ -0,0 +1,148 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::time::Duration;
+
+use serde_json::json;
+use tokio::time;
+
+use super::*;
+
+/// How long the outcome of a job is kept after its completion, before the
+/// job is forgotten
+const JOB_RETENTION: Duration = Duration::from_secs(10 * 60);
+
+type JobId = String;
+
+enum JobState {
+    Pending,
+    Completed(Vec<u8>),
+    Failed(String),
+}
+
+/// A queue of proving jobs.
+///
+/// Submitted circuits are proved in the background; the outcome can be
+/// polled through `prover:<id>/status` for [`JOB_RETENTION`] after the job
+/// completes, and is published as a `prover:<id>/completed` or
+/// `prover:<id>/failed` RUES event.
+pub(super) struct ProverJobs {
+    backend: Arc<dyn ProofBackend>,
+    jobs: Arc<RwLock<HashMap<JobId, JobState>>>,
+    rues_sender: broadcast::Sender<RuesEvent>,
+}
+
+impl ProverJobs {
+    pub fn new(
+        backend: Arc<dyn ProofBackend>,
+        rues_sender: broadcast::Sender<RuesEvent>,
+    ) -> Self {
+        Self {
+            backend,
+            jobs: Arc::default(),
+            rues_sender,
+        }
+    }
+
+    /// Queues the given circuit for proving, returning the job id.
+    pub async fn submit(&self, circuit: Vec<u8>) -> JobId {
+        let id = hex::encode(rand::random::<[u8; 16]>());
+
+        self.jobs
+            .write()
+            .await
+            .insert(id.clone(), JobState::Pending);
+
+        let backend = self.backend.clone();
+        let jobs = self.jobs.clone();
+        let rues_sender = self.rues_sender.clone();
+        let job_id = id.clone();
+        task::spawn(async move {
+            let (state, topic, data) = match backend.prove(&circuit).await {
+                Ok(proof) => (
+                    JobState::Completed(proof.clone()),
+                    "completed",
+                    DataType::from(proof),
+                ),
+                Err(e) => {
+                    warn!("Proving job {job_id} failed: {e}");
+                    let err = e.to_string();
+                    (JobState::Failed(err.clone()), "failed", err.into())
+                }
+            };
+
+            jobs.write().await.insert(job_id.clone(), state);
+
+            // Nobody listening is not an error
+            let _ = rues_sender.send(RuesEvent {
+                uri: RuesEventUri {
+                    component: "prover".into(),
+                    entity: Some(job_id.clone()),
+                    topic: topic.into(),
+                },
+                headers: Default::default(),
+                data,
+            });
+
+            time::sleep(JOB_RETENTION).await;
+            jobs.write().await.remove(&job_id);
+        });
+
+        id
+    }
+
+    /// Returns the status of the given job as JSON.
+    pub async fn status(&self, id: &str) -> anyhow::Result<serde_json::Value> {
+        let jobs = self.jobs.read().await;
+        let job = jobs.get(id).ok_or_else(|| {
+            ApiError::not_found(format!("Unknown proving job {id}"))
+        })?;
+
+        Ok(match job {
+            JobState::Pending => json!({ "status": "pending" }),
+            JobState::Completed(proof) => {
+                json!({ "status": "completed", "proof": hex::encode(proof) })
+            }
+            JobState::Failed(error) => {
+                json!({ "status": "failed", "error": error })
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Local;
+
+    #[async_trait]
+    impl ProofBackend for Local {
+        async fn prove(&self, _: &[u8]) -> anyhow::Result<Vec<u8>> {
+            Ok(b"proof".to_vec())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn finished_jobs_expire() -> anyhow::Result<()> {
+        let (rues_sender, mut rues_receiver) = broadcast::channel(1);
+        let jobs = ProverJobs::new(Arc::new(Local), rues_sender);
+
+        let id = jobs.submit(vec![]).await;
+        let event = rues_receiver.recv().await?;
+        assert_eq!(event.uri.topic, "completed");
+
+        let status = jobs.status(&id).await?;
+        assert_eq!(status["status"], "completed");
+        assert_eq!(status["proof"], hex::encode(b"proof"));
+
+        time::sleep(JOB_RETENTION + Duration::from_secs(1)).await;
+        assert!(jobs.status(&id).await.is_err());
+        assert!(jobs.jobs.read().await.is_empty());
+
+        Ok(())
+    }
+}
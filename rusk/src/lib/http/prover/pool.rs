@@ -0,0 +1,301 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Weak;
+use std::time::Duration;
+
+use super::*;
+
+/// Interval between two health checks of the remote provers
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Timeout of a health check request
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Failure of a remote prover to produce a proof.
+#[derive(Debug)]
+enum RemoteError {
+    /// The prover is unreachable or failed, and is not to be used until it
+    /// recovers
+    Unavailable(anyhow::Error),
+    /// The prover refused the request, while being able to serve others
+    Refused(anyhow::Error),
+}
+
+impl RemoteError {
+    fn is_unavailable(&self) -> bool {
+        matches!(self, Self::Unavailable(_))
+    }
+}
+
+impl std::fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unavailable(e) => write!(f, "unavailable: {e}"),
+            Self::Refused(e) => write!(f, "refused: {e}"),
+        }
+    }
+}
+
+/// A remote prover, reachable through its RUES `prover/prove` endpoint.
+struct RemoteProver {
+    url: String,
+    /// Number of proofs currently delegated to this prover
+    load: AtomicUsize,
+    healthy: AtomicBool,
+}
+
+impl RemoteProver {
+    fn new(url: String) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            load: AtomicUsize::new(0),
+            healthy: AtomicBool::new(true),
+        }
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    async fn prove(
+        &self,
+        client: &reqwest::Client,
+        circuit: &[u8],
+    ) -> Result<Vec<u8>, RemoteError> {
+        let unavailable =
+            |e: reqwest::Error| RemoteError::Unavailable(e.into());
+        let response = client
+            .post(format!("{}/on/prover/prove", self.url))
+            .header("Content-Type", "application/octet-stream")
+            .header(RUSK_VERSION_HEADER, crate::VERSION.as_str())
+            .body(circuit.to_vec())
+            .send()
+            .await
+            .map_err(unavailable)?;
+
+        let status = response.status();
+        let body = response.bytes().await.map_err(unavailable)?;
+        if !status.is_success() {
+            let error = anyhow!("{status}: {}", String::from_utf8_lossy(&body));
+            // Only a failure of the prover makes it unavailable, not a
+            // request it refuses
+            return Err(match status.is_server_error() {
+                true => RemoteError::Unavailable(error),
+                false => RemoteError::Refused(error),
+            });
+        }
+        Ok(body.to_vec())
+    }
+
+    async fn check_health(&self, client: &reqwest::Client) {
+        // Any HTTP response means the prover is up and reachable.
+        let healthy = client
+            .get(&self.url)
+            .timeout(HEALTH_CHECK_TIMEOUT)
+            .send()
+            .await
+            .is_ok();
+
+        if healthy != self.healthy.swap(healthy, Ordering::Relaxed) {
+            match healthy {
+                true => info!("Remote prover {} is back online", self.url),
+                false => warn!("Remote prover {} is unreachable", self.url),
+            }
+        }
+    }
+}
+
+/// A pool of remote provers, falling back to a local backend when none of
+/// them is able to produce a proof.
+///
+/// Proofs are delegated to the healthy prover with the least amount of
+/// in-flight requests. A request failing is retried on the next candidate,
+/// and the prover is marked as unhealthy if it is unreachable or answered
+/// with a server error; unhealthy provers are periodically checked and
+/// brought back in the pool once reachable.
+pub(super) struct RemoteProverPool {
+    provers: Arc<Vec<RemoteProver>>,
+    client: reqwest::Client,
+    fallback: Arc<dyn ProofBackend>,
+}
+
+impl RemoteProverPool {
+    /// Creates a pool with the given prover URLs and spawns its health
+    /// checks.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn new(urls: Vec<String>, fallback: Arc<dyn ProofBackend>) -> Self {
+        let provers: Arc<Vec<_>> =
+            Arc::new(urls.into_iter().map(RemoteProver::new).collect());
+        let client = reqwest::Client::new();
+
+        task::spawn(health_check_loop(
+            Arc::downgrade(&provers),
+            client.clone(),
+        ));
+
+        Self {
+            provers,
+            client,
+            fallback,
+        }
+    }
+
+    /// Selects the least loaded healthy prover among the ones not already
+    /// tried.
+    fn select(&self, tried: &[usize]) -> Option<usize> {
+        self.provers
+            .iter()
+            .enumerate()
+            .filter(|(idx, p)| p.is_healthy() && !tried.contains(idx))
+            .min_by_key(|(_, p)| p.load.load(Ordering::Relaxed))
+            .map(|(idx, _)| idx)
+    }
+}
+
+async fn health_check_loop(
+    provers: Weak<Vec<RemoteProver>>,
+    client: reqwest::Client,
+) {
+    loop {
+        tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
+
+        // Stop checking once the pool is dropped
+        let Some(provers) = provers.upgrade() else {
+            break;
+        };
+        for prover in provers.iter() {
+            prover.check_health(&client).await;
+        }
+    }
+}
+
+#[async_trait]
+impl ProofBackend for RemoteProverPool {
+    async fn prove(&self, circuit: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let mut tried = vec![];
+        while let Some(idx) = self.select(&tried) {
+            let prover = &self.provers[idx];
+
+            prover.load.fetch_add(1, Ordering::Relaxed);
+            let result = prover.prove(&self.client, circuit).await;
+            prover.load.fetch_sub(1, Ordering::Relaxed);
+
+            match result {
+                Ok(proof) => return Ok(proof),
+                Err(e) => {
+                    warn!("Remote prover {} failed: {e}", prover.url);
+                    if e.is_unavailable() {
+                        prover.healthy.store(false, Ordering::Relaxed);
+                    }
+                    tried.push(idx);
+                }
+            }
+        }
+
+        debug!("No remote prover available, proving locally");
+        self.fallback.prove(circuit).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    use super::*;
+
+    struct Local;
+
+    #[async_trait]
+    impl ProofBackend for Local {
+        async fn prove(&self, _: &[u8]) -> anyhow::Result<Vec<u8>> {
+            Ok(b"local".to_vec())
+        }
+    }
+
+    /// Reads a whole HTTP request, its body included.
+    async fn read_request(stream: &mut TcpStream) -> std::io::Result<()> {
+        let mut request = vec![];
+        let mut buf = [0; 1024];
+        loop {
+            let read = stream.read(&mut buf).await?;
+            if read == 0 {
+                return Ok(());
+            }
+            request.extend_from_slice(&buf[..read]);
+
+            let text = String::from_utf8_lossy(&request).to_lowercase();
+            let Some(end) = text.find("\r\n\r\n") else {
+                continue;
+            };
+            let length = text
+                .lines()
+                .find_map(|l| l.strip_prefix("content-length:"))
+                .and_then(|l| l.trim().parse::<usize>().ok())
+                .unwrap_or_default();
+            if request.len() >= end + 4 + length {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Serves every request with `status` and `body`, returning the URL to
+    /// reach the server.
+    async fn serve(status: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                if read_request(&mut stream).await.is_err() {
+                    continue;
+                }
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\n\
+                     Connection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        url
+    }
+
+    async fn unreachable() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    }
+
+    #[tokio::test]
+    async fn remote_proof() {
+        let url = serve("200 OK", "proof").await;
+        let pool = RemoteProverPool::new(vec![url], Arc::new(Local));
+
+        assert_eq!(pool.prove(b"circuit").await.unwrap(), b"proof");
+        assert!(pool.provers[0].is_healthy());
+    }
+
+    #[tokio::test]
+    async fn only_failures_make_unhealthy() {
+        let urls = vec![
+            serve("400 Bad Request", "invalid circuit").await,
+            serve("429 Too Many Requests", "busy").await,
+            serve("500 Internal Server Error", "crashed").await,
+            serve("503 Service Unavailable", "stopping").await,
+            unreachable().await,
+        ];
+        let pool = RemoteProverPool::new(urls, Arc::new(Local));
+
+        // Every prover is tried before falling back to the local one
+        assert_eq!(pool.prove(b"circuit").await.unwrap(), b"local");
+
+        let healthy: Vec<_> =
+            pool.provers.iter().map(|p| p.is_healthy()).collect();
+        assert_eq!(healthy, [true, true, false, false, false]);
+    }
+}
@@ -0,0 +1,175 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex as StdMutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use dusk_core::transfer::phoenix::TxCircuitVec;
+use metrics::{counter, gauge, histogram};
+use parking_lot::Mutex;
+
+use super::*;
+
+/// Names of the transaction circuits, indexed by their number of inputs
+/// minus one.
+pub(super) const TX_CIRCUITS: [&str; 4] = [
+    "TxCircuitOneTwo",
+    "TxCircuitTwoTwo",
+    "TxCircuitThreeTwo",
+    "TxCircuitFourTwo",
+];
+
+struct ProvingTask {
+    circuit: Vec<u8>,
+    enqueued_at: Instant,
+    responder: oneshot::Sender<anyhow::Result<Vec<u8>>>,
+}
+
+/// Timings of the proofs computed for a circuit.
+#[derive(Debug, Default, Clone, Copy)]
+pub(super) struct CircuitTimings {
+    pub proofs: u64,
+    pub total: Duration,
+}
+
+impl CircuitTimings {
+    /// Returns the average proving time, if any proof has been computed.
+    pub fn average(&self) -> Option<Duration> {
+        (self.proofs > 0).then(|| self.total.div_f64(self.proofs as f64))
+    }
+}
+
+type Timings = Arc<Mutex<HashMap<&'static str, CircuitTimings>>>;
+
+/// A pool of dedicated threads computing proofs with the [`LocalProver`].
+///
+/// Proofs are queued in a bounded queue; once the queue is full new requests
+/// are rejected straight away, leaving the client free to retry later or on
+/// another node.
+pub(super) struct LocalProverPool {
+    queue: std_mpsc::SyncSender<ProvingTask>,
+    /// Number of tasks queued and not yet picked by a worker
+    queued: Arc<AtomicUsize>,
+    timings: Timings,
+}
+
+impl LocalProverPool {
+    /// Spawns `workers` proving threads sharing a queue of `queue_size`
+    /// pending proofs.
+    pub fn new(workers: usize, queue_size: usize) -> Self {
+        let (queue, receiver) = std_mpsc::sync_channel(queue_size);
+        let receiver = Arc::new(StdMutex::new(receiver));
+        let queued = Arc::new(AtomicUsize::new(0));
+        let timings = Timings::default();
+
+        for i in 0..workers.max(1) {
+            let receiver = receiver.clone();
+            let queued = queued.clone();
+            let timings = timings.clone();
+            thread::Builder::new()
+                .name(format!("prover-{i}"))
+                .spawn(move || worker_loop(&receiver, &queued, &timings))
+                .expect("Prover thread should spawn");
+        }
+
+        Self {
+            queue,
+            queued,
+            timings,
+        }
+    }
+
+    /// Returns the timings of the circuits proved so far, by circuit name.
+    pub fn timings(&self) -> HashMap<&'static str, CircuitTimings> {
+        self.timings.lock().clone()
+    }
+}
+
+fn worker_loop(
+    receiver: &StdMutex<std_mpsc::Receiver<ProvingTask>>,
+    queued: &AtomicUsize,
+    timings: &Mutex<HashMap<&'static str, CircuitTimings>>,
+) {
+    loop {
+        // The lock is released as soon as a task is received, letting the
+        // other workers wait for the next one.
+        let task = match receiver.lock().map(|r| r.recv()) {
+            Ok(Ok(task)) => task,
+            // The pool has been dropped
+            _ => break,
+        };
+        let queue_len = queued.fetch_sub(1, Ordering::Relaxed) - 1;
+        gauge!("dusk_prover_queue_len").set(queue_len as f64);
+        histogram!("dusk_prover_queue_time")
+            .record(task.enqueued_at.elapsed().as_secs_f64());
+
+        let start = Instant::now();
+        let result =
+            Prove::prove(&LocalProver, &task.circuit).map_err(|e| anyhow!(e));
+
+        if result.is_ok() {
+            if let Some(name) = circuit_name(&task.circuit) {
+                let elapsed = start.elapsed();
+                histogram!("dusk_prover_proof_time", "circuit" => name)
+                    .record(elapsed.as_secs_f64());
+
+                let mut timings = timings.lock();
+                let entry = timings.entry(name).or_default();
+                entry.proofs += 1;
+                entry.total += elapsed;
+            }
+        }
+
+        // The requester may have given up waiting
+        let _ = task.responder.send(result);
+    }
+}
+
+/// Returns the name of the circuit the given serialized circuit is for.
+fn circuit_name(circuit: &[u8]) -> Option<&'static str> {
+    let inputs = TxCircuitVec::from_slice(circuit)
+        .ok()?
+        .input_notes_info
+        .len();
+    TX_CIRCUITS.get(inputs.checked_sub(1)?).copied()
+}
+
+#[async_trait]
+impl ProofBackend for LocalProverPool {
+    async fn prove(&self, circuit: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let (responder, receiver) = oneshot::channel();
+        let task = ProvingTask {
+            circuit: circuit.to_vec(),
+            enqueued_at: Instant::now(),
+            responder,
+        };
+
+        // Count the task before sending it, so a worker picking it up
+        // immediately never sees the counter underflow.
+        let queue_len = self.queued.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Err(e) = self.queue.try_send(task) {
+            self.queued.fetch_sub(1, Ordering::Relaxed);
+            return match e {
+                std_mpsc::TrySendError::Full(_) => {
+                    counter!("dusk_prover_rejected").increment(1);
+                    Err(ApiError::new(
+                        ErrorCode::Overloaded,
+                        "Prover queue is full, retry later",
+                    )
+                    .into())
+                }
+                std_mpsc::TrySendError::Disconnected(_) => {
+                    Err(anyhow!("Prover workers are gone"))
+                }
+            };
+        }
+        gauge!("dusk_prover_queue_len").set(queue_len as f64);
+
+        receiver.await?
+    }
+}
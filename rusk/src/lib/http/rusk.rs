@@ -45,6 +45,7 @@ impl HandleRequest for Rusk {
             ("contracts", Some(_), _) => true,
             ("node", _, "provisioners") => true,
             ("node", _, "crs") => true,
+            ("notes", _, "sync-bundle") => true,
             _ => false,
         }
     }
@@ -60,7 +61,10 @@ impl HandleRequest for Rusk {
             }
             ("node", _, "provisioners") => self.get_provisioners(),
             ("node", _, "crs") => self.get_crs(),
-            _ => Err(anyhow::anyhow!("Unsupported")),
+            ("notes", _, "sync-bundle") => {
+                self.notes_sync(request.data.as_string())
+            }
+            _ => Err(ApiError::unsupported_location().into()),
         }
     }
 
@@ -77,7 +81,10 @@ impl HandleRequest for Rusk {
                 self.get_provisioners()
             }
             (Target::Host(_), "rusk", "crs") => self.get_crs(),
-            _ => Err(anyhow::anyhow!("Unsupported")),
+            (Target::Host(_), "rusk", "notes_sync_bundle") => {
+                self.notes_sync(request.event.data.as_string())
+            }
+            _ => Err(ApiError::unsupported_location().into()),
         }
     }
 }
@@ -105,7 +112,7 @@ impl Rusk {
 
         let contract_bytes = contract_bytes
             .try_into()
-            .map_err(|_| anyhow::anyhow!("Invalid contract bytes"))?;
+            .map_err(|_| ApiError::invalid_request("Invalid contract bytes"))?;
         let contract_id = ContractId::from_bytes(contract_bytes);
         let fn_name = topic.to_string();
         let data = data.to_vec();
@@ -151,6 +158,20 @@ impl Rusk {
         Ok(ResponseData::new(serde_json::to_value(prov)?))
     }
 
+    fn notes_sync(&self, from_height: String) -> anyhow::Result<ResponseData> {
+        let from_height = from_height.trim().parse()?;
+        let (sender, receiver) = mpsc::channel();
+
+        let rusk = self.clone();
+        thread::spawn(move || {
+            if let Err(e) = rusk.notes_sync_bundle(from_height, sender) {
+                tracing::warn!("Cannot send the notes sync bundle: {e}");
+            }
+        });
+
+        Ok(ResponseData::new(receiver))
+    }
+
     fn get_crs(&self) -> anyhow::Result<ResponseData> {
         let crs = rusk_profile::get_common_reference_string()?;
         Ok(ResponseData::new(crs).with_header("crs-hash", CRS_17_HASH))
@@ -0,0 +1,220 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Server side state of the RUES sessions.
+//!
+//! Every event emitted is numbered, and the most recent ones are kept in an
+//! [`EventStore`]. When a WebSocket connection drops, the subscriptions of its
+//! session are kept for [`SESSION_TTL`], so that a client reconnecting with
+//! the `Rusk-Session-Id` and `Rusk-Last-Seen` headers resumes them, and
+//! receives the events it missed in the meantime.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{broadcast, RwLock};
+use tracing::{debug, warn};
+
+use super::event::{RuesEvent, RuesEventUri, SessionId};
+
+/// Header carrying the sequence number of an event.
+pub const SEQUENCE_HEADER: &str = "Rusk-Sequence";
+
+/// Header carrying the sequence number of the last event received by a
+/// client resuming its session.
+pub const LAST_SEEN_HEADER: &str = "Rusk-Last-Seen";
+
+/// How long the subscriptions of a disconnected session are kept.
+pub const SESSION_TTL: Duration = Duration::from_secs(120);
+
+/// The most recent events, along with their sequence number.
+pub(crate) struct EventStore {
+    events: VecDeque<(u64, RuesEvent)>,
+    next_sequence: u64,
+    capacity: usize,
+}
+
+impl EventStore {
+    fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::with_capacity(capacity),
+            next_sequence: 1,
+            capacity,
+        }
+    }
+
+    /// Numbers the event and stores it, evicting the oldest event if the
+    /// store is full.
+    fn push(&mut self, event: &mut RuesEvent) -> u64 {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+
+        event.add_header(SEQUENCE_HEADER, sequence);
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back((sequence, event.clone()));
+
+        sequence
+    }
+
+    /// Returns the events following `last_seen`, or `None` if some of them
+    /// were already evicted.
+    fn since(&self, last_seen: u64) -> Option<Vec<(u64, RuesEvent)>> {
+        let oldest = self
+            .events
+            .front()
+            .map_or(self.next_sequence, |(sequence, _)| *sequence);
+        if last_seen.saturating_add(1) < oldest
+            || last_seen >= self.next_sequence
+        {
+            return None;
+        }
+
+        Some(
+            self.events
+                .iter()
+                .filter(|(sequence, _)| *sequence > last_seen)
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+struct Detached {
+    subscriptions: HashSet<RuesEventUri>,
+    since: Instant,
+}
+
+/// A session resumed on a new connection.
+pub(crate) struct Resumed {
+    pub subscriptions: HashSet<RuesEventUri>,
+    /// The events emitted since the last one seen by the client, in order
+    pub missed: Vec<(u64, RuesEvent)>,
+}
+
+pub(crate) struct Sessions {
+    store: RwLock<EventStore>,
+    detached: RwLock<HashMap<SessionId, Detached>>,
+}
+
+impl Sessions {
+    /// Creates the sessions, keeping the `capacity` most recent events for
+    /// the resumed ones.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            store: RwLock::new(EventStore::new(capacity)),
+            detached: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Numbers the events received, stores them, and forwards them to the
+    /// WebSocket sessions, until the `events` channel closes.
+    pub async fn sequence(
+        &self,
+        mut events: broadcast::Receiver<RuesEvent>,
+        sender: broadcast::Sender<RuesEvent>,
+    ) {
+        loop {
+            let mut event = match events.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    warn!("RUES event store lagged by {missed} events");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            self.store.write().await.push(&mut event);
+            let _ = sender.send(event);
+        }
+    }
+
+    /// Keeps the subscriptions of a session whose connection dropped.
+    pub async fn detach(
+        &self,
+        sid: SessionId,
+        subscriptions: HashSet<RuesEventUri>,
+    ) {
+        let mut detached = self.detached.write().await;
+        detached.retain(|_, d| d.since.elapsed() < SESSION_TTL);
+
+        if !subscriptions.is_empty() {
+            debug!("Detaching session {sid}");
+            detached.insert(
+                sid,
+                Detached {
+                    subscriptions,
+                    since: Instant::now(),
+                },
+            );
+        }
+    }
+
+    /// Resumes the session `sid`, returning its subscriptions and the events
+    /// following `last_seen`.
+    ///
+    /// Returns `None` if the session is unknown or expired, or if some of the
+    /// events following `last_seen` are not stored anymore, in which case
+    /// the client is to start a new session.
+    pub async fn resume(
+        &self,
+        sid: SessionId,
+        last_seen: u64,
+    ) -> Option<Resumed> {
+        let mut detached = self.detached.write().await;
+        detached.retain(|_, d| d.since.elapsed() < SESSION_TTL);
+
+        let missed = self.store.read().await.since(last_seen)?;
+        let subscriptions = detached.remove(&sid)?.subscriptions;
+        debug!("Resuming session {sid} after event {last_seen}");
+
+        Some(Resumed {
+            subscriptions,
+            missed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::DataType;
+
+    fn event(topic: &str) -> RuesEvent {
+        RuesEvent {
+            uri: RuesEventUri {
+                component: "blocks".into(),
+                entity: None,
+                topic: topic.into(),
+            },
+            headers: serde_json::Map::new(),
+            data: DataType::None,
+        }
+    }
+
+    #[test]
+    fn store_returns_missed_events() {
+        let mut store = EventStore::new(3);
+        for topic in ["a", "b", "c", "d"] {
+            store.push(&mut event(topic));
+        }
+
+        let missed = store.since(2).expect("events to be stored");
+        let sequences: Vec<_> = missed.iter().map(|(s, _)| *s).collect();
+        assert_eq!(sequences, vec![3, 4]);
+        assert_eq!(
+            missed[0].1.headers.get(SEQUENCE_HEADER),
+            Some(&serde_json::json!(3))
+        );
+
+        assert!(store.since(4).expect("no event missed").is_empty());
+        // Event 2 was evicted
+        assert!(store.since(0).is_none());
+        // Event 5 was never emitted
+        assert!(store.since(5).is_none());
+    }
+}
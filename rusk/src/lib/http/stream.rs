@@ -78,8 +78,10 @@ impl Listener {
         })
     }
 
-    pub async fn accept(&self) -> io::Result<Stream> {
-        let (stream, _) = self.inner.accept().await?;
+    /// Accepts a connection, returning it along with the address of the
+    /// client.
+    pub async fn accept(&self) -> io::Result<(Stream, SocketAddr)> {
+        let (stream, client_addr) = self.inner.accept().await?;
 
         let stream = match &self.acceptor {
             None => Stream::Raw(stream),
@@ -89,7 +91,7 @@ impl Listener {
             }
         };
 
-        Ok(stream)
+        Ok((stream, client_addr))
     }
 
     pub fn local_addr(&self) -> io::Result<SocketAddr> {
@@ -8,6 +8,8 @@
 
 mod bloom;
 mod error;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod http;
 #[cfg(feature = "chain")]
 pub mod node;
@@ -20,6 +22,8 @@ pub use crate::error::Error;
 pub use version::{VERSION, VERSION_BUILD};
 
 pub use builder::Builder;
+#[cfg(feature = "chain")]
+pub use builder::{ConfigReload, RuntimeConfig};
 pub type Result<T, E = Error> = core::result::Result<T, E>;
 
 #[cfg(feature = "chain")]
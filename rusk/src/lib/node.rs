@@ -4,25 +4,47 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+mod artifacts;
+mod call_graph;
+mod commit_gc;
+mod contract_stats;
 mod events;
+#[cfg(feature = "debug-state")]
+pub mod inspect;
+mod migration;
+mod panic;
 mod rusk;
+mod verified;
 mod vm;
 
 use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 use std::time::Duration;
 
 use dusk_core::{dusk, Dusk};
 
 use dusk_vm::VM;
+use node::chain::{BlockSpaceReservation, GasLimitRule, UpgradeSchedule};
 use node::database::rocksdb::{self, Backend};
+use node::mempool::policy::{AllowAll, TxPolicy};
 use node::network::Kadcast;
 use node::LongLivedService;
-use parking_lot::RwLock;
-use tokio::sync::broadcast;
+use parking_lot::{Mutex, RwLock};
+use tokio::sync::{broadcast, OnceCell, Semaphore};
 
-use crate::http::RuesEvent;
+use crate::http::{FeedHub, RuesEvent, MAX_RAW_STREAMS};
+pub use artifacts::{
+    ArtifactsConfig, ArtifactsWriter, BlockArtifacts, TxReceipt,
+};
+use commit_gc::CommitGc;
+pub use commit_gc::{DEFAULT_COMMIT_RETENTION, MAX_COMMIT_RETENTION};
+pub use contract_stats::{ContractStats, ContractUsage};
 pub(crate) use events::ChainEventStreamer;
+pub use migration::stake_config;
+pub(crate) use panic::install_hook as install_panic_hook;
+pub use panic::{NODE_THREAD_NAME, PANIC_EXIT_CODE};
+use verified::VerifiedBlocks;
 #[cfg(feature = "archive")]
 use {
     node::archive::Archive, node_data::archive::ArchivalData, tokio::sync::mpsc,
@@ -45,9 +67,17 @@ pub struct Rusk {
     pub(crate) min_deployment_gas_price: u64,
     pub(crate) min_gas_limit: u64,
     pub(crate) min_deploy_points: u64,
-    pub(crate) feeder_gas_limit: u64,
-    pub(crate) block_gas_limit: u64,
+    pub(crate) feeder_gas_limit: Arc<AtomicU64>,
+    pub(crate) gas_limit_rule: GasLimitRule,
+    pub(crate) block_space_reservation: Arc<BlockSpaceReservation>,
+    pub(crate) upgrades: Arc<UpgradeSchedule>,
     pub(crate) event_sender: broadcast::Sender<RuesEvent>,
+    pub(crate) contract_stats: Arc<ContractStats>,
+    pub(crate) verified_blocks: Arc<VerifiedBlocks>,
+    /// Digest of the nullifiers set, along with the state it was computed at
+    pub(crate) nullifiers_digest: Arc<Mutex<Option<([u8; 32], [u8; 32])>>>,
+    pub(crate) commit_gc: Arc<CommitGc>,
+    pub(crate) artifacts: Option<Arc<ArtifactsWriter>>,
     #[cfg(feature = "archive")]
     pub(crate) archive_sender: mpsc::Sender<ArchivalData>,
 }
@@ -60,6 +90,18 @@ pub struct RuskNode {
     inner: node::Node<Kadcast<255>, Backend, Rusk>,
     #[cfg(feature = "archive")]
     archive: Archive,
+    /// Whether the node is a query replica, refusing the transactions
+    replica: bool,
+    /// Endpoints of the query replicas advertised to the clients
+    replicas: Vec<String>,
+    /// Protocol upgrades declared for the network
+    upgrades: Arc<UpgradeSchedule>,
+    /// Policy of the operator on the transactions admitted in the mempool
+    tx_policy: Arc<dyn TxPolicy>,
+    /// Records of the chain feed, started by its first subscriber
+    feed_hub: Arc<OnceCell<FeedHub>>,
+    /// Permits of the raw blocks streams served at once
+    raw_streams: Arc<Semaphore>,
 }
 
 impl RuskNode {
@@ -71,9 +113,40 @@ impl RuskNode {
             inner,
             #[cfg(feature = "archive")]
             archive,
+            replica: false,
+            replicas: vec![],
+            upgrades: Arc::default(),
+            tx_policy: Arc::new(AllowAll),
+            feed_hub: Arc::default(),
+            raw_streams: Arc::new(Semaphore::new(MAX_RAW_STREAMS)),
         }
     }
 
+    /// Serves the node as a query replica, which only serves reads and
+    /// refuses the transactions submitted to it.
+    pub fn with_replica(mut self, replica: bool) -> Self {
+        self.replica = replica;
+        self
+    }
+
+    /// Advertises the query replicas the clients can send their reads to.
+    pub fn with_replicas(mut self, replicas: Vec<String>) -> Self {
+        self.replicas = replicas;
+        self
+    }
+
+    /// Exposes the protocol upgrades declared for the network.
+    pub fn with_upgrades(mut self, upgrades: Arc<UpgradeSchedule>) -> Self {
+        self.upgrades = upgrades;
+        self
+    }
+
+    /// Applies the mempool policy to the verdicts on the transactions.
+    pub fn with_tx_policy(mut self, tx_policy: Arc<dyn TxPolicy>) -> Self {
+        self.tx_policy = tx_policy;
+        self
+    }
+
     #[cfg(feature = "archive")]
     pub fn with_archive(mut self, archive: Archive) -> Self {
         self.archive = archive;
@@ -98,6 +171,30 @@ impl RuskNode {
     pub fn inner(&self) -> &node::Node<Kadcast<255>, Backend, Rusk> {
         &self.inner
     }
+
+    pub fn is_replica(&self) -> bool {
+        self.replica
+    }
+
+    pub fn replicas(&self) -> &[String] {
+        &self.replicas
+    }
+
+    pub fn upgrades(&self) -> &UpgradeSchedule {
+        &self.upgrades
+    }
+
+    pub fn tx_policy(&self) -> &dyn TxPolicy {
+        self.tx_policy.as_ref()
+    }
+
+    pub(crate) fn feed_hub(&self) -> &OnceCell<FeedHub> {
+        &self.feed_hub
+    }
+
+    pub(crate) fn raw_streams(&self) -> &Arc<Semaphore> {
+        &self.raw_streams
+    }
 }
 
 /// Calculates the value that the coinbase notes should contain.
@@ -0,0 +1,366 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Execution artifacts of the accepted blocks.
+//!
+//! After a block is accepted, its receipts, contract events, state root, gas
+//! usage and provisioner changes are written as a single JSON file, so that
+//! auditors and data pipelines can consume the chain without querying the
+//! node. An optional hook command is run with the path of each file, e.g. to
+//! upload it to an object store.
+//!
+//! The bundles dropped or failed are not retried. Instead, the `cursor` file
+//! of the directory holds the height up to which every bundle was written,
+//! so that the heights above it can be backfilled, after which the cursor is
+//! to be moved to the last height written.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::time::{Duration, Instant};
+use std::{fs, io, thread};
+
+use dusk_consensus::operations::VerificationOutput;
+use dusk_core::stake::STAKE_CONTRACT;
+use metrics::counter;
+use node_data::events::contract::{ContractEvent, ContractTxEvent};
+use node_data::ledger::{Hash, SpentTransaction};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// Maximum number of bundles waiting to be written
+const QUEUE_SIZE: usize = 64;
+
+/// Name of the file holding the height up to which every bundle was written
+const CURSOR_FILE: &str = "cursor";
+
+/// Interval at which a running hook is polled
+const HOOK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Topics of the stake contract events changing the provisioners
+const PROVISIONER_TOPICS: &[&str] =
+    &["stake", "unstake", "slash", "hard_slash"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactsConfig {
+    /// Directory the bundles are written to
+    pub dir: PathBuf,
+
+    /// Executable run with the path of each bundle written
+    #[serde(default)]
+    pub hook: Option<PathBuf>,
+
+    /// Arguments passed to the hook, before the path of the bundle
+    #[serde(default)]
+    pub hook_args: Vec<String>,
+
+    /// Time after which the hook is killed
+    #[serde(with = "humantime_serde", default = "default_hook_timeout")]
+    pub hook_timeout: Duration,
+}
+
+const fn default_hook_timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
+/// Outcome of the execution of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxReceipt {
+    pub id: String,
+    pub gas_spent: u64,
+    pub err: Option<String>,
+}
+
+/// The execution artifacts of an accepted block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockArtifacts {
+    pub height: u64,
+    pub hash: String,
+    pub state_root: String,
+    pub gas_spent: u64,
+    pub receipts: Vec<TxReceipt>,
+    pub events: Vec<ContractTxEvent>,
+    pub provisioner_changes: Vec<ContractEvent>,
+}
+
+impl BlockArtifacts {
+    pub fn new(
+        height: u64,
+        hash: Hash,
+        output: &VerificationOutput,
+        txs: &[SpentTransaction],
+        events: &[ContractTxEvent],
+    ) -> Self {
+        let receipts: Vec<_> = txs
+            .iter()
+            .map(|tx| TxReceipt {
+                id: hex::encode(tx.inner.id()),
+                gas_spent: tx.gas_spent,
+                err: tx.err.clone(),
+            })
+            .collect();
+        let provisioner_changes = events
+            .iter()
+            .map(|e| &e.event)
+            .filter(|e| {
+                e.target.0 == STAKE_CONTRACT
+                    && PROVISIONER_TOPICS.contains(&e.topic.as_str())
+            })
+            .cloned()
+            .collect();
+
+        Self {
+            height,
+            hash: hex::encode(hash),
+            state_root: hex::encode(output.state_root),
+            gas_spent: receipts.iter().map(|r| r.gas_spent).sum(),
+            receipts,
+            events: events.to_vec(),
+            provisioner_changes,
+        }
+    }
+
+    /// Name of the file of the bundle, ordered by height.
+    ///
+    /// The hash is part of the name, so that the bundle of a reverted block
+    /// is not overwritten by the one replacing it.
+    fn file_name(&self) -> String {
+        format!("{:012}-{}.json", self.height, self.hash)
+    }
+}
+
+/// Writes the bundles of the accepted blocks from a dedicated thread, so that
+/// the acceptance is not slowed down by the disk or the hook.
+pub struct ArtifactsWriter {
+    sender: SyncSender<BlockArtifacts>,
+}
+
+impl ArtifactsWriter {
+    pub fn spawn(conf: ArtifactsConfig) -> io::Result<Self> {
+        fs::create_dir_all(&conf.dir)?;
+        info!("Writing block artifacts to {:?}", conf.dir);
+
+        let mut cursor = Cursor::load(&conf.dir)?;
+        if let Some(height) = cursor.height {
+            info!("Block artifacts written up to height {height}");
+        }
+
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_SIZE);
+        thread::Builder::new()
+            .name("artifacts".into())
+            .spawn(move || {
+                for artifacts in receiver {
+                    if let Err(e) = write(&conf, &artifacts) {
+                        counter!("dusk_artifacts_errors").increment(1);
+                        warn!(
+                            "Cannot write artifacts of block {}: {e}",
+                            artifacts.height
+                        );
+                        continue;
+                    }
+                    if let Err(e) = cursor.advance(artifacts.height) {
+                        warn!("Cannot record the artifacts cursor: {e}");
+                    }
+                }
+            })?;
+
+        Ok(Self { sender })
+    }
+
+    /// Queues the bundle of a block to be written.
+    ///
+    /// The bundle is discarded if the queue is full, rather than delaying the
+    /// acceptance of the next blocks, and the cursor stops advancing until the
+    /// missing height is backfilled.
+    pub fn send(&self, artifacts: BlockArtifacts) {
+        match self.sender.try_send(artifacts) {
+            Ok(()) => {}
+            Err(TrySendError::Full(artifacts)) => {
+                counter!("dusk_artifacts_dropped").increment(1);
+                warn!("Dropping artifacts of block {}", artifacts.height);
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                warn!("Artifacts writer is not running");
+            }
+        }
+    }
+}
+
+fn write(conf: &ArtifactsConfig, artifacts: &BlockArtifacts) -> io::Result<()> {
+    let path = conf.dir.join(artifacts.file_name());
+    let bytes = serde_json::to_vec(artifacts)?;
+
+    // The bundle is renamed once complete, so that a consumer never reads a
+    // partial file
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, bytes)?;
+    fs::rename(&tmp, &path)?;
+    counter!("dusk_artifacts_written").increment(1);
+
+    if let Some(hook) = &conf.hook {
+        let mut command = Command::new(hook);
+        command.args(&conf.hook_args).arg(&path);
+        run_hook(&mut command, conf.hook_timeout)?;
+    }
+
+    Ok(())
+}
+
+/// Runs the hook, killing it if it does not exit within `timeout`.
+fn run_hook(command: &mut Command, timeout: Duration) -> io::Result<()> {
+    let mut child = command.spawn()?;
+    let deadline = Instant::now() + timeout;
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            // The hook may have exited in the meantime
+            let _ = child.kill();
+            child.wait()?;
+            counter!("dusk_artifacts_hook_timeouts").increment(1);
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("hook {command:?} timed out after {timeout:?}"),
+            ));
+        }
+        thread::sleep(HOOK_POLL_INTERVAL);
+    };
+
+    if !status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("hook {command:?} exited with {status}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Height up to which every bundle was written, persisted in the directory
+/// of the bundles.
+struct Cursor {
+    path: PathBuf,
+    height: Option<u64>,
+}
+
+impl Cursor {
+    fn load(dir: &Path) -> io::Result<Self> {
+        let path = dir.join(CURSOR_FILE);
+        let height = match fs::read_to_string(&path) {
+            Ok(height) => Some(height.trim().parse().map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid cursor {path:?}: {e}"),
+                )
+            })?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        Ok(Self { path, height })
+    }
+
+    /// Advances the cursor to `height` if it follows the last height written.
+    ///
+    /// A height at or below the cursor is a block replacing a reverted one,
+    /// and moves the cursor back to it, since the bundles of the heights above
+    /// are to be written again. A height past the next one leaves the cursor
+    /// where it is, the bundles in between having been dropped.
+    fn advance(&mut self, height: u64) -> io::Result<()> {
+        match self.height {
+            Some(cursor) if height > cursor.saturating_add(1) => return Ok(()),
+            Some(cursor) if height == cursor => return Ok(()),
+            _ => {}
+        }
+        self.height = Some(height);
+
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, height.to_string())?;
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dusk_core::abi::ContractId;
+    use node_data::events::contract::WrappedContractId;
+
+    use super::*;
+
+    fn event(target: ContractId, topic: &str) -> ContractTxEvent {
+        ContractTxEvent {
+            event: ContractEvent {
+                target: WrappedContractId(target),
+                topic: topic.into(),
+                data: vec![],
+            },
+            origin: [0; 32],
+        }
+    }
+
+    #[test]
+    fn provisioner_changes() {
+        let other = ContractId::from_bytes([0xff; 32]);
+        let events = [
+            event(STAKE_CONTRACT, "stake"),
+            event(STAKE_CONTRACT, "reward"),
+            event(STAKE_CONTRACT, "hard_slash"),
+            event(other, "stake"),
+        ];
+
+        let artifacts = BlockArtifacts::new(
+            7,
+            [1; 32],
+            &VerificationOutput::default(),
+            &[],
+            &events,
+        );
+
+        assert_eq!(artifacts.events.len(), 4);
+        assert_eq!(artifacts.gas_spent, 0);
+        let topics: Vec<_> = artifacts
+            .provisioner_changes
+            .iter()
+            .map(|e| e.topic.as_str())
+            .collect();
+        assert_eq!(topics, ["stake", "hard_slash"]);
+        assert!(artifacts.file_name().starts_with("000000000007-0101"));
+    }
+
+    #[test]
+    fn cursor_stops_at_dropped_heights() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cursor = Cursor::load(dir.path()).unwrap();
+        assert_eq!(cursor.height, None);
+
+        cursor.advance(10).unwrap();
+        cursor.advance(11).unwrap();
+        // Height 12 was dropped
+        cursor.advance(13).unwrap();
+        assert_eq!(cursor.height, Some(11));
+
+        // A reverted block moves the cursor back
+        cursor.advance(9).unwrap();
+        assert_eq!(Cursor::load(dir.path()).unwrap().height, Some(9));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hook_is_killed_after_timeout() {
+        let mut command = Command::new("sleep");
+        command.arg("10");
+
+        let start = Instant::now();
+        let err = run_hook(&mut command, Duration::from_millis(100))
+            .expect_err("hook should time out");
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        assert!(
+            run_hook(&mut Command::new("true"), Duration::from_secs(5)).is_ok()
+        );
+    }
+}
@@ -0,0 +1,132 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Calls between contracts made by a transaction, rebuilt from its call
+//! tree.
+
+use dusk_vm::CallTree;
+use node_data::ledger::CallEdge;
+
+/// A call of the tree, with the gas it was given and the one it spent.
+#[derive(Debug, Clone, Copy)]
+struct Call {
+    contract: [u8; 32],
+    limit: u64,
+    spent: u64,
+}
+
+/// A call which may have made the next ones, with the gas it had left to
+/// give and to spend.
+struct Caller {
+    contract: [u8; 32],
+    limit_left: u64,
+    spent_left: u64,
+}
+
+/// Returns the calls between contracts listed in `tree`.
+///
+/// The VM lists the calls of the tree last first, each after the calls it
+/// made, so the reversed list has each call before the ones it made.
+pub(crate) fn call_graph(tree: &CallTree) -> Vec<CallEdge> {
+    let calls: Vec<_> = tree
+        .iter()
+        .map(|call| Call {
+            contract: call.contract_id.to_bytes(),
+            limit: call.limit,
+            spent: call.spent,
+        })
+        .collect();
+    edges(calls.into_iter().rev())
+}
+
+/// Links each call to its caller, the calls being listed each before the
+/// ones it made.
+///
+/// The caller of a call is one of the calls still ongoing when it is made.
+/// A caller cannot give more gas than it has left, nor spend less than its
+/// callees, which rules out the ongoing calls that returned before: the
+/// caller is the most recent ongoing call with both the gas limit and the
+/// gas spent left to cover the call.
+fn edges(calls: impl Iterator<Item = Call>) -> Vec<CallEdge> {
+    let mut edges = vec![];
+    let mut ongoing: Vec<Caller> = vec![];
+
+    for call in calls {
+        while ongoing.last().is_some_and(|caller| {
+            caller.spent_left < call.spent || caller.limit_left < call.limit
+        }) {
+            ongoing.pop();
+        }
+
+        if let Some(caller) = ongoing.last_mut() {
+            caller.spent_left -= call.spent;
+            caller.limit_left = caller.limit_left.saturating_sub(call.spent);
+            edges.push(CallEdge {
+                caller: caller.contract,
+                callee: call.contract,
+                gas_spent: call.spent,
+            });
+        }
+        ongoing.push(Caller {
+            contract: call.contract,
+            limit_left: call.limit,
+            spent_left: call.spent,
+        });
+    }
+
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(contract: u8, limit: u64, spent: u64) -> Call {
+        Call {
+            contract: [contract; 32],
+            limit,
+            spent,
+        }
+    }
+
+    fn pairs(edges: Vec<CallEdge>) -> Vec<(u8, u8)> {
+        edges.iter().map(|e| (e.caller[0], e.callee[0])).collect()
+    }
+
+    #[test]
+    fn nested_calls() {
+        let calls = [call(1, 1_000, 500), call(2, 900, 300), call(3, 800, 100)];
+        let edges = edges(calls.into_iter());
+
+        assert_eq!(pairs(edges.clone()), vec![(1, 2), (2, 3)]);
+        assert_eq!(edges[1].gas_spent, 100);
+    }
+
+    #[test]
+    fn sibling_calls() {
+        // The second call spent more than the first one has left
+        let calls = [call(1, 1_000, 500), call(2, 900, 100), call(3, 800, 200)];
+        assert_eq!(pairs(edges(calls.into_iter())), vec![(1, 2), (1, 3)]);
+
+        // The second call was given more than the first one had
+        let calls = [call(1, 1_000, 500), call(2, 100, 50), call(3, 800, 20)];
+        assert_eq!(pairs(edges(calls.into_iter())), vec![(1, 2), (1, 3)]);
+    }
+
+    #[test]
+    fn returning_to_an_outer_caller() {
+        let calls = [
+            call(1, 1_000, 600),
+            call(2, 900, 300),
+            call(3, 800, 250),
+            call(4, 500, 280),
+        ];
+        assert_eq!(
+            pairs(edges(calls.into_iter())),
+            vec![(1, 2), (2, 3), (1, 4)]
+        );
+    }
+}
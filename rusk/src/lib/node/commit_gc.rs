@@ -0,0 +1,260 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use parking_lot::Mutex;
+use tracing::warn;
+
+/// Number of accepted states kept by default beyond the non-final ones
+pub const DEFAULT_COMMIT_RETENTION: usize = 100;
+
+/// Maximum number of accepted states kept beyond the non-final ones, bounding
+/// the disk used by the retained states
+pub const MAX_COMMIT_RETENTION: usize = 1_000;
+
+/// Reference counts of the VM commits, telling apart the ones still
+/// reachable from the ones to delete.
+///
+/// A commit is referenced by:
+/// - the accepted states not yet final, which the chain can be reverted to
+/// - the last [`retention`](Self::set_retention) accepted states, up to
+///   [`MAX_COMMIT_RETENTION`], which are queried for the committees and the
+///   history of recent blocks
+/// - the commits found at startup, until the chain moved past them
+///
+/// The tip and the base are referenced by [`Self::unreachable`] callers.
+pub(crate) struct CommitGc {
+    state: Mutex<GcState>,
+}
+
+#[derive(Default)]
+struct GcState {
+    refs: HashMap<[u8; 32], usize>,
+    /// States accepted, the oldest first
+    window: VecDeque<[u8; 32]>,
+    /// Number of states at the back of `window` accepted after the base
+    non_final: usize,
+    retention: usize,
+    /// Commits found at startup, until `retention` states are accepted
+    startup: Vec<[u8; 32]>,
+    accepted_since_start: usize,
+    /// Commits found unreferenced by the previous collection
+    unreferenced: HashSet<[u8; 32]>,
+}
+
+impl GcState {
+    fn retain(&mut self, commit: [u8; 32]) {
+        *self.refs.entry(commit).or_default() += 1;
+    }
+
+    fn release(&mut self, commit: [u8; 32]) {
+        if let Some(count) = self.refs.get_mut(&commit) {
+            *count -= 1;
+            if *count == 0 {
+                self.refs.remove(&commit);
+            }
+        }
+    }
+
+    /// Releases the oldest final states beyond the retention, along with the
+    /// commits found at startup once enough states are accepted.
+    fn trim(&mut self) {
+        while self.window.len() > self.retention
+            && self.window.len() > self.non_final + 1
+        {
+            let commit = self.window.pop_front().expect("window not empty");
+            self.release(commit);
+        }
+
+        if self.accepted_since_start >= self.retention {
+            for commit in std::mem::take(&mut self.startup) {
+                self.release(commit);
+            }
+        }
+    }
+}
+
+impl CommitGc {
+    /// Creates the reference counts, keeping `existing` commits until
+    /// `retention` states are accepted.
+    pub fn new(retention: usize, existing: Vec<[u8; 32]>) -> Self {
+        let mut state = GcState {
+            retention: bounded(retention),
+            ..Default::default()
+        };
+        for commit in &existing {
+            state.retain(*commit);
+        }
+        state.startup = existing;
+
+        Self {
+            state: Mutex::new(state),
+        }
+    }
+
+    pub fn set_retention(&self, retention: usize) {
+        let mut state = self.state.lock();
+        state.retention = bounded(retention);
+        state.trim();
+    }
+
+    /// References the state accepted on top of the tip.
+    pub fn accepted(&self, commit: [u8; 32]) {
+        let mut state = self.state.lock();
+        state.retain(commit);
+        state.window.push_back(commit);
+        state.non_final += 1;
+        state.accepted_since_start += 1;
+        state.trim();
+    }
+
+    /// Marks `base` as the last final state, so that the states before it
+    /// are released once beyond the retention.
+    pub fn finalized(&self, base: [u8; 32]) {
+        let mut state = self.state.lock();
+        if let Some(pos) = state.window.iter().rposition(|c| *c == base) {
+            state.non_final = state.window.len() - pos - 1;
+        }
+        state.trim();
+    }
+
+    /// Releases the states accepted after `commit`, the chain being reverted
+    /// to it.
+    pub fn reverted(&self, commit: [u8; 32]) {
+        let mut state = self.state.lock();
+        while let Some(last) = state.window.back().copied() {
+            if last == commit {
+                break;
+            }
+            state.window.pop_back();
+            state.non_final = state.non_final.saturating_sub(1);
+            state.release(last);
+        }
+    }
+
+    /// Returns the `commits` to delete, being referenced neither by the
+    /// counts nor by `pinned`.
+    ///
+    /// A commit is only returned if it was already unreferenced in the
+    /// previous call, so that the commits created in the meantime are not
+    /// deleted before being referenced.
+    pub fn unreachable(
+        &self,
+        commits: Vec<[u8; 32]>,
+        pinned: &[[u8; 32]],
+    ) -> Vec<[u8; 32]> {
+        let mut state = self.state.lock();
+
+        let unreferenced: HashSet<_> = commits
+            .into_iter()
+            .filter(|c| !state.refs.contains_key(c) && !pinned.contains(c))
+            .collect();
+
+        let unreachable = unreferenced
+            .intersection(&state.unreferenced)
+            .copied()
+            .collect();
+        state.unreferenced = unreferenced;
+
+        unreachable
+    }
+}
+
+/// Bounds the retention to [`MAX_COMMIT_RETENTION`].
+fn bounded(retention: usize) -> usize {
+    if retention > MAX_COMMIT_RETENTION {
+        warn!(
+            "Commit retention {retention} exceeds the maximum, keeping \
+             {MAX_COMMIT_RETENTION} states"
+        );
+    }
+    retention.min(MAX_COMMIT_RETENTION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(n: u8) -> [u8; 32] {
+        [n; 32]
+    }
+
+    /// Returns the commits in `0..n` that are unreachable, after two
+    /// collections.
+    fn collect(gc: &CommitGc, n: u8) -> Vec<u8> {
+        let commits = || (0..n).map(commit).collect::<Vec<_>>();
+        gc.unreachable(commits(), &[]);
+        let mut unreachable: Vec<_> = gc
+            .unreachable(commits(), &[])
+            .into_iter()
+            .map(|c| c[0])
+            .collect();
+        unreachable.sort();
+        unreachable
+    }
+
+    #[test]
+    fn final_states_are_kept_within_retention() {
+        let gc = CommitGc::new(2, vec![]);
+        for n in 0..5 {
+            gc.accepted(commit(n));
+        }
+        // Nothing is final yet
+        assert!(collect(&gc, 5).is_empty());
+
+        gc.finalized(commit(3));
+        assert_eq!(collect(&gc, 5), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn reverted_states_are_released() {
+        let gc = CommitGc::new(10, vec![]);
+        for n in 0..5 {
+            gc.accepted(commit(n));
+        }
+        gc.reverted(commit(2));
+        assert_eq!(collect(&gc, 5), vec![3, 4]);
+
+        // The states of the new branch are referenced again
+        gc.accepted(commit(3));
+        assert_eq!(collect(&gc, 5), vec![4]);
+    }
+
+    #[test]
+    fn startup_commits_are_kept_until_retention() {
+        let gc = CommitGc::new(2, vec![commit(0), commit(1)]);
+        gc.accepted(commit(2));
+        assert!(collect(&gc, 3).is_empty());
+
+        gc.accepted(commit(3));
+        gc.finalized(commit(3));
+        assert_eq!(collect(&gc, 4), vec![0, 1]);
+    }
+
+    #[test]
+    fn new_commits_survive_one_collection() {
+        let gc = CommitGc::new(2, vec![]);
+        assert!(gc.unreachable(vec![commit(0)], &[]).is_empty());
+        assert!(gc.unreachable(vec![commit(0)], &[commit(0)]).is_empty());
+        assert!(gc.unreachable(vec![commit(0)], &[]).is_empty());
+        assert_eq!(gc.unreachable(vec![commit(0)], &[]), vec![commit(0)]);
+    }
+
+    #[test]
+    fn retention_is_bounded() {
+        let gc = CommitGc::new(usize::MAX, vec![]);
+        let n = MAX_COMMIT_RETENTION + 2;
+        for i in 0..n {
+            let mut c = [0; 32];
+            c[..8].copy_from_slice(&(i as u64).to_le_bytes());
+            gc.accepted(c);
+        }
+        gc.finalized(gc.state.lock().window.back().copied().unwrap());
+
+        assert_eq!(gc.state.lock().window.len(), MAX_COMMIT_RETENTION);
+    }
+}
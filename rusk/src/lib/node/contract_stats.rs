@@ -0,0 +1,287 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use dusk_core::transfer::TRANSFER_CONTRACT;
+use metrics::counter;
+use node_data::ledger::SpentTransaction;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+const STATS_FILE: &str = "contract_stats.json";
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Number of days the aggregates are kept for
+const RETAINED_DAYS: u64 = 30;
+
+/// Number of contracts tracked separately each day, the usage of the other
+/// ones being aggregated under [`OTHER_CONTRACTS`]
+const MAX_DAILY_CONTRACTS: usize = 1_000;
+
+/// Number of blocks accounted between two writes of the aggregates
+const PERSIST_INTERVAL: u64 = 100;
+
+/// Key of the usage of the contracts not tracked separately, and label of
+/// the metrics of the contracts not deployed at genesis
+pub const OTHER_CONTRACTS: &str = "other";
+
+/// Gas spent by and calls made to a contract.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub struct ContractUsage {
+    pub gas_spent: u64,
+    pub calls: u64,
+}
+
+type DailyUsage = BTreeMap<u64, BTreeMap<String, ContractUsage>>;
+
+/// Usage of each contract by the accepted blocks, aggregated by day.
+///
+/// A transaction is accounted to the contract it calls, and to each
+/// contract called in turn with the gas spent by that call, the one of the
+/// calls it made included. Transfers and deployments, which call no
+/// contract, are accounted to the transfer contract.
+///
+/// The aggregates are written every [`PERSIST_INTERVAL`] blocks and when the
+/// stats are dropped, so that the last blocks accounted before a crash are
+/// lost.
+pub struct ContractStats {
+    path: PathBuf,
+    /// Usage by contract id, by day since the UNIX epoch
+    daily: RwLock<DailyUsage>,
+    /// Number of blocks accounted since the last write
+    unpersisted: AtomicU64,
+}
+
+impl ContractStats {
+    /// Loads the aggregates persisted in `dir`, if any.
+    pub fn load(dir: &Path) -> Self {
+        let path = dir.join(STATS_FILE);
+
+        let daily = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                warn!("Discarding invalid contract stats {path:?}: {e}");
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+
+        Self {
+            path,
+            daily: RwLock::new(daily),
+            unpersisted: AtomicU64::new(0),
+        }
+    }
+
+    /// Accounts the transactions of a block accepted at `timestamp`.
+    pub fn record(&self, timestamp: u64, spent_txs: &[SpentTransaction]) {
+        let day = timestamp / SECONDS_PER_DAY;
+
+        let calls = spent_txs.iter().flat_map(|spent_tx| {
+            let contract = spent_tx
+                .inner
+                .inner
+                .call()
+                .map_or(TRANSFER_CONTRACT, |call| call.contract)
+                .to_bytes();
+            let nested = spent_tx
+                .call_graph
+                .iter()
+                .map(|edge| (edge.callee, edge.gas_spent));
+            iter::once((contract, spent_tx.gas_spent)).chain(nested)
+        });
+        self.account(day, calls);
+    }
+
+    /// Accounts calls to contracts, with the gas they spent, made on `day`.
+    fn account(&self, day: u64, calls: impl Iterator<Item = ([u8; 32], u64)>) {
+        let mut daily = self.daily.write();
+        let usages = daily.entry(day).or_default();
+        for (contract, gas_spent) in calls {
+            let label = metric_label(&contract);
+            counter!("dusk_contract_gas_spent", "contract" => label.clone())
+                .increment(gas_spent);
+            counter!("dusk_contract_calls", "contract" => label).increment(1);
+
+            let contract = hex::encode(contract);
+            let key = if usages.contains_key(&contract)
+                || usages.len() < MAX_DAILY_CONTRACTS
+            {
+                contract
+            } else {
+                OTHER_CONTRACTS.to_string()
+            };
+            let usage = usages.entry(key).or_default();
+            usage.gas_spent += gas_spent;
+            usage.calls += 1;
+        }
+
+        let oldest = day.saturating_sub(RETAINED_DAYS - 1);
+        daily.retain(|day, _| *day >= oldest);
+
+        if self.unpersisted.fetch_add(1, Ordering::Relaxed) + 1
+            >= PERSIST_INTERVAL
+        {
+            self.unpersisted.store(0, Ordering::Relaxed);
+            self.persist(&daily);
+        }
+    }
+
+    fn persist(&self, daily: &DailyUsage) {
+        if let Err(e) = self.write(daily) {
+            warn!("Cannot persist contract stats to {:?}: {e}", self.path);
+        }
+    }
+
+    fn write(&self, daily: &DailyUsage) -> std::io::Result<()> {
+        // Write to a temporary file first, not to leave a truncated file
+        // behind if interrupted
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(daily)?)?;
+        fs::rename(tmp, &self.path)
+    }
+
+    /// Returns the usage of each contract over the last `days` days with
+    /// activity, the most recent first.
+    pub fn daily(&self, days: usize) -> Vec<(u64, String, ContractUsage)> {
+        self.daily
+            .read()
+            .iter()
+            .rev()
+            .take(days)
+            .flat_map(|(day, usages)| {
+                usages
+                    .iter()
+                    .map(|(contract, usage)| (*day, contract.clone(), *usage))
+            })
+            .collect()
+    }
+}
+
+impl Drop for ContractStats {
+    fn drop(&mut self) {
+        if *self.unpersisted.get_mut() > 0 {
+            let daily = std::mem::take(self.daily.get_mut());
+            self.persist(&daily);
+        }
+    }
+}
+
+/// Returns the label of the metrics of a contract: its id if deployed at
+/// genesis, with a reserved id, [`OTHER_CONTRACTS`] otherwise, not to create
+/// a series for each contract ever called.
+fn metric_label(contract: &[u8; 32]) -> String {
+    if contract[1..].iter().all(|b| *b == 0) {
+        hex::encode(contract)
+    } else {
+        OTHER_CONTRACTS.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    const DAY: u64 = 19_000;
+
+    fn contract(n: u16) -> [u8; 32] {
+        let mut id = [0xff; 32];
+        id[..2].copy_from_slice(&n.to_le_bytes());
+        id
+    }
+
+    fn usage(gas_spent: u64, calls: u64) -> ContractUsage {
+        ContractUsage { gas_spent, calls }
+    }
+
+    #[test]
+    fn nested_calls_are_accounted() {
+        let dir = tempdir().unwrap();
+        let stats = ContractStats::load(dir.path());
+
+        // A call to the first contract calling the second one twice
+        let calls =
+            [(contract(1), 1_000), (contract(2), 300), (contract(2), 200)];
+        stats.account(DAY, calls.into_iter());
+
+        let mut daily = stats.daily(1);
+        daily.sort_by(|a, b| a.1.cmp(&b.1));
+        assert_eq!(
+            daily,
+            vec![
+                (DAY, hex::encode(contract(1)), usage(1_000, 1)),
+                (DAY, hex::encode(contract(2)), usage(500, 2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn contracts_tracked_are_bounded() {
+        let dir = tempdir().unwrap();
+        let stats = ContractStats::load(dir.path());
+
+        let calls =
+            (0..MAX_DAILY_CONTRACTS as u16 + 10).map(|n| (contract(n), 1));
+        stats.account(DAY, calls);
+        // A contract already tracked is still tracked separately
+        stats.account(DAY, [(contract(0), 1)].into_iter());
+
+        let daily = stats.daily(1);
+        assert_eq!(daily.len(), MAX_DAILY_CONTRACTS + 1);
+        let usage_of = |key: &str| {
+            daily.iter().find(|(_, c, _)| c == key).map(|(_, _, u)| *u)
+        };
+        assert_eq!(usage_of(OTHER_CONTRACTS), Some(usage(10, 10)));
+        assert_eq!(usage_of(&hex::encode(contract(0))), Some(usage(2, 2)));
+
+        assert_eq!(metric_label(&contract(0)), OTHER_CONTRACTS);
+        assert_eq!(
+            metric_label(&TRANSFER_CONTRACT.to_bytes()),
+            hex::encode(TRANSFER_CONTRACT.to_bytes())
+        );
+    }
+
+    #[test]
+    fn writes_are_batched() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(STATS_FILE);
+        let stats = ContractStats::load(dir.path());
+
+        for _ in 1..PERSIST_INTERVAL {
+            stats.account(DAY, [(contract(1), 1)].into_iter());
+        }
+        assert!(!path.exists());
+
+        stats.account(DAY, [(contract(1), 1)].into_iter());
+        assert!(path.exists());
+
+        // The blocks accounted since are written when dropped
+        stats.account(DAY + 1, [(contract(1), 1)].into_iter());
+        drop(stats);
+
+        let stats = ContractStats::load(dir.path());
+        let daily = stats.daily(2);
+        assert_eq!(daily[0], (DAY + 1, hex::encode(contract(1)), usage(1, 1)));
+        assert_eq!(
+            daily[1],
+            (
+                DAY,
+                hex::encode(contract(1)),
+                usage(PERSIST_INTERVAL, PERSIST_INTERVAL)
+            )
+        );
+    }
+}
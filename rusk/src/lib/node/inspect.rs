@@ -0,0 +1,176 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Inspection of the state of the contracts at a commit, to debug state
+//! divergences between nodes.
+
+use std::sync::mpsc;
+
+use dusk_bytes::Serializable;
+use dusk_core::abi::ContractId;
+use dusk_core::stake::{StakeData, StakeKeys, STAKE_CONTRACT};
+use dusk_core::transfer::TRANSFER_CONTRACT;
+use dusk_core::BlsScalar;
+use dusk_vm::Session;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::{Error, Result};
+
+/// Default number of memory pages returned, and the maximum served by a
+/// single request to a running node
+pub const MAX_PAGES: usize = 16;
+
+/// Memory pages of a contract.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractPages {
+    pub contract: String,
+    pub pages: Vec<ContractPage>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractPage {
+    pub index: usize,
+    /// Hex encoded content of the page
+    pub data: String,
+}
+
+/// Returns up to `count` memory pages of `contract`, starting from the
+/// `from`-th page in the session state.
+///
+/// Only the pages written to since the deployment are stored, so the page
+/// indices are not contiguous.
+pub fn contract_pages(
+    session: &Session,
+    contract: ContractId,
+    from: usize,
+    count: usize,
+) -> Result<ContractPages> {
+    let pages = session
+        .memory_pages(contract)
+        .ok_or_else(|| Error::Other("Contract not found".into()))?
+        .skip(from)
+        .take(count)
+        .map(|(index, data, _)| ContractPage {
+            index,
+            data: hex::encode(data),
+        })
+        .collect();
+
+    Ok(ContractPages {
+        contract: hex::encode(contract.as_bytes()),
+        pages,
+    })
+}
+
+/// Returns the state of a genesis contract, decoded through its queries, or
+/// `None` if the contract is not known.
+pub fn known_contract_state(
+    session: &mut Session,
+    contract: ContractId,
+) -> Result<Option<Value>> {
+    let state = if contract == TRANSFER_CONTRACT {
+        transfer_state(session)?
+    } else if contract == STAKE_CONTRACT {
+        stake_state(session)?
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(state))
+}
+
+fn transfer_state(session: &mut Session) -> Result<Value> {
+    let root: BlsScalar =
+        session.call(TRANSFER_CONTRACT, "root", &(), u64::MAX)?.data;
+    let num_notes: u64 = session
+        .call(TRANSFER_CONTRACT, "num_notes", &(), u64::MAX)?
+        .data;
+    let chain_id: u8 = session
+        .call(TRANSFER_CONTRACT, "chain_id", &(), u64::MAX)?
+        .data;
+    let stake_balance: u64 = session
+        .call(
+            TRANSFER_CONTRACT,
+            "contract_balance",
+            &STAKE_CONTRACT,
+            u64::MAX,
+        )?
+        .data;
+
+    Ok(json!({
+        "root": hex::encode(root.to_bytes()),
+        "numNotes": num_notes,
+        "chainId": chain_id,
+        "stakeContractBalance": stake_balance,
+    }))
+}
+
+fn stake_state(session: &mut Session) -> Result<Value> {
+    let burnt_amount: u64 = session
+        .call(STAKE_CONTRACT, "burnt_amount", &(), u64::MAX)?
+        .data;
+
+    let (sender, receiver) = mpsc::channel();
+    session.feeder_call::<_, ()>(
+        STAKE_CONTRACT,
+        "stakes",
+        &(),
+        u64::MAX,
+        sender,
+    )?;
+
+    let stakes: Vec<_> = receiver
+        .into_iter()
+        .map(|bytes| {
+            let (keys, stake) =
+                rkyv::from_bytes::<(StakeKeys, StakeData)>(&bytes).expect(
+                    "The contract should only return (StakeKeys, StakeData) \
+                     tuples",
+                );
+            json!({
+                "account": bs58::encode(keys.account.to_bytes()).into_string(),
+                "amount": stake.amount.map(|a| a.value),
+                "locked": stake.amount.map(|a| a.locked),
+                "eligibility": stake.amount.map(|a| a.eligibility),
+                "reward": stake.reward,
+                "faults": stake.faults,
+                "hardFaults": stake.hard_faults,
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "burntAmount": burnt_amount,
+        "stakes": stakes,
+    }))
+}
+
+impl crate::node::Rusk {
+    /// Returns the memory pages of `contract` at `commit`, the tip if not
+    /// given, along with its decoded state if it is a genesis contract.
+    pub fn inspect_contract(
+        &self,
+        contract: ContractId,
+        commit: Option<[u8; 32]>,
+        from: usize,
+        count: usize,
+    ) -> Result<Value> {
+        let commit = commit.unwrap_or_else(|| self.state_root());
+        let mut session = self.query_session(Some(commit))?;
+
+        let pages = contract_pages(&session, contract, from, count)?;
+        let state = known_contract_state(&mut session, contract)?;
+
+        Ok(json!({
+            "commit": hex::encode(commit),
+            "contract": pages.contract,
+            "pages": pages.pages,
+            "state": state,
+        }))
+    }
+}
@@ -0,0 +1,107 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Migrations of the protocol contracts, applied by the first block of the
+//! upgrade changing their state layout.
+
+use std::sync::mpsc;
+
+use dusk_core::stake::{
+    LegacyStakeConfig, StakeConfig, StakeData, StakeKeys, STAKE_CONTRACT,
+};
+use dusk_vm::{ContractData, Session};
+use node::DUSK_CONSENSUS_KEY;
+use tracing::info;
+
+use crate::{Error, Result};
+
+const STAKE_CODE: &[u8] = include_bytes!(
+    "../../../../target/dusk/wasm32-unknown-unknown/release/stake_contract.wasm"
+);
+
+/// Returns the version of the stake contract of the session.
+pub(crate) fn stake_version(session: &mut Session) -> Result<u64> {
+    Ok(session
+        .call(STAKE_CONTRACT, "get_version", &(), u64::MAX)?
+        .data)
+}
+
+/// Returns the configuration of the stake contract of the session, in the
+/// layout of its version.
+pub fn stake_config(session: &mut Session) -> Result<StakeConfig> {
+    if stake_version(session)? <= LegacyStakeConfig::VERSION {
+        let config: LegacyStakeConfig = session
+            .call(STAKE_CONTRACT, "get_config", &(), u64::MAX)?
+            .data;
+        return Ok(config.into());
+    }
+    Ok(session
+        .call(STAKE_CONTRACT, "get_config", &(), u64::MAX)?
+        .data)
+}
+
+/// Replaces a stake contract storing a [`LegacyStakeConfig`] by the one of
+/// this release, moving its configuration, stakes and burnt amount.
+///
+/// The epoch and maturity of the migrated configuration are the defaults,
+/// which the replaced contract implied, so that the stakes keep their
+/// eligibility.
+pub(crate) fn migrate_stake_contract(mut session: Session) -> Result<Session> {
+    if stake_version(&mut session)? > LegacyStakeConfig::VERSION {
+        return Ok(session);
+    }
+
+    let config = stake_config(&mut session)?;
+    let burnt_amount: u64 = session
+        .call(STAKE_CONTRACT, "burnt_amount", &(), u64::MAX)?
+        .data;
+
+    let (sender, receiver) = mpsc::channel();
+    session.feeder_call::<_, ()>(
+        STAKE_CONTRACT,
+        "stakes",
+        &(),
+        u64::MAX,
+        sender,
+    )?;
+    let stakes = receiver
+        .try_iter()
+        .map(|bytes| rkyv::from_bytes::<(StakeKeys, StakeData)>(&bytes))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| {
+            Error::Other(format!("invalid stake to migrate: {e}").into())
+        })?;
+    let count = stakes.len();
+
+    let session = session.migrate(
+        STAKE_CONTRACT,
+        STAKE_CODE,
+        ContractData::builder().owner(DUSK_CONSENSUS_KEY.to_bytes()),
+        u64::MAX,
+        |migrated, session| {
+            session.call::<_, ()>(migrated, "set_config", &config, u64::MAX)?;
+            session.call::<_, ()>(
+                migrated,
+                "set_burnt_amount",
+                &burnt_amount,
+                u64::MAX,
+            )?;
+            for stake in &stakes {
+                session.call::<_, ()>(
+                    migrated,
+                    "insert_stake",
+                    stake,
+                    u64::MAX,
+                )?;
+            }
+            Ok(())
+        },
+    )?;
+
+    info!(event = "stake contract migrated", stakes = count);
+
+    Ok(session)
+}
@@ -0,0 +1,89 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::backtrace::Backtrace;
+use std::panic::{self, PanicInfo};
+use std::{process, thread};
+
+use node::database::rocksdb::Backend;
+use tracing::error;
+
+use super::Rusk;
+
+/// Exit code of the node terminating because of a panic.
+pub const PANIC_EXIT_CODE: i32 = 70;
+
+/// Name of the threads of the runtime the node runs on.
+pub const NODE_THREAD_NAME: &str = "rusk-node";
+
+/// Threads whose panic may leave the state of the node half-updated.
+const CRITICAL_THREADS: &[&str] = &["main", NODE_THREAD_NAME];
+
+/// Installs a panic hook persisting the state of the node before exiting
+/// with [`PANIC_EXIT_CODE`].
+///
+/// Since a panic may leave the state half-updated, the database is marked as
+/// dirty to have its integrity checked at the next start. The panics of the
+/// other threads, such as the HTTP ones, are only logged, and unwind as
+/// usual.
+pub(crate) fn install_hook(db: Backend, rusk: Rusk) {
+    panic::set_hook(Box::new(move |info| {
+        log_panic(info);
+
+        if !is_critical(thread::current().name()) {
+            return;
+        }
+
+        if let Err(e) = db.mark_dirty() {
+            error!("Cannot mark the database as dirty: {e}");
+        }
+        db.flush();
+
+        if let Err(e) = rusk.persist_base_commit() {
+            error!("Cannot persist the base commit: {e}");
+        }
+
+        process::exit(PANIC_EXIT_CODE);
+    }));
+}
+
+fn is_critical(thread: Option<&str>) -> bool {
+    thread.is_some_and(|name| CRITICAL_THREADS.contains(&name))
+}
+
+fn log_panic(info: &PanicInfo) {
+    let thread = thread::current();
+    let thread = thread.name().unwrap_or("<unnamed>");
+
+    let payload = info
+        .payload()
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| info.payload().downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("<non-string payload>");
+
+    let location = info.location().map(ToString::to_string).unwrap_or_default();
+
+    let backtrace = Backtrace::force_capture();
+
+    error!(event = "panic", thread, location, "{payload}\n{backtrace}");
+    // The log may not be registered yet
+    eprintln!("thread '{thread}' panicked at {location}:\n{payload}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_node_threads_are_critical() {
+        assert!(is_critical(Some("main")));
+        assert!(is_critical(Some(NODE_THREAD_NAME)));
+        assert!(!is_critical(Some("http")));
+        assert!(!is_critical(Some("artifacts")));
+        assert!(!is_critical(None));
+    }
+}
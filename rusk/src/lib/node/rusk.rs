@@ -4,7 +4,9 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use std::io::Write;
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{mpsc, Arc};
 use std::time::{Duration, Instant};
 use std::{fs, io};
@@ -19,26 +21,37 @@ use dusk_consensus::operations::{CallParams, VerificationOutput, Voter};
 use dusk_core::abi::Event;
 use dusk_core::signatures::bls::PublicKey as BlsPublicKey;
 use dusk_core::stake::{
-    Reward, RewardReason, StakeData, StakeKeys, STAKE_CONTRACT,
+    Reward, RewardReason, StakeConfig, StakeData, StakeKeys, STAKE_CONTRACT,
 };
 use dusk_core::transfer::{
     moonlight::AccountData, PANIC_NONCE_NOT_READY, TRANSFER_CONTRACT,
 };
 use dusk_core::{BlsScalar, Dusk};
 use dusk_vm::{execute, CallReceipt, Error as VMError, Session, VM};
+use node::chain::{
+    BlockSpace, BlockSpaceReservation, GasLimitRule, Upgrade, UpgradeSchedule,
+    BLOCK_SPACE, STAKE_CONFIG, TX_EXPIRY,
+};
 use node::DUSK_CONSENSUS_KEY;
 use node_data::events::contract::{ContractEvent, ContractTxEvent};
 use node_data::ledger::{Hash, Slash, SpentTransaction, Transaction};
 use parking_lot::RwLock;
-use rusk_profile::to_rusk_state_id_path;
+use rusk_profile::{to_rusk_state_id_path, to_rusk_upgrades_path};
+use serde::Deserialize;
 use tokio::sync::broadcast;
-use tracing::info;
+use tracing::{info, warn};
 #[cfg(feature = "archive")]
 use {node_data::archive::ArchivalData, tokio::sync::mpsc::Sender};
 
 use crate::bloom::Bloom;
 use crate::http::RuesEvent;
-use crate::node::{coinbase_value, Rusk, RuskTip};
+use crate::node::call_graph::call_graph;
+use crate::node::migration;
+use crate::node::verified::VerifiedBlock;
+use crate::node::{
+    coinbase_value, ArtifactsWriter, BlockArtifacts, CommitGc, ContractStats,
+    Rusk, RuskTip, DEFAULT_COMMIT_RETENTION,
+};
 use crate::Error::InvalidCreditsCount;
 use crate::{Error, Result};
 
@@ -52,7 +65,8 @@ impl Rusk {
         min_deployment_gas_price: u64,
         min_gas_limit: u64,
         min_deploy_points: u64,
-        block_gas_limit: u64,
+        gas_limit_rule: GasLimitRule,
+        block_space_reservation: BlockSpaceReservation,
         feeder_gas_limit: u64,
         event_sender: broadcast::Sender<RuesEvent>,
         #[cfg(feature = "archive")] archive_sender: Sender<ArchivalData>,
@@ -76,7 +90,16 @@ impl Rusk {
         let mut base_commit = [0u8; 32];
         base_commit.copy_from_slice(&base_commit_bytes);
 
+        let upgrades = load_upgrades(dir)?;
+        for upgrade in upgrades.upgrades() {
+            info!(
+                "Protocol upgrade {} at height {}",
+                upgrade.name, upgrade.height
+            );
+        }
+
         let vm = Arc::new(VM::new(dir)?);
+        let commit_gc = CommitGc::new(DEFAULT_COMMIT_RETENTION, vm.commits());
 
         let tip = Arc::new(RwLock::new(RuskTip {
             current: base_commit,
@@ -93,14 +116,39 @@ impl Rusk {
             min_deployment_gas_price,
             min_gas_limit,
             min_deploy_points,
-            feeder_gas_limit,
+            feeder_gas_limit: Arc::new(AtomicU64::new(feeder_gas_limit)),
             event_sender,
+            contract_stats: Arc::new(ContractStats::load(dir)),
+            verified_blocks: Arc::default(),
+            nullifiers_digest: Arc::default(),
+            commit_gc: Arc::new(commit_gc),
+            artifacts: None,
             #[cfg(feature = "archive")]
             archive_sender,
-            block_gas_limit,
+            gas_limit_rule,
+            block_space_reservation: Arc::new(block_space_reservation),
+            upgrades: Arc::new(upgrades),
         })
     }
 
+    /// Returns the protocol upgrades declared by the genesis of the state.
+    pub fn upgrades(&self) -> Arc<UpgradeSchedule> {
+        self.upgrades.clone()
+    }
+
+    /// Returns the block space reservation applying to the block at
+    /// `block_height`, nothing being reserved before the `block-space`
+    /// upgrade.
+    pub fn block_space_reservation(
+        &self,
+        block_height: u64,
+    ) -> BlockSpaceReservation {
+        match self.upgrades.is_active(BLOCK_SPACE, block_height) {
+            true => self.block_space_reservation.as_ref().clone(),
+            false => BlockSpaceReservation::default(),
+        }
+    }
+
     pub fn execute_transactions<I: Iterator<Item = Transaction>>(
         &self,
         params: &CallParams,
@@ -110,7 +158,7 @@ impl Rusk {
         let started = Instant::now();
 
         let block_height = params.round;
-        let block_gas_limit = self.block_gas_limit;
+        let block_gas_limit = params.block_gas_limit;
         let generator = params.generator_pubkey.inner();
         let to_slash = params.to_slash.clone();
         let prev_state_root = params.prev_state_root;
@@ -121,6 +169,10 @@ impl Rusk {
             self.new_block_session(block_height, prev_state_root)?;
 
         let mut block_gas_left = block_gas_limit;
+        let block_space_reservation =
+            self.block_space_reservation(block_height);
+        let mut block_space =
+            BlockSpace::new(&block_space_reservation, block_gas_limit);
 
         let mut spent_txs = Vec::<SpentTransaction>::new();
         let mut discarded_txs = vec![];
@@ -159,6 +211,22 @@ impl Rusk {
                 continue;
             }
 
+            // A transaction with an expiry height is rejected by the
+            // transfer contract before the upgrade, while an expired one can
+            // never be included
+            if unspent_tx.inner.expiry_height().is_some()
+                && !self.upgrades.is_active(TX_EXPIRY, block_height)
+            {
+                info!("discard tx {tx_id_hex} due to expiry not active");
+                discarded_txs.push(unspent_tx);
+                continue;
+            }
+            if unspent_tx.inner.is_expired_at(block_height) {
+                info!("discard tx {tx_id_hex} due to expiry");
+                discarded_txs.push(unspent_tx);
+                continue;
+            }
+
             match execute(
                 &mut session,
                 &unspent_tx.inner,
@@ -169,11 +237,20 @@ impl Rusk {
                 Ok(receipt) => {
                     let gas_spent = receipt.gas_spent;
 
-                    // If the transaction went over the block gas limit we
+                    // If the transaction went over the block gas limit, or
+                    // over the gas left to the user transactions, we
                     // re-execute all spent transactions. We don't discard the
                     // transaction, since it is technically valid.
-                    if gas_spent > block_gas_left {
-                        info!("Skipping {tx_id_hex} due gas_spent {gas_spent} greater than left: {block_gas_left}");
+                    let skip_reason = if gas_spent > block_gas_left {
+                        Some(format!("gas_spent {gas_spent} greater than left: {block_gas_left}"))
+                    } else {
+                        block_space
+                            .check(&unspent_tx, gas_spent)
+                            .err()
+                            .map(|e| e.to_string())
+                    };
+                    if let Some(reason) = skip_reason {
+                        info!("Skipping {tx_id_hex} due {reason}");
                         session = self
                             .new_block_session(block_height, prev_state_root)?;
 
@@ -201,6 +278,8 @@ impl Rusk {
                     event_bloom.add_events(&receipt.events);
 
                     block_gas_left -= gas_spent;
+                    // Checked above
+                    let _ = block_space.include(&unspent_tx, gas_spent);
                     let gas_price = unspent_tx.inner.gas_price();
                     dusk_spent += gas_spent * gas_price;
                     spent_txs.push(SpentTransaction {
@@ -208,6 +287,7 @@ impl Rusk {
                         gas_spent,
                         block_height,
                         err,
+                        call_graph: call_graph(&receipt.call_tree),
                     });
                 }
                 Err(VMError::Panic(val)) if val == PANIC_NONCE_NOT_READY => {
@@ -265,11 +345,13 @@ impl Rusk {
     ) -> Result<(Vec<SpentTransaction>, VerificationOutput)> {
         let session = self.new_block_session(block_height, prev_commit)?;
 
-        accept(
+        let (spent_txs, verification_output, session, events) = accept(
             session,
             block_height,
             block_hash,
             block_gas_limit,
+            &self.block_space_reservation(block_height),
+            &self.upgrades,
             generator,
             txs,
             slashing,
@@ -277,8 +359,24 @@ impl Rusk {
             self.gas_per_deploy_byte,
             self.min_deploy_points,
             self.min_deployment_gas_price,
-        )
-        .map(|(a, b, _, _)| (a, b))
+        )?;
+
+        // Keep the resulting state, to be taken if the block is accepted
+        let commit = session.commit()?;
+        self.verified_blocks.insert(VerifiedBlock {
+            prev_commit,
+            block_hash,
+            tx_ids: txs.iter().map(|t| t.id()).collect(),
+            spent_txs: spent_txs.clone(),
+            verification_output: VerificationOutput {
+                state_root: verification_output.state_root,
+                event_bloom: verification_output.event_bloom,
+            },
+            commit,
+            events,
+        });
+
+        Ok((spent_txs, verification_output))
     }
 
     /// Accept the given transactions.
@@ -303,25 +401,45 @@ impl Rusk {
         VerificationOutput,
         Vec<ContractEvent>,
     )> {
-        let session = self.new_block_session(block_height, prev_commit)?;
-
-        let (spent_txs, verification_output, session, events) = accept(
-            session,
-            block_height,
-            block_hash,
-            block_gas_limit,
-            &generator,
-            &txs[..],
-            slashing,
-            voters,
-            self.gas_per_deploy_byte,
-            self.min_deploy_points,
-            self.min_deployment_gas_price,
-        )?;
+        // A block verified during the Validation step is not executed again
+        let verified = self.verified_blocks.take(prev_commit, block_hash, &txs);
+        let (spent_txs, verification_output, state, events) = match verified {
+            Some(verified) => (
+                verified.spent_txs,
+                verified.verification_output,
+                BlockState::Committed(verified.commit),
+                verified.events,
+            ),
+            None => {
+                let session =
+                    self.new_block_session(block_height, prev_commit)?;
+                let (spent_txs, verification_output, session, events) = accept(
+                    session,
+                    block_height,
+                    block_hash,
+                    block_gas_limit,
+                    &self.block_space_reservation(block_height),
+                    &self.upgrades,
+                    &generator,
+                    &txs[..],
+                    slashing,
+                    voters,
+                    self.gas_per_deploy_byte,
+                    self.min_deploy_points,
+                    self.min_deployment_gas_price,
+                )?;
+                (
+                    spent_txs,
+                    verification_output,
+                    BlockState::Executed(session),
+                    events,
+                )
+            }
+        };
 
         if let Some(expected_verification) = consistency_check {
             if expected_verification != verification_output {
-                // Drop the session if the resulting is inconsistent
+                // Drop the state if the resulting is inconsistent
                 // with the callers one.
                 return Err(Error::InconsistentState(Box::new(
                     verification_output,
@@ -329,7 +447,13 @@ impl Rusk {
             }
         }
 
-        self.set_current_commit(session.commit()?);
+        let commit = match state {
+            BlockState::Committed(commit) => commit,
+            BlockState::Executed(session) => session.commit()?,
+        };
+        self.set_current_commit(commit);
+        self.commit_gc.accepted(commit);
+        self.verified_blocks.clear();
 
         // Sent all events from this block to the archivist
         #[cfg(feature = "archive")]
@@ -341,6 +465,16 @@ impl Rusk {
             ));
         }
 
+        if let Some(artifacts) = &self.artifacts {
+            artifacts.send(BlockArtifacts::new(
+                block_height,
+                block_hash,
+                &verification_output,
+                &spent_txs,
+                &events,
+            ));
+        }
+
         let mut stake_events = vec![];
         for event in events {
             if event.event.target.0 == STAKE_CONTRACT {
@@ -360,12 +494,31 @@ impl Rusk {
         to_merge: Vec<[u8; 32]>,
     ) -> Result<()> {
         self.set_base_and_merge(commit, to_merge)?;
+        self.commit_gc.finalized(commit);
 
         let commit_id_path = to_rusk_state_id_path(&self.dir);
         fs::write(commit_id_path, commit)?;
         Ok(())
     }
 
+    /// Writes the base commit to disk, synchronously, so that the state can
+    /// be loaded at the next start even if the node terminates abruptly.
+    ///
+    /// Fails if the tip is being updated, as its base may be inconsistent.
+    pub fn persist_base_commit(&self) -> io::Result<()> {
+        let base = self
+            .tip
+            .try_read()
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::Other, "tip is being updated")
+            })?
+            .base;
+
+        let mut file = fs::File::create(to_rusk_state_id_path(&self.dir))?;
+        file.write_all(&base)?;
+        file.sync_all()
+    }
+
     pub fn revert(&self, state_hash: [u8; 32]) -> Result<[u8; 32]> {
         let mut tip = self.tip.write();
 
@@ -375,6 +528,8 @@ impl Rusk {
         }
 
         tip.current = state_hash;
+        self.commit_gc.reverted(state_hash);
+        self.verified_blocks.clear();
         Ok(tip.current)
     }
 
@@ -401,6 +556,60 @@ impl Rusk {
         self.query(TRANSFER_CONTRACT, "existing_nullifiers", nullifiers)
     }
 
+    /// Feeds `sender` with what a wallet needs to catch up from
+    /// `from_height`, all read from the same state.
+    ///
+    /// The first chunk is made of the 32 bytes of the root of the notes
+    /// tree, followed by the 32 bytes of the digest of the nullifiers set.
+    /// The next chunks are the leaves of the notes from `from_height`, as fed
+    /// by the `leaves_from_height` query of the transfer contract.
+    ///
+    /// The digest is the BLAKE2b-256 hash of the nullifiers in ascending
+    /// order. It is computed once per state.
+    pub fn notes_sync_bundle(
+        &self,
+        from_height: u64,
+        sender: mpsc::Sender<Vec<u8>>,
+    ) -> Result<()> {
+        let state_root = self.state_root();
+        let mut session = self.query_session(Some(state_root))?;
+        let gas_limit = self.feeder_gas_limit.load(Ordering::Relaxed);
+
+        let root: CallReceipt<BlsScalar> =
+            session.call(TRANSFER_CONTRACT, "root", &(), u64::MAX)?;
+
+        let digest = {
+            // Requests for the same state wait for the digest to be computed
+            // once
+            let mut cached = self.nullifiers_digest.lock();
+            match *cached {
+                Some((root, digest)) if root == state_root => digest,
+                _ => {
+                    let digest = nullifiers_digest(&mut session, gas_limit)?;
+                    *cached = Some((state_root, digest));
+                    digest
+                }
+            }
+        };
+
+        let mut header = root.data.to_bytes().to_vec();
+        header.extend_from_slice(&digest);
+        if sender.send(header).is_err() {
+            // Nobody is listening anymore
+            return Ok(());
+        }
+
+        session.feeder_call::<_, ()>(
+            TRANSFER_CONTRACT,
+            "leaves_from_height",
+            &from_height,
+            gas_limit,
+            sender,
+        )?;
+
+        Ok(())
+    }
+
     /// Returns the stakes.
     pub fn provisioners(
         &self,
@@ -460,10 +669,46 @@ impl Rusk {
         }).collect())
     }
 
+    /// Returns the stakes changed by the block that resulted in the state
+    /// `commit`, each with its value before and after the block.
+    pub fn provisioners_diff(
+        &self,
+        commit: [u8; 32],
+    ) -> Result<Vec<(BlsPublicKey, Option<StakeData>, Option<StakeData>)>> {
+        let changes = self.last_provisioners_change(Some(commit))?;
+        let mut session = self.query_session(Some(commit))?;
+
+        changes
+            .into_iter()
+            .map(|(pk, before)| {
+                let after = session
+                    .call(STAKE_CONTRACT, "get_stake", &pk, u64::MAX)?
+                    .data;
+                Ok((pk, before, after))
+            })
+            .collect()
+    }
+
     pub fn provisioner(&self, pk: &BlsPublicKey) -> Result<Option<StakeData>> {
         self.query(STAKE_CONTRACT, "get_stake", pk)
     }
 
+    /// Returns the parameters of the stake contract, as set at genesis or by
+    /// the contract owner.
+    pub fn stake_config(&self) -> Result<StakeConfig> {
+        self.stake_config_at(None)
+    }
+
+    /// Returns the parameters of the stake contract at the state `commit`, or
+    /// at the current one if `None`.
+    pub fn stake_config_at(
+        &self,
+        commit: Option<[u8; 32]>,
+    ) -> Result<StakeConfig> {
+        let mut session = self.query_session(commit)?;
+        migration::stake_config(&mut session)
+    }
+
     /// Opens a session for a new block proposal/verification.
     ///
     /// Before returning the session, "before_state_transition" of Stake
@@ -477,6 +722,13 @@ impl Rusk {
         if session.root() != commit {
             return Err(Error::TipChanged);
         }
+        if self
+            .upgrades
+            .activated_at(block_height)
+            .any(|u| u.name == STAKE_CONFIG)
+        {
+            session = migration::migrate_stake_contract(session)?;
+        }
         let _: CallReceipt<()> = session
             .call(STAKE_CONTRACT, "before_state_transition", &(), u64::MAX)
             .expect("before_state_transition to success");
@@ -553,17 +805,91 @@ impl Rusk {
         Ok(())
     }
 
-    pub(crate) fn block_gas_limit(&self) -> u64 {
-        self.block_gas_limit
+    /// Writes the execution artifacts of the accepted blocks with `writer`.
+    pub fn with_artifacts(mut self, writer: ArtifactsWriter) -> Self {
+        self.artifacts = Some(Arc::new(writer));
+        self
+    }
+
+    /// Sets the number of accepted states kept beyond the non-final ones.
+    pub fn set_commit_retention(&self, retention: usize) {
+        self.commit_gc.set_retention(retention);
     }
+
+    /// Deletes the commits no longer reachable from the tip, the base, the
+    /// retained states or the verified candidates, returning how many were
+    /// deleted.
+    pub fn collect_commits(&self) -> usize {
+        let tip = *self.tip.read();
+        let mut pinned = self.verified_blocks.commits();
+        pinned.extend([tip.current, tip.base]);
+        let unreachable =
+            self.commit_gc.unreachable(self.vm.commits(), &pinned);
+
+        let mut deleted = 0;
+        for commit in unreachable {
+            match self.vm.delete_commit(commit) {
+                Ok(()) => deleted += 1,
+                Err(e) => {
+                    warn!("Cannot delete commit {}: {e}", hex::encode(commit))
+                }
+            }
+        }
+        deleted
+    }
+
+    /// Sets the gas limit of feeder queries, applying to the queries started
+    /// afterwards.
+    pub fn set_feeder_gas_limit(&self, feeder_gas_limit: u64) {
+        self.feeder_gas_limit
+            .store(feeder_gas_limit, Ordering::Relaxed);
+    }
+}
+
+/// State resulting from the transactions of an accepted block.
+enum BlockState {
+    /// Committed when the block was verified
+    Committed([u8; 32]),
+    /// Executed at acceptance, to be committed
+    Executed(Session),
 }
 
 #[allow(clippy::too_many_arguments)]
+/// Returns the BLAKE2b-256 hash of the nullifiers in the session, in
+/// ascending order.
+fn nullifiers_digest(
+    session: &mut Session,
+    gas_limit: u64,
+) -> Result<[u8; 32]> {
+    let (sender, nullifiers) = mpsc::channel();
+    session.feeder_call::<_, ()>(
+        TRANSFER_CONTRACT,
+        "sync_nullifiers",
+        &(0u64, 0u64),
+        gas_limit,
+        sender,
+    )?;
+
+    let mut digest = blake2b_simd::Params::new().hash_length(32).to_state();
+    for bytes in nullifiers.try_iter() {
+        let nullifier = rkyv::from_bytes::<BlsScalar>(&bytes).map_err(|e| {
+            Error::Other(format!("invalid nullifier fed: {e}").into())
+        })?;
+        digest.update(&nullifier.to_bytes());
+    }
+
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(digest.finalize().as_bytes());
+    Ok(hash)
+}
+
 fn accept(
     session: Session,
     block_height: u64,
     block_hash: Hash,
     block_gas_limit: u64,
+    block_space_reservation: &BlockSpaceReservation,
+    upgrades: &UpgradeSchedule,
     generator: &BlsPublicKey,
     txs: &[Transaction],
     slashing: Vec<Slash>,
@@ -580,6 +906,8 @@ fn accept(
     let mut session = session;
 
     let mut block_gas_left = block_gas_limit;
+    let mut block_space =
+        BlockSpace::new(block_space_reservation, block_gas_limit);
 
     let mut spent_txs = Vec::with_capacity(txs.len());
     let mut dusk_spent = 0;
@@ -590,6 +918,14 @@ fn accept(
     for unspent_tx in txs {
         let tx = &unspent_tx.inner;
         let tx_id = unspent_tx.id();
+        if let Some(expiry_height) = tx.expiry_height() {
+            if !upgrades.is_active(TX_EXPIRY, block_height) {
+                return Err(Error::ExpiryNotActive);
+            }
+            if block_height > expiry_height {
+                return Err(Error::TransactionExpired(expiry_height));
+            }
+        }
         let receipt = execute(
             &mut session,
             tx,
@@ -599,6 +935,7 @@ fn accept(
         )?;
 
         event_bloom.add_events(&receipt.events);
+        let call_graph = call_graph(&receipt.call_tree);
 
         let tx_events: Vec<_> = receipt
             .events
@@ -617,6 +954,7 @@ fn accept(
         block_gas_left = block_gas_left
             .checked_sub(gas_spent)
             .ok_or(Error::OutOfGas)?;
+        block_space.include(unspent_tx, gas_spent)?;
 
         spent_txs.push(SpentTransaction {
             inner: unspent_tx.clone(),
@@ -624,6 +962,7 @@ fn accept(
             block_height,
             // We're currently ignoring the result of successful calls
             err: receipt.data.err().map(|e| format!("{e}")),
+            call_graph,
         });
     }
 
@@ -800,3 +1139,32 @@ fn slash(session: &mut Session, slash: Vec<Slash>) -> Result<Vec<Event>> {
     }
     Ok(events)
 }
+
+/// Reads the protocol upgrades declared by the genesis of the state in `dir`.
+///
+/// A state without upgrades declares none.
+fn load_upgrades(dir: &Path) -> Result<UpgradeSchedule> {
+    #[derive(Deserialize)]
+    struct GenesisUpgrades {
+        #[serde(default)]
+        upgrade: Vec<Upgrade>,
+    }
+
+    let path = to_rusk_upgrades_path(dir);
+    let upgrades = match fs::read_to_string(&path) {
+        Ok(toml) => {
+            let upgrades: GenesisUpgrades =
+                toml::from_str(&toml).map_err(|e| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Invalid upgrades in {path:?}: {e}"),
+                    )
+                })?;
+            upgrades.upgrade
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => vec![],
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(UpgradeSchedule::new(upgrades))
+}
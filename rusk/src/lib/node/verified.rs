@@ -0,0 +1,124 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::collections::VecDeque;
+
+use dusk_consensus::operations::VerificationOutput;
+use metrics::counter;
+use node_data::events::contract::ContractTxEvent;
+use node_data::ledger::{Hash, SpentTransaction, Transaction};
+use parking_lot::Mutex;
+
+/// Number of verified candidates kept, enough for the iterations of a round
+const MAX_VERIFIED_BLOCKS: usize = 4;
+
+/// Result of the verification of a candidate block, with the commit of the
+/// state it results in.
+pub(crate) struct VerifiedBlock {
+    pub prev_commit: [u8; 32],
+    pub block_hash: Hash,
+    pub tx_ids: Vec<[u8; 32]>,
+    pub spent_txs: Vec<SpentTransaction>,
+    pub verification_output: VerificationOutput,
+    pub commit: [u8; 32],
+    pub events: Vec<ContractTxEvent>,
+}
+
+/// Candidates verified during the Validation step, so that the state of the
+/// one reaching a quorum is taken at acceptance rather than executed again.
+///
+/// Only the results are kept: the states of the candidates are committed
+/// once verified, and the commits not accepted are deleted by the commit
+/// collection once dropped from the cache.
+#[derive(Default)]
+pub(crate) struct VerifiedBlocks {
+    blocks: Mutex<VecDeque<VerifiedBlock>>,
+}
+
+impl VerifiedBlocks {
+    pub fn insert(&self, block: VerifiedBlock) {
+        let mut blocks = self.blocks.lock();
+        blocks.retain(|b| b.block_hash != block.block_hash);
+        if blocks.len() == MAX_VERIFIED_BLOCKS {
+            blocks.pop_front();
+        }
+        blocks.push_back(block);
+    }
+
+    /// Takes the verification of the block, if it was executed on top of
+    /// `prev_commit` with the same transactions.
+    pub fn take(
+        &self,
+        prev_commit: [u8; 32],
+        block_hash: Hash,
+        txs: &[Transaction],
+    ) -> Option<VerifiedBlock> {
+        let mut blocks = self.blocks.lock();
+        let pos = blocks.iter().position(|b| {
+            b.block_hash == block_hash
+                && b.prev_commit == prev_commit
+                && b.tx_ids.iter().copied().eq(txs.iter().map(|t| t.id()))
+        });
+        match pos.and_then(|pos| blocks.remove(pos)) {
+            Some(block) => {
+                counter!("rusk_verified_block_hit").increment(1);
+                Some(block)
+            }
+            None => {
+                counter!("rusk_verified_block_miss").increment(1);
+                None
+            }
+        }
+    }
+
+    /// Returns the commits of the verified candidates, to be kept until they
+    /// are accepted or dropped.
+    pub fn commits(&self) -> Vec<[u8; 32]> {
+        self.blocks.lock().iter().map(|b| b.commit).collect()
+    }
+
+    /// Drops the verified candidates, as they are executed on top of a
+    /// state that is no longer the tip.
+    pub fn clear(&self) {
+        self.blocks.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn verified(n: u8) -> VerifiedBlock {
+        VerifiedBlock {
+            prev_commit: [0; 32],
+            block_hash: [n; 32],
+            tx_ids: vec![],
+            spent_txs: vec![],
+            verification_output: VerificationOutput::default(),
+            commit: [n; 32],
+            events: vec![],
+        }
+    }
+
+    #[test]
+    fn commits_are_kept_until_taken() {
+        let blocks = VerifiedBlocks::default();
+        for n in 1..=(MAX_VERIFIED_BLOCKS as u8 + 1) {
+            blocks.insert(verified(n));
+        }
+        // The oldest candidate is dropped
+        assert_eq!(blocks.commits().len(), MAX_VERIFIED_BLOCKS);
+        assert!(!blocks.commits().contains(&[1; 32]));
+
+        assert!(blocks.take([1; 32], [2; 32], &[]).is_none());
+        let taken = blocks.take([0; 32], [2; 32], &[]).expect("verified");
+        assert_eq!(taken.commit, [2; 32]);
+        assert!(!blocks.commits().contains(&[2; 32]));
+
+        blocks.clear();
+        assert!(blocks.commits().is_empty());
+    }
+}
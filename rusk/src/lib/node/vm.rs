@@ -6,6 +6,8 @@
 
 mod query;
 
+use std::sync::Arc;
+
 use dusk_consensus::errors::VstError;
 use node_data::events::contract::ContractEvent;
 use tracing::{debug, info};
@@ -15,9 +17,11 @@ use dusk_consensus::operations::{CallParams, VerificationOutput, Voter};
 use dusk_consensus::user::provisioners::Provisioners;
 use dusk_consensus::user::stake::Stake;
 use dusk_core::{
-    signatures::bls::PublicKey as BlsPublicKey, stake::StakeData,
+    signatures::bls::PublicKey as BlsPublicKey,
+    stake::{StakeConfig, StakeData},
     transfer::Transaction as ProtocolTransaction,
 };
+use node::chain::{BlockSpaceReservation, GasLimitRule, UpgradeSchedule};
 use node::vm::{PreverificationResult, VMExecution};
 use node_data::bls::PublicKey;
 use node_data::ledger::{Block, Slash, SpentTransaction, Transaction};
@@ -114,6 +118,8 @@ impl VMExecution for Rusk {
             )
             .map_err(|inner| anyhow::anyhow!("Cannot accept txs: {inner}!!"))?;
 
+        self.contract_stats.record(blk.header().timestamp, &txs);
+
         Ok((txs, verification_output, stake_events))
     }
 
@@ -121,6 +127,7 @@ impl VMExecution for Rusk {
         self.query_session(Some(commit))
             .map_err(|e| anyhow::anyhow!("Cannot open session {e}"))?;
         self.set_current_commit(commit);
+        self.commit_gc.accepted(commit);
         Ok(())
     }
 
@@ -173,10 +180,35 @@ impl VMExecution for Rusk {
                     anyhow::anyhow!("Cannot check account: {e}")
                 })?;
 
-                let max_value = tx
+                let gas_value = tx
                     .gas_limit()
                     .checked_mul(tx.gas_price())
-                    .and_then(|v| v.checked_add(tx.value()))
+                    .ok_or(anyhow::anyhow!("Value spent will overflow"))?;
+
+                // The gas of a sponsored transaction is paid by the sponsor
+                let sender_gas_value = match tx.sponsor() {
+                    Some(sponsor) => {
+                        if tx.refund_address() != &sponsor.account {
+                            return Err(anyhow::anyhow!(
+                                "Refund address must be the sponsor"
+                            ));
+                        }
+                        let sponsor_data =
+                            self.account(&sponsor.account).map_err(|e| {
+                                anyhow::anyhow!("Cannot check sponsor: {e}")
+                            })?;
+                        if gas_value > sponsor_data.balance {
+                            return Err(anyhow::anyhow!(
+                                "Gas spent larger than sponsor holds"
+                            ));
+                        }
+                        0
+                    }
+                    None => gas_value,
+                };
+
+                let max_value = sender_gas_value
+                    .checked_add(tx.value())
                     .and_then(|v| v.checked_add(tx.deposit()))
                     .ok_or(anyhow::anyhow!("Value spent will overflow"))?;
 
@@ -240,6 +272,11 @@ impl VMExecution for Rusk {
         Ok(stake)
     }
 
+    fn get_stake_config(&self) -> anyhow::Result<StakeConfig> {
+        self.stake_config()
+            .map_err(|e| anyhow::anyhow!("Cannot get stake config {e}"))
+    }
+
     fn get_state_root(&self) -> anyhow::Result<[u8; 32]> {
         Ok(self.state_root())
     }
@@ -264,8 +301,19 @@ impl VMExecution for Rusk {
         Ok(state_hash)
     }
 
-    fn get_block_gas_limit(&self) -> u64 {
-        self.block_gas_limit()
+    fn gas_limit_rule(&self) -> GasLimitRule {
+        self.gas_limit_rule
+    }
+
+    fn upgrades(&self) -> Arc<UpgradeSchedule> {
+        self.upgrades.clone()
+    }
+
+    fn block_space_reservation(
+        &self,
+        block_height: u64,
+    ) -> BlockSpaceReservation {
+        Rusk::block_space_reservation(self, block_height)
     }
 
     fn gas_per_deploy_byte(&self) -> u64 {
@@ -306,7 +354,11 @@ impl Rusk {
             .map(|(pk, stake)| {
                 (PublicKey::new(pk.account), Self::to_stake(stake))
             });
+        let config = self
+            .stake_config_at(base_commit)
+            .map_err(|e| anyhow::anyhow!("Cannot get stake config {e}"))?;
         let mut ret = Provisioners::empty();
+        ret.set_minimum_stake(config.minimum_stake);
         for (pubkey_bls, stake) in provisioners {
             ret.add_member_with_stake(pubkey_bls, stake);
         }
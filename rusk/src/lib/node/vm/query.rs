@@ -7,6 +7,7 @@
 use crate::node::Rusk;
 use crate::Result;
 
+use std::sync::atomic::Ordering;
 use std::sync::mpsc;
 
 use bytecheck::CheckBytes;
@@ -107,7 +108,7 @@ impl Rusk {
             contract_id,
             call_name,
             call_arg,
-            self.feeder_gas_limit,
+            self.feeder_gas_limit.load(Ordering::Relaxed),
             feeder,
         )?;
 
@@ -132,7 +133,7 @@ impl Rusk {
             contract_id,
             call_name.as_ref(),
             call_arg,
-            self.feeder_gas_limit,
+            self.feeder_gas_limit.load(Ordering::Relaxed),
             feeder,
         )?;
 
@@ -56,13 +56,23 @@ pub fn verify_proof(tx: &PhoenixTransaction) -> Result<bool> {
     ))
 }
 
-/// Verifies the signature of the incoming transaction.
+/// Verifies the signature of the incoming transaction, and the one of its
+/// sponsor if the transaction is sponsored.
 pub fn verify_signature(tx: &MoonlightTransaction) -> Result<bool> {
-    Ok(host_queries::verify_bls(
-        tx.signature_message(),
-        *tx.sender(),
-        *tx.signature(),
-    ))
+    let sponsor_valid = tx.sponsor().map_or(true, |sponsor| {
+        host_queries::verify_bls(
+            tx.sponsor_signature_message(),
+            sponsor.account,
+            sponsor.signature,
+        )
+    });
+
+    Ok(sponsor_valid
+        && host_queries::verify_bls(
+            tx.signature_message(),
+            *tx.sender(),
+            *tx.signature(),
+        ))
 }
 
 fn fetch_verifier(circuit_name: &str) -> Vec<u8> {
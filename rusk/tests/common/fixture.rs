@@ -0,0 +1,215 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Declarative builder of initial states, for the tests that would otherwise
+//! need a snapshot file of their own.
+//!
+//! The states built are deterministic: the same builder always yields the
+//! same state root.
+
+use std::path::Path;
+
+use dusk_bytes::Serializable;
+use dusk_core::abi::ContractId;
+use dusk_core::signatures::bls::{
+    PublicKey as BlsPublicKey, SecretKey as BlsSecretKey,
+};
+use dusk_core::transfer::phoenix::PublicKey as PhoenixPublicKey;
+use dusk_vm::{ContractData, Session};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rusk::{Result, Rusk};
+use rusk_recovery_tools::state::{self, Snapshot};
+use test_wallet::{derive_bls_sk, derive_phoenix_pk};
+use toml::{Table, Value};
+
+use crate::common::state::{open_state, CHAIN_ID};
+use crate::common::wallet::TEST_SEED;
+
+/// Points spent deploying each of the contracts
+const POINT_LIMIT: u64 = 0x10000000;
+
+/// Seed the keys of the provisioners are generated from
+const PROVISIONERS_SEED: u64 = 0xf1;
+
+type Deploy = Box<dyn FnOnce(&mut Session)>;
+
+/// Builder of an initial state, funding accounts, staking provisioners and
+/// deploying contracts in the genesis.
+pub struct StateBuilder {
+    phoenix: Vec<(PhoenixPublicKey, Vec<u64>)>,
+    moonlight: Vec<(BlsPublicKey, u64)>,
+    stakes: Vec<(BlsPublicKey, u64, u64)>,
+    deploys: Vec<Deploy>,
+    block_gas_limit: u64,
+    chain_id: u8,
+}
+
+#[allow(dead_code)]
+impl StateBuilder {
+    pub fn new(block_gas_limit: u64) -> Self {
+        Self {
+            phoenix: vec![],
+            moonlight: vec![],
+            stakes: vec![],
+            deploys: vec![],
+            block_gas_limit,
+            chain_id: CHAIN_ID,
+        }
+    }
+
+    pub fn chain_id(mut self, chain_id: u8) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    /// Mints a Phoenix note of each of the `notes` values to `pk`.
+    pub fn phoenix(mut self, pk: PhoenixPublicKey, notes: &[u64]) -> Self {
+        self.phoenix.push((pk, notes.to_vec()));
+        self
+    }
+
+    /// Funds the Moonlight account of `pk` with `balance`.
+    pub fn moonlight(mut self, pk: BlsPublicKey, balance: u64) -> Self {
+        self.moonlight.push((pk, balance));
+        self
+    }
+
+    /// Mints a Phoenix note of each of the `notes` values to the profile
+    /// `index` of the test wallet.
+    pub fn wallet_phoenix(self, index: u8, notes: &[u64]) -> Self {
+        let pk = derive_phoenix_pk(&TEST_SEED, index);
+        self.phoenix(pk, notes)
+    }
+
+    /// Funds the Moonlight account of the profile `index` of the test wallet
+    /// with `balance`.
+    pub fn wallet_moonlight(self, index: u8, balance: u64) -> Self {
+        let pk = BlsPublicKey::from(&derive_bls_sk(&TEST_SEED, index));
+        self.moonlight(pk, balance)
+    }
+
+    /// Stakes `amount` for `pk`, eligible from the genesis, with `reward`
+    /// already accumulated.
+    pub fn stake(mut self, pk: BlsPublicKey, amount: u64, reward: u64) -> Self {
+        self.stakes.push((pk, amount, reward));
+        self
+    }
+
+    /// Stakes `amount` for `count` provisioners, whose secret keys are
+    /// returned by [`provisioner_sk`].
+    pub fn provisioners(mut self, count: usize, amount: u64) -> Self {
+        for idx in 0..count {
+            let pk = BlsPublicKey::from(&provisioner_sk(idx));
+            self = self.stake(pk, amount, 0);
+        }
+        self
+    }
+
+    /// Deploys `bytecode` as `contract_id`, owned by `owner`.
+    pub fn contract(
+        self,
+        bytecode: &'static [u8],
+        owner: [u8; 32],
+        contract_id: ContractId,
+    ) -> Self {
+        self.deploy(move |session| {
+            session
+                .deploy(
+                    bytecode,
+                    ContractData::builder()
+                        .owner(owner)
+                        .contract_id(contract_id),
+                    POINT_LIMIT,
+                )
+                .expect("Deploying the contract should succeed");
+        })
+    }
+
+    /// Runs `deploy` on the genesis session, for contracts needing an init
+    /// argument or further calls.
+    pub fn deploy<F>(mut self, deploy: F) -> Self
+    where
+        F: FnOnce(&mut Session) + 'static,
+    {
+        self.deploys.push(Box::new(deploy));
+        self
+    }
+
+    /// Returns the snapshot of the balances and stakes of the state.
+    pub fn snapshot(&self) -> Snapshot {
+        let phoenix =
+            self.phoenix.iter().enumerate().map(|(idx, (pk, notes))| {
+                let mut entry = Table::new();
+                entry.insert("address".into(), address(pk));
+                // Set the seed of the notes so that the state is deterministic
+                entry.insert("seed".into(), integer(idx));
+                let notes = notes.iter().map(|v| integer(*v));
+                entry.insert("notes".into(), Value::Array(notes.collect()));
+                Value::Table(entry)
+            });
+
+        let moonlight = self.moonlight.iter().map(|(pk, balance)| {
+            let mut entry = Table::new();
+            entry.insert("address".into(), address(pk));
+            entry.insert("balance".into(), integer(*balance));
+            Value::Table(entry)
+        });
+
+        let stakes = self.stakes.iter().map(|(pk, amount, reward)| {
+            let mut entry = Table::new();
+            entry.insert("address".into(), address(pk));
+            entry.insert("amount".into(), integer(*amount));
+            entry.insert("reward".into(), integer(*reward));
+            Value::Table(entry)
+        });
+
+        let mut snapshot = Table::new();
+        snapshot.insert("phoenix_balance".into(), phoenix.collect());
+        snapshot.insert("moonlight_account".into(), moonlight.collect());
+        snapshot.insert("stake".into(), stakes.collect());
+
+        Value::Table(snapshot)
+            .try_into()
+            .expect("The snapshot should be valid")
+    }
+
+    /// Builds the state in the given directory.
+    pub fn build<P: AsRef<Path>>(self, dir: P) -> Result<Rusk> {
+        let dir = dir.as_ref();
+        let snapshot = self.snapshot();
+
+        let deploys = self.deploys;
+        let (_vm, commit_id) = state::deploy(dir, &snapshot, |session| {
+            deploys.into_iter().for_each(|deploy| deploy(session))
+        })
+        .expect("Building the initial state should succeed");
+
+        open_state(dir, commit_id, self.block_gas_limit, self.chain_id)
+    }
+}
+
+/// Returns the secret key of the provisioner `idx` staked by
+/// [`StateBuilder::provisioners`].
+#[allow(dead_code)]
+pub fn provisioner_sk(idx: usize) -> BlsSecretKey {
+    let mut rng = StdRng::seed_from_u64(PROVISIONERS_SEED + idx as u64);
+    BlsSecretKey::random(&mut rng)
+}
+
+/// Returns `value` as a TOML integer, which is signed.
+fn integer<T>(value: T) -> Value
+where
+    i64: TryFrom<T>,
+{
+    let value = i64::try_from(value)
+        .unwrap_or_else(|_| panic!("The value should fit in a TOML integer"));
+    Value::Integer(value)
+}
+
+fn address<const N: usize, T: Serializable<N>>(key: &T) -> Value {
+    Value::String(bs58::encode(key.to_bytes()).into_string())
+}
@@ -4,9 +4,13 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
-use std::{path::Path, usize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::{fs, io, usize};
 
 use dusk_bytes::Serializable;
+use node::chain::{BlockSpaceReservation, GasLimitRule};
 use node::vm::VMExecution;
 use rusk::{Result, Rusk};
 use rusk_recovery_tools::state::{self, Snapshot, DUSK_CONSENSUS_KEY};
@@ -26,10 +30,11 @@ use node_data::{
     message::payload::Vote,
 };
 
+use tempfile::TempDir;
 use tokio::sync::broadcast;
 use tracing::info;
 
-const CHAIN_ID: u8 = 0xFA;
+pub const CHAIN_ID: u8 = 0xFA;
 pub const DEFAULT_GAS_PER_DEPLOY_BYTE: u64 = 100;
 pub const DEFAULT_MIN_DEPLOYMENT_GAS_PRICE: u64 = 2000;
 pub const DEFAULT_MIN_GAS_LIMIT: u64 = 75000;
@@ -44,6 +49,23 @@ pub fn new_state<P: AsRef<Path>>(
     new_state_with_chainid(dir, snapshot, block_gas_limit, CHAIN_ID)
 }
 
+/// A genesis state built once, to be copied by the tests using the same
+/// snapshot
+type CachedState = Arc<Mutex<Option<(Arc<TempDir>, [u8; 32])>>>;
+
+/// Genesis states built by this test binary run, by snapshot
+static GENESIS_STATES: LazyLock<Mutex<HashMap<String, CachedState>>> =
+    LazyLock::new(Default::default);
+
+/// Directory the genesis states are built in, under the target directory so
+/// that they are removed by `cargo clean` should the guard not be dropped
+static GENESIS_DIR: LazyLock<TempDir> = LazyLock::new(|| {
+    tempfile::Builder::new()
+        .prefix("genesis-")
+        .tempdir_in(env!("CARGO_TARGET_TMPDIR"))
+        .expect("Creating the genesis directory should succeed")
+});
+
 // Creates a Rusk initial state in the given directory
 pub fn new_state_with_chainid<P: AsRef<Path>>(
     dir: P,
@@ -53,8 +75,66 @@ pub fn new_state_with_chainid<P: AsRef<Path>>(
 ) -> Result<Rusk> {
     let dir = dir.as_ref();
 
-    let (_, commit_id) = state::deploy(dir, snapshot, |_| {})
-        .expect("Deploying initial state should succeed");
+    let commit_id = genesis_state(snapshot, dir)
+        .expect("Building initial state should succeed");
+
+    open_state(dir, commit_id, block_gas_limit, chain_id)
+}
+
+/// Copies the genesis state of `snapshot` into `dir`, building it the first
+/// time the snapshot is used.
+///
+/// The genesis is deterministic, so a copy has the same commit as a state
+/// built from scratch. The files are copied rather than hard linked, since
+/// the VM rewrites some of them in place.
+fn genesis_state(snapshot: &Snapshot, dir: &Path) -> io::Result<[u8; 32]> {
+    let key = toml::to_string(snapshot)
+        .expect("Serializing the snapshot should succeed");
+    let cached = GENESIS_STATES
+        .lock()
+        .expect("Genesis states lock not to be poisoned")
+        .entry(key)
+        .or_default()
+        .clone();
+
+    // Tests using the same snapshot wait for the first one to build it
+    let mut cached = cached.lock().expect("Genesis lock not to be poisoned");
+    let (built_dir, commit_id) = match &*cached {
+        Some(built) => built.clone(),
+        None => {
+            let built_dir = tempfile::tempdir_in(GENESIS_DIR.path())?;
+            let commit_id = state::build_genesis(snapshot, built_dir.path())
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            cached.insert((Arc::new(built_dir), commit_id)).clone()
+        }
+    };
+
+    copy_dir(built_dir.path(), dir)?;
+    Ok(commit_id)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let to = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &to)?;
+        } else {
+            fs::copy(entry.path(), to)?;
+        }
+    }
+    Ok(())
+}
+
+// Instantiates Rusk on the initial state built in the given directory
+pub fn open_state<P: AsRef<Path>>(
+    dir: P,
+    commit_id: [u8; 32],
+    block_gas_limit: u64,
+    chain_id: u8,
+) -> Result<Rusk> {
+    let dir = dir.as_ref();
 
     let (sender, _) = broadcast::channel(10);
 
@@ -66,7 +146,8 @@ pub fn new_state_with_chainid<P: AsRef<Path>>(
         DEFAULT_MIN_DEPLOYMENT_GAS_PRICE,
         DEFAULT_MIN_GAS_LIMIT,
         DEFAULT_MIN_DEPLOY_POINTS,
-        block_gas_limit,
+        GasLimitRule::fixed(block_gas_limit),
+        BlockSpaceReservation::default(),
         u64::MAX,
         sender,
     )
@@ -150,6 +231,7 @@ pub fn generator_procedure(
         voters_pubkey: voters.clone(),
         max_txs_bytes: usize::MAX,
         prev_state_root: prev_root,
+        block_gas_limit,
     };
 
     let (transfer_txs, discarded, execute_output) =
@@ -262,6 +344,7 @@ pub fn generator_procedure2(
         voters_pubkey: voters.clone(),
         max_txs_bytes: usize::MAX,
         prev_state_root: prev_root,
+        block_gas_limit,
     };
 
     let (transfer_txs, discarded, execute_output) =
@@ -25,6 +25,9 @@ use rusk::{Error, Result, Rusk};
 use test_wallet::{self as wallet, Store};
 use tracing::info;
 
+/// Seed of the wallets used in the tests
+pub const TEST_SEED: [u8; 64] = [0; 64];
+
 #[derive(Debug, Clone)]
 pub struct TestStore;
 
@@ -32,7 +35,7 @@ impl Store for TestStore {
     type Error = ();
 
     fn get_seed(&self) -> Result<[u8; 64], Self::Error> {
-        Ok([0; 64])
+        Ok(TEST_SEED)
     }
 }
 
@@ -122,6 +125,11 @@ impl wallet::StateClient for TestStateClient {
         let chain_id = self.rusk.chain_id()?;
         Ok(chain_id)
     }
+
+    fn fetch_minimum_stake(&self) -> Result<u64, Self::Error> {
+        let config = self.rusk.stake_config()?;
+        Ok(config.minimum_stake)
+    }
 }
 
 #[derive(Default, Debug, Clone)]
@@ -13,6 +13,7 @@ use dusk_core::transfer::data::{
     ContractBytecode, ContractDeploy, TransactionData,
 };
 use dusk_vm::{gen_contract_id, ContractData, Error as VMError, VM};
+use node::chain::{BlockSpaceReservation, GasLimitRule};
 use rand::prelude::*;
 use rand::rngs::StdRng;
 use rusk::{Result, Rusk};
@@ -107,7 +108,8 @@ fn initial_state<P: AsRef<Path>>(dir: P, deploy_bob: bool) -> Result<Rusk> {
         DEFAULT_MIN_DEPLOYMENT_GAS_PRICE,
         DEFAULT_MIN_GAS_LIMIT,
         DEFAULT_MIN_DEPLOY_POINTS,
-        BLOCK_GAS_LIMIT,
+        GasLimitRule::fixed(BLOCK_GAS_LIMIT),
+        BlockSpaceReservation::default(),
         u64::MAX,
         sender,
     )
@@ -157,7 +157,7 @@ pub async fn stake_from_contract() -> Result<()> {
     );
 
     let unstake = wallet
-        .moonlight_unstake(&mut rng, 0, 0, GAS_LIMIT, 1)
+        .moonlight_unstake(&mut rng, 0, 0, None, GAS_LIMIT, 1)
         .expect("stake to be successful");
 
     execute_transaction(
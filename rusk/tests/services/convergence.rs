@@ -0,0 +1,48 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::time::Duration;
+
+use crate::common::logger;
+use crate::testbed::Testbed;
+
+const NODES: usize = 4;
+const BLOCKS: u64 = 3;
+
+// Blocks are at least 10 seconds apart, with some slack for the first
+// rounds
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+#[tokio::test(flavor = "multi_thread")]
+pub async fn nodes_converge() -> anyhow::Result<()> {
+    logger();
+
+    let testbed = Testbed::start(NODES).await?;
+    testbed.wait_for_height(BLOCKS, TIMEOUT).await?;
+
+    for height in 1..=BLOCKS {
+        testbed.assert_converged(height).await?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+pub async fn nodes_progress_without_a_provisioner() -> anyhow::Result<()> {
+    logger();
+
+    let mut testbed = Testbed::start(NODES).await?;
+    testbed.wait_for_height(1, TIMEOUT).await?;
+
+    // The remaining provisioners hold enough stake to reach quorum
+    testbed.stop(NODES - 1);
+    let height = testbed.nodes[0].tip().await?.height;
+    testbed.wait_for_height(height + BLOCKS, TIMEOUT).await?;
+
+    testbed.assert_converged(height + BLOCKS).await?;
+
+    Ok(())
+}
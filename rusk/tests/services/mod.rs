@@ -6,6 +6,7 @@
 
 pub mod contract_deployment;
 pub mod contract_stake;
+pub mod convergence;
 pub mod conversion;
 //pub mod deploy;
 pub mod finalization;
@@ -15,5 +16,7 @@ pub mod moonlight_stake;
 pub mod multi_transfer;
 pub mod owner_calls;
 pub mod phoenix_stake;
+pub mod provisioners_diff;
+pub mod state_builder;
 pub mod transfer;
 pub mod unspendable;
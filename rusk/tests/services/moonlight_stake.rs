@@ -35,8 +35,9 @@ fn stake_state<P: AsRef<Path>>(dir: P) -> Result<Rusk> {
 }
 
 /// Stakes an amount Dusk and produces a block with this single transaction,
-/// checking the stake is set successfully. It then proceeds to withdraw the
-/// stake and checking it is correctly withdrawn.
+/// checking the stake is set successfully. It then proceeds to withdraw part
+/// of the stake, down to the minimum stake, then the rest of it, checking it
+/// is correctly withdrawn.
 fn wallet_stake(
     rusk: &Rusk,
     wallet: &wallet::Wallet<TestStore, TestStateClient>,
@@ -84,14 +85,76 @@ fn wallet_stake(
 
     assert_eq!(stake_value, value);
 
-    wallet
+    let staked = wallet
         .get_stake(0)
         .expect("stakeinfo to be found")
         .amount
-        .expect("stake amount to be found");
+        .expect("stake amount to be found")
+        .total_funds();
+
+    let res = wallet.moonlight_unstake(
+        &mut rng,
+        0,
+        0,
+        Some(staked + 1),
+        GAS_LIMIT,
+        GAS_PRICE,
+    );
+    assert!(
+        matches!(res, Err(wallet::Error::NotEnoughStake { .. })),
+        "unstaking more than the stake should fail"
+    );
+
+    let minimum_stake = rusk
+        .stake_config()
+        .expect("stake config to be found")
+        .minimum_stake;
+    let res = wallet.moonlight_unstake(
+        &mut rng,
+        0,
+        0,
+        Some(staked - minimum_stake + 1),
+        GAS_LIMIT,
+        GAS_PRICE,
+    );
+    assert!(
+        matches!(res, Err(wallet::Error::StakeBelowMinimum { .. })),
+        "leaving less than the minimum stake should fail"
+    );
+
+    // Unstake part of the stake, leaving the minimum stake
+    let tx = wallet
+        .moonlight_unstake(
+            &mut rng,
+            0,
+            0,
+            Some(staked - minimum_stake),
+            GAS_LIMIT,
+            GAS_PRICE,
+        )
+        .expect("Failed to unstake partially");
+    let spent_txs = generator_procedure(
+        rusk,
+        &[tx],
+        BLOCK_HEIGHT,
+        BLOCK_GAS_LIMIT,
+        vec![],
+        None,
+    )
+    .expect("generator procedure to succeed");
+    let spent_tx = spent_txs.first().expect("Unstake tx to be included");
+    assert_eq!(spent_tx.err, None, "partial unstake to be successfull");
+
+    let left = wallet
+        .get_stake(0)
+        .expect("stakeinfo to be found")
+        .amount
+        .expect("stake amount to be left")
+        .total_funds();
+    assert_eq!(left, minimum_stake);
 
     let tx = wallet
-        .moonlight_unstake(&mut rng, 0, 0, GAS_LIMIT, GAS_PRICE)
+        .moonlight_unstake(&mut rng, 0, 0, None, GAS_LIMIT, GAS_PRICE)
         .expect("Failed to unstake");
     let spent_txs = generator_procedure(
         rusk,
@@ -20,6 +20,7 @@ use dusk_core::signatures::bls::{
     Signature as BlsSignature,
 };
 use dusk_vm::{gen_contract_id, CallReceipt, ContractData, Session, VM};
+use node::chain::{BlockSpaceReservation, GasLimitRule};
 use rusk::{Error, Result, Rusk};
 use rusk_recovery_tools::state;
 use tempfile::tempdir;
@@ -84,7 +85,8 @@ fn initial_state<P: AsRef<Path>>(
         DEFAULT_MIN_DEPLOYMENT_GAS_PRICE,
         DEFAULT_MIN_GAS_LIMIT,
         DEFAULT_MIN_DEPLOY_POINTS,
-        BLOCK_GAS_LIMIT,
+        GasLimitRule::fixed(BLOCK_GAS_LIMIT),
+        BlockSpaceReservation::default(),
         u64::MAX,
         sender,
     )
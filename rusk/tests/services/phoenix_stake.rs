@@ -106,7 +106,7 @@ fn wallet_stake(
         .expect("stake amount to be found");
 
     let tx = wallet
-        .phoenix_unstake(&mut rng, 0, 0, GAS_LIMIT, GAS_PRICE)
+        .phoenix_unstake(&mut rng, 0, 0, None, GAS_LIMIT, GAS_PRICE)
         .expect("Failed to unstake");
     let spent_txs = generator_procedure(
         rusk,
@@ -0,0 +1,89 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use dusk_core::dusk;
+use rusk::{Result, Rusk};
+use tempfile::tempdir;
+use test_wallet::{self as wallet};
+
+use crate::common::state::{generator_procedure, new_state};
+use crate::common::wallet::{TestStateClient, TestStore};
+use crate::common::*;
+
+const BLOCK_HEIGHT: u64 = 1;
+const BLOCK_GAS_LIMIT: u64 = 100_000_000_000;
+
+// Creates the Rusk initial state for the tests below
+fn slash_state<P: AsRef<Path>>(dir: P) -> Result<Rusk> {
+    let snapshot = toml::from_str(include_str!("../config/slash.toml"))
+        .expect("Cannot deserialize config");
+
+    new_state(dir, &snapshot, BLOCK_GAS_LIMIT)
+}
+
+/// Slashes a provisioner and checks the diff reported for the resulting
+/// state root, then checks a commit that is not retained is an error.
+#[tokio::test(flavor = "multi_thread")]
+pub async fn provisioners_diff() -> Result<()> {
+    // Setup the logger
+    logger();
+
+    let tmp = tempdir().expect("Should be able to create temporary directory");
+    let rusk = slash_state(&tmp)?;
+
+    let cache = Arc::new(RwLock::new(HashMap::new()));
+
+    // Create a wallet
+    let wallet = wallet::Wallet::new(
+        TestStore,
+        TestStateClient {
+            rusk: rusk.clone(),
+            cache,
+        },
+    );
+
+    let to_slash = wallet.account_public_key(0).unwrap();
+    let before_slash = wallet.get_stake(0).unwrap();
+    assert_eq!(
+        before_slash.amount.map(|a| a.value),
+        Some(dusk(20.0)),
+        "The provisioner should start with its genesis stake"
+    );
+
+    for _ in 0..2 {
+        generator_procedure(
+            &rusk,
+            &[],
+            BLOCK_HEIGHT,
+            BLOCK_GAS_LIMIT,
+            vec![to_slash],
+            None,
+        )
+        .expect("to work");
+    }
+
+    let after_slash = wallet.get_stake(0).unwrap();
+    assert_eq!(after_slash.amount.map(|a| a.value), Some(dusk(18.0)));
+
+    let diff = rusk
+        .provisioners_diff(rusk.state_root())
+        .expect("The current state root should be retained");
+    assert_eq!(diff.len(), 1, "Only the slashed provisioner changed");
+
+    let (key, before, after) = diff.first().expect("Something changed");
+    assert_eq!(key, &to_slash);
+    assert_eq!(before.as_ref().and_then(|s| s.amount), before_slash.amount);
+    assert_eq!(after.as_ref().and_then(|s| s.amount), after_slash.amount);
+
+    rusk.provisioners_diff([0xff; 32])
+        .expect_err("A commit that is not retained must be an error");
+
+    Ok(())
+}
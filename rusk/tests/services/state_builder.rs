@@ -0,0 +1,57 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use dusk_core::dusk;
+use dusk_core::signatures::bls::PublicKey as BlsPublicKey;
+use rusk::Result;
+use tempfile::tempdir;
+use test_wallet::derive_bls_sk;
+
+use crate::common::fixture::{provisioner_sk, StateBuilder};
+use crate::common::logger;
+use crate::common::wallet::TEST_SEED;
+
+const BLOCK_GAS_LIMIT: u64 = 100_000_000_000;
+const MOONLIGHT_BALANCE: u64 = dusk(10.0);
+const STAKE: u64 = dusk(1_000.0);
+
+fn builder() -> StateBuilder {
+    StateBuilder::new(BLOCK_GAS_LIMIT)
+        .wallet_phoenix(0, &[dusk(1.0), dusk(2.0)])
+        .wallet_moonlight(1, MOONLIGHT_BALANCE)
+        .provisioners(2, STAKE)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+pub async fn built_state() -> Result<()> {
+    // Setup the logger
+    logger();
+
+    let tmp = tempdir().expect("Should be able to create temporary directory");
+    let rusk = builder().build(&tmp)?;
+
+    let pk = BlsPublicKey::from(&derive_bls_sk(&TEST_SEED, 1));
+    assert_eq!(rusk.account(&pk)?.balance, MOONLIGHT_BALANCE);
+
+    let stakes: Vec<_> = rusk.provisioners(None)?.collect();
+    assert_eq!(stakes.len(), 2);
+    for idx in 0..2 {
+        let pk = BlsPublicKey::from(&provisioner_sk(idx));
+        let (_, stake) = stakes
+            .iter()
+            .find(|(keys, _)| keys.account == pk)
+            .expect("The provisioner should be staked");
+        let amount = stake.amount.expect("The stake should have an amount");
+        assert_eq!(amount.value, STAKE);
+    }
+
+    // The same builder yields the same state
+    let other =
+        tempdir().expect("Should be able to create temporary directory");
+    assert_eq!(builder().build(&other)?.state_root(), rusk.state_root());
+
+    Ok(())
+}
@@ -0,0 +1,271 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! In-process network of full nodes, for tests involving several
+//! provisioners such as sync, reorgs and slashing.
+//!
+//! Every node runs the mempool, chain and databroker services on its own
+//! state and database, sharing the genesis and reaching the others through a
+//! [`Hub`].
+
+pub mod network;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dusk_bytes::Serializable;
+use dusk_core::signatures::bls::{
+    PublicKey as BlsPublicKey, SecretKey as BlsSecretKey,
+};
+use node::chain::ChainSrv;
+use node::database::rocksdb::{Backend, MD_HASH_KEY};
+use node::database::{DatabaseOptions, Ledger, Metadata, DB};
+use node::databroker::DataBrokerSrv;
+use node::mempool::MempoolSrv;
+use node::LongLivedService;
+use node_data::get_current_timestamp;
+use node_data::ledger::Header;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rusk::Rusk;
+use rusk_recovery_tools::state::Snapshot;
+use tempfile::{tempdir, TempDir};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::info;
+
+use self::network::{Hub, LoopbackNetwork};
+use crate::common::state::new_state;
+
+const BLOCK_GAS_LIMIT: u64 = 5 * 1_000_000_000;
+
+/// Stake of every provisioner in the genesis, in LUX
+const GENESIS_STAKE: u64 = 1_000_000_000_000;
+
+const KEYS_PASSWORD: &str = "testbed";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+type TestbedNode = node::Node<LoopbackNetwork, Backend, Rusk>;
+type Service = dyn LongLivedService<LoopbackNetwork, Backend, Rusk>;
+
+/// A full node of the [`Testbed`].
+pub struct TestNode {
+    pub addr: SocketAddr,
+    #[allow(dead_code)]
+    pub pk: BlsPublicKey,
+    pub node: TestbedNode,
+    task: Option<JoinHandle<()>>,
+    _dir: TempDir,
+}
+
+impl TestNode {
+    /// Returns the header of the tip of the node.
+    pub async fn tip(&self) -> anyhow::Result<Header> {
+        let db = self.node.database();
+        let db = db.read().await;
+        db.view(|t| {
+            let hash = t
+                .op_read(MD_HASH_KEY)?
+                .ok_or_else(|| anyhow::anyhow!("No tip"))?;
+            t.block_header(&hash)?
+                .ok_or_else(|| anyhow::anyhow!("Tip header not found"))
+        })
+    }
+
+    /// Returns the hash of the block accepted at `height`, if any.
+    pub async fn block_hash(&self, height: u64) -> anyhow::Result<[u8; 32]> {
+        let db = self.node.database();
+        let db = db.read().await;
+        db.view(|t| t.block_hash_by_height(height))?
+            .ok_or_else(|| anyhow::anyhow!("No block at height {height}"))
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.task.is_some()
+    }
+}
+
+/// A network of `N` full nodes, each a provisioner staked in the genesis.
+pub struct Testbed {
+    pub hub: Arc<Hub>,
+    pub nodes: Vec<TestNode>,
+}
+
+impl Testbed {
+    /// Starts `count` nodes sharing a genesis where each of them is a
+    /// provisioner with its own consensus keys.
+    pub async fn start(count: usize) -> anyhow::Result<Self> {
+        // The consensus keys are loaded with the password in this variable
+        std::env::set_var("DUSK_CONSENSUS_KEYS_PASS", KEYS_PASSWORD);
+
+        let keys: Vec<_> = (0..count)
+            .map(|i| {
+                let mut rng = StdRng::seed_from_u64(0xbed + i as u64);
+                let sk = BlsSecretKey::random(&mut rng);
+                let pk = BlsPublicKey::from(&sk);
+                (sk, pk)
+            })
+            .collect();
+
+        let snapshot = genesis_snapshot(keys.iter().map(|(_, pk)| pk))?;
+        let genesis_timestamp = get_current_timestamp();
+
+        let hub = Arc::new(Hub::default());
+        let mut nodes = Vec::with_capacity(count);
+
+        for (i, (sk, pk)) in keys.into_iter().enumerate() {
+            let addr: SocketAddr =
+                format!("127.0.0.1:{}", 20_000 + i).parse()?;
+            let dir = tempdir()?;
+
+            let keys_path = dir.path().join("consensus.keys");
+            let mut rng = StdRng::seed_from_u64(i as u64);
+            node_data::bls::save_keys(
+                &keys_path,
+                &sk,
+                &pk,
+                KEYS_PASSWORD,
+                &mut rng,
+            )?;
+
+            let rusk = new_state(
+                dir.path().join("state"),
+                &snapshot,
+                BLOCK_GAS_LIMIT,
+            )?;
+            let db = Backend::create_or_open(
+                dir.path().join("chain.db"),
+                DatabaseOptions::default(),
+            );
+            let node = node::Node::new(hub.attach(addr), db, rusk);
+
+            let (event_sender, mut events) = mpsc::channel(1000);
+            tokio::spawn(async move { while events.recv().await.is_some() {} });
+
+            let mut services: Vec<Box<Service>> = vec![
+                Box::new(MempoolSrv::new(
+                    Default::default(),
+                    event_sender.clone(),
+                )),
+                Box::new(ChainSrv::new(
+                    keys_path.display().to_string(),
+                    1000,
+                    event_sender,
+                    genesis_timestamp,
+                )),
+                Box::new(DataBrokerSrv::new(Default::default())),
+            ];
+            node.initialize(&mut services).await?;
+
+            let task = {
+                let node = node.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = node.spawn_all(services).await {
+                        tracing::error!("testbed node {i} failed: {e}");
+                    }
+                })
+            };
+
+            info!("testbed node {i} started at {addr}");
+            nodes.push(TestNode {
+                addr,
+                pk,
+                node,
+                task: Some(task),
+                _dir: dir,
+            });
+        }
+
+        Ok(Self { hub, nodes })
+    }
+
+    #[allow(dead_code)]
+    pub fn addrs(&self) -> Vec<SocketAddr> {
+        self.nodes.iter().map(|n| n.addr).collect()
+    }
+
+    /// Stops the node at `index`, detaching it from the network. Its
+    /// state is kept until the testbed is dropped.
+    pub fn stop(&mut self, index: usize) {
+        let node = &mut self.nodes[index];
+        self.hub.detach(&node.addr);
+        if let Some(task) = node.task.take() {
+            task.abort();
+        }
+    }
+
+    /// Waits for every running node to reach `height`.
+    pub async fn wait_for_height(
+        &self,
+        height: u64,
+        timeout: Duration,
+    ) -> anyhow::Result<()> {
+        let start = Instant::now();
+        loop {
+            let mut reached = true;
+            for node in self.nodes.iter().filter(|n| n.is_running()) {
+                reached &= node.tip().await?.height >= height;
+            }
+            if reached {
+                return Ok(());
+            }
+            if start.elapsed() > timeout {
+                anyhow::bail!("Height {height} not reached in {timeout:?}");
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Asserts every running node accepted the same block at `height`.
+    pub async fn assert_converged(&self, height: u64) -> anyhow::Result<()> {
+        let mut running = self.nodes.iter().filter(|n| n.is_running());
+        let Some(first) = running.next() else {
+            return Ok(());
+        };
+        let expected = first.block_hash(height).await?;
+
+        for node in running {
+            let hash = node.block_hash(height).await?;
+            assert_eq!(
+                hex::encode(hash),
+                hex::encode(expected),
+                "node {} diverged from {} at height {height}",
+                node.addr,
+                first.addr,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Testbed {
+    fn drop(&mut self) {
+        for node in &mut self.nodes {
+            if let Some(task) = node.task.take() {
+                task.abort();
+            }
+        }
+    }
+}
+
+/// Builds a genesis snapshot staking [`GENESIS_STAKE`] for each of the
+/// `provisioners`.
+fn genesis_snapshot<'a>(
+    provisioners: impl Iterator<Item = &'a BlsPublicKey>,
+) -> anyhow::Result<Snapshot> {
+    let stakes: String = provisioners
+        .map(|pk| {
+            let address = bs58::encode(pk.to_bytes()).into_string();
+            format!(
+                "[[stake]]\naddress = '{address}'\namount = {GENESIS_STAKE}\n"
+            )
+        })
+        .collect();
+
+    Ok(toml::from_str(&stakes)?)
+}
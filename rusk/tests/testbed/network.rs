@@ -0,0 +1,258 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use node::{BoxedFilter, Network};
+use node_data::get_current_timestamp;
+use node_data::message::payload::{GetResource, Inv};
+use node_data::message::{AsyncQueue, Message, Metadata};
+use node_data::Serializable;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, error};
+
+/// Number of peers a `flood_request` is sent to, as in Kadcast
+const REDUNDANCY_PEER_COUNT: usize = 8;
+
+/// The inbound side of a node attached to the [`Hub`].
+#[derive(Default)]
+struct Inbox {
+    routes: RwLock<HashMap<u8, AsyncQueue<Message>>>,
+    filters: Mutex<HashMap<u8, BoxedFilter>>,
+}
+
+impl Inbox {
+    async fn deliver(&self, mut msg: Message, src_addr: SocketAddr) {
+        // Every node is reached directly, so messages are never
+        // re-broadcast
+        msg.metadata = Some(Metadata {
+            height: 0,
+            src_addr,
+            ray_id: String::new(),
+        });
+
+        let topic = msg.topic().into();
+
+        if let Some(filter) = self.filters.lock().await.get_mut(&topic) {
+            if let Err(e) = filter.filter(&msg) {
+                debug!("discard message due to {e}");
+                return;
+            }
+        }
+
+        if let Some(queue) = self.routes.read().await.get(&topic) {
+            queue.try_send(msg);
+        }
+    }
+}
+
+/// In-process network connecting the nodes of a [`super::Testbed`].
+///
+/// Every node is linked to every other, unless the links are cut to
+/// partition the network.
+#[derive(Default)]
+pub struct Hub {
+    inboxes: std::sync::RwLock<BTreeMap<SocketAddr, Arc<Inbox>>>,
+    cut: std::sync::RwLock<HashSet<(SocketAddr, SocketAddr)>>,
+}
+
+impl Hub {
+    /// Attaches a new node to the hub, returning its network.
+    pub fn attach(self: &Arc<Self>, addr: SocketAddr) -> LoopbackNetwork {
+        let inbox = Arc::new(Inbox::default());
+        self.inboxes
+            .write()
+            .expect("lock not to be poisoned")
+            .insert(addr, inbox.clone());
+
+        LoopbackNetwork {
+            addr,
+            hub: self.clone(),
+            inbox,
+        }
+    }
+
+    /// Detaches a node, which stops receiving messages.
+    pub fn detach(&self, addr: &SocketAddr) {
+        self.inboxes
+            .write()
+            .expect("lock not to be poisoned")
+            .remove(addr);
+    }
+
+    /// Splits the network in `groups`, cutting the links between nodes of
+    /// different groups.
+    #[allow(dead_code)]
+    pub fn partition(&self, groups: &[&[SocketAddr]]) {
+        let mut cut = self.cut.write().expect("lock not to be poisoned");
+        for (i, group) in groups.iter().enumerate() {
+            for other in &groups[i + 1..] {
+                for a in group.iter() {
+                    for b in other.iter() {
+                        cut.insert((*a, *b));
+                        cut.insert((*b, *a));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Restores all the links cut by [`Hub::partition`].
+    #[allow(dead_code)]
+    pub fn heal(&self) {
+        self.cut.write().expect("lock not to be poisoned").clear();
+    }
+
+    /// Returns the inboxes of the nodes reachable from `src`.
+    fn peers_of(&self, src: &SocketAddr) -> Vec<(SocketAddr, Arc<Inbox>)> {
+        let cut = self.cut.read().expect("lock not to be poisoned");
+        self.inboxes
+            .read()
+            .expect("lock not to be poisoned")
+            .iter()
+            .filter(|(addr, _)| *addr != src && !cut.contains(&(*src, **addr)))
+            .map(|(addr, inbox)| (*addr, inbox.clone()))
+            .collect()
+    }
+
+    fn peer(&self, src: &SocketAddr, dst: &SocketAddr) -> Option<Arc<Inbox>> {
+        if self
+            .cut
+            .read()
+            .expect("lock not to be poisoned")
+            .contains(&(*src, *dst))
+        {
+            return None;
+        }
+        self.inboxes
+            .read()
+            .expect("lock not to be poisoned")
+            .get(dst)
+            .cloned()
+    }
+}
+
+/// A [`Network`] delivering messages through a [`Hub`].
+///
+/// Messages are encoded and decoded as they would be on the wire.
+pub struct LoopbackNetwork {
+    addr: SocketAddr,
+    hub: Arc<Hub>,
+    inbox: Arc<Inbox>,
+}
+
+impl LoopbackNetwork {
+    fn encode(msg: &Message) -> anyhow::Result<Vec<u8>> {
+        let mut encoded = vec![];
+        msg.write(&mut encoded).map_err(|err| {
+            error!("could not encode message {msg:?}: {err}");
+            anyhow::anyhow!("failed to encode: {err}")
+        })?;
+        Ok(encoded)
+    }
+
+    async fn send(
+        &self,
+        encoded: &[u8],
+        peers: impl IntoIterator<Item = Arc<Inbox>>,
+    ) -> anyhow::Result<()> {
+        for inbox in peers {
+            let msg = Message::read(&mut &encoded[..])?;
+            inbox.deliver(msg, self.addr).await;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Network for LoopbackNetwork {
+    async fn broadcast(&self, msg: &Message) -> anyhow::Result<()> {
+        if let Some(Metadata { height: 0, .. }) = msg.metadata {
+            return Ok(());
+        }
+
+        let encoded = Self::encode(msg)?;
+        let peers = self.hub.peers_of(&self.addr);
+        self.send(&encoded, peers.into_iter().map(|(_, inbox)| inbox))
+            .await
+    }
+
+    async fn flood_request(
+        &self,
+        msg_inv: &Inv,
+        ttl_as_sec: Option<u64>,
+        hops_limit: u16,
+    ) -> anyhow::Result<()> {
+        let ttl_as_sec = ttl_as_sec
+            .map_or_else(|| u64::MAX, |v| get_current_timestamp() + v);
+
+        let msg = GetResource::new(
+            msg_inv.clone(),
+            Some(self.addr),
+            ttl_as_sec,
+            hops_limit,
+        );
+        self.send_to_alive_peers(msg.into(), REDUNDANCY_PEER_COUNT)
+            .await
+    }
+
+    async fn send_to_peer(
+        &self,
+        msg: Message,
+        peer_addr: SocketAddr,
+    ) -> anyhow::Result<()> {
+        let encoded = Self::encode(&msg)?;
+        let peer = self.hub.peer(&self.addr, &peer_addr);
+        self.send(&encoded, peer).await
+    }
+
+    async fn send_to_alive_peers(
+        &self,
+        msg: Message,
+        amount: usize,
+    ) -> anyhow::Result<()> {
+        let encoded = Self::encode(&msg)?;
+        let peers = self.hub.peers_of(&self.addr);
+        self.send(
+            &encoded,
+            peers.into_iter().take(amount).map(|(_, inbox)| inbox),
+        )
+        .await
+    }
+
+    async fn add_route(
+        &mut self,
+        msg_type: u8,
+        queue: AsyncQueue<Message>,
+    ) -> anyhow::Result<()> {
+        self.inbox.routes.write().await.insert(msg_type, queue);
+        Ok(())
+    }
+
+    async fn add_filter(
+        &mut self,
+        msg_type: u8,
+        filter: BoxedFilter,
+    ) -> anyhow::Result<()> {
+        self.inbox.filters.lock().await.insert(msg_type, filter);
+        Ok(())
+    }
+
+    fn get_info(&self) -> anyhow::Result<String> {
+        Ok(format!("loopback {}", self.addr))
+    }
+
+    fn public_addr(&self) -> &SocketAddr {
+        &self.addr
+    }
+
+    async fn alive_nodes_count(&self) -> usize {
+        self.hub.peers_of(&self.addr).len()
+    }
+}
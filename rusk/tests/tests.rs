@@ -7,3 +7,4 @@
 #![feature(lazy_cell)]
 mod common;
 mod services;
+mod testbed;
@@ -6,9 +6,8 @@
 
 use blake2b_simd::Params;
 use dusk_core::abi::{ContractError, ContractId, CONTRACT_ID_BYTES};
-use dusk_core::transfer::{
-    data::ContractBytecode, Transaction, TRANSFER_CONTRACT,
-};
+use dusk_core::transfer::data::{ContractBytecode, ContractDeploy};
+use dusk_core::transfer::{CompleteUpgrade, Transaction, TRANSFER_CONTRACT};
 use piecrust::{CallReceipt, Error, Session};
 
 /// Executes a transaction in the provided session.
@@ -40,6 +39,9 @@ use piecrust::{CallReceipt, Error, Session};
 ///    - corrupted bytecode
 ///    If deployment execution fails, the entire gas limit is consumed and error
 ///    is returned.
+///    If the deployed bytecode was approved as the upgrade of a contract by
+///    its owner, the transfer contract records the new contract as its
+///    successor.
 ///
 /// 4. Call the "refund" function on the transfer contract with unlimited gas.
 ///    The amount charged depends on the gas spent by the transaction, and the
@@ -175,7 +177,15 @@ fn contract_deploy(
                 );
                 match result {
                     // Should the gas spent by the INIT method charged too?
-                    Ok(_) => receipt.gas_spent += deploy_charge,
+                    Ok(contract) => {
+                        receipt.gas_spent += deploy_charge;
+                        if let Err(err) =
+                            complete_upgrade(session, deploy, contract, receipt)
+                        {
+                            let msg = format!("failed upgrade: {err:?}");
+                            receipt.data = Err(ContractError::Panic(msg))
+                        }
+                    }
                     Err(err) => {
                         let msg = format!("failed deployment: {err:?}");
                         receipt.data = Err(ContractError::Panic(msg))
@@ -186,6 +196,33 @@ fn contract_deploy(
     }
 }
 
+// Completes the upgrade of the contract that approved the bytecode of the
+// newly deployed contract, if any. The transfer contract guarantees this call
+// never panics, but the session may still fail to execute it.
+fn complete_upgrade(
+    session: &mut Session,
+    deploy: &ContractDeploy,
+    contract: ContractId,
+    receipt: &mut CallReceipt<Result<Vec<u8>, ContractError>>,
+) -> Result<(), Error> {
+    let upgrade = CompleteUpgrade {
+        contract,
+        bytecode_hash: deploy.bytecode.hash,
+        owner: deploy.owner.clone(),
+    };
+
+    let upgrade_receipt = session.call::<_, Option<ContractId>>(
+        TRANSFER_CONTRACT,
+        "complete_upgrade",
+        &upgrade,
+        u64::MAX,
+    )?;
+
+    receipt.events.extend(upgrade_receipt.events);
+
+    Ok(())
+}
+
 // Verifies that the stored contract bytecode hash is correct.
 fn verify_bytecode_hash(bytecode: &ContractBytecode) -> bool {
     let computed: [u8; 32] = blake3::hash(bytecode.bytes.as_slice()).into();
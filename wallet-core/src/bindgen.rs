@@ -0,0 +1,381 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Bindings of the wallet functionalities generated by `wasm-bindgen`, for
+//! web wallets to scan the notes, compute the balances and build the
+//! transactions with the same logic as the CLI wallet.
+//!
+//! Notes, openings and note lists are exchanged in their `rkyv` archived
+//! form, as served by the node, and keys are derived from the seed given to
+//! each call. The bindings are exported under camel case names, distinct
+//! from the ones of the raw FFI, which remains available.
+
+// The arguments are owned by the bindings generated for JavaScript
+#![allow(clippy::needless_pass_by_value)]
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use bytecheck::CheckBytes;
+use dusk_bytes::{DeserializableSlice, Serializable};
+use dusk_core::signatures::bls::PublicKey as BlsPublicKey;
+use dusk_core::transfer::data::TransactionData;
+use dusk_core::transfer::moonlight::Transaction as MoonlightTransaction;
+use dusk_core::transfer::phoenix::{
+    Note, NoteLeaf, NoteOpening, Prove, PublicKey as PhoenixPublicKey,
+};
+use dusk_core::transfer::{phoenix, Transaction};
+use dusk_core::BlsScalar;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use rkyv::de::deserializers::SharedDeserializeMap;
+use rkyv::validation::validators::DefaultValidator;
+use rkyv::{check_archived_root, to_bytes, AlignedVec, Archive, Deserialize};
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+use crate::keys::{
+    derive_bls_pk, derive_bls_sk, derive_phoenix_pk, derive_phoenix_sk,
+    derive_phoenix_vk,
+};
+use crate::notes::{balance, owned, pick};
+use crate::Seed;
+
+/// The size of the scratch buffer used for archiving the notes.
+const NOTES_BUFFER_SIZE: usize = 96 * 1024;
+
+/// The size of the scratch buffer used for archiving a transaction.
+const TX_BUFFER_SIZE: usize = 4096;
+
+/// The public keys of a profile, in their serialized form.
+#[wasm_bindgen(getter_with_clone)]
+pub struct Profile {
+    /// The Phoenix address of the profile
+    pub address: Vec<u8>,
+    /// The Moonlight account of the profile
+    pub account: Vec<u8>,
+}
+
+/// The balance of the notes of a profile, in Lux.
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub struct Balance {
+    /// The total value of the notes
+    pub value: u64,
+    /// The value that can be spent in a single transaction
+    pub spendable: u64,
+}
+
+/// A Phoenix transaction yet to be proven, along with the circuits to prove.
+#[wasm_bindgen(getter_with_clone)]
+pub struct UnprovenTransaction {
+    /// The archived transaction, to give to [`into_proven`]
+    pub tx: Vec<u8>,
+    /// The circuits to send to a prover
+    pub circuits: Vec<u8>,
+}
+
+/// A transaction ready to be propagated.
+#[wasm_bindgen(getter_with_clone)]
+pub struct ProvenTransaction {
+    /// The serialized transaction
+    pub bytes: Vec<u8>,
+    /// The hash of the transaction, as displayed by the node
+    pub hash: String,
+}
+
+#[derive(Default)]
+struct NoOpProver {
+    circuits: core::cell::RefCell<Vec<u8>>,
+}
+
+impl Prove for NoOpProver {
+    fn prove(&self, circuits: &[u8]) -> Result<Vec<u8>, dusk_core::Error> {
+        *self.circuits.borrow_mut() = circuits.to_vec();
+
+        Ok(circuits.to_vec())
+    }
+}
+
+fn seed(bytes: &[u8]) -> Result<Seed, JsError> {
+    bytes
+        .try_into()
+        .map_err(|_| JsError::new("The seed must be 64 bytes long"))
+}
+
+fn unarchive<T>(bytes: &[u8]) -> Result<T, JsError>
+where
+    T: Archive,
+    for<'a> T::Archived:
+        CheckBytes<DefaultValidator<'a>> + Deserialize<T, SharedDeserializeMap>,
+{
+    // The archived value must be aligned, which a slice coming from
+    // JavaScript is not guaranteed to be
+    let mut aligned = AlignedVec::with_capacity(bytes.len());
+    aligned.extend_from_slice(bytes);
+
+    check_archived_root::<T>(&aligned)
+        .map_err(|_| JsError::new("Invalid archived data"))?
+        .deserialize(&mut SharedDeserializeMap::default())
+        .map_err(|_| JsError::new("Invalid archived data"))
+}
+
+fn hash(scalar: &BlsScalar) -> String {
+    scalar
+        .to_bytes()
+        .iter()
+        .map(|byte| alloc::format!("{byte:02x}"))
+        .collect()
+}
+
+fn memo(memo: Option<Vec<u8>>) -> Option<TransactionData> {
+    memo.map(Into::into)
+}
+
+/// Generates the profile of the given seed at `index`.
+///
+/// # Errors
+/// Fails if the seed is not 64 bytes long.
+#[wasm_bindgen(js_name = generateProfile)]
+pub fn generate_profile(
+    seed_bytes: &[u8],
+    index: u8,
+) -> Result<Profile, JsError> {
+    let seed = seed(seed_bytes)?;
+
+    Ok(Profile {
+        address: derive_phoenix_pk(&seed, index).to_bytes().to_vec(),
+        account: derive_bls_pk(&seed, index).to_bytes().to_vec(),
+    })
+}
+
+/// Filters the archived `notes` owned by the profiles at `indexes`, returning
+/// an archived note list per profile, mapping the notes to their nullifiers.
+///
+/// # Errors
+/// Fails if the seed is not 64 bytes long, or the notes can't be unarchived.
+#[wasm_bindgen(js_name = mapOwned)]
+pub fn map_owned(
+    seed_bytes: &[u8],
+    indexes: &[u8],
+    notes: &[u8],
+) -> Result<Vec<u8>, JsError> {
+    let seed = seed(seed_bytes)?;
+    let notes: Vec<NoteLeaf> = unarchive(notes)?;
+
+    let mut keys: Vec<_> = indexes
+        .iter()
+        .map(|&index| derive_phoenix_sk(&seed, index))
+        .collect();
+    let owned = owned::map(&keys, notes);
+    keys.iter_mut().for_each(Zeroize::zeroize);
+
+    to_bytes::<_, NOTES_BUFFER_SIZE>(&owned)
+        .map(|bytes| bytes.to_vec())
+        .map_err(|_| JsError::new("Cannot archive the owned notes"))
+}
+
+/// Computes the balance of the archived `notes` owned by the profile at
+/// `index`.
+///
+/// # Errors
+/// Fails if the seed is not 64 bytes long, or the notes can't be unarchived.
+#[wasm_bindgen(js_name = phoenixBalance)]
+pub fn phoenix_balance(
+    seed_bytes: &[u8],
+    index: u8,
+    notes: &[u8],
+) -> Result<Balance, JsError> {
+    let vk = derive_phoenix_vk(&seed(seed_bytes)?, index);
+    let notes: Vec<NoteLeaf> = unarchive(notes)?;
+
+    let info = balance::calculate(&vk, notes.iter());
+    Ok(Balance {
+        value: info.value,
+        spendable: info.spendable,
+    })
+}
+
+/// Picks the notes to spend for `value` from an archived note list owned by
+/// the profile at `index`, returning them as an archived note list.
+///
+/// # Errors
+/// Fails if the seed is not 64 bytes long, or the notes can't be unarchived.
+#[wasm_bindgen(js_name = pickNotes)]
+pub fn pick_notes(
+    seed_bytes: &[u8],
+    index: u8,
+    value: u64,
+    notes: &[u8],
+) -> Result<Vec<u8>, JsError> {
+    let vk = derive_phoenix_vk(&seed(seed_bytes)?, index);
+    let notes: owned::NoteList = unarchive(notes)?;
+
+    let picked = pick::notes(&vk, notes, value);
+    to_bytes::<_, NOTES_BUFFER_SIZE>(&picked)
+        .map(|bytes| bytes.to_vec())
+        .map_err(|_| JsError::new("Cannot archive the picked notes"))
+}
+
+/// Builds a Phoenix transfer from the profile at `sender_index`, spending the
+/// archived `inputs` with their archived `openings`, to be proven before
+/// being propagated.
+///
+/// # Errors
+/// Fails if an argument can't be deserialized, or the transaction can't be
+/// built from the inputs.
+#[wasm_bindgen(js_name = phoenixTransaction)]
+#[allow(clippy::too_many_arguments)]
+pub fn phoenix(
+    rng_seed: &[u8],
+    seed_bytes: &[u8],
+    sender_index: u8,
+    receiver: &[u8],
+    inputs: &[u8],
+    openings: &[u8],
+    root: &[u8],
+    transfer_value: u64,
+    obfuscated_transaction: bool,
+    deposit: u64,
+    gas_limit: u64,
+    gas_price: u64,
+    chain_id: u8,
+    data: Option<Vec<u8>>,
+) -> Result<UnprovenTransaction, JsError> {
+    let rng_seed: [u8; 32] = rng_seed
+        .try_into()
+        .map_err(|_| JsError::new("The RNG seed must be 32 bytes long"))?;
+    let mut rng = ChaCha12Rng::from_seed(rng_seed);
+
+    let mut sender_sk = derive_phoenix_sk(&seed(seed_bytes)?, sender_index);
+    let change_pk = PhoenixPublicKey::from(&sender_sk);
+    let receiver_pk = PhoenixPublicKey::from_slice(receiver)
+        .map_err(|_| JsError::new("Invalid receiver address"))?;
+
+    let root: BlsScalar = unarchive(root)?;
+    let openings: Vec<Option<NoteOpening>> = unarchive(openings)?;
+    let notes: Vec<NoteLeaf> = unarchive(inputs)?;
+    let inputs: Vec<(Note, NoteOpening)> = notes
+        .into_iter()
+        .map(|leaf| leaf.note)
+        .zip(openings)
+        .filter_map(|(note, opening)| opening.map(|op| (note, op)))
+        .collect();
+
+    let prover = NoOpProver::default();
+    let tx = phoenix::Transaction::new(
+        &mut rng,
+        &sender_sk,
+        &change_pk,
+        &receiver_pk,
+        inputs,
+        root,
+        transfer_value,
+        obfuscated_transaction,
+        deposit,
+        gas_limit,
+        gas_price,
+        chain_id,
+        memo(data),
+        &prover,
+    );
+    sender_sk.zeroize();
+    let tx = tx.map_err(|e| JsError::new(&alloc::format!("{e:?}")))?;
+
+    let tx = to_bytes::<_, TX_BUFFER_SIZE>(&tx)
+        .map_err(|_| JsError::new("Cannot archive the transaction"))?;
+
+    Ok(UnprovenTransaction {
+        tx: tx.to_vec(),
+        circuits: prover.circuits.into_inner(),
+    })
+}
+
+/// Sets the `proof` of an archived Phoenix transaction built by [`phoenix`].
+///
+/// # Errors
+/// Fails if the transaction can't be unarchived.
+#[wasm_bindgen(js_name = intoProven)]
+pub fn into_proven(
+    tx: &[u8],
+    proof: Vec<u8>,
+) -> Result<ProvenTransaction, JsError> {
+    let mut tx: phoenix::Transaction = unarchive(tx)?;
+    tx.set_proof(proof);
+
+    let hash = hash(&tx.hash());
+    Ok(ProvenTransaction {
+        bytes: Transaction::Phoenix(tx).to_var_bytes(),
+        hash,
+    })
+}
+
+/// Builds and signs a Moonlight transfer from the account of the profile at
+/// `sender_index`. Without `receiver`, only the `deposit` is transferred.
+///
+/// # Errors
+/// Fails if an argument can't be deserialized, or the transaction can't be
+/// built.
+#[wasm_bindgen(js_name = moonlightTransaction)]
+#[allow(clippy::too_many_arguments)]
+pub fn moonlight(
+    seed_bytes: &[u8],
+    sender_index: u8,
+    receiver: Option<Vec<u8>>,
+    transfer_value: u64,
+    deposit: u64,
+    gas_limit: u64,
+    gas_price: u64,
+    nonce: u64,
+    chain_id: u8,
+    data: Option<Vec<u8>>,
+) -> Result<ProvenTransaction, JsError> {
+    let receiver_pk = receiver
+        .map(|receiver| BlsPublicKey::from_slice(&receiver))
+        .transpose()
+        .map_err(|_| JsError::new("Invalid receiver account"))?;
+
+    let mut sender_sk = derive_bls_sk(&seed(seed_bytes)?, sender_index);
+    let tx = MoonlightTransaction::new(
+        &sender_sk,
+        receiver_pk,
+        transfer_value,
+        deposit,
+        gas_limit,
+        gas_price,
+        nonce,
+        chain_id,
+        memo(data),
+    );
+    sender_sk.zeroize();
+    let tx = tx.map_err(|e| JsError::new(&alloc::format!("{e:?}")))?;
+
+    let hash = hash(&tx.hash());
+    Ok(ProvenTransaction {
+        bytes: Transaction::Moonlight(tx).to_var_bytes(),
+        hash,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unarchive_misaligned_bytes() {
+        let values: Vec<u64> = (0..16).collect();
+        let archived = to_bytes::<_, 256>(&values).expect("archived values");
+
+        // Shift the archive by one byte to misalign it
+        let mut shifted = Vec::with_capacity(archived.len() + 1);
+        shifted.push(0);
+        shifted.extend_from_slice(&archived);
+
+        let unarchived: Vec<u64> =
+            unarchive(&shifted[1..]).expect("unarchived values");
+        assert_eq!(unarchived, values);
+    }
+}
@@ -0,0 +1,196 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Tracking of the head of the chain of a node through its chain feed.
+//!
+//! The [`HeadTracker`] holds no connection to the node: a client subscribes
+//! to the `chain/feed` RUES stream of the node from
+//! [`HeadTracker::resume_height`], however it transports it, and applies the
+//! records received to get the changes to the head of the chain.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+/// Hash of a block.
+pub type BlockHash = [u8; 32];
+
+/// Type of the feed records of an accepted block.
+pub const BLOCK_ACCEPTED: &str = "blockAccepted";
+/// Type of the feed records of a finalized block.
+pub const BLOCK_FINALIZED: &str = "blockFinalized";
+/// Type of the feed records of reverted blocks.
+pub const REORG: &str = "reorg";
+/// Type of the feed record ending a feed the node could not keep up with.
+pub const INTERRUPTED: &str = "interrupted";
+
+/// The head of the chain of a node.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Head {
+    /// Height of the tip
+    pub height: u64,
+    /// Hash of the tip
+    pub hash: BlockHash,
+    /// Height of the last finalized block
+    pub finalized_height: u64,
+    /// Hash of the last finalized block
+    pub finalized_hash: BlockHash,
+}
+
+/// A change to the head of the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeadEvent {
+    /// A block became the tip
+    Accepted {
+        /// Height of the block
+        height: u64,
+        /// Hash of the block
+        hash: BlockHash,
+    },
+    /// A block reached finality
+    Finalized {
+        /// Height of the block
+        height: u64,
+        /// Hash of the block
+        hash: BlockHash,
+    },
+    /// The blocks from `height` on were reverted
+    Reorg {
+        /// Height of the first block reverted
+        height: u64,
+    },
+}
+
+/// A record of the chain feed changing the head of the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedRecord {
+    /// A `blockAccepted` record
+    BlockAccepted {
+        /// Height of the block
+        height: u64,
+        /// Hash of the block
+        hash: BlockHash,
+    },
+    /// A `blockFinalized` record
+    BlockFinalized {
+        /// Height of the block
+        height: u64,
+        /// Hash of the block
+        hash: BlockHash,
+    },
+    /// A `reorg` record
+    Reorg {
+        /// Height of the first block reverted
+        height: u64,
+    },
+}
+
+impl FeedRecord {
+    /// Returns the record of the feed of the given type, or `None` if
+    /// records of that type don't change the head of the chain.
+    #[must_use]
+    pub fn new(kind: &str, height: u64, hash: BlockHash) -> Option<Self> {
+        match kind {
+            BLOCK_ACCEPTED => Some(Self::BlockAccepted { height, hash }),
+            BLOCK_FINALIZED => Some(Self::BlockFinalized { height, hash }),
+            REORG => Some(Self::Reorg { height }),
+            _ => None,
+        }
+    }
+}
+
+/// Follows the head of the chain of a node: its tip, its last finalized
+/// block, and the reorganizations of the blocks not final yet.
+#[derive(Debug, Clone)]
+pub struct HeadTracker {
+    head: Head,
+    /// Hashes of the blocks accepted and not final yet, by height
+    pending: BTreeMap<u64, BlockHash>,
+}
+
+impl HeadTracker {
+    /// Starts tracking from the head of the chain reported by the node.
+    #[must_use]
+    pub fn new(head: Head) -> Self {
+        Self {
+            head,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the head of the chain.
+    #[must_use]
+    pub fn head(&self) -> &Head {
+        &self.head
+    }
+
+    /// Returns the height to follow the chain feed from.
+    ///
+    /// The feed is followed from the block after the last finalized one, so
+    /// that the blocks replayed reveal the reorganizations that happened
+    /// while it was not followed.
+    #[must_use]
+    pub fn resume_height(&self) -> u64 {
+        self.head.finalized_height + 1
+    }
+
+    /// Applies a record of the chain feed, returning the changes to the head
+    /// of the chain.
+    pub fn apply(&mut self, record: FeedRecord) -> Vec<HeadEvent> {
+        let head = &mut self.head;
+
+        let mut changes = Vec::new();
+        match record {
+            FeedRecord::BlockAccepted { height, hash } => {
+                let known = self
+                    .pending
+                    .get(&height)
+                    .or((height == head.height).then_some(&head.hash))
+                    .map(|known| *known == hash);
+                match known {
+                    // Replayed after following the feed again
+                    Some(true) => {
+                        self.pending.insert(height, hash);
+                        return changes;
+                    }
+                    Some(false) => {
+                        self.pending.retain(|h, _| *h < height);
+                        changes.push(HeadEvent::Reorg { height });
+                    }
+                    None => {}
+                }
+
+                self.pending.insert(height, hash);
+                if height >= head.height || !changes.is_empty() {
+                    head.height = height;
+                    head.hash = hash;
+                    changes.push(HeadEvent::Accepted { height, hash });
+                }
+            }
+            FeedRecord::BlockFinalized { height, hash } => {
+                if height <= head.finalized_height {
+                    return changes;
+                }
+                self.pending.retain(|h, _| *h > height);
+                head.finalized_height = height;
+                head.finalized_hash = hash;
+                changes.push(HeadEvent::Finalized { height, hash });
+            }
+            FeedRecord::Reorg { height } => {
+                self.pending.retain(|h, _| *h < height);
+                let (tip_height, tip_hash) = match self.pending.last_key_value()
+                {
+                    Some((h, hash)) => (*h, *hash),
+                    None => (head.finalized_height, head.finalized_hash),
+                };
+                head.height = tip_height;
+                head.hash = tip_hash;
+                changes.push(HeadEvent::Reorg { height });
+            }
+        }
+
+        changes
+    }
+}
@@ -24,6 +24,9 @@ extern crate alloc;
 #[macro_use]
 mod ffi;
 
+#[cfg(feature = "wasm-bindgen")]
+pub mod bindgen;
+pub mod head;
 pub mod keys;
 pub mod notes;
 pub mod transaction;
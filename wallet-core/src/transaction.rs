@@ -13,11 +13,17 @@ use dusk_core::abi::ContractId;
 use dusk_core::signatures::bls::{
     PublicKey as BlsPublicKey, SecretKey as BlsSecretKey,
 };
-use dusk_core::stake::{Stake, Withdraw as StakeWithdraw, STAKE_CONTRACT};
+use dusk_core::stake::{
+    Beneficiary, SetBeneficiary, Stake, Withdraw as StakeWithdraw,
+    WithdrawToBeneficiary, STAKE_CONTRACT,
+};
 use dusk_core::transfer::data::{
     ContractBytecode, ContractCall, ContractDeploy, TransactionData,
 };
-use dusk_core::transfer::moonlight::Transaction as MoonlightTransaction;
+use dusk_core::transfer::moonlight::{
+    Fee as MoonlightFee, Payload as MoonlightPayload,
+    Transaction as MoonlightTransaction,
+};
 use dusk_core::transfer::phoenix::{
     Note, NoteOpening, Prove, PublicKey as PhoenixPublicKey,
     SecretKey as PhoenixSecretKey, Transaction as PhoenixTransaction,
@@ -131,6 +137,96 @@ pub fn moonlight(
     .into())
 }
 
+/// Creates a generic Moonlight [`Transaction`] that can only be included in
+/// a block up to `expiry_height`, after which it can never confirm.
+///
+/// # Note
+/// The `moonlight_nonce` is NOT incremented and should be incremented
+/// by the caller of this function.
+///
+/// # Errors
+/// The creation of a transaction is not possible and will error if:
+/// - the Memo provided with `data` is too large
+#[allow(clippy::too_many_arguments)]
+pub fn moonlight_expiring(
+    sender_sk: &BlsSecretKey,
+    receiver_pk: Option<BlsPublicKey>,
+    transfer_value: u64,
+    deposit: u64,
+    gas_limit: u64,
+    gas_price: u64,
+    moonlight_nonce: u64,
+    chain_id: u8,
+    expiry_height: u64,
+    data: Option<impl Into<TransactionData>>,
+) -> Result<Transaction, Error> {
+    let sender = BlsPublicKey::from(sender_sk);
+    let payload = MoonlightPayload {
+        chain_id,
+        sender,
+        receiver: receiver_pk.unwrap_or(sender),
+        value: transfer_value,
+        deposit,
+        fee: MoonlightFee {
+            gas_limit,
+            gas_price,
+            refund_address: sender,
+        },
+        nonce: moonlight_nonce,
+        data: data.map(Into::into),
+    };
+
+    Ok(MoonlightTransaction::sign_expiring_payload(
+        sender_sk,
+        payload,
+        expiry_height,
+    )?
+    .into())
+}
+
+/// Creates a Moonlight [`Transaction`] whose gas is paid by a sponsor.
+///
+/// The sender only needs to hold the `transfer_value` and the `deposit`,
+/// while the `sponsor_sk` account is charged for the gas and refunded any
+/// unspent part of it.
+///
+/// # Note
+/// The `moonlight_nonce` is the sender's nonce, and is NOT incremented.
+///
+/// # Errors
+/// The creation of a transaction is not possible and will error if:
+/// - the Memo provided with `data` is too large
+#[allow(clippy::too_many_arguments)]
+pub fn moonlight_sponsored(
+    sender_sk: &BlsSecretKey,
+    sponsor_sk: &BlsSecretKey,
+    receiver_pk: Option<BlsPublicKey>,
+    transfer_value: u64,
+    deposit: u64,
+    gas_limit: u64,
+    gas_price: u64,
+    moonlight_nonce: u64,
+    chain_id: u8,
+    data: Option<impl Into<TransactionData>>,
+) -> Result<Transaction, Error> {
+    let sponsor_pk = BlsPublicKey::from(sponsor_sk);
+
+    Ok(MoonlightTransaction::new_with_refund(
+        sender_sk,
+        &sponsor_pk,
+        receiver_pk,
+        transfer_value,
+        deposit,
+        gas_limit,
+        gas_price,
+        moonlight_nonce,
+        chain_id,
+        data,
+    )?
+    .sponsored(sponsor_sk)
+    .into())
+}
+
 /// Create a [`Transaction`] to stake from phoenix-notes.
 ///
 /// # Errors
@@ -343,6 +439,105 @@ pub fn moonlight_stake_reward<R: RngCore + CryptoRng>(
     )
 }
 
+/// Create a [`Transaction`] to register, or clear, the beneficiary receiving
+/// the rewards of a stake.
+///
+/// # Note
+/// The `moonlight_nonce` is NOT incremented and should be incremented by the
+/// caller of this function, if its not done so, rusk will throw 500 error.
+/// The same goes for the `beneficiary_nonce`, which must be higher than the
+/// one of the last registration.
+///
+/// # Errors
+/// The creation of a transaction is not possible and will error if:
+/// - the stake contract call cannot be serialized
+#[allow(clippy::too_many_arguments)]
+pub fn moonlight_set_stake_beneficiary(
+    moonlight_sender_sk: &BlsSecretKey,
+    stake_pk: &BlsPublicKey,
+    stake_owner_sk: &BlsSecretKey,
+    beneficiary: Option<Beneficiary>,
+    beneficiary_nonce: u64,
+    gas_limit: u64,
+    gas_price: u64,
+    moonlight_nonce: u64,
+    chain_id: u8,
+) -> Result<Transaction, Error> {
+    let set_beneficiary = SetBeneficiary::new(
+        stake_owner_sk,
+        *stake_pk,
+        beneficiary,
+        beneficiary_nonce,
+        chain_id,
+    );
+
+    let contract_call =
+        ContractCall::new(STAKE_CONTRACT, "set_beneficiary", &set_beneficiary)?;
+
+    moonlight(
+        moonlight_sender_sk,
+        None,
+        0,
+        0,
+        gas_limit,
+        gas_price,
+        moonlight_nonce,
+        chain_id,
+        Some(contract_call),
+    )
+}
+
+/// Create a [`Transaction`] to withdraw stake rewards to the beneficiary
+/// registered for the stake.
+///
+/// # Note
+/// The `moonlight_nonce` is NOT incremented and should be incremented by the
+/// caller of this function, if its not done so, rusk will throw 500 error.
+/// The same holds for the `withdraw_nonce`, which must be higher than the one
+/// of the previous withdrawal to the beneficiary.
+///
+/// # Errors
+/// The creation of a transaction is not possible and will error if:
+/// - the stake contract call cannot be serialized
+#[allow(clippy::too_many_arguments)]
+pub fn moonlight_stake_reward_to_beneficiary<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    moonlight_sender_sk: &BlsSecretKey,
+    stake_sk: &BlsSecretKey,
+    reward_amount: u64,
+    withdraw_nonce: u64,
+    gas_limit: u64,
+    gas_price: u64,
+    moonlight_nonce: u64,
+    chain_id: u8,
+) -> Result<Transaction, Error> {
+    let withdraw = WithdrawToBeneficiary::new(
+        rng,
+        stake_sk,
+        reward_amount,
+        withdraw_nonce,
+        chain_id,
+    );
+
+    let contract_call = ContractCall::new(
+        STAKE_CONTRACT,
+        "withdraw_to_beneficiary",
+        &withdraw,
+    )?;
+
+    moonlight(
+        moonlight_sender_sk,
+        None,
+        0,
+        0,
+        gas_limit,
+        gas_price,
+        moonlight_nonce,
+        chain_id,
+        Some(contract_call),
+    )
+}
+
 /// Create an unproven [`Transaction`] to unstake into a phoenix-note.
 ///
 /// # Errors
@@ -0,0 +1,79 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use wallet_core::head::{
+    FeedRecord, Head, HeadEvent, HeadTracker, BLOCK_ACCEPTED, BLOCK_FINALIZED,
+    INTERRUPTED, REORG,
+};
+
+fn accepted(height: u64, hash: u8) -> FeedRecord {
+    FeedRecord::new(BLOCK_ACCEPTED, height, [hash; 32]).unwrap()
+}
+
+fn finalized(height: u64, hash: u8) -> FeedRecord {
+    FeedRecord::new(BLOCK_FINALIZED, height, [hash; 32]).unwrap()
+}
+
+#[test]
+fn head_follows_the_feed() {
+    let mut tracker = HeadTracker::new(Head {
+        height: 11,
+        hash: [2; 32],
+        finalized_height: 10,
+        finalized_hash: [1; 32],
+    });
+    assert_eq!(tracker.resume_height(), 11);
+
+    // The tip is replayed after following the feed
+    assert!(tracker.apply(accepted(11, 2)).is_empty());
+
+    let changes = tracker.apply(accepted(12, 3));
+    assert_eq!(
+        changes,
+        vec![HeadEvent::Accepted {
+            height: 12,
+            hash: [3; 32]
+        }]
+    );
+
+    // A different block at a known height reverts the branch
+    let changes = tracker.apply(accepted(12, 4));
+    assert_eq!(
+        changes,
+        vec![
+            HeadEvent::Reorg { height: 12 },
+            HeadEvent::Accepted {
+                height: 12,
+                hash: [4; 32]
+            }
+        ]
+    );
+    assert_eq!(tracker.head().hash, [4; 32]);
+
+    let changes = tracker.apply(finalized(11, 2));
+    assert_eq!(
+        changes,
+        vec![HeadEvent::Finalized {
+            height: 11,
+            hash: [2; 32]
+        }]
+    );
+    assert_eq!(tracker.resume_height(), 12);
+
+    // Finality is never reverted by a replay
+    assert!(tracker.apply(finalized(10, 1)).is_empty());
+
+    let reorg = FeedRecord::new(REORG, 12, [0; 32]).unwrap();
+    assert_eq!(tracker.apply(reorg), vec![HeadEvent::Reorg { height: 12 }]);
+    let head = tracker.head();
+    assert_eq!((head.height, head.hash), (11, [2; 32]));
+}
+
+#[test]
+fn records_not_changing_the_head() {
+    assert_eq!(FeedRecord::new(INTERRUPTED, 12, [0; 32]), None);
+    assert_eq!(FeedRecord::new("txExecuted", 12, [0; 32]), None);
+}